@@ -0,0 +1,251 @@
+use std::fmt::Write as _;
+
+use chrono::{DateTime, Datelike, Duration, FixedOffset, NaiveTime, TimeZone, Utc, Weekday};
+use serde::Serialize;
+use tracing::warn;
+
+use crate::anime::anilist;
+use crate::anime::my_anime_list::database;
+use crate::anime::my_anime_list::model::{AnimeData, DayOfTheWeek};
+use crate::global::database::DatabaseInstance;
+use crate::global::error::AppError;
+
+/// Airing anime considered when no list of ids is given
+const MAX_AIRING_ANIME: i64 = 500;
+
+/// Episode length used when the anime has none recorded
+const DEFAULT_EPISODE_MINUTES: i64 = 24;
+
+/// An upcoming episode broadcast
+#[derive(Debug, Clone)]
+pub struct AiringEpisode {
+    pub mal_id: i32,
+    pub title: String,
+    pub url: String,
+    /// Episode number, only known when AniList reported the next episode
+    pub episode: Option<i32>,
+    pub airs_at: DateTime<Utc>,
+    pub duration: Duration,
+}
+
+/// Weekly broadcast slot converted to UTC
+#[derive(Debug, Clone, Serialize)]
+pub struct BroadcastSlot {
+    /// Weekday in UTC, e.g. "Friday" for a Saturday 01:00 JST broadcast
+    pub day: String,
+    /// "HH:MM" in UTC
+    pub time: String,
+    /// Next broadcast, absent when the anime is neither airing nor upcoming
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_at: Option<DateTime<Utc>>,
+}
+
+/// Broadcast slot of an anime in UTC, `None` when its day, time or timezone isn't understood
+pub fn broadcast_slot(anime: &AnimeData, now: DateTime<Utc>) -> Option<BroadcastSlot> {
+    let next = next_broadcast(anime, now)?;
+    let upcoming = anime.airing || anime.aired.from.is_some_and(|from| from > now);
+
+    Some(BroadcastSlot {
+        day: next.format("%A").to_string(),
+        time: next.format("%H:%M").to_string(),
+        next_at: upcoming.then_some(next),
+    })
+}
+
+/// Offset of a broadcast timezone. Only zones without daylight saving time are known,
+/// for them a fixed offset is exact.
+fn broadcast_offset(timezone: &str) -> Option<FixedOffset> {
+    match timezone {
+        "Asia/Tokyo" | "JST" | "Asia/Seoul" | "KST" => FixedOffset::east_opt(9 * 3600),
+        "Asia/Shanghai" | "Asia/Taipei" => FixedOffset::east_opt(8 * 3600),
+        "UTC" | "Etc/UTC" | "GMT" => FixedOffset::east_opt(0),
+        _ => None,
+    }
+}
+
+/// Upcoming episodes of collected anime within the next `days`, ordered by air time.
+///
+/// The exact time of the next episode comes from AniList when the anime was also collected
+/// there, following episodes are assumed weekly. Otherwise the MAL broadcast slot is used,
+/// which is only understood for timezones without daylight saving time (Asia/Tokyo, UTC).
+/// With `mal_ids`, only these anime are considered.
+pub async fn upcoming_episodes(
+    db: &DatabaseInstance,
+    mal_ids: &[i32],
+    days: i64,
+) -> Result<Vec<AiringEpisode>, AppError> {
+    let now = Utc::now();
+    let until = now + Duration::days(days);
+
+    let anime = if mal_ids.is_empty() {
+        database::get_airing_anime(db.db(), MAX_AIRING_ANIME).await?
+    } else {
+        let mut anime = Vec::with_capacity(mal_ids.len());
+        for &mal_id in mal_ids {
+            match database::get_anime_by_id(db.db(), mal_id).await? {
+                Some(data) => anime.push(data),
+                None => warn!(mal_id = mal_id, "Anime in airing calendar filter is not collected"),
+            }
+        }
+        anime
+    };
+
+    let mut episodes = Vec::new();
+    for anime in anime {
+        let next = anilist::database::get_anime_by_mal_id(db.db(), anime.mal_id).await?
+            .and_then(|anilist| anilist.next_airing_episode)
+            .and_then(|next| Some((Utc.timestamp_opt(next.airing_at, 0).single()?, next.episode)));
+
+        match next {
+            Some((airs_at, episode)) => schedule_from_anilist(&anime, airs_at, episode, until, &mut episodes),
+            None if anime.airing => schedule_from_broadcast(&anime, now, until, &mut episodes),
+            None => {}
+        }
+    }
+
+    episodes.sort_by_key(|episode| episode.airs_at);
+    Ok(episodes)
+}
+
+fn schedule_from_anilist(
+    anime: &AnimeData,
+    mut airs_at: DateTime<Utc>,
+    mut episode: i32,
+    until: DateTime<Utc>,
+    episodes: &mut Vec<AiringEpisode>,
+) {
+    while airs_at <= until && (anime.num_episodes <= 0 || episode <= anime.num_episodes) {
+        episodes.push(airing_episode(anime, Some(episode), airs_at));
+        airs_at += Duration::weeks(1);
+        episode += 1;
+    }
+}
+
+fn schedule_from_broadcast(
+    anime: &AnimeData,
+    now: DateTime<Utc>,
+    until: DateTime<Utc>,
+    episodes: &mut Vec<AiringEpisode>,
+) {
+    let Some(mut airs_at) = next_broadcast(anime, now) else { return };
+
+    let until = match anime.aired.to {
+        Some(end) if end < until => end + Duration::days(1),
+        _ => until,
+    };
+    while airs_at <= until {
+        episodes.push(airing_episode(anime, None, airs_at));
+        airs_at += Duration::weeks(1);
+    }
+}
+
+/// Next weekly broadcast after `now`, or after the premiere for anime that did not start yet
+fn next_broadcast(anime: &AnimeData, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let broadcast = &anime.broadcast;
+    let offset = broadcast_offset(broadcast.timezone.as_deref()?)?;
+    let weekday = match broadcast.day.as_ref()? {
+        DayOfTheWeek::Mondays => Weekday::Mon,
+        DayOfTheWeek::Tuesdays => Weekday::Tue,
+        DayOfTheWeek::Wednesdays => Weekday::Wed,
+        DayOfTheWeek::Thursdays => Weekday::Thu,
+        DayOfTheWeek::Fridays => Weekday::Fri,
+        DayOfTheWeek::Saturdays => Weekday::Sat,
+        DayOfTheWeek::Sundays => Weekday::Sun,
+        DayOfTheWeek::Other => return None,
+    };
+    let time = NaiveTime::parse_from_str(broadcast.time.as_deref()?.trim(), "%H:%M").ok()?;
+
+    let start = anime.aired.from.filter(|from| *from > now).unwrap_or(now);
+    let local = start.with_timezone(&offset);
+
+    let days_ahead = (weekday.num_days_from_monday() + 7 - local.weekday().num_days_from_monday()) % 7;
+    let date = local.date_naive() + Duration::days(days_ahead as i64);
+    let mut airs_at = offset.from_local_datetime(&date.and_time(time)).single()?.with_timezone(&Utc);
+    if airs_at < start {
+        airs_at += Duration::weeks(1);
+    }
+    Some(airs_at)
+}
+
+fn airing_episode(anime: &AnimeData, episode: Option<i32>, airs_at: DateTime<Utc>) -> AiringEpisode {
+    let duration = if anime.average_episode_duration > 0 {
+        Duration::seconds(anime.average_episode_duration as i64)
+    } else {
+        Duration::minutes(DEFAULT_EPISODE_MINUTES)
+    };
+
+    AiringEpisode {
+        mal_id: anime.mal_id,
+        title: anime.display_title().to_string(),
+        url: anime.url.clone(),
+        episode,
+        airs_at,
+        duration,
+    }
+}
+
+/// Render episodes as an iCalendar (RFC 5545) document
+pub fn to_icalendar(episodes: &[AiringEpisode]) -> String {
+    let now = Utc::now();
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//media-collector//Airing schedule//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        "METHOD:PUBLISH".to_string(),
+        "X-WR-CALNAME:Anime airing schedule".to_string(),
+    ];
+
+    for episode in episodes {
+        let summary = match episode.episode {
+            Some(number) => format!("{} - Episode {}", episode.title, number),
+            None => format!("{} - New episode", episode.title),
+        };
+
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:mal-{}-{}@media-collector", episode.mal_id, episode.airs_at.timestamp()));
+        lines.push(format!("DTSTAMP:{}", ical_datetime(now)));
+        lines.push(format!("DTSTART:{}", ical_datetime(episode.airs_at)));
+        lines.push(format!("DTEND:{}", ical_datetime(episode.airs_at + episode.duration)));
+        lines.push(format!("SUMMARY:{}", escape_text(&summary)));
+        if !episode.url.is_empty() {
+            lines.push(format!("URL:{}", episode.url));
+        }
+        lines.push("END:VEVENT".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    let mut calendar = String::new();
+    for line in lines {
+        let _ = write!(calendar, "{}\r\n", fold_line(&line));
+    }
+    calendar
+}
+
+fn ical_datetime(date: DateTime<Utc>) -> String {
+    date.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+fn escape_text(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Fold content lines longer than 75 octets, without splitting UTF-8 characters
+fn fold_line(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len());
+    let mut width = 0;
+    for c in line.chars() {
+        if width + c.len_utf8() > 75 {
+            folded.push_str("\r\n ");
+            // The leading space counts toward the continuation line
+            width = 1;
+        }
+        folded.push(c);
+        width += c.len_utf8();
+    }
+    folded
+}
@@ -1,8 +1,12 @@
+use std::collections::BTreeMap;
+
 use chrono::{DateTime, NaiveDate, Utc};
 use tracing::{debug, warn};
 
 use super::model::*;
 use crate::anime::my_anime_list::model::*;
+use crate::anime::anilist::description;
+use crate::anime::season;
 
 /// Convert AniList media to AniList-specific data structure
 pub fn anilist_to_anime_data(anilist: AniListMedia) -> AniListAnimeData {
@@ -126,8 +130,13 @@ pub fn anilist_to_anime_data(anilist: AniListMedia) -> AniListAnimeData {
         string: None,
     };
 
+    // AniList descriptions are HTML with spoiler markers
+    let synopsis = description::to_plain_text(anilist.description.as_deref().unwrap_or_default());
+
     // Convert season
-    let season = anilist.season.as_ref().and_then(|s| parse_anilist_season(s));
+    let mut season = anilist.season.as_ref().and_then(|s| parse_anilist_season(s));
+    let mut year = anilist.season_year;
+    season::fill_missing(&mut season, &mut year, aired.from);
 
     // Convert relations
     let relations = convert_anilist_relations(&anilist.relations);
@@ -170,6 +179,8 @@ pub fn anilist_to_anime_data(anilist: AniListMedia) -> AniListAnimeData {
             id: None,
             name: site.clone(),
             url: ep.url.clone().unwrap_or_default(),
+            title: ep.title.clone(),
+            thumbnail: ep.thumbnail.clone().filter(|thumbnail| !thumbnail.is_empty()),
         })
     }).collect();
 
@@ -236,6 +247,7 @@ pub fn anilist_to_anime_data(anilist: AniListMedia) -> AniListAnimeData {
         source: anilist.source.as_ref().and_then(|s| parse_anilist_source(s)),
         num_episodes: anilist.episodes.unwrap_or(0),
         average_episode_duration: anilist.duration.map(|d| d * 60).unwrap_or(0),
+        duration_seconds: anilist.duration.filter(|minutes| *minutes > 0).map(|minutes| minutes * 60),
         status: anilist.status.as_ref().and_then(|s| parse_anilist_status(s)),
         airing: anilist.status.as_deref() == Some("RELEASING"),
         aired,
@@ -243,10 +255,13 @@ pub fn anilist_to_anime_data(anilist: AniListMedia) -> AniListAnimeData {
         score,
         popularity: anilist.popularity.unwrap_or(0),
         favorites: anilist.favourites.unwrap_or(0),
-        synopsis: strip_html(&anilist.description.unwrap_or_default()),
+        synopses: provider_synopses(&synopsis),
+        local_images: BTreeMap::new(),
+        synopsis,
+        synopsis_html: anilist.description.as_deref().and_then(description::to_rich_text),
         background: None,
         season,
-        year: anilist.season_year,
+        year,
         broadcast,
         studios,
         genres,
@@ -320,7 +335,8 @@ fn parse_anilist_status(status: &str) -> Option<Status> {
         "RELEASING" => Some(Status::CurrentlyAiring),
         "NOT_YET_RELEASED" => Some(Status::NotYetAired),
         "CANCELLED" => Some(Status::FinishedAiring),
-        _ => None,
+        "" => None,
+        other => Some(Status::Other(other.to_lowercase())),
     }
 }
 
@@ -331,17 +347,15 @@ fn parse_anilist_source(source: &str) -> Option<Source> {
         "LIGHT_NOVEL" => Some(Source::LightNovel),
         "VISUAL_NOVEL" => Some(Source::VisualNovel),
         "VIDEO_GAME" => Some(Source::Game),
-        "OTHER" => Some(Source::Other),
         "NOVEL" => Some(Source::Novel),
         "DOUJINSHI" => Some(Source::Manga),
         "ANIME" => Some(Source::Original),
         "WEB_NOVEL" => Some(Source::Novel),
-        "LIVE_ACTION" => Some(Source::Other),
         "GAME" => Some(Source::Game),
         "COMIC" => Some(Source::Manga),
-        "MULTIMEDIA_PROJECT" => Some(Source::Other),
         "PICTURE_BOOK" => Some(Source::PictureBook),
-        _ => Some(Source::Other),
+        "" => None,
+        other => Some(Source::Other(other.to_lowercase())),
     }
 }
 
@@ -482,9 +496,3 @@ fn convert_anilist_staff(staff: &Option<StaffConnection>) -> Vec<Staff> {
         })
         .unwrap_or_default()
 }
-
-fn strip_html(html: &str) -> String {
-    // Simple HTML tag removal
-    let re = regex::Regex::new(r"<[^>]*>").unwrap_or_else(|_| regex::Regex::new(r"").unwrap());
-    re.replace_all(html, "").to_string()
-}
\ No newline at end of file
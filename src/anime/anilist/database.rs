@@ -1,11 +1,14 @@
 use anyhow::Result;
-use mongodb::{Database, IndexModel};
+use mongodb::{Cursor, Database, IndexModel};
 use mongodb::options::{FindOptions, IndexOptions, ReplaceOptions};
-use mongodb::bson::doc;
+use mongodb::bson::{self, doc};
 use tracing::{info, debug, warn};
 use futures::stream::StreamExt;
 
 use crate::anime::anilist::model::AniListAnimeData;
+use crate::anime::my_anime_list::model::LocalImage;
+use crate::anime::{duration, season};
+use crate::anime::titles::{self, TitleSource};
 use crate::global::error::DatabaseError;
 
 // Collection name for AniList anime
@@ -101,11 +104,22 @@ pub async fn upsert_anime(db: &Database, data: &AniListAnimeData) -> Result<(),
     let filter = doc! { "anilist_id": data.anilist_id };
     let options = ReplaceOptions::builder().upsert(true).build();
 
-    collection.replace_one(filter, data)
+    // The local images are owned by the picture module, keep them across refetches
+    let mut data = data.clone();
+    if data.local_images.is_empty()
+        && let Some(existing) = collection.find_one(filter.clone()).await
+            .map_err(|e| DatabaseError::Query(format!("Failed to get anime: {}", e)))?
+    {
+        data.local_images = existing.local_images;
+    }
+
+    collection.replace_one(filter, &data)
         .with_options(options)
         .await
         .map_err(|e| DatabaseError::Query(format!("Failed to upsert anime: {}", e)))?;
 
+    titles::index_titles(db, TitleSource::Anilist, data.anilist_id, data.mal_id, data.titles.iter().map(|t| t.title.as_str())).await?;
+
     debug!(
         anilist_id = data.anilist_id,
         title = %data.titles.first().map(|t| t.title.as_str()).unwrap_or("Unknown"),
@@ -114,6 +128,28 @@ pub async fn upsert_anime(db: &Database, data: &AniListAnimeData) -> Result<(),
     Ok(())
 }
 
+/// Record the downloaded copy of an image of a stored anime
+pub async fn set_local_image(db: &Database, anilist_id: i32, key: &str, image: &LocalImage) -> Result<(), DatabaseError> {
+    let image = bson::to_bson(image)
+        .map_err(|e| DatabaseError::Query(format!("Failed to serialize local image: {}", e)))?;
+
+    db.collection::<AniListAnimeData>(COLLECTION_NAME)
+        .update_one(doc! { "anilist_id": anilist_id }, doc! { "$set": { format!("local_images.{}", key): image } })
+        .await
+        .map_err(|e| DatabaseError::Query(format!("Failed to set local image: {}", e)))?;
+    Ok(())
+}
+
+/// Set `duration_seconds` on the AniList anime stored before it existed
+pub async fn backfill_duration_seconds(db: &Database) -> Result<u64, DatabaseError> {
+    duration::backfill_duration_seconds(db.collection(COLLECTION_NAME)).await
+}
+
+/// Infer the missing season and year of the AniList anime stored before inference
+pub async fn backfill_seasons(db: &Database) -> Result<u64, DatabaseError> {
+    season::backfill_seasons(db.collection(COLLECTION_NAME)).await
+}
+
 /// Get anime by AniList ID
 pub async fn get_anime_by_id(db: &Database, anilist_id: i32) -> Result<Option<AniListAnimeData>, DatabaseError> {
     let collection = db.collection::<AniListAnimeData>(COLLECTION_NAME);
@@ -123,6 +159,16 @@ pub async fn get_anime_by_id(db: &Database, anilist_id: i32) -> Result<Option<An
         .map_err(|e| DatabaseError::Query(format!("Failed to get anime: {}", e)))
 }
 
+/// Iterate over every stored anime, ordered by AniList ID
+pub async fn get_all_anime_cursor(db: &Database) -> Result<Cursor<AniListAnimeData>, DatabaseError> {
+    let collection = db.collection::<AniListAnimeData>(COLLECTION_NAME);
+
+    collection.find(doc! {})
+        .sort(doc! { "anilist_id": 1 })
+        .await
+        .map_err(|e| DatabaseError::Query(format!("Failed to list anime: {}", e)))
+}
+
 /// Get anime by MAL ID
 pub async fn get_anime_by_mal_id(db: &Database, mal_id: i32) -> Result<Option<AniListAnimeData>, DatabaseError> {
     let collection = db.collection::<AniListAnimeData>(COLLECTION_NAME);
@@ -191,6 +237,20 @@ pub async fn delete_anime(db: &Database, anilist_id: i32) -> Result<bool, Databa
 
     let result = collection.delete_one(filter).await
         .map_err(|e| DatabaseError::Query(format!("Failed to delete anime: {}", e)))?;
+    titles::remove_titles(db, TitleSource::Anilist, anilist_id).await?;
 
     Ok(result.deleted_count > 0)
-}
\ No newline at end of file
+}
+
+/// Point entries referencing `old_mal_id` to `new_mal_id`, returns the number of entries updated
+pub async fn replace_mal_id(db: &Database, old_mal_id: i32, new_mal_id: i32) -> Result<u64, DatabaseError> {
+    let collection = db.collection::<AniListAnimeData>(COLLECTION_NAME);
+
+    let result = collection.update_many(
+        doc! { "mal_id": old_mal_id },
+        doc! { "$set": { "mal_id": new_mal_id } },
+    ).await
+        .map_err(|e| DatabaseError::Query(format!("Failed to replace MAL ID: {}", e)))?;
+
+    Ok(result.modified_count)
+}
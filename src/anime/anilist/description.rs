@@ -0,0 +1,70 @@
+use regex::{Captures, Regex};
+
+/// Plain text of an AniList description: line breaks kept, spoilers (`~!...!~`),
+/// tags and markdown emphasis removed and HTML entities decoded
+pub fn to_plain_text(description: &str) -> String {
+    let text = replace(description, r"(?i)<br\s*/?>", "\n");
+    let text = replace(&text, r"(?i)</p>", "\n\n");
+    let text = replace(&text, r"(?s)~!.*?!~", "");
+    let text = replace(&text, r"<[^>]*>", "");
+    let text = text.replace("__", "").replace("**", "");
+    let text = decode_entities(&text);
+
+    let lines: Vec<&str> = text.lines().map(str::trim).collect();
+    replace(&lines.join("\n"), r"\n{3,}", "\n\n").trim().to_string()
+}
+
+/// Description kept as rich text: spoiler markers become `<span class="spoiler">` and
+/// blank trailing markup is trimmed, the HTML itself is left as AniList sent it
+pub fn to_rich_text(description: &str) -> Option<String> {
+    let rich = Regex::new(r"(?s)~!(.*?)!~")
+        .map(|re| re.replace_all(description, r#"<span class="spoiler">$1</span>"#).into_owned())
+        .unwrap_or_else(|_| description.to_string());
+    let rich = replace(&rich, r"(?i)(\s*<br\s*/?>)+\s*$", "");
+
+    let rich = rich.trim();
+    (!rich.is_empty()).then(|| rich.to_string())
+}
+
+fn replace(text: &str, pattern: &str, replacement: &str) -> String {
+    match Regex::new(pattern) {
+        Ok(re) => re.replace_all(text, replacement).into_owned(),
+        Err(_) => text.to_string(),
+    }
+}
+
+/// Decode named entities of AniList descriptions and numeric ones, unknown entities are kept
+fn decode_entities(text: &str) -> String {
+    let Ok(re) = Regex::new(r"&(#[0-9]{1,7}|#[xX][0-9a-fA-F]{1,6}|[a-zA-Z]{2,8});") else {
+        return text.to_string();
+    };
+
+    re.replace_all(text, |captures: &Captures| {
+        let entity = &captures[1];
+        let decoded = match entity {
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "nbsp" => Some(' '),
+            "ndash" => Some('–'),
+            "mdash" => Some('—'),
+            "hellip" => Some('…'),
+            "lsquo" => Some('‘'),
+            "rsquo" => Some('’'),
+            "ldquo" => Some('“'),
+            "rdquo" => Some('”'),
+            _ => entity.strip_prefix("#x").or_else(|| entity.strip_prefix("#X"))
+                .map(|hex| u32::from_str_radix(hex, 16))
+                .or_else(|| entity.strip_prefix('#').map(str::parse::<u32>))
+                .and_then(Result::ok)
+                .and_then(char::from_u32),
+        };
+        match decoded {
+            Some(c) => c.to_string(),
+            None => captures[0].to_string(),
+        }
+    })
+    .into_owned()
+}
@@ -4,6 +4,7 @@ pub mod queries;
 pub mod module;
 pub mod task;
 pub mod database;
+pub mod description;
 
 pub use model::{AniListMedia, GraphQLRequest, GraphQLResponse};
 pub use converter::anilist_to_anime_data;
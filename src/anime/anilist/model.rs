@@ -1,9 +1,11 @@
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
 use crate::anime::my_anime_list::model::{
     Images, Title, Trailer, MediaType, NSFW, Source, Status, 
     Aired, Season, Broadcast, Genre, Themes, Studio, Demographic,
-    Relation, Theme, External, Streaming, Character, Staff, Statistics
+    Relation, Theme, External, Streaming, Character, Staff, Statistics, LocalImage
 };
 
 // ========================================================================
@@ -373,6 +375,9 @@ pub struct AniListAnimeData {
     // Basic Info
     pub url: String,
     pub images: Images,
+    /// Downloaded copies of the images by category (e.g. "cover_large"), set by the picture module
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub local_images: BTreeMap<String, LocalImage>,
     pub trailer: Trailer,
     pub titles: Vec<Title>,
     pub media_type: Option<MediaType>,
@@ -380,6 +385,9 @@ pub struct AniListAnimeData {
     pub source: Option<Source>,
     pub num_episodes: i32,
     pub average_episode_duration: i32,
+    /// Seconds per episode, normalized from the provider duration
+    #[serde(default)]
+    pub duration_seconds: Option<i32>,
     pub status: Option<Status>,
     pub airing: bool,
     pub aired: Aired,
@@ -388,6 +396,12 @@ pub struct AniListAnimeData {
     pub popularity: i32,
     pub favorites: i32,
     pub synopsis: String,
+    /// Synopses by language code, `synopsis` is the one of the provider language
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub synopses: BTreeMap<String, String>,
+    /// Description as AniList sent it (HTML), with spoilers in `<span class="spoiler">`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub synopsis_html: Option<String>,
     pub background: Option<String>,
     pub season: Option<Season>,
     pub year: Option<i32>,
@@ -3,6 +3,7 @@ use tracing::info;
 
 use crate::global::config::AppConfig;
 use crate::global::error::AppError;
+use crate::global::events::EventBus;
 use crate::global::http::ClientWithLimiter;
 use crate::global::queue::TaskQueue;
 use crate::picture::PictureFetcherModule;
@@ -14,6 +15,7 @@ pub struct AniListModule {
     config: Arc<AppConfig>,
     queue: TaskQueue,
     picture_module: Option<Arc<PictureFetcherModule>>,
+    events: Option<EventBus>,
 }
 
 impl AniListModule {
@@ -31,6 +33,7 @@ impl AniListModule {
             config, 
             queue,
             picture_module: None,
+            events: None,
         })
     }
     
@@ -40,6 +43,12 @@ impl AniListModule {
         self
     }
 
+    /// Publish data events from the queued fetch tasks
+    pub fn with_events(mut self, events: EventBus) -> Self {
+        self.events = Some(events);
+        self
+    }
+
     pub fn is_available(config: &AppConfig) -> bool {
         config.can_start_child_module("anilist", false)
     }
@@ -89,6 +98,10 @@ impl AniListModule {
             }
         }
 
+        if let Some(events) = &self.events {
+            task = task.with_events(events.clone());
+        }
+
         info!(
             module = "anilist",
             mal_id = mal_id,
@@ -145,6 +158,10 @@ impl AniListModule {
             }
         }
 
+        if let Some(events) = &self.events {
+            task = task.with_events(events.clone());
+        }
+
         info!(
             module = "anilist",
             anilist_id = anilist_id,
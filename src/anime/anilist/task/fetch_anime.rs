@@ -3,12 +3,12 @@ use serde::{Deserialize, Serialize};
 use tracing::{info, debug, warn};
 
 use crate::anime::anilist::database::upsert_anime;
-use crate::global::queue::{TaskData, TaskPriority, TaskStatus};
+use crate::global::queue::{TaskData, TaskPriority};
 use crate::global::{
-    database::DatabaseInstance,
-    error::AppError,
-    queue::Task,
+    error::{AppError, HttpError},
+    queue::{Task, TaskContext},
     http::RequestConfig,
+    events::{AnimeSource, DataEvent, EventBus},
 };
 use crate::anime::anilist::{
     model::{GraphQLRequest, GraphQLResponse, MediaData},
@@ -37,6 +37,8 @@ pub struct FetchAnimeTask {
     full_fetch: bool,
     /// Optional picture module reference
     picture_module: Option<Arc<crate::picture::PictureFetcherModule>>,
+    /// Where to publish the stored anime event
+    events: Option<EventBus>,
 }
 
 impl FetchAnimeTask {
@@ -55,6 +57,7 @@ impl FetchAnimeTask {
             with_pictures: false,
             full_fetch: false,
             picture_module: None,
+            events: None,
         }
     }
 
@@ -73,6 +76,7 @@ impl FetchAnimeTask {
             with_pictures: false,
             full_fetch: false,
             picture_module: None,
+            events: None,
         }
     }
     
@@ -90,6 +94,12 @@ impl FetchAnimeTask {
         self.picture_module = Some(picture_module);
         self
     }
+
+    /// Publish an event once the anime is stored
+    pub fn with_events(mut self, events: EventBus) -> Self {
+        self.events = Some(events);
+        self
+    }
 }
 
 #[async_trait::async_trait]
@@ -114,17 +124,17 @@ impl Task for FetchAnimeTask {
             full_fetch: self.full_fetch,
         };
 
-        TaskData {
-            id: self.id(),
-            name: self.name().to_string(),
-            priority: self.priority(),
-            status: TaskStatus::Pending,
-            created_at: self.created_at,
-            payload: serde_json::json!(payload),
-        }
+        TaskData::new(
+            self.id(),
+            self.name().to_string(),
+            self.priority(),
+            self.created_at,
+            serde_json::json!(payload),
+        )
     }
 
-    async fn execute(&self, db: Arc<DatabaseInstance>, _client: reqwest::Client) -> Result<(), AppError> {
+    async fn execute(&self, ctx: &TaskContext) -> Result<(), AppError> {
+        let db = ctx.db.clone();
         info!(
             task = %self.name(),
             mal_id = ?self.mal_id,
@@ -146,7 +156,7 @@ impl Task for FetchAnimeTask {
                 serde_json::json!({ "id": anilist_id })
             )
         } else {
-            return Err(AppError::Module("No ID provided for AniList fetch".to_string()));
+            return Err(AppError::Validation("No ID provided for AniList fetch".to_string()));
         };
 
         let graphql_request = GraphQLRequest {
@@ -156,37 +166,36 @@ impl Task for FetchAnimeTask {
 
         debug!(task = %self.name(), "Sending GraphQL request to AniList");
         
-        let url = "https://graphql.anilist.co";
+        let url = self.client.base_url.as_str();
         
         let config = RequestConfig::new()
             .with_header("Content-Type", "application/json")
             .with_header("Accept", "application/json");
 
-        let response = self.client.client
-            .post(url)
-            .json(&graphql_request)
-            .send()
-            .await
-            .map_err(|e| AppError::Module(format!("AniList API request failed: {}", e)))?;
+        let body = self.client.post_json(url, &graphql_request).await.map_err(|e| match e {
+            HttpError::RequestFailed(e) => AppError::provider("anilist", format!("AniList API request failed: {}", e)),
+            e => AppError::Http(e),
+        })?;
+        let graphql_response = serde_json::from_str::<GraphQLResponse<MediaData>>(&body)
+            .map_err(|e| AppError::provider_rejected("anilist", format!("Failed to parse AniList response: {}", e)))?;
 
-        let graphql_response = response
-            .json::<GraphQLResponse<MediaData>>()
-            .await
-            .map_err(|e| AppError::Module(format!("Failed to parse AniList response: {}", e)))?;
+        if graphql_response.errors.is_empty() {
+            self.client.archive(url, graphql_request.variables.as_ref(), &body).await;
+        }
 
         if !graphql_response.errors.is_empty() {
             let error_messages: Vec<String> = graphql_response.errors
                 .iter()
                 .map(|e| e.message.clone())
                 .collect();
-            return Err(AppError::Module(format!(
+            return Err(AppError::provider_rejected("anilist", format!(
                 "AniList GraphQL errors: {}",
                 error_messages.join(", ")
             )));
         }
 
         let media_data = graphql_response.data
-            .ok_or_else(|| AppError::Module("No data returned from AniList".to_string()))?;
+            .ok_or_else(|| AppError::provider_rejected("anilist", "No data returned from AniList"))?;
 
         let anilist_media = media_data.media;
         let fetched_anilist_id = anilist_media.id;
@@ -245,6 +254,50 @@ impl Task for FetchAnimeTask {
             }
         }
 
+        if let Some(events) = &self.events {
+            events.publish(DataEvent::AnimeStored {
+                source: AnimeSource::AniList,
+                id: fetched_anilist_id as u32,
+                pictures_queued: self.with_pictures && self.picture_module.is_some(),
+            });
+        }
+
         Ok(())
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use wiremock::matchers::{body_partial_json, method, path};
+    use wiremock::{Mock, ResponseTemplate};
+
+    use super::*;
+    use crate::anime::anilist::database::get_anime_by_mal_id;
+    use crate::global::config::HttpMode;
+    use crate::testing::{Providers, TestDatabase, ANILIST_PATH};
+
+    #[tokio::test]
+    #[ignore = "needs a Docker daemon for the MongoDB container"]
+    async fn stores_the_anime_fetched_from_anilist() {
+        let providers = Providers::start(HttpMode::Live).await;
+        let database = TestDatabase::start().await;
+        Mock::given(method("POST"))
+            .and(path(ANILIST_PATH))
+            .and(body_partial_json(json!({ "variables": { "malId": 1 } })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": { "Media": { "id": 21, "idMal": 1, "title": { "romaji": "Cowboy Bebop" } } }
+            })))
+            .expect(1)
+            .mount(&providers.server)
+            .await;
+
+        FetchAnimeTask::by_mal_id(1, providers.http.anilist().clone())
+            .execute(&TaskContext::new(database.db.clone(), reqwest::Client::new()))
+            .await
+            .unwrap();
+
+        let anime = get_anime_by_mal_id(database.db.db(), 1).await.unwrap().expect("anime not stored");
+        assert_eq!(anime.anilist_id, 21);
+        assert!(anime.titles.iter().any(|title| title.title == "Cowboy Bebop"));
+    }
+}
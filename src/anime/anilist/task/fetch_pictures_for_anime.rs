@@ -3,13 +3,13 @@ use serde::{Deserialize, Serialize};
 use tracing::{info, debug, warn};
 
 use crate::global::{
-    database::DatabaseInstance,
     error::AppError,
-    queue::{Task, TaskPriority, TaskData, TaskStatus},
+    queue::{Task, TaskContext, TaskPriority, TaskData},
 };
 use crate::anime::anilist::{database::get_anime_by_id, model::AniListAnimeData};
-use crate::anime::my_anime_list::model::Image;
-use crate::picture::PictureFetcherModule;
+use crate::anime::image_variants;
+use crate::anime::my_anime_list::model::Images;
+use crate::picture::{batch::PictureRequest, PictureFetcherModule};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FetchAniListAnimePicturesPayload {
@@ -38,99 +38,59 @@ impl FetchAniListAnimePicturesTask {
         }
     }
     
-    /// Queue an image download if URL is not empty
-    async fn queue_image(
+    /// Queue the variants of an image kept by the picture policy,
+    /// `sub_category` gives the tag of each format ("jpg" or "webp")
+    fn queue_image(
         &self,
-        image: &Image,
+        requests: &mut Vec<PictureRequest>,
+        images: &Images,
         category: &str,
-        sub_category: Option<&str>,
-    ) -> Result<(), AppError> {
-        let mut tags = vec![
-            "anime".to_string(),
-            "anilist".to_string(),
-            self.anilist_id.to_string(),
-            category.to_string(),
-        ];
-        
-        if let Some(sub) = sub_category {
-            tags.push(sub.to_string());
-        }
-        
-        // Queue JPG version
-        if !image.image_url.is_empty() {
-            debug!(
-                anilist_id = self.anilist_id,
-                category = category,
-                url = %image.image_url,
-                "Queueing JPG image"
-            );
-            
-            self.picture_module.queue_fetch_picture_for_entity(
-                image.image_url.clone(),
-                None,
-                "anime_anilist".to_string(),
-                self.anilist_id.to_string(),
-                tags.clone(),
-            ).await?;
-        }
-        
-        // Queue large JPG version if different
-        if !image.large_image_url.is_empty() && image.large_image_url != image.image_url {
-            let mut large_tags = tags.clone();
-            large_tags.push("large".to_string());
-            
-            debug!(
-                anilist_id = self.anilist_id,
-                category = category,
-                url = %image.large_image_url,
-                "Queueing large JPG image"
-            );
-            
-            self.picture_module.queue_fetch_picture_for_entity(
-                image.large_image_url.clone(),
-                None,
-                "anime_anilist".to_string(),
-                self.anilist_id.to_string(),
-                large_tags,
-            ).await?;
-        }
-        
-        // Queue small JPG version if different
-        if !image.small_image_url.is_empty() 
-            && image.small_image_url != image.image_url 
-            && image.small_image_url != image.large_image_url {
-            
-            let mut small_tags = tags.clone();
-            small_tags.push("small".to_string());
-            
-            debug!(
-                anilist_id = self.anilist_id,
-                category = category,
-                url = %image.small_image_url,
-                "Queueing small JPG image"
-            );
-            
-            self.picture_module.queue_fetch_picture_for_entity(
-                image.small_image_url.clone(),
-                None,
-                "anime_anilist".to_string(),
+        sub_category: impl Fn(&str) -> String,
+    ) {
+        let policy = self.picture_module.variant_policy();
+
+        for (format, image) in image_variants::formats(policy, images) {
+            let tags = vec![
+                "anime".to_string(),
+                "anilist".to_string(),
                 self.anilist_id.to_string(),
-                small_tags,
-            ).await?;
+                category.to_string(),
+                sub_category(format),
+            ];
+
+            for (url, size) in image_variants::sizes(policy, image) {
+                let mut tags = tags.clone();
+                tags.extend(size.map(str::to_string));
+
+                debug!(
+                    anilist_id = self.anilist_id,
+                    category = category,
+                    format = format,
+                    size = size.unwrap_or("regular"),
+                    url = %url,
+                    "Queueing image"
+                );
+
+                requests.push(PictureRequest::new(
+                    url.to_string(),
+                    "anime_anilist".to_string(),
+                    self.anilist_id.to_string(),
+                    tags,
+                ));
+            }
         }
-        
-        Ok(())
     }
     
     /// Queue a single URL with tags
-    async fn queue_url(
+    fn queue_url(
         &self,
+        requests: &mut Vec<PictureRequest>,
         url: &str,
         category: &str,
         sub_category: Option<&str>,
-    ) -> Result<(), AppError> {
+    ) {
         if url.is_empty() {
-            return Ok(());
+            return;
         }
         
         let mut tags = vec![
@@ -151,15 +111,12 @@ impl FetchAniListAnimePicturesTask {
             "Queueing image"
         );
         
-        self.picture_module.queue_fetch_picture_for_entity(
+        requests.push(PictureRequest::new(
             url.to_string(),
-            None,
             "anime_anilist".to_string(),
             self.anilist_id.to_string(),
             tags,
-        ).await?;
-        
-        Ok(())
+        ));
     }
 }
 
@@ -182,21 +139,17 @@ impl Task for FetchAniListAnimePicturesTask {
             anilist_id: self.anilist_id,
         };
 
-        TaskData {
-            id: self.id.clone(),
-            name: self.name().to_string(),
-            priority: self.priority(),
-            status: TaskStatus::Pending,
-            created_at: self.created_at,
-            payload: serde_json::json!(payload),
-        }
+        TaskData::new(
+            self.id.clone(),
+            self.name().to_string(),
+            self.priority(),
+            self.created_at,
+            serde_json::json!(payload),
+        )
     }
 
-    async fn execute(
-        &self,
-        db: Arc<DatabaseInstance>,
-        _client: reqwest::Client,
-    ) -> Result<(), AppError> {
+    async fn execute(&self, ctx: &TaskContext) -> Result<(), AppError> {
+        let db = ctx.db.clone();
         info!(
             task = %self.name(),
             anilist_id = self.anilist_id,
@@ -216,14 +169,12 @@ impl Task for FetchAniListAnimePicturesTask {
             }
         };
 
-        let mut total_queued = 0;
+        let mut requests = Vec::new();
 
         // 1. Main cover images
         debug!(anilist_id = self.anilist_id, "Queueing main anime images");
         
-        self.queue_image(&anime.images.jpg, "cover", Some("jpg")).await?;
-        self.queue_image(&anime.images.webp, "cover", Some("webp")).await?;
-        total_queued += 2;
+        self.queue_image(&mut requests, &anime.images, "anilist_cover", str::to_string);
 
         // 2. Banner image (AniList specific)
         if let Some(banner) = &anime.banner_image {
@@ -232,8 +183,12 @@ impl Task for FetchAniListAnimePicturesTask {
                 "Queueing banner image"
             );
             
-            self.queue_url(banner, "banner", None).await?;
-            total_queued += 1;
+            self.queue_url(&mut requests, banner, "anilist_banner", None);
+        }
+
+        // The cover color stands in for the cover and banner until they are loaded
+        for request in &mut requests {
+            request.placeholder_color = anime.cover_color.clone();
         }
 
         // 3. Character images
@@ -246,38 +201,23 @@ impl Task for FetchAniListAnimePicturesTask {
         for character in &anime.characters {
             let character_tags_suffix = format!("character_{}", character.character.mal_id);
             
-            // Character image (JPG and WebP)
-            self.queue_image(
-                &character.character.images.jpg,
-                "character",
-                Some(&format!("{}_jpg", character_tags_suffix))
-            ).await?;
-            
             self.queue_image(
-                &character.character.images.webp,
-                "character",
-                Some(&format!("{}_webp", character_tags_suffix))
-            ).await?;
-            
-            total_queued += 2;
+                &mut requests,
+                &character.character.images,
+                "anilist_character",
+                |format| format!("{}_{}", character_tags_suffix, format),
+            );
             
             // Voice actor images
             for va in &character.voice_actors {
                 let va_tags_suffix = format!("va_{}_{}", character.character.mal_id, va.person.mal_id);
                 
                 self.queue_image(
-                    &va.person.images.jpg,
-                    "voice_actor",
-                    Some(&format!("{}_jpg", va_tags_suffix))
-                ).await?;
-                
-                self.queue_image(
-                    &va.person.images.webp,
-                    "voice_actor",
-                    Some(&format!("{}_webp", va_tags_suffix))
-                ).await?;
-                
-                total_queued += 2;
+                    &mut requests,
+                    &va.person.images,
+                    "anilist_voice_actor",
+                    |format| format!("{}_{}", va_tags_suffix, format),
+                );
             }
         }
 
@@ -291,26 +231,43 @@ impl Task for FetchAniListAnimePicturesTask {
         for staff in &anime.staffs {
             let staff_tags_suffix = format!("staff_{}", staff.person.mal_id);
             
-            // Staff image (JPG and WebP)
-            self.queue_image(
-                &staff.person.images.jpg,
-                "staff",
-                Some(&format!("{}_jpg", staff_tags_suffix))
-            ).await?;
-            
             self.queue_image(
-                &staff.person.images.webp,
-                "staff",
-                Some(&format!("{}_webp", staff_tags_suffix))
-            ).await?;
-            
-            total_queued += 2;
+                &mut requests,
+                &staff.person.images,
+                "anilist_staff",
+                |format| format!("{}_{}", staff_tags_suffix, format),
+            );
+        }
+
+        // 5. Streaming episode thumbnails, one directory for all episodes
+        if self.picture_module.streaming_thumbnails() {
+            info!(
+                anilist_id = self.anilist_id,
+                count = anime.streaming.len(),
+                "Queueing streaming episode thumbnails"
+            );
+
+            for (idx, streaming) in anime.streaming.iter().enumerate() {
+                if let Some(thumbnail) = &streaming.thumbnail {
+                    self.queue_url(&mut requests, thumbnail, "anilist_streaming", Some(&format!("streaming_episode_{}", idx)));
+                }
+            }
         }
 
+        let batch = self.picture_module.queue_batch(
+            &db,
+            &self.id,
+            format!("Pictures of AniList anime {}", self.anilist_id),
+            requests,
+        ).await?;
+
         info!(
             task = %self.name(),
             anilist_id = self.anilist_id,
-            total_queued = total_queued,
+            job_id = %batch.job_id,
+            total_queued = batch.queued,
+            tags_merged = batch.merged,
+            resumed_from = batch.resumed_from,
             "All AniList anime pictures queued successfully"
         );
 
@@ -1,9 +1,8 @@
-use std::sync::Arc;
 use serde::{Deserialize, Serialize};
 use tracing::info;
 
 use crate::{anime::anilist::database::upsert_anime, global::{
-    database::DatabaseInstance, error::AppError, http::RequestConfig, queue::{Task, TaskData, TaskPriority, TaskStatus}
+    error::{AppError, HttpError}, http::RequestConfig, queue::{Task, TaskContext, TaskData, TaskPriority}
 }};
 use crate::anime::anilist::{
     model::{GraphQLRequest, GraphQLResponse, PageData},
@@ -67,17 +66,17 @@ impl Task for SearchAnimeTask {
             per_page: self.per_page,
         };
 
-        TaskData {
-            id: self.id.clone(),
-            name: self.name().to_string(),
-            priority: self.priority(),
-            status: TaskStatus::Pending,
-            created_at: self.created_at,
-            payload: serde_json::to_value(payload).unwrap(),
-        }
+        TaskData::new(
+            self.id.clone(),
+            self.name().to_string(),
+            self.priority(),
+            self.created_at,
+            serde_json::to_value(payload).unwrap(),
+        )
     }
 
-    async fn execute(&self, db: Arc<DatabaseInstance>, _client: reqwest::Client) -> Result<(), AppError> {
+    async fn execute(&self, ctx: &TaskContext) -> Result<(), AppError> {
+        let db = ctx.db.clone();
         info!(
             task = %self.name(),
             query = %self.query,
@@ -95,37 +94,33 @@ impl Task for SearchAnimeTask {
             })),
         };
 
-        let url = "https://graphql.anilist.co";
+        let url = self.client.base_url.as_str();
         
         let config = RequestConfig::new()
             .with_header("Content-Type", "application/json")
             .with_header("Accept", "application/json");
 
-        let response = self.client.client
-            .post(url)
-            .json(&graphql_request)
-            .send()
-            .await
-            .map_err(|e| AppError::Module(format!("AniList API request failed: {}", e)))?;
+        let body = self.client.post_json(url, &graphql_request).await.map_err(|e| match e {
+            HttpError::RequestFailed(e) => AppError::provider("anilist", format!("AniList API request failed: {}", e)),
+            e => AppError::Http(e),
+        })?;
 
-        let graphql_response = response
-            .json::<GraphQLResponse<PageData>>()
-            .await
-            .map_err(|e| AppError::Module(format!("Failed to parse AniList response: {}", e)))?;
+        let graphql_response = serde_json::from_str::<GraphQLResponse<PageData>>(&body)
+            .map_err(|e| AppError::provider_rejected("anilist", format!("Failed to parse AniList response: {}", e)))?;
 
         if !graphql_response.errors.is_empty() {
             let error_messages: Vec<String> = graphql_response.errors
                 .iter()
                 .map(|e| e.message.clone())
                 .collect();
-            return Err(AppError::Module(format!(
+            return Err(AppError::provider_rejected("anilist", format!(
                 "AniList GraphQL errors: {}",
                 error_messages.join(", ")
             )));
         }
 
         let page_data = graphql_response.data
-            .ok_or_else(|| AppError::Module("No data returned from AniList".to_string()))?;
+            .ok_or_else(|| AppError::provider_rejected("anilist", "No data returned from AniList"))?;
 
         info!(
             task = %self.name(),
@@ -0,0 +1,48 @@
+use std::sync::Arc;
+
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{debug, info, warn};
+
+use crate::anime::anilist::task::FetchAniListAnimePicturesTask;
+use crate::anime::my_anime_list::task::FetchAnimePicturesTask;
+use crate::global::error::AppError;
+use crate::global::events::{AnimeSource, DataEvent, EventBus};
+use crate::global::queue::Task;
+use crate::picture::PictureFetcherModule;
+
+/// Queue picture downloads for every anime stored without them (`[anime] auto_pictures`)
+pub fn spawn_auto_pictures(events: &EventBus, picture_module: Arc<PictureFetcherModule>) {
+    let mut rx = events.subscribe();
+    info!("Automatic anime picture downloads enabled");
+
+    tokio::spawn(async move {
+        loop {
+            match rx.recv().await {
+                Ok(DataEvent::AnimeStored { source, id, pictures_queued: false }) => {
+                    if let Err(e) = queue_pictures(&picture_module, source, id).await {
+                        warn!(source = ?source, id = id, error = %e, "Failed to queue anime pictures");
+                    }
+                }
+                Ok(_) => {}
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!(skipped = skipped, "Auto pictures fell behind, some anime were skipped");
+                }
+                Err(RecvError::Closed) => break,
+            }
+        }
+    });
+}
+
+async fn queue_pictures(
+    picture_module: &Arc<PictureFetcherModule>,
+    source: AnimeSource,
+    id: u32,
+) -> Result<(), AppError> {
+    let task: Box<dyn Task> = match source {
+        AnimeSource::MyAnimeList => Box::new(FetchAnimePicturesTask::new(id, picture_module.clone())),
+        AnimeSource::AniList => Box::new(FetchAniListAnimePicturesTask::new(id, picture_module.clone())),
+    };
+
+    debug!(source = ?source, id = id, "Queueing pictures of stored anime");
+    picture_module.queue().enqueue(task).await
+}
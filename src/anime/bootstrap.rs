@@ -0,0 +1,241 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use mongodb::bson::{self, doc};
+use mongodb::Database;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, error, info};
+
+use crate::anime::anilist::AniListModule;
+use crate::anime::collect::CollectTarget;
+use crate::anime::my_anime_list::{self, model::Season, MyAnimeListModule};
+use crate::global::config::AppConfig;
+use crate::global::database::DatabaseInstance;
+use crate::global::error::{AppError, DatabaseError};
+use crate::global::http::{ClientWithLimiter, HttpClientManager};
+use crate::global::job;
+
+// Collection name for the bootstrap state, a single document
+const COLLECTION_NAME: &str = "bootstrap_state";
+const STATE_ID: &str = "state";
+
+/// `[bootstrap]` config section: collections queued once, on the first start of a deployment
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct BootstrapConfig {
+    #[serde(default)]
+    pub jobs: Vec<BootstrapJob>,
+}
+
+/// A collection queued on first start, `[[bootstrap.jobs]]` with a `kind`
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum BootstrapJob {
+    /// Every anime of the season airing when the job runs
+    CurrentSeason,
+    /// Every anime of a season
+    Season { year: u32, season: Season },
+    /// The `limit` best ranked anime
+    Top { limit: usize },
+}
+
+impl BootstrapJob {
+    /// Key of the job in the bootstrap state, a job runs once per key
+    pub fn id(&self) -> String {
+        match self {
+            Self::CurrentSeason => "current_season".to_string(),
+            Self::Season { year, season } => format!("season_{}_{}", year, season.as_str()),
+            Self::Top { limit } => format!("top_{}", limit),
+        }
+    }
+}
+
+/// A bootstrap job that was queued
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BootstrapRecord {
+    /// Job following the queued collections
+    pub job_id: String,
+    pub queued: u64,
+    /// Listed anime already stored
+    pub skipped: u64,
+    pub completed_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct BootstrapState {
+    /// Bootstrap jobs already queued, by job key
+    #[serde(default)]
+    jobs: HashMap<String, BootstrapRecord>,
+    /// Job of each bootstrap job whose queueing didn't finish, resumed on the next start
+    #[serde(default)]
+    started: HashMap<String, String>,
+}
+
+async fn get_state(db: &Database) -> Result<BootstrapState, DatabaseError> {
+    let state = db.collection::<BootstrapState>(COLLECTION_NAME)
+        .find_one(doc! { "_id": STATE_ID })
+        .await
+        .map_err(|e| DatabaseError::Query(format!("Failed to get bootstrap state: {}", e)))?;
+
+    Ok(state.unwrap_or_default())
+}
+
+async fn record_started(db: &Database, id: &str, job_id: &str) -> Result<(), DatabaseError> {
+    db.collection::<bson::Document>(COLLECTION_NAME)
+        .update_one(doc! { "_id": STATE_ID }, doc! { "$set": { format!("started.{}", id): job_id } })
+        .upsert(true)
+        .await
+        .map_err(|e| DatabaseError::Query(format!("Failed to record bootstrap job start: {}", e)))?;
+
+    Ok(())
+}
+
+async fn record_completed(db: &Database, id: &str, record: &BootstrapRecord) -> Result<(), DatabaseError> {
+    let record = bson::to_bson(record)
+        .map_err(|e| DatabaseError::Query(format!("Failed to serialize bootstrap record: {}", e)))?;
+
+    db.collection::<bson::Document>(COLLECTION_NAME)
+        .update_one(
+            doc! { "_id": STATE_ID },
+            doc! {
+                "$set": { format!("jobs.{}", id): record },
+                "$unset": { format!("started.{}", id): "" },
+            },
+        )
+        .upsert(true)
+        .await
+        .map_err(|e| DatabaseError::Query(format!("Failed to record bootstrap job: {}", e)))?;
+
+    Ok(())
+}
+
+/// List the anime of a bootstrap job and queue the complete collection of those not stored yet.
+/// The job of an interrupted run is resumed, anime stored meanwhile are skipped.
+async fn run_job(
+    bootstrap_job: &BootstrapJob,
+    started_job: Option<&str>,
+    config: &AppConfig,
+    db: &DatabaseInstance,
+    http_manager: &HttpClientManager,
+    mal_module: &MyAnimeListModule,
+) -> Result<BootstrapRecord, AppError> {
+    let api_key = config.get_api_key("my_anime_list")
+        .expect("API key should be validated during module creation");
+    let mal = http_manager.my_anime_list();
+
+    let ids = match bootstrap_job {
+        BootstrapJob::CurrentSeason => {
+            let (season, year) = crate::anime::season::season_of(chrono::Utc::now());
+            my_anime_list::season::fetch_season_anime_ids(mal, &api_key, year as u32, &season).await?
+        }
+        BootstrapJob::Season { year, season } => {
+            my_anime_list::season::fetch_season_anime_ids(mal, &api_key, *year, season).await?
+        }
+        BootstrapJob::Top { limit } => {
+            my_anime_list::season::fetch_top_anime_ids(mal, &api_key, *limit).await?
+        }
+    };
+
+    let anilist_client = AniListModule::is_available(config)
+        .then(|| http_manager.anilist().clone());
+    let resumed = match started_job {
+        Some(job_id) => job::reopen_job(db, job_id).await?,
+        None => None,
+    };
+    let job_id = match resumed {
+        Some(job) => {
+            info!(bootstrap_job = %bootstrap_job.id(), job_id = %job.job_id, "Resuming interrupted bootstrap job");
+            job.job_id
+        }
+        None => {
+            let job = job::create_job(db, "bootstrap", format!("Bootstrap {}", bootstrap_job.id())).await?;
+            record_started(db.db(), &bootstrap_job.id(), &job.job_id).await?;
+            job.job_id
+        }
+    };
+
+    // Sealed even when queueing fails, so the job finishes with the tasks it got
+    let result = queue_missing(ids, db, mal_module, anilist_client, &job_id).await;
+    job::seal_job(db, &job_id, mal_module.events()).await?;
+    let (queued, skipped) = result?;
+
+    Ok(BootstrapRecord {
+        job_id,
+        queued,
+        skipped,
+        completed_at: chrono::Utc::now(),
+    })
+}
+
+/// Queue the complete collection of the listed anime not stored yet.
+/// Returns the number of queued and skipped anime.
+async fn queue_missing(
+    ids: Vec<u32>,
+    db: &DatabaseInstance,
+    mal_module: &MyAnimeListModule,
+    anilist_client: Option<ClientWithLimiter>,
+    job_id: &str,
+) -> Result<(u64, u64), AppError> {
+    let mut queued = 0;
+    let mut skipped = 0;
+    for mal_id in ids {
+        if my_anime_list::database::anime_exists(db.db(), mal_id as i32).await? {
+            skipped += 1;
+            continue;
+        }
+        mal_module.queue_collect(CollectTarget::MalId(mal_id), anilist_client.clone(), job_id).await?;
+        queued += 1;
+    }
+    Ok((queued, skipped))
+}
+
+/// Queue the `[bootstrap]` jobs that never ran on this database. A job is recorded once
+/// its collections are queued, a failed job runs again on the next start.
+pub fn spawn_bootstrap(
+    config: Arc<AppConfig>,
+    db: Arc<DatabaseInstance>,
+    http_manager: HttpClientManager,
+    mal_module: Arc<MyAnimeListModule>,
+) {
+    if config.bootstrap.jobs.is_empty() {
+        return;
+    }
+
+    tokio::spawn(async move {
+        let state = match get_state(db.db()).await {
+            Ok(state) => state,
+            Err(e) => {
+                error!(error = %e, "Failed to read bootstrap state, skipping bootstrap");
+                return;
+            }
+        };
+
+        for bootstrap_job in &config.bootstrap.jobs {
+            let id = bootstrap_job.id();
+            if state.jobs.contains_key(&id) {
+                debug!(bootstrap_job = %id, "Bootstrap job already ran");
+                continue;
+            }
+
+            info!(bootstrap_job = %id, "Running bootstrap job");
+            let started_job = state.started.get(&id).map(String::as_str);
+            let record = match run_job(bootstrap_job, started_job, &config, &db, &http_manager, &mal_module).await {
+                Ok(record) => record,
+                Err(e) => {
+                    error!(bootstrap_job = %id, error = %e, "Bootstrap job failed, it resumes on the next start");
+                    continue;
+                }
+            };
+
+            info!(
+                bootstrap_job = %id,
+                job_id = %record.job_id,
+                queued = record.queued,
+                skipped = record.skipped,
+                "Bootstrap job queued"
+            );
+            if let Err(e) = record_completed(db.db(), &id, &record).await {
+                error!(bootstrap_job = %id, error = %e, "Failed to record bootstrap job");
+            }
+        }
+    });
+}
@@ -0,0 +1,237 @@
+use std::sync::Arc;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::global::{
+    error::AppError,
+    events::EventBus,
+    http::{ClientWithLimiter, RequestConfig},
+    queue::{Task, TaskContext, TaskData, TaskPriority, TaskQueue},
+};
+use crate::anime::anilist;
+use crate::anime::my_anime_list::task::{
+    FetchAnimeTask, FetchCharactersTask, FetchEpisodesTask, FetchMoreInfoTask,
+    FetchPicturesTask, FetchRecommendationsTask, FetchStaffTask, FetchStatisticsTask,
+    FetchVideosTask,
+};
+use crate::picture::PictureFetcherModule;
+
+/// Anime to collect, by MAL id or by title (first MAL search result)
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CollectTarget {
+    MalId(u32),
+    Title(String),
+}
+
+impl std::fmt::Display for CollectTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CollectTarget::MalId(id) => write!(f, "MAL id {}", id),
+            CollectTarget::Title(title) => write!(f, "title '{}'", title),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CollectAnimePayload {
+    pub target: CollectTarget,
+    pub with_anilist: bool,
+}
+
+/// Task that queues the complete collection chain of an anime:
+/// MAL fetch with Jikan enrichment, extended data, AniList fetch and all pictures.
+/// Queued tasks inherit the job of this task.
+pub struct CollectAnimeTask {
+    id: String,
+    target: CollectTarget,
+    api_key: String,
+    mal_client: ClientWithLimiter,
+    jikan_client: ClientWithLimiter,
+    /// AniList client, None when the AniList child module is disabled
+    anilist_client: Option<ClientWithLimiter>,
+    queue: TaskQueue,
+    picture_module: Option<Arc<PictureFetcherModule>>,
+    events: Option<EventBus>,
+    created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl CollectAnimeTask {
+    pub fn new(
+        target: CollectTarget,
+        api_key: String,
+        mal_client: ClientWithLimiter,
+        jikan_client: ClientWithLimiter,
+        queue: TaskQueue,
+    ) -> Self {
+        Self {
+            id: format!("collect_anime_{}", uuid::Uuid::new_v4()),
+            target,
+            api_key,
+            mal_client,
+            jikan_client,
+            anilist_client: None,
+            queue,
+            picture_module: None,
+            events: None,
+            created_at: chrono::Utc::now(),
+        }
+    }
+
+    /// Also fetch the anime from AniList
+    pub fn with_anilist(mut self, client: ClientWithLimiter) -> Self {
+        self.anilist_client = Some(client);
+        self
+    }
+
+    /// Download the pictures of the anime
+    pub fn with_pictures(mut self, picture_module: Arc<PictureFetcherModule>) -> Self {
+        self.picture_module = Some(picture_module);
+        self
+    }
+
+    pub fn with_events(mut self, events: EventBus) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    /// Resolve the target to a MAL id
+    async fn resolve_mal_id(&self) -> Result<u32, AppError> {
+        let title = match &self.target {
+            CollectTarget::MalId(id) => return Ok(*id),
+            CollectTarget::Title(title) => title,
+        };
+
+        #[derive(Deserialize)]
+        struct SearchResponse {
+            data: Vec<SearchResult>,
+        }
+
+        #[derive(Deserialize)]
+        struct SearchResult {
+            node: SearchNode,
+        }
+
+        #[derive(Deserialize)]
+        struct SearchNode {
+            id: u32,
+            title: String,
+        }
+
+        let url = format!(
+            "{}/anime?q={}&limit=1&fields=id,title",
+            self.mal_client.base_url,
+            urlencoding::encode(title)
+        );
+        let config = RequestConfig::new().with_header("X-MAL-CLIENT-ID", &self.api_key);
+
+        let response = self.mal_client
+            .fetch_json::<SearchResponse>(&url, Some(config))
+            .await?;
+
+        let node = response.data.into_iter().next()
+            .map(|result| result.node)
+            .ok_or_else(|| AppError::provider_rejected("my_anime_list", format!("No anime found for title '{}'", title)))?;
+
+        info!(
+            task = %self.name(),
+            query = %title,
+            mal_id = node.id,
+            title = %node.title,
+            "Resolved anime title"
+        );
+
+        Ok(node.id)
+    }
+}
+
+#[async_trait::async_trait]
+impl Task for CollectAnimeTask {
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+
+    fn name(&self) -> &str {
+        "collect_anime"
+    }
+
+    fn priority(&self) -> TaskPriority {
+        TaskPriority::Normal
+    }
+
+    fn to_data(&self) -> TaskData {
+        let payload = CollectAnimePayload {
+            target: self.target.clone(),
+            with_anilist: self.anilist_client.is_some(),
+        };
+
+        TaskData::new(
+            self.id.clone(),
+            self.name().to_string(),
+            self.priority(),
+            self.created_at,
+            serde_json::json!(payload),
+        )
+    }
+
+    async fn execute(&self, _ctx: &TaskContext) -> Result<(), AppError> {
+        let mal_id = self.resolve_mal_id().await?;
+
+        info!(
+            task = %self.name(),
+            mal_id = mal_id,
+            with_anilist = self.anilist_client.is_some(),
+            with_pictures = self.picture_module.is_some(),
+            "Queueing complete anime collection"
+        );
+
+        // MyAnimeList + Jikan, then pictures
+        let mut fetch = FetchAnimeTask::new(
+            mal_id,
+            self.api_key.clone(),
+            self.mal_client.clone(),
+            self.jikan_client.clone(),
+        )
+        .with_jikan();
+        if let Some(picture_module) = &self.picture_module {
+            fetch = fetch.with_pictures(picture_module.clone());
+        }
+        if let Some(events) = &self.events {
+            fetch = fetch.with_events(events.clone());
+        }
+        self.queue.enqueue(Box::new(fetch)).await?;
+
+        // Extended data from Jikan
+        let jikan = &self.jikan_client;
+        let extended: Vec<Box<dyn Task>> = vec![
+            Box::new(FetchCharactersTask::new(mal_id, jikan.clone())),
+            Box::new(FetchStaffTask::new(mal_id, jikan.clone())),
+            Box::new(FetchEpisodesTask::new(mal_id, jikan.clone())),
+            Box::new(FetchVideosTask::new(mal_id, jikan.clone())),
+            Box::new(FetchStatisticsTask::new(mal_id, jikan.clone())),
+            Box::new(FetchMoreInfoTask::new(mal_id, jikan.clone())),
+            Box::new(FetchRecommendationsTask::new(mal_id, jikan.clone())),
+            Box::new(FetchPicturesTask::new(mal_id, jikan.clone())),
+        ];
+        for task in extended {
+            self.queue.enqueue(task).await?;
+        }
+
+        // AniList, then pictures
+        match &self.anilist_client {
+            Some(client) => {
+                let mut fetch = anilist::task::FetchAnimeTask::by_mal_id(mal_id, client.clone());
+                if let Some(picture_module) = &self.picture_module {
+                    fetch = fetch.with_pictures(picture_module.clone());
+                }
+                if let Some(events) = &self.events {
+                    fetch = fetch.with_events(events.clone());
+                }
+                self.queue.enqueue(Box::new(fetch)).await?;
+            }
+            None => warn!(task = %self.name(), mal_id = mal_id, "AniList disabled, skipping AniList data"),
+        }
+
+        Ok(())
+    }
+}
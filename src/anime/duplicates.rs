@@ -0,0 +1,330 @@
+use std::collections::HashMap;
+
+use futures::stream::StreamExt;
+use mongodb::bson::doc;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::anime::anilist;
+use crate::anime::episodes;
+use crate::anime::error::AnimeError;
+use crate::anime::my_anime_list;
+use crate::anime::my_anime_list::model::AnimeData;
+use crate::global::database::DatabaseInstance;
+use crate::global::error::{AppError, DatabaseError};
+use crate::global::events::AnimeSource;
+use crate::global::queue::{Task, TaskContext, TaskData, TaskPriority};
+use crate::picture;
+
+// Collection name for the reports of duplicate scans
+const REPORTS_COLLECTION: &str = "anime_duplicates";
+
+/// Collection a duplicate entry lives in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DuplicateSource {
+    /// `anime_mal`, ids are MAL ids
+    Mal,
+    /// `anime_anilist`, ids are AniList ids
+    Anilist,
+}
+
+impl From<DuplicateSource> for AnimeSource {
+    fn from(source: DuplicateSource) -> Self {
+        match source {
+            DuplicateSource::Mal => AnimeSource::MyAnimeList,
+            DuplicateSource::Anilist => AnimeSource::AniList,
+        }
+    }
+}
+
+/// Entries that most likely describe the same anime
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicateGroup {
+    pub source: DuplicateSource,
+    /// What the entries have in common, e.g. `"cowboy bebop" (1998, TV)` or `mal_id 1`
+    pub reason: String,
+    pub ids: Vec<i32>,
+}
+
+/// Result of a duplicate scan, stored in the `anime_duplicates` collection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DuplicateReport {
+    pub scanned_mal: u64,
+    pub scanned_anilist: u64,
+    pub groups: Vec<DuplicateGroup>,
+    pub detected_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Result of merging duplicate entries into one
+#[derive(Debug, Clone, Serialize)]
+pub struct MergeReport {
+    pub source: DuplicateSource,
+    pub kept: i32,
+    pub removed: Vec<i32>,
+    pub pictures_moved: u64,
+    /// Episodes of removed MAL entries moved to the kept one
+    pub episodes_moved: u64,
+    /// AniList entries whose `mal_id` pointed to a removed MAL entry
+    pub anilist_repointed: u64,
+}
+
+/// Scan both anime collections for duplicates:
+/// MAL entries with the same title, year and media type (e.g. left behind by a MAL ID merge)
+/// and AniList entries sharing the same `mal_id`.
+pub async fn detect_duplicates(db: &DatabaseInstance) -> Result<DuplicateReport, AppError> {
+    let mut scanned_mal = 0;
+    let mut by_title: HashMap<String, Vec<i32>> = HashMap::new();
+    let mut cursor = my_anime_list::database::get_all_anime_cursor(db.db()).await?;
+    while let Some(result) = cursor.next().await {
+        match result {
+            Ok(anime) => {
+                scanned_mal += 1;
+                by_title.entry(title_key(&anime)).or_default().push(anime.mal_id);
+            }
+            Err(e) => warn!(error = %e, "Failed to deserialize anime, skipping"),
+        }
+    }
+
+    let mut scanned_anilist = 0;
+    let mut by_mal_id: HashMap<i32, Vec<i32>> = HashMap::new();
+    let mut cursor = anilist::database::get_all_anime_cursor(db.db()).await?;
+    while let Some(result) = cursor.next().await {
+        match result {
+            Ok(anime) => {
+                scanned_anilist += 1;
+                if let Some(mal_id) = anime.mal_id {
+                    by_mal_id.entry(mal_id).or_default().push(anime.anilist_id);
+                }
+            }
+            Err(e) => warn!(error = %e, "Failed to deserialize AniList anime, skipping"),
+        }
+    }
+
+    let mut groups: Vec<DuplicateGroup> = by_title.into_iter()
+        .filter(|(_, ids)| ids.len() > 1)
+        .map(|(reason, ids)| DuplicateGroup { source: DuplicateSource::Mal, reason, ids })
+        .chain(
+            by_mal_id.into_iter()
+                .filter(|(_, ids)| ids.len() > 1)
+                .map(|(mal_id, ids)| DuplicateGroup {
+                    source: DuplicateSource::Anilist,
+                    reason: format!("mal_id {}", mal_id),
+                    ids,
+                })
+        )
+        .collect();
+    groups.sort_by(|a, b| a.ids.cmp(&b.ids));
+
+    info!(
+        scanned_mal = scanned_mal,
+        scanned_anilist = scanned_anilist,
+        groups = groups.len(),
+        "Duplicate scan finished"
+    );
+
+    Ok(DuplicateReport {
+        scanned_mal,
+        scanned_anilist,
+        groups,
+        detected_at: chrono::Utc::now(),
+    })
+}
+
+/// `"<normalized title>" (<year>, <media type>)`
+fn title_key(anime: &AnimeData) -> String {
+    let title: String = anime.display_title()
+        .to_lowercase()
+        .chars()
+        .filter(|c| c.is_alphanumeric() || c.is_whitespace())
+        .collect();
+    let title = title.split_whitespace().collect::<Vec<_>>().join(" ");
+
+    let year = anime.year.map(|year| year.to_string()).unwrap_or_else(|| "unknown year".to_string());
+    let media_type = anime.media_type.as_ref()
+        .map(|media_type| format!("{:?}", media_type))
+        .unwrap_or_else(|| "unknown type".to_string());
+
+    format!("\"{}\" ({}, {})", title, year, media_type)
+}
+
+pub async fn save_report(db: &DatabaseInstance, report: &DuplicateReport) -> Result<(), AppError> {
+    db.collection::<DuplicateReport>(REPORTS_COLLECTION)
+        .insert_one(report)
+        .await
+        .map_err(|e| AppError::Database(DatabaseError::Query(format!("Failed to save duplicate report: {}", e))))?;
+
+    Ok(())
+}
+
+/// Most recent duplicate scan
+pub async fn latest_report(db: &DatabaseInstance) -> Result<Option<DuplicateReport>, AppError> {
+    db.collection::<DuplicateReport>(REPORTS_COLLECTION)
+        .find_one(doc! {})
+        .sort(doc! { "detected_at": -1 })
+        .await
+        .map_err(|e| AppError::Database(DatabaseError::Query(format!("Failed to get duplicate report: {}", e))))
+}
+
+/// Merge the `remove` entries into `keep` and delete them.
+/// Extended data missing from `keep` is taken from the removed entries, their pictures
+/// are moved to `keep` and, for MAL entries, AniList entries pointing to them are updated.
+pub async fn merge_duplicates(
+    db: &DatabaseInstance,
+    source: DuplicateSource,
+    keep: i32,
+    remove: &[i32],
+) -> Result<MergeReport, AppError> {
+    let remove: Vec<i32> = remove.iter().copied().filter(|id| *id != keep).collect();
+
+    let mut report = MergeReport {
+        source,
+        kept: keep,
+        removed: Vec::with_capacity(remove.len()),
+        pictures_moved: 0,
+        episodes_moved: 0,
+        anilist_repointed: 0,
+    };
+
+    match source {
+        DuplicateSource::Mal => merge_mal(db, keep, &remove, &mut report).await?,
+        DuplicateSource::Anilist => merge_anilist(db, keep, &remove, &mut report).await?,
+    }
+
+    info!(
+        source = ?source,
+        kept = keep,
+        removed = ?report.removed,
+        pictures_moved = report.pictures_moved,
+        "Duplicate anime merged"
+    );
+
+    Ok(report)
+}
+
+async fn merge_mal(db: &DatabaseInstance, keep: i32, remove: &[i32], report: &mut MergeReport) -> Result<(), AppError> {
+    let mut kept = my_anime_list::database::get_anime_by_id(db.db(), keep).await?
+        .ok_or(AnimeError::NotFound)?;
+
+    let mut duplicates = Vec::with_capacity(remove.len());
+    for &mal_id in remove {
+        duplicates.push(my_anime_list::database::get_anime_by_id(db.db(), mal_id).await?.ok_or(AnimeError::NotFound)?);
+    }
+
+    for duplicate in duplicates {
+        fill_if_empty(&mut kept.characters, duplicate.characters);
+        fill_if_empty(&mut kept.staffs, duplicate.staffs);
+        fill_if_empty(&mut kept.pictures, duplicate.pictures);
+        fill_if_empty(&mut kept.recommendations, duplicate.recommendations);
+        kept.videos = kept.videos.take().or(duplicate.videos);
+        kept.statistics = kept.statistics.take().or(duplicate.statistics);
+        kept.more_info = kept.more_info.take().or(duplicate.more_info);
+        kept.collected_at = match (kept.collected_at, duplicate.collected_at) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        };
+    }
+    my_anime_list::database::upsert_anime(db.db(), &kept).await?;
+    if let Some(collected_at) = kept.collected_at {
+        my_anime_list::database::set_earliest_collected_at(db.db(), keep, collected_at).await?;
+    }
+
+    for &mal_id in remove {
+        report.pictures_moved += picture::database::reassign_entity_pictures(db.db(), "anime", &mal_id.to_string(), &keep.to_string()).await?;
+        report.episodes_moved += episodes::reassign_episodes(db.db(), mal_id, keep).await?;
+        report.anilist_repointed += anilist::database::replace_mal_id(db.db(), mal_id, keep).await?;
+        my_anime_list::database::delete_anime(db.db(), mal_id).await?;
+        report.removed.push(mal_id);
+    }
+
+    Ok(())
+}
+
+async fn merge_anilist(db: &DatabaseInstance, keep: i32, remove: &[i32], report: &mut MergeReport) -> Result<(), AppError> {
+    let mut kept = anilist::database::get_anime_by_id(db.db(), keep).await?
+        .ok_or(AnimeError::NotFound)?;
+
+    let mut duplicates = Vec::with_capacity(remove.len());
+    for &anilist_id in remove {
+        duplicates.push(anilist::database::get_anime_by_id(db.db(), anilist_id).await?.ok_or(AnimeError::NotFound)?);
+    }
+
+    for duplicate in duplicates {
+        fill_if_empty(&mut kept.characters, duplicate.characters);
+        fill_if_empty(&mut kept.staffs, duplicate.staffs);
+        fill_if_empty(&mut kept.tags, duplicate.tags);
+        kept.statistics = kept.statistics.take().or(duplicate.statistics);
+        kept.mal_id = kept.mal_id.or(duplicate.mal_id);
+    }
+    anilist::database::upsert_anime(db.db(), &kept).await?;
+
+    for &anilist_id in remove {
+        report.pictures_moved += picture::database::reassign_entity_pictures(db.db(), "anilist", &anilist_id.to_string(), &keep.to_string()).await?;
+        anilist::database::delete_anime(db.db(), anilist_id).await?;
+        report.removed.push(anilist_id);
+    }
+
+    Ok(())
+}
+
+fn fill_if_empty<T>(target: &mut Vec<T>, other: Vec<T>) {
+    if target.is_empty() {
+        *target = other;
+    }
+}
+
+// ========================================================================
+// Detect Duplicates Task
+// ========================================================================
+
+/// Scans for duplicate anime entries and stores the report
+pub struct DetectDuplicatesTask {
+    id: String,
+    created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl DetectDuplicatesTask {
+    pub fn new() -> Self {
+        Self {
+            id: format!("detect_duplicates_{}", uuid::Uuid::new_v4()),
+            created_at: chrono::Utc::now(),
+        }
+    }
+}
+
+impl Default for DetectDuplicatesTask {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait::async_trait]
+impl Task for DetectDuplicatesTask {
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+
+    fn name(&self) -> &str {
+        "detect_duplicates"
+    }
+
+    fn priority(&self) -> TaskPriority {
+        TaskPriority::Low
+    }
+
+    fn to_data(&self) -> TaskData {
+        TaskData::new(
+            self.id.clone(),
+            self.name().to_string(),
+            self.priority(),
+            self.created_at,
+            serde_json::json!({}),
+        )
+    }
+
+    async fn execute(&self, ctx: &TaskContext) -> Result<(), AppError> {
+        let db = ctx.db.clone();
+        let report = detect_duplicates(&db).await?;
+        save_report(&db, &report).await
+    }
+}
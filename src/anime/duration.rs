@@ -0,0 +1,70 @@
+use futures::stream::StreamExt;
+use mongodb::bson::{doc, Document};
+use mongodb::Collection;
+use tracing::warn;
+
+use crate::global::error::DatabaseError;
+
+/// Seconds per episode from provider durations like "24 min per ep", "1 hr 55 min"
+/// or "23 min. per ep.", `None` when no amount is recognized
+pub fn parse_duration_seconds(duration: &str) -> Option<i32> {
+    let words: Vec<String> = duration.split_whitespace()
+        .map(|word| word.trim_end_matches('.').to_lowercase())
+        .collect();
+
+    let seconds: i32 = words.windows(2)
+        .filter_map(|pair| {
+            let value: i32 = pair[0].parse().ok()?;
+            match pair[1].as_str() {
+                "hr" | "hrs" | "hour" | "hours" | "h" => Some(value * 3600),
+                "min" | "mins" | "minute" | "minutes" | "m" => Some(value * 60),
+                "sec" | "secs" | "second" | "seconds" | "s" => Some(value),
+                _ => None,
+            }
+        })
+        .sum();
+
+    (seconds > 0).then_some(seconds)
+}
+
+/// Seconds per episode of a stored anime: the provider average when known,
+/// otherwise parsed from the human readable duration
+pub fn duration_seconds(average_episode_duration: i32, duration: &str) -> Option<i32> {
+    if average_episode_duration > 0 {
+        return Some(average_episode_duration);
+    }
+    parse_duration_seconds(duration)
+}
+
+/// Set `duration_seconds` on the anime of a collection stored before it existed,
+/// returns the number of updated documents
+pub async fn backfill_duration_seconds(collection: Collection<Document>) -> Result<u64, DatabaseError> {
+    let mut cursor = collection
+        .find(doc! { "duration_seconds": { "$exists": false } })
+        .projection(doc! { "average_episode_duration": 1, "duration": 1 })
+        .await
+        .map_err(|e| DatabaseError::Query(format!("Failed to find anime without duration: {}", e)))?;
+
+    let mut updated = 0;
+    while let Some(result) = cursor.next().await {
+        let anime = match result {
+            Ok(anime) => anime,
+            Err(e) => {
+                warn!(error = %e, "Failed to read anime during duration backfill");
+                continue;
+            }
+        };
+        let Ok(id) = anime.get_object_id("_id") else { continue };
+
+        let seconds = duration_seconds(
+            anime.get_i32("average_episode_duration").unwrap_or(0),
+            anime.get_str("duration").unwrap_or_default(),
+        );
+        collection.update_one(doc! { "_id": id }, doc! { "$set": { "duration_seconds": seconds } })
+            .await
+            .map_err(|e| DatabaseError::Query(format!("Failed to backfill duration: {}", e)))?;
+        updated += 1;
+    }
+
+    Ok(updated)
+}
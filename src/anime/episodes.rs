@@ -0,0 +1,312 @@
+use chrono::{DateTime, Utc};
+use futures::stream::StreamExt;
+use mongodb::bson::{doc, Document};
+use mongodb::options::{FindOptions, IndexOptions};
+use mongodb::{Database, IndexModel};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, warn};
+
+use crate::anime::my_anime_list::{self, model::{Episode, EpisodeSummary}};
+use crate::global::error::DatabaseError;
+
+// Collection name for the episodes of the MyAnimeList anime, one document per episode
+const COLLECTION_NAME: &str = "anime_episodes";
+
+/// Episode of a stored anime, keyed by the anime MAL id and the episode number
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnimeEpisode {
+    /// MAL id of the anime
+    pub mal_id: i32,
+    /// Episode number
+    pub episode: i32,
+    pub url: Option<String>,
+    pub title: String,
+    pub title_japanese: Option<String>,
+    pub title_romanji: Option<String>,
+    /// Seconds
+    pub duration: Option<i32>,
+    pub aired: Option<DateTime<Utc>>,
+    pub score: Option<f32>,
+    pub filler: bool,
+    pub recap: bool,
+    #[serde(default)]
+    pub forum_url: Option<String>,
+    /// Only returned by the episode detail endpoint
+    #[serde(default)]
+    pub synopsis: Option<String>,
+    /// When the episode detail was last fetched
+    #[serde(default)]
+    pub detail_updated_at: Option<DateTime<Utc>>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl AnimeEpisode {
+    pub fn new(mal_id: i32, episode: Episode) -> Self {
+        Self {
+            mal_id,
+            episode: episode.mal_id,
+            url: episode.url,
+            title: episode.title,
+            title_japanese: episode.title_japanese,
+            title_romanji: episode.title_romanji,
+            duration: episode.duration,
+            aired: episode.aired,
+            score: episode.score,
+            filler: episode.filler,
+            recap: episode.recap,
+            forum_url: episode.forum_url,
+            synopsis: None,
+            detail_updated_at: None,
+            updated_at: Utc::now(),
+        }
+    }
+}
+
+/// Filters of an episode list, unset ones match every episode
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+pub struct EpisodeFilter {
+    pub filler: Option<bool>,
+    pub recap: Option<bool>,
+}
+
+pub async fn initialize_collection(db: &Database) -> Result<(), DatabaseError> {
+    let collection = db.collection::<AnimeEpisode>(COLLECTION_NAME);
+
+    let episode_index = IndexModel::builder()
+        .keys(doc! { "mal_id": 1, "episode": 1 })
+        .options(IndexOptions::builder().unique(true).build())
+        .build();
+
+    // Filler lists across titles
+    let filler_index = IndexModel::builder()
+        .keys(doc! { "filler": 1, "mal_id": 1 })
+        .build();
+
+    collection.create_indexes(vec![episode_index, filler_index]).await
+        .map_err(|e| DatabaseError::Query(format!("Failed to create episode indexes: {}", e)))?;
+
+    debug!("Created indexes for anime_episodes collection");
+    Ok(())
+}
+
+/// Listed episodes in order. Jikan can list an episode twice across pages, the last one wins.
+fn listed_episodes(mal_id: i32, episodes: Vec<Episode>) -> Vec<AnimeEpisode> {
+    let mut episodes: Vec<AnimeEpisode> = episodes.into_iter()
+        .map(|episode| AnimeEpisode::new(mal_id, episode))
+        .collect();
+    episodes.reverse();
+    let mut seen = std::collections::HashSet::new();
+    episodes.retain(|episode| seen.insert(episode.episode));
+    episodes.sort_by_key(|episode| episode.episode);
+    episodes
+}
+
+/// Keep what the detail endpoint added, the list endpoint has no synopsis nor duration
+fn keep_details(episodes: &mut [AnimeEpisode], stored: Vec<AnimeEpisode>) {
+    let details: std::collections::HashMap<i32, AnimeEpisode> = stored.into_iter()
+        .filter(|episode| episode.detail_updated_at.is_some())
+        .map(|episode| (episode.episode, episode))
+        .collect();
+    for episode in episodes {
+        if let Some(detail) = details.get(&episode.episode) {
+            episode.synopsis = detail.synopsis.clone();
+            episode.duration = episode.duration.or(detail.duration);
+            episode.detail_updated_at = detail.detail_updated_at;
+        }
+    }
+}
+
+/// Replace the episodes of an anime and update the summary on its document
+pub async fn replace_episodes(db: &Database, mal_id: i32, episodes: Vec<Episode>) -> Result<EpisodeSummary, DatabaseError> {
+    let collection = db.collection::<AnimeEpisode>(COLLECTION_NAME);
+
+    let mut episodes = listed_episodes(mal_id, episodes);
+    keep_details(&mut episodes, get_episodes(db, mal_id, EpisodeFilter::default()).await?);
+
+    collection.delete_many(doc! { "mal_id": mal_id }).await
+        .map_err(|e| DatabaseError::Query(format!("Failed to delete episodes: {}", e)))?;
+
+    if !episodes.is_empty() {
+        collection.insert_many(&episodes).await
+            .map_err(|e| DatabaseError::Query(format!("Failed to insert episodes: {}", e)))?;
+    }
+
+    let summary = summarize(&episodes);
+    my_anime_list::database::set_episode_summary(db, mal_id, &summary).await?;
+
+    debug!(mal_id = mal_id, total = summary.total, "Episodes stored");
+    Ok(summary)
+}
+
+/// Add or update some episodes of an anime, keeping the others, and update the summary
+/// on its document. Used by the incremental refresh, which only fetches the last pages.
+pub async fn merge_episodes(db: &Database, mal_id: i32, episodes: Vec<Episode>) -> Result<EpisodeSummary, DatabaseError> {
+    let collection = db.collection::<AnimeEpisode>(COLLECTION_NAME);
+
+    let mut episodes = listed_episodes(mal_id, episodes);
+    let mut stored = get_episodes(db, mal_id, EpisodeFilter::default()).await?;
+    keep_details(&mut episodes, stored.clone());
+
+    for episode in &episodes {
+        collection.replace_one(doc! { "mal_id": mal_id, "episode": episode.episode }, episode)
+            .upsert(true)
+            .await
+            .map_err(|e| DatabaseError::Query(format!("Failed to save episode: {}", e)))?;
+    }
+
+    stored.retain(|episode| !episodes.iter().any(|merged| merged.episode == episode.episode));
+    stored.extend(episodes);
+
+    let summary = summarize(&stored);
+    my_anime_list::database::set_episode_summary(db, mal_id, &summary).await?;
+
+    debug!(mal_id = mal_id, total = summary.total, "Episodes merged");
+    Ok(summary)
+}
+
+/// Store an episode fetched from the detail endpoint, replacing the stored one.
+/// The summary is updated when the episode was not listed yet.
+pub async fn save_episode_detail(db: &Database, episode: &AnimeEpisode) -> Result<(), DatabaseError> {
+    let collection = db.collection::<AnimeEpisode>(COLLECTION_NAME);
+
+    let result = collection.replace_one(doc! { "mal_id": episode.mal_id, "episode": episode.episode }, episode)
+        .upsert(true)
+        .await
+        .map_err(|e| DatabaseError::Query(format!("Failed to save episode: {}", e)))?;
+
+    if result.upserted_id.is_some() {
+        refresh_summary(db, episode.mal_id).await?;
+    }
+
+    debug!(mal_id = episode.mal_id, episode = episode.episode, "Episode detail stored");
+    Ok(())
+}
+
+/// Recompute the summary on the anime document from the stored episodes
+async fn refresh_summary(db: &Database, mal_id: i32) -> Result<(), DatabaseError> {
+    let episodes = get_episodes(db, mal_id, EpisodeFilter::default()).await?;
+    my_anime_list::database::set_episode_summary(db, mal_id, &summarize(&episodes)).await
+}
+
+/// Episode counts of an anime, kept on its document
+pub fn summarize(episodes: &[AnimeEpisode]) -> EpisodeSummary {
+    let now = Utc::now();
+    EpisodeSummary {
+        total: episodes.len() as u32,
+        filler: episodes.iter().filter(|episode| episode.filler).count() as u32,
+        recap: episodes.iter().filter(|episode| episode.recap).count() as u32,
+        latest_aired: latest_aired(episodes, now),
+        updated_at: now,
+    }
+}
+
+/// Number of the last episode aired by `now`
+pub fn latest_aired(episodes: &[AnimeEpisode], now: DateTime<Utc>) -> Option<i32> {
+    episodes.iter()
+        .filter(|episode| episode.aired.is_some_and(|aired| aired <= now))
+        .map(|episode| episode.episode)
+        .max()
+}
+
+/// Episodes of an anime in order
+pub async fn get_episodes(db: &Database, mal_id: i32, filter: EpisodeFilter) -> Result<Vec<AnimeEpisode>, DatabaseError> {
+    let collection = db.collection::<AnimeEpisode>(COLLECTION_NAME);
+
+    let mut query = doc! { "mal_id": mal_id };
+    if let Some(filler) = filter.filler {
+        query.insert("filler", filler);
+    }
+    if let Some(recap) = filter.recap {
+        query.insert("recap", recap);
+    }
+
+    let options = FindOptions::builder()
+        .sort(doc! { "episode": 1 })
+        .build();
+
+    let mut cursor = collection.find(query)
+        .with_options(options)
+        .await
+        .map_err(|e| DatabaseError::Query(format!("Failed to get episodes: {}", e)))?;
+
+    let mut episodes = Vec::new();
+    while let Some(result) = cursor.next().await {
+        match result {
+            Ok(episode) => episodes.push(episode),
+            Err(e) => warn!(mal_id = mal_id, error = %e, "Failed to deserialize episode"),
+        }
+    }
+    Ok(episodes)
+}
+
+pub async fn get_episode(db: &Database, mal_id: i32, episode: i32) -> Result<Option<AnimeEpisode>, DatabaseError> {
+    db.collection::<AnimeEpisode>(COLLECTION_NAME)
+        .find_one(doc! { "mal_id": mal_id, "episode": episode })
+        .await
+        .map_err(|e| DatabaseError::Query(format!("Failed to get episode: {}", e)))
+}
+
+/// Move the episodes of a removed duplicate to the kept anime, unless it has its own.
+/// Returns the number of episodes moved.
+pub async fn reassign_episodes(db: &Database, from: i32, to: i32) -> Result<u64, DatabaseError> {
+    let collection = db.collection::<AnimeEpisode>(COLLECTION_NAME);
+
+    let target_count = collection.count_documents(doc! { "mal_id": to }).await
+        .map_err(|e| DatabaseError::Query(format!("Failed to count episodes: {}", e)))?;
+
+    if target_count > 0 {
+        collection.delete_many(doc! { "mal_id": from }).await
+            .map_err(|e| DatabaseError::Query(format!("Failed to delete episodes: {}", e)))?;
+        return Ok(0);
+    }
+
+    let moved = collection.update_many(doc! { "mal_id": from }, doc! { "$set": { "mal_id": to } }).await
+        .map_err(|e| DatabaseError::Query(format!("Failed to move episodes: {}", e)))?
+        .modified_count;
+
+    if moved > 0 {
+        refresh_summary(db, to).await?;
+    }
+    Ok(moved)
+}
+
+/// Anime document with the episodes embedded before `anime_episodes` existed
+#[derive(Deserialize)]
+struct EmbeddedEpisodes {
+    mal_id: i32,
+    #[serde(default)]
+    episodes: Vec<Episode>,
+}
+
+/// Move the episodes embedded in the anime documents to `anime_episodes`.
+/// Returns the number of anime migrated.
+pub async fn migrate_embedded_episodes(db: &Database) -> Result<u64, DatabaseError> {
+    let anime = db.collection::<Document>(my_anime_list::database::COLLECTION_NAME);
+
+    let mut cursor = anime.clone_with_type::<EmbeddedEpisodes>()
+        .find(doc! { "episodes": { "$exists": true } })
+        .projection(doc! { "mal_id": 1, "episodes": 1 })
+        .await
+        .map_err(|e| DatabaseError::Query(format!("Failed to get embedded episodes: {}", e)))?;
+
+    let mut migrated = 0;
+    while let Some(result) = cursor.next().await {
+        let embedded = match result {
+            Ok(embedded) => embedded,
+            Err(e) => {
+                warn!(error = %e, "Failed to read embedded episodes, skipping anime");
+                continue;
+            }
+        };
+
+        if !embedded.episodes.is_empty() {
+            replace_episodes(db, embedded.mal_id, embedded.episodes).await?;
+        }
+        anime.update_one(doc! { "mal_id": embedded.mal_id }, doc! { "$unset": { "episodes": "" } }).await
+            .map_err(|e| DatabaseError::Query(format!("Failed to remove embedded episodes: {}", e)))?;
+        migrated += 1;
+    }
+
+    info!(migrated = migrated, "Moved embedded episodes to anime_episodes");
+    Ok(migrated)
+}
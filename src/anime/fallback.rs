@@ -0,0 +1,55 @@
+use serde::{Deserialize, Serialize};
+
+use crate::global::error::{AppError, HttpError};
+use crate::global::http::ClientWithLimiter;
+
+/// Provider tried when MyAnimeList has no usable entry for an id
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FallbackProvider {
+    /// Build the entry from Jikan alone, stored in `anime_mal`
+    Jikan,
+    /// Fetch the entry from AniList through its MAL id, stored in `anime_anilist`
+    Anilist,
+}
+
+/// Providers tried in order when a MyAnimeList fetch returns 404
+#[derive(Clone, Default)]
+pub struct ProviderFallback {
+    providers: Vec<FallbackProvider>,
+    anilist_client: Option<ClientWithLimiter>,
+}
+
+impl ProviderFallback {
+    pub fn new(providers: Vec<FallbackProvider>) -> Self {
+        Self {
+            providers,
+            anilist_client: None,
+        }
+    }
+
+    /// Enable the AniList fallback, it is skipped without a client
+    pub fn with_anilist(mut self, client: ClientWithLimiter) -> Self {
+        self.anilist_client = Some(client);
+        self
+    }
+
+    pub fn providers(&self) -> &[FallbackProvider] {
+        &self.providers
+    }
+
+    pub fn anilist_client(&self) -> Option<&ClientWithLimiter> {
+        self.anilist_client.as_ref()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.providers.is_empty()
+    }
+}
+
+/// Errors that retrying MyAnimeList won't fix: the entry doesn't exist there.
+/// Other client errors (bad request, forbidden, region locked), authentication errors
+/// and rate limiting are returned as is rather than hidden behind another provider.
+pub fn is_permanent_error(error: &AppError) -> bool {
+    matches!(error, AppError::Http(HttpError::NotFound(_)))
+}
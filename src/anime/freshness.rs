@@ -0,0 +1,231 @@
+use std::collections::BTreeSet;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tracing::{debug, info};
+
+use crate::anime::image_variants;
+use crate::anime::my_anime_list::database::get_anime_needing_update;
+use crate::anime::my_anime_list::model::{AnimeData, AnimeSection};
+use crate::anime::my_anime_list::task::{
+    FetchCharactersTask, FetchEpisodesTask, FetchMoreInfoTask, FetchPicturesTask,
+    FetchRecommendationsTask, FetchStaffTask, FetchStatisticsTask, FetchVideosTask,
+};
+use crate::global::config::ImageVariantPolicy;
+use crate::global::error::AppError;
+use crate::global::http::ClientWithLimiter;
+use crate::global::queue::{Task, TaskContext, TaskData, TaskPriority, TaskQueue};
+
+/// Largest section age accepted, in days (about a century)
+pub const MAX_SECTION_AGE_DAYS: u64 = 36_500;
+
+/// Task fetching one section of an anime from Jikan
+pub fn section_task(section: AnimeSection, mal_id: u32, jikan_client: ClientWithLimiter) -> Box<dyn Task> {
+    match section {
+        AnimeSection::Characters => Box::new(FetchCharactersTask::new(mal_id, jikan_client)),
+        AnimeSection::Staff => Box::new(FetchStaffTask::new(mal_id, jikan_client)),
+        AnimeSection::Episodes => Box::new(FetchEpisodesTask::new(mal_id, jikan_client).with_incremental(true)),
+        AnimeSection::Videos => Box::new(FetchVideosTask::new(mal_id, jikan_client)),
+        AnimeSection::Statistics => Box::new(FetchStatisticsTask::new(mal_id, jikan_client)),
+        AnimeSection::MoreInfo => Box::new(FetchMoreInfoTask::new(mal_id, jikan_client)),
+        AnimeSection::Recommendations => Box::new(FetchRecommendationsTask::new(mal_id, jikan_client)),
+        AnimeSection::Pictures => Box::new(FetchPicturesTask::new(mal_id, jikan_client)),
+    }
+}
+
+/// Why a section of an anime has to be fetched
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SectionReason {
+    /// Never fetched, or fetched without results
+    Empty,
+    /// Fetched longer ago than the maximum age
+    Stale,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SectionToFetch {
+    pub section: AnimeSection,
+    pub reason: SectionReason,
+}
+
+/// Whether a section holds no data on the stored anime
+pub fn is_section_empty(anime: &AnimeData, section: AnimeSection) -> bool {
+    match section {
+        AnimeSection::Characters => anime.characters.is_empty(),
+        AnimeSection::Staff => anime.staffs.is_empty(),
+        AnimeSection::Episodes => anime.episode_summary.as_ref().is_none_or(|summary| summary.total == 0),
+        AnimeSection::Videos => anime.videos.is_none(),
+        AnimeSection::Statistics => anime.statistics.is_none(),
+        AnimeSection::MoreInfo => anime.more_info.is_none(),
+        AnimeSection::Recommendations => anime.recommendations.is_empty(),
+        AnimeSection::Pictures => anime.pictures.is_empty(),
+    }
+}
+
+/// Sections of a stored anime that are empty or, with `max_age_days`, not fetched since then.
+/// Sections stored before fetch times were recorded count as stale.
+pub fn sections_to_fetch(anime: &AnimeData, max_age_days: Option<i64>, now: DateTime<Utc>) -> Vec<SectionToFetch> {
+    AnimeSection::ALL.into_iter()
+        .filter_map(|section| {
+            let reason = if is_section_empty(anime, section) {
+                SectionReason::Empty
+            } else {
+                let max_age_days = max_age_days?;
+                let fetched_at = anime.fetched_at.get(section.as_str());
+                // An age too large for a duration never goes stale
+                let max_age = chrono::TimeDelta::try_days(max_age_days)?;
+                if fetched_at.is_some_and(|fetched_at| now - *fetched_at < max_age) {
+                    return None;
+                }
+                SectionReason::Stale
+            };
+            Some(SectionToFetch { section, reason })
+        })
+        .collect()
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SectionCompleteness {
+    pub section: AnimeSection,
+    pub populated: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fetched_at: Option<DateTime<Utc>>,
+}
+
+/// Pictures of an anime kept by the variant policy, and how many of them are downloaded
+#[derive(Debug, Clone, Serialize)]
+pub struct PictureCompleteness {
+    pub referenced: usize,
+    pub downloaded: usize,
+}
+
+/// How much of an anime is collected
+#[derive(Debug, Clone, Serialize)]
+pub struct Completeness {
+    /// Share of the populated sections and downloaded pictures, from 0 to 1
+    pub score: f64,
+    /// Every section populated and every picture downloaded
+    pub complete: bool,
+    pub sections: Vec<SectionCompleteness>,
+    pub pictures: PictureCompleteness,
+}
+
+/// URLs of the main image and pictures of an anime downloaded under the variant policy
+pub fn picture_urls(anime: &AnimeData, policy: ImageVariantPolicy) -> BTreeSet<String> {
+    std::iter::once(&anime.images)
+        .chain(&anime.pictures)
+        .flat_map(|images| image_variants::formats(policy, images))
+        .flat_map(|(_, image)| image_variants::sizes(policy, image))
+        .map(|(url, _)| url.to_string())
+        .collect()
+}
+
+/// Completeness of a stored anime, `downloaded` holds the URLs of its completed pictures
+pub fn completeness(anime: &AnimeData, policy: ImageVariantPolicy, downloaded: &BTreeSet<String>) -> Completeness {
+    let sections: Vec<SectionCompleteness> = AnimeSection::ALL.into_iter()
+        .map(|section| SectionCompleteness {
+            section,
+            populated: !is_section_empty(anime, section),
+            fetched_at: anime.fetched_at.get(section.as_str()).copied(),
+        })
+        .collect();
+
+    let referenced = picture_urls(anime, policy);
+    let pictures = PictureCompleteness {
+        referenced: referenced.len(),
+        downloaded: referenced.intersection(downloaded).count(),
+    };
+
+    let populated = sections.iter().filter(|section| section.populated).count();
+    let picture_share = if pictures.referenced == 0 {
+        1.0
+    } else {
+        pictures.downloaded as f64 / pictures.referenced as f64
+    };
+    let score = (populated as f64 + picture_share) / (sections.len() + 1) as f64;
+
+    Completeness {
+        score: (score * 100.0).round() / 100.0,
+        complete: populated == sections.len() && pictures.downloaded == pictures.referenced,
+        sections,
+        pictures,
+    }
+}
+
+// ========================================================================
+// Refresh Stale Sections Task
+// ========================================================================
+
+/// Queues the fetch of every extended data section not fetched for `max_age_days`,
+/// so only the stale sections of an anime are fetched again
+pub struct RefreshStaleSectionsTask {
+    id: String,
+    queue: TaskQueue,
+    jikan_client: ClientWithLimiter,
+    max_age_days: i64,
+    /// Anime refreshed per section
+    limit: i64,
+    created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl RefreshStaleSectionsTask {
+    pub fn new(queue: TaskQueue, jikan_client: ClientWithLimiter, max_age_days: i64, limit: i64) -> Self {
+        Self {
+            id: format!("refresh_stale_sections_{}", uuid::Uuid::new_v4()),
+            queue,
+            jikan_client,
+            max_age_days,
+            limit,
+            created_at: chrono::Utc::now(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Task for RefreshStaleSectionsTask {
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+
+    fn name(&self) -> &str {
+        "refresh_stale_sections"
+    }
+
+    fn priority(&self) -> TaskPriority {
+        TaskPriority::Low
+    }
+
+    fn to_data(&self) -> TaskData {
+        TaskData::new(
+            self.id.clone(),
+            self.name().to_string(),
+            self.priority(),
+            self.created_at,
+            serde_json::json!({ "max_age_days": self.max_age_days, "limit": self.limit }),
+        )
+    }
+
+    async fn execute(&self, ctx: &TaskContext) -> Result<(), AppError> {
+        let db = ctx.db.clone();
+        let mut queued = 0;
+
+        for section in AnimeSection::ALL {
+            let stale = get_anime_needing_update(db.db(), Some(section), self.max_age_days, self.limit).await?;
+            debug!(task = %self.name(), section = section.as_str(), stale = stale.len(), "Queueing stale section fetches");
+
+            for mal_id in stale {
+                self.queue.enqueue(section_task(section, mal_id as u32, self.jikan_client.clone())).await?;
+                queued += 1;
+            }
+        }
+
+        info!(
+            task = %self.name(),
+            max_age_days = self.max_age_days,
+            queued = queued,
+            "Stale section refresh queued"
+        );
+        Ok(())
+    }
+}
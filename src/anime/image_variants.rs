@@ -0,0 +1,56 @@
+use crate::anime::my_anime_list::model::{Image, Images};
+use crate::global::config::ImageVariantPolicy;
+
+/// Formats of an image downloaded under the policy, as ("jpg" | "webp", image)
+pub fn formats(policy: ImageVariantPolicy, images: &Images) -> Vec<(&'static str, &Image)> {
+    match policy {
+        ImageVariantPolicy::All => vec![("jpg", &images.jpg), ("webp", &images.webp)],
+        ImageVariantPolicy::LargeJpg => vec![("jpg", &images.jpg)],
+        ImageVariantPolicy::Best if has_url(&images.jpg) => vec![("jpg", &images.jpg)],
+        ImageVariantPolicy::Best => vec![("webp", &images.webp)],
+    }
+}
+
+/// Sizes of one image downloaded under the policy, as (url, "large" | "small" tag).
+/// The regular size has no tag, URLs shared by several sizes are downloaded once.
+pub fn sizes(policy: ImageVariantPolicy, image: &Image) -> Vec<(&str, Option<&'static str>)> {
+    match policy {
+        ImageVariantPolicy::All => {
+            let mut sizes = Vec::new();
+            if !image.image_url.is_empty() {
+                sizes.push((image.image_url.as_str(), None));
+            }
+            if !image.large_image_url.is_empty() && image.large_image_url != image.image_url {
+                sizes.push((image.large_image_url.as_str(), Some("large")));
+            }
+            if !image.small_image_url.is_empty()
+                && image.small_image_url != image.image_url
+                && image.small_image_url != image.large_image_url
+            {
+                sizes.push((image.small_image_url.as_str(), Some("small")));
+            }
+            sizes
+        }
+        ImageVariantPolicy::LargeJpg => [
+            (image.large_image_url.as_str(), Some("large")),
+            (image.image_url.as_str(), None),
+        ]
+            .into_iter()
+            .find(|(url, _)| !url.is_empty())
+            .into_iter()
+            .collect(),
+        ImageVariantPolicy::Best => [
+            (image.large_image_url.as_str(), Some("large")),
+            (image.image_url.as_str(), None),
+            (image.small_image_url.as_str(), Some("small")),
+        ]
+            .into_iter()
+            .find(|(url, _)| !url.is_empty())
+            .into_iter()
+            .collect(),
+    }
+}
+
+fn has_url(image: &Image) -> bool {
+    !image.image_url.is_empty() || !image.large_image_url.is_empty() || !image.small_image_url.is_empty()
+}
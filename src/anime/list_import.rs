@@ -0,0 +1,156 @@
+use std::collections::HashSet;
+use std::io::Read;
+
+use flate2::read::GzDecoder;
+use mongodb::Database;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::anime::collect::CollectTarget;
+use crate::anime::watchlist;
+use crate::global::error::DatabaseError;
+
+/// Largest list export accepted once decompressed, larger gzip bodies are rejected
+const MAX_LIST_BYTES: u64 = 32 * 1024 * 1024;
+
+/// Format of an imported anime list
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ListFormat {
+    /// MyAnimeList XML export, `<myanimelist>` with a `<series_animedb_id>` per anime
+    MyAnimeList,
+    /// AniList JSON export or `MediaListCollection` query result
+    AniList,
+}
+
+/// Anime of a list export, in list order without duplicates
+#[derive(Debug, Clone)]
+pub struct ParsedList {
+    pub format: ListFormat,
+    pub targets: Vec<CollectTarget>,
+    /// Entries without a MAL id or a title
+    pub skipped: u64,
+}
+
+/// Watchlist changes of an import
+#[derive(Debug, Clone, Serialize)]
+pub struct ListImport {
+    pub format: ListFormat,
+    /// Targets newly watched
+    pub added: Vec<CollectTarget>,
+    /// Listed anime already watched
+    pub already_watched: u64,
+    pub skipped: u64,
+}
+
+/// Parse a MyAnimeList XML or AniList JSON list export, gzip compressed or not
+pub fn parse_list(body: &[u8]) -> Result<ParsedList, String> {
+    let text = if body.starts_with(&[0x1f, 0x8b]) {
+        let mut text = String::new();
+        GzDecoder::new(body)
+            .take(MAX_LIST_BYTES + 1)
+            .read_to_string(&mut text)
+            .map_err(|e| format!("Failed to decompress list export: {}", e))?;
+        if text.len() as u64 > MAX_LIST_BYTES {
+            return Err(format!("List export is larger than {} MB once decompressed", MAX_LIST_BYTES / (1024 * 1024)));
+        }
+        text
+    } else {
+        String::from_utf8(body.to_vec()).map_err(|_| "List export is not valid UTF-8".to_string())?
+    };
+
+    let trimmed = text.trim_start_matches('\u{feff}').trim_start();
+    if trimmed.starts_with('<') {
+        parse_mal_xml(trimmed)
+    } else if trimmed.starts_with('{') {
+        parse_anilist_json(trimmed)
+    } else {
+        Err("Unrecognized list export, expected MyAnimeList XML or AniList JSON".to_string())
+    }
+}
+
+fn parse_mal_xml(text: &str) -> Result<ParsedList, String> {
+    if !text.contains("<myanimelist") {
+        return Err("XML list export has no <myanimelist> root".to_string());
+    }
+
+    let anime = regex::Regex::new(r"(?s)<anime>(.*?)</anime>").expect("valid anime regex");
+    let mal_id = regex::Regex::new(r"<series_animedb_id>\s*(\d+)\s*</series_animedb_id>").expect("valid id regex");
+
+    let mut list = ParsedList { format: ListFormat::MyAnimeList, targets: Vec::new(), skipped: 0 };
+    let mut seen = HashSet::new();
+    for entry in anime.captures_iter(text) {
+        let id = mal_id.captures(&entry[1])
+            .and_then(|captures| captures[1].parse::<u32>().ok())
+            .filter(|id| *id > 0);
+        match id {
+            Some(id) => {
+                if seen.insert(id) {
+                    list.targets.push(CollectTarget::MalId(id));
+                }
+            }
+            None => list.skipped += 1,
+        }
+    }
+    Ok(list)
+}
+
+fn parse_anilist_json(text: &str) -> Result<ParsedList, String> {
+    let root: Value = serde_json::from_str(text).map_err(|e| format!("Invalid JSON list export: {}", e))?;
+    // Query results wrap the collection in data.MediaListCollection
+    let collection = root.pointer("/data/MediaListCollection").unwrap_or(&root);
+    let lists = collection.get("lists").and_then(Value::as_array)
+        .ok_or_else(|| "JSON list export has no lists".to_string())?;
+
+    let mut list = ParsedList { format: ListFormat::AniList, targets: Vec::new(), skipped: 0 };
+    let mut seen = HashSet::new();
+    let entries = lists.iter()
+        .filter_map(|list| list.get("entries").and_then(Value::as_array))
+        .flatten();
+    for entry in entries {
+        let media = entry.get("media").unwrap_or(entry);
+        let Some(target) = anilist_target(media) else {
+            list.skipped += 1;
+            continue;
+        };
+        if seen.insert(target.clone()) {
+            list.targets.push(target);
+        }
+    }
+    Ok(list)
+}
+
+/// MAL id of an AniList media, or its romaji then english title when it isn't on MAL
+fn anilist_target(media: &Value) -> Option<CollectTarget> {
+    let mal_id = media.get("idMal").and_then(Value::as_u64).filter(|id| *id > 0);
+    if let Some(id) = mal_id.and_then(|id| u32::try_from(id).ok()) {
+        return Some(CollectTarget::MalId(id));
+    }
+
+    let title = media.get("title")?;
+    ["romaji", "english"].iter()
+        .filter_map(|key| title.get(key).and_then(Value::as_str))
+        .map(str::trim)
+        .find(|title| !title.is_empty())
+        .map(|title| CollectTarget::Title(title.to_string()))
+}
+
+/// Watch every anime of a parsed list export
+pub async fn import_list(db: &Database, list: &ParsedList) -> Result<ListImport, DatabaseError> {
+    let mut import = ListImport {
+        format: list.format,
+        added: Vec::new(),
+        already_watched: 0,
+        skipped: list.skipped,
+    };
+
+    for target in &list.targets {
+        if watchlist::add_entry(db, target.clone()).await? {
+            import.added.push(target.clone());
+        } else {
+            import.already_watched += 1;
+        }
+    }
+
+    Ok(import)
+}
@@ -0,0 +1,47 @@
+use mongodb::Database;
+
+use crate::anime::anilist;
+use crate::anime::my_anime_list::{self, model::LocalImage};
+use crate::global::error::DatabaseError;
+use crate::picture::model::PictureMetadata;
+
+/// Write a downloaded picture back onto the anime owning it, under `local_images.<category>`.
+/// Pictures of other entities (characters, staff) are ignored.
+///
+/// The category is the picture tags following the anime id, e.g. `main_jpg_large` for the
+/// tags `["anime", "52991", "main", "jpg", "large"]`.
+pub async fn link_picture(db: &Database, picture: &PictureMetadata) -> Result<(), DatabaseError> {
+    let (Some(entity_type), Some(entity_id)) = (&picture.entity_type, &picture.entity_id) else {
+        return Ok(());
+    };
+    let Ok(id) = entity_id.parse::<i32>() else {
+        return Ok(());
+    };
+
+    let image = LocalImage {
+        url: picture.url.clone(),
+        file_path: picture.file_path.clone(),
+    };
+    match entity_type.as_str() {
+        // Tags: "anime", mal id, category...
+        "anime" => my_anime_list::database::set_local_image(db, id, &image_key(&picture.tags, 2), &image).await,
+        // Tags: "anime", "anilist", anilist id, "anilist_" category...
+        "anime_anilist" => {
+            let key = image_key(&picture.tags, 3);
+            let key = key.strip_prefix("anilist_").unwrap_or(&key);
+            anilist::database::set_local_image(db, id, key, &image).await
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Key of a picture in `local_images`, without the characters MongoDB reserves in field paths
+fn image_key(tags: &[String], skip: usize) -> String {
+    let key = tags.iter()
+        .skip(skip)
+        .map(|tag| tag.replace(['.', '$'], "_"))
+        .collect::<Vec<_>>()
+        .join("_");
+
+    if key.is_empty() { "image".to_string() } else { key }
+}
@@ -0,0 +1,50 @@
+use std::sync::Arc;
+
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{debug, info, warn};
+
+use crate::anime::anilist;
+use crate::anime::my_anime_list::{self, MyAnimeListModule};
+use crate::global::database::DatabaseInstance;
+use crate::global::error::AppError;
+use crate::global::events::{AnimeSource, DataEvent, EventBus};
+
+/// Queue a MyAnimeList fetch, with Jikan, for every AniList anime stored with a MAL id
+/// missing from `anime_mal` (`[anime] mal_backfill`)
+pub fn spawn_mal_backfill(events: &EventBus, db: Arc<DatabaseInstance>, mal_module: Arc<MyAnimeListModule>) {
+    let mut rx = events.subscribe();
+    info!("Automatic MyAnimeList backfill of AniList anime enabled");
+
+    tokio::spawn(async move {
+        loop {
+            match rx.recv().await {
+                Ok(DataEvent::AnimeStored { source: AnimeSource::AniList, id, .. })
+                | Ok(DataEvent::AnimeUpdated { source: AnimeSource::AniList, id }) => {
+                    if let Err(e) = backfill(&db, &mal_module, id).await {
+                        warn!(anilist_id = id, error = %e, "Failed to queue MyAnimeList backfill");
+                    }
+                }
+                Ok(_) => {}
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!(skipped = skipped, "MyAnimeList backfill fell behind, some anime were skipped");
+                }
+                Err(RecvError::Closed) => break,
+            }
+        }
+    });
+}
+
+async fn backfill(db: &DatabaseInstance, mal_module: &MyAnimeListModule, anilist_id: u32) -> Result<(), AppError> {
+    let Some(mal_id) = anilist::database::get_anime_by_id(db.db(), anilist_id as i32).await?
+        .and_then(|anime| anime.mal_id)
+    else {
+        return Ok(());
+    };
+
+    if my_anime_list::database::anime_exists(db.db(), mal_id).await? {
+        return Ok(());
+    }
+
+    debug!(anilist_id = anilist_id, mal_id = mal_id, "Queueing MyAnimeList fetch of AniList anime");
+    mal_module.queue_fetch_anime(mal_id as u32, true).await
+}
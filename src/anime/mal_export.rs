@@ -0,0 +1,85 @@
+use std::fmt::Write as _;
+
+use mongodb::Database;
+
+use crate::anime::collect::CollectTarget;
+use crate::anime::my_anime_list::database::get_anime_by_id;
+use crate::anime::my_anime_list::model::AnimeData;
+use crate::anime::watchlist;
+use crate::global::error::DatabaseError;
+use crate::global::xml;
+
+/// Status given to the exported anime, the watchlist doesn't track progress
+const EXPORT_STATUS: &str = "Plan to Watch";
+
+/// Watchlist in the MyAnimeList XML import format
+#[derive(Debug, Clone, Default)]
+pub struct MalExport {
+    pub xml: String,
+    pub exported: u64,
+    /// Title targets not resolved to a MAL id yet, left out of the export
+    pub unresolved: Vec<String>,
+}
+
+/// Export the watched anime in the XML format of the MyAnimeList list import
+/// (also read by AniList, Kitsu and other trackers), as "Plan to Watch" entries.
+/// Title targets are exported once a watchlist refresh resolved them to a MAL id.
+pub async fn export_watchlist(db: &Database) -> Result<MalExport, DatabaseError> {
+    let mut export = MalExport::default();
+    let mut entries = String::new();
+
+    for entry in watchlist::list_entries(db).await? {
+        let Some(mal_id) = entry.mal_id else {
+            if let CollectTarget::Title(title) = &entry.target {
+                export.unresolved.push(title.clone());
+            }
+            continue;
+        };
+
+        let anime = get_anime_by_id(db, mal_id).await?;
+        let title = match (&anime, &entry.target) {
+            (Some(anime), _) => anime.display_title().to_string(),
+            (None, CollectTarget::Title(title)) => title.clone(),
+            (None, CollectTarget::MalId(_)) => String::new(),
+        };
+        write_entry(&mut entries, mal_id, &title, anime.as_ref());
+        export.exported += 1;
+    }
+
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\" ?>\n<myanimelist>\n");
+    out.push_str("  <myinfo>\n");
+    out.push_str("    <user_export_type>1</user_export_type>\n");
+    let _ = writeln!(out, "    <user_total_anime>{}</user_total_anime>", export.exported);
+    let _ = writeln!(out, "    <user_total_plantowatch>{}</user_total_plantowatch>", export.exported);
+    out.push_str("  </myinfo>\n");
+    out.push_str(&entries);
+    out.push_str("</myanimelist>\n");
+
+    export.xml = out;
+    Ok(export)
+}
+
+/// One `<anime>` element, the MAL importer updates entries already on the list
+fn write_entry(out: &mut String, mal_id: i32, title: &str, anime: Option<&AnimeData>) {
+    let series_type = anime
+        .and_then(|anime| anime.media_type.as_ref())
+        .map(|media_type| format!("{:?}", media_type))
+        .unwrap_or_else(|| "Unknown".to_string());
+    let episodes = anime.map(|anime| anime.num_episodes).unwrap_or(0);
+
+    out.push_str("  <anime>\n");
+    let _ = writeln!(out, "    <series_animedb_id>{}</series_animedb_id>", mal_id);
+    let _ = writeln!(out, "    <series_title>{}</series_title>", xml::escape(title));
+    let _ = writeln!(out, "    <series_type>{}</series_type>", series_type);
+    let _ = writeln!(out, "    <series_episodes>{}</series_episodes>", episodes);
+    out.push_str("    <my_id>0</my_id>\n");
+    out.push_str("    <my_watched_episodes>0</my_watched_episodes>\n");
+    out.push_str("    <my_start_date>0000-00-00</my_start_date>\n");
+    out.push_str("    <my_finish_date>0000-00-00</my_finish_date>\n");
+    out.push_str("    <my_score>0</my_score>\n");
+    let _ = writeln!(out, "    <my_status>{}</my_status>", EXPORT_STATUS);
+    out.push_str("    <my_times_watched>0</my_times_watched>\n");
+    out.push_str("    <my_rewatching>0</my_rewatching>\n");
+    out.push_str("    <update_on_import>1</update_on_import>\n");
+    out.push_str("  </anime>\n");
+}
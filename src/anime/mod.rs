@@ -2,4 +2,26 @@ pub mod my_anime_list;
 pub mod anilist;
 
 pub mod error;
-pub mod module;
\ No newline at end of file
+pub mod module;
+pub mod auto_pictures;
+pub mod mal_backfill;
+pub mod collect;
+pub mod nfo;
+pub mod mal_export;
+pub mod list_import;
+pub mod airing;
+pub mod fallback;
+pub mod related;
+pub mod duplicates;
+pub mod quality;
+pub mod titles;
+pub mod watchlist;
+pub mod bootstrap;
+pub mod freshness;
+pub mod reprocess;
+pub mod picture_gc;
+pub mod duration;
+pub mod season;
+pub mod local_images;
+pub mod image_variants;
+pub mod episodes;
\ No newline at end of file
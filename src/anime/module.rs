@@ -1,32 +1,92 @@
 use std::sync::Arc;
 use std::pin::Pin;
 use std::future::Future;
+use std::time::Duration;
 use tokio::sync::mpsc;
 use tracing::{info, debug, warn};
 
+use crate::global::config::AnimeConfig;
 use crate::global::database::DatabaseInstance;
+use crate::anime::my_anime_list::task::SnapshotStatisticsTask;
+use crate::anime::watchlist::RefreshWatchlistTask;
+use crate::anime::freshness::RefreshStaleSectionsTask;
 use crate::global::error::AppError;
-use crate::global::module::{ParentModule, ModuleMessage};
-use crate::global::queue::{QueueWorker, TaskQueue};
+use crate::global::events::EventBus;
+use crate::global::http::ClientWithLimiter;
+use crate::global::module::{ParentModule, ModuleConfigUpdate, ModuleMessage, HEARTBEAT_INTERVAL};
+use crate::global::supervisor::Heartbeat;
+use crate::global::queue::{QueueWorker, Task, TaskQueue};
 
 #[derive(Clone)]
 pub struct AnimeModule {
     queue: TaskQueue,
+    snapshot_interval: Option<Duration>,
+    snapshot_anime: Vec<u32>,
+    jikan_client: Option<ClientWithLimiter>,
+    watchlist_interval: Option<Duration>,
+    /// MAL API key and client, used to collect watched anime that aren't stored yet
+    mal: Option<(String, ClientWithLimiter)>,
+    /// Days after which extended data sections are fetched again
+    section_refresh_age: Option<i64>,
+    section_refresh_limit: i64,
+}
+
+/// Period of the stale section checks
+const SECTION_REFRESH_PERIOD: Duration = Duration::from_secs(60 * 60);
+
+/// Timer periods while snapshots or watchlist refreshes are disabled, the timers don't fire then
+const DEFAULT_SNAPSHOT_PERIOD: Duration = Duration::from_secs(24 * 60 * 60);
+const DEFAULT_WATCHLIST_PERIOD: Duration = Duration::from_secs(60 * 60);
+
+/// Timer whose first tick comes after a full period
+fn delayed_timer(period: Duration) -> tokio::time::Interval {
+    tokio::time::interval_at(tokio::time::Instant::now() + period, period)
 }
 
 impl AnimeModule {
-    pub fn new(db: Arc<DatabaseInstance>, client: reqwest::Client) -> Self {
-        let (queue, rx) = TaskQueue::new("anime_queue".to_string(), 1000);
+    pub fn new(db: Arc<DatabaseInstance>, client: reqwest::Client, config: &AnimeConfig, events: EventBus) -> Self {
+        let (queue, rx) = TaskQueue::new("anime_queue".to_string(), config.queue_size, db.clone());
         
         // Spawn the queue worker
-        let worker = QueueWorker::new("anime_worker".to_string(), db, client);
+        let worker = QueueWorker::new("anime_worker".to_string(), db, client)
+            .with_stats(queue.stats())
+            .with_events(events);
         tokio::spawn(async move {
             if let Err(e) = worker.run(rx).await {
                 tracing::error!(error = %e, "Queue worker error");
             }
         });
 
-        Self { queue }
+        Self {
+            queue,
+            snapshot_interval: config.snapshot_interval(),
+            snapshot_anime: config.snapshot_anime.clone(),
+            jikan_client: None,
+            watchlist_interval: config.watchlist_refresh_interval(),
+            mal: None,
+            section_refresh_age: config.section_refresh_age(),
+            section_refresh_limit: config.section_refresh_limit,
+        }
+    }
+
+    /// Days after which extended data sections are fetched again, replaces the age of the
+    /// anime config (e.g. with the `auto_refresh` feature)
+    pub fn with_section_refresh_age(mut self, age: Option<i64>) -> Self {
+        self.section_refresh_age = age;
+        self
+    }
+
+    /// Jikan client used by the periodic statistics snapshots, watchlist and stale section
+    /// refreshes, which are skipped without it
+    pub fn with_jikan(mut self, jikan_client: ClientWithLimiter) -> Self {
+        self.jikan_client = Some(jikan_client);
+        self
+    }
+
+    /// MyAnimeList access, lets the watchlist refresh collect anime that aren't stored yet
+    pub fn with_mal(mut self, api_key: String, mal_client: ClientWithLimiter) -> Self {
+        self.mal = Some((api_key, mal_client));
+        self
     }
 
     pub fn queue(&self) -> &TaskQueue {
@@ -43,9 +103,28 @@ impl ParentModule for AnimeModule {
         &self,
         _db: Arc<DatabaseInstance>,
         mut rx: mpsc::Receiver<ModuleMessage>,
+        heartbeat: Heartbeat,
     ) -> Pin<Box<dyn Future<Output = Result<(), AppError>> + Send + '_>> {
         Box::pin(async move {
             info!(module = %self.name(), "Module started");
+
+            let mut heartbeat_interval = tokio::time::interval(HEARTBEAT_INTERVAL);
+
+            // Periodic tasks are configured from the startup settings, then from the reloaded ones
+            let mut snapshot_interval = self.snapshot_interval;
+            let mut snapshot_anime = self.snapshot_anime.clone();
+            let mut watchlist_interval = self.watchlist_interval;
+            let mut section_refresh_age = self.section_refresh_age;
+            let mut section_refresh_limit = self.section_refresh_limit;
+
+            // Statistics snapshots for popularity trends, first one after a full period
+            let mut snapshot_timer = delayed_timer(snapshot_interval.unwrap_or(DEFAULT_SNAPSHOT_PERIOD));
+
+            // Watched anime are refreshed ahead of everything else, first refresh right away
+            let mut watchlist_timer = tokio::time::interval(watchlist_interval.unwrap_or(DEFAULT_WATCHLIST_PERIOD));
+
+            // Stale sections are checked after a full period, the startup is busy enough
+            let mut section_refresh_timer = delayed_timer(SECTION_REFRESH_PERIOD);
             
             loop {
                 tokio::select! {
@@ -59,6 +138,52 @@ impl ParentModule for AnimeModule {
                                 }
                                 break;
                             }
+                            Some(ModuleMessage::Pause) => {
+                                info!(module = %self.name(), "Pausing module");
+                                if let Err(e) = self.queue.pause().await {
+                                    warn!(module = %self.name(), error = %e, "Failed to pause queue");
+                                }
+                            }
+                            Some(ModuleMessage::Resume) => {
+                                info!(module = %self.name(), "Resuming module");
+                                if let Err(e) = self.queue.resume().await {
+                                    warn!(module = %self.name(), error = %e, "Failed to resume queue");
+                                }
+                            }
+                            Some(ModuleMessage::UpdateConfig(update)) => match update {
+                                ModuleConfigUpdate::Concurrency(concurrency) => {
+                                    if let Err(e) = self.queue.set_concurrency(concurrency).await {
+                                        warn!(module = %self.name(), error = %e, "Failed to update concurrency");
+                                    }
+                                }
+                                ModuleConfigUpdate::Snapshots { interval, anime } => {
+                                    if interval != snapshot_interval || anime != snapshot_anime {
+                                        info!(module = %self.name(), interval = ?interval, anime = anime.len(), "Updating statistics snapshots");
+                                        if interval != snapshot_interval {
+                                            snapshot_timer = delayed_timer(interval.unwrap_or(DEFAULT_SNAPSHOT_PERIOD));
+                                        }
+                                        snapshot_interval = interval;
+                                        snapshot_anime = anime;
+                                    }
+                                }
+                                ModuleConfigUpdate::WatchlistInterval(interval) => {
+                                    if interval != watchlist_interval {
+                                        info!(module = %self.name(), interval = ?interval, "Updating watchlist refresh interval");
+                                        watchlist_interval = interval;
+                                        watchlist_timer = delayed_timer(interval.unwrap_or(DEFAULT_WATCHLIST_PERIOD));
+                                    }
+                                }
+                                ModuleConfigUpdate::SectionRefresh { max_age_days, limit } => {
+                                    if max_age_days != section_refresh_age || limit != section_refresh_limit {
+                                        info!(module = %self.name(), max_age_days = ?max_age_days, limit, "Updating stale section refresh");
+                                        section_refresh_age = max_age_days;
+                                        section_refresh_limit = limit;
+                                    }
+                                }
+                                update => {
+                                    debug!(module = %self.name(), update = ?update, "Ignoring unsupported config update");
+                                }
+                            },
                             Some(ModuleMessage::Custom(data)) => {
                                 debug!(module = %self.name(), message = %data, "Received custom message");
                             }
@@ -70,9 +195,39 @@ impl ParentModule for AnimeModule {
                     }
                     
                     // Periodic tasks
-                    _ = tokio::time::sleep(tokio::time::Duration::from_secs(60)) => {
+                    _ = heartbeat_interval.tick() => {
                         debug!(module = %self.name(), "Running periodic maintenance tasks");
-                        // Add periodic maintenance tasks here
+                        heartbeat.beat(&self.queue.stats()).await;
+                    }
+
+                    _ = snapshot_timer.tick(), if snapshot_interval.is_some() && self.jikan_client.is_some() => {
+                        let Some(jikan_client) = self.jikan_client.clone() else { continue };
+                        let task = SnapshotStatisticsTask::new(snapshot_anime.clone(), jikan_client);
+                        debug!(module = %self.name(), task_id = %task.id(), "Queueing statistics snapshots");
+                        if let Err(e) = self.queue.enqueue(Box::new(task)).await {
+                            warn!(module = %self.name(), error = %e, "Failed to queue statistics snapshots");
+                        }
+                    }
+
+                    _ = watchlist_timer.tick(), if watchlist_interval.is_some() && self.jikan_client.is_some() => {
+                        let Some(jikan_client) = self.jikan_client.clone() else { continue };
+                        let mut task = RefreshWatchlistTask::new(self.queue.clone(), jikan_client);
+                        if let Some((api_key, mal_client)) = &self.mal {
+                            task = task.with_mal(api_key.clone(), mal_client.clone());
+                        }
+                        debug!(module = %self.name(), task_id = %task.id(), "Queueing watchlist refresh");
+                        if let Err(e) = self.queue.enqueue(Box::new(task)).await {
+                            warn!(module = %self.name(), error = %e, "Failed to queue watchlist refresh");
+                        }
+                    }
+
+                    _ = section_refresh_timer.tick(), if section_refresh_age.is_some() && self.jikan_client.is_some() => {
+                        let (Some(jikan_client), Some(max_age_days)) = (self.jikan_client.clone(), section_refresh_age) else { continue };
+                        let task = RefreshStaleSectionsTask::new(self.queue.clone(), jikan_client, max_age_days, section_refresh_limit);
+                        debug!(module = %self.name(), task_id = %task.id(), "Queueing stale section refresh");
+                        if let Err(e) = self.queue.enqueue(Box::new(task)).await {
+                            warn!(module = %self.name(), error = %e, "Failed to queue stale section refresh");
+                        }
                     }
                 }
             }
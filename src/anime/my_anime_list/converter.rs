@@ -1,7 +1,11 @@
+use std::collections::BTreeMap;
+
 use chrono::{DateTime, Utc, NaiveDateTime};
 use tracing::{debug, warn};
 
 use super::model::*;
+use crate::anime::duration::parse_duration_seconds;
+use crate::anime::season;
 
 /// Convert MyAnimeList API response to unified AnimeData
 pub fn mal_to_anime_data(mal: MalAnimeResponse, jikan_url: Option<String>) -> AnimeData {
@@ -100,11 +104,12 @@ pub fn mal_to_anime_data(mal: MalAnimeResponse, jikan_url: Option<String>) -> An
     };
 
     // Parse season
-    let (season, year) = if let Some(s) = &mal.start_season {
+    let (mut season, mut year) = if let Some(s) = &mal.start_season {
         (parse_season(&s.season), Some(s.year))
     } else {
         (None, None)
     };
+    season::fill_missing(&mut season, &mut year, aired_from);
 
     // Convert statistics
     let statistics = mal.statistics.as_ref().map(|s| Statistics {
@@ -120,6 +125,7 @@ pub fn mal_to_anime_data(mal: MalAnimeResponse, jikan_url: Option<String>) -> An
     AnimeData {
         id: None,
         mal_id,
+        provider: DataProvider::MyAnimeList,
         url: jikan_url.unwrap_or_else(|| format!("https://myanimelist.net/anime/{}", mal_id)),
         images,
         trailer: Trailer {
@@ -134,6 +140,7 @@ pub fn mal_to_anime_data(mal: MalAnimeResponse, jikan_url: Option<String>) -> An
         source: mal.source.as_ref().and_then(|s| parse_source(s)),
         num_episodes: mal.num_episodes.unwrap_or(0),
         average_episode_duration: mal.average_episode_duration.unwrap_or(0),
+        duration_seconds: mal.average_episode_duration.filter(|seconds| *seconds > 0),
         status: mal.status.as_ref().and_then(|s| parse_status(s)),
         airing: mal.status.as_deref() == Some("currently_airing"),
         aired: Aired {
@@ -148,6 +155,8 @@ pub fn mal_to_anime_data(mal: MalAnimeResponse, jikan_url: Option<String>) -> An
         members: mal.num_list_users.unwrap_or(0),
         favorites: 0,
         popularity: mal.popularity,
+        synopses: provider_synopses(mal.synopsis.as_deref().unwrap_or_default()),
+        local_images: BTreeMap::new(),
         synopsis: mal.synopsis.unwrap_or_default(),
         background: mal.background,
         season,
@@ -166,9 +175,12 @@ pub fn mal_to_anime_data(mal: MalAnimeResponse, jikan_url: Option<String>) -> An
         streaming: vec![],
         created_at: parse_mal_timestamp(&mal.created_at).unwrap_or_else(Utc::now),
         updated_at: parse_mal_timestamp(&mal.updated_at).unwrap_or_else(Utc::now),
+        collected_at: None,
         characters: vec![],
         staffs: vec![],
-        episodes: vec![],
+        episode_summary: None,
+        overflow_fields: Vec::new(),
+        fetched_at: BTreeMap::new(),
         videos: None,
         pictures: mal.pictures.iter().map(|p| Images {
             jpg: Image {
@@ -234,6 +246,9 @@ pub fn merge_jikan_data(mut anime: AnimeData, jikan: JikanAnime) -> AnimeData {
 
     // Update duration
     if let Some(duration) = jikan.duration {
+        if anime.duration_seconds.is_none() {
+            anime.duration_seconds = parse_duration_seconds(&duration);
+        }
         anime.duration = duration;
     }
 
@@ -339,15 +354,146 @@ pub fn merge_jikan_data(mut anime: AnimeData, jikan: JikanAnime) -> AnimeData {
         id: None,
         name: s.name.clone(),
         url: s.url.clone(),
+        title: None,
+        thumbnail: None,
     }).collect();
 
+    // Older entries have no season, Jikan may have brought the start date
+    season::fill_missing(&mut anime.season, &mut anime.year, anime.aired.from);
+
+    anime
+}
+
+/// Keep the fields merged from Jikan of a stored anime, when Jikan told they are unchanged
+pub fn keep_jikan_data(mut anime: AnimeData, stored: AnimeData) -> AnimeData {
+    debug!(mal_id = anime.mal_id, "Keeping stored Jikan data of anime");
+
+    anime.url = stored.url;
+    anime.images = stored.images;
+    anime.trailer = stored.trailer;
+    anime.approved = stored.approved;
+    anime.titles = stored.titles;
+    anime.aired = stored.aired;
+    anime.duration = stored.duration;
+    anime.duration_seconds = anime.duration_seconds.or(stored.duration_seconds);
+    anime.favorites = stored.favorites;
+    anime.background = anime.background.or(stored.background);
+    anime.broadcast = stored.broadcast;
+    anime.producers = stored.producers;
+    anime.licensors = stored.licensors;
+    anime.studios = stored.studios;
+    anime.explicit_genres = stored.explicit_genres;
+    anime.themes = stored.themes;
+    anime.demographics = stored.demographics;
+    anime.relations = stored.relations;
+    anime.theme = stored.theme;
+    anime.external = stored.external;
+    anime.streaming = stored.streaming;
+
+    season::fill_missing(&mut anime.season, &mut anime.year, anime.aired.from);
+
     anime
 }
 
+/// Build AnimeData from Jikan alone, for entries MyAnimeList doesn't serve
+pub fn jikan_to_anime_data(jikan: JikanAnime) -> AnimeData {
+    let now = Utc::now();
+    let duration_seconds = jikan.duration.as_deref().and_then(parse_duration_seconds);
+
+    let base = AnimeData {
+        id: None,
+        mal_id: jikan.mal_id,
+        provider: DataProvider::Jikan,
+        url: String::new(),
+        images: default_images(),
+        trailer: Trailer {
+            youtube_id: None,
+            url: None,
+            embed_url: None,
+        },
+        approved: jikan.approved,
+        titles: vec![],
+        media_type: jikan.media_type.as_deref().map(normalize_jikan_value).and_then(|m| parse_media_type(&m)),
+        nsfw: None,
+        source: jikan.source.as_deref().map(normalize_jikan_value).and_then(|s| parse_source(&s)),
+        num_episodes: jikan.episodes.unwrap_or(0),
+        average_episode_duration: duration_seconds.unwrap_or(0),
+        duration_seconds,
+        status: jikan.status.as_deref().map(normalize_jikan_value).and_then(|s| parse_status(&s)),
+        airing: jikan.airing,
+        aired: Aired {
+            from: None,
+            to: None,
+        },
+        duration: String::new(),
+        // Jikan ratings look like "PG-13 - Teens 13 or older"
+        rating: jikan.rating.as_deref()
+            .and_then(|r| r.split(" - ").next())
+            .map(normalize_jikan_value)
+            .and_then(|r| parse_rating(&r)),
+        score: jikan.score,
+        scored_by: jikan.scored_by.unwrap_or(0),
+        rank: jikan.rank,
+        members: jikan.members.unwrap_or(0),
+        favorites: 0,
+        popularity: jikan.popularity,
+        synopsis: jikan.synopsis.clone().unwrap_or_default(),
+        synopses: provider_synopses(jikan.synopsis.as_deref().unwrap_or_default()),
+        local_images: BTreeMap::new(),
+        background: None,
+        season: jikan.season.as_deref().and_then(parse_season),
+        year: jikan.year,
+        broadcast: Broadcast {
+            day: None,
+            time: None,
+            timezone: None,
+            string: None,
+        },
+        producers: vec![],
+        licensors: vec![],
+        studios: vec![],
+        genres: jikan.genres.iter().map(|g| Genre {
+            id: None,
+            mal_id: g.mal_id,
+            genre_type: g.entity_type.clone(),
+            name: g.name.clone(),
+            url: g.url.clone(),
+        }).collect(),
+        explicit_genres: vec![],
+        themes: vec![],
+        demographics: vec![],
+        relations: vec![],
+        theme: Theme::default(),
+        external: vec![],
+        streaming: vec![],
+        created_at: now,
+        updated_at: now,
+        collected_at: None,
+        characters: vec![],
+        staffs: vec![],
+        episode_summary: None,
+        overflow_fields: Vec::new(),
+        fetched_at: BTreeMap::new(),
+        videos: None,
+        pictures: vec![],
+        statistics: None,
+        more_info: None,
+        recommendations: vec![],
+    };
+
+    // Everything else is filled like a Jikan enrichment
+    merge_jikan_data(base, jikan)
+}
+
 // ========================================================================
 // Helper Functions
 // ========================================================================
 
+/// Jikan uses display values ("Light novel", "Currently Airing"), MAL uses snake_case keys
+fn normalize_jikan_value(value: &str) -> String {
+    value.trim().to_lowercase().replace([' ', '-'], "_")
+}
+
 fn default_images() -> Images {
     Images {
         jpg: Image {
@@ -426,7 +572,8 @@ fn parse_source(s: &str) -> Option<Source> {
         "picture_book" => Some(Source::PictureBook),
         "radio" => Some(Source::Radio),
         "music" => Some(Source::Music),
-        _ => Some(Source::Other),
+        "" => None,
+        other => Some(Source::Other(other.to_string())),
     }
 }
 
@@ -435,19 +582,22 @@ fn parse_status(s: &str) -> Option<Status> {
         "finished_airing" => Some(Status::FinishedAiring),
         "currently_airing" => Some(Status::CurrentlyAiring),
         "not_yet_aired" => Some(Status::NotYetAired),
-        _ => None,
+        "" => None,
+        other => Some(Status::Other(other.to_string())),
     }
 }
 
+/// Rating of the MAL API ("pg_13", "r+") or of a normalized Jikan one ("pg_13", "r", "rx")
 fn parse_rating(s: &str) -> Option<Rating> {
-    match s {
+    match s.replace('_', "").as_str() {
         "g" => Some(Rating::G),
         "pg" => Some(Rating::PG),
-        "pg_13" => Some(Rating::PG13),
-        "r" => Some(Rating::R17Plus),
-        "r+" => Some(Rating::RPlus),
+        "pg13" => Some(Rating::PG13),
+        "r" | "r17" | "r17+" => Some(Rating::R17Plus),
+        "r+" | "rplus" => Some(Rating::RPlus),
         "rx" => Some(Rating::Rx),
-        _ => None,
+        "" => None,
+        _ => Some(Rating::Other(s.to_string())),
     }
 }
 
@@ -1,16 +1,48 @@
 use anyhow::Result;
-use mongodb::{Database, IndexModel};
-use mongodb::options::{FindOptions, IndexOptions, ReplaceOptions, UpdateOptions};
-use mongodb::bson::{doc, to_document};
-use serde::{Deserialize, Serialize};
+use mongodb::{Cursor, Database, IndexModel};
+use mongodb::options::{FindOptions, IndexOptions, ReplaceOptions, ReturnDocument};
+use mongodb::bson::{self, doc, to_document, Bson, Document};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use tracing::{info, debug, warn};
 use futures::stream::StreamExt;
-
-use super::model::AnimeData;
+use sha2::{Digest, Sha256};
+
+use super::model::{
+    AnimeData, AnimeHistoryEntry, AnimeSection, EpisodeSummary, FieldChange, GenreCategory, JikanAnime, LocalImage, SearchResults,
+    StatisticsSnapshot, TaxonomyGenre, TaxonomyProducer,
+};
+use super::converter;
+use crate::anime::{duration, season};
+use crate::anime::titles::{self, TitleSource};
+use super::overflow;
 use crate::global::error::DatabaseError;
+use crate::global::http::Validators;
 
 // Collection name for MyAnimeList anime
-const COLLECTION_NAME: &str = "anime_mal";
+pub const COLLECTION_NAME: &str = "anime_mal";
+
+// Collection name for the field-level changes of anime between upserts
+const HISTORY_COLLECTION: &str = "anime_history";
+
+/// Fields left out of the change history: bookkeeping and bulky extended data
+const HISTORY_IGNORED_FIELDS: [&str; 16] = [
+    "_id", "created_at", "updated_at", "collected_at", "characters", "staffs",
+    "episode_summary", "videos", "pictures", "statistics", "more_info", "recommendations",
+    "local_images", "overflow_fields", "fetched_at", "data_hash",
+];
+
+// Collection name for the results of search tasks
+const SEARCH_RESULTS_COLLECTION: &str = "search_results";
+
+// Collection name for the periodic score/popularity snapshots
+const SNAPSHOTS_COLLECTION: &str = "anime_statistics_snapshots";
+
+// Collection name for the validators of the Jikan responses of each anime, for conditional updates
+const VALIDATORS_COLLECTION: &str = "anime_mal_validators";
+
+// Collection names for the canonical Jikan taxonomies
+const GENRES_COLLECTION: &str = "genres";
+const PRODUCERS_COLLECTION: &str = "producers";
 
 /// Initialize MyAnimeList-specific collections and indexes
 pub async fn initialize_collections(db: &Database) -> Result<(), DatabaseError> {
@@ -25,6 +57,21 @@ pub async fn initialize_collections(db: &Database) -> Result<(), DatabaseError>
     // Search history collection
     create_search_history_indexes(db).await?;
 
+    // Search results collection
+    create_search_results_indexes(db).await?;
+
+    // Change history collection
+    create_history_indexes(db).await?;
+
+    // Statistics time series collection
+    create_snapshot_indexes(db).await?;
+
+    // Genre and producer taxonomies
+    create_taxonomy_indexes(db).await?;
+
+    // Jikan response validators
+    create_validators_indexes(db).await?;
+
     info!("MyAnimeList collections initialized");
     Ok(())
 }
@@ -76,6 +123,11 @@ async fn create_anime_indexes(db: &Database) -> Result<(), DatabaseError> {
         .keys(doc! { "updated_at": -1 })
         .build();
 
+    // Index on collected_at for the new anime feed
+    let collected_index = IndexModel::builder()
+        .keys(doc! { "collected_at": -1 })
+        .build();
+
     collection.create_indexes(vec![
         mal_id_index,
         title_index,
@@ -85,6 +137,7 @@ async fn create_anime_indexes(db: &Database) -> Result<(), DatabaseError> {
         status_index,
         season_index,
         updated_index,
+        collected_index,
     ]).await
         .map_err(|e| DatabaseError::Query(format!("Failed to create anime indexes: {}", e)))?;
 
@@ -123,6 +176,11 @@ async fn create_search_history_indexes(db: &Database) -> Result<(), DatabaseErro
     let query_index = IndexModel::builder()
         .keys(doc! { "query": 1 })
         .build();
+
+    // Index on the normalized query, for prefix matches of suggestions
+    let normalized_index = IndexModel::builder()
+        .keys(doc! { "normalized": 1 })
+        .build();
     
     // Index on timestamp for recent searches
     let timestamp_index = IndexModel::builder()
@@ -137,28 +195,162 @@ async fn create_search_history_indexes(db: &Database) -> Result<(), DatabaseErro
             .build())
         .build();
 
-    collection.create_indexes(vec![query_index, timestamp_index, ttl_index]).await
+    collection.create_indexes(vec![query_index, normalized_index, timestamp_index, ttl_index]).await
         .map_err(|e| DatabaseError::Query(format!("Failed to create search_history indexes: {}", e)))?;
 
     debug!("Created indexes for search_history collection");
     Ok(())
 }
 
+async fn create_history_indexes(db: &Database) -> Result<(), DatabaseError> {
+    let collection = db.collection::<AnimeHistoryEntry>(HISTORY_COLLECTION);
+
+    // History of one anime, newest first
+    let anime_index = IndexModel::builder()
+        .keys(doc! { "mal_id": 1, "recorded_at": -1 })
+        .build();
+
+    collection.create_indexes(vec![anime_index]).await
+        .map_err(|e| DatabaseError::Query(format!("Failed to create anime_history indexes: {}", e)))?;
+
+    debug!("Created indexes for anime_history collection");
+    Ok(())
+}
+
+async fn create_snapshot_indexes(db: &Database) -> Result<(), DatabaseError> {
+    let collection = db.collection::<StatisticsSnapshot>(SNAPSHOTS_COLLECTION);
+
+    // Time series of one anime
+    let anime_index = IndexModel::builder()
+        .keys(doc! { "mal_id": 1, "recorded_at": 1 })
+        .build();
+
+    collection.create_indexes(vec![anime_index]).await
+        .map_err(|e| DatabaseError::Query(format!("Failed to create anime_statistics_snapshots indexes: {}", e)))?;
+
+    debug!("Created indexes for anime_statistics_snapshots collection");
+    Ok(())
+}
+
+async fn create_taxonomy_indexes(db: &Database) -> Result<(), DatabaseError> {
+    // Unique index on MAL ID for both taxonomies
+    for name in [GENRES_COLLECTION, PRODUCERS_COLLECTION] {
+        let mal_id_index = IndexModel::builder()
+            .keys(doc! { "mal_id": 1 })
+            .options(IndexOptions::builder().unique(true).build())
+            .build();
+
+        db.collection::<Document>(name).create_indexes(vec![mal_id_index]).await
+            .map_err(|e| DatabaseError::Query(format!("Failed to create {} indexes: {}", name, e)))?;
+
+        debug!("Created indexes for {} collection", name);
+    }
+    Ok(())
+}
+
+async fn create_validators_indexes(db: &Database) -> Result<(), DatabaseError> {
+    let collection = db.collection::<Document>(VALIDATORS_COLLECTION);
+
+    // Unique index on MAL ID
+    let mal_id_index = IndexModel::builder()
+        .keys(doc! { "mal_id": 1 })
+        .options(IndexOptions::builder().unique(true).build())
+        .build();
+
+    collection.create_indexes(vec![mal_id_index]).await
+        .map_err(|e| DatabaseError::Query(format!("Failed to create anime_mal_validators indexes: {}", e)))?;
+
+    debug!("Created indexes for anime_mal_validators collection");
+    Ok(())
+}
+
+async fn create_search_results_indexes(db: &Database) -> Result<(), DatabaseError> {
+    let collection = db.collection::<SearchResults>(SEARCH_RESULTS_COLLECTION);
+
+    // Unique index on search ID
+    let search_id_index = IndexModel::builder()
+        .keys(doc! { "search_id": 1 })
+        .options(IndexOptions::builder().unique(true).build())
+        .build();
+
+    collection.create_indexes(vec![search_id_index]).await
+        .map_err(|e| DatabaseError::Query(format!("Failed to create search_results indexes: {}", e)))?;
+
+    debug!("Created indexes for search_results collection");
+    Ok(())
+}
+
 // ========================================================================
 // Database Operations for AnimeData
 // ========================================================================
 
-/// Insert or update anime in database
+/// Insert or update anime in database.
+/// The whole entry is overwritten, except `collected_at` which is only set on insert.
+/// Changes to an existing entry are recorded in the anime history.
+/// Oversized arrays are moved to `anime_mal_overflow`, see `overflow::split_document`.
+/// Nothing is written when the data did not change since the last upsert, so `updated_at`
+/// keeps the time the data last changed.
 pub async fn upsert_anime(db: &Database, data: &AnimeData) -> Result<(), DatabaseError> {
-    let collection = db.collection::<AnimeData>(COLLECTION_NAME);
+    let collection = db.collection::<Document>(COLLECTION_NAME);
     let filter = doc! { "mal_id": data.mal_id };
-    let options = ReplaceOptions::builder().upsert(true).build();
 
-    collection.replace_one(filter, data)
-        .with_options(options)
+    let mut document = to_document(data)
+        .map_err(|e| DatabaseError::Query(format!("Failed to serialize anime: {}", e)))?;
+    document.remove("_id");
+    document.remove("collected_at");
+    // Owned by the picture module, see set_local_image
+    document.remove("local_images");
+    // Owned by the episodes collection, see set_episode_summary
+    document.remove("episode_summary");
+    // Set by the extended data tasks, see set_section_fetched
+    document.remove("fetched_at");
+
+    document.remove("overflow_fields");
+
+    let data_hash = data_hash(&document)?;
+    let unchanged = collection.count_documents(doc! { "mal_id": data.mal_id, "data_hash": &data_hash })
+        .limit(1)
+        .await
+        .map_err(|e| DatabaseError::Query(format!("Failed to check anime changes: {}", e)))? > 0;
+    if unchanged {
+        debug!(mal_id = data.mal_id, "Anime unchanged, upsert skipped");
+        return Ok(());
+    }
+    document.insert("data_hash", data_hash);
+
+    let moved = overflow::split_document(db, data.mal_id, &mut document).await?;
+    let mut update = doc! {
+        "$setOnInsert": { "collected_at": chrono::Utc::now().to_rfc3339() },
+    };
+    if moved.is_empty() {
+        update.insert("$unset", doc! { "overflow_fields": "" });
+    } else {
+        document.insert("overflow_fields", moved);
+    }
+    update.insert("$set", set_synopses_by_language(document.clone()));
+
+    // The document before the update is kept to compute the changes
+    let previous = collection.find_one_and_update(filter, update)
+        .upsert(true)
+        .return_document(ReturnDocument::Before)
         .await
         .map_err(|e| DatabaseError::Query(format!("Failed to upsert anime: {}", e)))?;
 
+    if let Some(previous) = previous {
+        // Synopses in other languages are kept, compare against the merged map
+        if let (Ok(stored), Ok(synopses)) = (previous.get_document("synopses"), document.get_document("synopses")) {
+            let mut merged = stored.clone();
+            merged.extend(synopses.clone());
+            document.insert("synopses", merged);
+        }
+        let changes = diff_documents(&previous, &document);
+        if !changes.is_empty() {
+            record_history(db, data.mal_id, changes).await?;
+        }
+    }
+
+    titles::index_titles(db, TitleSource::Mal, data.mal_id, Some(data.mal_id), data.titles.iter().map(|t| t.title.as_str())).await?;
+
     debug!(
         mal_id = data.mal_id,
         title = %data.titles.first().map(|t| t.title.as_str()).unwrap_or("Unknown"),
@@ -167,18 +359,162 @@ pub async fn upsert_anime(db: &Database, data: &AnimeData) -> Result<(), Databas
     Ok(())
 }
 
+/// `$set` of an anime document writing each synopsis on its own `synopses.<lang>` path,
+/// so an upsert doesn't drop the synopses in languages the provider didn't send
+fn set_synopses_by_language(mut document: Document) -> Document {
+    if let Some(Bson::Document(synopses)) = document.remove("synopses") {
+        for (language, synopsis) in synopses {
+            if language.contains('.') || language.starts_with('$') {
+                continue;
+            }
+            document.insert(format!("synopses.{}", language), synopsis);
+        }
+    }
+    document
+}
+
+/// Move the first collection date of a stored anime back to `collected_at` when it is earlier.
+/// `upsert_anime` never writes it, it is only set on insert.
+pub async fn set_earliest_collected_at(db: &Database, mal_id: i32, collected_at: chrono::DateTime<chrono::Utc>) -> Result<(), DatabaseError> {
+    db.collection::<Document>(COLLECTION_NAME)
+        .update_one(doc! { "mal_id": mal_id }, doc! { "$min": { "collected_at": collected_at.to_rfc3339() } })
+        .await
+        .map_err(|e| DatabaseError::Query(format!("Failed to set collection date: {}", e)))?;
+    Ok(())
+}
+
+/// Record the downloaded copy of an image of a stored anime
+pub async fn set_local_image(db: &Database, mal_id: i32, key: &str, image: &LocalImage) -> Result<(), DatabaseError> {
+    let image = bson::to_bson(image)
+        .map_err(|e| DatabaseError::Query(format!("Failed to serialize local image: {}", e)))?;
+
+    db.collection::<Document>(COLLECTION_NAME)
+        .update_one(doc! { "mal_id": mal_id }, doc! { "$set": { format!("local_images.{}", key): image } })
+        .await
+        .map_err(|e| DatabaseError::Query(format!("Failed to set local image: {}", e)))?;
+    Ok(())
+}
+
+/// Record the episode counts of a stored anime, whose episodes live in `anime_episodes`
+pub async fn set_episode_summary(db: &Database, mal_id: i32, summary: &EpisodeSummary) -> Result<(), DatabaseError> {
+    let summary = bson::to_bson(summary)
+        .map_err(|e| DatabaseError::Query(format!("Failed to serialize episode summary: {}", e)))?;
+
+    db.collection::<Document>(COLLECTION_NAME)
+        .update_one(doc! { "mal_id": mal_id }, doc! { "$set": { "episode_summary": summary } })
+        .await
+        .map_err(|e| DatabaseError::Query(format!("Failed to set episode summary: {}", e)))?;
+    Ok(())
+}
+
+/// Record that an extended data section of a stored anime was just fetched
+pub async fn set_section_fetched(db: &Database, mal_id: i32, section: AnimeSection) -> Result<(), DatabaseError> {
+    db.collection::<Document>(COLLECTION_NAME)
+        .update_one(
+            doc! { "mal_id": mal_id },
+            doc! { "$set": { format!("fetched_at.{}", section.as_str()): chrono::Utc::now().to_rfc3339() } },
+        )
+        .await
+        .map_err(|e| DatabaseError::Query(format!("Failed to set section fetch time: {}", e)))?;
+    Ok(())
+}
+
 /// Insert anime (kept for compatibility)
 pub async fn insert_anime(db: &Database, data: &AnimeData) -> Result<(), DatabaseError> {
     upsert_anime(db, data).await
 }
 
+/// Set `duration_seconds` on the MyAnimeList anime stored before it existed
+pub async fn backfill_duration_seconds(db: &Database) -> Result<u64, DatabaseError> {
+    duration::backfill_duration_seconds(db.collection(COLLECTION_NAME)).await
+}
+
+/// Infer the missing season and year of the MyAnimeList anime stored before inference
+pub async fn backfill_seasons(db: &Database) -> Result<u64, DatabaseError> {
+    season::backfill_seasons(db.collection(COLLECTION_NAME)).await
+}
+
 /// Get anime by MAL ID
 pub async fn get_anime_by_id(db: &Database, mal_id: i32) -> Result<Option<AnimeData>, DatabaseError> {
     let collection = db.collection::<AnimeData>(COLLECTION_NAME);
     let filter = doc! { "mal_id": mal_id };
 
-    collection.find_one(filter).await
-        .map_err(|e| DatabaseError::Query(format!("Failed to get anime: {}", e)))
+    let mut anime = collection.find_one(filter).await
+        .map_err(|e| DatabaseError::Query(format!("Failed to get anime: {}", e)))?;
+    if let Some(anime) = &mut anime {
+        overflow::restore_anime(db, anime).await?;
+    }
+    Ok(anime)
+}
+
+/// Anime with only what the given v1 fields are built from read from the database,
+/// the other fields are left empty. Moved arrays among them are put back.
+pub async fn get_anime_fields(db: &Database, mal_id: i32, fields: &[String]) -> Result<Option<AnimeData>, DatabaseError> {
+    let document = db.collection::<Document>(COLLECTION_NAME)
+        .find_one(doc! { "mal_id": mal_id })
+        .projection(fields_projection(fields))
+        .await
+        .map_err(|e| DatabaseError::Query(format!("Failed to get anime: {}", e)))?;
+
+    match document {
+        Some(document) => projected_anime(db, document).await.map(Some),
+        None => Ok(None),
+    }
+}
+
+/// Projection of the stored fields the given v1 fields are built from,
+/// with `mal_id`, `updated_at` (Last-Modified) and `overflow_fields`
+fn fields_projection(fields: &[String]) -> Document {
+    let mut projection = doc! { "mal_id": 1, "updated_at": 1, "overflow_fields": 1 };
+    for field in fields {
+        let stored: &[&str] = match field.as_str() {
+            "id" => &["_id"],
+            "media_type_label" => &["media_type"],
+            "status_label" => &["status"],
+            "season_label" => &["season"],
+            // The synopsis is replaced by the one of the requested language
+            "synopsis" => &["synopsis", "synopses"],
+            field => &[field],
+        };
+        for field in stored {
+            projection.insert(*field, 1);
+        }
+    }
+    if !projection.contains_key("_id") {
+        projection.insert("_id", 0);
+    }
+    projection
+}
+
+/// Anime of a projected document, its moved arrays put back
+async fn projected_anime(db: &Database, mut document: Document) -> Result<AnimeData, DatabaseError> {
+    let mal_id = document.get_i32("mal_id")
+        .map_err(|e| DatabaseError::Query(format!("Failed to read projected anime: {}", e)))?;
+    overflow::restore_document(db, mal_id, &mut document).await?;
+
+    // Read onto an anime without data, so the fields left out deserialize
+    let mut anime = empty_anime_document(mal_id)?;
+    anime.extend(document);
+    bson::from_document(anime)
+        .map_err(|e| DatabaseError::Query(format!("Failed to read projected anime {}: {}", mal_id, e)))
+}
+
+/// Document of an anime without any data
+fn empty_anime_document(mal_id: i32) -> Result<Document, DatabaseError> {
+    let jikan: JikanAnime = serde_json::from_value(serde_json::json!({ "mal_id": mal_id }))
+        .map_err(|e| DatabaseError::Query(format!("Failed to build empty anime: {}", e)))?;
+    to_document(&converter::jikan_to_anime_data(jikan))
+        .map_err(|e| DatabaseError::Query(format!("Failed to build empty anime: {}", e)))
+}
+
+/// Iterate over every stored anime, ordered by MAL ID
+pub async fn get_all_anime_cursor(db: &Database) -> Result<Cursor<AnimeData>, DatabaseError> {
+    let collection = db.collection::<AnimeData>(COLLECTION_NAME);
+
+    collection.find(doc! {})
+        .sort(doc! { "mal_id": 1 })
+        .await
+        .map_err(|e| DatabaseError::Query(format!("Failed to list anime: {}", e)))
 }
 
 /// Check if anime exists in database
@@ -228,6 +564,35 @@ pub async fn search_anime_by_title(
     Ok(results)
 }
 
+/// Most recently collected anime first
+pub async fn get_recently_collected_anime(db: &Database, limit: i64) -> Result<Vec<AnimeData>, DatabaseError> {
+    let collection = db.collection::<AnimeData>(COLLECTION_NAME);
+
+    let filter = doc! {
+        "collected_at": { "$exists": true }
+    };
+
+    let options = FindOptions::builder()
+        .limit(limit)
+        .sort(doc! { "collected_at": -1 })
+        .build();
+
+    let mut cursor = collection.find(filter)
+        .with_options(options)
+        .await
+        .map_err(|e| DatabaseError::Query(format!("Failed to get recently collected: {}", e)))?;
+
+    let mut results = Vec::new();
+    while let Some(result) = cursor.next().await {
+        match result {
+            Ok(anime) => results.push(anime),
+            Err(e) => warn!(error = %e, "Failed to deserialize anime"),
+        }
+    }
+
+    Ok(results)
+}
+
 /// Get top rated anime
 pub async fn get_top_rated_anime(db: &Database, limit: i64) -> Result<Vec<AnimeData>, DatabaseError> {
     let collection = db.collection::<AnimeData>(COLLECTION_NAME);
@@ -321,6 +686,57 @@ pub async fn get_anime_by_season(
     Ok(results)
 }
 
+/// Filters of `get_random_anime`
+#[derive(Debug, Clone, Default)]
+pub struct RandomAnimeFilter {
+    /// MAL id of a genre, theme or demographic
+    pub genre_id: Option<i32>,
+    pub min_score: Option<f32>,
+    /// Media type name, case-insensitive ("tv", "movie", ...)
+    pub media_type: Option<String>,
+}
+
+/// Anime picked at random among those matching the filter
+pub async fn get_random_anime(db: &Database, filter: &RandomAnimeFilter, count: i64) -> Result<Vec<AnimeData>, DatabaseError> {
+    let collection = db.collection::<AnimeData>(COLLECTION_NAME);
+
+    let mut conditions = Vec::new();
+    if let Some(genre_id) = filter.genre_id {
+        conditions.push(genre_filter(genre_id));
+    }
+    if let Some(min_score) = filter.min_score {
+        conditions.push(doc! { "score": { "$gte": min_score as f64 } });
+    }
+    if let Some(media_type) = &filter.media_type {
+        let pattern = format!("^{}$", regex::escape(media_type));
+        conditions.push(doc! { "media_type": { "$regex": pattern, "$options": "i" } });
+    }
+    let matched = if conditions.is_empty() { doc! {} } else { doc! { "$and": conditions } };
+
+    let pipeline = vec![
+        doc! { "$match": matched },
+        doc! { "$sample": { "size": count } },
+    ];
+
+    let mut cursor = collection.aggregate(pipeline)
+        .with_type::<AnimeData>()
+        .await
+        .map_err(|e| DatabaseError::Query(format!("Failed to get random anime: {}", e)))?;
+
+    let mut results = Vec::new();
+    while let Some(result) = cursor.next().await {
+        match result {
+            Ok(mut anime) => {
+                overflow::restore_anime(db, &mut anime).await?;
+                results.push(anime);
+            }
+            Err(e) => warn!(error = %e, "Failed to deserialize anime"),
+        }
+    }
+
+    Ok(results)
+}
+
 /// Get anime by media type from anime_mal collection
 pub async fn get_anime_by_media_type(
     db: &Database,
@@ -383,6 +799,28 @@ pub async fn get_airing_anime(db: &Database, limit: i64) -> Result<Vec<AnimeData
     Ok(results)
 }
 
+/// MAL ids of every stored airing anime, most popular first
+pub async fn get_airing_anime_ids(db: &Database) -> Result<Vec<i32>, DatabaseError> {
+    let collection = db.collection::<Document>(COLLECTION_NAME);
+
+    let mut cursor = collection.find(doc! { "airing": true })
+        .sort(doc! { "popularity": 1 })
+        .projection(doc! { "mal_id": 1 })
+        .await
+        .map_err(|e| DatabaseError::Query(format!("Failed to get airing anime: {}", e)))?;
+
+    let mut results = Vec::new();
+    while let Some(result) = cursor.next().await {
+        match result.map(|document| document.get_i32("mal_id")) {
+            Ok(Ok(mal_id)) => results.push(mal_id),
+            Ok(Err(e)) => warn!(error = %e, "Anime without MAL id"),
+            Err(e) => warn!(error = %e, "Failed to read anime"),
+        }
+    }
+
+    Ok(results)
+}
+
 /// Get anime count in database
 pub async fn get_anime_count(db: &Database) -> Result<u64, DatabaseError> {
     let collection = db.collection::<AnimeData>(COLLECTION_NAME);
@@ -398,10 +836,42 @@ pub async fn delete_anime(db: &Database, mal_id: i32) -> Result<bool, DatabaseEr
 
     let result = collection.delete_one(filter).await
         .map_err(|e| DatabaseError::Query(format!("Failed to delete anime: {}", e)))?;
+    titles::remove_titles(db, TitleSource::Mal, mal_id).await?;
+    overflow::delete_anime_chunks(db, mal_id).await?;
+    db.collection::<Document>(VALIDATORS_COLLECTION).delete_one(doc! { "mal_id": mal_id }).await
+        .map_err(|e| DatabaseError::Query(format!("Failed to delete response validators: {}", e)))?;
 
     Ok(result.deleted_count > 0)
 }
 
+/// Validators of the last Jikan `/full` response stored for an anime
+pub async fn get_response_validators(db: &Database, mal_id: i32) -> Result<Option<Validators>, DatabaseError> {
+    let document = db.collection::<Document>(VALIDATORS_COLLECTION)
+        .find_one(doc! { "mal_id": mal_id })
+        .await
+        .map_err(|e| DatabaseError::Query(format!("Failed to get response validators: {}", e)))?;
+
+    document
+        .map(bson::from_document::<Validators>)
+        .transpose()
+        .map_err(|e| DatabaseError::Query(format!("Failed to read response validators: {}", e)))
+}
+
+/// Store the validators of the Jikan `/full` response an anime was updated from
+pub async fn set_response_validators(db: &Database, mal_id: i32, validators: &Validators) -> Result<(), DatabaseError> {
+    let mut document = to_document(validators)
+        .map_err(|e| DatabaseError::Query(format!("Failed to serialize response validators: {}", e)))?;
+    document.insert("mal_id", mal_id);
+    document.insert("updated_at", chrono::Utc::now().to_rfc3339());
+
+    db.collection::<Document>(VALIDATORS_COLLECTION)
+        .replace_one(doc! { "mal_id": mal_id }, document)
+        .upsert(true)
+        .await
+        .map_err(|e| DatabaseError::Query(format!("Failed to store response validators: {}", e)))?;
+    Ok(())
+}
+
 /// Bulk insert anime
 pub async fn bulk_insert_anime(db: &Database, anime_list: Vec<AnimeData>) -> Result<u64, DatabaseError> {
     if anime_list.is_empty() {
@@ -410,9 +880,13 @@ pub async fn bulk_insert_anime(db: &Database, anime_list: Vec<AnimeData>) -> Res
 
     let collection = db.collection::<AnimeData>(COLLECTION_NAME);
     
-    let result = collection.insert_many(anime_list).await
+    let result = collection.insert_many(&anime_list).await
         .map_err(|e| DatabaseError::Query(format!("Failed to bulk insert: {}", e)))?;
 
+    for anime in &anime_list {
+        titles::index_titles(db, TitleSource::Mal, anime.mal_id, Some(anime.mal_id), anime.titles.iter().map(|t| t.title.as_str())).await?;
+    }
+
     Ok(result.inserted_ids.len() as u64)
 }
 
@@ -440,22 +914,38 @@ pub async fn update_anime_extended_data<T: Serialize>(
     Ok(())
 }
 
-/// Get anime that need updating (older than specified days)
+/// MAL ids of the anime not updated for `days_old` days, oldest first.
+/// With a section, only that section's fetch time counts and anime never fetched come first.
 pub async fn get_anime_needing_update(
     db: &Database,
+    section: Option<AnimeSection>,
     days_old: i64,
     limit: i64,
-) -> Result<Vec<AnimeData>, DatabaseError> {
-    let collection = db.collection::<AnimeData>(COLLECTION_NAME);
+) -> Result<Vec<i32>, DatabaseError> {
+    let collection = db.collection::<Document>(COLLECTION_NAME);
     
-    let threshold = mongodb::bson::DateTime::now().timestamp_millis() - (days_old * 24 * 60 * 60 * 1000);
-    let filter = doc! {
-        "updated_at": { "$lt": threshold }
+    let threshold = chrono::TimeDelta::try_days(days_old)
+        .and_then(|age| chrono::Utc::now().checked_sub_signed(age))
+        .ok_or_else(|| DatabaseError::Query(format!("Age of {} days is out of range", days_old)))?
+        .to_rfc3339();
+    let (filter, sort) = match section {
+        Some(section) => {
+            let field = format!("fetched_at.{}", section.as_str());
+            (
+                doc! { "$or": [
+                    { field.as_str(): { "$exists": false } },
+                    { field.as_str(): { "$lt": &threshold } },
+                ] },
+                doc! { field.as_str(): 1 },
+            )
+        }
+        None => (doc! { "updated_at": { "$lt": &threshold } }, doc! { "updated_at": 1 }),
     };
     
     let options = FindOptions::builder()
         .limit(limit)
-        .sort(doc! { "updated_at": 1 }) // Oldest first
+        .sort(sort) // Oldest first
+        .projection(doc! { "mal_id": 1 })
         .build();
 
     let mut cursor = collection.find(filter)
@@ -465,9 +955,10 @@ pub async fn get_anime_needing_update(
 
     let mut results = Vec::new();
     while let Some(result) = cursor.next().await {
-        match result {
-            Ok(anime) => results.push(anime),
-            Err(e) => warn!(error = %e, "Failed to deserialize anime"),
+        match result.map(|document| document.get_i32("mal_id")) {
+            Ok(Ok(mal_id)) => results.push(mal_id),
+            Ok(Err(e)) => warn!(error = %e, "Anime without MAL id"),
+            Err(e) => warn!(error = %e, "Failed to read anime"),
         }
     }
 
@@ -536,15 +1027,27 @@ pub async fn get_cached_data(
 #[derive(Debug, Serialize, Deserialize)]
 struct SearchHistory {
     query: String,
+    /// Trimmed and lowercased query, searches are grouped and matched on it
+    #[serde(default)]
+    normalized: String,
     searched_at: chrono::DateTime<chrono::Utc>,
 }
 
-/// Record a search query
-async fn record_search_history(db: &Database, query: &str) -> Result<(), DatabaseError> {
+fn normalize_search(query: &str) -> String {
+    query.trim().to_lowercase()
+}
+
+/// Record a search query, blank queries are ignored
+pub async fn record_search_history(db: &Database, query: &str) -> Result<(), DatabaseError> {
     let collection = db.collection::<SearchHistory>("anime_mal_search_history");
-    
+
+    let normalized = normalize_search(query);
+    if normalized.is_empty() {
+        return Ok(());
+    }
     let history = SearchHistory {
         query: query.to_string(),
+        normalized,
         searched_at: chrono::Utc::now(),
     };
 
@@ -577,4 +1080,395 @@ pub async fn get_recent_searches(db: &Database, limit: i64) -> Result<Vec<String
     }
 
     Ok(results)
-}
\ No newline at end of file
+}
+
+/// A query of the search history with the number of times it was searched
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchCount {
+    #[serde(rename = "_id")]
+    pub query: String,
+    pub count: u64,
+    pub last_searched_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Order of the search history queries
+#[derive(Debug, Clone, Copy)]
+pub enum SearchCountOrder {
+    /// Most searched first
+    Popular,
+    /// Most recently searched first
+    Recent,
+}
+
+/// Queries of the search history grouped by normalized query, optionally only those starting with `prefix`
+pub async fn get_search_counts(
+    db: &Database,
+    prefix: Option<&str>,
+    order: SearchCountOrder,
+    limit: i64,
+) -> Result<Vec<SearchCount>, DatabaseError> {
+    let collection = db.collection::<SearchHistory>("anime_mal_search_history");
+
+    let sort = match order {
+        SearchCountOrder::Popular => doc! { "count": -1, "last_searched_at": -1 },
+        SearchCountOrder::Recent => doc! { "last_searched_at": -1 },
+    };
+    // An anchored regex on the indexed field only reads the matching entries before grouping
+    let filter = match prefix {
+        Some(prefix) => doc! { "normalized": { "$regex": format!("^{}", regex::escape(&normalize_search(prefix))) } },
+        None => doc! { "normalized": { "$gt": "" } },
+    };
+    let pipeline = vec![
+        doc! { "$match": filter },
+        doc! { "$group": {
+            "_id": "$normalized",
+            "count": { "$sum": 1_i64 },
+            "last_searched_at": { "$max": "$searched_at" },
+        } },
+        doc! { "$sort": sort },
+        doc! { "$limit": limit },
+    ];
+
+    let mut cursor = collection.aggregate(pipeline)
+        .with_type::<SearchCount>()
+        .await
+        .map_err(|e| DatabaseError::Query(format!("Failed to count searches: {}", e)))?;
+
+    let mut results = Vec::new();
+    while let Some(result) = cursor.next().await {
+        match result {
+            Ok(count) if !count.query.is_empty() => results.push(count),
+            Ok(_) => {}
+            Err(e) => warn!(error = %e, "Failed to deserialize search count"),
+        }
+    }
+
+    Ok(results)
+}
+
+// ========================================================================
+// Search Results Operations
+// ========================================================================
+
+/// Store the results of a search task
+pub async fn save_search_results(db: &Database, results: &SearchResults) -> Result<(), DatabaseError> {
+    let collection = db.collection::<SearchResults>(SEARCH_RESULTS_COLLECTION);
+    let filter = doc! { "search_id": &results.search_id };
+    let options = ReplaceOptions::builder().upsert(true).build();
+
+    collection.replace_one(filter, results)
+        .with_options(options)
+        .await
+        .map_err(|e| DatabaseError::Query(format!("Failed to save search results: {}", e)))?;
+
+    debug!(search_id = %results.search_id, count = results.results.len(), "Search results saved");
+    Ok(())
+}
+
+/// Get the results of a completed search
+pub async fn get_search_results(db: &Database, search_id: &str) -> Result<Option<SearchResults>, DatabaseError> {
+    let collection = db.collection::<SearchResults>(SEARCH_RESULTS_COLLECTION);
+
+    collection.find_one(doc! { "search_id": search_id }).await
+        .map_err(|e| DatabaseError::Query(format!("Failed to get search results: {}", e)))
+}
+
+// ========================================================================
+// Change History Operations
+// ========================================================================
+
+/// Hash of the stored data of an anime, without `updated_at` which changes on every fetch
+fn data_hash(document: &Document) -> Result<String, DatabaseError> {
+    let mut document = document.clone();
+    document.remove("updated_at");
+    let bytes = bson::to_vec(&document)
+        .map_err(|e| DatabaseError::Query(format!("Failed to serialize anime: {}", e)))?;
+    Ok(format!("{:x}", Sha256::digest(&bytes)))
+}
+
+/// Top-level fields that differ between the stored and the new document
+fn diff_documents(previous: &Document, current: &Document) -> Vec<FieldChange> {
+    let mut fields: Vec<&String> = previous.keys().chain(current.keys()).collect();
+    fields.sort();
+    fields.dedup();
+
+    fields.into_iter()
+        .filter(|field| !HISTORY_IGNORED_FIELDS.contains(&field.as_str()))
+        .filter_map(|field| {
+            let old = previous.get(field).cloned().unwrap_or(Bson::Null);
+            let new = current.get(field).cloned().unwrap_or(Bson::Null);
+            (old != new).then(|| FieldChange {
+                field: field.clone(),
+                old,
+                new,
+            })
+        })
+        .collect()
+}
+
+async fn record_history(db: &Database, mal_id: i32, changes: Vec<FieldChange>) -> Result<(), DatabaseError> {
+    let collection = db.collection::<AnimeHistoryEntry>(HISTORY_COLLECTION);
+
+    let entry = AnimeHistoryEntry {
+        mal_id,
+        changes,
+        recorded_at: chrono::Utc::now(),
+    };
+
+    collection.insert_one(&entry).await
+        .map_err(|e| DatabaseError::Query(format!("Failed to record anime history: {}", e)))?;
+
+    debug!(mal_id = mal_id, fields = entry.changes.len(), "Anime changes recorded");
+    Ok(())
+}
+
+/// Change history of an anime, newest first
+pub async fn get_anime_history(db: &Database, mal_id: i32, limit: i64) -> Result<Vec<AnimeHistoryEntry>, DatabaseError> {
+    let collection = db.collection::<AnimeHistoryEntry>(HISTORY_COLLECTION);
+
+    let options = FindOptions::builder()
+        .limit(limit)
+        .sort(doc! { "recorded_at": -1 })
+        .build();
+
+    let mut cursor = collection.find(doc! { "mal_id": mal_id })
+        .with_options(options)
+        .await
+        .map_err(|e| DatabaseError::Query(format!("Failed to get anime history: {}", e)))?;
+
+    let mut results = Vec::new();
+    while let Some(result) = cursor.next().await {
+        match result {
+            Ok(entry) => results.push(entry),
+            Err(e) => warn!(error = %e, "Failed to deserialize anime history"),
+        }
+    }
+
+    Ok(results)
+}
+
+// ========================================================================
+// Database Operations for StatisticsSnapshot
+// ========================================================================
+
+pub async fn insert_statistics_snapshots(db: &Database, snapshots: &[StatisticsSnapshot]) -> Result<(), DatabaseError> {
+    if snapshots.is_empty() {
+        return Ok(());
+    }
+
+    let collection = db.collection::<StatisticsSnapshot>(SNAPSHOTS_COLLECTION);
+    collection.insert_many(snapshots).await
+        .map_err(|e| DatabaseError::Query(format!("Failed to insert statistics snapshots: {}", e)))?;
+
+    debug!(count = snapshots.len(), "Statistics snapshots recorded");
+    Ok(())
+}
+
+/// Snapshots of an anime recorded after `since`, oldest first
+pub async fn get_statistics_snapshots(
+    db: &Database,
+    mal_id: i32,
+    since: chrono::DateTime<chrono::Utc>,
+) -> Result<Vec<StatisticsSnapshot>, DatabaseError> {
+    let collection = db.collection::<StatisticsSnapshot>(SNAPSHOTS_COLLECTION);
+
+    let filter = doc! {
+        "mal_id": mal_id,
+        // Same format as the serialized timestamps, so the strings compare chronologically
+        "recorded_at": { "$gte": since.to_rfc3339_opts(chrono::SecondsFormat::AutoSi, true) }
+    };
+
+    let options = FindOptions::builder()
+        .sort(doc! { "recorded_at": 1 })
+        .build();
+
+    let mut cursor = collection.find(filter)
+        .with_options(options)
+        .await
+        .map_err(|e| DatabaseError::Query(format!("Failed to get statistics snapshots: {}", e)))?;
+
+    let mut results = Vec::new();
+    while let Some(result) = cursor.next().await {
+        match result {
+            Ok(snapshot) => results.push(snapshot),
+            Err(e) => warn!(error = %e, "Failed to deserialize statistics snapshot"),
+        }
+    }
+
+    Ok(results)
+}
+
+// ========================================================================
+// Database Operations for the Genre/Producer Taxonomies
+// ========================================================================
+
+pub async fn upsert_genres(db: &Database, genres: &[TaxonomyGenre]) -> Result<(), DatabaseError> {
+    let collection = db.collection::<TaxonomyGenre>(GENRES_COLLECTION);
+    let options = ReplaceOptions::builder().upsert(true).build();
+
+    for genre in genres {
+        collection.replace_one(doc! { "mal_id": genre.mal_id }, genre)
+            .with_options(options.clone())
+            .await
+            .map_err(|e| DatabaseError::Query(format!("Failed to upsert genre: {}", e)))?;
+    }
+
+    debug!(count = genres.len(), "Genres upserted");
+    Ok(())
+}
+
+pub async fn upsert_producers(db: &Database, producers: &[TaxonomyProducer]) -> Result<(), DatabaseError> {
+    let collection = db.collection::<TaxonomyProducer>(PRODUCERS_COLLECTION);
+    let options = ReplaceOptions::builder().upsert(true).build();
+
+    for producer in producers {
+        collection.replace_one(doc! { "mal_id": producer.mal_id }, producer)
+            .with_options(options.clone())
+            .await
+            .map_err(|e| DatabaseError::Query(format!("Failed to upsert producer: {}", e)))?;
+    }
+
+    debug!(count = producers.len(), "Producers upserted");
+    Ok(())
+}
+
+/// Synced genres ordered by name, optionally of one category
+pub async fn get_genres(db: &Database, category: Option<GenreCategory>) -> Result<Vec<TaxonomyGenre>, DatabaseError> {
+    let collection = db.collection::<TaxonomyGenre>(GENRES_COLLECTION);
+    let filter = match category {
+        Some(category) => doc! { "category": category.as_str() },
+        None => doc! {},
+    };
+
+    let mut cursor = collection.find(filter)
+        .sort(doc! { "name": 1 })
+        .await
+        .map_err(|e| DatabaseError::Query(format!("Failed to get genres: {}", e)))?;
+
+    let mut results = Vec::new();
+    while let Some(result) = cursor.next().await {
+        match result {
+            Ok(genre) => results.push(genre),
+            Err(e) => warn!(error = %e, "Failed to deserialize genre"),
+        }
+    }
+
+    Ok(results)
+}
+
+/// Synced producers with the most anime first
+pub async fn get_producers(db: &Database, limit: i64, skip: u64) -> Result<Vec<TaxonomyProducer>, DatabaseError> {
+    let collection = db.collection::<TaxonomyProducer>(PRODUCERS_COLLECTION);
+
+    let mut cursor = collection.find(doc! {})
+        .sort(doc! { "count": -1, "mal_id": 1 })
+        .skip(skip)
+        .limit(limit)
+        .await
+        .map_err(|e| DatabaseError::Query(format!("Failed to get producers: {}", e)))?;
+
+    let mut results = Vec::new();
+    while let Some(result) = cursor.next().await {
+        match result {
+            Ok(producer) => results.push(producer),
+            Err(e) => warn!(error = %e, "Failed to deserialize producer"),
+        }
+    }
+
+    Ok(results)
+}
+
+/// Stored anime having the genre, theme or demographic with this MAL id, most members first
+pub async fn get_anime_by_genre(db: &Database, genre_id: i32, limit: i64) -> Result<Vec<AnimeData>, DatabaseError> {
+    find_anime_by_members(db, genre_filter(genre_id), limit, None).await
+}
+
+/// Same as `get_anime_by_genre` with only what the given v1 fields are built from
+pub async fn get_anime_fields_by_genre(
+    db: &Database,
+    genre_id: i32,
+    limit: i64,
+    fields: &[String],
+) -> Result<Vec<AnimeData>, DatabaseError> {
+    let documents = find_anime_by_members(db, genre_filter(genre_id), limit, Some(fields_projection(fields))).await?;
+    projected_list(db, documents).await
+}
+
+fn genre_filter(genre_id: i32) -> Document {
+    doc! {
+        "$or": [
+            { "genres.mal_id": genre_id },
+            { "explicit_genres.mal_id": genre_id },
+            { "themes.mal_id": genre_id },
+            { "demographics.mal_id": genre_id },
+        ]
+    }
+}
+
+/// Stored anime produced, licensed or animated by the producer with this MAL id, most members first
+pub async fn get_anime_by_producer(db: &Database, producer_id: i32, limit: i64) -> Result<Vec<AnimeData>, DatabaseError> {
+    find_anime_by_members(db, producer_filter(producer_id), limit, None).await
+}
+
+/// Same as `get_anime_by_producer` with only what the given v1 fields are built from
+pub async fn get_anime_fields_by_producer(
+    db: &Database,
+    producer_id: i32,
+    limit: i64,
+    fields: &[String],
+) -> Result<Vec<AnimeData>, DatabaseError> {
+    let documents = find_anime_by_members(db, producer_filter(producer_id), limit, Some(fields_projection(fields))).await?;
+    projected_list(db, documents).await
+}
+
+async fn projected_list(db: &Database, documents: Vec<Document>) -> Result<Vec<AnimeData>, DatabaseError> {
+    let mut anime = Vec::with_capacity(documents.len());
+    for document in documents {
+        anime.push(projected_anime(db, document).await?);
+    }
+    Ok(anime)
+}
+
+fn producer_filter(producer_id: i32) -> Document {
+    doc! {
+        "$or": [
+            { "producers.mal_id": producer_id },
+            { "licensors.mal_id": producer_id },
+            { "studios.mal_id": producer_id },
+        ]
+    }
+}
+
+/// Anime matching `filter`, most members first, as `AnimeData` or as projected documents
+async fn find_anime_by_members<T>(
+    db: &Database,
+    filter: Document,
+    limit: i64,
+    projection: Option<Document>,
+) -> Result<Vec<T>, DatabaseError>
+where
+    T: DeserializeOwned + Send + Sync,
+{
+    let collection = db.collection::<T>(COLLECTION_NAME);
+
+    let options = FindOptions::builder()
+        .limit(limit)
+        .sort(doc! { "members": -1 })
+        .projection(projection)
+        .build();
+
+    let mut cursor = collection.find(filter)
+        .with_options(options)
+        .await
+        .map_err(|e| DatabaseError::Query(format!("Failed to get anime: {}", e)))?;
+
+    let mut results = Vec::new();
+    while let Some(result) = cursor.next().await {
+        match result {
+            Ok(anime) => results.push(anime),
+            Err(e) => warn!(error = %e, "Failed to deserialize anime"),
+        }
+    }
+
+    Ok(results)
+}
@@ -3,6 +3,8 @@ pub mod module;
 pub mod model;
 pub mod converter;
 pub mod task;
+pub mod season;
+pub mod overflow;
 
 // Re-export commonly used types
 pub use model::{AnimeData, MalAnimeResponse, JikanAnimeResponse};
@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, NaiveTime, Utc};
 use mongodb::bson;
@@ -13,8 +15,14 @@ pub struct AnimeData {
     pub id: Option<bson::oid::ObjectId>,
     
     pub mal_id: i32,
+    /// API the entry was built from, Jikan when MyAnimeList had no usable entry
+    #[serde(default)]
+    pub provider: DataProvider,
     pub url: String,
     pub images: Images,
+    /// Downloaded copies of the images by category (e.g. "main_jpg_large"), set by the picture module
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub local_images: BTreeMap<String, LocalImage>,
     pub trailer: Trailer,
     pub approved: bool,
     pub titles: Vec<Title>,
@@ -23,6 +31,9 @@ pub struct AnimeData {
     pub source: Option<Source>,
     pub num_episodes: i32,
     pub average_episode_duration: i32,
+    /// Seconds per episode, normalized from the provider duration
+    #[serde(default)]
+    pub duration_seconds: Option<i32>,
     pub status: Option<Status>,
     pub airing: bool,
     pub aired: Aired,
@@ -35,6 +46,9 @@ pub struct AnimeData {
     pub favorites: i32,
     pub popularity: Option<i32>,
     pub synopsis: String,
+    /// Synopses by language code, `synopsis` is the one of the provider language
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub synopses: BTreeMap<String, String>,
     pub background: Option<String>,
     pub season: Option<Season>,
     pub year: Option<i32>,
@@ -52,14 +66,18 @@ pub struct AnimeData {
     pub streaming: Vec<Streaming>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
+    /// When the anime was first stored locally, set by the database on insert
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub collected_at: Option<DateTime<Utc>>,
     
     // Extended data (fetched separately)
     #[serde(default)]
     pub characters: Vec<Character>,
     #[serde(default)]
     pub staffs: Vec<Staff>,
-    #[serde(default)]
-    pub episodes: Vec<Episode>,
+    /// Episode counts, the episodes themselves are in `anime_episodes`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub episode_summary: Option<EpisodeSummary>,
     #[serde(default)]
     pub videos: Option<Videos>,
     #[serde(default)]
@@ -69,6 +87,226 @@ pub struct AnimeData {
     pub more_info: Option<String>,
     #[serde(default)]
     pub recommendations: Vec<Recommendation>,
+    /// Arrays stored in `anime_mal_overflow` because the document got too large,
+    /// they are put back when the anime is read by id
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub overflow_fields: Vec<String>,
+    /// When each extended data section was last fetched, keyed by `AnimeSection::as_str`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub fetched_at: BTreeMap<String, DateTime<Utc>>,
+}
+
+/// Extended data section fetched separately from the anime
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnimeSection {
+    Characters,
+    Staff,
+    Episodes,
+    Videos,
+    Statistics,
+    MoreInfo,
+    Recommendations,
+    Pictures,
+}
+
+impl AnimeSection {
+    pub const ALL: [AnimeSection; 8] = [
+        AnimeSection::Characters,
+        AnimeSection::Staff,
+        AnimeSection::Episodes,
+        AnimeSection::Videos,
+        AnimeSection::Statistics,
+        AnimeSection::MoreInfo,
+        AnimeSection::Recommendations,
+        AnimeSection::Pictures,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AnimeSection::Characters => "characters",
+            AnimeSection::Staff => "staff",
+            AnimeSection::Episodes => "episodes",
+            AnimeSection::Videos => "videos",
+            AnimeSection::Statistics => "statistics",
+            AnimeSection::MoreInfo => "more_info",
+            AnimeSection::Recommendations => "recommendations",
+            AnimeSection::Pictures => "pictures",
+        }
+    }
+}
+
+/// Provider a stored entry was built from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DataProvider {
+    /// MyAnimeList API, optionally enriched with Jikan
+    #[default]
+    MyAnimeList,
+    /// Jikan alone
+    Jikan,
+}
+
+/// Language of the synopses sent by MyAnimeList, Jikan and AniList
+pub const PROVIDER_SYNOPSIS_LANGUAGE: &str = "en";
+
+/// Synopses by language holding the provider one, empty when the provider sent none
+pub fn provider_synopses(synopsis: &str) -> BTreeMap<String, String> {
+    let mut synopses = BTreeMap::new();
+    if !synopsis.trim().is_empty() {
+        synopses.insert(PROVIDER_SYNOPSIS_LANGUAGE.to_string(), synopsis.to_string());
+    }
+    synopses
+}
+
+impl AnimeData {
+    /// Title of the given type (e.g. "Default", "English", "Japanese")
+    pub fn title_of_type(&self, title_type: &str) -> Option<&str> {
+        self.titles.iter()
+            .find(|title| title.title_type == title_type)
+            .map(|title| title.title.as_str())
+    }
+
+    /// Default title, falling back to the first known title
+    pub fn display_title(&self) -> &str {
+        self.title_of_type("Default")
+            .or_else(|| self.titles.first().map(|title| title.title.as_str()))
+            .unwrap_or("Unknown")
+    }
+
+    /// Replace `synopsis` with the one in `language` when it is stored,
+    /// returns the language `synopsis` is in
+    pub fn use_synopsis_language(&mut self, language: &str) -> String {
+        let language = language.to_lowercase();
+        match self.synopses.get(&language) {
+            Some(synopsis) => {
+                self.synopsis = synopsis.clone();
+                language
+            }
+            None => PROVIDER_SYNOPSIS_LANGUAGE.to_string(),
+        }
+    }
+}
+
+/// Changes of an anime between two upserts, stored in the `anime_history` collection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnimeHistoryEntry {
+    pub mal_id: i32,
+    pub changes: Vec<FieldChange>,
+    pub recorded_at: DateTime<Utc>,
+}
+
+/// A top-level field whose value changed, `old` is null for fields that didn't exist
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FieldChange {
+    pub field: String,
+    pub old: bson::Bson,
+    pub new: bson::Bson,
+}
+
+/// Popularity figures of an anime at a point in time, stored in the
+/// `anime_statistics_snapshots` collection by the statistics snapshot task
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StatisticsSnapshot {
+    pub mal_id: i32,
+    pub score: Option<f32>,
+    pub members: Option<i32>,
+    pub favorites: Option<i32>,
+    /// Users currently watching, from the Jikan statistics endpoint
+    pub watching: Option<i32>,
+    pub recorded_at: DateTime<Utc>,
+}
+
+/// Kind of genre, as filtered by Jikan `/genres/anime?filter=`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GenreCategory {
+    Genres,
+    ExplicitGenres,
+    Themes,
+    Demographics,
+}
+
+impl GenreCategory {
+    pub const ALL: [GenreCategory; 4] = [
+        GenreCategory::Genres,
+        GenreCategory::ExplicitGenres,
+        GenreCategory::Themes,
+        GenreCategory::Demographics,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            GenreCategory::Genres => "genres",
+            GenreCategory::ExplicitGenres => "explicit_genres",
+            GenreCategory::Themes => "themes",
+            GenreCategory::Demographics => "demographics",
+        }
+    }
+}
+
+/// Canonical genre from Jikan `/genres/anime`, stored in the `genres` collection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaxonomyGenre {
+    pub mal_id: i32,
+    pub name: String,
+    pub url: String,
+    pub category: GenreCategory,
+    /// Anime with this genre on MAL
+    pub count: i32,
+    pub synced_at: DateTime<Utc>,
+}
+
+/// Canonical producer (studios and licensors included) from Jikan `/producers`,
+/// stored in the `producers` collection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaxonomyProducer {
+    pub mal_id: i32,
+    pub name: String,
+    pub titles: Vec<Title>,
+    pub url: String,
+    pub image_url: Option<String>,
+    pub favorites: i32,
+    /// Anime produced on MAL
+    pub count: i32,
+    pub established: Option<DateTime<Utc>>,
+    pub synced_at: DateTime<Utc>,
+}
+
+/// Results of a search task, stored in the `search_results` collection.
+/// `search_id` is the id of the task that ran the search.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchResults {
+    pub search_id: String,
+    pub query: String,
+    pub results: Vec<SearchResultEntry>,
+    pub completed_at: DateTime<Utc>,
+}
+
+/// Summary of an anime found by a search, the full entry is stored in `anime_mal`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchResultEntry {
+    pub mal_id: i32,
+    pub title: String,
+    pub url: String,
+    pub image_url: String,
+    pub media_type: Option<MediaType>,
+    pub score: Option<f32>,
+    pub year: Option<i32>,
+}
+
+impl From<&AnimeData> for SearchResultEntry {
+    fn from(anime: &AnimeData) -> Self {
+        Self {
+            mal_id: anime.mal_id,
+            title: anime.display_title().to_string(),
+            url: anime.url.clone(),
+            image_url: anime.images.jpg.image_url.clone(),
+            media_type: anime.media_type.clone(),
+            score: anime.score,
+            year: anime.year,
+        }
+    }
 }
 
 // ========================================================================
@@ -78,6 +316,7 @@ pub struct AnimeData {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MalAnimeResponse {
     pub id: i32,
+    #[serde(default)]
     pub title: String,
     #[serde(default)]
     pub main_picture: Option<MalPicture>,
@@ -161,6 +400,7 @@ pub struct MalStudio {
 pub struct MalRelatedAnime {
     pub node: MalNode,
     pub relation_type: String,
+    #[serde(default)]
     pub relation_type_formatted: String,
 }
 
@@ -168,12 +408,14 @@ pub struct MalRelatedAnime {
 pub struct MalRelatedManga {
     pub node: MalNode,
     pub relation_type: String,
+    #[serde(default)]
     pub relation_type_formatted: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MalNode {
     pub id: i32,
+    #[serde(default)]
     pub title: String,
     pub main_picture: Option<MalPicture>,
 }
@@ -181,6 +423,7 @@ pub struct MalNode {
 #[serde_as]
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MalStatistics {
+    #[serde(default)]
     pub num_list_users: i32,
     pub status: MalStatusStats,
 }
@@ -212,18 +455,24 @@ pub struct JikanAnimeResponse {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct JikanAnime {
     pub mal_id: i32,
+    #[serde(default)]
     pub url: String,
+    #[serde(default)]
     pub images: JikanImages,
     #[serde(default)]
     pub trailer: JikanTrailer,
+    #[serde(default)]
     pub approved: bool,
+    #[serde(default)]
     pub titles: Vec<JikanTitle>,
     #[serde(rename = "type")]
     pub media_type: Option<String>,
     pub source: Option<String>,
     pub episodes: Option<i32>,
     pub status: Option<String>,
+    #[serde(default)]
     pub airing: bool,
+    #[serde(default)]
     pub aired: JikanAired,
     pub duration: Option<String>,
     pub rating: Option<String>,
@@ -263,7 +512,7 @@ pub struct JikanAnime {
     pub streaming: Vec<JikanStreaming>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct JikanImages {
     #[serde(default)]
     pub jpg: JikanImage,
@@ -292,7 +541,7 @@ pub struct JikanTitle {
     pub title: String,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct JikanAired {
     pub from: Option<String>,
     pub to: Option<String>,
@@ -309,24 +558,27 @@ pub struct JikanBroadcast {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct JikanEntity {
     pub mal_id: i32,
-    #[serde(rename = "type")]
+    #[serde(rename = "type", default)]
     pub entity_type: String,
     pub name: String,
+    #[serde(default)]
     pub url: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct JikanRelation {
     pub relation: String,
+    #[serde(default)]
     pub entry: Vec<JikanRelationEntry>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct JikanRelationEntry {
     pub mal_id: i32,
-    #[serde(rename = "type")]
+    #[serde(rename = "type", default)]
     pub entry_type: String,
     pub name: String,
+    #[serde(default)]
     pub url: String,
 }
 
@@ -354,6 +606,14 @@ pub struct JikanStreaming {
 // Shared Models (used in final AnimeData)
 // ========================================================================
 
+/// Local copy of a provider image
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LocalImage {
+    /// Provider URL the file was downloaded from
+    pub url: String,
+    pub file_path: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Title {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -378,33 +638,42 @@ pub struct Image {
     pub large_image_url: String,
 }
 
+/// Age rating, the aliases are the spellings of the MAL API and Jikan.
+/// Values no variant knows are kept as received in `Other`.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Rating {
+    #[serde(alias = "g")]
     G,
+    #[serde(alias = "pg")]
     PG,
-    #[serde(rename = "PG-13")]
+    #[serde(rename = "PG-13", alias = "pg_13", alias = "PG13")]
     PG13,
-    #[serde(rename = "R-17+")]
+    #[serde(rename = "R-17+", alias = "r", alias = "R", alias = "R - 17+")]
     R17Plus,
-    #[serde(rename = "R+")]
+    #[serde(rename = "R+", alias = "r+")]
     RPlus,
+    #[serde(alias = "rx", alias = "RX")]
     Rx,
+    #[serde(untagged)]
+    Other(String),
 }
 
+/// Airing status, values no variant knows are kept as received in `Other`
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Status {
-    #[serde(rename = "finished_airing")]
+    #[serde(rename = "finished_airing", alias = "Finished Airing")]
     FinishedAiring,
-    #[serde(rename = "currently_airing")]
+    #[serde(rename = "currently_airing", alias = "Currently Airing")]
     CurrentlyAiring,
-    #[serde(rename = "not_yet_aired")]
+    #[serde(rename = "not_yet_aired", alias = "Not yet aired")]
     NotYetAired,
+    #[serde(untagged)]
+    Other(String),
 }
 
+/// Source material, values no variant knows (including "other") are kept as received in `Other`
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Source {
-    #[serde(rename = "other")]
-    Other,
     #[serde(rename = "original")]
     Original,
     #[serde(rename = "manga")]
@@ -433,6 +702,8 @@ pub enum Source {
     Radio,
     #[serde(rename = "music")]
     Music,
+    #[serde(untagged)]
+    Other(String),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -505,6 +776,12 @@ pub struct Streaming {
     pub id: Option<i32>,
     pub name: String,
     pub url: String,
+    /// Episode title, for the streaming episodes of AniList
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// Episode thumbnail URL, for the streaming episodes of AniList
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thumbnail: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -555,6 +832,18 @@ pub enum Season {
     Winter,
 }
 
+impl Season {
+    /// Lowercase name, as used in MAL API paths
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Season::Spring => "spring",
+            Season::Summer => "summer",
+            Season::Fall => "fall",
+            Season::Winter => "winter",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Relation {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -673,6 +962,7 @@ pub struct StaffInfo {
     pub name: String,
 }
 
+/// Episode as listed by Jikan, `mal_id` is the episode number
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Episode {
     pub mal_id: i32,
@@ -689,6 +979,17 @@ pub struct Episode {
     pub forum_url: Option<String>,
 }
 
+/// Episode counts of an anime, updated with its episode list
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EpisodeSummary {
+    pub total: u32,
+    pub filler: u32,
+    pub recap: u32,
+    /// Number of the last aired episode when the list was fetched
+    pub latest_aired: Option<i32>,
+    pub updated_at: DateTime<Utc>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Videos {
     #[serde(default)]
@@ -1,15 +1,20 @@
 use std::sync::Arc;
 use tracing::{info, warn};
 
+use crate::anime::collect::{CollectAnimeTask, CollectTarget};
+use crate::anime::fallback::ProviderFallback;
 use crate::global::config::AppConfig;
 use crate::global::error::AppError;
+use crate::global::events::EventBus;
 use crate::global::http::ClientWithLimiter;
-use crate::global::queue::TaskQueue;
+use crate::global::queue::{Task, TaskPriority, TaskQueue};
 use crate::picture::PictureFetcherModule;
 
+use super::model::AnimeSection;
+
 use super::task::{
     FetchAnimeTask, SearchAnimeTask, UpdateAnimeTask, BatchFetchTask,
-    FetchCharactersTask, FetchEpisodesTask, FetchStaffTask,
+    FetchCharactersTask, FetchEpisodesTask, FetchEpisodeDetailTask, FetchStaffTask,
     FetchVideosTask, FetchStatisticsTask, FetchMoreInfoTask,
     FetchRecommendationsTask, FetchPicturesTask,
 };
@@ -20,6 +25,9 @@ pub struct MyAnimeListModule {
     config: Arc<AppConfig>,
     queue: TaskQueue,
     picture_module: Option<Arc<PictureFetcherModule>>,
+    events: Option<EventBus>,
+    /// Providers tried when MyAnimeList has no entry, from `anime.fallback`
+    fallback: ProviderFallback,
 }
 
 impl MyAnimeListModule {
@@ -33,12 +41,16 @@ impl MyAnimeListModule {
             return None;
         }
 
+        let fallback = ProviderFallback::new(config.anime.fallback.clone());
+
         Some(Self { 
             mal_client,
             jikan_client,
             config, 
             queue,
             picture_module: None,
+            events: None,
+            fallback,
         })
     }
     
@@ -48,6 +60,24 @@ impl MyAnimeListModule {
         self
     }
 
+    /// Publish data events from the queued fetch tasks
+    pub fn with_events(mut self, events: EventBus) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    pub fn events(&self) -> Option<&EventBus> {
+        self.events.as_ref()
+    }
+
+    /// Enable the AniList fallback of fetch tasks, when the AniList module is configured
+    pub fn with_anilist_client(mut self, client: ClientWithLimiter) -> Self {
+        if self.config.is_child_module_enabled("anilist") {
+            self.fallback = self.fallback.with_anilist(client);
+        }
+        self
+    }
+
     pub fn is_available(config: &AppConfig) -> bool {
         config.can_start_child_module("my_anime_list", true)
     }
@@ -119,6 +149,11 @@ impl MyAnimeListModule {
             }
         }
 
+        if let Some(events) = &self.events {
+            task = task.with_events(events.clone());
+        }
+        task = task.with_fallback(self.fallback.clone());
+
         info!(
             module = "my_anime_list",
             anime_id = anime_id,
@@ -131,8 +166,48 @@ impl MyAnimeListModule {
         self.queue.enqueue(Box::new(task)).await
     }
 
+    /// Queue the complete collection chain of an anime as a job
+    /// (MAL + Jikan + extended data + AniList + pictures)
+    pub async fn queue_collect(
+        &self,
+        target: CollectTarget,
+        anilist_client: Option<ClientWithLimiter>,
+        job_id: &str,
+    ) -> Result<(), AppError> {
+        let api_key = self.config.get_api_key("my_anime_list")
+            .expect("API key should be validated during module creation");
+
+        let mut task = CollectAnimeTask::new(
+            target.clone(),
+            api_key,
+            self.mal_client.clone(),
+            self.jikan_client.clone(),
+            self.queue.clone(),
+        );
+
+        if let Some(client) = anilist_client {
+            task = task.with_anilist(client);
+        }
+        if let Some(picture_module) = &self.picture_module {
+            task = task.with_pictures(picture_module.clone());
+        }
+        if let Some(events) = &self.events {
+            task = task.with_events(events.clone());
+        }
+
+        info!(
+            module = "my_anime_list",
+            target = ?target,
+            job_id = %job_id,
+            "Queueing anime collection"
+        );
+
+        self.queue.enqueue_for_job(Box::new(task), job_id).await
+    }
+
     /// Queue a task to search for anime
-    pub async fn queue_search_anime(&self, query: String, limit: Option<u32>) -> Result<(), AppError> {
+    /// Queue a search, returns the search id under which the results are stored
+    pub async fn queue_search_anime(&self, query: String, limit: Option<u32>) -> Result<String, AppError> {
         let api_key = self.config.get_api_key("my_anime_list")
             .expect("API key should be validated during module creation");
 
@@ -149,7 +224,9 @@ impl MyAnimeListModule {
             "Queueing search anime task"
         );
 
-        self.queue.enqueue(Box::new(task)).await
+        let search_id = task.id();
+        self.queue.enqueue(Box::new(task)).await?;
+        Ok(search_id)
     }
 
     /// Queue a task to update an existing anime
@@ -167,6 +244,9 @@ impl MyAnimeListModule {
         if with_jikan {
             task = task.with_jikan();
         }
+        if let Some(events) = &self.events {
+            task = task.with_events(events.clone());
+        }
 
         info!(
             module = "my_anime_list",
@@ -177,7 +257,9 @@ impl MyAnimeListModule {
         self.queue.enqueue(Box::new(task)).await
     }
 
-    /// Queue a batch fetch task
+    /// Queue a batch fetch.
+    /// Plain fetches run as one `BatchFetchTask` whose payload lists the outcome of every anime,
+    /// fetches with pictures queue one task per anime.
     pub async fn queue_batch_fetch(
         &self, 
         anime_ids: Vec<u32>, 
@@ -194,7 +276,27 @@ impl MyAnimeListModule {
             "Queueing batch fetch"
         );
 
-        // Queue individual fetch tasks for each anime
+        // Without follow-up tasks, one batch task records the outcome of every anime
+        if !with_pictures && !full_fetch {
+            let api_key = self.config.get_api_key("my_anime_list")
+                .expect("API key should be validated during module creation");
+            let mut task = BatchFetchTask::new(
+                anime_ids,
+                api_key,
+                self.mal_client.clone(),
+                self.jikan_client.clone(),
+            );
+            if with_jikan {
+                task = task.with_jikan();
+            }
+            if let Some(events) = &self.events {
+                task = task.with_events(events.clone());
+            }
+            task = task.with_fallback(self.fallback.clone());
+            return self.queue.enqueue(Box::new(task)).await;
+        }
+
+        // Queue individual fetch tasks for each anime, their failures are reported by the job
         for anime_id in anime_ids {
             self.queue_fetch_anime_with_options(
                 anime_id,
@@ -219,12 +321,46 @@ impl MyAnimeListModule {
         self.queue.enqueue(Box::new(task)).await
     }
 
-    pub async fn queue_fetch_episodes(&self, anime_id: u32) -> Result<(), AppError> {
-        let task = FetchEpisodesTask::new(anime_id, self.jikan_client.clone());
-        info!(module = "my_anime_list", anime_id = anime_id, "Queueing fetch episodes task");
+    /// `incremental` only fetches the episodes after the stored ones when the anime is airing
+    pub async fn queue_fetch_episodes(&self, anime_id: u32, incremental: bool) -> Result<(), AppError> {
+        let task = FetchEpisodesTask::new(anime_id, self.jikan_client.clone()).with_incremental(incremental);
+        info!(module = "my_anime_list", anime_id = anime_id, incremental = incremental, "Queueing fetch episodes task");
         self.queue.enqueue(Box::new(task)).await
     }
 
+    /// Queue one detail fetch per episode, for the synopsis and duration
+    pub async fn queue_fetch_episode_details(&self, anime_id: u32, episodes: &[u32]) -> Result<(), AppError> {
+        info!(module = "my_anime_list", anime_id = anime_id, count = episodes.len(), "Queueing fetch episode detail tasks");
+        for &episode in episodes {
+            let task = FetchEpisodeDetailTask::new(anime_id, episode, self.jikan_client.clone());
+            self.queue.enqueue(Box::new(task)).await?;
+        }
+        Ok(())
+    }
+
+    /// Queue high priority statistics and incremental episode refreshes of airing anime
+    pub async fn queue_refresh_airing(&self, anime_ids: &[u32]) -> Result<(), AppError> {
+        info!(module = "my_anime_list", count = anime_ids.len(), "Queueing airing anime refresh");
+        for &anime_id in anime_ids {
+            let statistics = FetchStatisticsTask::new(anime_id, self.jikan_client.clone())
+                .with_priority(TaskPriority::High);
+            self.queue.enqueue(Box::new(statistics)).await?;
+
+            let episodes = FetchEpisodesTask::new(anime_id, self.jikan_client.clone())
+                .with_priority(TaskPriority::High)
+                .with_incremental(true);
+            self.queue.enqueue(Box::new(episodes)).await?;
+        }
+        Ok(())
+    }
+
+    /// Queue the fetch of one extended data section
+    pub async fn queue_fetch_section(&self, anime_id: u32, section: AnimeSection) -> Result<(), AppError> {
+        let task = crate::anime::freshness::section_task(section, anime_id, self.jikan_client.clone());
+        info!(module = "my_anime_list", anime_id = anime_id, section = section.as_str(), "Queueing fetch section task");
+        self.queue.enqueue(task).await
+    }
+
     pub async fn queue_fetch_videos(&self, anime_id: u32) -> Result<(), AppError> {
         let task = FetchVideosTask::new(anime_id, self.jikan_client.clone());
         info!(module = "my_anime_list", anime_id = anime_id, "Queueing fetch videos task");
@@ -279,7 +415,7 @@ impl MyAnimeListModule {
         
         self.queue_fetch_characters(anime_id).await?;
         self.queue_fetch_staff(anime_id).await?;
-        self.queue_fetch_episodes(anime_id).await?;
+        self.queue_fetch_episodes(anime_id, false).await?;
         self.queue_fetch_videos(anime_id).await?;
         self.queue_fetch_statistics(anime_id).await?;
         self.queue_fetch_more_info(anime_id).await?;
@@ -0,0 +1,209 @@
+use std::collections::BTreeMap;
+
+use futures::stream::StreamExt;
+use mongodb::bson::{self, doc, Bson, Document};
+use mongodb::options::IndexOptions;
+use mongodb::{Database, IndexModel};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, warn};
+
+use super::model::AnimeData;
+use crate::global::error::DatabaseError;
+
+// Collection name for the arrays moved out of oversized anime documents
+pub const COLLECTION_NAME: &str = "anime_mal_overflow";
+
+/// MongoDB rejects documents above 16MB
+const MAX_DOCUMENT_BYTES: usize = 16 * 1024 * 1024;
+
+/// Size above which an upsert is logged
+const WARN_DOCUMENT_BYTES: usize = MAX_DOCUMENT_BYTES / 2;
+
+/// Size above which the largest arrays are moved to `anime_mal_overflow`
+const OVERFLOW_DOCUMENT_BYTES: usize = 12 * 1024 * 1024;
+
+/// Size of the items stored in one overflow document
+const CHUNK_BYTES: usize = 4 * 1024 * 1024;
+
+/// Arrays that can be moved out of the anime document, they are only read with the whole anime
+const OVERFLOW_FIELDS: [&str; 4] = ["characters", "staffs", "pictures", "recommendations"];
+
+/// Part of an array moved out of an anime document
+#[derive(Debug, Serialize, Deserialize)]
+struct OverflowChunk {
+    mal_id: i32,
+    field: String,
+    /// Position of the chunk in the array
+    chunk: u32,
+    items: Vec<Bson>,
+}
+
+pub async fn initialize_collection(db: &Database) -> Result<(), DatabaseError> {
+    let collection = db.collection::<OverflowChunk>(COLLECTION_NAME);
+
+    let chunk_index = IndexModel::builder()
+        .keys(doc! { "mal_id": 1, "field": 1, "chunk": 1 })
+        .options(IndexOptions::builder().unique(true).build())
+        .build();
+
+    collection.create_indexes(vec![chunk_index]).await
+        .map_err(|e| DatabaseError::Query(format!("Failed to create overflow indexes: {}", e)))?;
+
+    debug!("Created indexes for anime_mal_overflow collection");
+    Ok(())
+}
+
+/// BSON size of a document
+fn document_size(document: &Document) -> usize {
+    bson::to_vec(document).map(|bytes| bytes.len()).unwrap_or(0)
+}
+
+/// BSON size of a value, as a field of a document
+fn value_size(value: &Bson) -> usize {
+    document_size(&doc! { "v": value.clone() })
+}
+
+/// Move the largest arrays of an anime document to `anime_mal_overflow` until it is
+/// under the overflow size, leaving them empty in the document.
+/// Returns the moved fields. Chunks of the fields kept in the document are deleted.
+pub async fn split_document(db: &Database, mal_id: i32, document: &mut Document) -> Result<Vec<String>, DatabaseError> {
+    let mut size = document_size(document);
+    if size > WARN_DOCUMENT_BYTES {
+        warn!(mal_id = mal_id, size = size, limit = MAX_DOCUMENT_BYTES, "Anime document is approaching the MongoDB size limit");
+    }
+
+    let mut moved = Vec::new();
+    if size > OVERFLOW_DOCUMENT_BYTES {
+        let mut candidates: Vec<(&str, usize)> = OVERFLOW_FIELDS.iter()
+            .filter_map(|&field| match document.get(field) {
+                Some(value @ Bson::Array(items)) if !items.is_empty() => Some((field, value_size(value))),
+                _ => None,
+            })
+            .collect();
+        candidates.sort_by_key(|&(_, size)| std::cmp::Reverse(size));
+
+        for (field, _) in candidates {
+            if size <= OVERFLOW_DOCUMENT_BYTES {
+                break;
+            }
+            let Some(Bson::Array(items)) = document.insert(field, Bson::Array(Vec::new())) else {
+                continue;
+            };
+            store_field(db, mal_id, field, items).await?;
+            moved.push(field.to_string());
+            size = document_size(document);
+        }
+
+        info!(mal_id = mal_id, moved = ?moved, size = size, "Moved oversized arrays out of the anime document");
+        if size > MAX_DOCUMENT_BYTES {
+            warn!(mal_id = mal_id, size = size, "Anime document is still above the MongoDB size limit");
+        }
+    }
+
+    db.collection::<OverflowChunk>(COLLECTION_NAME)
+        .delete_many(doc! { "mal_id": mal_id, "field": { "$nin": &moved } })
+        .await
+        .map_err(|e| DatabaseError::Query(format!("Failed to delete overflow chunks: {}", e)))?;
+
+    Ok(moved)
+}
+
+/// Replace the stored chunks of a field with `items`, split by size
+async fn store_field(db: &Database, mal_id: i32, field: &str, items: Vec<Bson>) -> Result<(), DatabaseError> {
+    let collection = db.collection::<OverflowChunk>(COLLECTION_NAME);
+
+    let mut chunks = Vec::new();
+    let mut current = Vec::new();
+    let mut current_size = 0;
+    for item in items {
+        let item_size = value_size(&item);
+        if !current.is_empty() && current_size + item_size > CHUNK_BYTES {
+            chunks.push(std::mem::take(&mut current));
+            current_size = 0;
+        }
+        current_size += item_size;
+        current.push(item);
+    }
+    chunks.push(current);
+
+    let chunks: Vec<OverflowChunk> = chunks.into_iter()
+        .enumerate()
+        .map(|(chunk, items)| OverflowChunk { mal_id, field: field.to_string(), chunk: chunk as u32, items })
+        .collect();
+
+    collection.delete_many(doc! { "mal_id": mal_id, "field": field }).await
+        .map_err(|e| DatabaseError::Query(format!("Failed to delete overflow chunks: {}", e)))?;
+    collection.insert_many(&chunks).await
+        .map_err(|e| DatabaseError::Query(format!("Failed to insert overflow chunks: {}", e)))?;
+
+    debug!(mal_id = mal_id, field = field, chunks = chunks.len(), "Stored overflowed array");
+    Ok(())
+}
+
+/// Items of the moved fields of an anime, in order
+async fn load_fields(db: &Database, mal_id: i32, fields: &[String]) -> Result<BTreeMap<String, Vec<Bson>>, DatabaseError> {
+    let mut cursor = db.collection::<OverflowChunk>(COLLECTION_NAME)
+        .find(doc! { "mal_id": mal_id, "field": { "$in": fields } })
+        .sort(doc! { "field": 1, "chunk": 1 })
+        .await
+        .map_err(|e| DatabaseError::Query(format!("Failed to get overflow chunks: {}", e)))?;
+
+    let mut loaded: BTreeMap<String, Vec<Bson>> = BTreeMap::new();
+    while let Some(result) = cursor.next().await {
+        let chunk = result
+            .map_err(|e| DatabaseError::Query(format!("Failed to read overflow chunk: {}", e)))?;
+        loaded.entry(chunk.field).or_default().extend(chunk.items);
+    }
+    Ok(loaded)
+}
+
+/// Put the moved arrays back into an anime read from the database
+pub async fn restore_anime(db: &Database, anime: &mut AnimeData) -> Result<(), DatabaseError> {
+    if anime.overflow_fields.is_empty() {
+        return Ok(());
+    }
+
+    for (field, items) in load_fields(db, anime.mal_id, &anime.overflow_fields).await? {
+        let items = Bson::Array(items);
+        let restored = match field.as_str() {
+            "characters" => bson::from_bson(items).map(|items| anime.characters = items),
+            "staffs" => bson::from_bson(items).map(|items| anime.staffs = items),
+            "pictures" => bson::from_bson(items).map(|items| anime.pictures = items),
+            "recommendations" => bson::from_bson(items).map(|items| anime.recommendations = items),
+            _ => Ok(()),
+        };
+        restored.map_err(|e| DatabaseError::Query(format!("Failed to read overflowed {}: {}", field, e)))?;
+    }
+    Ok(())
+}
+
+/// Put back the moved arrays of a projected anime document, those left out of the projection stay out
+pub async fn restore_document(db: &Database, mal_id: i32, document: &mut Document) -> Result<(), DatabaseError> {
+    let fields: Vec<String> = match document.get_array("overflow_fields") {
+        Ok(fields) => fields.iter()
+            .filter_map(|field| field.as_str())
+            .filter(|field| document.contains_key(field))
+            .map(str::to_string)
+            .collect(),
+        Err(_) => return Ok(()),
+    };
+    if fields.is_empty() {
+        return Ok(());
+    }
+
+    for (field, items) in load_fields(db, mal_id, &fields).await? {
+        document.insert(field, Bson::Array(items));
+    }
+    // Restored, they are not to be restored again when read as an anime
+    document.remove("overflow_fields");
+    Ok(())
+}
+
+/// Delete the moved arrays of a removed anime
+pub async fn delete_anime_chunks(db: &Database, mal_id: i32) -> Result<(), DatabaseError> {
+    db.collection::<OverflowChunk>(COLLECTION_NAME)
+        .delete_many(doc! { "mal_id": mal_id })
+        .await
+        .map_err(|e| DatabaseError::Query(format!("Failed to delete overflow chunks: {}", e)))?;
+    Ok(())
+}
@@ -0,0 +1,104 @@
+use serde::Deserialize;
+use tracing::{debug, info};
+
+use crate::global::{
+    error::AppError,
+    http::{ClientWithLimiter, RequestConfig},
+};
+use super::model::Season;
+
+/// Maximum page size accepted by the MAL seasonal endpoint
+const SEASON_PAGE_SIZE: u32 = 500;
+
+/// Maximum page size accepted by the MAL ranking endpoint
+const RANKING_PAGE_SIZE: u32 = 500;
+
+#[derive(Deserialize)]
+struct SeasonResponse {
+    data: Vec<SeasonEntry>,
+    #[serde(default)]
+    paging: SeasonPaging,
+}
+
+#[derive(Deserialize)]
+struct SeasonEntry {
+    node: SeasonNode,
+}
+
+#[derive(Deserialize)]
+struct SeasonNode {
+    id: u32,
+}
+
+#[derive(Deserialize, Default)]
+struct SeasonPaging {
+    next: Option<String>,
+}
+
+/// List the MAL ids of all anime of a season, following pagination
+pub async fn fetch_season_anime_ids(
+    client: &ClientWithLimiter,
+    api_key: &str,
+    year: u32,
+    season: &Season,
+) -> Result<Vec<u32>, AppError> {
+    let url = format!(
+        "{}/anime/season/{}/{}?limit={}&fields=id",
+        client.base_url,
+        year,
+        season.as_str(),
+        SEASON_PAGE_SIZE
+    );
+    let ids = fetch_anime_ids(client, api_key, url, None).await?;
+
+    info!(year = year, season = season.as_str(), count = ids.len(), "Listed season anime");
+    Ok(ids)
+}
+
+/// List the MAL ids of the `limit` best ranked anime
+pub async fn fetch_top_anime_ids(
+    client: &ClientWithLimiter,
+    api_key: &str,
+    limit: usize,
+) -> Result<Vec<u32>, AppError> {
+    let url = format!(
+        "{}/anime/ranking?ranking_type=all&limit={}&fields=id",
+        client.base_url,
+        RANKING_PAGE_SIZE.min(limit.max(1) as u32)
+    );
+    let ids = fetch_anime_ids(client, api_key, url, Some(limit)).await?;
+
+    info!(count = ids.len(), "Listed top anime");
+    Ok(ids)
+}
+
+/// Follow the pagination of an anime list, stopping once `limit` ids were listed
+async fn fetch_anime_ids(
+    client: &ClientWithLimiter,
+    api_key: &str,
+    url: String,
+    limit: Option<usize>,
+) -> Result<Vec<u32>, AppError> {
+    let mut url = Some(url);
+    let mut ids = Vec::new();
+
+    while let Some(page_url) = url {
+        let config = RequestConfig::new().with_header("X-MAL-CLIENT-ID", api_key);
+        let response = client
+            .fetch_json::<SeasonResponse>(&page_url, Some(config))
+            .await?;
+
+        debug!(url = %page_url, count = response.data.len(), "Fetched anime list page");
+
+        ids.extend(response.data.into_iter().map(|entry| entry.node.id));
+        if let Some(limit) = limit
+            && ids.len() >= limit
+        {
+            ids.truncate(limit);
+            break;
+        }
+        url = response.paging.next;
+    }
+
+    Ok(ids)
+}
@@ -1,16 +1,53 @@
-use std::sync::Arc;
+use std::sync::Mutex;
 use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
 use tracing::{info, warn};
 
+use crate::anime::fallback::ProviderFallback;
 use crate::global::{
     database::DatabaseInstance,
-    error::AppError,
-    queue::{Task, TaskPriority, TaskData, TaskStatus},
+    error::{AppError, HttpError},
+    events::EventBus,
+    job::{self, JobFailure},
+    queue::{self as task_queue, Task, TaskContext, TaskPriority, TaskData},
 };
 
+/// MyAnimeList responses fetched ahead of the anime being enriched and stored
+const PIPELINE_DEPTH: usize = 2;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BatchFetchPayload {
     pub anime_ids: Vec<u32>,
+    /// Set once the batch ran
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<BatchFetchSummary>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub results: Vec<BatchItemResult>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BatchFetchSummary {
+    pub total: usize,
+    pub fetched: usize,
+    /// Anime that don't exist on MyAnimeList (404)
+    pub not_found: usize,
+    pub failed: usize,
+}
+
+/// Outcome of one anime of a batch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchItemResult {
+    pub anime_id: u32,
+    #[serde(flatten)]
+    pub outcome: BatchItemOutcome,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "outcome", rename_all = "snake_case")]
+pub enum BatchItemOutcome {
+    Fetched,
+    NotFound,
+    Failed { error: String },
 }
 
 pub struct BatchFetchTask {
@@ -22,6 +59,10 @@ pub struct BatchFetchTask {
     created_at: chrono::DateTime<chrono::Utc>,
     /// Whether to also fetch Jikan data for enrichment
     fetch_jikan: bool,
+    events: Option<EventBus>,
+    fallback: Option<ProviderFallback>,
+    /// Per-anime outcomes, persisted in the task payload
+    results: Mutex<Vec<BatchItemResult>>,
 }
 
 impl BatchFetchTask {
@@ -40,6 +81,9 @@ impl BatchFetchTask {
             jikan_client,
             created_at: chrono::Utc::now(),
             fetch_jikan: false,
+            events: None,
+            fallback: None,
+            results: Mutex::new(Vec::new()),
         }
     }
 
@@ -48,6 +92,80 @@ impl BatchFetchTask {
         self.fetch_jikan = true;
         self
     }
+
+    /// Publish an event for every stored anime
+    pub fn with_events(mut self, events: EventBus) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    /// Try other providers for anime MyAnimeList doesn't serve
+    pub fn with_fallback(mut self, fallback: ProviderFallback) -> Self {
+        self.fallback = Some(fallback);
+        self
+    }
+
+    /// Fetch task of one anime of the batch
+    fn fetch_task(&self, anime_id: u32) -> super::fetch_anime::FetchAnimeTask {
+        let mut fetch_task = super::fetch_anime::FetchAnimeTask::new(
+            anime_id,
+            self.api_key.clone(),
+            self.mal_client.clone(),
+            self.jikan_client.clone(),
+        );
+
+        if self.fetch_jikan {
+            fetch_task = fetch_task.with_jikan();
+        }
+        if let Some(events) = &self.events {
+            fetch_task = fetch_task.with_events(events.clone());
+        }
+        if let Some(fallback) = &self.fallback {
+            fetch_task = fetch_task.with_fallback(fallback.clone());
+        }
+        fetch_task
+    }
+
+    fn summary(results: &[BatchItemResult]) -> BatchFetchSummary {
+        let mut summary = BatchFetchSummary {
+            total: results.len(),
+            ..Default::default()
+        };
+        for result in results {
+            match result.outcome {
+                BatchItemOutcome::Fetched => summary.fetched += 1,
+                BatchItemOutcome::NotFound => summary.not_found += 1,
+                BatchItemOutcome::Failed { .. } => summary.failed += 1,
+            }
+        }
+        summary
+    }
+
+    /// Add the anime that could not be fetched to the failures of the job
+    async fn record_job_failures(&self, db: &DatabaseInstance, results: &[BatchItemResult]) {
+        let Some(job_id) = task_queue::current_job_id() else { return };
+
+        let now = chrono::Utc::now();
+        let failures: Vec<JobFailure> = results.iter()
+            .filter_map(|result| {
+                let error = match &result.outcome {
+                    BatchItemOutcome::Fetched => return None,
+                    BatchItemOutcome::NotFound => "not found on MyAnimeList".to_string(),
+                    BatchItemOutcome::Failed { error } => error.clone(),
+                };
+                Some(JobFailure {
+                    task_id: self.id.clone(),
+                    task_name: self.name().to_string(),
+                    error: format!("anime {}: {}", result.anime_id, error),
+                    failed_at: now,
+                })
+            })
+            .collect();
+
+        if let Err(e) = job::record_failures(db, &job_id, failures).await {
+            warn!(task = %self.name(), job_id = %job_id, error = %e, "Failed to record batch failures in job");
+        }
+    }
 }
 
 #[async_trait::async_trait]
@@ -65,21 +183,30 @@ impl Task for BatchFetchTask {
     }
 
     fn to_data(&self) -> TaskData {
+        let results = self.results.lock().unwrap().clone();
         let payload = BatchFetchPayload {
             anime_ids: self.anime_ids.clone(),
+            summary: (!results.is_empty()).then(|| Self::summary(&results)),
+            results,
         };
 
-        TaskData {
-            id: self.id.clone(),
-            name: self.name().to_string(),
-            priority: self.priority(),
-            status: TaskStatus::Pending,
-            created_at: self.created_at,
-            payload: serde_json::to_value(payload).unwrap(),
-        }
+        TaskData::new(
+            self.id.clone(),
+            self.name().to_string(),
+            self.priority(),
+            self.created_at,
+            serde_json::to_value(payload).unwrap(),
+        )
     }
 
-    async fn execute(&self, db: Arc<DatabaseInstance>, _client: reqwest::Client) -> Result<(), AppError> {
+    fn provider(&self) -> Option<&crate::global::http::ClientWithLimiter> {
+        Some(&self.mal_client)
+    }
+
+    /// Fetch every anime of the batch. Fails only when no anime could be fetched,
+    /// partial failures are reported in the payload and the job.
+    async fn execute(&self, ctx: &TaskContext) -> Result<(), AppError> {
+        let db = ctx.db.clone();
         info!(
             task = %self.name(),
             count = self.anime_ids.len(),
@@ -87,57 +214,92 @@ impl Task for BatchFetchTask {
             "Batch fetching anime from MyAnimeList"
         );
 
-        let mut successful = 0;
-        let mut failed = 0;
-
-        for anime_id in &self.anime_ids {
-            // Create individual fetch task
-            let mut fetch_task = super::fetch_anime::FetchAnimeTask::new(
-                *anime_id,
-                self.api_key.clone(),
-                self.mal_client.clone(),
-                self.jikan_client.clone(),
-            );
+        // Two stages: MyAnimeList responses are fetched ahead while the previous anime
+        // is enriched from Jikan and stored, each stage waits on its own rate limiter
+        let (tx, mut rx) = mpsc::channel(PIPELINE_DEPTH);
 
-            if self.fetch_jikan {
-                fetch_task = fetch_task.with_jikan();
-            }
-
-            match fetch_task.execute(db.clone(), _client.clone()).await {
-                Ok(_) => {
-                    successful += 1;
-                    info!(
-                        task = %self.name(),
-                        anime_id = anime_id,
-                        progress = format!("{}/{}", successful + failed, self.anime_ids.len()),
-                        "Anime fetched successfully"
-                    );
+        let fetch_mal = async move {
+            for anime_id in &self.anime_ids {
+                if ctx.cancel.is_cancelled() {
+                    break;
                 }
-                Err(e) => {
-                    warn!(
-                        task = %self.name(),
-                        anime_id = anime_id,
-                        error = %e,
-                        "Failed to fetch anime in batch"
-                    );
-                    failed += 1;
+                let fetch_task = self.fetch_task(*anime_id);
+                let mal_result = fetch_task.fetch_mal_data().await;
+                if tx.send((*anime_id, fetch_task, mal_result)).await.is_err() {
+                    break;
                 }
             }
+        };
 
-            // Small delay between fetches to be nice to the API
-            if self.anime_ids.len() > 1 {
-                tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
+        let complete = async {
+            let mut results = Vec::with_capacity(self.anime_ids.len());
+            while let Some((anime_id, fetch_task, mal_result)) = rx.recv().await {
+                // Without a MyAnimeList response, Jikan is only asked by the fallback
+                let jikan_result = if self.fetch_jikan && mal_result.is_ok() {
+                    Some(fetch_task.fetch_jikan_data(anime_id).await)
+                } else {
+                    None
+                };
+
+                let outcome = match fetch_task.complete(db.clone(), mal_result, jikan_result).await {
+                    Ok(_) => {
+                        info!(
+                            task = %self.name(),
+                            anime_id = anime_id,
+                            progress = format!("{}/{}", results.len() + 1, self.anime_ids.len()),
+                            "Anime fetched successfully"
+                        );
+                        BatchItemOutcome::Fetched
+                    }
+                    Err(AppError::Http(HttpError::NotFound(_))) => {
+                        warn!(task = %self.name(), anime_id = anime_id, "Anime not found on MyAnimeList");
+                        BatchItemOutcome::NotFound
+                    }
+                    Err(e) => {
+                        warn!(
+                            task = %self.name(),
+                            anime_id = anime_id,
+                            error = %e,
+                            "Failed to fetch anime in batch"
+                        );
+                        BatchItemOutcome::Failed { error: e.to_string() }
+                    }
+                };
+                results.push(BatchItemResult { anime_id, outcome });
             }
-        }
+            results
+        };
 
+        let ((), results) = tokio::join!(fetch_mal, complete);
+
+        let summary = Self::summary(&results);
         info!(
             task = %self.name(),
-            total = self.anime_ids.len(),
-            successful = successful,
-            failed = failed,
+            total = summary.total,
+            fetched = summary.fetched,
+            not_found = summary.not_found,
+            failed = summary.failed,
             "Batch fetch completed"
         );
 
+        if summary.fetched < summary.total {
+            self.record_job_failures(&db, &results).await;
+        }
+        *self.results.lock().unwrap() = results;
+        ctx.check_canceled()?;
+
+        if summary.total > 0 && summary.fetched == 0 {
+            // Only a batch of unknown ids is final
+            return Err(AppError::Provider {
+                provider: "my_anime_list".to_string(),
+                message: format!(
+                    "No anime of the batch could be fetched ({} not found, {} failed)",
+                    summary.not_found, summary.failed
+                ),
+                retryable: summary.failed > 0,
+            });
+        }
+
         Ok(())
     }
-}
\ No newline at end of file
+}
@@ -2,17 +2,19 @@ use std::sync::Arc;
 use serde::{Deserialize, Serialize};
 use tracing::{info, debug, warn};
 
-use crate::global::queue::{TaskData, TaskPriority, TaskStatus};
+use crate::global::queue::{TaskData, TaskPriority};
 use crate::global::{
     database::DatabaseInstance,
     error::AppError,
-    queue::Task,
+    queue::{Task, TaskContext},
     http::RequestConfig,
+    events::{AnimeSource, DataEvent, EventBus},
 };
+use crate::anime::fallback::{is_permanent_error, FallbackProvider, ProviderFallback};
 use crate::anime::my_anime_list::{
     model::{AnimeData, MalAnimeResponse, JikanAnimeResponse},
     database::upsert_anime,
-    converter::{mal_to_anime_data, merge_jikan_data},
+    converter::{jikan_to_anime_data, mal_to_anime_data, merge_jikan_data},
 };
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -39,6 +41,10 @@ pub struct FetchAnimeTask {
     full_fetch: bool,
     /// Optional picture module reference
     picture_module: Option<Arc<crate::picture::PictureFetcherModule>>,
+    /// Where to publish the stored anime event
+    events: Option<EventBus>,
+    /// Providers tried when MyAnimeList has no entry for this id
+    fallback: Option<ProviderFallback>,
 }
 
 impl FetchAnimeTask {
@@ -60,6 +66,8 @@ impl FetchAnimeTask {
             with_pictures: false,
             full_fetch: false,
             picture_module: None,
+            events: None,
+            fallback: None,
         }
     }
 
@@ -84,6 +92,20 @@ impl FetchAnimeTask {
         self.picture_module = Some(picture_module);
         self
     }
+
+    /// Publish an event once the anime is stored
+    pub fn with_events(mut self, events: EventBus) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    /// Try other providers when MyAnimeList returns 404 for the anime
+    pub fn with_fallback(mut self, fallback: ProviderFallback) -> Self {
+        if !fallback.is_empty() {
+            self.fallback = Some(fallback);
+        }
+        self
+    }
 }
 
 #[async_trait::async_trait]
@@ -108,17 +130,17 @@ impl Task for FetchAnimeTask {
             full_fetch: self.full_fetch,
         };
 
-        TaskData {
-            id: self.id(),
-            name: self.name().to_string(),
-            priority: self.priority(),
-            status: TaskStatus::Pending,
-            created_at: self.created_at,
-            payload: serde_json::json!(payload),
-        }
+        TaskData::new(
+            self.id(),
+            self.name().to_string(),
+            self.priority(),
+            self.created_at,
+            serde_json::json!(payload),
+        )
     }
 
-    async fn execute(&self, db: Arc<DatabaseInstance>, _client: reqwest::Client) -> Result<(), AppError> {
+    async fn execute(&self, ctx: &TaskContext) -> Result<(), AppError> {
+        let db = ctx.db.clone();
         info!(
             task = %self.name(),
             anime_id = self.anime_id,
@@ -128,19 +150,60 @@ impl Task for FetchAnimeTask {
             "Fetching anime from MyAnimeList API"
         );
 
-        // Step 1: Fetch from MyAnimeList API
+        // Step 1: Fetch from MyAnimeList API, and from Jikan at the same time for enrichment,
+        // each request waits on its own provider's rate limiter
+        let jikan_request = async {
+            if self.with_jikan {
+                Some(self.fetch_jikan_data(self.anime_id).await)
+            } else {
+                None
+            }
+        };
+        let (mal_result, jikan_result) = tokio::join!(self.fetch_mal_data(), jikan_request);
+
+        self.complete(db, mal_result, jikan_result).await
+    }
+}
+
+impl FetchAnimeTask {
+    /// Fetch the anime from the MyAnimeList API
+    pub(super) async fn fetch_mal_data(&self) -> Result<MalAnimeResponse, AppError> {
         let mal_url = format!(
-            "https://api.myanimelist.net/v2/anime/{}?fields=id,title,main_picture,alternative_titles,start_date,end_date,synopsis,mean,rank,popularity,num_list_users,num_scoring_users,nsfw,genres,created_at,updated_at,media_type,status,num_episodes,start_season,broadcast,source,average_episode_duration,rating,studios,pictures,background,related_anime,related_manga,statistics",
+            "{}/anime/{}?fields=id,title,main_picture,alternative_titles,start_date,end_date,synopsis,mean,rank,popularity,num_list_users,num_scoring_users,nsfw,genres,created_at,updated_at,media_type,status,num_episodes,start_season,broadcast,source,average_episode_duration,rating,studios,pictures,background,related_anime,related_manga,statistics",
+            self.mal_client.base_url,
             self.anime_id
         );
 
         let config = RequestConfig::new().with_header("X-MAL-CLIENT-ID", &self.api_key);
 
         debug!(task = %self.name(), url = %mal_url, "Fetching from MAL API");
-        let mal_response = self.mal_client
+        let response = self.mal_client
             .fetch_json::<MalAnimeResponse>(&mal_url, Some(config))
             .await?;
 
+        Ok(response)
+    }
+
+    /// Store the anime from its MyAnimeList response, merged with the Jikan response when
+    /// one was fetched. A MyAnimeList 404 goes through the fallback providers.
+    pub(super) async fn complete(
+        &self,
+        db: Arc<DatabaseInstance>,
+        mal_result: Result<MalAnimeResponse, AppError>,
+        jikan_result: Option<Result<JikanAnimeResponse, AppError>>,
+    ) -> Result<(), AppError> {
+        let mal_response = match mal_result {
+            Ok(response) => response,
+            Err(error) => {
+                return match &self.fallback {
+                    Some(fallback) if is_permanent_error(&error) => {
+                        self.execute_fallback(db, fallback, error, jikan_result).await
+                    }
+                    _ => Err(error),
+                };
+            }
+        };
+
         info!(
             task = %self.name(),
             anime_id = mal_response.id,
@@ -154,9 +217,9 @@ impl Task for FetchAnimeTask {
             Some(format!("https://myanimelist.net/anime/{}", self.anime_id))
         );
 
-        // Step 2: Optionally fetch from Jikan API for enrichment
-        if self.with_jikan {
-            match self.fetch_jikan_data(self.anime_id).await {
+        // Step 2: Merge the Jikan data fetched for enrichment
+        if let Some(jikan_result) = jikan_result {
+            match jikan_result {
                 Ok(jikan_response) => {
                     info!(
                         task = %self.name(),
@@ -176,6 +239,11 @@ impl Task for FetchAnimeTask {
             }
         }
 
+        self.store(db, anime_data).await
+    }
+
+    /// Store the anime, then queue the follow-up tasks and publish the stored event
+    async fn store(&self, db: Arc<DatabaseInstance>, anime_data: AnimeData) -> Result<(), AppError> {
         // Step 3: Store in database
         debug!(task = %self.name(), anime_id = anime_data.mal_id, "Storing anime in database");
         upsert_anime(db.db(), &anime_data).await?;
@@ -231,15 +299,86 @@ impl Task for FetchAnimeTask {
                 );
             }
         }
+
+        if let Some(events) = &self.events {
+            events.publish(DataEvent::AnimeStored {
+                source: AnimeSource::MyAnimeList,
+                id: self.anime_id,
+                pictures_queued: self.with_pictures && self.picture_module.is_some(),
+            });
+        }
         
         Ok(())
     }
-}
 
-impl FetchAnimeTask {
+    /// Try the fallback providers in order, returns the MAL error when none has the anime.
+    /// `jikan_result` is the Jikan response already fetched for enrichment, if any
+    async fn execute_fallback(
+        &self,
+        db: Arc<DatabaseInstance>,
+        fallback: &ProviderFallback,
+        mal_error: AppError,
+        mut jikan_result: Option<Result<JikanAnimeResponse, AppError>>,
+    ) -> Result<(), AppError> {
+        warn!(
+            task = %self.name(),
+            anime_id = self.anime_id,
+            error = %mal_error,
+            providers = ?fallback.providers(),
+            "MyAnimeList has no usable entry, trying fallback providers"
+        );
+
+        for provider in fallback.providers() {
+            let result = match provider {
+                FallbackProvider::Jikan => {
+                    let jikan_response = match jikan_result.take() {
+                        Some(result) => result,
+                        None => self.fetch_jikan_data(self.anime_id).await,
+                    };
+                    match jikan_response {
+                        Ok(jikan_response) => self.store(db.clone(), jikan_to_anime_data(jikan_response.data)).await,
+                        Err(e) => Err(e),
+                    }
+                }
+                FallbackProvider::Anilist => {
+                    let Some(client) = fallback.anilist_client() else {
+                        debug!(task = %self.name(), anime_id = self.anime_id, "AniList fallback not available");
+                        continue;
+                    };
+                    let mut task = crate::anime::anilist::task::FetchAnimeTask::by_mal_id(self.anime_id, client.clone());
+                    if let Some(events) = &self.events {
+                        task = task.with_events(events.clone());
+                    }
+                    task.execute(&TaskContext::new(db.clone(), client.client.clone())).await
+                }
+            };
+
+            match result {
+                Ok(()) => {
+                    info!(
+                        task = %self.name(),
+                        anime_id = self.anime_id,
+                        provider = ?provider,
+                        "Anime sourced from fallback provider"
+                    );
+                    return Ok(());
+                }
+                Err(e) => warn!(
+                    task = %self.name(),
+                    anime_id = self.anime_id,
+                    provider = ?provider,
+                    error = %e,
+                    "Fallback provider failed"
+                ),
+            }
+        }
+
+        Err(mal_error)
+    }
+
     /// Fetch anime data from Jikan API (no authentication required)
-    async fn fetch_jikan_data(&self, mal_id: u32) -> Result<JikanAnimeResponse, AppError> {
-        let jikan_url = format!("https://api.jikan.moe/v4/anime/{}/full", mal_id);
+    pub(super) async fn fetch_jikan_data(&self, mal_id: u32) -> Result<JikanAnimeResponse, AppError> {
+        let jikan_url = format!("{}/anime/{}/full", self.jikan_client.base_url, mal_id);
         
         debug!(
             task = %self.name(),
@@ -303,4 +442,84 @@ impl FetchAnimeTask {
         
         Ok(())
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, ResponseTemplate};
+
+    use super::*;
+    use crate::anime::my_anime_list::{database::get_anime_by_id, model::DataProvider};
+    use crate::global::config::HttpMode;
+    use crate::testing::{Providers, TestDatabase, JIKAN_PATH, MY_ANIME_LIST_PATH};
+
+    fn task(providers: &Providers) -> FetchAnimeTask {
+        FetchAnimeTask::new(
+            1,
+            "key".to_string(),
+            providers.http.my_anime_list().clone(),
+            providers.http.jikan().clone(),
+        )
+        .with_jikan()
+    }
+
+    #[tokio::test]
+    #[ignore = "needs a Docker daemon for the MongoDB container"]
+    async fn stores_the_anime_fetched_from_my_anime_list_and_jikan() {
+        let providers = Providers::start(HttpMode::Live).await;
+        let database = TestDatabase::start().await;
+        Mock::given(method("GET"))
+            .and(path(format!("{}/anime/1", MY_ANIME_LIST_PATH)))
+            .and(header("X-MAL-CLIENT-ID", "key"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "id": 1, "title": "Cowboy Bebop" })))
+            .expect(1)
+            .mount(&providers.server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(format!("{}/anime/1/full", JIKAN_PATH)))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "data": { "mal_id": 1 } })))
+            .expect(1)
+            .mount(&providers.server)
+            .await;
+
+        task(&providers)
+            .execute(&TaskContext::new(database.db.clone(), reqwest::Client::new()))
+            .await
+            .unwrap();
+
+        let anime = get_anime_by_id(database.db.db(), 1).await.unwrap().expect("anime not stored");
+        assert_eq!(anime.provider, DataProvider::MyAnimeList);
+        assert!(anime.titles.iter().any(|title| title.title == "Cowboy Bebop"));
+    }
+
+    #[tokio::test]
+    #[ignore = "needs a Docker daemon for the MongoDB container"]
+    async fn stores_the_jikan_anime_when_my_anime_list_has_none() {
+        let providers = Providers::start(HttpMode::Live).await;
+        let database = TestDatabase::start().await;
+        Mock::given(method("GET"))
+            .and(path(format!("{}/anime/1", MY_ANIME_LIST_PATH)))
+            .respond_with(ResponseTemplate::new(404))
+            .mount(&providers.server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(format!("{}/anime/1/full", JIKAN_PATH)))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "data": { "mal_id": 1, "titles": [{ "type": "Default", "title": "Cowboy Bebop" }] }
+            })))
+            .expect(1)
+            .mount(&providers.server)
+            .await;
+
+        task(&providers)
+            .with_fallback(ProviderFallback::new(vec![FallbackProvider::Jikan]))
+            .execute(&TaskContext::new(database.db.clone(), reqwest::Client::new()))
+            .await
+            .unwrap();
+
+        let anime = get_anime_by_id(database.db.db(), 1).await.unwrap().expect("anime not stored");
+        assert_eq!(anime.provider, DataProvider::Jikan);
+        assert!(anime.titles.iter().any(|title| title.title == "Cowboy Bebop"));
+    }
+}
@@ -1,18 +1,18 @@
-use std::sync::Arc;
 use serde::Deserialize;
 use tracing::{debug, info, warn};
 
 use crate::anime::my_anime_list::database::get_anime_by_id;
-use crate::global::queue::{TaskData, TaskPriority, TaskStatus};
+use crate::global::queue::{TaskData, TaskPriority};
 use crate::global::{
     database::DatabaseInstance,
     error::AppError,
-    queue::Task,
+    queue::{Task, TaskContext},
 };
 use crate::anime::my_anime_list::{
     model::*,
     database::update_anime_extended_data,
 };
+use crate::anime::my_anime_list::database::set_section_fetched;
 
 // ========================================================================
 // Fetch Characters Task (Jikan)
@@ -90,24 +90,28 @@ impl Task for FetchCharactersTask {
     }
 
     fn to_data(&self) -> TaskData {
-        TaskData {
-            id: self.id(),
-            name: self.name().to_string(),
-            priority: self.priority(),
-            status: TaskStatus::Pending,
-            created_at: self.created_at,
-            payload: serde_json::json!({ "anime_id": self.anime_id }),
-        }
+        TaskData::new(
+            self.id(),
+            self.name().to_string(),
+            self.priority(),
+            self.created_at,
+            serde_json::json!({ "anime_id": self.anime_id }),
+        )
+    }
+
+    fn provider(&self) -> Option<&crate::global::http::ClientWithLimiter> {
+        Some(&self.jikan_client)
     }
 
-    async fn execute(&self, db: Arc<DatabaseInstance>, _client: reqwest::Client) -> Result<(), AppError> {
+    async fn execute(&self, ctx: &TaskContext) -> Result<(), AppError> {
+        let db = ctx.db.clone();
         info!(
             task = %self.name(),
             anime_id = self.anime_id,
             "Fetching characters from Jikan API"
         );
 
-        let url = format!("https://api.jikan.moe/v4/anime/{}/characters", self.anime_id);
+        let url = format!("{}/anime/{}/characters", self.jikan_client.base_url, self.anime_id);
         
         // Respect Jikan rate limit
         // tokio::time::sleep(tokio::time::Duration::from_millis(400)).await;
@@ -149,6 +153,7 @@ impl Task for FetchCharactersTask {
         if let Some(mut anime) = get_anime_by_id(db.db(), self.anime_id as i32).await? {
             anime.characters = characters;
             crate::anime::my_anime_list::database::upsert_anime(db.db(), &anime).await?;
+            set_section_fetched(db.db(), self.anime_id as i32, AnimeSection::Characters).await?;
         } else {
             warn!(
                 task = %self.name(),
@@ -213,24 +218,28 @@ impl Task for FetchStaffTask {
     }
 
     fn to_data(&self) -> TaskData {
-        TaskData {
-            id: self.id(),
-            name: self.name().to_string(),
-            priority: self.priority(),
-            status: TaskStatus::Pending,
-            created_at: self.created_at,
-            payload: serde_json::json!({ "anime_id": self.anime_id }),
-        }
+        TaskData::new(
+            self.id(),
+            self.name().to_string(),
+            self.priority(),
+            self.created_at,
+            serde_json::json!({ "anime_id": self.anime_id }),
+        )
+    }
+
+    fn provider(&self) -> Option<&crate::global::http::ClientWithLimiter> {
+        Some(&self.jikan_client)
     }
 
-    async fn execute(&self, db: Arc<DatabaseInstance>, _client: reqwest::Client) -> Result<(), AppError> {
+    async fn execute(&self, ctx: &TaskContext) -> Result<(), AppError> {
+        let db = ctx.db.clone();
         info!(
             task = %self.name(),
             anime_id = self.anime_id,
             "Fetching staff from Jikan API"
         );
 
-        let url = format!("https://api.jikan.moe/v4/anime/{}/staff", self.anime_id);
+        let url = format!("{}/anime/{}/staff", self.jikan_client.base_url, self.anime_id);
         
         tokio::time::sleep(tokio::time::Duration::from_millis(400)).await;
 
@@ -261,6 +270,7 @@ impl Task for FetchStaffTask {
         if let Some(mut anime) = get_anime_by_id(db.db(), self.anime_id as i32).await? {
             anime.staffs = staff;
             crate::anime::my_anime_list::database::upsert_anime(db.db(), &anime).await?;
+            set_section_fetched(db.db(), self.anime_id as i32, AnimeSection::Staff).await?;
         } else {
             warn!(
                 task = %self.name(),
@@ -277,11 +287,16 @@ impl Task for FetchStaffTask {
 // Fetch Episodes Task (Jikan)
 // ========================================================================
 
+/// Episodes per page of the Jikan episode list
+const JIKAN_EPISODES_PER_PAGE: usize = 100;
+
 pub struct FetchEpisodesTask {
     id: String,
     anime_id: u32,
     jikan_client: crate::global::http::ClientWithLimiter,
     created_at: chrono::DateTime<chrono::Utc>,
+    priority: TaskPriority,
+    incremental: bool,
 }
 
 #[derive(Debug, Deserialize)]
@@ -322,50 +337,36 @@ impl FetchEpisodesTask {
             anime_id,
             jikan_client,
             created_at: chrono::Utc::now(),
+            priority: TaskPriority::Low,
+            incremental: false,
         }
     }
-}
-
-#[async_trait::async_trait]
-impl Task for FetchEpisodesTask {
-    fn id(&self) -> String {
-        self.id.clone()
-    }
 
-    fn name(&self) -> &str {
-        "fetch_episodes"
+    /// Run before the regular extended data fetches, for watched anime
+    pub fn with_priority(mut self, priority: TaskPriority) -> Self {
+        self.priority = priority;
+        self
     }
 
-    fn priority(&self) -> TaskPriority {
-        TaskPriority::Low
-    }
-
-    fn to_data(&self) -> TaskData {
-        TaskData {
-            id: self.id(),
-            name: self.name().to_string(),
-            priority: self.priority(),
-            status: TaskStatus::Pending,
-            created_at: self.created_at,
-            payload: serde_json::json!({ "anime_id": self.anime_id }),
-        }
+    /// For airing anime with stored episodes, only fetch from the page of the last
+    /// stored episode on and keep the earlier episodes as stored
+    pub fn with_incremental(mut self, incremental: bool) -> Self {
+        self.incremental = incremental;
+        self
     }
 
-    async fn execute(&self, db: Arc<DatabaseInstance>, _client: reqwest::Client) -> Result<(), AppError> {
-        info!(
-            task = %self.name(),
-            anime_id = self.anime_id,
-            "Fetching episodes from Jikan API (paginated)"
-        );
-
+    /// Fetch the episode list from `first_page` to the last page.
+    /// Returns the episodes and the number of pages fetched, fails once the task is canceled.
+    async fn fetch_pages(&self, ctx: &TaskContext, first_page: usize) -> Result<(Vec<JikanEpisode>, usize), AppError> {
         let mut all_episodes = Vec::new();
-        let mut page = 1;
+        let mut page = first_page;
         let mut has_next_page = true;
 
-        // Fetch all pages
         while has_next_page {
+            ctx.check_canceled()?;
             let url = format!(
-                "https://api.jikan.moe/v4/anime/{}/episodes?page={}",
+                "{}/anime/{}/episodes?page={}",
+                self.jikan_client.base_url,
                 self.anime_id, page
             );
             
@@ -415,8 +416,101 @@ impl Task for FetchEpisodesTask {
             }
         }
 
+        Ok((all_episodes, page + 1 - first_page))
+    }
+
+    /// First page to fetch in incremental mode, `None` when the whole list is needed
+    async fn incremental_first_page(&self, db: &DatabaseInstance, anime: &AnimeData) -> Result<Option<usize>, AppError> {
+        if !self.incremental || !anime.airing {
+            return Ok(None);
+        }
+
+        let stored = crate::anime::episodes::get_episodes(
+            db.db(),
+            self.anime_id as i32,
+            crate::anime::episodes::EpisodeFilter::default(),
+        ).await?;
+        let Some(highest) = stored.iter().map(|episode| episode.episode).max() else {
+            return Ok(None);
+        };
+
+        // Pages are filled in list order, the last stored episode is on the page of its position
+        let first_page = (stored.len() - 1) / JIKAN_EPISODES_PER_PAGE + 1;
+        debug!(
+            task = %self.name(),
+            anime_id = self.anime_id,
+            stored = stored.len(),
+            highest_episode = highest,
+            first_page = first_page,
+            "Refreshing episodes incrementally"
+        );
+        Ok(Some(first_page))
+    }
+}
+
+#[async_trait::async_trait]
+impl Task for FetchEpisodesTask {
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+
+    fn name(&self) -> &str {
+        "fetch_episodes"
+    }
+
+    fn priority(&self) -> TaskPriority {
+        self.priority
+    }
+
+    fn to_data(&self) -> TaskData {
+        TaskData::new(
+            self.id(),
+            self.name().to_string(),
+            self.priority(),
+            self.created_at,
+            serde_json::json!({ "anime_id": self.anime_id, "incremental": self.incremental }),
+        )
+    }
+
+    fn provider(&self) -> Option<&crate::global::http::ClientWithLimiter> {
+        Some(&self.jikan_client)
+    }
+
+    async fn execute(&self, ctx: &TaskContext) -> Result<(), AppError> {
+        let db = ctx.db.clone();
+        info!(
+            task = %self.name(),
+            anime_id = self.anime_id,
+            incremental = self.incremental,
+            "Fetching episodes from Jikan API (paginated)"
+        );
+
+        let Some(anime) = get_anime_by_id(db.db(), self.anime_id as i32).await? else {
+            warn!(
+                task = %self.name(),
+                anime_id = self.anime_id,
+                "Anime not found in database, cannot update episodes"
+            );
+            return Ok(());
+        };
+
+        let first_page = self.incremental_first_page(&db, &anime).await?;
+        let (mut fetched, mut pages) = self.fetch_pages(ctx, first_page.unwrap_or(1)).await?;
+
+        // Fewer episodes listed than stored (e.g. some were merged), start over from the first page
+        let mut incremental = first_page.is_some();
+        if incremental && fetched.is_empty() {
+            warn!(
+                task = %self.name(),
+                anime_id = self.anime_id,
+                "Incremental episode page is empty, fetching the whole list"
+            );
+            (fetched, pages) = self.fetch_pages(ctx, 1).await?;
+            incremental = false;
+        }
+
         // Convert to our model
-        let episodes: Vec<Episode> = all_episodes.into_iter().map(|e| {
+        let episodes: Vec<Episode> = fetched.into_iter().map(|e| {
             Episode {
                 mal_id: e.mal_id,
                 url: e.url,
@@ -436,22 +530,160 @@ impl Task for FetchEpisodesTask {
             task = %self.name(),
             anime_id = self.anime_id,
             total_episodes = episodes.len(),
-            total_pages = page,
-            "Fetched all episodes, updating anime"
+            total_pages = pages,
+            incremental = incremental,
+            "Fetched episodes, storing them"
         );
 
-        // Get existing anime and update it
-        if let Some(mut anime) = get_anime_by_id(db.db(), self.anime_id as i32).await? {
-            anime.episodes = episodes;
-            crate::anime::my_anime_list::database::upsert_anime(db.db(), &anime).await?;
+        if incremental {
+            crate::anime::episodes::merge_episodes(db.db(), self.anime_id as i32, episodes).await?;
         } else {
+            crate::anime::episodes::replace_episodes(db.db(), self.anime_id as i32, episodes).await?;
+        }
+        set_section_fetched(db.db(), self.anime_id as i32, AnimeSection::Episodes).await?;
+
+        Ok(())
+    }
+}
+
+// ========================================================================
+// Fetch Episode Detail Task (Jikan)
+// ========================================================================
+
+/// Fetches one episode from the detail endpoint, which has the synopsis and
+/// duration missing from the episode list
+pub struct FetchEpisodeDetailTask {
+    id: String,
+    anime_id: u32,
+    episode: u32,
+    jikan_client: crate::global::http::ClientWithLimiter,
+    created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JikanEpisodeDetailResponse {
+    data: JikanEpisodeDetail,
+}
+
+#[derive(Debug, Deserialize)]
+struct JikanEpisodeDetail {
+    mal_id: i32,
+    url: Option<String>,
+    title: String,
+    title_japanese: Option<String>,
+    title_romanji: Option<String>,
+    duration: Option<i32>,
+    aired: Option<String>,
+    #[serde(default)]
+    filler: bool,
+    #[serde(default)]
+    recap: bool,
+    synopsis: Option<String>,
+}
+
+impl FetchEpisodeDetailTask {
+    pub fn new(
+        anime_id: u32,
+        episode: u32,
+        jikan_client: crate::global::http::ClientWithLimiter,
+    ) -> Self {
+        let id = format!("fetch_episode_detail_{}_{}", anime_id, episode);
+        Self {
+            id,
+            anime_id,
+            episode,
+            jikan_client,
+            created_at: chrono::Utc::now(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Task for FetchEpisodeDetailTask {
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+
+    fn name(&self) -> &str {
+        "fetch_episode_detail"
+    }
+
+    fn priority(&self) -> TaskPriority {
+        TaskPriority::Low
+    }
+
+    fn to_data(&self) -> TaskData {
+        TaskData::new(
+            self.id(),
+            self.name().to_string(),
+            self.priority(),
+            self.created_at,
+            serde_json::json!({ "anime_id": self.anime_id, "episode": self.episode }),
+        )
+    }
+
+    fn provider(&self) -> Option<&crate::global::http::ClientWithLimiter> {
+        Some(&self.jikan_client)
+    }
+
+    async fn execute(&self, ctx: &TaskContext) -> Result<(), AppError> {
+        let db = ctx.db.clone();
+        info!(
+            task = %self.name(),
+            anime_id = self.anime_id,
+            episode = self.episode,
+            "Fetching episode detail from Jikan API"
+        );
+
+        if get_anime_by_id(db.db(), self.anime_id as i32).await?.is_none() {
             warn!(
                 task = %self.name(),
                 anime_id = self.anime_id,
-                "Anime not found in database, cannot update episodes"
+                "Anime not found in database, cannot update episode"
             );
+            return Ok(());
         }
 
+        let url = format!(
+            "{}/anime/{}/episodes/{}",
+            self.jikan_client.base_url,
+            self.anime_id, self.episode
+        );
+
+        let detail = self.jikan_client
+            .fetch_json::<JikanEpisodeDetailResponse>(&url, None)
+            .await?
+            .data;
+
+        let stored = crate::anime::episodes::get_episode(db.db(), self.anime_id as i32, detail.mal_id).await?;
+
+        // The detail endpoint has no score nor forum link, keep the listed ones
+        let mut episode = crate::anime::episodes::AnimeEpisode::new(self.anime_id as i32, Episode {
+            mal_id: detail.mal_id,
+            url: detail.url,
+            title: detail.title,
+            title_japanese: detail.title_japanese,
+            title_romanji: detail.title_romanji,
+            duration: detail.duration,
+            aired: detail.aired.as_ref().and_then(|s| parse_jikan_date(s)),
+            score: stored.as_ref().and_then(|stored| stored.score),
+            filler: detail.filler,
+            recap: detail.recap,
+            forum_url: stored.and_then(|stored| stored.forum_url),
+        });
+        episode.synopsis = detail.synopsis;
+        episode.detail_updated_at = Some(chrono::Utc::now());
+
+        crate::anime::episodes::save_episode_detail(db.db(), &episode).await?;
+
+        debug!(
+            task = %self.name(),
+            anime_id = self.anime_id,
+            episode = self.episode,
+            has_synopsis = episode.synopsis.is_some(),
+            "Episode detail stored"
+        );
+
         Ok(())
     }
 }
@@ -548,24 +780,28 @@ impl Task for FetchVideosTask {
     }
 
     fn to_data(&self) -> TaskData {
-        TaskData {
-            id: self.id(),
-            name: self.name().to_string(),
-            priority: self.priority(),
-            status: TaskStatus::Pending,
-            created_at: self.created_at,
-            payload: serde_json::json!({ "anime_id": self.anime_id }),
-        }
+        TaskData::new(
+            self.id(),
+            self.name().to_string(),
+            self.priority(),
+            self.created_at,
+            serde_json::json!({ "anime_id": self.anime_id }),
+        )
+    }
+
+    fn provider(&self) -> Option<&crate::global::http::ClientWithLimiter> {
+        Some(&self.jikan_client)
     }
 
-    async fn execute(&self, db: Arc<DatabaseInstance>, _client: reqwest::Client) -> Result<(), AppError> {
+    async fn execute(&self, ctx: &TaskContext) -> Result<(), AppError> {
+        let db = ctx.db.clone();
         info!(
             task = %self.name(),
             anime_id = self.anime_id,
             "Fetching videos from Jikan API"
         );
 
-        let url = format!("https://api.jikan.moe/v4/anime/{}/videos", self.anime_id);
+        let url = format!("{}/anime/{}/videos", self.jikan_client.base_url, self.anime_id);
         
         tokio::time::sleep(tokio::time::Duration::from_millis(400)).await;
 
@@ -618,6 +854,7 @@ impl Task for FetchVideosTask {
         if let Some(mut anime) = get_anime_by_id(db.db(), self.anime_id as i32).await? {
             anime.videos = Some(videos);
             crate::anime::my_anime_list::database::upsert_anime(db.db(), &anime).await?;
+            set_section_fetched(db.db(), self.anime_id as i32, AnimeSection::Videos).await?;
         } else {
             warn!(
                 task = %self.name(),
@@ -639,6 +876,7 @@ pub struct FetchStatisticsTask {
     anime_id: u32,
     jikan_client: crate::global::http::ClientWithLimiter,
     created_at: chrono::DateTime<chrono::Utc>,
+    priority: TaskPriority,
 }
 
 #[derive(Debug, Deserialize)]
@@ -676,8 +914,15 @@ impl FetchStatisticsTask {
             anime_id,
             jikan_client,
             created_at: chrono::Utc::now(),
+            priority: TaskPriority::Low,
         }
     }
+
+    /// Run before the regular extended data fetches, for watched anime
+    pub fn with_priority(mut self, priority: TaskPriority) -> Self {
+        self.priority = priority;
+        self
+    }
 }
 
 #[async_trait::async_trait]
@@ -691,28 +936,32 @@ impl Task for FetchStatisticsTask {
     }
 
     fn priority(&self) -> TaskPriority {
-        TaskPriority::Low
+        self.priority
     }
 
     fn to_data(&self) -> TaskData {
-        TaskData {
-            id: self.id(),
-            name: self.name().to_string(),
-            priority: self.priority(),
-            status: TaskStatus::Pending,
-            created_at: self.created_at,
-            payload: serde_json::json!({ "anime_id": self.anime_id }),
-        }
+        TaskData::new(
+            self.id(),
+            self.name().to_string(),
+            self.priority(),
+            self.created_at,
+            serde_json::json!({ "anime_id": self.anime_id }),
+        )
     }
 
-    async fn execute(&self, db: Arc<DatabaseInstance>, _client: reqwest::Client) -> Result<(), AppError> {
+    fn provider(&self) -> Option<&crate::global::http::ClientWithLimiter> {
+        Some(&self.jikan_client)
+    }
+
+    async fn execute(&self, ctx: &TaskContext) -> Result<(), AppError> {
+        let db = ctx.db.clone();
         info!(
             task = %self.name(),
             anime_id = self.anime_id,
             "Fetching statistics from Jikan API"
         );
 
-        let url = format!("https://api.jikan.moe/v4/anime/{}/statistics", self.anime_id);
+        let url = format!("{}/anime/{}/statistics", self.jikan_client.base_url, self.anime_id);
         
         tokio::time::sleep(tokio::time::Duration::from_millis(400)).await;
 
@@ -744,6 +993,7 @@ impl Task for FetchStatisticsTask {
         if let Some(mut anime) = get_anime_by_id(db.db(), self.anime_id as i32).await? {
             anime.statistics = Some(statistics);
             crate::anime::my_anime_list::database::upsert_anime(db.db(), &anime).await?;
+            set_section_fetched(db.db(), self.anime_id as i32, AnimeSection::Statistics).await?;
         } else {
             warn!(
                 task = %self.name(),
@@ -807,24 +1057,28 @@ impl Task for FetchMoreInfoTask {
     }
 
     fn to_data(&self) -> TaskData {
-        TaskData {
-            id: self.id(),
-            name: self.name().to_string(),
-            priority: self.priority(),
-            status: TaskStatus::Pending,
-            created_at: self.created_at,
-            payload: serde_json::json!({ "anime_id": self.anime_id }),
-        }
+        TaskData::new(
+            self.id(),
+            self.name().to_string(),
+            self.priority(),
+            self.created_at,
+            serde_json::json!({ "anime_id": self.anime_id }),
+        )
     }
 
-    async fn execute(&self, db: Arc<DatabaseInstance>, _client: reqwest::Client) -> Result<(), AppError> {
+    fn provider(&self) -> Option<&crate::global::http::ClientWithLimiter> {
+        Some(&self.jikan_client)
+    }
+
+    async fn execute(&self, ctx: &TaskContext) -> Result<(), AppError> {
+        let db = ctx.db.clone();
         info!(
             task = %self.name(),
             anime_id = self.anime_id,
             "Fetching more info from Jikan API"
         );
 
-        let url = format!("https://api.jikan.moe/v4/anime/{}/moreinfo", self.anime_id);
+        let url = format!("{}/anime/{}/moreinfo", self.jikan_client.base_url, self.anime_id);
         
         tokio::time::sleep(tokio::time::Duration::from_millis(400)).await;
 
@@ -844,6 +1098,7 @@ impl Task for FetchMoreInfoTask {
                 if let Some(mut anime) = get_anime_by_id(db.db(), self.anime_id as i32).await? {
                     anime.more_info = Some(more_info);
                     crate::anime::my_anime_list::database::upsert_anime(db.db(), &anime).await?;
+                    set_section_fetched(db.db(), self.anime_id as i32, AnimeSection::MoreInfo).await?;
                 } else {
                     warn!(
                         task = %self.name(),
@@ -919,24 +1174,28 @@ impl Task for FetchRecommendationsTask {
     }
 
     fn to_data(&self) -> TaskData {
-        TaskData {
-            id: self.id(),
-            name: self.name().to_string(),
-            priority: self.priority(),
-            status: TaskStatus::Pending,
-            created_at: self.created_at,
-            payload: serde_json::json!({ "anime_id": self.anime_id }),
-        }
+        TaskData::new(
+            self.id(),
+            self.name().to_string(),
+            self.priority(),
+            self.created_at,
+            serde_json::json!({ "anime_id": self.anime_id }),
+        )
     }
 
-    async fn execute(&self, db: Arc<DatabaseInstance>, _client: reqwest::Client) -> Result<(), AppError> {
+    fn provider(&self) -> Option<&crate::global::http::ClientWithLimiter> {
+        Some(&self.jikan_client)
+    }
+
+    async fn execute(&self, ctx: &TaskContext) -> Result<(), AppError> {
+        let db = ctx.db.clone();
         info!(
             task = %self.name(),
             anime_id = self.anime_id,
             "Fetching recommendations from Jikan API"
         );
 
-        let url = format!("https://api.jikan.moe/v4/anime/{}/recommendations", self.anime_id);
+        let url = format!("{}/anime/{}/recommendations", self.jikan_client.base_url, self.anime_id);
         
         tokio::time::sleep(tokio::time::Duration::from_millis(400)).await;
 
@@ -968,6 +1227,7 @@ impl Task for FetchRecommendationsTask {
         if let Some(mut anime) = get_anime_by_id(db.db(), self.anime_id as i32).await? {
             anime.recommendations = recommendations;
             crate::anime::my_anime_list::database::upsert_anime(db.db(), &anime).await?;
+            set_section_fetched(db.db(), self.anime_id as i32, AnimeSection::Recommendations).await?;
         } else {
             warn!(
                 task = %self.name(),
@@ -989,6 +1249,7 @@ pub struct FetchPicturesTask {
     anime_id: u32,
     jikan_client: crate::global::http::ClientWithLimiter,
     created_at: chrono::DateTime<chrono::Utc>,
+    priority: TaskPriority,
 }
 
 #[derive(Debug, Deserialize)]
@@ -1013,8 +1274,15 @@ impl FetchPicturesTask {
             anime_id,
             jikan_client,
             created_at: chrono::Utc::now(),
+            priority: TaskPriority::Low,
         }
     }
+
+    /// Run before the regular extended data fetches, for watched anime
+    pub fn with_priority(mut self, priority: TaskPriority) -> Self {
+        self.priority = priority;
+        self
+    }
 }
 
 #[async_trait::async_trait]
@@ -1028,28 +1296,32 @@ impl Task for FetchPicturesTask {
     }
 
     fn priority(&self) -> TaskPriority {
-        TaskPriority::Low
+        self.priority
     }
 
     fn to_data(&self) -> TaskData {
-        TaskData {
-            id: self.id(),
-            name: self.name().to_string(),
-            priority: self.priority(),
-            status: TaskStatus::Pending,
-            created_at: self.created_at,
-            payload: serde_json::json!({ "anime_id": self.anime_id }),
-        }
+        TaskData::new(
+            self.id(),
+            self.name().to_string(),
+            self.priority(),
+            self.created_at,
+            serde_json::json!({ "anime_id": self.anime_id }),
+        )
+    }
+
+    fn provider(&self) -> Option<&crate::global::http::ClientWithLimiter> {
+        Some(&self.jikan_client)
     }
 
-    async fn execute(&self, db: Arc<DatabaseInstance>, _client: reqwest::Client) -> Result<(), AppError> {
+    async fn execute(&self, ctx: &TaskContext) -> Result<(), AppError> {
+        let db = ctx.db.clone();
         info!(
             task = %self.name(),
             anime_id = self.anime_id,
             "Fetching pictures from Jikan API"
         );
 
-        let url = format!("https://api.jikan.moe/v4/anime/{}/pictures", self.anime_id);
+        let url = format!("{}/anime/{}/pictures", self.jikan_client.base_url, self.anime_id);
         
         tokio::time::sleep(tokio::time::Duration::from_millis(400)).await;
 
@@ -1079,6 +1351,7 @@ impl Task for FetchPicturesTask {
                 anime.images = first_picture.clone();
             }
             crate::anime::my_anime_list::database::upsert_anime(db.db(), &anime).await?;
+            set_section_fetched(db.db(), self.anime_id as i32, AnimeSection::Pictures).await?;
         } else {
             warn!(
                 task = %self.name(),
@@ -3,12 +3,12 @@ use serde::{Deserialize, Serialize};
 use tracing::{info, debug, warn};
 
 use crate::global::{
-    database::DatabaseInstance,
     error::AppError,
-    queue::{Task, TaskPriority, TaskData, TaskStatus},
+    queue::{Task, TaskContext, TaskPriority, TaskData},
 };
-use crate::anime::my_anime_list::{database::get_anime_by_id, model::Image};
-use crate::picture::PictureFetcherModule;
+use crate::anime::image_variants;
+use crate::anime::my_anime_list::{database::get_anime_by_id, model::Images};
+use crate::picture::{batch::PictureRequest, PictureFetcherModule};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FetchAnimePicturesPayload {
@@ -37,90 +37,50 @@ impl FetchAnimePicturesTask {
         }
     }
     
-    /// Queue an image download if URL is not empty
-    async fn queue_image(
+    /// Queue the variants of an image kept by the picture policy,
+    /// `sub_category` gives the tag of each format ("jpg" or "webp")
+    fn queue_image(
         &self,
-        image: &Image,
+        requests: &mut Vec<PictureRequest>,
+        images: &Images,
         entity_id: i32,
         category: &str,
         entity_type: &str,
-        sub_category: Option<&str>,
-    ) -> Result<(), AppError> {
-        let mut tags = vec![
-            entity_type.to_string(),
-            entity_id.to_string(),
-            category.to_string(),
-        ];
-        
-        if let Some(sub) = sub_category {
-            tags.push(sub.to_string());
-        }
-        
-        // Queue JPG version
-        if !image.image_url.is_empty() {
-            debug!(
-                anime_id = self.anime_id,
-                category = category,
-                entity_type = entity_type,
-                entity_id = entity_id,
-                url = %image.image_url,
-                "Queueing JPG image"
-            );
-            
-            self.picture_module.queue_fetch_picture_for_entity(
-                image.image_url.clone(),
-                None,
-                entity_type.to_string(),
-                entity_id.to_string(),
-                tags.clone(),
-            ).await?;
-        }
-        
-        // Queue large JPG version if different
-        if !image.large_image_url.is_empty() && image.large_image_url != image.image_url {
-            tags.push("large".to_string());
-            
-            debug!(
-                anime_id = self.anime_id,
-                category = category,
-                url = %image.large_image_url,
-                "Queueing large JPG image"
-            );
-            
-            self.picture_module.queue_fetch_picture_for_entity(
-                image.large_image_url.clone(),
-                None,
-                entity_type.to_string(),
-                entity_id.to_string(),
-                tags.clone(),
-            ).await?;
-        }
-        
-        // Queue small JPG version if different
-        if !image.small_image_url.is_empty() 
-            && image.small_image_url != image.image_url 
-            && image.small_image_url != image.large_image_url {
-            
-            let mut small_tags = tags.clone();
-            small_tags.push("small".to_string());
-            
-            debug!(
-                anime_id = self.anime_id,
-                category = category,
-                url = %image.small_image_url,
-                "Queueing small JPG image"
-            );
-            
-            self.picture_module.queue_fetch_picture_for_entity(
-                image.small_image_url.clone(),
-                None,
+        sub_category: impl Fn(&str) -> String,
+    ) {
+        let policy = self.picture_module.variant_policy();
+
+        for (format, image) in image_variants::formats(policy, images) {
+            let tags = vec![
                 entity_type.to_string(),
                 entity_id.to_string(),
-                small_tags,
-            ).await?;
+                category.to_string(),
+                sub_category(format),
+            ];
+
+            for (url, size) in image_variants::sizes(policy, image) {
+                let mut tags = tags.clone();
+                tags.extend(size.map(str::to_string));
+
+                debug!(
+                    anime_id = self.anime_id,
+                    category = category,
+                    entity_type = entity_type,
+                    entity_id = entity_id,
+                    format = format,
+                    size = size.unwrap_or("regular"),
+                    url = %url,
+                    "Queueing image"
+                );
+
+                requests.push(PictureRequest::new(
+                    url.to_string(),
+                    entity_type.to_string(),
+                    entity_id.to_string(),
+                    tags,
+                ));
+            }
         }
-        
-        Ok(())
     }
 }
 
@@ -143,21 +103,17 @@ impl Task for FetchAnimePicturesTask {
             anime_id: self.anime_id,
         };
 
-        TaskData {
-            id: self.id.clone(),
-            name: self.name().to_string(),
-            priority: self.priority(),
-            status: TaskStatus::Pending,
-            created_at: self.created_at,
-            payload: serde_json::json!(payload),
-        }
+        TaskData::new(
+            self.id.clone(),
+            self.name().to_string(),
+            self.priority(),
+            self.created_at,
+            serde_json::json!(payload),
+        )
     }
 
-    async fn execute(
-        &self,
-        db: Arc<DatabaseInstance>,
-        _client: reqwest::Client,
-    ) -> Result<(), AppError> {
+    async fn execute(&self, ctx: &TaskContext) -> Result<(), AppError> {
+        let db = ctx.db.clone();
         info!(
             task = %self.name(),
             anime_id = self.anime_id,
@@ -177,18 +133,12 @@ impl Task for FetchAnimePicturesTask {
             }
         };
 
-        let mut total_queued = 0;
+        let mut requests = Vec::new();
 
         // 1. Main images (JPG and WebP)
         debug!(anime_id = self.anime_id, "Queueing main anime images");
         
-        // JPG images
-        self.queue_image(&anime.images.jpg, self.anime_id as i32, "main", "anime", Some("jpg")).await?;
-        total_queued += 1;
-        
-        // WebP images
-        self.queue_image(&anime.images.webp, self.anime_id as i32, "main", "anime", Some("webp")).await?;
-        total_queued += 1;
+        self.queue_image(&mut requests, &anime.images, self.anime_id as i32, "main", "anime", str::to_string);
 
         // 2. Additional pictures
         info!(
@@ -198,11 +148,7 @@ impl Task for FetchAnimePicturesTask {
         );
         
         for (idx, picture) in anime.pictures.iter().enumerate() {
-            // JPG
-            self.queue_image(&picture.jpg, self.anime_id as i32, "picture", "anime", Some(&format!("jpg_{}", idx))).await?;
-            // WebP
-            self.queue_image(&picture.webp, self.anime_id as i32, "picture", "anime", Some(&format!("webp_{}", idx))).await?;
-            total_queued += 2;
+            self.queue_image(&mut requests, picture, self.anime_id as i32, "picture", "anime", |format| format!("{}_{}", format, idx));
         }
 
         // 3. Character images
@@ -215,46 +161,27 @@ impl Task for FetchAnimePicturesTask {
         for character in &anime.characters {
             let character_tags_suffix = format!("character_{}", character.character.mal_id);
             
-            // Character image (JPG and WebP)
-            self.queue_image(
-                &character.character.images.jpg,
-                character.character.mal_id,
-                "character",
-                "character",
-                Some(&format!("{}_jpg", character_tags_suffix))
-            ).await?;
-            
             self.queue_image(
-                &character.character.images.webp,
+                &mut requests,
+                &character.character.images,
                 character.character.mal_id,
                 "character",
                 "character",
-                Some(&format!("{}_webp", character_tags_suffix))
-            ).await?;
-            
-            total_queued += 2;
+                |format| format!("{}_{}", character_tags_suffix, format),
+            );
             
             // Voice actor images
             for va in &character.voice_actors {
                 let va_tags_suffix = format!("va_{}_{}", character.character.mal_id, va.person.mal_id);
                 
                 self.queue_image(
-                    &va.person.images.jpg,
+                    &mut requests,
+                    &va.person.images,
                     va.person.mal_id,
                     "voice_actor",
                     "voice_actor",
-                    Some(&format!("{}_jpg", va_tags_suffix))
-                ).await?;
-                
-                self.queue_image(
-                    &va.person.images.webp,
-                    va.person.mal_id,
-                    "voice_actor",
-                    "voice_actor",
-                    Some(&format!("{}_webp", va_tags_suffix))
-                ).await?;
-                
-                total_queued += 2;
+                    |format| format!("{}_{}", va_tags_suffix, format),
+                );
             }
         }
 
@@ -268,24 +195,14 @@ impl Task for FetchAnimePicturesTask {
         for staff in &anime.staffs {
             let staff_tags_suffix = format!("staff_{}", staff.person.mal_id);
             
-            // Staff image (JPG and WebP)
             self.queue_image(
-                &staff.person.images.jpg,
+                &mut requests,
+                &staff.person.images,
                 staff.person.mal_id,
                 "staff",
                 "staff",
-                Some(&format!("{}_jpg", staff_tags_suffix))
-            ).await?;
-            
-            self.queue_image(
-                &staff.person.images.webp,
-                staff.person.mal_id,
-                "staff",
-                "staff",
-                Some(&format!("{}_webp", staff_tags_suffix))
-            ).await?;
-            
-            total_queued += 2;
+                |format| format!("{}_{}", staff_tags_suffix, format),
+            );
         }
 
         // 5. Video thumbnails (if videos exist)
@@ -302,94 +219,52 @@ impl Task for FetchAnimePicturesTask {
             for (idx, promo) in videos.promo.iter().enumerate() {
                 if let Some(images) = &promo.trailer.images {
                     // Tags format: ["anime", "123", "video_promo", "video_promo_jpg_0"]
-                    let tags = vec![
-                        "anime".to_string(),
-                        self.anime_id.to_string(),
-                        "video_promo".to_string(),
-                        format!("video_promo_jpg_{}", idx),  // Used to extract video ID
-                    ];
-                    
                     self.queue_image_with_custom_tags(
-                        &images.jpg,
+                        &mut requests,
+                        images,
                         self.anime_id as i32,
                         "anime",
-                        tags.clone(),
-                    ).await?;
-
-                    // WebP version
-                    let mut webp_tags = tags.clone();
-                    webp_tags[3] = format!("video_promo_webp_{}", idx);
-                    
-                    self.queue_image_with_custom_tags(
-                        &images.webp,
-                        self.anime_id as i32,
-                        "anime",
-                        webp_tags,
-                    ).await?;
-
-                    total_queued += 2;
+                        |format| vec![
+                            "anime".to_string(),
+                            self.anime_id.to_string(),
+                            "video_promo".to_string(),
+                            format!("video_promo_{}_{}", format, idx),  // Used to extract video ID
+                        ],
+                    );
                 }
             }
             
             // Episode video images - stored under anime/{id}/videos/{episode_mal_id}/
             for episode in &videos.episodes {
-                let tags = vec![
-                    "anime".to_string(),
-                    self.anime_id.to_string(),
-                    "video_episode".to_string(),
-                    format!("video_episode_jpg_{}", episode.mal_id),  // MAL ID used for directory
-                ];
-                
-                self.queue_image_with_custom_tags(
-                    &episode.images.jpg,
-                    self.anime_id as i32,
-                    "anime",
-                    tags.clone(),
-                ).await?;
-                
-                // WebP version
-                let mut webp_tags = tags.clone();
-                webp_tags[3] = format!("video_episode_webp_{}", episode.mal_id);
-                
                 self.queue_image_with_custom_tags(
-                    &episode.images.webp,
+                    &mut requests,
+                    &episode.images,
                     self.anime_id as i32,
                     "anime",
-                    webp_tags,
-                ).await?;
-                
-                total_queued += 2;
+                    |format| vec![
+                        "anime".to_string(),
+                        self.anime_id.to_string(),
+                        "video_episode".to_string(),
+                        format!("video_episode_{}_{}", format, episode.mal_id),  // MAL ID used for directory
+                    ],
+                );
             }
             
             // Music video images - stored under anime/{id}/videos/{music_index}/
             for (idx, music) in videos.music_videos.iter().enumerate() {
                 if let Some(images) = &music.video.images {
-                    let tags = vec![
-                        "anime".to_string(),
-                        self.anime_id.to_string(),
-                        "video_music".to_string(),
-                        format!("video_music_jpg_{}", idx),
-                    ];
-                    
-                    self.queue_image_with_custom_tags(
-                        &images.jpg,
-                        self.anime_id as i32,
-                        "anime",
-                        tags.clone(),
-                    ).await?;
-                    
-                    // WebP version
-                    let mut webp_tags = tags.clone();
-                    webp_tags[3] = format!("video_music_webp_{}", idx);
-                    
                     self.queue_image_with_custom_tags(
-                        &images.webp,
+                        &mut requests,
+                        images,
                         self.anime_id as i32,
                         "anime",
-                        webp_tags,
-                    ).await?;
-                    
-                    total_queued += 2;
+                        |format| vec![
+                            "anime".to_string(),
+                            self.anime_id.to_string(),
+                            "video_music".to_string(),
+                            format!("video_music_{}_{}", format, idx),
+                        ],
+                    );
                 }
             }
         }
@@ -419,10 +294,20 @@ impl Task for FetchAnimePicturesTask {
         //     total_queued += 2;
         // }
 
+        let batch = self.picture_module.queue_batch(
+            &db,
+            &self.id,
+            format!("Pictures of anime {}", self.anime_id),
+            requests,
+        ).await?;
+
         info!(
             task = %self.name(),
             anime_id = self.anime_id,
-            total_queued = total_queued,
+            job_id = %batch.job_id,
+            total_queued = batch.queued,
+            tags_merged = batch.merged,
+            resumed_from = batch.resumed_from,
             "All anime pictures queued successfully"
         );
 
@@ -431,23 +316,24 @@ impl Task for FetchAnimePicturesTask {
 }
 
 impl FetchAnimePicturesTask {
-    // Helper method to queue images with custom tags
-    async fn queue_image_with_custom_tags(
+    // Helper method to queue images with custom tags, `tags` gives the tags of each format
+    fn queue_image_with_custom_tags(
         &self,
-        image: &Image,
+        requests: &mut Vec<PictureRequest>,
+        images: &Images,
         entity_id: i32,
         entity_type: &str,
-        tags: Vec<String>,
-    ) -> Result<(), AppError> {
-        if !image.image_url.is_empty() {
-            self.picture_module.queue_fetch_picture_for_entity(
-                image.image_url.clone(),
-                None,
-                entity_type.to_string(),
-                entity_id.to_string(),
-                tags,
-            ).await?;
+        tags: impl Fn(&str) -> Vec<String>,
+    ) {
+        for (format, image) in image_variants::formats(self.picture_module.variant_policy(), images) {
+            if !image.image_url.is_empty() {
+                requests.push(PictureRequest::new(
+                    image.image_url.clone(),
+                    entity_type.to_string(),
+                    entity_id.to_string(),
+                    tags(format),
+                ));
+            }
         }
-        Ok(())
     }
 }
\ No newline at end of file
@@ -4,6 +4,8 @@ pub mod update_anime;
 pub mod batch_fetch;
 pub mod fetch_extended;
 pub mod fetch_pictures_for_anime;
+pub mod snapshot_statistics;
+pub mod sync_taxonomy;
 
 // Re-export task types
 pub use fetch_anime::FetchAnimeTask;
@@ -14,10 +16,13 @@ pub use fetch_extended::{
     FetchCharactersTask,
     FetchStaffTask,
     FetchEpisodesTask,
+    FetchEpisodeDetailTask,
     FetchVideosTask,        // NEW
     FetchStatisticsTask,    // NEW
     FetchMoreInfoTask,      // NEW
     FetchRecommendationsTask, // NEW
     FetchPicturesTask,      // NEW
 };
-pub use fetch_pictures_for_anime::FetchAnimePicturesTask;
\ No newline at end of file
+pub use fetch_pictures_for_anime::FetchAnimePicturesTask;
+pub use snapshot_statistics::SnapshotStatisticsTask;
+pub use sync_taxonomy::{SyncGenresTask, SyncProducersTask};
\ No newline at end of file
@@ -1,13 +1,12 @@
-use std::sync::Arc;
 use serde::{Deserialize, Serialize};
 use tracing::info;
 
 use crate::global::{
-    database::DatabaseInstance,
     error::AppError,
-    queue::{Task, TaskPriority, TaskData, TaskStatus},
+    queue::{Task, TaskContext, TaskPriority, TaskData},
     http::RequestConfig,
 };
+use crate::anime::my_anime_list::model::{SearchResultEntry, SearchResults};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SearchAnimePayload {
@@ -63,17 +62,17 @@ impl Task for SearchAnimeTask {
             limit: Some(self.limit),
         };
 
-        TaskData {
-            id: self.id.clone(),
-            name: self.name().to_string(),
-            priority: self.priority(),
-            status: TaskStatus::Pending,
-            created_at: self.created_at,
-            payload: serde_json::to_value(payload).unwrap(),
-        }
+        TaskData::new(
+            self.id.clone(),
+            self.name().to_string(),
+            self.priority(),
+            self.created_at,
+            serde_json::to_value(payload).unwrap(),
+        )
     }
 
-    async fn execute(&self, db: Arc<DatabaseInstance>, _client: reqwest::Client) -> Result<(), AppError> {
+    async fn execute(&self, ctx: &TaskContext) -> Result<(), AppError> {
+        let db = ctx.db.clone();
         info!(
             task = %self.name(),
             query = %self.query,
@@ -82,7 +81,8 @@ impl Task for SearchAnimeTask {
         );
 
         let url = format!(
-            "https://api.myanimelist.net/v2/anime?q={}&limit={}&fields=id,title,main_picture,alternative_titles,start_date,end_date,synopsis,mean,rank,popularity,num_list_users,num_scoring_users,nsfw,genres,created_at,updated_at,media_type,status,num_episodes,start_season,broadcast,source,average_episode_duration,rating,studios,pictures,background,related_anime,related_manga,statistics",
+            "{}/anime?q={}&limit={}&fields=id,title,main_picture,alternative_titles,start_date,end_date,synopsis,mean,rank,popularity,num_list_users,num_scoring_users,nsfw,genres,created_at,updated_at,media_type,status,num_episodes,start_season,broadcast,source,average_episode_duration,rating,studios,pictures,background,related_anime,related_manga,statistics",
+            self.client_with_limiter.base_url,
             urlencoding::encode(&self.query),
             self.limit
         );
@@ -111,14 +111,25 @@ impl Task for SearchAnimeTask {
         );
 
         // Convert and store results in database (anime_mal collection)
+        let mut entries = Vec::with_capacity(response.data.len());
         for result in response.data {
             let anime_data = crate::anime::my_anime_list::converter::mal_to_anime_data(
                 result.node,
                 None
             );
             crate::anime::my_anime_list::database::insert_anime(db.db(), &anime_data).await?;
+            entries.push(SearchResultEntry::from(&anime_data));
         }
 
+        // Keep the ranked results for GET /api/anime/search/{id}
+        let results = SearchResults {
+            search_id: self.id.clone(),
+            query: self.query.clone(),
+            results: entries,
+            completed_at: chrono::Utc::now(),
+        };
+        crate::anime::my_anime_list::database::save_search_results(db.db(), &results).await?;
+
         Ok(())
     }
 }
\ No newline at end of file
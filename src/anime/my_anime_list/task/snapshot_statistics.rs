@@ -0,0 +1,147 @@
+use serde::Deserialize;
+use tracing::{debug, info, warn};
+
+use crate::global::{
+    database::DatabaseInstance,
+    error::AppError,
+    queue::{Task, TaskContext, TaskPriority, TaskData},
+};
+use crate::anime::my_anime_list::{
+    database::{get_airing_anime, insert_statistics_snapshots},
+    model::{JikanAnimeResponse, StatisticsSnapshot},
+};
+
+/// Airing anime snapshotted when no anime are tracked explicitly
+const MAX_AIRING_SNAPSHOTS: i64 = 500;
+
+#[derive(Debug, Deserialize)]
+struct JikanStatisticsResponse {
+    data: JikanWatching,
+}
+
+#[derive(Debug, Deserialize)]
+struct JikanWatching {
+    watching: i32,
+}
+
+/// Records score, members, favorites and watching counts of tracked anime
+/// into the statistics time series. Tracks currently airing anime when
+/// `anime_ids` is empty.
+pub struct SnapshotStatisticsTask {
+    id: String,
+    anime_ids: Vec<u32>,
+    jikan_client: crate::global::http::ClientWithLimiter,
+    created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl SnapshotStatisticsTask {
+    pub fn new(anime_ids: Vec<u32>, jikan_client: crate::global::http::ClientWithLimiter) -> Self {
+        let created_at = chrono::Utc::now();
+        Self {
+            id: format!("snapshot_statistics_{}", created_at.timestamp()),
+            anime_ids,
+            jikan_client,
+            created_at,
+        }
+    }
+
+    async fn tracked_ids(&self, db: &DatabaseInstance) -> Result<Vec<u32>, AppError> {
+        if !self.anime_ids.is_empty() {
+            return Ok(self.anime_ids.clone());
+        }
+
+        let airing = get_airing_anime(db.db(), MAX_AIRING_SNAPSHOTS).await?;
+        Ok(airing.into_iter().map(|anime| anime.mal_id as u32).collect())
+    }
+
+    async fn snapshot(&self, anime_id: u32) -> Result<StatisticsSnapshot, AppError> {
+        let anime_url = format!("{}/anime/{}", self.jikan_client.base_url, anime_id);
+        let anime = self.jikan_client
+            .fetch_json::<JikanAnimeResponse>(&anime_url, None)
+            .await?;
+
+        let statistics_url = format!("{}/anime/{}/statistics", self.jikan_client.base_url, anime_id);
+        let statistics = self.jikan_client
+            .fetch_json::<JikanStatisticsResponse>(&statistics_url, None)
+            .await?;
+
+        Ok(StatisticsSnapshot {
+            mal_id: anime_id as i32,
+            score: anime.data.score,
+            members: anime.data.members,
+            favorites: anime.data.favorites,
+            watching: Some(statistics.data.watching),
+            recorded_at: chrono::Utc::now(),
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl Task for SnapshotStatisticsTask {
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+
+    fn name(&self) -> &str {
+        "snapshot_statistics"
+    }
+
+    fn priority(&self) -> TaskPriority {
+        TaskPriority::Low
+    }
+
+    fn to_data(&self) -> TaskData {
+        TaskData::new(
+            self.id(),
+            self.name().to_string(),
+            self.priority(),
+            self.created_at,
+            serde_json::json!({ "anime_ids": self.anime_ids }),
+        )
+    }
+
+    fn provider(&self) -> Option<&crate::global::http::ClientWithLimiter> {
+        Some(&self.jikan_client)
+    }
+
+    async fn execute(&self, ctx: &TaskContext) -> Result<(), AppError> {
+        let db = ctx.db.clone();
+        let anime_ids = self.tracked_ids(&db).await?;
+
+        info!(
+            task = %self.name(),
+            anime = anime_ids.len(),
+            "Recording statistics snapshots"
+        );
+
+        let mut snapshots = Vec::with_capacity(anime_ids.len());
+        let mut failed = 0;
+        for anime_id in &anime_ids {
+            match self.snapshot(*anime_id).await {
+                Ok(snapshot) => {
+                    debug!(task = %self.name(), anime_id = anime_id, "Statistics snapshot taken");
+                    snapshots.push(snapshot);
+                }
+                Err(e) => {
+                    warn!(task = %self.name(), anime_id = anime_id, error = %e, "Failed to snapshot statistics");
+                    failed += 1;
+                }
+            }
+        }
+
+        insert_statistics_snapshots(db.db(), &snapshots).await?;
+
+        info!(
+            task = %self.name(),
+            recorded = snapshots.len(),
+            failed = failed,
+            "Statistics snapshots recorded"
+        );
+
+        if snapshots.is_empty() && failed > 0 {
+            return Err(AppError::provider("jikan", format!("All {} statistics snapshots failed", failed)));
+        }
+
+        Ok(())
+    }
+}
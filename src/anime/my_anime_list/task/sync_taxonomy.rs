@@ -0,0 +1,242 @@
+use serde::Deserialize;
+use tracing::{debug, info};
+
+use crate::global::{
+    error::AppError,
+    queue::{Task, TaskContext, TaskPriority, TaskData},
+};
+use crate::anime::my_anime_list::{
+    database::{upsert_genres, upsert_producers},
+    model::{GenreCategory, JikanImages, TaxonomyGenre, TaxonomyProducer, Title},
+};
+
+// ========================================================================
+// Sync Genres Task (Jikan)
+// ========================================================================
+
+#[derive(Debug, Deserialize)]
+struct JikanGenresResponse {
+    data: Vec<JikanGenre>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JikanGenre {
+    mal_id: i32,
+    name: String,
+    url: String,
+    #[serde(default)]
+    count: i32,
+}
+
+/// Fetches every anime genre, explicit genre, theme and demographic from Jikan
+pub struct SyncGenresTask {
+    id: String,
+    jikan_client: crate::global::http::ClientWithLimiter,
+    created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl SyncGenresTask {
+    pub fn new(jikan_client: crate::global::http::ClientWithLimiter) -> Self {
+        let created_at = chrono::Utc::now();
+        Self {
+            id: format!("sync_genres_{}", created_at.timestamp()),
+            jikan_client,
+            created_at,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Task for SyncGenresTask {
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+
+    fn name(&self) -> &str {
+        "sync_genres"
+    }
+
+    fn priority(&self) -> TaskPriority {
+        TaskPriority::Low
+    }
+
+    fn to_data(&self) -> TaskData {
+        TaskData::new(
+            self.id(),
+            self.name().to_string(),
+            self.priority(),
+            self.created_at,
+            serde_json::json!({}),
+        )
+    }
+
+    fn provider(&self) -> Option<&crate::global::http::ClientWithLimiter> {
+        Some(&self.jikan_client)
+    }
+
+    async fn execute(&self, ctx: &TaskContext) -> Result<(), AppError> {
+        let db = ctx.db.clone();
+        info!(task = %self.name(), "Syncing genres from Jikan API");
+
+        let synced_at = chrono::Utc::now();
+        let mut genres = Vec::new();
+        for category in GenreCategory::ALL {
+            let url = format!("{}/genres/anime?filter={}", self.jikan_client.base_url, category.as_str());
+            let response = self.jikan_client
+                .fetch_json::<JikanGenresResponse>(&url, None)
+                .await?;
+
+            debug!(task = %self.name(), category = category.as_str(), count = response.data.len(), "Fetched genres");
+
+            genres.extend(response.data.into_iter().map(|genre| TaxonomyGenre {
+                mal_id: genre.mal_id,
+                name: genre.name,
+                url: genre.url,
+                category,
+                count: genre.count,
+                synced_at,
+            }));
+        }
+
+        upsert_genres(db.db(), &genres).await?;
+
+        info!(task = %self.name(), count = genres.len(), "Genres synced");
+        Ok(())
+    }
+}
+
+// ========================================================================
+// Sync Producers Task (Jikan)
+// ========================================================================
+
+#[derive(Debug, Deserialize)]
+struct JikanProducersResponse {
+    data: Vec<JikanProducer>,
+    pagination: JikanPagination,
+}
+
+#[derive(Debug, Deserialize)]
+struct JikanPagination {
+    has_next_page: bool,
+}
+
+#[derive(Debug, Deserialize)]
+struct JikanProducer {
+    mal_id: i32,
+    url: String,
+    #[serde(default)]
+    titles: Vec<JikanTitle>,
+    images: JikanImages,
+    #[serde(default)]
+    favorites: i32,
+    #[serde(default)]
+    count: i32,
+    established: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JikanTitle {
+    #[serde(rename = "type")]
+    title_type: String,
+    title: String,
+}
+
+/// Fetches every producer (studios and licensors included) from Jikan, page by page
+pub struct SyncProducersTask {
+    id: String,
+    jikan_client: crate::global::http::ClientWithLimiter,
+    created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl SyncProducersTask {
+    pub fn new(jikan_client: crate::global::http::ClientWithLimiter) -> Self {
+        let created_at = chrono::Utc::now();
+        Self {
+            id: format!("sync_producers_{}", created_at.timestamp()),
+            jikan_client,
+            created_at,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Task for SyncProducersTask {
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+
+    fn name(&self) -> &str {
+        "sync_producers"
+    }
+
+    fn priority(&self) -> TaskPriority {
+        TaskPriority::Low
+    }
+
+    fn to_data(&self) -> TaskData {
+        TaskData::new(
+            self.id(),
+            self.name().to_string(),
+            self.priority(),
+            self.created_at,
+            serde_json::json!({}),
+        )
+    }
+
+    fn provider(&self) -> Option<&crate::global::http::ClientWithLimiter> {
+        Some(&self.jikan_client)
+    }
+
+    async fn execute(&self, ctx: &TaskContext) -> Result<(), AppError> {
+        let db = ctx.db.clone();
+        info!(task = %self.name(), "Syncing producers from Jikan API");
+
+        let synced_at = chrono::Utc::now();
+        let mut total = 0;
+        let mut page = 1;
+        loop {
+            let url = format!("{}/producers?page={}", self.jikan_client.base_url, page);
+            let response = self.jikan_client
+                .fetch_json::<JikanProducersResponse>(&url, None)
+                .await?;
+
+            let producers: Vec<TaxonomyProducer> = response.data.into_iter().map(|producer| {
+                let name = producer.titles.iter()
+                    .find(|title| title.title_type == "Default")
+                    .or_else(|| producer.titles.first())
+                    .map(|title| title.title.clone())
+                    .unwrap_or_default();
+
+                TaxonomyProducer {
+                    mal_id: producer.mal_id,
+                    name,
+                    titles: producer.titles.into_iter().map(|title| Title {
+                        id: None,
+                        title_type: title.title_type,
+                        title: title.title,
+                    }).collect(),
+                    url: producer.url,
+                    image_url: producer.images.jpg.image_url,
+                    favorites: producer.favorites,
+                    count: producer.count,
+                    established: producer.established,
+                    synced_at,
+                }
+            }).collect();
+
+            // Stored page by page so an interrupted sync keeps what it fetched
+            upsert_producers(db.db(), &producers).await?;
+            total += producers.len();
+
+            debug!(task = %self.name(), page = page, count = producers.len(), "Fetched producer page");
+
+            if !response.pagination.has_next_page {
+                break;
+            }
+            page += 1;
+        }
+
+        info!(task = %self.name(), count = total, pages = page, "Producers synced");
+        Ok(())
+    }
+}
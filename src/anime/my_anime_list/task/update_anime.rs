@@ -1,9 +1,9 @@
-use std::sync::Arc;
 use serde::{Deserialize, Serialize};
 use tracing::{info, debug, warn};
 
-use crate::{anime::my_anime_list::{AnimeData, JikanAnimeResponse, MalAnimeResponse, database::upsert_anime, mal_to_anime_data, merge_jikan_data}, global::{
-    database::DatabaseInstance, error::AppError, http::RequestConfig, queue::{Task, TaskData, TaskPriority, TaskStatus}
+use crate::{anime::my_anime_list::{AnimeData, JikanAnimeResponse, MalAnimeResponse, converter::keep_jikan_data, database::{self, upsert_anime}, mal_to_anime_data, merge_jikan_data}, global::{
+    error::AppError, http::{Conditional, RequestConfig, Validators}, queue::{Task, TaskContext, TaskData, TaskPriority},
+    events::{AnimeSource, DataEvent, EventBus},
 }};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,6 +21,8 @@ pub struct UpdateAnimeTask {
     created_at: chrono::DateTime<chrono::Utc>,
     /// Whether to also fetch Jikan data for enrichment
     with_jikan: bool,
+    /// Where to publish the updated anime event
+    events: Option<EventBus>,
 }
 
 impl UpdateAnimeTask {
@@ -39,6 +41,7 @@ impl UpdateAnimeTask {
             jikan_client,
             created_at: chrono::Utc::now(),
             with_jikan: false,
+            events: None,
         }
     }
 
@@ -47,6 +50,11 @@ impl UpdateAnimeTask {
         self.with_jikan = true;
         self
     }
+
+    pub fn with_events(mut self, events: EventBus) -> Self {
+        self.events = Some(events);
+        self
+    }
 }
 
 #[async_trait::async_trait]
@@ -69,17 +77,17 @@ impl Task for UpdateAnimeTask {
             with_jikan: self.with_jikan,
         };
 
-        TaskData {
-            id: self.id.clone(),
-            name: self.name().to_string(),
-            priority: self.priority(),
-            status: TaskStatus::Pending,
-            created_at: self.created_at,
-            payload: serde_json::json!(payload),
-        }
+        TaskData::new(
+            self.id.clone(),
+            self.name().to_string(),
+            self.priority(),
+            self.created_at,
+            serde_json::json!(payload),
+        )
     }
 
-    async fn execute(&self, db: Arc<DatabaseInstance>, _client: reqwest::Client) -> Result<(), AppError> {
+    async fn execute(&self, ctx: &TaskContext) -> Result<(), AppError> {
+        let db = ctx.db.clone();
         info!(
             task = %self.name(),
             anime_id = self.anime_id,
@@ -87,8 +95,35 @@ impl Task for UpdateAnimeTask {
             "Updating anime from MyAnimeList"
         );
 
+        // Step 1: With Jikan, ask whether its data changed since the last update.
+        // Jikan serves a cached copy of MyAnimeList, so MAL is fetched either way.
+        let mut jikan = None;
+        let mut jikan_unchanged = false;
+        if self.with_jikan {
+            match self.fetch_jikan_data(db.db(), self.anime_id).await {
+                Ok(Conditional::NotModified) => {
+                    debug!(
+                        task = %self.name(),
+                        anime_id = self.anime_id,
+                        "Jikan data unchanged since the last update (304), keeping the stored data"
+                    );
+                    jikan_unchanged = true;
+                }
+                Ok(Conditional::Modified(response, validators)) => jikan = Some((response, validators)),
+                Err(e) => {
+                    warn!(
+                        task = %self.name(),
+                        anime_id = self.anime_id,
+                        error = %e,
+                        "Failed to fetch Jikan data, continuing with MAL data only"
+                    );
+                }
+            }
+        }
+
         let mal_url = format!(
-            "https://api.myanimelist.net/v2/anime/{}?fields=id,title,main_picture,alternative_titles,start_date,end_date,synopsis,mean,rank,popularity,num_list_users,num_scoring_users,nsfw,genres,created_at,updated_at,media_type,status,num_episodes,start_season,broadcast,source,average_episode_duration,rating,studios,pictures,background,related_anime,related_manga,statistics",
+            "{}/anime/{}?fields=id,title,main_picture,alternative_titles,start_date,end_date,synopsis,mean,rank,popularity,num_list_users,num_scoring_users,nsfw,genres,created_at,updated_at,media_type,status,num_episodes,start_season,broadcast,source,average_episode_duration,rating,studios,pictures,background,related_anime,related_manga,statistics",
+            self.mal_client.base_url,
             self.anime_id
         );
 
@@ -112,31 +147,30 @@ impl Task for UpdateAnimeTask {
             Some(format!("https://myanimelist.net/anime/{}", self.anime_id))
         );
 
-        // Step 2: Optionally fetch from Jikan API for enrichment
-        if self.with_jikan {
-            match self.fetch_jikan_data(self.anime_id).await {
-                Ok(jikan_response) => {
-                    info!(
-                        task = %self.name(),
-                        anime_id = self.anime_id,
-                        "Successfully fetched Jikan data, merging..."
-                    );
-                    anime_data = merge_jikan_data(anime_data, jikan_response.data);
-                }
-                Err(e) => {
-                    warn!(
-                        task = %self.name(),
-                        anime_id = self.anime_id,
-                        error = %e,
-                        "Failed to fetch Jikan data, continuing with MAL data only"
-                    );
-                }
-            }
+        // Step 2: Merge the Jikan data for enrichment
+        let mut validators = None;
+        if let Some((jikan_response, jikan_validators)) = jikan {
+            info!(
+                task = %self.name(),
+                anime_id = self.anime_id,
+                "Successfully fetched Jikan data, merging..."
+            );
+            anime_data = merge_jikan_data(anime_data, jikan_response.data);
+            validators = Some(jikan_validators);
+        } else if jikan_unchanged
+            && let Some(stored) = database::get_anime_by_id(db.db(), self.anime_id as i32).await?
+        {
+            anime_data = keep_jikan_data(anime_data, stored);
         }
 
         // Step 3: Store in database
         debug!(task = %self.name(), anime_id = anime_data.mal_id, "Updating anime in database");
         upsert_anime(db.db(), &anime_data).await?;
+
+        // Saved once stored, a failed update must not be skipped as unchanged next time
+        if let Some(validators) = validators.filter(|v| !v.is_empty()) {
+            database::set_response_validators(db.db(), self.anime_id as i32, &validators).await?;
+        }
         
         info!(
             task = %self.name(),
@@ -146,14 +180,22 @@ impl Task for UpdateAnimeTask {
             "Update completed successfully"
         );
 
+        if let Some(events) = &self.events {
+            events.publish(DataEvent::AnimeUpdated {
+                source: AnimeSource::MyAnimeList,
+                id: self.anime_id,
+            });
+        }
+
         Ok(())
     }
 }
 
 impl UpdateAnimeTask {
-    /// Fetch anime data from Jikan API (no authentication required)
-    async fn fetch_jikan_data(&self, mal_id: u32) -> Result<JikanAnimeResponse, AppError> {
-        let jikan_url = format!("https://api.jikan.moe/v4/anime/{}/full", mal_id);
+    /// Fetch anime data from Jikan API (no authentication required).
+    /// The request is conditional on the validators of the last update of a stored anime.
+    async fn fetch_jikan_data(&self, db: &mongodb::Database, mal_id: u32) -> Result<Conditional<JikanAnimeResponse>, AppError> {
+        let jikan_url = format!("{}/anime/{}/full", self.jikan_client.base_url, mal_id);
         
         info!(
             task = %self.name(),
@@ -161,9 +203,16 @@ impl UpdateAnimeTask {
             "Fetching from Jikan API for update"
         );
 
+        // Validators are ignored when the anime itself is missing, it must be fetched again
+        let validators = if database::anime_exists(db, mal_id as i32).await? {
+            database::get_response_validators(db, mal_id as i32).await?.unwrap_or_default()
+        } else {
+            Validators::default()
+        };
+
         // Use Jikan client with its own rate limiter (no manual delay needed)
         let response = self.jikan_client  // CHANGED: use jikan_client
-            .fetch_json::<JikanAnimeResponse>(&jikan_url, None)
+            .fetch_json_if_modified::<JikanAnimeResponse>(&jikan_url, None, &validators)
             .await?;
 
         Ok(response)
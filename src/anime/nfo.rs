@@ -0,0 +1,276 @@
+use std::fmt::Write as _;
+use std::path::Path;
+
+use chrono::Datelike;
+use futures::stream::StreamExt;
+use serde::Serialize;
+use tokio::fs;
+use tracing::{debug, info, warn};
+
+use crate::anime::my_anime_list::database::{get_all_anime_cursor, get_anime_by_id};
+use crate::anime::my_anime_list::overflow;
+use crate::anime::episodes::{self, AnimeEpisode, EpisodeFilter};
+use crate::anime::my_anime_list::model::{AnimeData, Rating, Status};
+use crate::global::database::DatabaseInstance;
+use crate::global::error::AppError;
+use crate::global::xml;
+use crate::picture::database::get_picture_metadata;
+
+/// Result of a media-library export
+#[derive(Debug, Clone, Serialize, Default)]
+pub struct NfoExportReport {
+    pub shows: u64,
+    pub episodes: u64,
+    pub artwork_copied: u64,
+    /// Artwork whose picture was not downloaded yet
+    pub artwork_missing: u64,
+    pub failed: Vec<String>,
+}
+
+/// Write Kodi/Jellyfin metadata for collected anime into `output`:
+///
+/// ```text
+/// <output>/<Title> (<year>)/tvshow.nfo
+///                          /poster.jpg, fanart.jpg
+///                          /Season 01/<Title> S01E01.nfo
+/// ```
+///
+/// Exports every stored MAL anime when `mal_ids` is empty. Artwork is copied
+/// from the picture storage, pictures that were never downloaded are skipped.
+pub async fn export_library(db: &DatabaseInstance, output: &Path, mal_ids: &[u32]) -> Result<NfoExportReport, AppError> {
+    let mut report = NfoExportReport::default();
+    fs::create_dir_all(output).await
+        .map_err(|e| AppError::Storage(format!("Failed to create {}: {}", output.display(), e)))?;
+
+    if mal_ids.is_empty() {
+        let mut cursor = get_all_anime_cursor(db.db()).await?;
+        while let Some(result) = cursor.next().await {
+            match result {
+                Ok(mut anime) => {
+                    overflow::restore_anime(db.db(), &mut anime).await?;
+                    export_show(db, output, &anime, &mut report).await
+                }
+                Err(e) => warn!(error = %e, "Failed to deserialize anime, skipping"),
+            }
+        }
+    } else {
+        for &mal_id in mal_ids {
+            match get_anime_by_id(db.db(), mal_id as i32).await? {
+                Some(anime) => export_show(db, output, &anime, &mut report).await,
+                None => report.failed.push(format!("{}: not collected", mal_id)),
+            }
+        }
+    }
+
+    info!(
+        output = %output.display(),
+        shows = report.shows,
+        episodes = report.episodes,
+        artwork_copied = report.artwork_copied,
+        artwork_missing = report.artwork_missing,
+        failed = report.failed.len(),
+        "Media library export finished"
+    );
+
+    Ok(report)
+}
+
+async fn export_show(db: &DatabaseInstance, output: &Path, anime: &AnimeData, report: &mut NfoExportReport) {
+    match write_show(db, output, anime, report).await {
+        Ok(()) => report.shows += 1,
+        Err(e) => {
+            warn!(mal_id = anime.mal_id, error = %e, "Failed to export anime");
+            report.failed.push(format!("{}: {}", anime.mal_id, e));
+        }
+    }
+}
+
+async fn write_show(db: &DatabaseInstance, output: &Path, anime: &AnimeData, report: &mut NfoExportReport) -> Result<(), AppError> {
+    let title = anime.display_title();
+    let folder = match anime.year.or_else(|| anime.aired.from.map(|date| date.year())) {
+        Some(year) => format!("{} ({})", title, year),
+        None => title.to_string(),
+    };
+    let show_dir = output.join(sanitize_path_component(&folder));
+
+    write_file(&show_dir.join("tvshow.nfo"), &tvshow_nfo(anime)).await?;
+
+    // Poster from the main picture, fanart from the first additional picture
+    let poster = preferred_url(&anime.images.jpg.large_image_url, &anime.images.jpg.image_url);
+    let fanart = anime.pictures.first()
+        .and_then(|picture| preferred_url(&picture.jpg.large_image_url, &picture.jpg.image_url));
+    for (url, name) in [(poster, "poster.jpg"), (fanart, "fanart.jpg")] {
+        let Some(url) = url else { continue };
+        if copy_artwork(db, anime.mal_id, url, &show_dir.join(name)).await? {
+            report.artwork_copied += 1;
+        } else {
+            report.artwork_missing += 1;
+        }
+    }
+
+    let episodes = episodes::get_episodes(db.db(), anime.mal_id, EpisodeFilter::default()).await?;
+    if !episodes.is_empty() {
+        let season_dir = show_dir.join("Season 01");
+        for episode in &episodes {
+            let name = sanitize_path_component(&format!("{} S01E{:02}.nfo", title, episode.episode));
+            write_file(&season_dir.join(name), &episode_nfo(episode)).await?;
+            report.episodes += 1;
+        }
+    }
+
+    debug!(mal_id = anime.mal_id, path = %show_dir.display(), "Exported anime");
+    Ok(())
+}
+
+/// Copy a downloaded picture, returns false when it is not available locally
+async fn copy_artwork(db: &DatabaseInstance, mal_id: i32, url: &str, target: &Path) -> Result<bool, AppError> {
+    let entity_id = mal_id.to_string();
+    let Some(picture) = get_picture_metadata(db.db(), url, Some(&entity_id), Some("anime")).await? else {
+        return Ok(false);
+    };
+    if !picture.is_completed() {
+        return Ok(false);
+    }
+
+    match fs::copy(&picture.file_path, target).await {
+        Ok(_) => Ok(true),
+        Err(e) => {
+            warn!(path = %picture.file_path, error = %e, "Failed to copy artwork");
+            Ok(false)
+        }
+    }
+}
+
+async fn write_file(path: &Path, content: &str) -> Result<(), AppError> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).await
+            .map_err(|e| AppError::Storage(format!("Failed to create {}: {}", parent.display(), e)))?;
+    }
+    fs::write(path, content).await
+        .map_err(|e| AppError::Storage(format!("Failed to write {}: {}", path.display(), e)))
+}
+
+fn tvshow_nfo(anime: &AnimeData) -> String {
+    let mut nfo = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n<tvshow>\n");
+
+    element(&mut nfo, "title", anime.display_title());
+    if let Some(original) = anime.title_of_type("Japanese") {
+        element(&mut nfo, "originaltitle", original);
+    }
+    if let Some(english) = anime.title_of_type("English") {
+        element(&mut nfo, "sorttitle", english);
+    }
+    element(&mut nfo, "plot", &anime.synopsis);
+
+    if let Some(score) = anime.score {
+        let _ = write!(
+            nfo,
+            "  <ratings>\n    <rating name=\"myanimelist\" max=\"10\" default=\"true\">\n      <value>{}</value>\n      <votes>{}</votes>\n    </rating>\n  </ratings>\n",
+            score, anime.scored_by
+        );
+    }
+    if let Some(year) = anime.year {
+        element(&mut nfo, "year", &year.to_string());
+    }
+    if let Some(from) = anime.aired.from {
+        element(&mut nfo, "premiered", &from.format("%Y-%m-%d").to_string());
+    }
+    if let Some(status) = &anime.status {
+        let status = match status {
+            Status::CurrentlyAiring | Status::NotYetAired => "Continuing",
+            Status::FinishedAiring => "Ended",
+            Status::Other(other) => other.as_str(),
+        };
+        element(&mut nfo, "status", status);
+    }
+    if let Some(rating) = &anime.rating {
+        let mpaa = match rating {
+            Rating::G => "G",
+            Rating::PG => "PG",
+            Rating::PG13 => "PG-13",
+            Rating::R17Plus => "R",
+            Rating::RPlus | Rating::Rx => "NC-17",
+            Rating::Other(other) => other.as_str(),
+        };
+        element(&mut nfo, "mpaa", mpaa);
+    }
+    for genre in anime.genres.iter().chain(&anime.explicit_genres) {
+        element(&mut nfo, "genre", &genre.name);
+    }
+    for theme in &anime.themes {
+        element(&mut nfo, "tag", &theme.name);
+    }
+    for studio in &anime.studios {
+        element(&mut nfo, "studio", &studio.name);
+    }
+    let _ = writeln!(nfo, "  <uniqueid type=\"myanimelist\" default=\"true\">{}</uniqueid>", anime.mal_id);
+    if let Some(youtube_id) = &anime.trailer.youtube_id {
+        element(&mut nfo, "trailer", &format!("plugin://plugin.video.youtube/?action=play_video&videoid={}", youtube_id));
+    }
+
+    // Japanese voice actors with the character they play
+    for character in &anime.characters {
+        let actor = character.voice_actors.iter()
+            .find(|va| va.language == "Japanese")
+            .map(|va| va.person.name.as_str())
+            .unwrap_or(&character.character.name);
+        nfo.push_str("  <actor>\n");
+        element_indented(&mut nfo, 4, "name", actor);
+        element_indented(&mut nfo, 4, "role", &character.character.name);
+        if !character.character.images.jpg.image_url.is_empty() {
+            element_indented(&mut nfo, 4, "thumb", &character.character.images.jpg.image_url);
+        }
+        nfo.push_str("  </actor>\n");
+    }
+
+    nfo.push_str("</tvshow>\n");
+    nfo
+}
+
+fn episode_nfo(episode: &AnimeEpisode) -> String {
+    let mut nfo = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n<episodedetails>\n");
+
+    element(&mut nfo, "title", &episode.title);
+    if let Some(original) = &episode.title_japanese {
+        element(&mut nfo, "originaltitle", original);
+    }
+    element(&mut nfo, "season", "1");
+    element(&mut nfo, "episode", &episode.episode.to_string());
+    if let Some(aired) = episode.aired {
+        element(&mut nfo, "aired", &aired.format("%Y-%m-%d").to_string());
+    }
+    if let Some(score) = episode.score {
+        element(&mut nfo, "rating", &score.to_string());
+    }
+    if let Some(duration) = episode.duration {
+        // Jikan reports episode durations in seconds, Kodi expects minutes
+        element(&mut nfo, "runtime", &(duration / 60).to_string());
+    }
+
+    nfo.push_str("</episodedetails>\n");
+    nfo
+}
+
+fn element(nfo: &mut String, name: &str, value: &str) {
+    element_indented(nfo, 2, name, value);
+}
+
+fn element_indented(nfo: &mut String, indent: usize, name: &str, value: &str) {
+    let _ = writeln!(nfo, "{:indent$}<{name}>{}</{name}>", "", xml::escape(value), indent = indent, name = name);
+}
+
+fn preferred_url<'a>(preferred: &'a str, fallback: &'a str) -> Option<&'a str> {
+    [preferred, fallback].into_iter().find(|url| !url.is_empty())
+}
+
+/// Replace characters that are invalid in file names on common file systems
+fn sanitize_path_component(name: &str) -> String {
+    let sanitized: String = name.chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    sanitized.trim().trim_end_matches('.').to_string()
+}
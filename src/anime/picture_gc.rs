@@ -0,0 +1,174 @@
+use std::sync::Arc;
+
+use mongodb::Database;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{debug, info, warn};
+
+use crate::anime::{anilist, my_anime_list};
+use crate::global::database::DatabaseInstance;
+use crate::global::error::AppError;
+use crate::global::events::{AnimeSource, DataEvent, EventBus};
+use crate::global::queue::{Task, TaskContext, TaskData, TaskPriority};
+use crate::picture::database as picture_db;
+
+/// Entity type of the pictures of a MyAnimeList anime, whose id is the MAL id
+const MAL_ENTITY_TYPE: &str = "anime";
+
+/// Entity type of the pictures of an AniList anime, whose id is the AniList id
+const ANILIST_ENTITY_TYPE: &str = "anime_anilist";
+
+/// Pictures removed by a collection
+#[derive(Debug, Default)]
+pub struct PictureGcReport {
+    /// Picture entries deleted
+    pub pictures: u64,
+    /// Files deleted, files still used by another entry are kept
+    pub files: u64,
+}
+
+fn entity_type(source: AnimeSource) -> &'static str {
+    match source {
+        AnimeSource::MyAnimeList => MAL_ENTITY_TYPE,
+        AnimeSource::AniList => ANILIST_ENTITY_TYPE,
+    }
+}
+
+/// Delete the pictures of a removed anime, and their files once no other entry uses them.
+/// Characters, staff and voice actors are shared by several anime and are kept.
+/// Nothing is deleted while the anime is stored.
+pub async fn collect_anime_pictures(db: &Database, source: AnimeSource, id: u32) -> Result<PictureGcReport, AppError> {
+    let mut report = PictureGcReport::default();
+    let exists = match source {
+        AnimeSource::MyAnimeList => my_anime_list::database::anime_exists(db, id as i32).await?,
+        AnimeSource::AniList => anilist::database::anime_exists(db, id as i32).await?,
+    };
+    if exists {
+        return Ok(report);
+    }
+
+    let entity_type = entity_type(source);
+    let entity_id = id.to_string();
+
+    for picture in picture_db::get_pictures_by_entity(db, entity_type, &entity_id).await? {
+        if !picture_db::delete_entity_picture(db, &picture.url, entity_type, &entity_id).await? {
+            continue;
+        }
+        report.pictures += 1;
+
+        if picture.file_path.is_empty() || picture_db::count_pictures_by_path(db, &picture.file_path).await? > 0 {
+            continue;
+        }
+        match tokio::fs::remove_file(&picture.file_path).await {
+            Ok(()) => report.files += 1,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => warn!(path = %picture.file_path, error = %e, "Failed to delete picture file"),
+        }
+    }
+
+    if report.pictures > 0 {
+        debug!(source = ?source, id = id, pictures = report.pictures, files = report.files, "Collected pictures of removed anime");
+    }
+    Ok(report)
+}
+
+/// Collect the pictures of every anime that is no longer stored
+pub async fn collect_orphan_pictures(db: &Database) -> Result<PictureGcReport, AppError> {
+    let mut report = PictureGcReport::default();
+
+    for source in [AnimeSource::MyAnimeList, AnimeSource::AniList] {
+        for entity_id in picture_db::get_entity_ids(db, entity_type(source)).await? {
+            let Ok(id) = entity_id.parse::<u32>() else { continue };
+            let collected = collect_anime_pictures(db, source, id).await?;
+            report.pictures += collected.pictures;
+            report.files += collected.files;
+        }
+    }
+
+    Ok(report)
+}
+
+/// Collect the pictures of the anime removed from the collections (`picture.collect_garbage`)
+pub fn spawn_picture_gc(events: &EventBus, db: Arc<DatabaseInstance>) {
+    let mut rx = events.subscribe();
+    info!("Picture garbage collection of removed anime enabled");
+
+    tokio::spawn(async move {
+        loop {
+            match rx.recv().await {
+                Ok(DataEvent::AnimeDeleted { source, id }) => {
+                    if let Err(e) = collect_anime_pictures(db.db(), source, id).await {
+                        warn!(source = ?source, id = id, error = %e, "Failed to collect pictures of removed anime");
+                    }
+                }
+                Ok(_) => {}
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!(skipped = skipped, "Picture garbage collection fell behind, run the collection task to catch up");
+                }
+                Err(RecvError::Closed) => break,
+            }
+        }
+    });
+}
+
+// ========================================================================
+// Collect Picture Garbage Task
+// ========================================================================
+
+/// Deletes the pictures left by anime that are no longer stored
+pub struct CollectPictureGarbageTask {
+    id: String,
+    created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl CollectPictureGarbageTask {
+    pub fn new() -> Self {
+        Self {
+            id: format!("collect_picture_garbage_{}", uuid::Uuid::new_v4()),
+            created_at: chrono::Utc::now(),
+        }
+    }
+}
+
+impl Default for CollectPictureGarbageTask {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait::async_trait]
+impl Task for CollectPictureGarbageTask {
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+
+    fn name(&self) -> &str {
+        "collect_picture_garbage"
+    }
+
+    fn priority(&self) -> TaskPriority {
+        TaskPriority::Low
+    }
+
+    fn to_data(&self) -> TaskData {
+        TaskData::new(
+            self.id.clone(),
+            self.name().to_string(),
+            self.priority(),
+            self.created_at,
+            serde_json::json!({}),
+        )
+    }
+
+    async fn execute(&self, ctx: &TaskContext) -> Result<(), AppError> {
+        let db = ctx.db.clone();
+        let report = collect_orphan_pictures(db.db()).await?;
+
+        info!(
+            task = %self.name(),
+            pictures = report.pictures,
+            files = report.files,
+            "Pictures of removed anime collected"
+        );
+        Ok(())
+    }
+}
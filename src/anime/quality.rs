@@ -0,0 +1,252 @@
+
+use futures::stream::StreamExt;
+use mongodb::bson::doc;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::anime::my_anime_list;
+use crate::anime::my_anime_list::model::{AnimeData, Status};
+use crate::global::database::DatabaseInstance;
+use crate::global::error::{AppError, DatabaseError};
+use crate::global::queue::{Task, TaskContext, TaskData, TaskPriority};
+
+// Collection name for the anime that failed the last validation
+const QUALITY_COLLECTION: &str = "anime_quality";
+
+/// Completeness problem of a stored anime
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QualityIssue {
+    EmptySynopsis,
+    /// Finished airing but no episode count
+    ZeroEpisodes,
+    NoImages,
+    NoCharacters,
+    /// Has an episode count but no episode list
+    NoEpisodeList,
+}
+
+impl QualityIssue {
+    /// Points subtracted from the completeness score
+    fn weight(self) -> u32 {
+        match self {
+            QualityIssue::NoImages => 30,
+            QualityIssue::EmptySynopsis => 25,
+            QualityIssue::ZeroEpisodes => 20,
+            QualityIssue::NoCharacters => 15,
+            QualityIssue::NoEpisodeList => 10,
+        }
+    }
+
+    /// What fixes the issue
+    fn action(self) -> QualityAction {
+        match self {
+            QualityIssue::EmptySynopsis | QualityIssue::ZeroEpisodes | QualityIssue::NoImages => QualityAction::Refetch,
+            QualityIssue::NoCharacters | QualityIssue::NoEpisodeList => QualityAction::ExtendedFetch,
+        }
+    }
+}
+
+/// Fetch needed to complete an anime
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QualityAction {
+    /// Fetch the anime again (POST /api/anime/update)
+    Refetch,
+    /// Fetch extended data only (POST /api/anime/extended)
+    ExtendedFetch,
+}
+
+impl QualityAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            QualityAction::Refetch => "refetch",
+            QualityAction::ExtendedFetch => "extended_fetch",
+        }
+    }
+}
+
+/// An anime with completeness issues, stored in the `anime_quality` collection
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QualityEntry {
+    pub mal_id: i32,
+    pub title: String,
+    /// 100 for a complete entry, lower is worse
+    pub score: u32,
+    pub issues: Vec<QualityIssue>,
+    /// Refetch when any issue needs it, extended fetch otherwise
+    pub action: QualityAction,
+    /// Used to rank entries with the same score, popular titles first
+    pub members: i32,
+    pub validated_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Summary of a validation run
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ValidationSummary {
+    pub checked: u64,
+    pub incomplete: u64,
+}
+
+/// Score an anime for completeness, `None` when nothing is missing
+pub fn validate_anime(anime: &AnimeData) -> Option<QualityEntry> {
+    let mut issues = Vec::new();
+    if anime.synopsis.trim().is_empty() {
+        issues.push(QualityIssue::EmptySynopsis);
+    }
+    if anime.num_episodes == 0 && matches!(anime.status, Some(Status::FinishedAiring)) {
+        issues.push(QualityIssue::ZeroEpisodes);
+    }
+    if anime.images.jpg.image_url.is_empty() && anime.images.webp.image_url.is_empty() {
+        issues.push(QualityIssue::NoImages);
+    }
+    if anime.characters.is_empty() && !anime.overflow_fields.iter().any(|field| field == "characters") {
+        issues.push(QualityIssue::NoCharacters);
+    }
+    if anime.num_episodes > 0 && anime.episode_summary.as_ref().is_none_or(|summary| summary.total == 0) {
+        issues.push(QualityIssue::NoEpisodeList);
+    }
+
+    if issues.is_empty() {
+        return None;
+    }
+
+    let penalty: u32 = issues.iter().map(|issue| issue.weight()).sum();
+    let action = if issues.iter().any(|issue| issue.action() == QualityAction::Refetch) {
+        QualityAction::Refetch
+    } else {
+        QualityAction::ExtendedFetch
+    };
+
+    Some(QualityEntry {
+        mal_id: anime.mal_id,
+        title: anime.display_title().to_string(),
+        score: 100u32.saturating_sub(penalty),
+        issues,
+        action,
+        members: anime.members,
+        validated_at: chrono::Utc::now(),
+    })
+}
+
+/// Validate every stored MAL anime and replace the previous results
+pub async fn validate_collection(db: &DatabaseInstance) -> Result<ValidationSummary, AppError> {
+    let mut summary = ValidationSummary::default();
+    let mut entries = Vec::new();
+
+    let mut cursor = my_anime_list::database::get_all_anime_cursor(db.db()).await?;
+    while let Some(result) = cursor.next().await {
+        match result {
+            Ok(anime) => {
+                summary.checked += 1;
+                if let Some(entry) = validate_anime(&anime) {
+                    entries.push(entry);
+                }
+            }
+            Err(e) => warn!(error = %e, "Failed to deserialize anime, skipping"),
+        }
+    }
+    summary.incomplete = entries.len() as u64;
+
+    let collection = db.collection::<QualityEntry>(QUALITY_COLLECTION);
+    collection.delete_many(doc! {})
+        .await
+        .map_err(|e| AppError::Database(DatabaseError::Query(format!("Failed to clear quality results: {}", e))))?;
+    if !entries.is_empty() {
+        collection.insert_many(&entries)
+            .await
+            .map_err(|e| AppError::Database(DatabaseError::Query(format!("Failed to save quality results: {}", e))))?;
+    }
+
+    info!(
+        checked = summary.checked,
+        incomplete = summary.incomplete,
+        "Collection validation finished"
+    );
+
+    Ok(summary)
+}
+
+/// Anime needing a fetch from the last validation, least complete and most popular first
+pub async fn incomplete_anime(
+    db: &DatabaseInstance,
+    action: Option<QualityAction>,
+    limit: i64,
+) -> Result<Vec<QualityEntry>, AppError> {
+    let filter = match action {
+        Some(action) => doc! { "action": action.as_str() },
+        None => doc! {},
+    };
+
+    let mut cursor = db.collection::<QualityEntry>(QUALITY_COLLECTION)
+        .find(filter)
+        .sort(doc! { "score": 1, "members": -1 })
+        .limit(limit)
+        .await
+        .map_err(|e| AppError::Database(DatabaseError::Query(format!("Failed to list quality results: {}", e))))?;
+
+    let mut entries = Vec::new();
+    while let Some(result) = cursor.next().await {
+        match result {
+            Ok(entry) => entries.push(entry),
+            Err(e) => warn!(error = %e, "Failed to deserialize quality result"),
+        }
+    }
+
+    Ok(entries)
+}
+
+// ========================================================================
+// Validate Collection Task
+// ========================================================================
+
+/// Scores every stored anime for completeness
+pub struct ValidateCollectionTask {
+    id: String,
+    created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl ValidateCollectionTask {
+    pub fn new() -> Self {
+        Self {
+            id: format!("validate_collection_{}", uuid::Uuid::new_v4()),
+            created_at: chrono::Utc::now(),
+        }
+    }
+}
+
+impl Default for ValidateCollectionTask {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait::async_trait]
+impl Task for ValidateCollectionTask {
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+
+    fn name(&self) -> &str {
+        "validate_collection"
+    }
+
+    fn priority(&self) -> TaskPriority {
+        TaskPriority::Low
+    }
+
+    fn to_data(&self) -> TaskData {
+        TaskData::new(
+            self.id.clone(),
+            self.name().to_string(),
+            self.priority(),
+            self.created_at,
+            serde_json::json!({}),
+        )
+    }
+
+    async fn execute(&self, ctx: &TaskContext) -> Result<(), AppError> {
+        let db = ctx.db.clone();
+        validate_collection(&db).await.map(|_| ())
+    }
+}
@@ -0,0 +1,160 @@
+use std::collections::{HashSet, VecDeque};
+
+use serde::Serialize;
+
+use crate::anime::my_anime_list::database;
+use crate::anime::my_anime_list::model::AnimeData;
+use crate::global::database::DatabaseInstance;
+use crate::global::error::AppError;
+
+/// Nodes returned at most, the traversal stops expanding once reached
+const MAX_NODES: usize = 500;
+
+/// Related anime around one anime, for visualization clients
+#[derive(Debug, Clone, Serialize)]
+pub struct RelatedGraph {
+    pub root: i32,
+    pub depth: u32,
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+    /// The node limit was reached before the requested depth
+    pub truncated: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GraphNode {
+    pub mal_id: i32,
+    pub title: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image_url: Option<String>,
+    /// Distance from the root anime
+    pub depth: u32,
+    /// Whether the anime is stored locally. Uncollected anime are leaves,
+    /// their own relations and recommendations are unknown.
+    pub collected: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GraphEdge {
+    pub source: i32,
+    pub target: i32,
+    #[serde(flatten)]
+    pub kind: EdgeKind,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum EdgeKind {
+    /// MAL relation such as "Sequel" or "Side Story"
+    Relation { relation: String },
+    /// User recommendation with its vote count
+    Recommendation { votes: i32 },
+}
+
+/// Build the graph of anime reachable from `mal_id` through the stored `relations`
+/// (anime entries only) and `recommendations`, up to `depth` hops.
+/// Returns `None` when the root anime is not collected.
+pub async fn related_graph(db: &DatabaseInstance, mal_id: i32, depth: u32) -> Result<Option<RelatedGraph>, AppError> {
+    let Some(root) = database::get_anime_by_id(db.db(), mal_id).await? else {
+        return Ok(None);
+    };
+
+    let mut nodes = vec![collected_node(&root, 0)];
+    let mut visited = HashSet::from([root.mal_id]);
+    let mut edges = Vec::new();
+    let mut seen_edges = HashSet::new();
+    let mut truncated = false;
+
+    let mut pending = VecDeque::from([(root, 0)]);
+    while let Some((anime, distance)) = pending.pop_front() {
+        if distance >= depth {
+            continue;
+        }
+
+        for (target, title, image_url, kind) in neighbours(&anime) {
+            if target == anime.mal_id {
+                continue;
+            }
+
+            if !visited.contains(&target) {
+                if nodes.len() >= MAX_NODES {
+                    truncated = true;
+                    continue;
+                }
+
+                let node = match database::get_anime_by_id(db.db(), target).await? {
+                    Some(data) => {
+                        let node = collected_node(&data, distance + 1);
+                        pending.push_back((data, distance + 1));
+                        node
+                    }
+                    None => GraphNode {
+                        mal_id: target,
+                        title,
+                        image_url,
+                        depth: distance + 1,
+                        collected: false,
+                    },
+                };
+                visited.insert(target);
+                nodes.push(node);
+            }
+
+            // Relations are usually listed on both sides, keep one edge per pair and kind
+            let (low, high) = (anime.mal_id.min(target), anime.mal_id.max(target));
+            let key = (low, high, matches!(kind, EdgeKind::Relation { .. }));
+            if seen_edges.insert(key) {
+                edges.push(GraphEdge {
+                    source: anime.mal_id,
+                    target,
+                    kind,
+                });
+            }
+        }
+    }
+
+    Ok(Some(RelatedGraph {
+        root: mal_id,
+        depth,
+        nodes,
+        edges,
+        truncated,
+    }))
+}
+
+fn collected_node(anime: &AnimeData, depth: u32) -> GraphNode {
+    let image_url = &anime.images.jpg.image_url;
+    GraphNode {
+        mal_id: anime.mal_id,
+        title: anime.display_title().to_string(),
+        image_url: (!image_url.is_empty()).then(|| image_url.clone()),
+        depth,
+        collected: true,
+    }
+}
+
+/// Anime directly connected to `anime`: (mal_id, title, image, edge)
+fn neighbours(anime: &AnimeData) -> Vec<(i32, String, Option<String>, EdgeKind)> {
+    let relations = anime.relations.iter().flat_map(|relation| {
+        relation.entry.iter()
+            .filter(|entry| entry.entry_type == "anime")
+            .map(|entry| (
+                entry.mal_id,
+                entry.name.clone(),
+                None,
+                EdgeKind::Relation { relation: relation.relation.clone() },
+            ))
+    });
+
+    let recommendations = anime.recommendations.iter().map(|recommendation| {
+        let image_url = &recommendation.entry.images.jpg.image_url;
+        (
+            recommendation.entry.mal_id,
+            recommendation.entry.title.clone(),
+            (!image_url.is_empty()).then(|| image_url.clone()),
+            EdgeKind::Recommendation { votes: recommendation.votes },
+        )
+    });
+
+    relations.chain(recommendations).collect()
+}
@@ -0,0 +1,149 @@
+
+use futures::stream::StreamExt;
+use mongodb::Database;
+use tracing::{debug, info};
+
+use crate::anime::anilist;
+use crate::anime::anilist::converter::anilist_to_anime_data;
+use crate::anime::anilist::model::{GraphQLResponse, MediaData};
+use crate::anime::my_anime_list;
+use crate::anime::my_anime_list::converter::{mal_to_anime_data, merge_jikan_data};
+use crate::anime::my_anime_list::model::{JikanAnimeResponse, MalAnimeResponse};
+use crate::global::archive::{self, RawResponse};
+use crate::global::error::{AppError, DatabaseError};
+use crate::global::queue::{self, Task, TaskContext, TaskData, TaskPriority};
+
+/// URLs of the MyAnimeList anime details requests of `FetchAnimeTask`
+const MAL_ANIME_URL_PATTERN: &str = r"/anime/\d+\?";
+
+/// Counts of a reprocessing run
+#[derive(Debug, Default)]
+struct ReprocessCounts {
+    mal: usize,
+    anilist: usize,
+    failed: usize,
+}
+
+/// Runs the converters again over the archived provider responses (`[http.archive]`)
+/// and stores the result, to apply a converter fix without fetching every anime again.
+///
+/// MyAnimeList anime are rebuilt from their latest details response, merged with the latest
+/// Jikan `/full` response when there is one. Anime stored from Jikan alone are left as they are.
+pub struct ReprocessTask {
+    id: String,
+    created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl ReprocessTask {
+    pub fn new() -> Self {
+        Self {
+            id: format!("reprocess_raw_responses_{}", uuid::Uuid::new_v4()),
+            created_at: chrono::Utc::now(),
+        }
+    }
+
+    async fn reprocess_mal(&self, db: &Database, counts: &mut ReprocessCounts) -> Result<(), AppError> {
+        let mut responses = archive::latest_responses(db, "my_anime_list", MAL_ANIME_URL_PATTERN).await?;
+
+        while let Some(result) = responses.next().await {
+            let response = read_response(result)?;
+            let mal = match serde_json::from_str::<MalAnimeResponse>(&response.body) {
+                Ok(mal) => mal,
+                Err(e) => {
+                    queue::record_warning(format!("{}: unreadable archived response: {}", response.url, e));
+                    counts.failed += 1;
+                    continue;
+                }
+            };
+            let mal_id = mal.id;
+
+            let mut anime = mal_to_anime_data(mal, Some(format!("https://myanimelist.net/anime/{}", mal_id)));
+            let jikan_pattern = format!(r"/anime/{}/full$", mal_id);
+            if let Some(jikan) = archive::latest_response(db, "jikan", &jikan_pattern).await? {
+                match serde_json::from_str::<JikanAnimeResponse>(&jikan.body) {
+                    Ok(jikan) => anime = merge_jikan_data(anime, jikan.data),
+                    Err(e) => queue::record_warning(format!("{}: unreadable archived response: {}", jikan.url, e)),
+                }
+            }
+
+            my_anime_list::database::upsert_anime(db, &anime).await?;
+            debug!(task = %self.name(), mal_id = mal_id, "Reprocessed MyAnimeList anime");
+            counts.mal += 1;
+        }
+        Ok(())
+    }
+
+    async fn reprocess_anilist(&self, db: &Database, counts: &mut ReprocessCounts) -> Result<(), AppError> {
+        let mut responses = archive::latest_responses(db, "anilist", ".").await?;
+
+        while let Some(result) = responses.next().await {
+            let response = read_response(result)?;
+            let media = match serde_json::from_str::<GraphQLResponse<MediaData>>(&response.body) {
+                Ok(GraphQLResponse { data: Some(data), .. }) => data.media,
+                Ok(_) => continue,
+                Err(e) => {
+                    queue::record_warning(format!("{} {:?}: unreadable archived response: {}", response.url, response.request, e));
+                    counts.failed += 1;
+                    continue;
+                }
+            };
+
+            let anime = anilist_to_anime_data(media);
+            anilist::database::upsert_anime(db, &anime).await?;
+            debug!(task = %self.name(), anilist_id = anime.anilist_id, "Reprocessed AniList anime");
+            counts.anilist += 1;
+        }
+        Ok(())
+    }
+}
+
+impl Default for ReprocessTask {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn read_response(result: Result<RawResponse, mongodb::error::Error>) -> Result<RawResponse, DatabaseError> {
+    result.map_err(|e| DatabaseError::Query(format!("Failed to read archived response: {}", e)))
+}
+
+#[async_trait::async_trait]
+impl Task for ReprocessTask {
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+
+    fn name(&self) -> &str {
+        "reprocess_raw_responses"
+    }
+
+    fn priority(&self) -> TaskPriority {
+        TaskPriority::Low
+    }
+
+    fn to_data(&self) -> TaskData {
+        TaskData::new(
+            self.id.clone(),
+            self.name().to_string(),
+            self.priority(),
+            self.created_at,
+            serde_json::json!({}),
+        )
+    }
+
+    async fn execute(&self, ctx: &TaskContext) -> Result<(), AppError> {
+        let db = ctx.db.clone();
+        let mut counts = ReprocessCounts::default();
+        self.reprocess_mal(db.db(), &mut counts).await?;
+        self.reprocess_anilist(db.db(), &mut counts).await?;
+
+        info!(
+            task = %self.name(),
+            mal = counts.mal,
+            anilist = counts.anilist,
+            failed = counts.failed,
+            "Archived provider responses reprocessed"
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,95 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use chrono::{DateTime, Datelike, Utc};
+use futures::stream::StreamExt;
+use mongodb::bson::{self, doc, Bson, Document};
+use mongodb::Collection;
+use tracing::warn;
+
+use crate::anime::my_anime_list::model::Season;
+use crate::global::error::DatabaseError;
+
+/// Whether converters fill a missing season and year from the start date, follows `anime.infer_season`
+static INFER_SEASON: AtomicBool = AtomicBool::new(true);
+
+/// Enable or disable season inference, called on start and on configuration reload
+pub fn set_inference(enabled: bool) {
+    INFER_SEASON.store(enabled, Ordering::Relaxed);
+}
+
+/// Broadcast season and year of a start date: winter is January to March, spring April
+/// to June, summer July to September and fall October to December
+pub fn season_of(date: DateTime<Utc>) -> (Season, i32) {
+    let season = match date.month() {
+        1..=3 => Season::Winter,
+        4..=6 => Season::Spring,
+        7..=9 => Season::Summer,
+        _ => Season::Fall,
+    };
+    (season, date.year())
+}
+
+/// Fill the season and year a provider omitted from the start date, when inference is enabled
+pub fn fill_missing(season: &mut Option<Season>, year: &mut Option<i32>, aired_from: Option<DateTime<Utc>>) {
+    if !INFER_SEASON.load(Ordering::Relaxed) || (season.is_some() && year.is_some()) {
+        return;
+    }
+    let Some(from) = aired_from else { return };
+
+    let (inferred_season, inferred_year) = season_of(from);
+    // A known year from another season keeps the provider value, only the season is inferred
+    if year.is_none_or(|year| year == inferred_year) {
+        season.get_or_insert(inferred_season);
+    }
+    year.get_or_insert(inferred_year);
+}
+
+/// Fill the missing season and year of the anime of a collection stored before inference,
+/// returns the number of updated documents
+pub async fn backfill_seasons(collection: Collection<Document>) -> Result<u64, DatabaseError> {
+    let filter = doc! {
+        "$or": [{ "season": null }, { "year": null }],
+        "aired.from": { "$ne": null },
+    };
+    let mut cursor = collection
+        .find(filter)
+        .projection(doc! { "season": 1, "year": 1, "aired.from": 1 })
+        .await
+        .map_err(|e| DatabaseError::Query(format!("Failed to find anime without season: {}", e)))?;
+
+    let mut updated = 0;
+    while let Some(result) = cursor.next().await {
+        let anime = match result {
+            Ok(anime) => anime,
+            Err(e) => {
+                warn!(error = %e, "Failed to read anime during season backfill");
+                continue;
+            }
+        };
+        let Ok(id) = anime.get_object_id("_id") else { continue };
+        let aired_from = anime.get_document("aired").ok()
+            .and_then(|aired| match aired.get("from") {
+                Some(Bson::String(date)) => DateTime::parse_from_rfc3339(date).ok().map(|date| date.with_timezone(&Utc)),
+                Some(Bson::DateTime(date)) => DateTime::from_timestamp_millis(date.timestamp_millis()),
+                _ => None,
+            });
+
+        let mut season = anime.get("season")
+            .and_then(|season| bson::from_bson::<Season>(season.clone()).ok());
+        let mut year = anime.get_i32("year").ok();
+        let (had_season, had_year) = (season.is_some(), year.is_some());
+        fill_missing(&mut season, &mut year, aired_from);
+        if season.is_some() == had_season && year.is_some() == had_year {
+            continue;
+        }
+
+        let season = bson::to_bson(&season)
+            .map_err(|e| DatabaseError::Query(format!("Failed to serialize season: {}", e)))?;
+        collection.update_one(doc! { "_id": id }, doc! { "$set": { "season": season, "year": year } })
+            .await
+            .map_err(|e| DatabaseError::Query(format!("Failed to backfill season: {}", e)))?;
+        updated += 1;
+    }
+
+    Ok(updated)
+}
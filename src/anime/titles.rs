@@ -0,0 +1,459 @@
+use std::collections::HashSet;
+
+use futures::stream::StreamExt;
+use mongodb::bson::{doc, Document};
+use mongodb::options::{IndexOptions, ReplaceOptions};
+use mongodb::{Database, IndexModel};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, warn};
+
+use crate::anime::anilist;
+use crate::anime::my_anime_list;
+use crate::global::error::{AppError, DatabaseError};
+use crate::global::queue::{Task, TaskContext, TaskData, TaskPriority};
+
+// Collection name for the normalized title variants of stored anime
+const COLLECTION_NAME: &str = "anime_titles";
+
+/// Candidates fetched from the database before ranking
+const MAX_CANDIDATES: i64 = 200;
+
+/// Matches below this similarity are dropped
+const MIN_SIMILARITY: f64 = 0.3;
+
+/// Longest word prefix indexed for autocompletion, longer inputs are looked up
+/// by their first characters then filtered
+const MAX_PREFIX_LENGTH: usize = 16;
+
+/// Collection the indexed anime is stored in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TitleSource {
+    Mal,
+    Anilist,
+}
+
+impl TitleSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TitleSource::Mal => "mal",
+            TitleSource::Anilist => "anilist",
+        }
+    }
+}
+
+/// Title variants of one anime, stored in the `anime_titles` collection.
+/// `id` is the MAL or AniList id depending on `source`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TitleIndexEntry {
+    pub source: TitleSource,
+    pub id: i32,
+    pub mal_id: Option<i32>,
+    /// Titles as stored (romaji, English, native, synonyms)
+    pub titles: Vec<String>,
+    /// `normalize` of each title, same order as `titles`
+    pub variants: Vec<String>,
+    /// Character trigrams of all variants, used to find candidates
+    pub trigrams: Vec<String>,
+    /// Prefixes of the text from each word start of the variants, used for autocompletion.
+    /// Entries indexed before they existed get them from the `title_prefixes` migration.
+    #[serde(default)]
+    pub prefixes: Vec<String>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// A fuzzy search hit
+#[derive(Debug, Clone, Serialize)]
+pub struct TitleMatch {
+    pub source: TitleSource,
+    pub id: i32,
+    pub mal_id: Option<i32>,
+    /// Best matching title
+    pub title: String,
+    /// Dice similarity of the trigrams, 1.0 for an exact match after normalization
+    pub similarity: f64,
+}
+
+pub async fn initialize_collection(db: &Database) -> Result<(), DatabaseError> {
+    let collection = db.collection::<TitleIndexEntry>(COLLECTION_NAME);
+
+    // One entry per anime and source
+    let key_index = IndexModel::builder()
+        .keys(doc! { "source": 1, "id": 1 })
+        .options(IndexOptions::builder().unique(true).build())
+        .build();
+
+    // Candidate lookup
+    let trigram_index = IndexModel::builder()
+        .keys(doc! { "trigrams": 1 })
+        .build();
+
+    // Autocompletion lookup
+    let prefix_index = IndexModel::builder()
+        .keys(doc! { "prefixes": 1 })
+        .build();
+
+    collection.create_indexes(vec![key_index, trigram_index, prefix_index]).await
+        .map_err(|e| DatabaseError::Query(format!("Failed to create anime_titles indexes: {}", e)))?;
+
+    debug!("Created indexes for anime_titles collection");
+    Ok(())
+}
+
+/// Lowercase, strip diacritics and punctuation, collapse whitespace and romaji long vowels,
+/// so that "Shōjo", "Shoujo" and "shojo" give the same variant
+pub fn normalize(title: &str) -> String {
+    let folded: String = title.chars()
+        .flat_map(char::to_lowercase)
+        .map(fold_diacritic)
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect();
+
+    let collapsed = folded.split_whitespace().collect::<Vec<_>>().join(" ");
+    collapsed.replace("ou", "o").replace("uu", "u").replace("oo", "o")
+}
+
+/// Base letter of common Latin letters with diacritics (Hepburn macrons included)
+fn fold_diacritic(c: char) -> char {
+    match c {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' => 'a',
+        'ç' | 'ć' | 'č' => 'c',
+        'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ě' => 'e',
+        'ì' | 'í' | 'î' | 'ï' | 'ī' => 'i',
+        'ñ' | 'ń' => 'n',
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' => 'o',
+        'ù' | 'ú' | 'û' | 'ü' | 'ū' | 'ů' => 'u',
+        'ý' | 'ÿ' => 'y',
+        'š' | 'ś' => 's',
+        'ž' | 'ź' | 'ż' => 'z',
+        '×' => 'x',
+        c => c,
+    }
+}
+
+/// Character trigrams of a normalized variant, padded so short titles still produce some
+fn trigrams(variant: &str) -> HashSet<String> {
+    let chars: Vec<char> = format!("  {} ", variant).chars().collect();
+    chars.windows(3).map(|window| window.iter().collect()).collect()
+}
+
+/// Prefixes up to MAX_PREFIX_LENGTH of the text starting at each word of a normalized variant,
+/// so "shingeki no kyojin" is found from "shin", "no kyo" or "kyojin"
+fn prefixes(variant: &str) -> impl Iterator<Item = String> + '_ {
+    let word_starts = variant.char_indices()
+        .filter(|&(i, c)| c != ' ' && (i == 0 || variant[..i].ends_with(' ')))
+        .map(|(i, _)| i);
+
+    word_starts.flat_map(move |start| {
+        let chars: Vec<char> = variant[start..].chars().take(MAX_PREFIX_LENGTH).collect();
+        (1..=chars.len())
+            .filter(|&length| chars[length - 1] != ' ')
+            .map(|length| chars[..length].iter().collect())
+            .collect::<Vec<String>>()
+    })
+}
+
+/// Sorted distinct prefixes of all the variants of an anime
+fn variant_prefixes(variants: &[String]) -> Vec<String> {
+    let mut prefixes: Vec<String> = variants.iter().flat_map(|variant| prefixes(variant)).collect::<HashSet<_>>().into_iter().collect();
+    prefixes.sort();
+    prefixes
+}
+
+fn similarity(a: &HashSet<String>, b: &HashSet<String>) -> f64 {
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    2.0 * a.intersection(b).count() as f64 / (a.len() + b.len()) as f64
+}
+
+/// Replace the title variants of an anime, called whenever it is stored
+pub async fn index_titles<'a>(
+    db: &Database,
+    source: TitleSource,
+    id: i32,
+    mal_id: Option<i32>,
+    titles: impl IntoIterator<Item = &'a str>,
+) -> Result<(), DatabaseError> {
+    let mut seen = HashSet::new();
+    let titles: Vec<String> = titles.into_iter()
+        .filter(|title| !title.trim().is_empty() && seen.insert(*title))
+        .map(str::to_string)
+        .collect();
+    let variants: Vec<String> = titles.iter().map(|title| normalize(title)).collect();
+    let mut trigrams: Vec<String> = variants.iter().flat_map(|variant| trigrams(variant)).collect::<HashSet<_>>().into_iter().collect();
+    trigrams.sort();
+    let prefixes = variant_prefixes(&variants);
+
+    let entry = TitleIndexEntry {
+        source,
+        id,
+        mal_id,
+        titles,
+        variants,
+        trigrams,
+        prefixes,
+        updated_at: chrono::Utc::now(),
+    };
+
+    db.collection::<TitleIndexEntry>(COLLECTION_NAME)
+        .replace_one(doc! { "source": source.as_str(), "id": id }, &entry)
+        .with_options(ReplaceOptions::builder().upsert(true).build())
+        .await
+        .map_err(|e| DatabaseError::Query(format!("Failed to index titles: {}", e)))?;
+
+    Ok(())
+}
+
+pub async fn remove_titles(db: &Database, source: TitleSource, id: i32) -> Result<(), DatabaseError> {
+    db.collection::<TitleIndexEntry>(COLLECTION_NAME)
+        .delete_one(doc! { "source": source.as_str(), "id": id })
+        .await
+        .map_err(|e| DatabaseError::Query(format!("Failed to remove titles: {}", e)))?;
+
+    Ok(())
+}
+
+/// Anime whose romaji, English, native or alternative title resembles `query`, best match first
+pub async fn fuzzy_search(db: &Database, query: &str, limit: usize) -> Result<Vec<TitleMatch>, DatabaseError> {
+    let normalized = normalize(query);
+    if normalized.is_empty() {
+        return Ok(Vec::new());
+    }
+    let query_trigrams = trigrams(&normalized);
+    let query_list: Vec<String> = query_trigrams.iter().cloned().collect();
+
+    // Entries sharing the most trigrams with the query, ranked precisely below
+    let pipeline = vec![
+        doc! { "$match": { "trigrams": { "$in": query_list.clone() } } },
+        doc! { "$addFields": { "shared": { "$size": { "$setIntersection": ["$trigrams", query_list] } } } },
+        doc! { "$sort": { "shared": -1 } },
+        doc! { "$limit": MAX_CANDIDATES },
+        doc! { "$project": { "shared": 0, "trigrams": 0, "prefixes": 0 } },
+    ];
+
+    let mut cursor = db.collection::<Document>(COLLECTION_NAME)
+        .aggregate(pipeline)
+        .await
+        .map_err(|e| DatabaseError::Query(format!("Failed to search titles: {}", e)))?;
+
+    let mut matches = Vec::new();
+    while let Some(result) = cursor.next().await {
+        let document = match result {
+            Ok(document) => document,
+            Err(e) => {
+                warn!(error = %e, "Failed to read title index entry");
+                continue;
+            }
+        };
+        let Ok(entry) = mongodb::bson::from_document::<CandidateEntry>(document) else {
+            warn!("Failed to deserialize title index entry");
+            continue;
+        };
+
+        let best = entry.titles.iter()
+            .zip(&entry.variants)
+            .map(|(title, variant)| (title, similarity(&query_trigrams, &trigrams(variant))))
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+
+        if let Some((title, similarity)) = best
+            && similarity >= MIN_SIMILARITY
+        {
+            matches.push(TitleMatch {
+                source: entry.source,
+                id: entry.id,
+                mal_id: entry.mal_id,
+                title: title.clone(),
+                similarity,
+            });
+        }
+    }
+
+    matches.sort_by(|a, b| b.similarity.total_cmp(&a.similarity));
+    matches.truncate(limit);
+    Ok(matches)
+}
+
+/// Anime with a title word starting with `prefix` after normalization, closest title first.
+/// Looked up in the `prefixes` index, fast enough to run on every keystroke.
+pub async fn prefix_search(db: &Database, prefix: &str, limit: usize) -> Result<Vec<TitleMatch>, DatabaseError> {
+    let normalized = normalize(prefix);
+    if normalized.is_empty() {
+        return Ok(Vec::new());
+    }
+    let pattern = format!("(^| ){}", regex::escape(&normalized));
+    let prefix_trigrams = trigrams(&normalized);
+    let key: String = normalized.chars().take(MAX_PREFIX_LENGTH).collect();
+
+    let mut cursor = db.collection::<CandidateEntry>(COLLECTION_NAME)
+        .find(doc! { "prefixes": key.trim_end() })
+        .projection(doc! { "trigrams": 0, "prefixes": 0 })
+        .limit(MAX_CANDIDATES)
+        .await
+        .map_err(|e| DatabaseError::Query(format!("Failed to search titles: {}", e)))?;
+
+    let word_start = regex::Regex::new(&pattern)
+        .map_err(|e| DatabaseError::Query(format!("Invalid title prefix: {}", e)))?;
+    let mut matches = Vec::new();
+    while let Some(result) = cursor.next().await {
+        let entry = match result {
+            Ok(entry) => entry,
+            Err(e) => {
+                warn!(error = %e, "Failed to read title index entry");
+                continue;
+            }
+        };
+
+        let best = entry.titles.iter()
+            .zip(&entry.variants)
+            .filter(|(_, variant)| word_start.is_match(variant))
+            .map(|(title, variant)| (title, similarity(&prefix_trigrams, &trigrams(variant))))
+            .max_by(|a, b| a.1.total_cmp(&b.1));
+
+        if let Some((title, similarity)) = best {
+            matches.push(TitleMatch {
+                source: entry.source,
+                id: entry.id,
+                mal_id: entry.mal_id,
+                title: title.clone(),
+                similarity,
+            });
+        }
+    }
+
+    matches.sort_by(|a, b| b.similarity.total_cmp(&a.similarity));
+    matches.truncate(limit);
+    Ok(matches)
+}
+
+/// Index entry without the trigrams, as returned by the search pipeline
+#[derive(Deserialize)]
+struct CandidateEntry {
+    source: TitleSource,
+    id: i32,
+    mal_id: Option<i32>,
+    titles: Vec<String>,
+    variants: Vec<String>,
+}
+
+/// Index the titles of every stored anime, for entries stored before the index existed.
+/// Returns the number of anime indexed.
+pub async fn rebuild_index(db: &Database) -> Result<u64, DatabaseError> {
+    let mut indexed = 0;
+
+    let mut cursor = my_anime_list::database::get_all_anime_cursor(db).await?;
+    while let Some(result) = cursor.next().await {
+        match result {
+            Ok(anime) => {
+                index_titles(db, TitleSource::Mal, anime.mal_id, Some(anime.mal_id), anime.titles.iter().map(|t| t.title.as_str())).await?;
+                indexed += 1;
+            }
+            Err(e) => warn!(error = %e, "Failed to deserialize anime, skipping"),
+        }
+    }
+
+    let mut cursor = anilist::database::get_all_anime_cursor(db).await?;
+    while let Some(result) = cursor.next().await {
+        match result {
+            Ok(anime) => {
+                index_titles(db, TitleSource::Anilist, anime.anilist_id, anime.mal_id, anime.titles.iter().map(|t| t.title.as_str())).await?;
+                indexed += 1;
+            }
+            Err(e) => warn!(error = %e, "Failed to deserialize AniList anime, skipping"),
+        }
+    }
+
+    info!(indexed = indexed, "Title index rebuilt");
+    Ok(indexed)
+}
+
+/// Recompute the prefixes of every index entry from its stored variants, for the entries
+/// indexed before autocompletion. Returns the number of entries updated.
+pub async fn backfill_prefixes(db: &Database) -> Result<u64, DatabaseError> {
+    let collection = db.collection::<TitleIndexEntry>(COLLECTION_NAME);
+    let mut updated = 0;
+
+    let mut cursor = collection.find(doc! {})
+        .await
+        .map_err(|e| DatabaseError::Query(format!("Failed to list title index: {}", e)))?;
+    while let Some(result) = cursor.next().await {
+        let entry = match result {
+            Ok(entry) => entry,
+            Err(e) => {
+                warn!(error = %e, "Failed to deserialize title index entry, skipping");
+                continue;
+            }
+        };
+
+        let prefixes = variant_prefixes(&entry.variants);
+        if prefixes == entry.prefixes {
+            continue;
+        }
+        collection.update_one(
+            doc! { "source": entry.source.as_str(), "id": entry.id },
+            doc! { "$set": { "prefixes": prefixes } },
+        )
+            .await
+            .map_err(|e| DatabaseError::Query(format!("Failed to update title prefixes: {}", e)))?;
+        updated += 1;
+    }
+
+    debug!(updated = updated, "Title prefixes backfilled");
+    Ok(updated)
+}
+
+// ========================================================================
+// Rebuild Title Index Task
+// ========================================================================
+
+/// Indexes the titles of every stored anime
+pub struct RebuildTitleIndexTask {
+    id: String,
+    created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl RebuildTitleIndexTask {
+    pub fn new() -> Self {
+        Self {
+            id: format!("rebuild_title_index_{}", uuid::Uuid::new_v4()),
+            created_at: chrono::Utc::now(),
+        }
+    }
+}
+
+impl Default for RebuildTitleIndexTask {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[async_trait::async_trait]
+impl Task for RebuildTitleIndexTask {
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+
+    fn name(&self) -> &str {
+        "rebuild_title_index"
+    }
+
+    fn priority(&self) -> TaskPriority {
+        TaskPriority::Low
+    }
+
+    fn to_data(&self) -> TaskData {
+        TaskData::new(
+            self.id.clone(),
+            self.name().to_string(),
+            self.priority(),
+            self.created_at,
+            serde_json::json!({}),
+        )
+    }
+
+    async fn execute(&self, ctx: &TaskContext) -> Result<(), AppError> {
+        let db = ctx.db.clone();
+        rebuild_index(db.db()).await?;
+        Ok(())
+    }
+}
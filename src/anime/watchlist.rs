@@ -0,0 +1,251 @@
+
+use futures::stream::StreamExt;
+use mongodb::bson::{self, doc};
+use mongodb::options::IndexOptions;
+use mongodb::{Database, IndexModel};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, warn};
+
+use crate::anime::collect::{CollectAnimeTask, CollectTarget};
+use crate::anime::my_anime_list;
+use crate::anime::my_anime_list::task::{FetchEpisodesTask, FetchPicturesTask, FetchStatisticsTask};
+use crate::anime::titles::{self, TitleSource};
+use crate::global::error::{AppError, DatabaseError};
+use crate::global::http::ClientWithLimiter;
+use crate::global::queue::{Task, TaskContext, TaskData, TaskPriority, TaskQueue};
+
+// Collection name for the watched anime
+const COLLECTION_NAME: &str = "watchlist";
+
+/// Title matches below this similarity don't resolve a watched title to a stored anime
+const RESOLVE_SIMILARITY: f64 = 0.8;
+
+/// A watched anime, by MAL id or by title
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WatchlistEntry {
+    pub target: CollectTarget,
+    /// MAL id of the stored anime, resolved on refresh for title targets
+    pub mal_id: Option<i32>,
+    pub added_at: chrono::DateTime<chrono::Utc>,
+    pub refreshed_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+pub async fn initialize_collection(db: &Database) -> Result<(), DatabaseError> {
+    let collection = db.collection::<WatchlistEntry>(COLLECTION_NAME);
+
+    let target_index = IndexModel::builder()
+        .keys(doc! { "target": 1 })
+        .options(IndexOptions::builder().unique(true).build())
+        .build();
+
+    collection.create_index(target_index).await
+        .map_err(|e| DatabaseError::Query(format!("Failed to create watchlist index: {}", e)))?;
+
+    debug!("Created index for watchlist collection");
+    Ok(())
+}
+
+fn target_filter(target: &CollectTarget) -> Result<bson::Document, DatabaseError> {
+    let target = bson::to_bson(target)
+        .map_err(|e| DatabaseError::Query(format!("Failed to serialize watchlist target: {}", e)))?;
+    Ok(doc! { "target": target })
+}
+
+/// Watch an anime, returns false when it was already watched
+pub async fn add_entry(db: &Database, target: CollectTarget) -> Result<bool, DatabaseError> {
+    let collection = db.collection::<WatchlistEntry>(COLLECTION_NAME);
+    if collection.find_one(target_filter(&target)?).await
+        .map_err(|e| DatabaseError::Query(format!("Failed to find watchlist entry: {}", e)))?
+        .is_some()
+    {
+        return Ok(false);
+    }
+
+    let entry = WatchlistEntry {
+        mal_id: match &target {
+            CollectTarget::MalId(id) => Some(*id as i32),
+            CollectTarget::Title(_) => None,
+        },
+        target,
+        added_at: chrono::Utc::now(),
+        refreshed_at: None,
+    };
+
+    collection.insert_one(&entry).await
+        .map_err(|e| DatabaseError::Query(format!("Failed to add watchlist entry: {}", e)))?;
+
+    Ok(true)
+}
+
+/// Stop watching an anime, returns false when it wasn't watched
+pub async fn remove_entry(db: &Database, target: &CollectTarget) -> Result<bool, DatabaseError> {
+    let result = db.collection::<WatchlistEntry>(COLLECTION_NAME)
+        .delete_one(target_filter(target)?)
+        .await
+        .map_err(|e| DatabaseError::Query(format!("Failed to remove watchlist entry: {}", e)))?;
+
+    Ok(result.deleted_count > 0)
+}
+
+/// Watched anime, oldest first
+pub async fn list_entries(db: &Database) -> Result<Vec<WatchlistEntry>, DatabaseError> {
+    let mut cursor = db.collection::<WatchlistEntry>(COLLECTION_NAME)
+        .find(doc! {})
+        .sort(doc! { "added_at": 1 })
+        .await
+        .map_err(|e| DatabaseError::Query(format!("Failed to list watchlist: {}", e)))?;
+
+    let mut entries = Vec::new();
+    while let Some(result) = cursor.next().await {
+        match result {
+            Ok(entry) => entries.push(entry),
+            Err(e) => warn!(error = %e, "Failed to deserialize watchlist entry"),
+        }
+    }
+
+    Ok(entries)
+}
+
+async fn mark_refreshed(db: &Database, target: &CollectTarget, mal_id: Option<i32>) -> Result<(), DatabaseError> {
+    let refreshed_at = bson::to_bson(&chrono::Utc::now())
+        .map_err(|e| DatabaseError::Query(format!("Failed to serialize refresh date: {}", e)))?;
+
+    db.collection::<WatchlistEntry>(COLLECTION_NAME)
+        .update_one(target_filter(target)?, doc! { "$set": { "mal_id": mal_id, "refreshed_at": refreshed_at } })
+        .await
+        .map_err(|e| DatabaseError::Query(format!("Failed to update watchlist entry: {}", e)))?;
+
+    Ok(())
+}
+
+/// MAL id of the stored anime a watchlist entry points to, `None` when it isn't collected yet
+async fn resolve_stored(db: &Database, entry: &WatchlistEntry) -> Result<Option<i32>, DatabaseError> {
+    if let Some(mal_id) = entry.mal_id
+        && my_anime_list::database::anime_exists(db, mal_id).await?
+    {
+        return Ok(Some(mal_id));
+    }
+
+    let CollectTarget::Title(title) = &entry.target else {
+        return Ok(None);
+    };
+
+    let best = titles::fuzzy_search(db, title, 1).await?.into_iter()
+        .find(|hit| hit.source == TitleSource::Mal && hit.similarity >= RESOLVE_SIMILARITY);
+    Ok(best.map(|hit| hit.id))
+}
+
+// ========================================================================
+// Refresh Watchlist Task
+// ========================================================================
+
+/// Queues high priority episode, statistics and picture refreshes for every watched anime,
+/// and the complete collection of watched anime that aren't stored yet
+pub struct RefreshWatchlistTask {
+    id: String,
+    queue: TaskQueue,
+    jikan_client: ClientWithLimiter,
+    /// MAL API key and client, needed to collect watched anime that aren't stored
+    mal: Option<(String, ClientWithLimiter)>,
+    created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl RefreshWatchlistTask {
+    pub fn new(queue: TaskQueue, jikan_client: ClientWithLimiter) -> Self {
+        Self {
+            id: format!("refresh_watchlist_{}", uuid::Uuid::new_v4()),
+            queue,
+            jikan_client,
+            mal: None,
+            created_at: chrono::Utc::now(),
+        }
+    }
+
+    /// Collect watched anime that aren't stored yet
+    pub fn with_mal(mut self, api_key: String, mal_client: ClientWithLimiter) -> Self {
+        self.mal = Some((api_key, mal_client));
+        self
+    }
+
+    async fn queue_refresh(&self, mal_id: u32) -> Result<(), AppError> {
+        let tasks: Vec<Box<dyn Task>> = vec![
+            Box::new(
+                FetchEpisodesTask::new(mal_id, self.jikan_client.clone())
+                    .with_priority(TaskPriority::High)
+                    .with_incremental(true)
+            ),
+            Box::new(FetchStatisticsTask::new(mal_id, self.jikan_client.clone()).with_priority(TaskPriority::High)),
+            Box::new(FetchPicturesTask::new(mal_id, self.jikan_client.clone()).with_priority(TaskPriority::High)),
+        ];
+        for task in tasks {
+            self.queue.enqueue(task).await?;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl Task for RefreshWatchlistTask {
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+
+    fn name(&self) -> &str {
+        "refresh_watchlist"
+    }
+
+    fn priority(&self) -> TaskPriority {
+        TaskPriority::High
+    }
+
+    fn to_data(&self) -> TaskData {
+        TaskData::new(
+            self.id.clone(),
+            self.name().to_string(),
+            self.priority(),
+            self.created_at,
+            serde_json::json!({ "collect": self.mal.is_some() }),
+        )
+    }
+
+    async fn execute(&self, ctx: &TaskContext) -> Result<(), AppError> {
+        let db = ctx.db.clone();
+        let entries = list_entries(db.db()).await?;
+        let mut refreshed = 0;
+        let mut collected = 0;
+
+        for entry in &entries {
+            match resolve_stored(db.db(), entry).await? {
+                Some(mal_id) => {
+                    self.queue_refresh(mal_id as u32).await?;
+                    mark_refreshed(db.db(), &entry.target, Some(mal_id)).await?;
+                    refreshed += 1;
+                }
+                None => {
+                    let Some((api_key, mal_client)) = &self.mal else {
+                        warn!(task = %self.name(), target = %entry.target, "Watched anime not stored and MyAnimeList not configured, skipping");
+                        continue;
+                    };
+                    let task = CollectAnimeTask::new(
+                        entry.target.clone(),
+                        api_key.clone(),
+                        mal_client.clone(),
+                        self.jikan_client.clone(),
+                        self.queue.clone(),
+                    );
+                    self.queue.enqueue(Box::new(task)).await?;
+                    collected += 1;
+                }
+            }
+        }
+
+        info!(
+            task = %self.name(),
+            watched = entries.len(),
+            refreshed = refreshed,
+            collected = collected,
+            "Watchlist refresh queued"
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,44 @@
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::Response,
+};
+use tracing::warn;
+
+use crate::api::error::{ApiError, ErrorCode};
+use crate::api::namespace::API_KEY_HEADER;
+use crate::api::state::ApiState;
+
+/// Whether `key` is one of `api.admin.api_keys`
+pub fn is_admin_key(state: &ApiState, key: &str) -> bool {
+    state.config.load().api.admin.api_keys.iter().any(|allowed| allowed == key)
+}
+
+/// Reject requests to admin routes without one of `api.admin.api_keys` in X-Api-Key.
+/// The routes are disabled while no admin key is configured.
+pub async fn require_admin_key(
+    State(state): State<ApiState>,
+    request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    if state.config.load().api.admin.api_keys.is_empty() {
+        return Err(ApiError::new(
+            ErrorCode::Unauthorized,
+            "Admin routes are disabled, configure api.admin.api_keys",
+        ));
+    }
+
+    let key = request.headers()
+        .get(API_KEY_HEADER)
+        .and_then(|value| value.to_str().ok());
+
+    if !key.is_some_and(|key| is_admin_key(&state, key)) {
+        warn!(path = %request.uri().path(), "Rejected admin request without a valid admin API key");
+        return Err(ApiError::new(
+            ErrorCode::Unauthorized,
+            format!("An admin key is required in the {} header", API_KEY_HEADER),
+        ));
+    }
+
+    Ok(next.run(request).await)
+}
@@ -0,0 +1,68 @@
+use axum::{
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use tracing::error;
+
+/// Clients may reuse a response only after revalidating it with its ETag
+const CACHE_CONTROL: &str = "no-cache";
+
+/// JSON response with an ETag and Last-Modified, or an empty 304 Not Modified
+/// when `If-None-Match` already holds the ETag.
+///
+/// The ETag is a hash of the body rather than of `last_modified`, because extended data
+/// (episodes, characters, pictures) is refetched without changing the MAL `updated_at`.
+/// For the same reason `If-Modified-Since` is not honored.
+pub fn conditional_json<T: Serialize>(
+    request_headers: &HeaderMap,
+    value: &T,
+    last_modified: Option<DateTime<Utc>>,
+) -> Response {
+    let body = match serde_json::to_vec(value) {
+        Ok(body) => body,
+        Err(e) => {
+            error!(error = %e, "Failed to serialize response");
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+
+    // 128 bits of the body hash are plenty to tell versions of a document apart
+    let digest = format!("{:x}", Sha256::digest(&body));
+    let etag = format!("\"{}\"", &digest[..32]);
+
+    let mut headers = HeaderMap::new();
+    if let Ok(value) = HeaderValue::from_str(&etag) {
+        headers.insert(header::ETAG, value);
+    }
+    if let Some(last_modified) = last_modified
+        && let Ok(value) = HeaderValue::from_str(&http_date(last_modified))
+    {
+        headers.insert(header::LAST_MODIFIED, value);
+    }
+    headers.insert(header::CACHE_CONTROL, HeaderValue::from_static(CACHE_CONTROL));
+
+    if etag_matches(request_headers, &etag) {
+        return (StatusCode::NOT_MODIFIED, headers).into_response();
+    }
+
+    headers.insert(header::CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    (headers, body).into_response()
+}
+
+/// Whether `If-None-Match` contains `etag` or `*`, weak validators included
+fn etag_matches(request_headers: &HeaderMap, etag: &str) -> bool {
+    request_headers.get_all(header::IF_NONE_MATCH)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(|candidate| candidate.trim().trim_start_matches("W/"))
+        .any(|candidate| candidate == "*" || candidate == etag)
+}
+
+/// IMF-fixdate, e.g. "Sun, 06 Nov 1994 08:49:37 GMT"
+fn http_date(date: DateTime<Utc>) -> String {
+    date.format("%a, %d %b %Y %H:%M:%S GMT").to_string()
+}
@@ -0,0 +1,166 @@
+use crate::anime::my_anime_list::model::{
+    DataProvider, DayOfTheWeek, MediaType, NSFW, Rating, Season, Source, Status,
+};
+
+/// Language of the display labels, picked with `?lang=`. Other languages get English labels.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Locale {
+    #[default]
+    En,
+    Fr,
+    Ja,
+}
+
+impl Locale {
+    /// Locale of a language code or tag, e.g. "fr" or "fr-CA"
+    pub fn from_code(code: &str) -> Self {
+        let language = code.split(['-', '_']).next().unwrap_or_default();
+        match language.to_ascii_lowercase().as_str() {
+            "fr" => Self::Fr,
+            "ja" => Self::Ja,
+            _ => Self::En,
+        }
+    }
+}
+
+/// Stable value of an airing status, unknown statuses are kept as received
+pub fn status_code(status: &Status) -> String {
+    match status {
+        Status::FinishedAiring => "finished_airing".to_string(),
+        Status::CurrentlyAiring => "currently_airing".to_string(),
+        Status::NotYetAired => "not_yet_aired".to_string(),
+        Status::Other(status) => status.clone(),
+    }
+}
+
+pub fn status_label(status: &Status, locale: Locale) -> String {
+    let label = match (status, locale) {
+        (Status::FinishedAiring, Locale::En) => "Finished airing",
+        (Status::FinishedAiring, Locale::Fr) => "Diffusion terminée",
+        (Status::FinishedAiring, Locale::Ja) => "放送終了",
+        (Status::CurrentlyAiring, Locale::En) => "Currently airing",
+        (Status::CurrentlyAiring, Locale::Fr) => "En cours de diffusion",
+        (Status::CurrentlyAiring, Locale::Ja) => "放送中",
+        (Status::NotYetAired, Locale::En) => "Not yet aired",
+        (Status::NotYetAired, Locale::Fr) => "Pas encore diffusé",
+        (Status::NotYetAired, Locale::Ja) => "放送前",
+        (Status::Other(status), _) => status,
+    };
+    label.to_string()
+}
+
+pub fn season_label(season: &Season, locale: Locale) -> &'static str {
+    match (season, locale) {
+        (Season::Spring, Locale::En) => "Spring",
+        (Season::Spring, Locale::Fr) => "Printemps",
+        (Season::Spring, Locale::Ja) => "春",
+        (Season::Summer, Locale::En) => "Summer",
+        (Season::Summer, Locale::Fr) => "Été",
+        (Season::Summer, Locale::Ja) => "夏",
+        (Season::Fall, Locale::En) => "Fall",
+        (Season::Fall, Locale::Fr) => "Automne",
+        (Season::Fall, Locale::Ja) => "秋",
+        (Season::Winter, Locale::En) => "Winter",
+        (Season::Winter, Locale::Fr) => "Hiver",
+        (Season::Winter, Locale::Ja) => "冬",
+    }
+}
+
+/// Stable value of a media type, the one `/api/anime/random?type=` takes
+pub fn media_type_code(media_type: &MediaType) -> &'static str {
+    match media_type {
+        MediaType::TV => "tv",
+        MediaType::OVA => "ova",
+        MediaType::Movie => "movie",
+        MediaType::Special => "special",
+        MediaType::ONA => "ona",
+        MediaType::Music => "music",
+        MediaType::Unknown => "unknown",
+    }
+}
+
+pub fn media_type_label(media_type: &MediaType, locale: Locale) -> &'static str {
+    match (media_type, locale) {
+        (MediaType::TV, Locale::Fr) => "Série TV",
+        (MediaType::TV, _) => "TV",
+        (MediaType::OVA, _) => "OVA",
+        (MediaType::ONA, _) => "ONA",
+        (MediaType::Movie, Locale::En) => "Movie",
+        (MediaType::Movie, Locale::Fr) => "Film",
+        (MediaType::Movie, Locale::Ja) => "劇場版",
+        (MediaType::Special, Locale::En) => "Special",
+        (MediaType::Special, Locale::Fr) => "Spécial",
+        (MediaType::Special, Locale::Ja) => "特別編",
+        (MediaType::Music, Locale::En) => "Music",
+        (MediaType::Music, Locale::Fr) => "Musique",
+        (MediaType::Music, Locale::Ja) => "音楽",
+        (MediaType::Unknown, Locale::En) => "Unknown",
+        (MediaType::Unknown, Locale::Fr) => "Inconnu",
+        (MediaType::Unknown, Locale::Ja) => "不明",
+    }
+}
+
+/// Stable value of a data provider
+pub fn provider_code(provider: &DataProvider) -> &'static str {
+    match provider {
+        DataProvider::MyAnimeList => "my_anime_list",
+        DataProvider::Jikan => "jikan",
+    }
+}
+
+/// Age rating as shown on MyAnimeList, unknown ratings are kept as received
+pub fn rating_code(rating: &Rating) -> String {
+    match rating {
+        Rating::G => "G".to_string(),
+        Rating::PG => "PG".to_string(),
+        Rating::PG13 => "PG-13".to_string(),
+        Rating::R17Plus => "R-17+".to_string(),
+        Rating::RPlus => "R+".to_string(),
+        Rating::Rx => "Rx".to_string(),
+        Rating::Other(rating) => rating.clone(),
+    }
+}
+
+/// Stable value of a source material, unknown sources are kept as received
+pub fn source_code(source: &Source) -> String {
+    let code = match source {
+        Source::Original => "original",
+        Source::Manga => "manga",
+        Source::FourKomaManga => "4_koma_manga",
+        Source::WebManga => "web_manga",
+        Source::DigitalManga => "digital_manga",
+        Source::Novel => "novel",
+        Source::LightNovel => "light_novel",
+        Source::VisualNovel => "visual_novel",
+        Source::Game => "game",
+        Source::CardGame => "card_game",
+        Source::Book => "book",
+        Source::PictureBook => "picture_book",
+        Source::Radio => "radio",
+        Source::Music => "music",
+        Source::Other(source) => source,
+    };
+    code.to_string()
+}
+
+pub fn nsfw_code(nsfw: &NSFW) -> &'static str {
+    match nsfw {
+        NSFW::White => "white",
+        NSFW::Gray => "gray",
+        NSFW::Black => "black",
+    }
+}
+
+/// Broadcast day as Jikan spells it, e.g. "Sundays"
+pub fn day_code(day: &DayOfTheWeek) -> &'static str {
+    match day {
+        DayOfTheWeek::Sundays => "Sundays",
+        DayOfTheWeek::Mondays => "Mondays",
+        DayOfTheWeek::Tuesdays => "Tuesdays",
+        DayOfTheWeek::Wednesdays => "Wednesdays",
+        DayOfTheWeek::Thursdays => "Thursdays",
+        DayOfTheWeek::Fridays => "Fridays",
+        DayOfTheWeek::Saturdays => "Saturdays",
+        DayOfTheWeek::Other => "Other",
+    }
+}
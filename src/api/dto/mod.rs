@@ -0,0 +1,5 @@
+//! Response bodies of the API, by version. Storage models are converted into these
+//! before being serialized, so a model change doesn't change the JSON of a version.
+
+pub mod labels;
+pub mod v1;
@@ -0,0 +1,844 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::anime::my_anime_list::model::{self, AnimeData};
+use super::labels::{self, Locale};
+use crate::music::model::{self as music, ThemeKind};
+use crate::picture::model::{PictureMetadata, PictureStatus};
+use crate::video::model::{VideoMetadata, VideoStatus};
+
+/// Download state of a picture or video, the error of a failed download is next to it
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DownloadStatus {
+    Pending,
+    Downloading,
+    Completed,
+    Failed,
+}
+
+/// An anime as collected from MyAnimeList and Jikan
+#[derive(Debug, Clone, Serialize)]
+pub struct Anime {
+    /// Storage id, the anime is addressed by `mal_id`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub mal_id: i32,
+    /// "my_anime_list" or "jikan"
+    pub provider: String,
+    pub url: String,
+    pub images: Images,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub local_images: BTreeMap<String, LocalImage>,
+    pub trailer: Trailer,
+    pub approved: bool,
+    pub titles: Vec<Title>,
+    /// e.g. "tv" or "movie"
+    pub media_type: Option<String>,
+    pub media_type_label: Option<String>,
+    /// "white", "gray" or "black"
+    pub nsfw: Option<String>,
+    /// e.g. "light_novel"
+    pub source: Option<String>,
+    pub num_episodes: i32,
+    pub average_episode_duration: i32,
+    pub duration_seconds: Option<i32>,
+    /// e.g. "currently_airing"
+    pub status: Option<String>,
+    pub status_label: Option<String>,
+    pub airing: bool,
+    pub aired: Aired,
+    pub duration: String,
+    /// e.g. "PG-13"
+    pub rating: Option<String>,
+    pub score: Option<f32>,
+    pub scored_by: i32,
+    pub rank: Option<i32>,
+    pub members: i32,
+    pub favorites: i32,
+    pub popularity: Option<i32>,
+    pub synopsis: String,
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub synopses: BTreeMap<String, String>,
+    pub background: Option<String>,
+    /// e.g. "spring"
+    pub season: Option<String>,
+    pub season_label: Option<String>,
+    pub year: Option<i32>,
+    pub broadcast: Broadcast,
+    pub producers: Vec<Entity>,
+    pub licensors: Vec<Entity>,
+    pub studios: Vec<Entity>,
+    pub genres: Vec<Entity>,
+    pub explicit_genres: Vec<Entity>,
+    pub themes: Vec<Entity>,
+    pub demographics: Vec<Entity>,
+    pub relations: Vec<Relation>,
+    pub theme: Theme,
+    pub external: Vec<External>,
+    pub streaming: Vec<Streaming>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub collected_at: Option<DateTime<Utc>>,
+    pub characters: Vec<Character>,
+    pub staffs: Vec<Staff>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub episode_summary: Option<EpisodeSummary>,
+    pub videos: Option<Videos>,
+    pub pictures: Vec<Images>,
+    pub statistics: Option<Statistics>,
+    pub more_info: Option<String>,
+    pub recommendations: Vec<Recommendation>,
+    /// When each extended data section was last fetched
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub fetched_at: BTreeMap<String, DateTime<Utc>>,
+}
+
+impl Anime {
+    /// Anime with its status, season and media type labels in `locale`
+    pub fn localized(anime: AnimeData, locale: Locale) -> Self {
+        Self {
+            id: anime.id.map(|id| id.to_hex()),
+            mal_id: anime.mal_id,
+            provider: labels::provider_code(&anime.provider).to_string(),
+            url: anime.url,
+            images: anime.images.into(),
+            local_images: convert_map(anime.local_images),
+            trailer: anime.trailer.into(),
+            approved: anime.approved,
+            titles: convert(anime.titles),
+            media_type: anime.media_type.as_ref().map(|media_type| labels::media_type_code(media_type).to_string()),
+            media_type_label: anime.media_type.as_ref().map(|media_type| labels::media_type_label(media_type, locale).to_string()),
+            nsfw: anime.nsfw.as_ref().map(|nsfw| labels::nsfw_code(nsfw).to_string()),
+            source: anime.source.as_ref().map(labels::source_code),
+            num_episodes: anime.num_episodes,
+            average_episode_duration: anime.average_episode_duration,
+            duration_seconds: anime.duration_seconds,
+            status: anime.status.as_ref().map(labels::status_code),
+            status_label: anime.status.as_ref().map(|status| labels::status_label(status, locale)),
+            airing: anime.airing,
+            aired: anime.aired.into(),
+            duration: anime.duration,
+            rating: anime.rating.as_ref().map(labels::rating_code),
+            score: anime.score,
+            scored_by: anime.scored_by,
+            rank: anime.rank,
+            members: anime.members,
+            favorites: anime.favorites,
+            popularity: anime.popularity,
+            synopsis: anime.synopsis,
+            synopses: anime.synopses,
+            background: anime.background,
+            season: anime.season.as_ref().map(|season| season.as_str().to_string()),
+            season_label: anime.season.as_ref().map(|season| labels::season_label(season, locale).to_string()),
+            year: anime.year,
+            broadcast: anime.broadcast.into(),
+            producers: convert(anime.producers),
+            licensors: convert(anime.licensors),
+            studios: convert(anime.studios),
+            genres: convert(anime.genres),
+            explicit_genres: convert(anime.explicit_genres),
+            themes: convert(anime.themes),
+            demographics: convert(anime.demographics),
+            relations: convert(anime.relations),
+            theme: anime.theme.into(),
+            external: convert(anime.external),
+            streaming: convert(anime.streaming),
+            created_at: anime.created_at,
+            updated_at: anime.updated_at,
+            collected_at: anime.collected_at,
+            characters: convert(anime.characters),
+            staffs: convert(anime.staffs),
+            episode_summary: anime.episode_summary.map(Into::into),
+            videos: anime.videos.map(Into::into),
+            pictures: convert(anime.pictures),
+            statistics: anime.statistics.map(Into::into),
+            more_info: anime.more_info,
+            recommendations: convert(anime.recommendations),
+            fetched_at: anime.fetched_at,
+        }
+    }
+}
+
+impl From<AnimeData> for Anime {
+    fn from(anime: AnimeData) -> Self {
+        Self::localized(anime, Locale::default())
+    }
+}
+
+fn convert<T, U: From<T>>(items: Vec<T>) -> Vec<U> {
+    items.into_iter().map(U::from).collect()
+}
+
+fn convert_map<T, U: From<T>>(items: BTreeMap<String, T>) -> BTreeMap<String, U> {
+    items.into_iter().map(|(key, item)| (key, U::from(item))).collect()
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Images {
+    pub jpg: Image,
+    pub webp: Image,
+}
+
+impl From<model::Images> for Images {
+    fn from(images: model::Images) -> Self {
+        Self { jpg: images.jpg.into(), webp: images.webp.into() }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Image {
+    pub image_url: String,
+    pub small_image_url: String,
+    pub large_image_url: String,
+}
+
+impl From<model::Image> for Image {
+    fn from(image: model::Image) -> Self {
+        Self {
+            image_url: image.image_url,
+            small_image_url: image.small_image_url,
+            large_image_url: image.large_image_url,
+        }
+    }
+}
+
+/// Local copy of a provider image
+#[derive(Debug, Clone, Serialize)]
+pub struct LocalImage {
+    /// Provider URL the file was downloaded from
+    pub url: String,
+    pub file_path: String,
+}
+
+impl From<model::LocalImage> for LocalImage {
+    fn from(image: model::LocalImage) -> Self {
+        Self { url: image.url, file_path: image.file_path }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Trailer {
+    pub youtube_id: Option<String>,
+    pub url: Option<String>,
+    pub embed_url: Option<String>,
+}
+
+impl From<model::Trailer> for Trailer {
+    fn from(trailer: model::Trailer) -> Self {
+        Self { youtube_id: trailer.youtube_id, url: trailer.url, embed_url: trailer.embed_url }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Title {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<i32>,
+    /// e.g. "Default", "English" or "Japanese"
+    #[serde(rename = "type")]
+    pub title_type: String,
+    pub title: String,
+}
+
+impl From<model::Title> for Title {
+    fn from(title: model::Title) -> Self {
+        Self { id: title.id, title_type: title.title_type, title: title.title }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Aired {
+    pub from: Option<DateTime<Utc>>,
+    pub to: Option<DateTime<Utc>>,
+}
+
+impl From<model::Aired> for Aired {
+    fn from(aired: model::Aired) -> Self {
+        Self { from: aired.from, to: aired.to }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Broadcast {
+    /// e.g. "Sundays"
+    pub day: Option<String>,
+    pub time: Option<String>,
+    pub timezone: Option<String>,
+    pub string: Option<String>,
+}
+
+impl From<model::Broadcast> for Broadcast {
+    fn from(broadcast: model::Broadcast) -> Self {
+        Self {
+            day: broadcast.day.as_ref().map(|day| labels::day_code(day).to_string()),
+            time: broadcast.time,
+            timezone: broadcast.timezone,
+            string: broadcast.string,
+        }
+    }
+}
+
+/// Producer, licensor, studio, genre, theme, demographic or related entry on MyAnimeList
+#[derive(Debug, Clone, Serialize)]
+pub struct Entity {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<i32>,
+    pub mal_id: i32,
+    /// e.g. "anime" or "manga"
+    #[serde(rename = "type")]
+    pub entity_type: String,
+    pub name: String,
+    pub url: String,
+}
+
+macro_rules! entity_from {
+    ($($source:ident => $kind:ident),* $(,)?) => {
+        $(
+            impl From<model::$source> for Entity {
+                fn from(entity: model::$source) -> Self {
+                    Self {
+                        id: entity.id,
+                        mal_id: entity.mal_id,
+                        entity_type: entity.$kind,
+                        name: entity.name,
+                        url: entity.url,
+                    }
+                }
+            }
+        )*
+    };
+}
+
+entity_from! {
+    Producer => producer_type,
+    Licensors => licensor_type,
+    Studio => studio_type,
+    Genre => genre_type,
+    Themes => theme_type,
+    Demographic => demographic_type,
+    RelationEntry => entry_type,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Relation {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<i32>,
+    /// e.g. "Sequel"
+    pub relation: String,
+    pub entry: Vec<Entity>,
+}
+
+impl From<model::Relation> for Relation {
+    fn from(relation: model::Relation) -> Self {
+        Self { id: relation.id, relation: relation.relation, entry: convert(relation.entry) }
+    }
+}
+
+/// Opening and ending songs as listed by MyAnimeList
+#[derive(Debug, Clone, Serialize)]
+pub struct Theme {
+    pub openings: Vec<String>,
+    pub endings: Vec<String>,
+}
+
+impl From<model::Theme> for Theme {
+    fn from(theme: model::Theme) -> Self {
+        Self { openings: theme.openings, endings: theme.endings }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct External {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<i32>,
+    pub name: String,
+    pub url: String,
+}
+
+impl From<model::External> for External {
+    fn from(external: model::External) -> Self {
+        Self { id: external.id, name: external.name, url: external.url }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Streaming {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<i32>,
+    pub name: String,
+    pub url: String,
+    /// Episode title, for the streaming episodes of AniList
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    /// Episode thumbnail URL, for the streaming episodes of AniList
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thumbnail: Option<String>,
+}
+
+impl From<model::Streaming> for Streaming {
+    fn from(streaming: model::Streaming) -> Self {
+        Self {
+            id: streaming.id,
+            name: streaming.name,
+            url: streaming.url,
+            title: streaming.title,
+            thumbnail: streaming.thumbnail,
+        }
+    }
+}
+
+/// A character, voice actor or staff member
+#[derive(Debug, Clone, Serialize)]
+pub struct Person {
+    pub mal_id: i32,
+    pub url: String,
+    pub images: Images,
+    pub name: String,
+}
+
+macro_rules! person_from {
+    ($($source:ident),* $(,)?) => {
+        $(
+            impl From<model::$source> for Person {
+                fn from(person: model::$source) -> Self {
+                    Self {
+                        mal_id: person.mal_id,
+                        url: person.url,
+                        images: person.images.into(),
+                        name: person.name,
+                    }
+                }
+            }
+        )*
+    };
+}
+
+person_from!(CharacterInfo, VoiceActorInfo, StaffInfo);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Character {
+    pub character: Person,
+    /// "Main" or "Supporting"
+    pub role: String,
+    pub voice_actors: Vec<VoiceActor>,
+}
+
+impl From<model::Character> for Character {
+    fn from(character: model::Character) -> Self {
+        Self {
+            character: character.character.into(),
+            role: character.role,
+            voice_actors: convert(character.voice_actors),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct VoiceActor {
+    pub person: Person,
+    pub language: String,
+}
+
+impl From<model::VoiceActor> for VoiceActor {
+    fn from(voice_actor: model::VoiceActor) -> Self {
+        Self { person: voice_actor.person.into(), language: voice_actor.language }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Staff {
+    pub person: Person,
+    pub positions: Vec<String>,
+}
+
+impl From<model::Staff> for Staff {
+    fn from(staff: model::Staff) -> Self {
+        Self { person: staff.person.into(), positions: staff.positions }
+    }
+}
+
+/// Episode counts of an anime, updated with its episode list
+#[derive(Debug, Clone, Serialize)]
+pub struct EpisodeSummary {
+    pub total: u32,
+    pub filler: u32,
+    pub recap: u32,
+    /// Number of the last aired episode when the list was fetched
+    pub latest_aired: Option<i32>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<model::EpisodeSummary> for EpisodeSummary {
+    fn from(summary: model::EpisodeSummary) -> Self {
+        Self {
+            total: summary.total,
+            filler: summary.filler,
+            recap: summary.recap,
+            latest_aired: summary.latest_aired,
+            updated_at: summary.updated_at,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Videos {
+    pub promo: Vec<VideoPromo>,
+    pub episodes: Vec<VideoEpisode>,
+    pub music_videos: Vec<MusicVideo>,
+}
+
+impl From<model::Videos> for Videos {
+    fn from(videos: model::Videos) -> Self {
+        Self {
+            promo: convert(videos.promo),
+            episodes: convert(videos.episodes),
+            music_videos: convert(videos.music_videos),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct VideoPromo {
+    pub title: String,
+    pub trailer: VideoTrailer,
+}
+
+impl From<model::VideoPromoInfo> for VideoPromo {
+    fn from(promo: model::VideoPromoInfo) -> Self {
+        Self { title: promo.title, trailer: promo.trailer.into() }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct VideoEpisode {
+    pub mal_id: i32,
+    pub url: String,
+    pub title: String,
+    /// e.g. "Episode 1"
+    pub episode: String,
+    pub images: Images,
+}
+
+impl From<model::VideoEpisodeInfo> for VideoEpisode {
+    fn from(episode: model::VideoEpisodeInfo) -> Self {
+        Self {
+            mal_id: episode.mal_id,
+            url: episode.url,
+            title: episode.title,
+            episode: episode.episode,
+            images: episode.images.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct VideoTrailer {
+    pub youtube_id: Option<String>,
+    pub url: Option<String>,
+    pub embed_url: Option<String>,
+    pub images: Option<Images>,
+}
+
+impl From<model::VideoTrailer> for VideoTrailer {
+    fn from(trailer: model::VideoTrailer) -> Self {
+        Self {
+            youtube_id: trailer.youtube_id,
+            url: trailer.url,
+            embed_url: trailer.embed_url,
+            images: trailer.images.map(Into::into),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MusicVideo {
+    pub title: String,
+    pub video: VideoTrailer,
+    pub meta: MusicVideoMeta,
+}
+
+impl From<model::VideoMusicInfo> for MusicVideo {
+    fn from(music_video: model::VideoMusicInfo) -> Self {
+        Self {
+            title: music_video.title,
+            video: music_video.video.into(),
+            meta: MusicVideoMeta { title: music_video.meta.title, author: music_video.meta.author },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MusicVideoMeta {
+    pub title: Option<String>,
+    pub author: Option<String>,
+}
+
+/// Watching statuses and score distribution of MyAnimeList members
+#[derive(Debug, Clone, Serialize)]
+pub struct Statistics {
+    pub watching: i32,
+    pub completed: i32,
+    pub on_hold: i32,
+    pub dropped: i32,
+    pub plan_to_watch: i32,
+    pub total: i32,
+    pub scores: Vec<StatisticsScore>,
+}
+
+impl From<model::Statistics> for Statistics {
+    fn from(statistics: model::Statistics) -> Self {
+        Self {
+            watching: statistics.watching,
+            completed: statistics.completed,
+            on_hold: statistics.on_hold,
+            dropped: statistics.dropped,
+            plan_to_watch: statistics.plan_to_watch,
+            total: statistics.total,
+            scores: convert(statistics.scores),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StatisticsScore {
+    pub score: i32,
+    pub votes: i32,
+    pub percentage: f32,
+}
+
+impl From<model::StatisticsScore> for StatisticsScore {
+    fn from(score: model::StatisticsScore) -> Self {
+        Self { score: score.score, votes: score.votes, percentage: score.percentage }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Recommendation {
+    pub entry: RecommendedAnime,
+    pub url: String,
+    pub votes: i32,
+}
+
+impl From<model::Recommendation> for Recommendation {
+    fn from(recommendation: model::Recommendation) -> Self {
+        Self {
+            entry: RecommendedAnime {
+                mal_id: recommendation.entry.mal_id,
+                url: recommendation.entry.url,
+                images: recommendation.entry.images.into(),
+                title: recommendation.entry.title,
+            },
+            url: recommendation.url,
+            votes: recommendation.votes,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RecommendedAnime {
+    pub mal_id: i32,
+    pub url: String,
+    pub images: Images,
+    pub title: String,
+}
+
+/// A picture download and its stored file
+#[derive(Debug, Clone, Serialize)]
+pub struct Picture {
+    /// Storage id, addresses the picture in `/api/picture/{id}`
+    pub id: Option<String>,
+    pub url: String,
+    pub canonical_url: Option<String>,
+    pub file_path: String,
+    pub filename: String,
+    pub file_size: Option<u64>,
+    pub mime_type: Option<String>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub status: DownloadStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub tags: Vec<String>,
+    pub entity_type: Option<String>,
+    pub entity_id: Option<String>,
+    pub placeholder_color: Option<String>,
+    pub download_attempts: u32,
+    pub content_hash: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub downloaded_at: Option<DateTime<Utc>>,
+}
+
+impl From<PictureMetadata> for Picture {
+    fn from(picture: PictureMetadata) -> Self {
+        let (status, error) = match picture.status {
+            PictureStatus::Pending => (DownloadStatus::Pending, None),
+            PictureStatus::Downloading => (DownloadStatus::Downloading, None),
+            PictureStatus::Completed => (DownloadStatus::Completed, None),
+            PictureStatus::Failed { error } => (DownloadStatus::Failed, Some(error)),
+        };
+
+        Self {
+            id: picture.id.map(|id| id.to_hex()),
+            url: picture.url,
+            canonical_url: picture.canonical_url,
+            file_path: picture.file_path,
+            filename: picture.filename,
+            file_size: picture.file_size,
+            mime_type: picture.mime_type,
+            width: picture.width,
+            height: picture.height,
+            status,
+            error,
+            tags: picture.tags,
+            entity_type: picture.entity_type,
+            entity_id: picture.entity_id,
+            placeholder_color: picture.placeholder_color,
+            download_attempts: picture.download_attempts,
+            content_hash: picture.content_hash,
+            created_at: picture.created_at,
+            updated_at: picture.updated_at,
+            downloaded_at: picture.downloaded_at,
+        }
+    }
+}
+
+/// A video download and its stored file
+#[derive(Debug, Clone, Serialize)]
+pub struct Video {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub url: String,
+    pub video_id: Option<String>,
+    pub title: Option<String>,
+    pub file_path: Option<String>,
+    pub file_size: Option<u64>,
+    pub mime_type: Option<String>,
+    pub duration_seconds: Option<u32>,
+    pub status: DownloadStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    pub tags: Vec<String>,
+    pub entity_type: Option<String>,
+    pub entity_id: Option<String>,
+    pub download_attempts: u32,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub downloaded_at: Option<DateTime<Utc>>,
+}
+
+impl From<VideoMetadata> for Video {
+    fn from(video: VideoMetadata) -> Self {
+        let (status, error) = match video.status {
+            VideoStatus::Pending => (DownloadStatus::Pending, None),
+            VideoStatus::Downloading => (DownloadStatus::Downloading, None),
+            VideoStatus::Completed => (DownloadStatus::Completed, None),
+            VideoStatus::Failed { error } => (DownloadStatus::Failed, Some(error)),
+        };
+
+        Self {
+            id: video.id.map(|id| id.to_hex()),
+            url: video.url,
+            video_id: video.video_id,
+            title: video.title,
+            file_path: video.file_path,
+            file_size: video.file_size,
+            mime_type: video.mime_type,
+            duration_seconds: video.duration_seconds,
+            status,
+            error,
+            tags: video.tags,
+            entity_type: video.entity_type,
+            entity_id: video.entity_id,
+            download_attempts: video.download_attempts,
+            created_at: video.created_at,
+            updated_at: video.updated_at,
+            downloaded_at: video.downloaded_at,
+        }
+    }
+}
+
+/// Opening or ending of an anime
+#[derive(Debug, Clone, Serialize)]
+pub struct ThemeSong {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub mal_id: i32,
+    /// "opening" or "ending"
+    pub kind: String,
+    pub sequence: u32,
+    pub raw: String,
+    pub title: String,
+    pub title_native: Option<String>,
+    pub artist: Option<String>,
+    pub episodes: Vec<EpisodeRange>,
+    pub anisongdb: Option<AnisongDbSong>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<music::ThemeSong> for ThemeSong {
+    fn from(song: music::ThemeSong) -> Self {
+        Self {
+            id: song.id.map(|id| id.to_hex()),
+            mal_id: song.mal_id,
+            kind: match song.kind {
+                ThemeKind::Opening => "opening".to_string(),
+                ThemeKind::Ending => "ending".to_string(),
+            },
+            sequence: song.sequence,
+            raw: song.raw,
+            title: song.title,
+            title_native: song.title_native,
+            artist: song.artist,
+            episodes: convert(song.episodes),
+            anisongdb: song.anisongdb.map(Into::into),
+            updated_at: song.updated_at,
+        }
+    }
+}
+
+/// Episodes a theme song is used in, `end` equals `start` for a single episode
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct EpisodeRange {
+    pub start: u32,
+    pub end: u32,
+}
+
+impl From<music::EpisodeRange> for EpisodeRange {
+    fn from(range: music::EpisodeRange) -> Self {
+        Self { start: range.start, end: range.end }
+    }
+}
+
+/// Song entry of AnisongDB
+#[derive(Debug, Clone, Serialize)]
+pub struct AnisongDbSong {
+    pub ann_song_id: Option<i64>,
+    pub song_name: String,
+    pub artist: Option<String>,
+    pub composer: Option<String>,
+    pub arranger: Option<String>,
+    /// Audio sample of the song
+    pub audio_url: Option<String>,
+    /// Video samples of the song in high and medium quality
+    pub hq_url: Option<String>,
+    pub mq_url: Option<String>,
+}
+
+impl From<music::AnisongDbSong> for AnisongDbSong {
+    fn from(song: music::AnisongDbSong) -> Self {
+        Self {
+            ann_song_id: song.ann_song_id,
+            song_name: song.song_name,
+            artist: song.artist,
+            composer: song.composer,
+            arranger: song.arranger,
+            audio_url: song.audio_url,
+            hq_url: song.hq_url,
+            mq_url: song.mq_url,
+        }
+    }
+}
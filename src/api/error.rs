@@ -0,0 +1,207 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use axum::{
+    http::{header, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+use validator::ValidationErrors;
+
+use crate::anime::error::AnimeError;
+use crate::global::error::{AppError, DatabaseError, HttpError};
+
+/// Machine-readable reason of an API error, serialized in the `code` field
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    /// Invalid request parameters or body, per-field messages in `fields` when known
+    Validation,
+    /// Missing or wrong API key of a namespace
+    Unauthorized,
+    NotFound,
+    /// The module needed by the route is disabled or not configured
+    ModuleDisabled,
+    /// Mutating requests are rejected while the API is read-only
+    ReadOnly,
+    /// A provider rate limit was hit, see the Retry-After header
+    RateLimited,
+    /// A task queue is full, see `queue` and the Retry-After header
+    QueueFull,
+    /// A provider returned an error or an unexpected response
+    Upstream,
+    /// The work was interrupted, e.g. by a shutdown
+    Canceled,
+    Internal,
+}
+
+impl ErrorCode {
+    pub fn status(&self) -> StatusCode {
+        match self {
+            ErrorCode::Validation => StatusCode::UNPROCESSABLE_ENTITY,
+            ErrorCode::Unauthorized => StatusCode::UNAUTHORIZED,
+            ErrorCode::NotFound => StatusCode::NOT_FOUND,
+            ErrorCode::ModuleDisabled => StatusCode::SERVICE_UNAVAILABLE,
+            ErrorCode::ReadOnly | ErrorCode::Canceled => StatusCode::SERVICE_UNAVAILABLE,
+            ErrorCode::RateLimited | ErrorCode::QueueFull => StatusCode::TOO_MANY_REQUESTS,
+            ErrorCode::Upstream => StatusCode::BAD_GATEWAY,
+            ErrorCode::Internal => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+}
+
+/// Error returned by every API handler, rendered as `{ "error": "...", "code": "..." }`
+#[derive(Debug)]
+pub struct ApiError {
+    pub code: ErrorCode,
+    pub message: String,
+    /// Sent as Retry-After for rate limited errors
+    pub retry_after: Option<Duration>,
+    /// Messages of the invalid request fields, by field name
+    pub fields: Option<BTreeMap<String, Vec<String>>>,
+    /// Depth of the full queue for queue full errors
+    pub queue: Option<Box<QueueBackpressure>>,
+}
+
+/// State of a full task queue, so clients can back off
+#[derive(Debug, Serialize)]
+pub struct QueueBackpressure {
+    pub name: String,
+    pub depth: u64,
+    pub capacity: u64,
+    /// Estimated seconds before the queued tasks are done, absent before any task completed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub estimated_wait_secs: Option<u64>,
+}
+
+#[derive(Serialize)]
+struct ErrorBody {
+    error: String,
+    code: ErrorCode,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fields: Option<BTreeMap<String, Vec<String>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    queue: Option<Box<QueueBackpressure>>,
+}
+
+/// Retry-After of queue full errors before any task completed
+const DEFAULT_QUEUE_RETRY_AFTER: Duration = Duration::from_secs(30);
+
+impl ApiError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            retry_after: None,
+            fields: None,
+            queue: None,
+        }
+    }
+
+    pub fn validation(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Validation, message)
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::NotFound, message)
+    }
+
+    pub fn module_disabled(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::ModuleDisabled, message)
+    }
+
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Internal, message)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let status = self.code.status();
+        let retry_after = self.retry_after;
+        let body = ErrorBody {
+            error: self.message,
+            code: self.code,
+            fields: self.fields,
+            queue: self.queue,
+        };
+        let mut response = (status, Json(body)).into_response();
+
+        if let Some(retry_after) = retry_after
+            && let Ok(value) = HeaderValue::from_str(&retry_after.as_secs().max(1).to_string())
+        {
+            response.headers_mut().insert(header::RETRY_AFTER, value);
+        }
+        response
+    }
+}
+
+impl From<DatabaseError> for ApiError {
+    fn from(e: DatabaseError) -> Self {
+        Self::internal(format!("Database error: {}", e))
+    }
+}
+
+impl From<HttpError> for ApiError {
+    fn from(e: HttpError) -> Self {
+        match e {
+            HttpError::NotFound(_) => Self::not_found(e.to_string()),
+            HttpError::RateLimited { retry_after, .. } => Self {
+                retry_after,
+                ..Self::new(ErrorCode::RateLimited, e.to_string())
+            },
+            HttpError::CircuitOpen { retry_after, .. } => Self {
+                retry_after: Some(retry_after),
+                ..Self::new(ErrorCode::Upstream, e.to_string())
+            },
+            e => Self::new(ErrorCode::Upstream, e.to_string()),
+        }
+    }
+}
+
+impl From<AppError> for ApiError {
+    fn from(e: AppError) -> Self {
+        match e {
+            AppError::Database(e) => e.into(),
+            AppError::Http(e) => e.into(),
+            AppError::Anime(AnimeError::NotFound) => Self::not_found("Anime not found"),
+            AppError::Module(message) | AppError::Storage(message) => Self::internal(message),
+            AppError::Provider { .. } => Self::new(ErrorCode::Upstream, e.to_string()),
+            AppError::Validation(message) => Self::validation(message),
+            AppError::Canceled(message) => Self::new(ErrorCode::Canceled, message),
+            AppError::QueueFull { queue, depth, capacity, estimated_wait } => Self {
+                retry_after: Some(estimated_wait.unwrap_or(DEFAULT_QUEUE_RETRY_AFTER)),
+                queue: Some(Box::new(QueueBackpressure {
+                    estimated_wait_secs: estimated_wait.map(|wait| wait.as_secs()),
+                    name: queue.clone(),
+                    depth,
+                    capacity,
+                })),
+                ..Self::new(ErrorCode::QueueFull, format!("Queue {} is full, retry later", queue))
+            },
+        }
+    }
+}
+
+impl From<ValidationErrors> for ApiError {
+    fn from(errors: ValidationErrors) -> Self {
+        let fields: BTreeMap<String, Vec<String>> = errors.field_errors()
+            .into_iter()
+            .map(|(field, errors)| {
+                let messages = errors.iter()
+                    .map(|error| match &error.message {
+                        Some(message) => message.to_string(),
+                        None => format!("invalid value ({})", error.code),
+                    })
+                    .collect();
+                (field.to_string(), messages)
+            })
+            .collect();
+
+        Self {
+            fields: Some(fields),
+            ..Self::validation("Invalid request")
+        }
+    }
+}
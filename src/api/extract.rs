@@ -0,0 +1,56 @@
+use axum::{
+    extract::{FromRequest, Request},
+    Json,
+};
+use serde::de::DeserializeOwned;
+use validator::{Validate, ValidateUrl, ValidationError};
+
+use crate::api::error::ApiError;
+
+/// JSON body deserialized then validated, malformed or invalid bodies are rejected with a 422
+pub struct ValidatedJson<T>(pub T);
+
+impl<S, T> FromRequest<S> for ValidatedJson<T>
+where
+    S: Send + Sync,
+    T: DeserializeOwned + Validate,
+{
+    type Rejection = ApiError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let Json(value) = Json::<T>::from_request(req, state)
+            .await
+            .map_err(|rejection| ApiError::validation(rejection.body_text()))?;
+
+        value.validate()?;
+        Ok(ValidatedJson(value))
+    }
+}
+
+/// Every id of a list is a valid MAL id
+pub fn validate_ids(ids: &[u32]) -> Result<(), ValidationError> {
+    if ids.contains(&0) {
+        return Err(ValidationError::new("range").with_message("ids must be greater than 0".into()));
+    }
+    Ok(())
+}
+
+/// Every URL of a list is an absolute http(s) URL
+pub fn validate_urls(urls: &[String]) -> Result<(), ValidationError> {
+    if let Some(url) = urls.iter().find(|url| !is_http_url(url)) {
+        return Err(ValidationError::new("url").with_message(format!("'{}' is not an http(s) URL", url).into()));
+    }
+    Ok(())
+}
+
+/// Single URL variant of `validate_urls`
+pub fn validate_url(url: &str) -> Result<(), ValidationError> {
+    if !is_http_url(url) {
+        return Err(ValidationError::new("url").with_message("must be an http(s) URL".into()));
+    }
+    Ok(())
+}
+
+fn is_http_url(url: &str) -> bool {
+    url.validate_url() && (url.starts_with("http://") || url.starts_with("https://"))
+}
@@ -0,0 +1,50 @@
+use serde::{Deserialize, Serialize};
+
+/// Sparse fieldset of anime responses: `?fields=titles,score,images`
+#[derive(Debug, Default, Deserialize)]
+pub struct FieldsQuery {
+    #[serde(default)]
+    pub fields: Option<String>,
+}
+
+impl FieldsQuery {
+    /// Requested top-level fields, `None` for the whole document.
+    /// Names are restricted to lowercase letters, digits and underscores,
+    /// so they can't address nested fields or MongoDB operators.
+    pub fn parse(&self) -> Result<Option<Vec<String>>, String> {
+        let Some(raw) = &self.fields else {
+            return Ok(None);
+        };
+
+        let fields: Vec<String> = raw.split(',')
+            .map(str::trim)
+            .filter(|field| !field.is_empty())
+            .map(str::to_string)
+            .collect();
+
+        if fields.is_empty() {
+            return Ok(None);
+        }
+        if let Some(invalid) = fields.iter().find(|field| {
+            !field.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+        }) {
+            return Err(format!("Invalid field name '{}'", invalid));
+        }
+
+        Ok(Some(fields))
+    }
+}
+
+/// JSON of a v1 anime keeping `mal_id` and the requested top-level fields, so a sparse
+/// fieldset has the same shape as the full response. The anime is read with a projection of
+/// those fields, the others are empty.
+pub fn select<T: Serialize>(body: &T, fields: &[String]) -> serde_json::Value {
+    match serde_json::to_value(body) {
+        Ok(serde_json::Value::Object(mut object)) => {
+            object.retain(|key, _| key == "mal_id" || fields.iter().any(|field| field == key));
+            serde_json::Value::Object(object)
+        }
+        Ok(value) => value,
+        Err(_) => serde_json::Value::Null,
+    }
+}
@@ -1,3 +1,11 @@
+pub mod admin_key;
+pub mod cache;
+pub mod dto;
+pub mod error;
+pub mod extract;
+pub mod fields;
+pub mod namespace;
+pub mod read_only;
 pub mod state;
 pub mod routes;
 pub mod server;
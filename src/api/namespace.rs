@@ -0,0 +1,189 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::Response,
+};
+use tracing::warn;
+
+use crate::anime::auto_pictures::spawn_auto_pictures;
+use crate::anime::mal_backfill::spawn_mal_backfill;
+use crate::anime::module::AnimeModule;
+use crate::anime::picture_gc::spawn_picture_gc;
+use crate::api::admin_key;
+use crate::api::error::{ApiError, ErrorCode};
+use crate::api::state::ApiState;
+use crate::global::config::{NamespaceConfig, PictureConfig, VideoConfig};
+use crate::global::database::DatabaseInstance;
+use crate::global::events::EventBus;
+use crate::global::module::{ModuleHandle, ParentModule};
+use crate::global::supervisor::ModuleSupervisor;
+use crate::picture::PictureFetcherModule;
+use crate::video::VideoModule;
+use crate::music::MusicModule;
+
+/// Header carrying the API key of a namespace
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// An isolated library served under /ns/{name}/.
+///
+/// Its anime, pictures, videos, theme songs, tasks and jobs live in its own database, and its
+/// tasks run on its own anime, picture, video and music modules. They run under the namespace's
+/// own supervisor, are paused and resumed with /ns/{name}/api/modules and shut down with the
+/// default library. Statistics snapshots, watchlist and stale section refreshes only run for
+/// the default library.
+///
+/// Its tasks publish to its own event bus, listened to by its own auto pictures, picture garbage
+/// collection and MyAnimeList backfill. Webhooks and Discord only get the default library's events.
+#[derive(Clone)]
+pub struct Namespace {
+    pub name: String,
+    pub state: ApiState,
+    api_keys: Arc<Vec<String>>,
+}
+
+impl Namespace {
+    /// Build the namespace state from the default one and start a module for each parent module
+    /// that runs for the default library
+    pub async fn new(name: &str, config: &NamespaceConfig, base: &ApiState, db: Arc<DatabaseInstance>) -> Self {
+        let app_config = base.config.load_full();
+        let events = EventBus::new();
+
+        let anime_module = base.anime_module.as_ref().map(|_| {
+            Arc::new(AnimeModule::new(
+                db.clone(),
+                base.http_manager.my_anime_list().client.clone(),
+                &app_config.anime,
+                events.clone(),
+            ))
+        });
+
+        let picture_module = base.picture_module.as_ref().map(|_| {
+            let picture_config = PictureConfig {
+                storage_path: Path::new(&app_config.picture.storage_path)
+                    .join("namespaces")
+                    .join(name)
+                    .to_string_lossy()
+                    .into_owned(),
+                ..app_config.picture.clone()
+            };
+            Arc::new(PictureFetcherModule::new(
+                db.clone(),
+                base.http_manager.default().client.clone(),
+                &picture_config,
+                events.clone(),
+            ))
+        });
+
+        let video_module = base.video_module.as_ref().map(|_| {
+            let video_config = VideoConfig {
+                storage_path: Path::new(&app_config.video.storage_path)
+                    .join("namespaces")
+                    .join(name)
+                    .to_string_lossy()
+                    .into_owned(),
+                ..app_config.video.clone()
+            };
+            Arc::new(VideoModule::new(
+                db.clone(),
+                base.http_manager.default().client.clone(),
+                &video_config,
+            ))
+        });
+
+        let music_module = base.music_module.as_ref().map(|_| {
+            Arc::new(MusicModule::new(
+                db.clone(),
+                base.http_manager.default().client.clone(),
+                &app_config.music,
+            ))
+        });
+
+        let supervisor = ModuleSupervisor::new(app_config.supervisor.clone());
+        let parents: [(&str, Option<Arc<dyn ParentModule>>); 4] = [
+            ("anime", anime_module.clone().map(|module| module as Arc<dyn ParentModule>)),
+            ("picture", picture_module.clone().map(|module| module as Arc<dyn ParentModule>)),
+            ("video", video_module.clone().map(|module| module as Arc<dyn ParentModule>)),
+            ("music", music_module.clone().map(|module| module as Arc<dyn ParentModule>)),
+        ];
+        let mut module_handles = Vec::new();
+        for (config_name, module) in parents {
+            if let Some(module) = module {
+                module_handles.push(supervisor.spawn(config_name, module, db.clone()).await);
+            }
+        }
+
+        let state = ApiState {
+            namespace: Some(name.to_string()),
+            db,
+            anime_module,
+            picture_module,
+            video_module,
+            music_module,
+            mal_module: None,
+            anilist_module: None,
+            events: events.clone(),
+            module_statuses: Some(supervisor.statuses()),
+            module_handles,
+            ..base.clone()
+        }
+        .with_provider_modules();
+
+        if app_config.anime.auto_pictures
+            && let Some(picture_module) = &state.picture_module
+        {
+            spawn_auto_pictures(&events, picture_module.clone());
+        }
+        if app_config.picture.collect_garbage {
+            spawn_picture_gc(&events, state.db.clone());
+        }
+        if app_config.anime.mal_backfill
+            && let Some(mal_module) = &state.mal_module
+        {
+            spawn_mal_backfill(&events, state.db.clone(), mal_module.clone());
+        }
+
+        Self {
+            name: name.to_string(),
+            state,
+            api_keys: Arc::new(config.api_keys.clone()),
+        }
+    }
+
+    /// Control handles of the namespace's modules, shut down with the default library's
+    pub fn module_handles(&self) -> &[ModuleHandle] {
+        &self.state.module_handles
+    }
+}
+
+/// Reject requests to a namespace without one of its API keys.
+/// Admin keys are accepted too, the admin routes of a namespace require one.
+pub async fn require_api_key(
+    State(namespace): State<Namespace>,
+    request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    if namespace.api_keys.is_empty() {
+        return Ok(next.run(request).await);
+    }
+
+    let key = request.headers()
+        .get(API_KEY_HEADER)
+        .and_then(|value| value.to_str().ok());
+
+    match key {
+        Some(key) if namespace.api_keys.iter().any(|allowed| allowed == key) || admin_key::is_admin_key(&namespace.state, key) => {
+            Ok(next.run(request).await)
+        }
+        Some(_) => {
+            warn!(namespace = %namespace.name, path = %request.uri().path(), "Rejected namespace request with an unknown API key");
+            Err(ApiError::new(ErrorCode::Unauthorized, "Invalid API key for this namespace"))
+        }
+        None => Err(ApiError::new(
+            ErrorCode::Unauthorized,
+            format!("The {} header is required for this namespace", API_KEY_HEADER),
+        )),
+    }
+}
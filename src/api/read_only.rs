@@ -0,0 +1,30 @@
+use axum::{
+    extract::{Request, State},
+    http::Method,
+    middleware::Next,
+    response::Response,
+};
+
+use crate::api::error::{ApiError, ErrorCode};
+use crate::api::state::ApiState;
+
+/// The read-only toggle itself stays available while read-only, it is guarded by the admin keys
+pub const READ_ONLY_TOGGLE_PATH: &str = "/api/admin/read-only";
+
+/// Reject every request that isn't a read while the API is read-only
+pub async fn reject_mutations(
+    State(state): State<ApiState>,
+    request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let is_read = matches!(*request.method(), Method::GET | Method::HEAD | Method::OPTIONS);
+
+    if !is_read && state.is_read_only() && request.uri().path() != READ_ONLY_TOGGLE_PATH {
+        return Err(ApiError::new(
+            ErrorCode::ReadOnly,
+            "The API is in read-only mode, only reads are available",
+        ));
+    }
+
+    Ok(next.run(request).await)
+}
@@ -0,0 +1,261 @@
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+use tracing::{error, info};
+
+use crate::api::{error::ApiError, extract::ValidatedJson};
+use crate::anime::duplicates::{self, DetectDuplicatesTask, DuplicateReport, DuplicateSource, MergeReport};
+use crate::anime::error::AnimeError;
+use crate::anime::picture_gc::CollectPictureGarbageTask;
+use crate::anime::quality::{self, QualityAction, QualityEntry, ValidateCollectionTask};
+use crate::anime::reprocess::ReprocessTask;
+use crate::anime::titles::RebuildTitleIndexTask;
+use crate::api::state::ApiState;
+use crate::global::error::AppError;
+use crate::global::events::DataEvent;
+use crate::global::queue::Task;
+
+// ========================================================================
+// Request/Response Types
+// ========================================================================
+
+#[derive(Serialize)]
+pub struct TaskQueuedResponse {
+    pub task_id: String,
+    pub message: String,
+}
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct MergeDuplicatesRequest {
+    pub source: DuplicateSource,
+    /// Entry that is kept, MAL id or AniList id depending on `source`
+    #[validate(range(min = 1, message = "must be an id greater than 0"))]
+    pub keep: i32,
+    /// Entries merged into `keep` and deleted
+    #[validate(length(min = 1, max = 100, message = "must contain between 1 and 100 ids"))]
+    pub remove: Vec<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct QualityQuery {
+    /// Only entries needing this fetch
+    #[serde(default)]
+    pub action: Option<QualityAction>,
+    #[serde(default = "default_quality_limit")]
+    pub limit: i64,
+}
+
+fn default_quality_limit() -> i64 {
+    100
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ReadOnlyRequest {
+    pub enabled: bool,
+}
+
+#[derive(Serialize)]
+pub struct ReadOnlyResponse {
+    pub read_only: bool,
+}
+
+#[derive(Serialize)]
+pub struct QualityResponse {
+    pub anime: Vec<QualityEntry>,
+    pub count: usize,
+}
+
+// ========================================================================
+// Handlers
+// ========================================================================
+
+/// Whether mutating requests are currently rejected
+/// GET /api/admin/read-only
+pub async fn get_read_only(
+    State(state): State<ApiState>,
+) -> Json<ReadOnlyResponse> {
+    Json(ReadOnlyResponse {
+        read_only: state.is_read_only(),
+    })
+}
+
+/// Enable or disable read-only mode until the next toggle or restart.
+/// Requires one of `api.admin.api_keys` in X-Api-Key, disabled when none is configured.
+/// POST /api/admin/read-only
+/// Body: { "enabled": true }
+pub async fn set_read_only(
+    State(state): State<ApiState>,
+    Json(request): Json<ReadOnlyRequest>,
+) -> Result<Json<ReadOnlyResponse>, ApiError> {
+    info!(enabled = request.enabled, "API request: set read-only mode");
+    state.set_read_only(request.enabled);
+
+    Ok(Json(ReadOnlyResponse {
+        read_only: request.enabled,
+    }))
+}
+
+/// Queue a scan for duplicate anime entries
+/// POST /api/admin/duplicates/scan
+pub async fn scan_duplicates(
+    State(state): State<ApiState>,
+) -> Result<Json<TaskQueuedResponse>, ApiError> {
+    let task = DetectDuplicatesTask::new();
+    let task_id = task.id();
+    queue_task(&state, Box::new(task)).await?;
+
+    Ok(Json(TaskQueuedResponse {
+        message: format!("Duplicate scan queued, results at /api/admin/duplicates once task {} completed", task_id),
+        task_id,
+    }))
+}
+
+/// Result of the most recent duplicate scan
+/// GET /api/admin/duplicates
+pub async fn get_duplicates(
+    State(state): State<ApiState>,
+) -> Result<Json<DuplicateReport>, ApiError> {
+    let report = duplicates::latest_report(&state.db)
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Failed to get duplicate report");
+            ApiError::from(e)
+        })?
+        .ok_or_else(|| ApiError::not_found("No duplicate scan yet, start one with POST /api/admin/duplicates/scan"))?;
+
+    Ok(Json(report))
+}
+
+/// Merge duplicate entries into one, moving their extended data and pictures
+/// POST /api/admin/duplicates/merge
+/// Body: { "source": "mal", "keep": 52991, "remove": [52992] }
+pub async fn merge_duplicates(
+    State(state): State<ApiState>,
+    ValidatedJson(request): ValidatedJson<MergeDuplicatesRequest>,
+) -> Result<Json<MergeReport>, ApiError> {
+    info!(
+        source = ?request.source,
+        keep = request.keep,
+        remove = ?request.remove,
+        "API request: merge duplicates"
+    );
+
+    if request.remove.iter().all(|id| *id == request.keep) {
+        return Err(ApiError::validation("remove must contain at least one id other than keep"));
+    }
+
+    let report = duplicates::merge_duplicates(&state.db, request.source, request.keep, &request.remove)
+        .await
+        .map_err(|e| match e {
+            AppError::Anime(AnimeError::NotFound) => ApiError::not_found("One of the anime to merge is not stored"),
+            e => {
+                error!(error = %e, "Failed to merge duplicates");
+                ApiError::from(e)
+            }
+        })?;
+
+    for &id in &report.removed {
+        state.events.publish(DataEvent::AnimeDeleted { source: report.source.into(), id: id as u32 });
+    }
+
+    Ok(Json(report))
+}
+
+/// Queue a completeness validation of the stored anime
+/// POST /api/admin/quality/validate
+pub async fn validate_collection(
+    State(state): State<ApiState>,
+) -> Result<Json<TaskQueuedResponse>, ApiError> {
+    let task = ValidateCollectionTask::new();
+    let task_id = task.id();
+    queue_task(&state, Box::new(task)).await?;
+
+    Ok(Json(TaskQueuedResponse {
+        message: format!("Validation queued, results at /api/admin/quality once task {} completed", task_id),
+        task_id,
+    }))
+}
+
+/// Anime needing a re-fetch or an extended fetch according to the last validation,
+/// least complete and most popular first
+/// GET /api/admin/quality?action=refetch&limit=100
+pub async fn get_quality(
+    State(state): State<ApiState>,
+    Query(query): Query<QualityQuery>,
+) -> Result<Json<QualityResponse>, ApiError> {
+    let anime = quality::incomplete_anime(&state.db, query.action, query.limit.clamp(1, 1000))
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Failed to get quality results");
+            ApiError::from(e)
+        })?;
+
+    Ok(Json(QualityResponse {
+        count: anime.len(),
+        anime,
+    }))
+}
+
+/// Queue a rebuild of the title index used by the fuzzy search
+/// POST /api/admin/titles/reindex
+pub async fn reindex_titles(
+    State(state): State<ApiState>,
+) -> Result<Json<TaskQueuedResponse>, ApiError> {
+    let task = RebuildTitleIndexTask::new();
+    let task_id = task.id();
+    queue_task(&state, Box::new(task)).await?;
+
+    Ok(Json(TaskQueuedResponse {
+        message: format!("Title index rebuild queued as task {}", task_id),
+        task_id,
+    }))
+}
+
+/// Queue a run of the converters over the archived provider responses, storing the corrected anime
+/// POST /api/admin/reprocess
+pub async fn reprocess_raw_responses(
+    State(state): State<ApiState>,
+) -> Result<Json<TaskQueuedResponse>, ApiError> {
+    info!("API request: reprocess archived provider responses");
+
+    let task = ReprocessTask::new();
+    let task_id = task.id();
+    queue_task(&state, Box::new(task)).await?;
+
+    Ok(Json(TaskQueuedResponse {
+        message: format!("Reprocessing of archived provider responses queued as task {}", task_id),
+        task_id,
+    }))
+}
+
+/// Queue the deletion of the pictures left by anime that are no longer stored
+/// POST /api/admin/pictures/gc
+pub async fn collect_picture_garbage(
+    State(state): State<ApiState>,
+) -> Result<Json<TaskQueuedResponse>, ApiError> {
+    info!("API request: collect pictures of removed anime");
+
+    let task = CollectPictureGarbageTask::new();
+    let task_id = task.id();
+    queue_task(&state, Box::new(task)).await?;
+
+    Ok(Json(TaskQueuedResponse {
+        message: format!("Picture garbage collection queued as task {}", task_id),
+        task_id,
+    }))
+}
+
+async fn queue_task(state: &ApiState, task: Box<dyn Task>) -> Result<(), ApiError> {
+    let anime_module = state.anime_module.as_ref()
+        .ok_or_else(|| ApiError::module_disabled("Anime module is not enabled"))?;
+    state.check_anime_queue()?;
+
+    anime_module.queue().enqueue(task).await
+        .map_err(|e| {
+            error!(error = %e, "Failed to queue admin task");
+            ApiError::internal(format!("Failed to queue task: {}", e))
+        })
+}
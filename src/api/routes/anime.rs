@@ -1,20 +1,34 @@
 use axum::{
-    extract::{Path, State},
-    http::StatusCode,
+    extract::{Path, Query, State},
+    http::{header, HeaderMap},
+    response::{IntoResponse, Response},
     Json,
 };
 use serde::{Deserialize, Serialize};
-use tracing::{info, error};
+use validator::Validate;
+use tracing::{info, error, warn};
 
-use crate::{anime::anilist::AniListModule, api::state::ApiState};
+use crate::api::{error::ApiError, extract::{validate_ids, ValidatedJson}};
+use crate::{anime::anilist::AniListModule, api::{cache, dto::{labels::Locale, v1}, fields::{self, FieldsQuery}, state::ApiState}};
+use crate::anime::airing;
+use crate::anime::episodes::{self, AnimeEpisode, EpisodeFilter};
+use crate::anime::freshness::{self, Completeness, SectionToFetch};
+use crate::anime::collect::CollectTarget;
+use crate::anime::related::{self, RelatedGraph};
+use crate::anime::titles::{self, TitleMatch};
 use crate::anime::my_anime_list;
+use crate::anime::my_anime_list::database::{RandomAnimeFilter, SearchCount, SearchCountOrder};
+use crate::anime::my_anime_list::model::{AnimeHistoryEntry, SearchResults, StatisticsSnapshot};
+use crate::global::{job, queue::{self, TaskStatus}};
+use crate::picture::{self, model::PictureStatus};
 
 // ========================================================================
 // Request/Response Types
 // ========================================================================
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Validate)]
 pub struct FetchAnimeRequest {
+    #[validate(range(min = 1, message = "must be a MAL id greater than 0"))]
     pub anime_id: u32,
     #[serde(default)]
     pub with_jikan: bool,
@@ -24,10 +38,12 @@ pub struct FetchAnimeRequest {
     pub full_fetch: bool,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Validate)]
 pub struct SearchAnimeRequest {
+    #[validate(length(min = 1, max = 200, message = "must be between 1 and 200 characters"))]
     pub query: String,
     #[serde(default = "default_limit")]
+    #[validate(range(min = 1, max = 100, message = "must be between 1 and 100"))]
     pub limit: u32,
 }
 
@@ -35,8 +51,9 @@ fn default_limit() -> u32 {
     10
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Validate)]
 pub struct UpdateAnimeRequest {
+    #[validate(range(min = 1, message = "must be a MAL id greater than 0"))]
     pub anime_id: u32,
     #[serde(default)]
     pub with_jikan: bool,
@@ -46,8 +63,12 @@ pub struct UpdateAnimeRequest {
     pub full_fetch: bool,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Validate)]
 pub struct BatchFetchRequest {
+    #[validate(
+        length(min = 1, max = 100, message = "must contain between 1 and 100 ids"),
+        custom(function = "validate_ids")
+    )]
     pub anime_ids: Vec<u32>,
     #[serde(default)]
     pub with_jikan: bool,
@@ -57,8 +78,9 @@ pub struct BatchFetchRequest {
     pub full_fetch: bool,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Validate)]
 pub struct FetchExtendedDataRequest {
+    #[validate(range(min = 1, message = "must be a MAL id greater than 0"))]
     pub anime_id: u32,
     #[serde(default)]
     pub fetch_characters: bool,
@@ -66,6 +88,16 @@ pub struct FetchExtendedDataRequest {
     pub fetch_staff: bool,
     #[serde(default)]
     pub fetch_episodes: bool,
+    /// For airing anime, only fetch the episodes after the stored ones
+    #[serde(default)]
+    pub incremental_episodes: bool,
+    /// One request per episode, for the synopsis and duration
+    #[serde(default)]
+    pub fetch_episode_details: bool,
+    /// Episodes whose details are fetched, the stored episodes without details when empty
+    #[serde(default)]
+    #[validate(length(max = 500, message = "must list at most 500 episodes"))]
+    pub episodes: Vec<u32>,
     #[serde(default)]
     pub fetch_moreinfo: bool,
     #[serde(default)]
@@ -74,20 +106,274 @@ pub struct FetchExtendedDataRequest {
     pub fetch_recommendations: bool,
 }
 
+#[derive(Debug, Deserialize, Validate)]
+pub struct CollectAnimeRequest {
+    #[validate(range(min = 1, message = "must be a MAL id greater than 0"))]
+    pub anime_id: Option<u32>,
+    #[validate(length(max = 200, message = "must be at most 200 characters"))]
+    pub title: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AiringCalendarQuery {
+    /// Comma separated MAL ids, all airing anime when omitted
+    #[serde(default)]
+    pub ids: Option<String>,
+    #[serde(default = "default_airing_days")]
+    pub days: i64,
+}
+
+fn default_airing_days() -> i64 {
+    14
+}
+
+/// Preferred synopsis and label language: `?lang=ja`
+#[derive(Debug, Deserialize, Validate)]
+pub struct LanguageQuery {
+    #[validate(length(min = 2, max = 8, message = "must be a language code like \"en\" or \"ja\""))]
+    #[serde(default)]
+    pub lang: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct HistoryQuery {
+    #[serde(default = "default_history_limit")]
+    pub limit: i64,
+}
+
+fn default_history_limit() -> i64 {
+    50
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CompleteAnimeQuery {
+    /// Only report the sections that would be fetched
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Sections fetched longer ago are fetched again, `anime.section_refresh_days` (or the
+    /// `auto_refresh` feature age) by default.
+    /// 0 only fetches the empty sections.
+    pub max_age_days: Option<u64>,
+}
+
 #[derive(Serialize)]
-pub struct TaskQueuedResponse {
+pub struct CompleteAnimeResponse {
+    pub anime_id: i32,
+    pub dry_run: bool,
+    pub sections: Vec<SectionToFetch>,
+    pub message: String,
+}
+
+#[derive(Serialize)]
+pub struct CompletenessResponse {
+    pub anime_id: i32,
+    #[serde(flatten)]
+    pub completeness: Completeness,
+}
+
+#[derive(Serialize)]
+pub struct AnimeHistoryResponse {
+    pub anime_id: i32,
+    pub history: Vec<AnimeHistoryEntry>,
+    pub count: usize,
+}
+
+#[derive(Serialize)]
+pub struct AnimeEpisodesResponse {
+    pub anime_id: i32,
+    pub episodes: Vec<AnimeEpisode>,
+    pub count: usize,
+}
+
+#[derive(Serialize)]
+pub struct AnimeEpisodeResponse {
+    pub episode: AnimeEpisode,
+}
+
+/// Consecutive episodes, `end` included
+#[derive(Serialize)]
+pub struct EpisodeRange {
+    pub start: i32,
+    pub end: i32,
+}
+
+#[derive(Serialize)]
+pub struct FillerListResponse {
+    pub anime_id: i32,
+    pub filler: Vec<EpisodeRange>,
+    pub recap: Vec<EpisodeRange>,
+    pub filler_count: usize,
+    pub recap_count: usize,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TrendsQuery {
+    #[serde(default = "default_trends_days")]
+    pub days: i64,
+}
+
+fn default_trends_days() -> i64 {
+    90
+}
+
+#[derive(Serialize)]
+pub struct AnimeTrendsResponse {
+    pub anime_id: i32,
+    pub days: i64,
+    pub snapshots: Vec<StatisticsSnapshot>,
+    pub count: usize,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RelatedQuery {
+    #[serde(default = "default_related_depth")]
+    pub depth: u32,
+}
+
+fn default_related_depth() -> u32 {
+    1
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FuzzySearchQuery {
+    pub q: String,
+    #[serde(default = "default_fuzzy_limit")]
+    pub limit: usize,
+}
+
+fn default_fuzzy_limit() -> usize {
+    20
+}
+
+#[derive(Serialize)]
+pub struct FuzzySearchResponse {
+    pub query: String,
+    pub results: Vec<TitleMatch>,
+    pub count: usize,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RandomAnimeQuery {
+    /// MAL id of a genre, theme or demographic
+    pub genre: Option<i32>,
+    pub min_score: Option<f32>,
+    /// Media type: tv, movie, ova, ona, special or music
+    #[serde(rename = "type")]
+    pub media_type: Option<String>,
+    #[serde(default = "default_random_count")]
+    pub count: i64,
+}
+
+fn default_random_count() -> i64 {
+    1
+}
+
+#[derive(Serialize)]
+pub struct RandomAnimeResponse {
+    pub anime: Vec<v1::Anime>,
+    pub count: usize,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SearchHistoryQuery {
+    #[serde(default = "default_fuzzy_limit")]
+    pub limit: usize,
+}
+
+#[derive(Serialize)]
+pub struct SearchHistoryResponse {
+    /// Most searched queries
+    pub popular: Vec<SearchCount>,
+    /// Most recently searched queries
+    pub recent: Vec<SearchCount>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SuggestQuery {
+    pub q: String,
+    #[serde(default = "default_suggest_limit")]
+    pub limit: usize,
+}
+
+fn default_suggest_limit() -> usize {
+    10
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SuggestionKind {
+    /// A previous search
+    History,
+    /// The title of a collected anime
+    Title,
+}
+
+#[derive(Serialize)]
+pub struct Suggestion {
+    pub text: String,
+    pub kind: SuggestionKind,
+    /// Times the query was searched, for history suggestions
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub searches: Option<u64>,
+    /// Anime of a title suggestion
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mal_id: Option<i32>,
+}
+
+#[derive(Serialize)]
+pub struct SuggestResponse {
+    pub query: String,
+    pub suggestions: Vec<Suggestion>,
+}
+
+#[derive(Serialize)]
+pub struct SearchQueuedResponse {
+    pub search_id: String,
+    pub message: String,
+}
+
+/// Progress of a search, `results` is set once it completed
+#[derive(Serialize)]
+pub struct SearchResultsResponse {
+    pub search_id: String,
+    pub status: SearchStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub results: Option<SearchResults>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SearchStatus {
+    Pending,
+    Running,
+    Completed,
+    Failed,
+}
+
+#[derive(Serialize)]
+pub struct JobQueuedResponse {
+    pub job_id: String,
     pub message: String,
     pub task_type: String,
 }
 
 #[derive(Serialize)]
-pub struct AnimeResponse {
-    pub anime: my_anime_list::model::AnimeData,
+pub struct TaskQueuedResponse {
+    pub message: String,
+    pub task_type: String,
 }
 
 #[derive(Serialize)]
-pub struct ErrorResponse {
-    pub error: String,
+pub struct AnimeResponse {
+    pub anime: v1::Anime,
+    /// Broadcast slot converted from the provider timezone, when it is understood
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub broadcast_utc: Option<airing::BroadcastSlot>,
+    /// Language of `anime.synopsis` when a language was requested
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub synopsis_language: Option<String>,
 }
 
 // ========================================================================
@@ -99,8 +385,8 @@ pub struct ErrorResponse {
 /// Body: { "anime_id": 1, "with_jikan": true }
 pub async fn fetch_anime(
     State(state): State<ApiState>,
-    Json(request): Json<FetchAnimeRequest>,
-) -> Result<Json<TaskQueuedResponse>, (StatusCode, Json<ErrorResponse>)> {
+    ValidatedJson(request): ValidatedJson<FetchAnimeRequest>,
+) -> Result<Json<TaskQueuedResponse>, ApiError> {
     info!(
         anime_id = request.anime_id,
         with_jikan = request.with_jikan,
@@ -109,53 +395,8 @@ pub async fn fetch_anime(
         "API request: fetch anime"
     );
 
-    let anime_module = state.anime_module.as_ref()
-        .ok_or_else(|| {
-            error!("Anime module not available");
-            (
-                StatusCode::SERVICE_UNAVAILABLE,
-                Json(ErrorResponse {
-                    error: "Anime module is not enabled".to_string(),
-                })
-            )
-        })?;
-
-    // Get MyAnimeList module
-    let mal_client = state.http_manager.my_anime_list().clone();
-    let jikan_client = state.http_manager.jikan().clone();
-    
-    let mut mal_module = my_anime_list::module::MyAnimeListModule::new(
-        mal_client,
-        jikan_client,
-        state.config.clone(),
-        anime_module.queue().clone(),
-    ).ok_or_else(|| {
-        error!("MyAnimeList module not configured");
-        (
-            StatusCode::SERVICE_UNAVAILABLE,
-            Json(ErrorResponse {
-                error: "MyAnimeList module is not properly configured".to_string(),
-            })
-        )
-    })?;
-
-    // Add picture module if available and requested
-    if request.with_pictures {
-        if let Some(picture_module) = &state.picture_module {
-            mal_module = mal_module.with_picture_module(picture_module.clone());
-        } else {
-            info!("Picture module not available, fetching without pictures");
-        }
-    }
-
-    // Add picture module if available and requested
-    if request.with_pictures || request.full_fetch {
-        if let Some(picture_module) = &state.picture_module {
-            mal_module = mal_module.with_picture_module(picture_module.clone());
-        } else {
-            info!("Picture module not available, fetching without pictures");
-        }
-    }
+    let mal_module = state.mal_module()?;
+    state.check_anime_queue()?;
 
     // Queue the task
     if request.full_fetch {
@@ -164,12 +405,7 @@ pub async fn fetch_anime(
             .await
             .map_err(|e| {
                 error!(error = %e, "Failed to queue full fetch anime task");
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(ErrorResponse {
-                        error: format!("Failed to queue task: {}", e),
-                    })
-                )
+                ApiError::internal(format!("Failed to queue task: {}", e))
             })?;
     } else if request.with_pictures {
         mal_module
@@ -177,12 +413,7 @@ pub async fn fetch_anime(
             .await
             .map_err(|e| {
                 error!(error = %e, "Failed to queue fetch anime task");
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(ErrorResponse {
-                        error: format!("Failed to queue task: {}", e),
-                    })
-                )
+                ApiError::internal(format!("Failed to queue task: {}", e))
             })?;
     } else {
         mal_module
@@ -190,12 +421,7 @@ pub async fn fetch_anime(
             .await
             .map_err(|e| {
                 error!(error = %e, "Failed to queue fetch anime task");
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(ErrorResponse {
-                        error: format!("Failed to queue task: {}", e),
-                    })
-                )
+                ApiError::internal(format!("Failed to queue task: {}", e))
             })?;
     }
 
@@ -205,62 +431,79 @@ pub async fn fetch_anime(
     }))
 }
 
-/// Search anime on MyAnimeList
+/// Search anime on MyAnimeList, results are retrieved with GET /api/anime/search/{id}
 /// POST /api/anime/search
 /// Body: { "query": "naruto", "limit": 10 }
 pub async fn search_anime(
     State(state): State<ApiState>,
-    Json(request): Json<SearchAnimeRequest>,
-) -> Result<Json<TaskQueuedResponse>, (StatusCode, Json<ErrorResponse>)> {
+    ValidatedJson(request): ValidatedJson<SearchAnimeRequest>,
+) -> Result<Json<SearchQueuedResponse>, ApiError> {
     info!(
         query = %request.query,
         limit = request.limit,
         "API request: search anime"
     );
 
-    let anime_module = state.anime_module.as_ref()
-        .ok_or_else(|| {
-            (
-                StatusCode::SERVICE_UNAVAILABLE,
-                Json(ErrorResponse {
-                    error: "Anime module is not enabled".to_string(),
-                })
-            )
-        })?;
+    let mal_module = state.mal_module()?;
+    state.check_anime_queue()?;
 
-    let mal_client = state.http_manager.my_anime_list().clone();
-    let jikan_client = state.http_manager.jikan().clone();
-    
-    let mal_module = my_anime_list::module::MyAnimeListModule::new(
-        mal_client,
-        jikan_client,
-        state.config.clone(),
-        anime_module.queue().clone(),
-    ).ok_or_else(|| {
-        (
-            StatusCode::SERVICE_UNAVAILABLE,
-            Json(ErrorResponse {
-                error: "MyAnimeList module is not properly configured".to_string(),
-            })
-        )
-    })?;
-
-    mal_module
+    let search_id = mal_module
         .queue_search_anime(request.query.clone(), Some(request.limit))
         .await
         .map_err(|e| {
             error!(error = %e, "Failed to queue search task");
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ErrorResponse {
-                    error: format!("Failed to queue task: {}", e),
-                })
-            )
+            ApiError::internal(format!("Failed to queue task: {}", e))
         })?;
+    record_search(&state, &request.query).await;
 
-    Ok(Json(TaskQueuedResponse {
-        message: format!("Search for '{}' queued", request.query),
-        task_type: "search_anime".to_string(),
+    Ok(Json(SearchQueuedResponse {
+        message: format!("Search for '{}' queued, get results at /api/anime/search/{}", request.query, search_id),
+        search_id,
+    }))
+}
+
+/// Get the results of a search, or its progress while the search task didn't complete
+/// GET /api/anime/search/{id}
+pub async fn get_search_results(
+    State(state): State<ApiState>,
+    Path(search_id): Path<String>,
+) -> Result<Json<SearchResultsResponse>, ApiError> {
+    let database_error = |e: String| {
+        error!(error = %e, "Failed to get search results");
+        ApiError::internal(format!("Database error: {}", e))
+    };
+
+    let results = my_anime_list::database::get_search_results(state.db.db(), &search_id)
+        .await
+        .map_err(|e| database_error(e.to_string()))?;
+
+    if let Some(results) = results {
+        return Ok(Json(SearchResultsResponse {
+            search_id,
+            status: SearchStatus::Completed,
+            error: None,
+            results: Some(results),
+        }));
+    }
+
+    let task = queue::get_task(&state.db, &search_id)
+        .await
+        .map_err(|e| database_error(e.to_string()))?
+        .ok_or_else(|| ApiError::not_found(format!("Search {} not found", search_id)))?;
+
+    let (status, error) = match task.status {
+        TaskStatus::Pending => (SearchStatus::Pending, None),
+        TaskStatus::Running => (SearchStatus::Running, None),
+        TaskStatus::Failed { error } => (SearchStatus::Failed, Some(error)),
+        // Completed before results were stored (searches queued by an older version)
+        TaskStatus::Completed => (SearchStatus::Failed, Some("Search completed without stored results".to_string())),
+    };
+
+    Ok(Json(SearchResultsResponse {
+        search_id,
+        status,
+        error,
+        results: None,
     }))
 }
 
@@ -269,52 +512,23 @@ pub async fn search_anime(
 /// Body: { "anime_id": 1, "with_jikan": true }
 pub async fn update_anime(
     State(state): State<ApiState>,
-    Json(request): Json<UpdateAnimeRequest>,
-) -> Result<Json<TaskQueuedResponse>, (StatusCode, Json<ErrorResponse>)> {
+    ValidatedJson(request): ValidatedJson<UpdateAnimeRequest>,
+) -> Result<Json<TaskQueuedResponse>, ApiError> {
     info!(
         anime_id = request.anime_id,
         with_jikan = request.with_jikan,
         "API request: update anime"
     );
 
-    let anime_module = state.anime_module.as_ref()
-        .ok_or_else(|| {
-            (
-                StatusCode::SERVICE_UNAVAILABLE,
-                Json(ErrorResponse {
-                    error: "Anime module is not enabled".to_string(),
-                })
-            )
-        })?;
-
-    let mal_client = state.http_manager.my_anime_list().clone();
-    let jikan_client = state.http_manager.jikan().clone();
-    
-    let mal_module = my_anime_list::module::MyAnimeListModule::new(
-        mal_client,
-        jikan_client,
-        state.config.clone(),
-        anime_module.queue().clone(),
-    ).ok_or_else(|| {
-        (
-            StatusCode::SERVICE_UNAVAILABLE,
-            Json(ErrorResponse {
-                error: "MyAnimeList module is not properly configured".to_string(),
-            })
-        )
-    })?;
+    let mal_module = state.mal_module()?;
+    state.check_anime_queue()?;
 
     mal_module
         .queue_update_anime(request.anime_id, request.with_jikan)
         .await
         .map_err(|e| {
             error!(error = %e, "Failed to queue update task");
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ErrorResponse {
-                    error: format!("Failed to queue task: {}", e),
-                })
-            )
+            ApiError::internal(format!("Failed to queue task: {}", e))
         })?;
 
     Ok(Json(TaskQueuedResponse {
@@ -328,8 +542,8 @@ pub async fn update_anime(
 /// Body: { "anime_ids": [1, 2, 3], "with_jikan": true }
 pub async fn batch_fetch(
     State(state): State<ApiState>,
-    Json(request): Json<BatchFetchRequest>,
-) -> Result<Json<TaskQueuedResponse>, (StatusCode, Json<ErrorResponse>)> {
+    ValidatedJson(request): ValidatedJson<BatchFetchRequest>,
+) -> Result<Json<JobQueuedResponse>, ApiError> {
     info!(
         count = request.anime_ids.len(),
         with_jikan = request.with_jikan,
@@ -338,72 +552,38 @@ pub async fn batch_fetch(
         "API request: batch fetch anime"
     );
 
-    // Validate request
-    if request.anime_ids.is_empty() {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse {
-                error: "No anime IDs provided".to_string(),
-            })
-        ));
-    }
+    let mal_module = state.mal_module()?;
+    state.check_anime_queue()?;
 
-    let anime_module = state.anime_module.as_ref()
-        .ok_or_else(|| {
-            (
-                StatusCode::SERVICE_UNAVAILABLE,
-                Json(ErrorResponse {
-                    error: "Anime module is not enabled".to_string(),
-                })
-            )
-        })?;
-
-    // Create MAL module
-    let mal_client = state.http_manager.my_anime_list().clone();
-    let jikan_client = state.http_manager.jikan().clone();
-    
-    let mut mal_module = my_anime_list::module::MyAnimeListModule::new(
-        mal_client,
-        jikan_client,
-        state.config.clone(),
-        anime_module.queue().clone(),
-    ).ok_or_else(|| {
-        (
-            StatusCode::SERVICE_UNAVAILABLE,
-            Json(ErrorResponse {
-                error: "MyAnimeList module is not properly configured".to_string(),
-            })
-        )
+    let job = job::create_job(
+        &state.db,
+        "batch_fetch",
+        format!("Batch fetch of {} anime", request.anime_ids.len()),
+    )
+    .await
+    .map_err(|e| {
+        error!(error = %e, "Failed to create batch fetch job");
+        ApiError::internal(format!("Failed to create job: {}", e))
     })?;
 
-    // Add picture module if available and requested
-    if request.with_pictures || request.full_fetch {
-        if let Some(picture_module) = &state.picture_module {
-            mal_module = mal_module.with_picture_module(picture_module.clone());
-        } else {
-            info!("Picture module not available, fetching without pictures");
-        }
-    }
-
-    // Queue the batch fetch
-    mal_module
-        .queue_batch_fetch(
-            request.anime_ids.clone(),
-            request.with_jikan,
-            request.with_pictures,
-            request.full_fetch,
-        )
+    // Queue the batch fetch, every queued task belongs to the job
+    let batch = mal_module.queue_batch_fetch(
+        request.anime_ids.clone(),
+        request.with_jikan,
+        request.with_pictures,
+        request.full_fetch,
+    );
+    queue::in_job(job.job_id.clone(), batch)
         .await
         .map_err(|e| {
             error!(error = %e, "Failed to queue batch fetch task");
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ErrorResponse {
-                    error: format!("Failed to queue task: {}", e),
-                })
-            )
+            ApiError::internal(format!("Failed to queue task: {}", e))
         })?;
 
+    if let Err(e) = job::seal_job(&state.db, &job.job_id, Some(&state.events)).await {
+        error!(job_id = %job.job_id, error = %e, "Failed to seal job");
+    }
+
     // Build response message
     let features = if request.full_fetch {
         "full_fetch: true (includes everything)".to_string()
@@ -422,7 +602,8 @@ pub async fn batch_fetch(
         }
     };
 
-    Ok(Json(TaskQueuedResponse {
+    Ok(Json(JobQueuedResponse {
+        job_id: job.job_id,
         message: format!(
             "{} anime queued for batch fetching ({})",
             request.anime_ids.len(),
@@ -437,45 +618,45 @@ pub async fn batch_fetch(
 /// Body: { "anime_id": 1, "fetch_characters": true, "fetch_staff": true, "fetch_episodes": true, "fetch_moreinfo": true, "fetch_videos": true, "fetch_recommendations": true }
 pub async fn fetch_extended_data(
     State(state): State<ApiState>,
-    Json(request): Json<FetchExtendedDataRequest>,
-) -> Result<Json<TaskQueuedResponse>, (StatusCode, Json<ErrorResponse>)> {
+    ValidatedJson(request): ValidatedJson<FetchExtendedDataRequest>,
+) -> Result<Json<TaskQueuedResponse>, ApiError> {
     info!(
         anime_id = request.anime_id,
         characters = request.fetch_characters,
         staff = request.fetch_staff,
         episodes = request.fetch_episodes,
+        incremental_episodes = request.incremental_episodes,
+        episode_details = request.fetch_episode_details,
         videos = request.fetch_videos,
         moreinfo = request.fetch_moreinfo,
         recommendations = request.fetch_recommendations,
         "API request: fetch extended data"
     );
 
-    let anime_module = state.anime_module.as_ref()
-        .ok_or_else(|| {
-            (
-                StatusCode::SERVICE_UNAVAILABLE,
-                Json(ErrorResponse {
-                    error: "Anime module is not enabled".to_string(),
-                })
-            )
-        })?;
+    let mal_module = state.mal_module()?;
+    state.check_anime_queue()?;
 
-    let mal_client = state.http_manager.my_anime_list().clone();
-    let jikan_client = state.http_manager.jikan().clone();
-    
-    let mal_module = my_anime_list::module::MyAnimeListModule::new(
-        mal_client,
-        jikan_client,
-        state.config.clone(),
-        anime_module.queue().clone(),
-    ).ok_or_else(|| {
-        (
-            StatusCode::SERVICE_UNAVAILABLE,
-            Json(ErrorResponse {
-                error: "MyAnimeList module is not properly configured".to_string(),
-            })
-        )
-    })?;
+    // Resolved before queueing anything, so a request without episodes queues nothing
+    let detail_episodes: Vec<u32> = if !request.fetch_episode_details {
+        Vec::new()
+    } else if !request.episodes.is_empty() {
+        request.episodes.clone()
+    } else {
+        let stored = episodes::get_episodes(state.db.db(), request.anime_id as i32, EpisodeFilter::default())
+            .await
+            .map_err(ApiError::from)?;
+        let missing: Vec<u32> = stored.iter()
+            .filter(|episode| episode.detail_updated_at.is_none())
+            .map(|episode| episode.episode as u32)
+            .collect();
+        if missing.is_empty() {
+            return Err(ApiError::validation(format!(
+                "No stored episodes without details for anime {}, fetch the episodes first or list them in episodes",
+                request.anime_id
+            )));
+        }
+        missing
+    };
 
     let mut tasks_queued = Vec::new();
 
@@ -483,12 +664,7 @@ pub async fn fetch_extended_data(
         mal_module.queue_fetch_characters(request.anime_id).await
             .map_err(|e| {
                 error!(error = %e, "Failed to queue characters task");
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(ErrorResponse {
-                        error: format!("Failed to queue characters: {}", e),
-                    })
-                )
+                ApiError::internal(format!("Failed to queue characters: {}", e))
             })?;
         tasks_queued.push("characters");
     }
@@ -497,40 +673,34 @@ pub async fn fetch_extended_data(
         mal_module.queue_fetch_staff(request.anime_id).await
             .map_err(|e| {
                 error!(error = %e, "Failed to queue staff task");
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(ErrorResponse {
-                        error: format!("Failed to queue staff: {}", e),
-                    })
-                )
+                ApiError::internal(format!("Failed to queue staff: {}", e))
             })?;
         tasks_queued.push("staff");
     }
 
     if request.fetch_episodes {
-        mal_module.queue_fetch_episodes(request.anime_id).await
+        mal_module.queue_fetch_episodes(request.anime_id, request.incremental_episodes).await
             .map_err(|e| {
                 error!(error = %e, "Failed to queue episodes task");
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(ErrorResponse {
-                        error: format!("Failed to queue episodes: {}", e),
-                    })
-                )
+                ApiError::internal(format!("Failed to queue episodes: {}", e))
             })?;
         tasks_queued.push("episodes");
     }
 
+    if request.fetch_episode_details {
+        mal_module.queue_fetch_episode_details(request.anime_id, &detail_episodes).await
+            .map_err(|e| {
+                error!(error = %e, "Failed to queue episode detail tasks");
+                ApiError::internal(format!("Failed to queue episode details: {}", e))
+            })?;
+        tasks_queued.push("episode_details");
+    }
+
     if request.fetch_videos {
         mal_module.queue_fetch_videos(request.anime_id).await
             .map_err(|e| {
                 error!(error = %e, "Failed to queue videos task");
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(ErrorResponse {
-                        error: format!("Failed to queue videos: {}", e),
-                    })
-                )
+                ApiError::internal(format!("Failed to queue videos: {}", e))
             })?;
         tasks_queued.push("videos");
     }
@@ -539,12 +709,7 @@ pub async fn fetch_extended_data(
         mal_module.queue_fetch_more_info(request.anime_id).await
             .map_err(|e| {
                 error!(error = %e, "Failed to queue moreinfo task");
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(ErrorResponse {
-                        error: format!("Failed to queue moreinfo: {}", e),
-                    })
-                )
+                ApiError::internal(format!("Failed to queue moreinfo: {}", e))
             })?;
         tasks_queued.push("moreinfo");
     }
@@ -553,23 +718,13 @@ pub async fn fetch_extended_data(
         mal_module.queue_fetch_recommendations(request.anime_id).await
             .map_err(|e| {
                 error!(error = %e, "Failed to queue recommendations task");
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(ErrorResponse {
-                        error: format!("Failed to queue recommendations: {}", e),
-                    })
-                )
+                ApiError::internal(format!("Failed to queue recommendations: {}", e))
             })?;
         tasks_queued.push("recommendations");
     }
 
     if tasks_queued.is_empty() {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse {
-                error: "No data types selected for fetching".to_string(),
-            })
-        ));
+        return Err(ApiError::validation("No data types selected for fetching"));
     }
 
     Ok(Json(TaskQueuedResponse {
@@ -578,35 +733,559 @@ pub async fn fetch_extended_data(
     }))
 }
 
-/// Get anime by ID from database
-/// GET /api/anime/:id
+/// Collect everything about an anime: MAL + Jikan + extended data + AniList + pictures
+/// POST /api/anime/collect
+/// Body: { "anime_id": 1 } or { "title": "Cowboy Bebop" }
+/// Progress of the returned job is available at GET /api/jobs/{id}
+pub async fn collect_anime(
+    State(state): State<ApiState>,
+    ValidatedJson(request): ValidatedJson<CollectAnimeRequest>,
+) -> Result<Json<JobQueuedResponse>, ApiError> {
+    info!(
+        anime_id = ?request.anime_id,
+        title = ?request.title,
+        "API request: collect anime"
+    );
+
+    let target = match (request.anime_id, request.title) {
+        (Some(anime_id), None) => CollectTarget::MalId(anime_id),
+        (None, Some(title)) if !title.trim().is_empty() => CollectTarget::Title(title.trim().to_string()),
+        _ => {
+            return Err(ApiError::validation("Provide either anime_id or a non-empty title"));
+        }
+    };
+
+    let mal_module = state.mal_module()?;
+    state.check_anime_queue()?;
+    let config = state.config.load_full();
+
+    let anilist_client = AniListModule::is_available(&config)
+        .then(|| state.http_manager.anilist().clone());
+
+    let job = job::create_job(&state.db, "collect", format!("Collect anime by {}", target))
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Failed to create collection job");
+            ApiError::internal(format!("Failed to create job: {}", e))
+        })?;
+
+    mal_module
+        .queue_collect(target, anilist_client, &job.job_id)
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Failed to queue anime collection");
+            ApiError::internal(format!("Failed to queue task: {}", e))
+        })?;
+
+    if let Err(e) = job::seal_job(&state.db, &job.job_id, Some(&state.events)).await {
+        error!(job_id = %job.job_id, error = %e, "Failed to seal job");
+    }
+
+    Ok(Json(JobQueuedResponse {
+        message: format!("Anime collection queued, follow progress at /api/jobs/{}", job.job_id),
+        job_id: job.job_id,
+        task_type: "collect".to_string(),
+    }))
+}
+
+/// Refresh the statistics and episodes of every stored airing anime ahead of other tasks
+/// POST /api/anime/refresh-airing
+/// Progress of the returned job is available at GET /api/jobs/{id}
+pub async fn refresh_airing(
+    State(state): State<ApiState>,
+) -> Result<Json<JobQueuedResponse>, ApiError> {
+    info!("API request: refresh airing anime");
+
+    let mal_module = state.mal_module()?;
+    state.check_anime_queue()?;
+
+    let anime_ids: Vec<u32> = my_anime_list::database::get_airing_anime_ids(state.db.db())
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Failed to get airing anime");
+            ApiError::from(e)
+        })?
+        .into_iter()
+        .map(|mal_id| mal_id as u32)
+        .collect();
+
+    if anime_ids.is_empty() {
+        return Err(ApiError::not_found("No airing anime stored"));
+    }
+
+    let job = job::create_job(&state.db, "refresh_airing", format!("Refresh of {} airing anime", anime_ids.len()))
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Failed to create airing refresh job");
+            ApiError::internal(format!("Failed to create job: {}", e))
+        })?;
+
+    queue::in_job(job.job_id.clone(), mal_module.queue_refresh_airing(&anime_ids))
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Failed to queue airing refresh");
+            ApiError::internal(format!("Failed to queue task: {}", e))
+        })?;
+
+    if let Err(e) = job::seal_job(&state.db, &job.job_id, Some(&state.events)).await {
+        error!(job_id = %job.job_id, error = %e, "Failed to seal job");
+    }
+
+    Ok(Json(JobQueuedResponse {
+        message: format!(
+            "Statistics and episodes of {} airing anime queued, follow progress at /api/jobs/{}",
+            anime_ids.len(), job.job_id
+        ),
+        job_id: job.job_id,
+        task_type: "refresh_airing".to_string(),
+    }))
+}
+
+/// Get anime by ID from database, 304 when `If-None-Match` holds the current ETag.
+/// `fields` limits the anime to the listed top-level fields, `lang` picks the synopsis
+/// language, falling back to the provider one (English), and the language of the
+/// status, season and media type labels.
+/// GET /api/anime/:id?fields=titles,score,images&lang=ja
 pub async fn get_anime(
     State(state): State<ApiState>,
     Path(anime_id): Path<i32>,
-) -> Result<Json<AnimeResponse>, (StatusCode, Json<ErrorResponse>)> {
-    info!(anime_id = anime_id, "API request: get anime");
+    Query(query): Query<FieldsQuery>,
+    Query(language): Query<LanguageQuery>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    info!(anime_id = anime_id, fields = ?query.fields, lang = ?language.lang, "API request: get anime");
+
+    language.validate()?;
+    let fields = query.parse().map_err(ApiError::validation)?;
+    let not_found = || ApiError::not_found(format!("Anime {} not found", anime_id));
+    let database_error = |e: crate::global::error::DatabaseError| {
+        error!(error = %e, "Failed to get anime from database");
+        ApiError::from(e)
+    };
+
+    let anime = match &fields {
+        Some(fields) => my_anime_list::database::get_anime_fields(state.db.db(), anime_id, fields).await,
+        None => my_anime_list::database::get_anime_by_id(state.db.db(), anime_id).await,
+    };
+    let mut anime = anime
+        .map_err(database_error)?
+        .ok_or_else(not_found)?;
+
+    let locale = language.lang.as_deref().map(Locale::from_code).unwrap_or_default();
+    let synopsis_language = language.lang.map(|lang| anime.use_synopsis_language(&lang));
+
+    let last_modified = anime.updated_at;
+    if let Some(fields) = fields {
+        let body = serde_json::json!({ "anime": fields::select(&v1::Anime::localized(anime, locale), &fields) });
+        return Ok(cache::conditional_json(&headers, &body, Some(last_modified)));
+    }
+
+    let broadcast_utc = airing::broadcast_slot(&anime, chrono::Utc::now());
+    let response = AnimeResponse { anime: v1::Anime::localized(anime, locale), broadcast_utc, synopsis_language };
+    Ok(cache::conditional_json(&headers, &response, Some(last_modified)))
+}
+
+/// Search collected anime by any of their titles, tolerating diacritics,
+/// romaji long-vowel spellings and small typos
+/// GET /api/anime/search/fuzzy?q=shingeki+no+kyojin&limit=20
+pub async fn fuzzy_search(
+    State(state): State<ApiState>,
+    Query(query): Query<FuzzySearchQuery>,
+) -> Result<Json<FuzzySearchResponse>, ApiError> {
+    let results = titles::fuzzy_search(state.db.db(), &query.q, query.limit.clamp(1, 100))
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Failed to search titles");
+            ApiError::from(e)
+        })?;
+    record_search(&state, &query.q).await;
+
+    Ok(Json(FuzzySearchResponse {
+        query: query.q,
+        count: results.len(),
+        results,
+    }))
+}
+
+/// Anime picked at random in the collection, for "what should I watch" features
+/// GET /api/anime/random?genre=1&min_score=7.5&type=tv&count=3
+pub async fn random_anime(
+    State(state): State<ApiState>,
+    Query(query): Query<RandomAnimeQuery>,
+) -> Result<Json<RandomAnimeResponse>, ApiError> {
+    let filter = RandomAnimeFilter {
+        genre_id: query.genre,
+        min_score: query.min_score,
+        media_type: query.media_type,
+    };
+
+    let anime = my_anime_list::database::get_random_anime(state.db.db(), &filter, query.count.clamp(1, 20))
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Failed to get random anime");
+            ApiError::from(e)
+        })?;
+
+    Ok(Json(RandomAnimeResponse {
+        count: anime.len(),
+        anime: anime.into_iter().map(v1::Anime::from).collect(),
+    }))
+}
+
+/// Collected anime with a title word starting with the input, for as-you-type matching.
+/// Anime indexed before the prefix index existed are found after `POST /api/admin/titles/reindex`.
+/// GET /api/anime/autocomplete?q=shingeki+no&limit=10
+pub async fn autocomplete(
+    State(state): State<ApiState>,
+    Query(query): Query<SuggestQuery>,
+) -> Result<Json<FuzzySearchResponse>, ApiError> {
+    let results = titles::prefix_search(state.db.db(), &query.q, query.limit.clamp(1, 50))
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Failed to autocomplete titles");
+            ApiError::from(e)
+        })?;
+
+    Ok(Json(FuzzySearchResponse {
+        query: query.q,
+        count: results.len(),
+        results,
+    }))
+}
+
+/// Add a query to the search history, a failure doesn't fail the search
+async fn record_search(state: &ApiState, query: &str) {
+    if let Err(e) = my_anime_list::database::record_search_history(state.db.db(), query).await {
+        warn!(error = %e, "Failed to record search history");
+    }
+}
+
+/// Popular and recent queries of the search history, with their counts
+/// GET /api/anime/search/history?limit=20
+pub async fn search_history(
+    State(state): State<ApiState>,
+    Query(query): Query<SearchHistoryQuery>,
+) -> Result<Json<SearchHistoryResponse>, ApiError> {
+    let limit = query.limit.clamp(1, 100) as i64;
+    let database_error = |e: crate::global::error::DatabaseError| {
+        error!(error = %e, "Failed to get search history");
+        ApiError::from(e)
+    };
+
+    let popular = my_anime_list::database::get_search_counts(state.db.db(), None, SearchCountOrder::Popular, limit)
+        .await
+        .map_err(database_error)?;
+    let recent = my_anime_list::database::get_search_counts(state.db.db(), None, SearchCountOrder::Recent, limit)
+        .await
+        .map_err(database_error)?;
+
+    Ok(Json(SearchHistoryResponse { popular, recent }))
+}
+
+/// Typeahead suggestions: previous searches starting with the input, most searched first,
+/// then collected anime with a title word starting with it
+/// GET /api/anime/search/suggest?q=shin&limit=10
+pub async fn suggest(
+    State(state): State<ApiState>,
+    Query(query): Query<SuggestQuery>,
+) -> Result<Json<SuggestResponse>, ApiError> {
+    let limit = query.limit.clamp(1, 50);
+    let database_error = |e: crate::global::error::DatabaseError| {
+        error!(error = %e, "Failed to get search suggestions");
+        ApiError::from(e)
+    };
+
+    let history = my_anime_list::database::get_search_counts(state.db.db(), Some(&query.q), SearchCountOrder::Popular, limit as i64)
+        .await
+        .map_err(database_error)?;
+    let titles = titles::prefix_search(state.db.db(), &query.q, limit)
+        .await
+        .map_err(database_error)?;
+
+    // A title already suggested by the history is not repeated
+    let mut seen = std::collections::HashSet::new();
+    let suggestions = history.into_iter()
+        .map(|search| Suggestion {
+            text: search.query,
+            kind: SuggestionKind::History,
+            searches: Some(search.count),
+            mal_id: None,
+        })
+        .chain(titles.into_iter().map(|title| Suggestion {
+            text: title.title,
+            kind: SuggestionKind::Title,
+            searches: None,
+            mal_id: title.mal_id,
+        }))
+        .filter(|suggestion| seen.insert(titles::normalize(&suggestion.text)))
+        .take(limit)
+        .collect();
+
+    Ok(Json(SuggestResponse {
+        query: query.q,
+        suggestions,
+    }))
+}
+
+/// Queue the extended data sections of a stored anime that are empty or stale
+/// POST /api/anime/{id}/complete?dry_run=true&max_age_days=30
+pub async fn complete_anime(
+    State(state): State<ApiState>,
+    Path(anime_id): Path<i32>,
+    Query(query): Query<CompleteAnimeQuery>,
+) -> Result<Json<CompleteAnimeResponse>, ApiError> {
+    info!(anime_id = anime_id, dry_run = query.dry_run, max_age_days = ?query.max_age_days, "API request: complete anime");
+
+    let mal_module = state.mal_module()?;
 
     let anime = my_anime_list::database::get_anime_by_id(state.db.db(), anime_id)
         .await
         .map_err(|e| {
             error!(error = %e, "Failed to get anime from database");
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ErrorResponse {
-                    error: format!("Database error: {}", e),
-                })
-            )
+            ApiError::from(e)
         })?
-        .ok_or_else(|| {
-            (
-                StatusCode::NOT_FOUND,
-                Json(ErrorResponse {
-                    error: format!("Anime {} not found", anime_id),
-                })
-            )
+        .ok_or_else(|| ApiError::not_found(format!("Anime {} not found", anime_id)))?;
+
+    let max_age_days = match query.max_age_days {
+        Some(days) if days > freshness::MAX_SECTION_AGE_DAYS => {
+            return Err(ApiError::validation(format!(
+                "max_age_days must be at most {}",
+                freshness::MAX_SECTION_AGE_DAYS
+            )));
+        }
+        Some(days) => (days > 0).then_some(days as i64),
+        None => state.config.load().section_refresh_age(),
+    };
+    let sections = freshness::sections_to_fetch(&anime, max_age_days, chrono::Utc::now());
+
+    if !query.dry_run && !sections.is_empty() {
+        state.check_anime_queue()?;
+        for section in &sections {
+            mal_module.queue_fetch_section(anime_id as u32, section.section).await
+                .map_err(|e| {
+                    error!(error = %e, section = section.section.as_str(), "Failed to queue section task");
+                    ApiError::internal(format!("Failed to queue {}: {}", section.section.as_str(), e))
+                })?;
+        }
+    }
+
+    let names: Vec<&str> = sections.iter().map(|section| section.section.as_str()).collect();
+    let message = match (names.is_empty(), query.dry_run) {
+        (true, _) => format!("Anime {} is complete", anime_id),
+        (false, true) => format!("Would fetch {} for anime {}", names.join(", "), anime_id),
+        (false, false) => format!("Queued {} for anime {}", names.join(", "), anime_id),
+    };
+
+    Ok(Json(CompleteAnimeResponse {
+        anime_id,
+        dry_run: query.dry_run,
+        sections,
+        message,
+    }))
+}
+
+/// Sections of a stored anime that are populated, and pictures downloaded out of those referenced
+/// GET /api/anime/{id}/completeness
+pub async fn get_anime_completeness(
+    State(state): State<ApiState>,
+    Path(anime_id): Path<i32>,
+) -> Result<Json<CompletenessResponse>, ApiError> {
+    let anime = my_anime_list::database::get_anime_by_id(state.db.db(), anime_id)
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Failed to get anime from database");
+            ApiError::from(e)
+        })?
+        .ok_or_else(|| ApiError::not_found(format!("Anime {} not found", anime_id)))?;
+
+    let downloaded = picture::database::get_pictures_by_entity(state.db.db(), "anime", &anime_id.to_string())
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Failed to get anime pictures");
+            ApiError::from(e)
+        })?
+        .into_iter()
+        .filter(|picture| picture.status == PictureStatus::Completed)
+        .map(|picture| picture.url)
+        .collect();
+
+    let policy = state.config.load().picture.variants;
+    Ok(Json(CompletenessResponse {
+        anime_id,
+        completeness: freshness::completeness(&anime, policy, &downloaded),
+    }))
+}
+
+/// Field-level changes of an anime across updates, newest first
+/// GET /api/anime/{id}/history?limit=50
+pub async fn get_anime_history(
+    State(state): State<ApiState>,
+    Path(anime_id): Path<i32>,
+    Query(query): Query<HistoryQuery>,
+) -> Result<Json<AnimeHistoryResponse>, ApiError> {
+    let history = my_anime_list::database::get_anime_history(state.db.db(), anime_id, query.limit.clamp(1, 500))
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Failed to get anime history");
+            ApiError::from(e)
         })?;
 
-    Ok(Json(AnimeResponse { anime }))
+    Ok(Json(AnimeHistoryResponse {
+        anime_id,
+        count: history.len(),
+        history,
+    }))
+}
+
+/// Episodes of an anime, optionally only the filler or recap ones
+/// GET /api/anime/{id}/episodes?filler=true&recap=false
+pub async fn get_anime_episodes(
+    State(state): State<ApiState>,
+    Path(anime_id): Path<i32>,
+    Query(filter): Query<EpisodeFilter>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    let episodes = episodes::get_episodes(state.db.db(), anime_id, filter)
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Failed to get episodes");
+            ApiError::from(e)
+        })?;
+
+    let last_modified = episodes.iter().map(|episode| episode.updated_at).max();
+    let response = AnimeEpisodesResponse {
+        anime_id,
+        count: episodes.len(),
+        episodes,
+    };
+    Ok(cache::conditional_json(&headers, &response, last_modified))
+}
+
+/// Filler and recap episodes of an anime, as ranges of consecutive episodes
+/// GET /api/anime/{id}/episodes/fillers
+pub async fn get_filler_list(
+    State(state): State<ApiState>,
+    Path(anime_id): Path<i32>,
+) -> Result<Json<FillerListResponse>, ApiError> {
+    let episodes = episodes::get_episodes(state.db.db(), anime_id, EpisodeFilter::default())
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Failed to get episodes");
+            ApiError::from(e)
+        })?;
+
+    let filler: Vec<i32> = episodes.iter().filter(|episode| episode.filler).map(|episode| episode.episode).collect();
+    let recap: Vec<i32> = episodes.iter().filter(|episode| episode.recap).map(|episode| episode.episode).collect();
+
+    Ok(Json(FillerListResponse {
+        anime_id,
+        filler_count: filler.len(),
+        recap_count: recap.len(),
+        filler: episode_ranges(&filler),
+        recap: episode_ranges(&recap),
+    }))
+}
+
+/// Group sorted episode numbers into ranges of consecutive episodes
+fn episode_ranges(numbers: &[i32]) -> Vec<EpisodeRange> {
+    let mut ranges: Vec<EpisodeRange> = Vec::new();
+    for &number in numbers {
+        match ranges.last_mut() {
+            Some(range) if range.end + 1 == number => range.end = number,
+            _ => ranges.push(EpisodeRange { start: number, end: number }),
+        }
+    }
+    ranges
+}
+
+/// One episode of an anime
+/// GET /api/anime/{id}/episodes/{episode}
+pub async fn get_anime_episode(
+    State(state): State<ApiState>,
+    Path((anime_id, episode)): Path<(i32, i32)>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    let episode = episodes::get_episode(state.db.db(), anime_id, episode)
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Failed to get episode");
+            ApiError::from(e)
+        })?
+        .ok_or_else(|| ApiError::not_found(format!("Episode {} of anime {} not found", episode, anime_id)))?;
+
+    let last_modified = episode.updated_at;
+    Ok(cache::conditional_json(&headers, &AnimeEpisodeResponse { episode }, Some(last_modified)))
+}
+
+/// Score and popularity time series of an anime, oldest first
+/// GET /api/anime/{id}/trends?days=90
+pub async fn get_anime_trends(
+    State(state): State<ApiState>,
+    Path(anime_id): Path<i32>,
+    Query(query): Query<TrendsQuery>,
+) -> Result<Json<AnimeTrendsResponse>, ApiError> {
+    let days = query.days.clamp(1, 3650);
+    let since = chrono::Utc::now() - chrono::Duration::days(days);
+
+    let snapshots = my_anime_list::database::get_statistics_snapshots(state.db.db(), anime_id, since)
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Failed to get statistics snapshots");
+            ApiError::from(e)
+        })?;
+
+    Ok(Json(AnimeTrendsResponse {
+        anime_id,
+        days,
+        count: snapshots.len(),
+        snapshots,
+    }))
+}
+
+/// Graph of related and recommended anime around an anime, as nodes and edges
+/// GET /api/anime/{id}/related?depth=2
+pub async fn get_related_graph(
+    State(state): State<ApiState>,
+    Path(anime_id): Path<i32>,
+    Query(query): Query<RelatedQuery>,
+) -> Result<Json<RelatedGraph>, ApiError> {
+    let graph = related::related_graph(&state.db, anime_id, query.depth.clamp(1, 3))
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Failed to build related graph");
+            ApiError::from(e)
+        })?
+        .ok_or_else(|| ApiError::not_found(format!("Anime {} not found", anime_id)))?;
+
+    Ok(Json(graph))
+}
+
+/// iCalendar feed of upcoming episode air times of collected anime
+/// GET /api/anime/airing.ics?ids=52991,51009&days=14
+pub async fn airing_calendar(
+    State(state): State<ApiState>,
+    Query(query): Query<AiringCalendarQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let ids = query.ids.as_deref()
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(|id| id.parse::<i32>())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| ApiError::validation(format!("Invalid anime id in ids: {}", e)))?;
+
+    let episodes = airing::upcoming_episodes(&state.db, &ids, query.days.clamp(1, 90))
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Failed to build airing calendar");
+            ApiError::from(e)
+        })?;
+
+    Ok((
+        [(header::CONTENT_TYPE, "text/calendar; charset=utf-8")],
+        airing::to_icalendar(&episodes),
+    ))
 }
 
 /// Fetch anime from AniList by MAL ID
@@ -614,8 +1293,8 @@ pub async fn get_anime(
 /// Body: { "mal_id": 1 }
 pub async fn fetch_from_anilist(
     State(state): State<ApiState>,
-    Json(request): Json<FetchAnimeRequest>,
-) -> Result<Json<TaskQueuedResponse>, (StatusCode, Json<ErrorResponse>)> {
+    ValidatedJson(request): ValidatedJson<FetchAnimeRequest>,
+) -> Result<Json<TaskQueuedResponse>, ApiError> {
     info!(
         mal_id = request.anime_id,
         with_pictures = request.with_pictures,
@@ -623,37 +1302,8 @@ pub async fn fetch_from_anilist(
         "API request: fetch anime from AniList"
     );
 
-    let anime_module = state.anime_module.as_ref()
-        .ok_or_else(|| {
-            (
-                StatusCode::SERVICE_UNAVAILABLE,
-                Json(ErrorResponse {
-                    error: "Anime module is not enabled".to_string(),
-                })
-            )
-        })?;
-
-    let anilist_client = state.http_manager.anilist().clone();
-    
-    let mut anilist_module = AniListModule::new(
-        anilist_client,
-        state.config.clone(),
-        anime_module.queue().clone(),
-    ).ok_or_else(|| {
-        (
-            StatusCode::SERVICE_UNAVAILABLE,
-            Json(ErrorResponse {
-                error: "AniList module is not properly configured".to_string(),
-            })
-        )
-    })?;
-    
-    // Add picture module if available and requested
-    if request.with_pictures || request.full_fetch {
-        if let Some(picture_module) = &state.picture_module {
-            anilist_module = anilist_module.with_picture_module(picture_module.clone());
-        }
-    }
+    let anilist_module = state.anilist_module()?;
+    state.check_anime_queue()?;
 
     if request.full_fetch {
         anilist_module
@@ -661,12 +1311,7 @@ pub async fn fetch_from_anilist(
             .await
             .map_err(|e| {
                 error!(error = %e, "Failed to queue AniList full fetch task");
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(ErrorResponse {
-                        error: format!("Failed to queue task: {}", e),
-                    })
-                )
+                ApiError::internal(format!("Failed to queue task: {}", e))
             })?;
     } else if request.with_pictures {
         anilist_module
@@ -674,12 +1319,7 @@ pub async fn fetch_from_anilist(
             .await
             .map_err(|e| {
                 error!(error = %e, "Failed to queue AniList fetch task");
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(ErrorResponse {
-                        error: format!("Failed to queue task: {}", e),
-                    })
-                )
+                ApiError::internal(format!("Failed to queue task: {}", e))
             })?;
     } else {
         anilist_module
@@ -687,12 +1327,7 @@ pub async fn fetch_from_anilist(
             .await
             .map_err(|e| {
                 error!(error = %e, "Failed to queue AniList fetch task");
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(ErrorResponse {
-                        error: format!("Failed to queue task: {}", e),
-                    })
-                )
+                ApiError::internal(format!("Failed to queue task: {}", e))
             })?;
     }
 
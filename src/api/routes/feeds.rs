@@ -0,0 +1,97 @@
+use std::fmt::Write as _;
+
+use axum::{
+    extract::{Query, State},
+    http::header,
+    response::IntoResponse,
+};
+use serde::Deserialize;
+use tracing::error;
+
+use crate::api::error::ApiError;
+use crate::anime::my_anime_list::database;
+use crate::anime::my_anime_list::model::AnimeData;
+use crate::api::state::ApiState;
+use crate::global::xml;
+
+// ========================================================================
+// Request/Response Types
+// ========================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct FeedQuery {
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+}
+
+fn default_limit() -> i64 {
+    50
+}
+
+// ========================================================================
+// Handlers
+// ========================================================================
+
+/// RSS 2.0 feed of the most recently collected anime
+/// GET /api/feeds/new-anime.xml?limit=50
+pub async fn new_anime_feed(
+    State(state): State<ApiState>,
+    Query(query): Query<FeedQuery>,
+) -> Result<impl IntoResponse, ApiError> {
+    let anime = database::get_recently_collected_anime(state.db.db(), query.limit.clamp(1, 200))
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Failed to get recently collected anime");
+            ApiError::from(e)
+        })?;
+
+    Ok((
+        [(header::CONTENT_TYPE, "application/rss+xml; charset=utf-8")],
+        render_feed(&anime),
+    ))
+}
+
+// ========================================================================
+// Rendering
+// ========================================================================
+
+fn render_feed(anime: &[AnimeData]) -> String {
+    let mut rss = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<rss version=\"2.0\">\n<channel>\n");
+    rss.push_str("  <title>Newly collected anime</title>\n");
+    rss.push_str("  <link>https://myanimelist.net/anime</link>\n");
+    rss.push_str("  <description>Anime recently added to the media collector</description>\n");
+    if let Some(collected_at) = anime.first().and_then(|anime| anime.collected_at) {
+        let _ = writeln!(rss, "  <lastBuildDate>{}</lastBuildDate>", collected_at.to_rfc2822());
+    }
+
+    for anime in anime {
+        rss.push_str("  <item>\n");
+        let _ = writeln!(rss, "    <title>{}</title>", xml::escape(anime.display_title()));
+        if !anime.url.is_empty() {
+            let _ = writeln!(rss, "    <link>{}</link>", xml::escape(&anime.url));
+        }
+        let _ = writeln!(rss, "    <guid isPermaLink=\"false\">mal:{}</guid>", anime.mal_id);
+        if let Some(collected_at) = anime.collected_at {
+            let _ = writeln!(rss, "    <pubDate>{}</pubDate>", collected_at.to_rfc2822());
+        }
+        let _ = writeln!(rss, "    <description>{}</description>", xml::escape(&description(anime)));
+        rss.push_str("  </item>\n");
+    }
+
+    rss.push_str("</channel>\n</rss>\n");
+    rss
+}
+
+/// HTML description: cover picture followed by the synopsis
+fn description(anime: &AnimeData) -> String {
+    let cover = [&anime.images.jpg.large_image_url, &anime.images.jpg.image_url]
+        .into_iter()
+        .find(|url| !url.is_empty());
+
+    let mut html = String::new();
+    if let Some(cover) = cover {
+        let _ = write!(html, "<p><img src=\"{}\" alt=\"{}\"/></p>", xml::escape(cover), xml::escape(anime.display_title()));
+    }
+    let _ = write!(html, "<p>{}</p>", xml::escape(&anime.synopsis));
+    html
+}
@@ -4,9 +4,22 @@ use axum::{
     Json,
 };
 use serde::{Deserialize, Serialize};
-use tracing::error;
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use tracing::{error, warn};
 
 use crate::api::state::ApiState;
+use crate::global::config::AppConfig;
+use crate::global::http::ClientWithLimiter;
+use crate::global::supervisor::{ModuleState, ModuleSupervisor};
+use crate::global::validation::check_storage_writable;
+
+/// Timeout of each individual readiness probe
+const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Provider reachability results are reused for this long
+const PROVIDER_CACHE_TTL: Duration = Duration::from_secs(60);
 
 #[derive(Serialize)]
 pub struct HealthResponse {
@@ -32,6 +45,98 @@ struct DatabaseStats {
 struct ModuleStats {
     anime_enabled: bool,
     picture_enabled: bool,
+    video_enabled: bool,
+    music_enabled: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ComponentStatus {
+    Up,
+    Down,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ComponentHealth {
+    name: String,
+    status: ComponentStatus,
+    /// Whether a failure of this component makes the service not ready
+    critical: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    latency_ms: Option<u64>,
+}
+
+impl ComponentHealth {
+    fn up(name: impl Into<String>, critical: bool) -> Self {
+        Self {
+            name: name.into(),
+            status: ComponentStatus::Up,
+            critical,
+            message: None,
+            latency_ms: None,
+        }
+    }
+
+    fn down(name: impl Into<String>, critical: bool, message: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            status: ComponentStatus::Down,
+            critical,
+            message: Some(message.into()),
+            latency_ms: None,
+        }
+    }
+
+    fn with_message(mut self, message: impl Into<String>) -> Self {
+        self.message = Some(message.into());
+        self
+    }
+
+    fn with_latency(mut self, started: Instant) -> Self {
+        self.latency_ms = Some(started.elapsed().as_millis() as u64);
+        self
+    }
+}
+
+#[derive(Serialize)]
+pub struct ReadinessResponse {
+    status: String,
+    components: Vec<ComponentHealth>,
+}
+
+#[derive(Serialize)]
+struct ProviderCapability {
+    name: String,
+    /// Enabled in the configuration
+    enabled: bool,
+    /// Enabled with everything it needs, e.g. the MyAnimeList API key
+    configured: bool,
+    /// Last reachability probe, not probed when the provider is disabled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reachable: Option<bool>,
+}
+
+#[derive(Serialize)]
+struct StorageCapability {
+    writable: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct CapabilitiesResponse {
+    /// Namespace of the library, absent for the default one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    namespace: Option<String>,
+    modules: ModuleStats,
+    /// Every action is unavailable while read-only
+    read_only: bool,
+    providers: Vec<ProviderCapability>,
+    picture_storage: StorageCapability,
+    /// Whether each API action can currently be served, by action name
+    actions: BTreeMap<&'static str, bool>,
 }
 
 /// Health check endpoint
@@ -42,6 +147,274 @@ pub async fn health_check() -> Json<HealthResponse> {
     })
 }
 
+/// Liveness probe: the process is running and serving requests
+/// GET /health/live
+pub async fn liveness() -> Json<HealthResponse> {
+    Json(HealthResponse {
+        status: "alive".to_string(),
+        version: env!("CARGO_PKG_VERSION").to_string(),
+    })
+}
+
+/// Readiness probe with per-component breakdown
+/// GET /health/ready
+/// Returns 503 if any critical component (database, storage, module heartbeats) is down.
+/// Provider reachability is reported but not critical.
+pub async fn readiness(
+    State(state): State<ApiState>,
+) -> (StatusCode, Json<ReadinessResponse>) {
+    let config = state.config.load_full();
+    let mut components = Vec::new();
+
+    // Database
+    let started = Instant::now();
+    let db_health = match tokio::time::timeout(PROBE_TIMEOUT, state.db.check_connection()).await {
+        Ok(Ok(())) => ComponentHealth::up("database", true),
+        Ok(Err(e)) => ComponentHealth::down("database", true, e.to_string()),
+        Err(_) => ComponentHealth::down("database", true, "ping timed out"),
+    };
+    components.push(db_health.with_latency(started));
+
+    // Picture storage
+    components.push(storage_health(&config).await);
+
+    // Free space of the storage filesystems
+    components.extend(disk_health(&state, &config).await);
+
+    // Module heartbeats
+    components.extend(module_heartbeats(&state).await);
+
+    // Providers
+    for (name, client) in providers(&state) {
+        if config.is_child_module_enabled(name) {
+            components.push(provider_health(&state, name, client).await);
+        }
+    }
+
+    let ready = components
+        .iter()
+        .all(|c| !c.critical || c.status == ComponentStatus::Up);
+
+    let (status_code, status) = if ready {
+        (StatusCode::OK, "ready")
+    } else {
+        warn!(components = ?components, "Readiness check failed");
+        (StatusCode::SERVICE_UNAVAILABLE, "not_ready")
+    };
+
+    (
+        status_code,
+        Json(ReadinessResponse {
+            status: status.to_string(),
+            components,
+        }),
+    )
+}
+
+/// Provider modules, actions and picture storage available to API clients,
+/// so frontends can hide what would only answer 503
+/// GET /api/capabilities
+pub async fn capabilities(
+    State(state): State<ApiState>,
+) -> Json<CapabilitiesResponse> {
+    let config = state.config.load_full();
+
+    let mut providers_capabilities = Vec::new();
+    for (name, client) in providers(&state) {
+        let enabled = config.is_child_module_enabled(name);
+        let reachable = if enabled {
+            Some(provider_health(&state, name, client).await.status == ComponentStatus::Up)
+        } else {
+            None
+        };
+        providers_capabilities.push(ProviderCapability {
+            name: name.to_string(),
+            enabled,
+            configured: provider_configured(&config, name),
+            reachable,
+        });
+    }
+
+    let storage = storage_health(&config).await;
+    let storage_writable = storage.status == ComponentStatus::Up;
+
+    let read_only = state.is_read_only();
+    let anime = state.anime_module.is_some();
+    let writable = !read_only;
+    let mal = writable && state.mal_module().is_ok();
+    let anilist = writable && state.anilist_module().is_ok();
+    let pictures = writable && state.picture_module.is_some() && storage_writable;
+    let videos = writable && state.video_module.is_some();
+
+    let actions = BTreeMap::from([
+        ("anime.fetch", mal),
+        ("anime.search", mal),
+        ("anime.update", mal),
+        ("anime.batch", mal),
+        ("anime.extended", mal),
+        ("anime.collect", mal),
+        ("anime.complete", mal),
+        ("anime.refresh_airing", mal),
+        ("anime.fetch_pictures", mal && pictures),
+        ("anime.anilist_fetch", anilist),
+        ("picture.fetch", pictures),
+        ("video.fetch", videos),
+        ("video.anime", videos && anime),
+        ("music.anime", writable && anime && state.music_module.is_some()),
+        ("taxonomy.sync", writable && anime),
+        ("watchlist.refresh", writable && anime),
+    ]);
+
+    Json(CapabilitiesResponse {
+        namespace: state.namespace.clone(),
+        read_only,
+        modules: ModuleStats {
+            anime_enabled: anime,
+            picture_enabled: state.picture_module.is_some(),
+            video_enabled: state.video_module.is_some(),
+            music_enabled: state.music_module.is_some(),
+        },
+        providers: providers_capabilities,
+        picture_storage: StorageCapability {
+            writable: storage_writable,
+            message: storage.message,
+        },
+        actions,
+    })
+}
+
+/// Providers, probed at their API URL to check their reachability
+fn providers(state: &ApiState) -> [(&'static str, &ClientWithLimiter); 3] {
+    let http = &state.http_manager;
+    [
+        ("my_anime_list", http.my_anime_list()),
+        ("jikan", http.jikan()),
+        ("anilist", http.anilist()),
+    ]
+}
+
+/// Whether a provider is enabled with everything it needs to start,
+/// without the warning logged by `can_start_child_module`
+fn provider_configured(config: &AppConfig, name: &str) -> bool {
+    config.validate_child_module(name, name == "my_anime_list").is_ok()
+}
+
+/// Whether the picture storage directory accepts writes
+async fn storage_health(config: &AppConfig) -> ComponentHealth {
+    let storage_path = Path::new(&config.picture.storage_path).to_path_buf();
+    match tokio::task::spawn_blocking(move || check_storage_writable(&storage_path)).await {
+        Ok(Ok(())) => ComponentHealth::up("storage", true),
+        Ok(Err(message)) => ComponentHealth::down("storage", true, message),
+        Err(e) => ComponentHealth::down("storage", true, e.to_string()),
+    }
+}
+
+/// Free space left for the storage directories, as of their last measurement.
+/// Low space is reported but not critical, downloads only start failing once it runs out.
+async fn disk_health(state: &ApiState, config: &AppConfig) -> Vec<ComponentHealth> {
+    state.disk_monitor
+        .usage(config)
+        .await
+        .into_iter()
+        .map(|usage| {
+            let name = format!("disk.{}", usage.name);
+            let Some(free_bytes) = usage.free_bytes else {
+                return ComponentHealth::up(name, false).with_message("free space unknown");
+            };
+            let message = format!("{} MB free, {} files using {} MB", free_bytes / (1024 * 1024), usage.files, usage.bytes / (1024 * 1024));
+            if usage.is_low(config.disk.min_free_mb) {
+                ComponentHealth::down(name, false, format!("low space, {}", message))
+            } else {
+                ComponentHealth::up(name, false).with_message(message)
+            }
+        })
+        .collect()
+}
+
+/// Heartbeat status of the supervised parent modules
+async fn module_heartbeats(state: &ApiState) -> Vec<ComponentHealth> {
+    let Some(statuses) = state.module_statuses.as_ref() else {
+        return Vec::new();
+    };
+
+    ModuleSupervisor::snapshot(statuses)
+        .await
+        .into_iter()
+        .map(|module| {
+            let name = format!("module.{}", module.name);
+            let message = match module.last_active {
+                Some(last_active) => format!(
+                    "last heartbeat {}s ago, {} items processed",
+                    (chrono::Utc::now() - last_active).num_seconds().max(0),
+                    module.items_processed
+                ),
+                None => "no heartbeat recorded".to_string(),
+            };
+
+            match module.state {
+                ModuleState::Running | ModuleState::Paused if module.stalled => {
+                    ComponentHealth::down(name, true, format!("stalled, {}", message))
+                }
+                ModuleState::Running => ComponentHealth::up(name, true).with_message(message),
+                ModuleState::Paused => ComponentHealth::up(name, true).with_message(format!("paused, {}", message)),
+                other => ComponentHealth::down(name, true, format!("{:?}, {}", other, message).to_lowercase()),
+            }
+        })
+        .collect()
+}
+
+/// Check that a provider answers HTTP requests, cached for PROVIDER_CACHE_TTL.
+/// Probes go through the provider's rate limiter and count against its daily budget.
+async fn provider_health(
+    state: &ApiState,
+    name: &str,
+    client: &ClientWithLimiter,
+) -> ComponentHealth {
+    let component = format!("provider.{}", name);
+
+    // Held during the probe, concurrent health requests reuse its result
+    let mut cache = state.provider_health_cache.lock().await;
+    if let Some((checked_at, health)) = cache.get(&component)
+        && checked_at.elapsed() < PROVIDER_CACHE_TTL
+    {
+        return health.clone();
+    }
+
+    let health = probe_provider(&component, client).await;
+    cache.insert(component, (Instant::now(), health.clone()));
+    health
+}
+
+/// Send a request to the provider's API URL, any HTTP response means it is reachable
+async fn probe_provider(component: &str, client: &ClientWithLimiter) -> ComponentHealth {
+    // Reachability is unknown then, the provider isn't reported down for it
+    if client.budget().is_exhausted() {
+        return ComponentHealth::up(component, false).with_message("not probed, daily budget exhausted");
+    }
+    if tokio::time::timeout(PROBE_TIMEOUT, client.limiter.acquire()).await.is_err() {
+        return ComponentHealth::up(component, false).with_message("not probed, rate limit reached");
+    }
+
+    let started = Instant::now();
+    let response = tokio::time::timeout(PROBE_TIMEOUT, client.client.get(&client.base_url).send()).await;
+    match response {
+        Ok(Ok(response)) => {
+            client.track_request(Some(response.status())).await;
+            ComponentHealth::up(component, false)
+                .with_message(format!("HTTP {}", response.status().as_u16()))
+                .with_latency(started)
+        }
+        Ok(Err(e)) => {
+            client.track_request(None).await;
+            ComponentHealth::down(component, false, e.to_string())
+        }
+        Err(_) => {
+            client.track_request(None).await;
+            ComponentHealth::down(component, false, "request timed out")
+        }
+    }
+}
+
 /// Get application statistics
 pub async fn get_stats(
     State(state): State<ApiState>,
@@ -62,6 +435,8 @@ pub async fn get_stats(
         modules: ModuleStats {
             anime_enabled: state.anime_module.is_some(),
             picture_enabled: state.picture_module.is_some(),
+            video_enabled: state.video_module.is_some(),
+            music_enabled: state.music_module.is_some(),
         },
     };
 
@@ -0,0 +1,92 @@
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use tracing::error;
+
+use crate::api::error::ApiError;
+use crate::api::state::ApiState;
+use crate::global::job::{self, Job, JobStatus};
+
+// ========================================================================
+// Request/Response Types
+// ========================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct JobsQuery {
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+}
+
+fn default_limit() -> i64 {
+    20
+}
+
+#[derive(Serialize)]
+pub struct JobResponse {
+    #[serde(flatten)]
+    pub job: Job,
+    pub status: JobStatus,
+    pub pending_tasks: u64,
+    pub progress: f64,
+}
+
+impl From<Job> for JobResponse {
+    fn from(job: Job) -> Self {
+        Self {
+            status: job.status(),
+            pending_tasks: job.pending_tasks(),
+            progress: job.progress(),
+            job,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct JobsResponse {
+    pub jobs: Vec<JobResponse>,
+    pub count: usize,
+}
+
+// ========================================================================
+// Handlers
+// ========================================================================
+
+/// List the most recent jobs
+/// GET /api/jobs?limit=20
+pub async fn list_jobs(
+    State(state): State<ApiState>,
+    Query(query): Query<JobsQuery>,
+) -> Result<Json<JobsResponse>, ApiError> {
+    let jobs = job::list_jobs(&state.db, query.limit.clamp(1, 500))
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Failed to list jobs");
+            ApiError::from(e)
+        })?;
+
+    let jobs: Vec<JobResponse> = jobs.into_iter().map(JobResponse::from).collect();
+
+    Ok(Json(JobsResponse {
+        count: jobs.len(),
+        jobs,
+    }))
+}
+
+/// Get the aggregate status of a job, its tasks are listed by GET /api/tasks?job_id=...
+/// GET /api/jobs/{id}
+pub async fn get_job(
+    State(state): State<ApiState>,
+    Path(job_id): Path<String>,
+) -> Result<Json<JobResponse>, ApiError> {
+    let job = job::get_job(&state.db, &job_id)
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Failed to get job");
+            ApiError::from(e)
+        })?
+        .ok_or_else(|| ApiError::not_found(format!("Job {} not found", job_id)))?;
+
+    Ok(Json(job.into()))
+}
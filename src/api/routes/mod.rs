@@ -1,27 +1,97 @@
 pub mod anime;
 pub mod picture;
+pub mod video;
+pub mod music;
 pub mod health;
+pub mod modules;
+pub mod tasks;
+pub mod jobs;
+pub mod feeds;
+pub mod admin;
+pub mod taxonomy;
+pub mod watchlist;
+pub mod stats;
 
 use axum::{
-    Router, routing::{delete, get, post}
+    Router, middleware, routing::{delete, get, post}
 };
 
+use crate::api::admin_key;
+use crate::api::namespace::{self, Namespace};
+use crate::api::read_only::{self, READ_ONLY_TOGGLE_PATH};
 use crate::api::state::ApiState;
 
-/// Create the main API router
-pub fn create_router(state: ApiState) -> Router {
-    Router::new()
+/// Create the main API router, with the library routes of each namespace under /ns/{name}
+pub fn create_router(state: ApiState, namespaces: Vec<Namespace>) -> Router {
+    let read_only_state = state.clone();
+    let mut router = Router::new()
         // Health check
         .route("/health", get(health::health_check))
+        .route("/health/live", get(health::liveness))
+        .route("/health/ready", get(health::readiness))
         .route("/stats", get(health::get_stats))
         
+        .route("/api/modules", get(modules::list_modules))
+        .route(READ_ONLY_TOGGLE_PATH, get(admin::get_read_only))
+
+        // Module control and the read-only toggle, shared by every namespace
+        .merge(
+            Router::new()
+                .route("/api/modules/{name}/pause", post(modules::pause_module))
+                .route("/api/modules/{name}/resume", post(modules::resume_module))
+                .route(READ_ONLY_TOGGLE_PATH, post(admin::set_read_only))
+                .route_layer(middleware::from_fn_with_state(state.clone(), admin_key::require_admin_key)),
+        )
+
+        .merge(library_routes(&state))
+        .with_state(state);
+
+    for namespace in namespaces {
+        let routes = library_routes(&namespace.state)
+            .with_state(namespace.state.clone())
+            .layer(middleware::from_fn_with_state(namespace.clone(), namespace::require_api_key));
+        router = router.nest(&format!("/ns/{}", namespace.name), routes);
+    }
+
+    router.layer(middleware::from_fn_with_state(read_only_state, read_only::reject_mutations))
+}
+
+/// Routes of a library's data and tasks, served for the default library and every namespace
+fn library_routes(state: &ApiState) -> Router<ApiState> {
+    Router::new()
+        .route("/api/capabilities", get(health::capabilities))
+        .route("/api/stats/overview", get(stats::overview))
+
+        // Task and job routes
+        .route("/api/tasks", get(tasks::list_tasks))
+        .route("/api/tasks/{id}/cancel", post(tasks::cancel_task))
+        .route("/api/jobs", get(jobs::list_jobs))
+        .route("/api/jobs/{id}", get(jobs::get_job))
+        
         // Anime routes
         .route("/api/anime/fetch", post(anime::fetch_anime))
         .route("/api/anime/search", post(anime::search_anime))
+        .route("/api/anime/search/fuzzy", get(anime::fuzzy_search))
+        .route("/api/anime/search/history", get(anime::search_history))
+        .route("/api/anime/search/suggest", get(anime::suggest))
+        .route("/api/anime/autocomplete", get(anime::autocomplete))
+        .route("/api/anime/random", get(anime::random_anime))
+        .route("/api/anime/search/{id}", get(anime::get_search_results))
         .route("/api/anime/update", post(anime::update_anime))
         .route("/api/anime/batch", post(anime::batch_fetch))
         .route("/api/anime/extended", post(anime::fetch_extended_data))
+        .route("/api/anime/collect", post(anime::collect_anime))
+        .route("/api/anime/refresh-airing", post(anime::refresh_airing))
+        .route("/api/anime/airing.ics", get(anime::airing_calendar))
         .route("/api/anime/{id}", get(anime::get_anime))
+        .route("/api/anime/{id}/history", get(anime::get_anime_history))
+        .route("/api/anime/{id}/complete", post(anime::complete_anime))
+        .route("/api/anime/{id}/completeness", get(anime::get_anime_completeness))
+        .route("/api/anime/{id}/episodes", get(anime::get_anime_episodes))
+        .route("/api/anime/{id}/episodes/fillers", get(anime::get_filler_list))
+        .route("/api/anime/{id}/episodes/{episode}", get(anime::get_anime_episode))
+        .route("/api/anime/{id}/trends", get(anime::get_anime_trends))
+        .route("/api/anime/{id}/related", get(anime::get_related_graph))
         
         .route("/api/anime/anilist/fetch", post(anime::fetch_from_anilist))
 
@@ -32,6 +102,53 @@ pub fn create_router(state: ApiState) -> Router {
         .route("/api/picture", delete(picture::delete_picture))
         .route("/api/picture/list", get(picture::list_pictures))
         .route("/api/picture/stats", get(picture::get_stats))
-        
-        .with_state(state)
+        .route("/api/picture/{id}", get(picture::get_picture_by_id).delete(picture::delete_picture_by_id))
+
+        // Video routes
+        .route("/api/video/fetch", post(video::fetch_video))
+        .route("/api/video/anime/{id}", post(video::fetch_anime_videos))
+        .route("/api/video", get(video::get_video))
+        .route("/api/video", delete(video::delete_video))
+        .route("/api/video/list", get(video::list_videos))
+        .route("/api/video/stats", get(video::get_stats))
+
+        // Theme song routes
+        .route("/api/music/anime/{id}", get(music::get_anime_theme_songs))
+        .route("/api/music/anime/{id}", post(music::fetch_anime_theme_songs))
+        .route("/api/music/search", get(music::search_theme_songs))
+
+        // Taxonomy routes
+        .route("/api/taxonomy/sync", post(taxonomy::sync_taxonomy))
+        .route("/api/taxonomy/genres", get(taxonomy::list_genres))
+        .route("/api/taxonomy/genres/{id}/anime", get(taxonomy::anime_by_genre))
+        .route("/api/taxonomy/producers", get(taxonomy::list_producers))
+        .route("/api/taxonomy/producers/{id}/anime", get(taxonomy::anime_by_producer))
+
+        // Watchlist routes
+        .route("/api/watchlist", get(watchlist::list_watchlist))
+        .route("/api/watchlist", post(watchlist::add_to_watchlist))
+        .route("/api/watchlist", delete(watchlist::remove_from_watchlist))
+        .route("/api/watchlist/refresh", post(watchlist::refresh_watchlist))
+        .route("/api/watchlist/import", post(watchlist::import_watchlist))
+        .route("/api/watchlist/export.xml", get(watchlist::export_watchlist))
+
+        // Feeds
+        .route("/api/feeds/new-anime.xml", get(feeds::new_anime_feed))
+
+        // Admin routes
+        .route("/api/admin/duplicates", get(admin::get_duplicates))
+        .route("/api/admin/quality", get(admin::get_quality))
+        .merge(admin_routes(state))
+}
+
+/// Mutating admin routes of a library, they require an admin API key
+fn admin_routes(state: &ApiState) -> Router<ApiState> {
+    Router::new()
+        .route("/api/admin/duplicates/scan", post(admin::scan_duplicates))
+        .route("/api/admin/duplicates/merge", post(admin::merge_duplicates))
+        .route("/api/admin/quality/validate", post(admin::validate_collection))
+        .route("/api/admin/titles/reindex", post(admin::reindex_titles))
+        .route("/api/admin/reprocess", post(admin::reprocess_raw_responses))
+        .route("/api/admin/pictures/gc", post(admin::collect_picture_garbage))
+        .route_layer(middleware::from_fn_with_state(state.clone(), admin_key::require_admin_key))
 }
\ No newline at end of file
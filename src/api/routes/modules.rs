@@ -0,0 +1,105 @@
+use axum::{
+    extract::{Path, State},
+    Json,
+};
+use serde::Serialize;
+
+use crate::api::error::ApiError;
+use crate::api::state::ApiState;
+use crate::global::module::ModuleHandle;
+use crate::global::supervisor::{ModuleStatus, ModuleSupervisor};
+
+// ========================================================================
+// Request/Response Types
+// ========================================================================
+
+#[derive(Serialize)]
+pub struct ModulesResponse {
+    pub modules: Vec<ModuleStatus>,
+    pub count: usize,
+}
+
+#[derive(Serialize)]
+pub struct ModuleActionResponse {
+    pub module: String,
+    pub action: String,
+    pub message: String,
+}
+
+// ========================================================================
+// Handlers
+// ========================================================================
+
+/// List parent modules with their supervisor state, restart counts and heartbeat
+/// GET /api/modules
+pub async fn list_modules(
+    State(state): State<ApiState>,
+) -> Result<Json<ModulesResponse>, ApiError> {
+    let statuses = state.module_statuses.as_ref().ok_or_else(|| ApiError::module_disabled("Module supervisor not available"))?;
+
+    let modules = ModuleSupervisor::snapshot(statuses).await;
+
+    Ok(Json(ModulesResponse {
+        count: modules.len(),
+        modules,
+    }))
+}
+
+/// Stop a module from processing tasks, e.g. during a maintenance window.
+/// Queued tasks are kept and processed after resume. Requires an admin API key.
+/// POST /api/modules/{name}/pause
+pub async fn pause_module(
+    State(state): State<ApiState>,
+    Path(name): Path<String>,
+) -> Result<Json<ModuleActionResponse>, ApiError> {
+    let handle = find_handle(&state, &name).await?;
+
+    handle.pause().await.map_err(|e| ApiError::internal(e.to_string()))?;
+
+    Ok(Json(ModuleActionResponse {
+        module: handle.name.clone(),
+        action: "pause".to_string(),
+        message: "Module paused".to_string(),
+    }))
+}
+
+/// Resume a paused module, requires an admin API key
+/// POST /api/modules/{name}/resume
+pub async fn resume_module(
+    State(state): State<ApiState>,
+    Path(name): Path<String>,
+) -> Result<Json<ModuleActionResponse>, ApiError> {
+    let handle = find_handle(&state, &name).await?;
+
+    handle.resume().await.map_err(|e| ApiError::internal(e.to_string()))?;
+
+    Ok(Json(ModuleActionResponse {
+        module: handle.name.clone(),
+        action: "resume".to_string(),
+        message: "Module resumed".to_string(),
+    }))
+}
+
+// ========================================================================
+// Helpers
+// ========================================================================
+
+/// Find a module handle by module name (e.g. `picture_fetcher`) or config name (e.g. `picture`)
+async fn find_handle<'a>(
+    state: &'a ApiState,
+    name: &str,
+) -> Result<&'a ModuleHandle, ApiError> {
+    let mut module_name = name.to_string();
+
+    if let Some(statuses) = state.module_statuses.as_ref()
+        && let Some(status) = statuses.read().await.values().find(|s| s.config_name == name)
+    {
+        module_name = status.name.clone();
+    }
+
+    state
+        .module_handles
+        .iter()
+        .find(|handle| handle.name == module_name)
+        .ok_or_else(|| ApiError::not_found(format!("Module '{}' is not running", name)))
+}
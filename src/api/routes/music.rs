@@ -0,0 +1,106 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::HeaderMap,
+    response::Response,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use tracing::{info, error};
+
+use crate::api::{cache, dto::v1, error::ApiError, state::ApiState};
+use crate::music::database;
+
+// ========================================================================
+// Request/Response Types
+// ========================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct SearchThemeSongsQuery {
+    pub artist: Option<String>,
+    pub title: Option<String>,
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+}
+
+fn default_limit() -> i64 {
+    50
+}
+
+#[derive(Serialize)]
+pub struct TaskQueuedResponse {
+    pub message: String,
+    pub task_type: String,
+}
+
+#[derive(Serialize)]
+pub struct ThemeSongsResponse {
+    pub theme_songs: Vec<v1::ThemeSong>,
+    pub count: usize,
+}
+
+// ========================================================================
+// Handlers
+// ========================================================================
+
+/// Parse and store the theme songs of a stored anime
+/// POST /api/music/anime/{id}
+pub async fn fetch_anime_theme_songs(
+    State(state): State<ApiState>,
+    Path(anime_id): Path<u32>,
+) -> Result<Json<TaskQueuedResponse>, ApiError> {
+    info!(anime_id = anime_id, "API request: fetch theme songs");
+
+    let music_module = state.music_module.as_ref()
+        .ok_or_else(|| ApiError::module_disabled("Music module is not enabled"))?;
+    state.check_music_queue()?;
+
+    music_module.queue_fetch_theme_songs(anime_id)
+        .await
+        .map_err(|e| {
+            error!(error = %e, anime_id = anime_id, "Failed to queue theme songs task");
+            ApiError::from(e)
+        })?;
+
+    Ok(Json(TaskQueuedResponse {
+        message: format!("Theme songs of anime {} queued for fetching", anime_id),
+        task_type: "fetch_theme_songs".to_string(),
+    }))
+}
+
+/// Stored theme songs of an anime, openings first
+/// GET /api/music/anime/{id}
+pub async fn get_anime_theme_songs(
+    State(state): State<ApiState>,
+    Path(anime_id): Path<u32>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    info!(anime_id = anime_id, "API request: get theme songs");
+
+    let theme_songs = database::get_anime_theme_songs(state.db.db(), anime_id as i32).await?;
+
+    let count = theme_songs.len();
+    let last_modified = theme_songs.iter().map(|song| song.updated_at).max();
+    let theme_songs = theme_songs.into_iter().map(v1::ThemeSong::from).collect();
+    Ok(cache::conditional_json(&headers, &ThemeSongsResponse { theme_songs, count }, last_modified))
+}
+
+/// Search the stored theme songs by artist and/or title
+/// GET /api/music/search?artist=Linked+Horizon&title=guren&limit=50
+pub async fn search_theme_songs(
+    State(state): State<ApiState>,
+    Query(query): Query<SearchThemeSongsQuery>,
+) -> Result<Json<ThemeSongsResponse>, ApiError> {
+    info!(artist = ?query.artist, title = ?query.title, "API request: search theme songs");
+
+    let artist = query.artist.as_deref().map(str::trim).filter(|artist| !artist.is_empty());
+    let title = query.title.as_deref().map(str::trim).filter(|title| !title.is_empty());
+    if artist.is_none() && title.is_none() {
+        return Err(ApiError::validation("Must provide artist or title"));
+    }
+
+    let theme_songs = database::search_theme_songs(state.db.db(), artist, title, query.limit.clamp(1, 200)).await?;
+
+    let count = theme_songs.len();
+    let theme_songs = theme_songs.into_iter().map(v1::ThemeSong::from).collect();
+    Ok(Json(ThemeSongsResponse { theme_songs, count }))
+}
@@ -1,20 +1,25 @@
 use axum::{
     extract::{Path, Query, State},
-    http::StatusCode,
+    http::HeaderMap,
+    response::Response,
     Json,
 };
+use mongodb::bson::oid::ObjectId;
 use serde::{Deserialize, Serialize};
+use validator::Validate;
 use tracing::{info, error};
 
-use crate::api::state::ApiState;
+use crate::api::{error::ApiError, extract::{validate_url, validate_urls, ValidatedJson}};
+use crate::api::{cache, dto::v1, state::ApiState};
 use crate::picture::{database, model::PictureStats};
 
 // ========================================================================
 // Request/Response Types
 // ========================================================================
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Validate)]
 pub struct FetchPictureRequest {
+    #[validate(custom(function = "validate_url"))]
     pub url: String,
     pub filename: Option<String>,
     #[serde(default)]
@@ -23,8 +28,12 @@ pub struct FetchPictureRequest {
     pub entity_id: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Validate)]
 pub struct BatchFetchPicturesRequest {
+    #[validate(
+        length(min = 1, max = 100, message = "must contain between 1 and 100 URLs"),
+        custom(function = "validate_urls")
+    )]
     pub urls: Vec<String>,
     #[serde(default)]
     pub tags: Vec<String>,
@@ -52,23 +61,25 @@ pub struct TaskQueuedResponse {
 
 #[derive(Serialize)]
 pub struct PictureResponse {
-    pub picture: crate::picture::model::PictureMetadata,
+    pub picture: v1::Picture,
 }
 
 #[derive(Serialize)]
 pub struct PicturesResponse {
-    pub pictures: Vec<crate::picture::model::PictureMetadata>,
+    pub pictures: Vec<v1::Picture>,
     pub count: usize,
 }
 
 #[derive(Serialize)]
-pub struct StatsResponse {
-    pub stats: PictureStats,
+pub struct PictureDeletedResponse {
+    pub id: Option<String>,
+    pub url: String,
+    pub message: String,
 }
 
 #[derive(Serialize)]
-pub struct ErrorResponse {
-    pub error: String,
+pub struct StatsResponse {
+    pub stats: PictureStats,
 }
 
 // ========================================================================
@@ -80,8 +91,8 @@ pub struct ErrorResponse {
 /// Body: { "url": "https://example.com/image.jpg", "filename": "custom_name.jpg", "tags": ["anime", "cover"], "entity_type": "anime", "entity_id": "123" }
 pub async fn fetch_picture(
     State(state): State<ApiState>,
-    Json(request): Json<FetchPictureRequest>,
-) -> Result<Json<TaskQueuedResponse>, (StatusCode, Json<ErrorResponse>)> {
+    ValidatedJson(request): ValidatedJson<FetchPictureRequest>,
+) -> Result<Json<TaskQueuedResponse>, ApiError> {
     info!(
         url = %request.url,
         filename = ?request.filename,
@@ -92,13 +103,9 @@ pub async fn fetch_picture(
     let picture_module = state.picture_module.as_ref()
         .ok_or_else(|| {
             error!("Picture module not available");
-            (
-                StatusCode::SERVICE_UNAVAILABLE,
-                Json(ErrorResponse {
-                    error: "Picture module is not enabled".to_string(),
-                })
-            )
+            ApiError::module_disabled("Picture module is not enabled")
         })?;
+    state.check_picture_queue()?;
 
     // Queue with entity and tags if provided
     if let (Some(entity_type), Some(entity_id)) = (request.entity_type, request.entity_id) {
@@ -113,12 +120,7 @@ pub async fn fetch_picture(
             .await
             .map_err(|e| {
                 error!(error = %e, "Failed to queue picture fetch task");
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(ErrorResponse {
-                        error: format!("Failed to queue task: {}", e),
-                    })
-                )
+                ApiError::internal(format!("Failed to queue task: {}", e))
             })?;
     } else if !request.tags.is_empty() {
         picture_module
@@ -130,12 +132,7 @@ pub async fn fetch_picture(
             .await
             .map_err(|e| {
                 error!(error = %e, "Failed to queue picture fetch task");
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(ErrorResponse {
-                        error: format!("Failed to queue task: {}", e),
-                    })
-                )
+                ApiError::internal(format!("Failed to queue task: {}", e))
             })?;
     } else {
         picture_module
@@ -143,12 +140,7 @@ pub async fn fetch_picture(
             .await
             .map_err(|e| {
                 error!(error = %e, "Failed to queue picture fetch task");
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(ErrorResponse {
-                        error: format!("Failed to queue task: {}", e),
-                    })
-                )
+                ApiError::internal(format!("Failed to queue task: {}", e))
             })?;
     }
 
@@ -163,33 +155,20 @@ pub async fn fetch_picture(
 /// Body: { "urls": ["https://example.com/1.jpg", "https://example.com/2.jpg"], "tags": ["anime"] }
 pub async fn batch_fetch(
     State(state): State<ApiState>,
-    Json(request): Json<BatchFetchPicturesRequest>,
-) -> Result<Json<TaskQueuedResponse>, (StatusCode, Json<ErrorResponse>)> {
+    ValidatedJson(request): ValidatedJson<BatchFetchPicturesRequest>,
+) -> Result<Json<TaskQueuedResponse>, ApiError> {
     info!(
         count = request.urls.len(),
         tags = ?request.tags,
         "API request: batch fetch pictures"
     );
 
-    if request.urls.is_empty() {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse {
-                error: "No URLs provided".to_string(),
-            })
-        ));
-    }
-
     let picture_module = state.picture_module.as_ref()
         .ok_or_else(|| {
             error!("Picture module not available");
-            (
-                StatusCode::SERVICE_UNAVAILABLE,
-                Json(ErrorResponse {
-                    error: "Picture module is not enabled".to_string(),
-                })
-            )
+            ApiError::module_disabled("Picture module is not enabled")
         })?;
+    state.check_picture_queue()?;
 
     // Queue each picture
     for url in &request.urls {
@@ -199,12 +178,7 @@ pub async fn batch_fetch(
                 .await
                 .map_err(|e| {
                     error!(error = %e, url = %url, "Failed to queue picture");
-                    (
-                        StatusCode::INTERNAL_SERVER_ERROR,
-                        Json(ErrorResponse {
-                            error: format!("Failed to queue picture {}: {}", url, e),
-                        })
-                    )
+                    ApiError::internal(format!("Failed to queue picture {}: {}", url, e))
                 })?;
         } else {
             picture_module
@@ -212,12 +186,7 @@ pub async fn batch_fetch(
                 .await
                 .map_err(|e| {
                     error!(error = %e, url = %url, "Failed to queue picture");
-                    (
-                        StatusCode::INTERNAL_SERVER_ERROR,
-                        Json(ErrorResponse {
-                            error: format!("Failed to queue picture {}: {}", url, e),
-                        })
-                    )
+                    ApiError::internal(format!("Failed to queue picture {}: {}", url, e))
                 })?;
         }
     }
@@ -228,21 +197,20 @@ pub async fn batch_fetch(
     }))
 }
 
+/// Picture id of a path, a hex ObjectId
+fn parse_picture_id(id: &str) -> Result<ObjectId, ApiError> {
+    ObjectId::parse_str(id).map_err(|_| ApiError::validation(format!("Invalid picture id '{}'", id)))
+}
+
 /// Get picture metadata by URL
 /// GET /api/picture?url=https://example.com/image.jpg
 pub async fn get_picture(
     State(state): State<ApiState>,
     Query(params): Query<std::collections::HashMap<String, String>>,
-) -> Result<Json<PictureResponse>, (StatusCode, Json<ErrorResponse>)> {
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
     let url = params.get("url")
-        .ok_or_else(|| {
-            (
-                StatusCode::BAD_REQUEST,
-                Json(ErrorResponse {
-                    error: "Missing 'url' query parameter".to_string(),
-                })
-            )
-        })?;
+        .ok_or_else(|| ApiError::validation("Missing 'url' query parameter"))?;
 
     info!(url = %url, "API request: get picture");
 
@@ -250,23 +218,33 @@ pub async fn get_picture(
         .await
         .map_err(|e| {
             error!(error = %e, "Failed to get picture from database");
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ErrorResponse {
-                    error: format!("Database error: {}", e),
-                })
-            )
+            ApiError::from(e)
         })?
-        .ok_or_else(|| {
-            (
-                StatusCode::NOT_FOUND,
-                Json(ErrorResponse {
-                    error: format!("Picture not found: {}", url),
-                })
-            )
-        })?;
+        .ok_or_else(|| ApiError::not_found(format!("Picture not found: {}", url)))?;
 
-    Ok(Json(PictureResponse { picture }))
+    let last_modified = picture.updated_at;
+    Ok(cache::conditional_json(&headers, &PictureResponse { picture: picture.into() }, Some(last_modified)))
+}
+
+/// Get picture metadata by id
+/// GET /api/picture/{id}
+pub async fn get_picture_by_id(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    info!(id = %id, "API request: get picture by id");
+
+    let picture = database::get_picture_by_id(state.db.db(), parse_picture_id(&id)?)
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Failed to get picture from database");
+            ApiError::from(e)
+        })?
+        .ok_or_else(|| ApiError::not_found(format!("Picture not found: {}", id)))?;
+
+    let last_modified = picture.updated_at;
+    Ok(cache::conditional_json(&headers, &PictureResponse { picture: picture.into() }, Some(last_modified)))
 }
 
 /// Get pictures with filters
@@ -274,7 +252,8 @@ pub async fn get_picture(
 pub async fn list_pictures(
     State(state): State<ApiState>,
     Query(query): Query<GetPicturesQuery>,
-) -> Result<Json<PicturesResponse>, (StatusCode, Json<ErrorResponse>)> {
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
     info!(
         entity_type = ?query.entity_type,
         entity_id = ?query.entity_id,
@@ -288,113 +267,94 @@ pub async fn list_pictures(
             .await
             .map_err(|e| {
                 error!(error = %e, "Failed to get pictures");
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(ErrorResponse {
-                        error: format!("Database error: {}", e),
-                    })
-                )
+                ApiError::from(e)
             })?
     } else if let Some(tag) = &query.tag {
         database::get_pictures_by_tag(state.db.db(), tag, query.limit)
             .await
             .map_err(|e| {
                 error!(error = %e, "Failed to get pictures");
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(ErrorResponse {
-                        error: format!("Database error: {}", e),
-                    })
-                )
+                ApiError::from(e)
             })?
     } else if let Some(status) = &query.status {
         database::get_pictures_by_status(state.db.db(), status, query.limit)
             .await
             .map_err(|e| {
                 error!(error = %e, "Failed to get pictures");
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(ErrorResponse {
-                        error: format!("Database error: {}", e),
-                    })
-                )
+                ApiError::from(e)
             })?
     } else {
-        return Err((
-            StatusCode::BAD_REQUEST,
-            Json(ErrorResponse {
-                error: "Must provide entity_type+entity_id, tag, or status".to_string(),
-            })
-        ));
+        return Err(ApiError::validation("Must provide entity_type+entity_id, tag, or status"));
     };
 
     let count = pictures.len();
-    Ok(Json(PicturesResponse { pictures, count }))
+    let last_modified = pictures.iter().map(|picture| picture.updated_at).max();
+    let pictures = pictures.into_iter().map(v1::Picture::from).collect();
+    Ok(cache::conditional_json(&headers, &PicturesResponse { pictures, count }, last_modified))
 }
 
 /// Get picture statistics
 /// GET /api/picture/stats
 pub async fn get_stats(
     State(state): State<ApiState>,
-) -> Result<Json<StatsResponse>, (StatusCode, Json<ErrorResponse>)> {
+) -> Result<Json<StatsResponse>, ApiError> {
     info!("API request: get picture stats");
 
     let stats = database::get_picture_stats(state.db.db())
         .await
         .map_err(|e| {
             error!(error = %e, "Failed to get picture stats");
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ErrorResponse {
-                    error: format!("Database error: {}", e),
-                })
-            )
+            ApiError::from(e)
         })?;
 
     Ok(Json(StatsResponse { stats }))
 }
 
-/// Delete picture metadata
+/// Delete picture metadata, the response keeps its original shape; `DELETE /api/picture/{id}`
+/// answers with the id and URL of the deleted picture
 /// DELETE /api/picture?url=https://example.com/image.jpg
 pub async fn delete_picture(
     State(state): State<ApiState>,
     Query(params): Query<std::collections::HashMap<String, String>>,
-) -> Result<Json<TaskQueuedResponse>, (StatusCode, Json<ErrorResponse>)> {
+) -> Result<Json<TaskQueuedResponse>, ApiError> {
     let url = params.get("url")
-        .ok_or_else(|| {
-            (
-                StatusCode::BAD_REQUEST,
-                Json(ErrorResponse {
-                    error: "Missing 'url' query parameter".to_string(),
-                })
-            )
-        })?;
+        .ok_or_else(|| ApiError::validation("Missing 'url' query parameter"))?;
 
     info!(url = %url, "API request: delete picture");
 
-    let deleted = database::delete_picture(state.db.db(), url)
+    database::delete_picture(state.db.db(), url)
         .await
         .map_err(|e| {
             error!(error = %e, "Failed to delete picture");
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ErrorResponse {
-                    error: format!("Database error: {}", e),
-                })
-            )
-        })?;
-
-    if !deleted {
-        return Err((
-            StatusCode::NOT_FOUND,
-            Json(ErrorResponse {
-                error: format!("Picture not found: {}", url),
-            })
-        ));
-    }
+            ApiError::from(e)
+        })?
+        .ok_or_else(|| ApiError::not_found(format!("Picture not found: {}", url)))?;
 
     Ok(Json(TaskQueuedResponse {
         message: format!("Picture metadata deleted: {}", url),
         task_type: "delete_picture".to_string(),
     }))
+}
+
+/// Delete picture metadata by id, the stored file is kept
+/// DELETE /api/picture/{id}
+pub async fn delete_picture_by_id(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+) -> Result<Json<PictureDeletedResponse>, ApiError> {
+    info!(id = %id, "API request: delete picture by id");
+
+    let picture = database::delete_picture_by_id(state.db.db(), parse_picture_id(&id)?)
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Failed to delete picture");
+            ApiError::from(e)
+        })?
+        .ok_or_else(|| ApiError::not_found(format!("Picture not found: {}", id)))?;
+
+    Ok(Json(PictureDeletedResponse {
+        message: format!("Picture metadata deleted: {}", id),
+        id: Some(id),
+        url: picture.url,
+    }))
 }
\ No newline at end of file
@@ -0,0 +1,106 @@
+use axum::{extract::State, Json};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use tracing::{info, error};
+
+use crate::anime::{anilist, my_anime_list};
+use crate::api::{error::ApiError, state::ApiState};
+use crate::global::budget::BudgetUsage;
+use crate::global::database::DatabaseStats;
+use crate::global::disk::DiskUsage;
+use crate::global::http::RequestCounts;
+use crate::global::supervisor::ModuleSupervisor;
+use crate::picture::{database as picture_db, model::PictureStats};
+use crate::video::{database as video_db, model::VideoStats};
+
+#[derive(Serialize)]
+pub struct OverviewResponse {
+    generated_at: chrono::DateTime<chrono::Utc>,
+    database: CollectionCounts,
+    /// Persisted tasks by status
+    tasks: DatabaseStats,
+    /// Live counters of the module queues, by module
+    queues: BTreeMap<String, QueueCounts>,
+    pictures: PictureStats,
+    videos: VideoStats,
+    /// Requests sent to each provider since startup
+    providers: BTreeMap<&'static str, RequestCounts>,
+    /// Requests sent to each provider today, against its daily budget
+    budgets: BTreeMap<&'static str, BudgetUsage>,
+    /// Storage directories, as of their last measurement
+    disk: Vec<DiskUsage>,
+}
+
+#[derive(Serialize)]
+struct CollectionCounts {
+    anime_mal: u64,
+    anime_anilist: u64,
+}
+
+#[derive(Serialize)]
+struct QueueCounts {
+    processed: u64,
+    failed: u64,
+    pending: u64,
+    running: u64,
+}
+
+/// Everything a dashboard shows in one call
+/// GET /api/stats/overview
+pub async fn overview(
+    State(state): State<ApiState>,
+) -> Result<Json<OverviewResponse>, ApiError> {
+    info!("API request: get stats overview");
+
+    let db = state.db.db();
+    let config = state.config.load_full();
+
+    let tasks = state.db.get_stats().await.map_err(|e| {
+        error!(error = %e, "Failed to get database stats");
+        ApiError::from(e)
+    })?;
+    let database = CollectionCounts {
+        anime_mal: my_anime_list::database::get_anime_count(db).await?,
+        anime_anilist: anilist::database::get_anime_count(db).await?,
+    };
+    let pictures = picture_db::get_picture_stats(db).await?;
+    let videos = video_db::get_video_stats(db).await?;
+
+    let mut queues = BTreeMap::new();
+    if let Some(statuses) = state.module_statuses.as_ref() {
+        for module in ModuleSupervisor::snapshot(statuses).await {
+            queues.insert(module.name, QueueCounts {
+                processed: module.items_processed,
+                failed: module.items_failed,
+                pending: module.tasks_pending,
+                running: module.tasks_running,
+            });
+        }
+    }
+
+    let http = &state.http_manager;
+    let providers = BTreeMap::from([
+        ("my_anime_list", http.my_anime_list().request_counts()),
+        ("jikan", http.jikan().request_counts()),
+        ("anilist", http.anilist().request_counts()),
+    ]);
+    let budgets = BTreeMap::from([
+        ("my_anime_list", http.my_anime_list().budget().usage()),
+        ("jikan", http.jikan().budget().usage()),
+        ("anilist", http.anilist().budget().usage()),
+    ]);
+
+    let disk = state.disk_monitor.usage(&config).await;
+
+    Ok(Json(OverviewResponse {
+        generated_at: chrono::Utc::now(),
+        database,
+        tasks,
+        queues,
+        pictures,
+        videos,
+        providers,
+        budgets,
+        disk,
+    }))
+}
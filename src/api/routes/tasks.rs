@@ -0,0 +1,95 @@
+use axum::{
+    extract::{Path, Query, State},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use tracing::error;
+
+use crate::api::error::ApiError;
+use crate::api::state::ApiState;
+use crate::global::queue::{self, TaskData, TaskStatus};
+
+// ========================================================================
+// Request/Response Types
+// ========================================================================
+
+#[derive(Debug, Deserialize)]
+pub struct TasksQuery {
+    pub job_id: Option<String>,
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+}
+
+fn default_limit() -> i64 {
+    50
+}
+
+#[derive(Serialize, Default)]
+pub struct TaskSummary {
+    pub pending: u64,
+    pub running: u64,
+    pub completed: u64,
+    pub failed: u64,
+}
+
+#[derive(Serialize)]
+pub struct CancelTaskResponse {
+    pub task_id: String,
+    pub canceled: bool,
+}
+
+#[derive(Serialize)]
+pub struct TasksResponse {
+    pub tasks: Vec<TaskData>,
+    pub count: usize,
+    pub summary: TaskSummary,
+}
+
+// ========================================================================
+// Handlers
+// ========================================================================
+
+/// List persisted tasks, newest first
+/// GET /api/tasks?job_id=collect_...&limit=50
+pub async fn list_tasks(
+    State(state): State<ApiState>,
+    Query(query): Query<TasksQuery>,
+) -> Result<Json<TasksResponse>, ApiError> {
+    let limit = query.limit.clamp(1, 1000);
+
+    let tasks = queue::find_tasks(&state.db, query.job_id.as_deref(), limit)
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Failed to list tasks");
+            ApiError::from(e)
+        })?;
+
+    let mut summary = TaskSummary::default();
+    for task in &tasks {
+        match task.status {
+            TaskStatus::Pending => summary.pending += 1,
+            TaskStatus::Running => summary.running += 1,
+            TaskStatus::Completed => summary.completed += 1,
+            TaskStatus::Failed { .. } => summary.failed += 1,
+        }
+    }
+
+    Ok(Json(TasksResponse {
+        count: tasks.len(),
+        tasks,
+        summary,
+    }))
+}
+
+/// Ask an executing task to stop, it fails with a canceled error once it notices
+/// POST /api/tasks/{id}/cancel
+pub async fn cancel_task(
+    State(state): State<ApiState>,
+    Path(task_id): Path<String>,
+) -> Result<Json<CancelTaskResponse>, ApiError> {
+    if !state.cancel_task(&task_id) {
+        return Err(ApiError::not_found(format!("Task {} is not executing", task_id)));
+    }
+
+    Ok(Json(CancelTaskResponse { task_id, canceled: true }))
+}
@@ -0,0 +1,222 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::HeaderMap,
+    response::Response,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use tracing::{error, info};
+
+use crate::api::error::ApiError;
+use crate::anime::my_anime_list::database;
+use crate::anime::my_anime_list::model::{AnimeData, GenreCategory, TaxonomyGenre, TaxonomyProducer};
+use crate::anime::my_anime_list::task::{SyncGenresTask, SyncProducersTask};
+use crate::api::{cache, dto::v1, fields, state::ApiState};
+use crate::global::queue::Task;
+
+// ========================================================================
+// Request/Response Types
+// ========================================================================
+
+#[derive(Serialize)]
+pub struct SyncQueuedResponse {
+    pub task_ids: Vec<String>,
+    pub message: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GenresQuery {
+    #[serde(default)]
+    pub category: Option<GenreCategory>,
+}
+
+#[derive(Serialize)]
+pub struct GenresResponse {
+    pub genres: Vec<TaxonomyGenre>,
+    pub count: usize,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ProducersQuery {
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+    #[serde(default)]
+    pub skip: u64,
+}
+
+#[derive(Serialize)]
+pub struct ProducersResponse {
+    pub producers: Vec<TaxonomyProducer>,
+    pub count: usize,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TaxonomyAnimeQuery {
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+    /// Comma-separated top-level anime fields, all when absent
+    #[serde(default)]
+    pub fields: Option<String>,
+}
+
+fn default_limit() -> i64 {
+    100
+}
+
+#[derive(Serialize)]
+pub struct TaxonomyAnimeResponse {
+    pub anime: Vec<v1::Anime>,
+    pub count: usize,
+}
+
+// ========================================================================
+// Handlers
+// ========================================================================
+
+/// Queue a sync of the genre and producer lists from Jikan
+/// POST /api/taxonomy/sync
+pub async fn sync_taxonomy(
+    State(state): State<ApiState>,
+) -> Result<Json<SyncQueuedResponse>, ApiError> {
+    info!("API request: sync taxonomy");
+
+    let anime_module = state.anime_module.as_ref()
+        .ok_or_else(|| ApiError::module_disabled("Anime module is not enabled"))?;
+    state.check_anime_queue()?;
+
+    let jikan_client = state.http_manager.jikan().clone();
+    let tasks: Vec<Box<dyn Task>> = vec![
+        Box::new(SyncGenresTask::new(jikan_client.clone())),
+        Box::new(SyncProducersTask::new(jikan_client)),
+    ];
+
+    let mut task_ids = Vec::with_capacity(tasks.len());
+    for task in tasks {
+        task_ids.push(task.id());
+        anime_module.queue().enqueue(task).await
+            .map_err(|e| {
+                error!(error = %e, "Failed to queue taxonomy sync");
+                ApiError::internal(format!("Failed to queue task: {}", e))
+            })?;
+    }
+
+    Ok(Json(SyncQueuedResponse {
+        task_ids,
+        message: "Genre and producer sync queued".to_string(),
+    }))
+}
+
+/// Synced genres, themes and demographics
+/// GET /api/taxonomy/genres?category=themes
+pub async fn list_genres(
+    State(state): State<ApiState>,
+    Query(query): Query<GenresQuery>,
+) -> Result<Json<GenresResponse>, ApiError> {
+    let genres = database::get_genres(state.db.db(), query.category)
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Failed to get genres");
+            ApiError::from(e)
+        })?;
+
+    Ok(Json(GenresResponse {
+        count: genres.len(),
+        genres,
+    }))
+}
+
+/// Synced producers, most anime first
+/// GET /api/taxonomy/producers?limit=100&skip=0
+pub async fn list_producers(
+    State(state): State<ApiState>,
+    Query(query): Query<ProducersQuery>,
+) -> Result<Json<ProducersResponse>, ApiError> {
+    let producers = database::get_producers(state.db.db(), query.limit.clamp(1, 1000), query.skip)
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Failed to get producers");
+            ApiError::from(e)
+        })?;
+
+    Ok(Json(ProducersResponse {
+        count: producers.len(),
+        producers,
+    }))
+}
+
+/// Collected anime with a genre, theme or demographic
+/// GET /api/taxonomy/genres/{id}/anime?limit=100&fields=titles,score
+pub async fn anime_by_genre(
+    State(state): State<ApiState>,
+    Path(genre_id): Path<i32>,
+    Query(query): Query<TaxonomyAnimeQuery>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    let limit = query.limit.clamp(1, 500);
+    let fields = parse_fields(query.fields)?;
+
+    if let Some(fields) = fields {
+        let anime = database::get_anime_fields_by_genre(state.db.db(), genre_id, limit, &fields)
+            .await
+            .map_err(|e| anime_list_error(e, "Failed to get anime by genre"))?;
+        return Ok(projected_list(&headers, anime, &fields));
+    }
+
+    let anime = database::get_anime_by_genre(state.db.db(), genre_id, limit)
+        .await
+        .map_err(|e| anime_list_error(e, "Failed to get anime by genre"))?;
+
+    let last_modified = anime.iter().map(|anime| anime.updated_at).max();
+    Ok(cache::conditional_json(&headers, &TaxonomyAnimeResponse {
+        count: anime.len(),
+        anime: anime.into_iter().map(v1::Anime::from).collect(),
+    }, last_modified))
+}
+
+/// Collected anime produced, licensed or animated by a producer
+/// GET /api/taxonomy/producers/{id}/anime?limit=100&fields=titles,score
+pub async fn anime_by_producer(
+    State(state): State<ApiState>,
+    Path(producer_id): Path<i32>,
+    Query(query): Query<TaxonomyAnimeQuery>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    let limit = query.limit.clamp(1, 500);
+    let fields = parse_fields(query.fields)?;
+
+    if let Some(fields) = fields {
+        let anime = database::get_anime_fields_by_producer(state.db.db(), producer_id, limit, &fields)
+            .await
+            .map_err(|e| anime_list_error(e, "Failed to get anime by producer"))?;
+        return Ok(projected_list(&headers, anime, &fields));
+    }
+
+    let anime = database::get_anime_by_producer(state.db.db(), producer_id, limit)
+        .await
+        .map_err(|e| anime_list_error(e, "Failed to get anime by producer"))?;
+
+    let last_modified = anime.iter().map(|anime| anime.updated_at).max();
+    Ok(cache::conditional_json(&headers, &TaxonomyAnimeResponse {
+        count: anime.len(),
+        anime: anime.into_iter().map(v1::Anime::from).collect(),
+    }, last_modified))
+}
+
+fn parse_fields(fields: Option<String>) -> Result<Option<Vec<String>>, ApiError> {
+    fields::FieldsQuery { fields }.parse().map_err(ApiError::validation)
+}
+
+fn anime_list_error(e: crate::global::error::DatabaseError, message: &str) -> ApiError {
+    error!(error = %e, "{}", message);
+    ApiError::from(e)
+}
+
+/// v1 anime read with a projection, reduced to the requested fields
+fn projected_list(headers: &HeaderMap, anime: Vec<AnimeData>, fields: &[String]) -> Response {
+    let count = anime.len();
+    let last_modified = anime.iter().map(|anime| anime.updated_at).max();
+    let anime: Vec<serde_json::Value> = anime.into_iter()
+        .map(|anime| fields::select(&v1::Anime::from(anime), fields))
+        .collect();
+    cache::conditional_json(headers, &serde_json::json!({ "anime": anime, "count": count }), last_modified)
+}
@@ -0,0 +1,250 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::HeaderMap,
+    response::Response,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use validator::Validate;
+use tracing::{info, error, warn};
+
+use crate::anime::my_anime_list::database::get_anime_by_id;
+use crate::api::{error::ApiError, extract::{validate_url, ValidatedJson}};
+use crate::api::{cache, dto::v1, state::ApiState};
+use crate::video::{database, model::VideoStats, VideoModule};
+
+// ========================================================================
+// Request/Response Types
+// ========================================================================
+
+#[derive(Debug, Deserialize, Validate)]
+pub struct FetchVideoRequest {
+    #[validate(custom(function = "validate_url"))]
+    pub url: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    pub entity_type: Option<String>,
+    pub entity_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct GetVideosQuery {
+    pub entity_type: Option<String>,
+    pub entity_id: Option<String>,
+    pub tag: Option<String>,
+    pub status: Option<String>,
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+}
+
+fn default_limit() -> i64 {
+    50
+}
+
+#[derive(Serialize)]
+pub struct TaskQueuedResponse {
+    pub message: String,
+    pub task_type: String,
+}
+
+#[derive(Serialize)]
+pub struct AnimeVideosQueuedResponse {
+    pub message: String,
+    pub anime_id: u32,
+    pub queued: usize,
+}
+
+#[derive(Serialize)]
+pub struct VideoResponse {
+    pub video: v1::Video,
+}
+
+#[derive(Serialize)]
+pub struct VideosResponse {
+    pub videos: Vec<v1::Video>,
+    pub count: usize,
+}
+
+#[derive(Serialize)]
+pub struct StatsResponse {
+    pub stats: VideoStats,
+}
+
+fn video_module(state: &ApiState) -> Result<&VideoModule, ApiError> {
+    state.video_module.as_deref()
+        .ok_or_else(|| ApiError::module_disabled("Video module is not enabled"))
+}
+
+// ========================================================================
+// Handlers
+// ========================================================================
+
+/// Download a video (e.g. a YouTube trailer)
+/// POST /api/video/fetch
+/// Body: { "url": "https://www.youtube.com/watch?v=...", "tags": ["trailer"], "entity_type": "anime", "entity_id": "123" }
+pub async fn fetch_video(
+    State(state): State<ApiState>,
+    ValidatedJson(request): ValidatedJson<FetchVideoRequest>,
+) -> Result<Json<TaskQueuedResponse>, ApiError> {
+    info!(
+        url = %request.url,
+        tags = ?request.tags,
+        "API request: fetch video"
+    );
+
+    let video_module = video_module(&state)?;
+    state.check_video_queue()?;
+
+    let entity = request.entity_type.zip(request.entity_id);
+    video_module
+        .queue_fetch_video(request.url.clone(), entity, request.tags)
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Failed to queue video fetch task");
+            ApiError::from(e)
+        })?;
+
+    Ok(Json(TaskQueuedResponse {
+        message: format!("Video queued for download: {}", request.url),
+        task_type: "fetch_video".to_string(),
+    }))
+}
+
+/// Download the trailer and promotional videos of a stored anime
+/// POST /api/video/anime/{id}
+pub async fn fetch_anime_videos(
+    State(state): State<ApiState>,
+    Path(anime_id): Path<u32>,
+) -> Result<Json<AnimeVideosQueuedResponse>, ApiError> {
+    info!(anime_id = anime_id, "API request: fetch anime videos");
+
+    let video_module = video_module(&state)?;
+    state.check_video_queue()?;
+
+    let anime = get_anime_by_id(state.db.db(), anime_id as i32)
+        .await?
+        .ok_or_else(|| ApiError::not_found(format!("Anime not found: {}", anime_id)))?;
+
+    let queued = video_module.queue_anime_videos(&anime)
+        .await
+        .map_err(|e| {
+            error!(error = %e, anime_id = anime_id, "Failed to queue anime videos");
+            ApiError::from(e)
+        })?;
+
+    Ok(Json(AnimeVideosQueuedResponse {
+        message: format!("{} videos of anime {} queued for download", queued, anime_id),
+        anime_id,
+        queued,
+    }))
+}
+
+/// Get video metadata by URL
+/// GET /api/video?url=https://www.youtube.com/watch?v=...
+pub async fn get_video(
+    State(state): State<ApiState>,
+    Query(params): Query<std::collections::HashMap<String, String>>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    let url = params.get("url")
+        .ok_or_else(|| ApiError::validation("Missing 'url' query parameter"))?;
+
+    info!(url = %url, "API request: get video");
+
+    let video = database::get_video_by_url(state.db.db(), url)
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Failed to get video from database");
+            ApiError::from(e)
+        })?
+        .ok_or_else(|| ApiError::not_found(format!("Video not found: {}", url)))?;
+
+    let last_modified = video.updated_at;
+    Ok(cache::conditional_json(&headers, &VideoResponse { video: video.into() }, Some(last_modified)))
+}
+
+/// Get videos with filters
+/// GET /api/video/list?entity_type=anime&entity_id=123&tag=trailer&status=Completed&limit=50
+pub async fn list_videos(
+    State(state): State<ApiState>,
+    Query(query): Query<GetVideosQuery>,
+    headers: HeaderMap,
+) -> Result<Response, ApiError> {
+    info!(
+        entity_type = ?query.entity_type,
+        entity_id = ?query.entity_id,
+        tag = ?query.tag,
+        status = ?query.status,
+        "API request: list videos"
+    );
+
+    let videos = if let (Some(entity_type), Some(entity_id)) = (&query.entity_type, &query.entity_id) {
+        database::get_videos_by_entity(state.db.db(), entity_type, entity_id).await?
+    } else if let Some(tag) = &query.tag {
+        database::get_videos_by_tag(state.db.db(), tag, query.limit).await?
+    } else if let Some(status) = &query.status {
+        database::get_videos_by_status(state.db.db(), status, query.limit).await?
+    } else {
+        return Err(ApiError::validation("Must provide entity_type+entity_id, tag, or status"));
+    };
+
+    let count = videos.len();
+    let last_modified = videos.iter().map(|video| video.updated_at).max();
+    let videos = videos.into_iter().map(v1::Video::from).collect();
+    Ok(cache::conditional_json(&headers, &VideosResponse { videos, count }, last_modified))
+}
+
+/// Get video statistics
+/// GET /api/video/stats
+pub async fn get_stats(
+    State(state): State<ApiState>,
+) -> Result<Json<StatsResponse>, ApiError> {
+    info!("API request: get video stats");
+
+    let stats = database::get_video_stats(state.db.db())
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Failed to get video stats");
+            ApiError::from(e)
+        })?;
+
+    Ok(Json(StatsResponse { stats }))
+}
+
+/// Delete a video and its file
+/// DELETE /api/video?url=https://www.youtube.com/watch?v=...
+pub async fn delete_video(
+    State(state): State<ApiState>,
+    Query(params): Query<std::collections::HashMap<String, String>>,
+) -> Result<Json<TaskQueuedResponse>, ApiError> {
+    let url = params.get("url")
+        .ok_or_else(|| ApiError::validation("Missing 'url' query parameter"))?;
+
+    info!(url = %url, "API request: delete video");
+
+    let deleted = database::delete_video(state.db.db(), url)
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Failed to delete video");
+            ApiError::from(e)
+        })?;
+
+    if deleted.is_empty() {
+        return Err(ApiError::not_found(format!("Video not found: {}", url)));
+    }
+
+    let mut file_paths: Vec<&str> = deleted.iter().filter_map(|video| video.file_path.as_deref()).collect();
+    file_paths.dedup();
+    for file_path in file_paths {
+        if let Err(e) = tokio::fs::remove_file(file_path).await
+            && e.kind() != std::io::ErrorKind::NotFound
+        {
+            warn!(error = %e, path = %file_path, "Failed to remove video file");
+        }
+    }
+
+    Ok(Json(TaskQueuedResponse {
+        message: format!("Video deleted: {}", url),
+        task_type: "delete_video".to_string(),
+    }))
+}
@@ -0,0 +1,246 @@
+use axum::{
+    body::Bytes,
+    extract::{Query, State},
+    http::header,
+    response::IntoResponse,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use tracing::{error, info};
+
+use crate::api::error::ApiError;
+use crate::anime::collect::CollectTarget;
+use crate::anime::list_import::{self, ListImport};
+use crate::anime::mal_export;
+use crate::anime::anilist::AniListModule;
+use crate::anime::watchlist::{self, RefreshWatchlistTask, WatchlistEntry};
+use crate::api::state::ApiState;
+use crate::global::job;
+use crate::global::queue::Task;
+
+// ========================================================================
+// Request/Response Types
+// ========================================================================
+
+/// Watched anime, either a MAL id or a title
+#[derive(Debug, Deserialize)]
+pub struct WatchlistTarget {
+    #[serde(default)]
+    pub mal_id: Option<u32>,
+    #[serde(default)]
+    pub title: Option<String>,
+}
+
+impl WatchlistTarget {
+    fn into_target(self) -> Result<CollectTarget, ApiError> {
+        match (self.mal_id, self.title.map(|title| title.trim().to_string())) {
+            (Some(mal_id), None) => Ok(CollectTarget::MalId(mal_id)),
+            (None, Some(title)) if !title.is_empty() => Ok(CollectTarget::Title(title)),
+            _ => Err(ApiError::validation("Exactly one of mal_id or title is required")),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct WatchlistResponse {
+    pub entries: Vec<WatchlistEntry>,
+    pub count: usize,
+}
+
+#[derive(Serialize)]
+pub struct WatchlistUpdateResponse {
+    pub target: CollectTarget,
+    /// False when the anime was already watched (add) or not watched (remove)
+    pub changed: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ImportQuery {
+    /// Queue the complete collection of every listed anime, watched before or not
+    #[serde(default)]
+    pub collect: bool,
+}
+
+#[derive(Serialize)]
+pub struct ImportResponse {
+    #[serde(flatten)]
+    pub import: ListImport,
+    /// Job of the queued collections, follow progress at /api/jobs/{id}
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub job_id: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct RefreshQueuedResponse {
+    pub task_id: String,
+    pub message: String,
+}
+
+// ========================================================================
+// Handlers
+// ========================================================================
+
+/// Watched anime, oldest first
+/// GET /api/watchlist
+pub async fn list_watchlist(
+    State(state): State<ApiState>,
+) -> Result<Json<WatchlistResponse>, ApiError> {
+    let entries = watchlist::list_entries(state.db.db())
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Failed to list watchlist");
+            ApiError::from(e)
+        })?;
+
+    Ok(Json(WatchlistResponse {
+        count: entries.len(),
+        entries,
+    }))
+}
+
+/// Watch an anime, collected on the next refresh when it isn't stored yet
+/// POST /api/watchlist
+/// Body: { "mal_id": 52991 } or { "title": "Sousou no Frieren" }
+pub async fn add_to_watchlist(
+    State(state): State<ApiState>,
+    Json(request): Json<WatchlistTarget>,
+) -> Result<Json<WatchlistUpdateResponse>, ApiError> {
+    let target = request.into_target()?;
+    info!(target = %target, "API request: add to watchlist");
+
+    let changed = watchlist::add_entry(state.db.db(), target.clone())
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Failed to add watchlist entry");
+            ApiError::from(e)
+        })?;
+
+    Ok(Json(WatchlistUpdateResponse { target, changed }))
+}
+
+/// Stop watching an anime, the stored data is kept
+/// DELETE /api/watchlist?mal_id=52991 or DELETE /api/watchlist?title=Sousou+no+Frieren
+pub async fn remove_from_watchlist(
+    State(state): State<ApiState>,
+    Query(query): Query<WatchlistTarget>,
+) -> Result<Json<WatchlistUpdateResponse>, ApiError> {
+    let target = query.into_target()?;
+    info!(target = %target, "API request: remove from watchlist");
+
+    let changed = watchlist::remove_entry(state.db.db(), &target)
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Failed to remove watchlist entry");
+            ApiError::from(e)
+        })?;
+
+    Ok(Json(WatchlistUpdateResponse { target, changed }))
+}
+
+/// Watchlist in the MyAnimeList XML import format, titles not resolved yet are left out
+/// GET /api/watchlist/export.xml
+pub async fn export_watchlist(
+    State(state): State<ApiState>,
+) -> Result<impl IntoResponse, ApiError> {
+    let export = mal_export::export_watchlist(state.db.db())
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Failed to export watchlist");
+            ApiError::from(e)
+        })?;
+
+    Ok((
+        [
+            (header::CONTENT_TYPE, "application/xml; charset=utf-8"),
+            (header::CONTENT_DISPOSITION, "attachment; filename=\"animelist.xml\""),
+        ],
+        export.xml,
+    ))
+}
+
+/// Watch every anime of a MyAnimeList XML or AniList JSON list export (gzip accepted)
+/// POST /api/watchlist/import?collect=true
+/// With `collect`, every listed anime is collected as one job, queued in the background
+pub async fn import_watchlist(
+    State(state): State<ApiState>,
+    Query(query): Query<ImportQuery>,
+    body: Bytes,
+) -> Result<Json<ImportResponse>, ApiError> {
+    let list = list_import::parse_list(&body).map_err(ApiError::validation)?;
+    info!(format = ?list.format, entries = list.targets.len(), collect = query.collect, "API request: import watchlist");
+
+    // Check the collection can be queued before changing the watchlist
+    let mal_module = if query.collect {
+        let mal_module = state.mal_module()?.clone();
+        state.check_anime_queue()?;
+        Some(mal_module)
+    } else {
+        None
+    };
+
+    let import = list_import::import_list(state.db.db(), &list)
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Failed to import watchlist");
+            ApiError::from(e)
+        })?;
+
+    let mut job_id = None;
+    if let Some(mal_module) = mal_module
+        && !list.targets.is_empty()
+    {
+        let config = state.config.load_full();
+        let anilist_client = AniListModule::is_available(&config)
+            .then(|| state.http_manager.anilist().clone());
+
+        let job = job::create_job(&state.db, "collect", format!("Collect {} imported anime", list.targets.len()))
+            .await
+            .map_err(|e| {
+                error!(error = %e, "Failed to create collection job");
+                ApiError::internal(format!("Failed to create job: {}", e))
+            })?;
+
+        // Large lists fill the queue, the response doesn't wait for the last collection to be queued
+        let (db, events, collect_job_id) = (state.db.clone(), state.events.clone(), job.job_id.clone());
+        tokio::spawn(async move {
+            for target in list.targets {
+                if let Err(e) = mal_module.queue_collect(target.clone(), anilist_client.clone(), &collect_job_id).await {
+                    error!(job_id = %collect_job_id, target = %target, error = %e, "Failed to queue imported anime collection");
+                }
+            }
+            if let Err(e) = job::seal_job(&db, &collect_job_id, Some(&events)).await {
+                error!(job_id = %collect_job_id, error = %e, "Failed to seal job");
+            }
+        });
+        job_id = Some(job.job_id);
+    }
+
+    Ok(Json(ImportResponse { import, job_id }))
+}
+
+/// Queue a refresh of the watched anime without waiting for the scheduler
+/// POST /api/watchlist/refresh
+pub async fn refresh_watchlist(
+    State(state): State<ApiState>,
+) -> Result<Json<RefreshQueuedResponse>, ApiError> {
+    let anime_module = state.anime_module.as_ref()
+        .ok_or_else(|| ApiError::module_disabled("Anime module is not enabled"))?;
+    state.check_anime_queue()?;
+
+    let mut task = RefreshWatchlistTask::new(anime_module.queue().clone(), state.http_manager.jikan().clone());
+    if let Some(api_key) = state.config.load_full().get_api_key("my_anime_list") {
+        task = task.with_mal(api_key, state.http_manager.my_anime_list().clone());
+    }
+    let task_id = task.id();
+
+    anime_module.queue().enqueue(Box::new(task)).await
+        .map_err(|e| {
+            error!(error = %e, "Failed to queue watchlist refresh");
+            ApiError::internal(format!("Failed to queue task: {}", e))
+        })?;
+
+    Ok(Json(RefreshQueuedResponse {
+        message: format!("Watchlist refresh queued as task {}", task_id),
+        task_id,
+    }))
+}
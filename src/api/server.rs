@@ -3,17 +3,19 @@ use axum::Router;
 use tokio::net::TcpListener;
 use tower_http::trace::TraceLayer;
 use tower_http::cors::{CorsLayer, Any};
+use tower_http::compression::{predicate::{DefaultPredicate, Predicate, SizeAbove}, CompressionLayer};
 use tracing::{info, error};
 
-use crate::api::{routes, state::ApiState};
+use crate::api::{namespace::Namespace, routes, state::ApiState};
 
 /// Start the API server
 pub async fn start_api_server(
     state: ApiState,
+    namespaces: Vec<Namespace>,
     host: &str,
     port: u16,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    let app = create_app(state);
+    let app = create_app(state, namespaces);
     
     let addr = format!("{}:{}", host, port);
     let socket_addr: SocketAddr = addr.parse()?;
@@ -33,9 +35,23 @@ pub async fn start_api_server(
 }
 
 /// Create the Axum application with middleware
-fn create_app(state: ApiState) -> Router {
-    let router = routes::create_router(state);
-    
+fn create_app(state: ApiState, namespaces: Vec<Namespace>) -> Router {
+    let compression = state.config.load_full().api.compression.clone();
+    let router = routes::create_router(state, namespaces);
+
+    // Compression settings are read at startup, changing them needs a restart
+    let router = if compression.enabled {
+        info!(gzip = compression.gzip, brotli = compression.brotli, "Response compression enabled");
+        router.layer(
+            CompressionLayer::new()
+                .gzip(compression.gzip)
+                .br(compression.brotli)
+                .compress_when(DefaultPredicate::new().and(SizeAbove::new(compression.min_size_bytes)))
+        )
+    } else {
+        router
+    };
+
     router
         // Add CORS middleware
         .layer(
@@ -1,40 +1,100 @@
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Instant;
+
+use tokio::sync::Mutex;
 
 use crate::global::{
-    config::AppConfig,
     database::DatabaseInstance,
+    disk::DiskMonitor,
+    events::EventBus,
     http::HttpClientManager,
+    reload::SharedConfig,
+    module::ModuleHandle,
+    supervisor::ModuleStatuses,
 };
+use crate::anime::anilist::AniListModule;
 use crate::anime::module::AnimeModule;
+use crate::anime::my_anime_list::module::MyAnimeListModule;
+use crate::api::error::ApiError;
 use crate::picture::PictureFetcherModule;
+use crate::video::VideoModule;
+use crate::music::MusicModule;
+use crate::api::routes::health::ComponentHealth;
 
 /// Application state shared across API handlers
 #[derive(Clone)]
 pub struct ApiState {
-    pub config: Arc<AppConfig>,
+    /// Current configuration, use `config.load_full()` to get a snapshot
+    pub config: SharedConfig,
+    /// Namespace served by this state, `None` for the default library
+    pub namespace: Option<String>,
     pub db: Arc<DatabaseInstance>,
     pub http_manager: Arc<HttpClientManager>,
+    /// Data events published by the tasks queued through the API
+    pub events: EventBus,
     
     // Module references
     pub anime_module: Option<Arc<AnimeModule>>,
     pub picture_module: Option<Arc<PictureFetcherModule>>,
+    pub video_module: Option<Arc<VideoModule>>,
+    pub music_module: Option<Arc<MusicModule>>,
+
+    /// Provider child modules, built once at startup on the anime module queue
+    /// by `with_provider_modules`.
+    /// Their configuration is the startup snapshot, a reload needs a restart to reach them.
+    pub mal_module: Option<Arc<MyAnimeListModule>>,
+    pub anilist_module: Option<Arc<AniListModule>>,
+
+    /// Supervisor state of the parent modules
+    pub module_statuses: Option<ModuleStatuses>,
+
+    /// Control handles of the running parent modules
+    pub module_handles: Vec<ModuleHandle>,
+
+    /// Mutating requests are rejected while set, shared by every namespace
+    pub read_only: Arc<AtomicBool>,
+
+    /// Last provider reachability results, to avoid probing providers on every readiness call
+    pub provider_health_cache: Arc<Mutex<HashMap<String, (Instant, ComponentHealth)>>>,
+
+    /// Latest measurement of the storage directories, shared by every namespace
+    pub disk_monitor: DiskMonitor,
 }
 
 impl ApiState {
     pub fn new(
-        config: Arc<AppConfig>,
+        config: SharedConfig,
         db: Arc<DatabaseInstance>,
         http_manager: Arc<HttpClientManager>,
     ) -> Self {
+        let read_only = config.load().api.read_only;
         Self {
             config,
+            namespace: None,
             db,
             http_manager,
+            events: EventBus::new(),
             anime_module: None,
             picture_module: None,
+            video_module: None,
+            music_module: None,
+            mal_module: None,
+            anilist_module: None,
+            module_statuses: None,
+            module_handles: Vec::new(),
+            read_only: Arc::new(AtomicBool::new(read_only)),
+            provider_health_cache: Arc::new(Mutex::new(HashMap::new())),
+            disk_monitor: DiskMonitor::new(),
         }
     }
 
+    pub fn with_events(mut self, events: EventBus) -> Self {
+        self.events = events;
+        self
+    }
+
     pub fn with_anime_module(mut self, module: Arc<AnimeModule>) -> Self {
         self.anime_module = Some(module);
         self
@@ -44,4 +104,142 @@ impl ApiState {
         self.picture_module = Some(module);
         self
     }
-}
\ No newline at end of file
+
+    pub fn with_video_module(mut self, module: Arc<VideoModule>) -> Self {
+        self.video_module = Some(module);
+        self
+    }
+
+    pub fn with_music_module(mut self, module: Arc<MusicModule>) -> Self {
+        self.music_module = Some(module);
+        self
+    }
+
+    /// Build the provider modules on the anime module queue, with the current picture module.
+    /// Requires the anime module, the providers that aren't configured are left out.
+    pub fn with_provider_modules(mut self) -> Self {
+        let Some(anime_module) = &self.anime_module else {
+            return self;
+        };
+        let config = self.config.load_full();
+
+        if let Some(mut mal_module) = MyAnimeListModule::new(
+            self.http_manager.my_anime_list().clone(),
+            self.http_manager.jikan().clone(),
+            config.clone(),
+            anime_module.queue().clone(),
+        ) {
+            mal_module = mal_module
+                .with_events(self.events.clone())
+                .with_anilist_client(self.http_manager.anilist().clone());
+            if let Some(picture_module) = &self.picture_module {
+                mal_module = mal_module.with_picture_module(picture_module.clone());
+            }
+            self.mal_module = Some(Arc::new(mal_module));
+        }
+
+        if let Some(mut anilist_module) = AniListModule::new(
+            self.http_manager.anilist().clone(),
+            config,
+            anime_module.queue().clone(),
+        ) {
+            anilist_module = anilist_module.with_events(self.events.clone());
+            if let Some(picture_module) = &self.picture_module {
+                anilist_module = anilist_module.with_picture_module(picture_module.clone());
+            }
+            self.anilist_module = Some(Arc::new(anilist_module));
+        }
+
+        self
+    }
+
+    pub fn with_module_statuses(mut self, statuses: ModuleStatuses) -> Self {
+        self.module_statuses = Some(statuses);
+        self
+    }
+
+    pub fn with_disk_monitor(mut self, monitor: DiskMonitor) -> Self {
+        self.disk_monitor = monitor;
+        self
+    }
+
+    pub fn with_module_handles(mut self, handles: Vec<ModuleHandle>) -> Self {
+        self.module_handles = handles;
+        self
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        self.read_only.load(Ordering::Relaxed)
+    }
+
+    pub fn set_read_only(&self, read_only: bool) {
+        self.read_only.store(read_only, Ordering::Relaxed);
+    }
+
+    /// 429 when the anime queue already holds `anime.queue_size` tasks
+    pub fn check_anime_queue(&self) -> Result<(), ApiError> {
+        match &self.anime_module {
+            Some(anime_module) => Ok(anime_module.queue().check_capacity()?),
+            None => Ok(()),
+        }
+    }
+
+    /// 429 when the picture queue already holds `picture.queue_size` tasks
+    pub fn check_picture_queue(&self) -> Result<(), ApiError> {
+        match &self.picture_module {
+            Some(picture_module) => Ok(picture_module.queue().check_capacity()?),
+            None => Ok(()),
+        }
+    }
+
+    /// 429 when the video queue already holds `video.queue_size` tasks
+    pub fn check_video_queue(&self) -> Result<(), ApiError> {
+        match &self.video_module {
+            Some(video_module) => Ok(video_module.queue().check_capacity()?),
+            None => Ok(()),
+        }
+    }
+
+    /// 429 when the music queue already holds `music.queue_size` tasks
+    pub fn check_music_queue(&self) -> Result<(), ApiError> {
+        match &self.music_module {
+            Some(music_module) => Ok(music_module.queue().check_capacity()?),
+            None => Ok(()),
+        }
+    }
+
+    /// Ask the executing task `task_id` of this library's queues to stop.
+    /// Returns false when none of them is executing it.
+    pub fn cancel_task(&self, task_id: &str) -> bool {
+        let queues = [
+            self.anime_module.as_ref().map(|module| module.queue()),
+            self.picture_module.as_ref().map(|module| module.queue()),
+            self.video_module.as_ref().map(|module| module.queue()),
+            self.music_module.as_ref().map(|module| module.queue()),
+        ];
+        // Every queue is asked, the same task id may run in more than one
+        let mut canceled = false;
+        for queue in queues.into_iter().flatten() {
+            canceled |= queue.cancel_task(task_id);
+        }
+        canceled
+    }
+
+    /// Shared MyAnimeList module, or the reason it isn't available
+    pub fn mal_module(&self) -> Result<&Arc<MyAnimeListModule>, ApiError> {
+        if self.anime_module.is_none() {
+            return Err(ApiError::module_disabled("Anime module is not enabled"));
+        }
+        self.mal_module.as_ref()
+            .ok_or_else(|| ApiError::module_disabled("MyAnimeList module is not properly configured"))
+    }
+
+    /// Shared AniList module, or the reason it isn't available
+    pub fn anilist_module(&self) -> Result<&Arc<AniListModule>, ApiError> {
+        if self.anime_module.is_none() {
+            return Err(ApiError::module_disabled("Anime module is not enabled"));
+        }
+        self.anilist_module.as_ref()
+            .ok_or_else(|| ApiError::module_disabled("AniList module is not properly configured"))
+    }
+}
@@ -0,0 +1,368 @@
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use anyhow::{anyhow, bail, Result};
+use clap::{Parser, Subcommand, ValueEnum};
+use futures::stream::StreamExt;
+use serde::Serialize;
+use tracing::{error, info, warn};
+
+use crate::anime::{anilist, mal_export, my_anime_list, nfo};
+use crate::anime::fallback::ProviderFallback;
+use crate::anime::my_anime_list::model::Season;
+use crate::anime::my_anime_list::task::{
+    FetchAnimeTask, FetchCharactersTask, FetchEpisodesTask, FetchMoreInfoTask,
+    FetchPicturesTask, FetchRecommendationsTask, FetchStaffTask, FetchStatisticsTask,
+    FetchVideosTask,
+};
+use crate::global::config::AppConfig;
+use crate::global::database::DatabaseInstance;
+use crate::global::http::HttpClientManager;
+use crate::global::queue::{Task, TaskContext};
+use crate::picture;
+
+#[derive(Debug, Parser)]
+#[command(name = "media-collector", version, about = "Collects anime data and pictures")]
+pub struct Cli {
+    /// Validate the configuration and exit without starting modules
+    #[arg(long, global = true)]
+    pub check_config: bool,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Run the modules and the API server (default)
+    Serve,
+    /// Fetch a single anime from MyAnimeList and exit
+    FetchAnime {
+        /// MyAnimeList anime ID
+        id: u32,
+        /// Skip the Jikan enrichment
+        #[arg(long)]
+        no_jikan: bool,
+        /// Also fetch characters, staff, episodes, videos, statistics, more info,
+        /// recommendations and picture lists from Jikan
+        #[arg(long)]
+        extended: bool,
+        /// Also fetch the anime from AniList
+        #[arg(long)]
+        anilist: bool,
+    },
+    /// Fetch every anime of a MyAnimeList season and exit
+    ImportSeason {
+        year: u32,
+        #[arg(value_enum)]
+        season: SeasonArg,
+        /// Skip anime already stored
+        #[arg(long)]
+        skip_existing: bool,
+    },
+    /// Export stored anime as JSON lines
+    Export {
+        #[arg(long, value_enum, default_value_t = ExportSource::Mal)]
+        source: ExportSource,
+        /// Output file, stdout when omitted
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Write Kodi/Jellyfin metadata (tvshow.nfo, episode NFOs, poster and fanart)
+    /// for collected anime into a media-library directory
+    ExportNfo {
+        /// Library root directory, one folder per anime is created in it
+        #[arg(short, long)]
+        output: PathBuf,
+        /// MAL ids to export, all collected anime when omitted
+        #[arg(long = "id")]
+        ids: Vec<u32>,
+    },
+    /// Write the watchlist in the MyAnimeList XML import format
+    ExportMal {
+        /// Output file, stdout when omitted
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Check downloaded pictures against their recorded size and hash
+    VerifyPictures {
+        /// Mark invalid pictures as failed so they are downloaded again
+        #[arg(long)]
+        fix: bool,
+        /// Print the report as JSON
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum SeasonArg {
+    Winter,
+    Spring,
+    Summer,
+    Fall,
+}
+
+impl From<SeasonArg> for Season {
+    fn from(season: SeasonArg) -> Self {
+        match season {
+            SeasonArg::Winter => Season::Winter,
+            SeasonArg::Spring => Season::Spring,
+            SeasonArg::Summer => Season::Summer,
+            SeasonArg::Fall => Season::Fall,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum ExportSource {
+    Mal,
+    Anilist,
+}
+
+/// Run a one-shot command. Tasks are executed directly, without queues or modules.
+pub async fn run(
+    command: Command,
+    config: Arc<AppConfig>,
+    db: Arc<DatabaseInstance>,
+    http_manager: HttpClientManager,
+) -> Result<()> {
+    match command {
+        Command::Serve => unreachable!("serve is handled by main"),
+        Command::FetchAnime { id, no_jikan, extended, anilist } => {
+            fetch_anime(&config, &db, &http_manager, id, !no_jikan, extended, anilist).await
+        }
+        Command::ImportSeason { year, season, skip_existing } => {
+            import_season(&config, &db, &http_manager, year, season.into(), skip_existing).await
+        }
+        Command::Export { source, output } => export(&db, source, output).await,
+        Command::ExportNfo { output, ids } => export_nfo(&db, &output, &ids).await,
+        Command::ExportMal { output } => export_mal(&db, output).await,
+        Command::VerifyPictures { fix, json } => verify_pictures(&db, fix, json).await,
+    }
+}
+
+fn mal_api_key(config: &AppConfig) -> Result<String> {
+    if !my_anime_list::MyAnimeListModule::is_available(config) {
+        bail!("MyAnimeList is not enabled or has no API key in config");
+    }
+    config.get_api_key("my_anime_list")
+        .ok_or_else(|| anyhow!("MyAnimeList API key is missing"))
+}
+
+fn provider_fallback(config: &AppConfig, http_manager: &HttpClientManager) -> ProviderFallback {
+    let fallback = ProviderFallback::new(config.anime.fallback.clone());
+    if config.is_child_module_enabled("anilist") {
+        fallback.with_anilist(http_manager.anilist().clone())
+    } else {
+        fallback
+    }
+}
+
+async fn execute(task: &dyn Task, db: &Arc<DatabaseInstance>, http_manager: &HttpClientManager) -> Result<()> {
+    info!(task = %task.name(), "Executing task");
+    task.execute(&TaskContext::new(db.clone(), http_manager.default().client.clone()))
+        .await
+        .map_err(|e| anyhow!("{} failed: {}", task.name(), e))
+}
+
+async fn fetch_anime(
+    config: &AppConfig,
+    db: &Arc<DatabaseInstance>,
+    http_manager: &HttpClientManager,
+    mal_id: u32,
+    with_jikan: bool,
+    extended: bool,
+    with_anilist: bool,
+) -> Result<()> {
+    let api_key = mal_api_key(config)?;
+    let jikan = http_manager.jikan();
+
+    let mut task = FetchAnimeTask::new(mal_id, api_key, http_manager.my_anime_list().clone(), jikan.clone())
+        .with_fallback(provider_fallback(config, http_manager));
+    if with_jikan {
+        task = task.with_jikan();
+    }
+    execute(&task, db, http_manager).await?;
+
+    let mut tasks: Vec<Box<dyn Task>> = Vec::new();
+    if extended {
+        tasks.push(Box::new(FetchCharactersTask::new(mal_id, jikan.clone())));
+        tasks.push(Box::new(FetchStaffTask::new(mal_id, jikan.clone())));
+        tasks.push(Box::new(FetchEpisodesTask::new(mal_id, jikan.clone())));
+        tasks.push(Box::new(FetchVideosTask::new(mal_id, jikan.clone())));
+        tasks.push(Box::new(FetchStatisticsTask::new(mal_id, jikan.clone())));
+        tasks.push(Box::new(FetchMoreInfoTask::new(mal_id, jikan.clone())));
+        tasks.push(Box::new(FetchRecommendationsTask::new(mal_id, jikan.clone())));
+        tasks.push(Box::new(FetchPicturesTask::new(mal_id, jikan.clone())));
+    }
+    if with_anilist {
+        if !anilist::module::AniListModule::is_available(config) {
+            bail!("AniList is not enabled in config");
+        }
+        tasks.push(Box::new(anilist::task::FetchAnimeTask::by_mal_id(mal_id, http_manager.anilist().clone())));
+    }
+
+    // Extended data is optional, report failures but keep going
+    let mut failed = 0;
+    for task in tasks {
+        if let Err(e) = execute(task.as_ref(), db, http_manager).await {
+            error!(error = %e, "Task failed");
+            failed += 1;
+        }
+    }
+
+    if failed > 0 {
+        bail!("Anime {} fetched, {} additional task(s) failed", mal_id, failed);
+    }
+    println!("Anime {} fetched", mal_id);
+    Ok(())
+}
+
+async fn import_season(
+    config: &AppConfig,
+    db: &Arc<DatabaseInstance>,
+    http_manager: &HttpClientManager,
+    year: u32,
+    season: Season,
+    skip_existing: bool,
+) -> Result<()> {
+    let api_key = mal_api_key(config)?;
+    let mal = http_manager.my_anime_list();
+    let ids = my_anime_list::season::fetch_season_anime_ids(mal, &api_key, year, &season).await?;
+
+    let mut fetched = 0;
+    let mut skipped = 0;
+    let mut failed = 0;
+    for mal_id in ids {
+        if skip_existing && my_anime_list::database::anime_exists(db.db(), mal_id as i32).await? {
+            skipped += 1;
+            continue;
+        }
+
+        let task = FetchAnimeTask::new(mal_id, api_key.clone(), mal.clone(), http_manager.jikan().clone())
+            .with_jikan()
+            .with_fallback(provider_fallback(config, http_manager));
+        match execute(&task, db, http_manager).await {
+            Ok(()) => fetched += 1,
+            Err(e) => {
+                warn!(mal_id = mal_id, error = %e, "Failed to import anime");
+                failed += 1;
+            }
+        }
+    }
+
+    println!(
+        "Season {} {}: {} fetched, {} skipped, {} failed",
+        season.as_str(), year, fetched, skipped, failed
+    );
+
+    if failed > 0 {
+        bail!("{} anime could not be imported", failed);
+    }
+    Ok(())
+}
+
+async fn export(db: &DatabaseInstance, source: ExportSource, output: Option<PathBuf>) -> Result<()> {
+    let out: Box<dyn Write> = match &output {
+        Some(path) => Box::new(std::fs::File::create(path)?),
+        None => Box::new(std::io::stdout().lock()),
+    };
+    let mut out = BufWriter::new(out);
+
+    let count = match source {
+        ExportSource::Mal => {
+            let cursor = my_anime_list::database::get_all_anime_cursor(db.db()).await?;
+            write_json_lines(cursor, &mut out).await?
+        }
+        ExportSource::Anilist => {
+            let cursor = anilist::database::get_all_anime_cursor(db.db()).await?;
+            write_json_lines(cursor, &mut out).await?
+        }
+    };
+    out.flush()?;
+
+    if let Some(path) = output {
+        println!("Exported {} anime to {}", count, path.display());
+    }
+    Ok(())
+}
+
+/// Write one JSON document per line, returns the number of documents written
+async fn write_json_lines<T>(mut cursor: mongodb::Cursor<T>, out: &mut impl Write) -> Result<u64>
+where
+    T: Serialize + serde::de::DeserializeOwned + Send + Sync,
+{
+    let mut count = 0;
+    while let Some(result) = cursor.next().await {
+        match result {
+            Ok(document) => {
+                serde_json::to_writer(&mut *out, &document)?;
+                writeln!(out)?;
+                count += 1;
+            }
+            Err(e) => warn!(error = %e, "Failed to deserialize anime, skipping"),
+        }
+    }
+    Ok(count)
+}
+
+async fn export_nfo(db: &DatabaseInstance, output: &Path, ids: &[u32]) -> Result<()> {
+    let report = nfo::export_library(db, output, ids).await?;
+
+    for failure in &report.failed {
+        println!("[FAILED] {}", failure);
+    }
+    println!(
+        "{} anime and {} episodes exported to {}, {} artwork files copied, {} not downloaded yet",
+        report.shows,
+        report.episodes,
+        output.display(),
+        report.artwork_copied,
+        report.artwork_missing
+    );
+
+    if !report.failed.is_empty() {
+        bail!("{} anime could not be exported", report.failed.len());
+    }
+    Ok(())
+}
+
+async fn export_mal(db: &DatabaseInstance, output: Option<PathBuf>) -> Result<()> {
+    let export = mal_export::export_watchlist(db.db()).await?;
+
+    match &output {
+        Some(path) => std::fs::write(path, &export.xml)?,
+        None => std::io::stdout().write_all(export.xml.as_bytes())?,
+    }
+
+    for title in &export.unresolved {
+        warn!(title = %title, "Watched title not resolved to a MAL id yet, refresh the watchlist to export it");
+    }
+    info!(exported = export.exported, unresolved = export.unresolved.len(), "Watchlist exported");
+    Ok(())
+}
+
+async fn verify_pictures(db: &DatabaseInstance, fix: bool, json: bool) -> Result<()> {
+    let report = picture::verify::verify_pictures(db, fix).await?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+    } else {
+        for invalid in &report.invalid {
+            println!("[INVALID] {} ({:?})", invalid.file_path, invalid.problem);
+        }
+        println!(
+            "{} pictures checked, {} invalid, {} marked as failed",
+            report.checked,
+            report.invalid.len(),
+            report.marked_failed
+        );
+    }
+
+    if !report.is_ok() {
+        bail!("{} pictures failed verification", report.invalid.len());
+    }
+    Ok(())
+}
@@ -0,0 +1,279 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::Mutex;
+use tracing::{debug, error, info, warn};
+
+use crate::global::database::DatabaseInstance;
+use crate::global::error::HttpError;
+use crate::global::module::RateLimiter;
+use crate::global::secrets::REDACTED;
+
+/// `[alerting]` config section
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AlertingConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub webhooks: Vec<WebhookTarget>,
+    /// Identical alerts are sent at most once per interval
+    #[serde(default = "default_repeat_interval_seconds")]
+    pub repeat_interval_seconds: u64,
+    /// How often thresholds are evaluated
+    #[serde(default = "default_check_interval_seconds")]
+    pub check_interval_seconds: u64,
+    /// Alert when the share of failed tasks since the previous check exceeds this ratio
+    #[serde(default = "default_failure_rate_threshold")]
+    pub failure_rate_threshold: f64,
+    /// Minimum number of finished tasks before the failure rate is considered
+    #[serde(default = "default_failure_rate_min_tasks")]
+    pub failure_rate_min_tasks: u64,
+}
+
+fn default_repeat_interval_seconds() -> u64 {
+    3600
+}
+
+fn default_check_interval_seconds() -> u64 {
+    300
+}
+
+fn default_failure_rate_threshold() -> f64 {
+    0.5
+}
+
+fn default_failure_rate_min_tasks() -> u64 {
+    10
+}
+
+impl Default for AlertingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            webhooks: Vec::new(),
+            repeat_interval_seconds: default_repeat_interval_seconds(),
+            check_interval_seconds: default_check_interval_seconds(),
+            failure_rate_threshold: default_failure_rate_threshold(),
+            failure_rate_min_tasks: default_failure_rate_min_tasks(),
+        }
+    }
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+pub struct WebhookTarget {
+    pub url: String,
+    #[serde(default)]
+    pub kind: WebhookKind,
+}
+
+/// Discord and Slack webhook URLs carry their token, only the kind is printed
+impl std::fmt::Debug for WebhookTarget {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WebhookTarget")
+            .field("url", &REDACTED)
+            .field("kind", &self.kind)
+            .finish()
+    }
+}
+
+/// Payload format expected by the webhook receiver
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookKind {
+    Discord,
+    Slack,
+    #[default]
+    Generic,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AlertKind {
+    TaskFailureRate,
+    DatabaseDown,
+    LowDiskSpace,
+    /// A provider client stopped sending requests after repeated failures
+    CircuitOpen,
+}
+
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AlertSeverity {
+    Warning,
+    Critical,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Alert {
+    pub kind: AlertKind,
+    pub severity: AlertSeverity,
+    pub title: String,
+    pub message: String,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+}
+
+impl Alert {
+    pub fn new(kind: AlertKind, severity: AlertSeverity, title: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            kind,
+            severity,
+            title: title.into(),
+            message: message.into(),
+            timestamp: chrono::Utc::now(),
+        }
+    }
+
+    /// Key used to deduplicate repeated alerts
+    fn dedup_key(&self) -> String {
+        format!("{:?}:{}", self.kind, self.title)
+    }
+
+    fn text(&self) -> String {
+        let level = match self.severity {
+            AlertSeverity::Warning => "WARNING",
+            AlertSeverity::Critical => "CRITICAL",
+        };
+        format!("[{}] media-collector: {}\n{}", level, self.title, self.message)
+    }
+}
+
+/// Sends alerts to the configured webhooks, deduplicated and rate limited
+#[derive(Clone)]
+pub struct AlertManager {
+    client: reqwest::Client,
+    config: AlertingConfig,
+    limiter: RateLimiter,
+    last_sent: Arc<Mutex<HashMap<String, Instant>>>,
+}
+
+impl AlertManager {
+    pub fn new(client: reqwest::Client, config: AlertingConfig) -> Self {
+        Self {
+            client,
+            config,
+            // Stay well below webhook provider limits (Discord allows ~30/min)
+            limiter: RateLimiter::new("alerting", 0.2),
+            last_sent: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.config.enabled && !self.config.webhooks.is_empty()
+    }
+
+    /// Send an alert unless the same alert was sent within the repeat interval
+    pub async fn notify(&self, alert: Alert) {
+        if !self.is_enabled() {
+            return;
+        }
+
+        let key = alert.dedup_key();
+        let repeat_interval = Duration::from_secs(self.config.repeat_interval_seconds);
+        {
+            let mut last_sent = self.last_sent.lock().await;
+            if let Some(sent_at) = last_sent.get(&key)
+                && sent_at.elapsed() < repeat_interval
+            {
+                debug!(alert = %key, "Alert suppressed (already sent recently)");
+                return;
+            }
+            last_sent.insert(key.clone(), Instant::now());
+        }
+
+        warn!(alert = %key, message = %alert.message, "Sending alert");
+
+        for target in &self.config.webhooks {
+            self.limiter.acquire().await;
+            if let Err(e) = self.send(target, &alert).await {
+                error!(url = %target.url, error = %e, "Failed to deliver alert");
+            }
+        }
+    }
+
+    async fn send(&self, target: &WebhookTarget, alert: &Alert) -> Result<(), HttpError> {
+        let body = match target.kind {
+            WebhookKind::Discord => serde_json::json!({ "content": alert.text() }),
+            WebhookKind::Slack => serde_json::json!({ "text": alert.text() }),
+            WebhookKind::Generic => serde_json::json!(alert),
+        };
+
+        let response = self.client.post(&target.url).json(&body).send().await?;
+        let status = response.status();
+
+        if !status.is_success() {
+            let message = response.text().await.unwrap_or_default();
+            return Err(HttpError::UnexpectedStatus {
+                status: status.as_u16(),
+                message,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Periodically evaluate alert thresholds in the background
+    pub fn spawn_monitor(self, db: Arc<DatabaseInstance>) {
+        if !self.is_enabled() {
+            debug!("Alerting disabled");
+            return;
+        }
+
+        info!(webhooks = self.config.webhooks.len(), "Alert monitor started");
+
+        tokio::spawn(async move {
+            let interval = Duration::from_secs(self.config.check_interval_seconds.max(1));
+            let mut previous: Option<(u64, u64)> = None;
+
+            loop {
+                tokio::time::sleep(interval).await;
+
+                match db.get_stats().await {
+                    Ok(stats) => {
+                        let current = (stats.completed_tasks, stats.failed_tasks);
+                        if let Some(previous) = previous {
+                            self.check_failure_rate(previous, current).await;
+                        }
+                        previous = Some(current);
+                    }
+                    Err(e) => {
+                        self.notify(Alert::new(
+                            AlertKind::DatabaseDown,
+                            AlertSeverity::Critical,
+                            "Database unavailable",
+                            format!("Failed to query MongoDB: {}", e),
+                        )).await;
+                    }
+                }
+            }
+        });
+    }
+
+    /// Compare task counters with the previous check
+    async fn check_failure_rate(&self, previous: (u64, u64), current: (u64, u64)) {
+        let completed = current.0.saturating_sub(previous.0);
+        let failed = current.1.saturating_sub(previous.1);
+        let finished = completed + failed;
+
+        if finished < self.config.failure_rate_min_tasks {
+            return;
+        }
+
+        let rate = failed as f64 / finished as f64;
+        if rate > self.config.failure_rate_threshold {
+            self.notify(Alert::new(
+                AlertKind::TaskFailureRate,
+                AlertSeverity::Warning,
+                "High task failure rate",
+                format!(
+                    "{} of {} tasks failed ({:.0}%) since the last check, threshold is {:.0}%",
+                    failed,
+                    finished,
+                    rate * 100.0,
+                    self.config.failure_rate_threshold * 100.0
+                ),
+            )).await;
+        }
+    }
+}
@@ -0,0 +1,120 @@
+use mongodb::bson::{self, doc};
+use mongodb::options::IndexOptions;
+use mongodb::{Cursor, Database, IndexModel};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, warn};
+
+use crate::global::config::ArchiveConfig;
+use crate::global::error::DatabaseError;
+
+// Collection name for the archived provider responses
+pub const COLLECTION_NAME: &str = "raw_responses";
+
+/// Name of the index expiring archived responses, updated when `retention_days` changes
+const TTL_INDEX_NAME: &str = "fetched_at_ttl";
+
+/// Body of a successful provider response, kept so converters can run again without
+/// fetching it from the provider
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RawResponse {
+    /// Name of the HTTP client: my_anime_list, jikan or anilist
+    pub provider: String,
+    pub url: String,
+    /// Request body for POST APIs (AniList GraphQL variables)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request: Option<serde_json::Value>,
+    pub status: u16,
+    pub body: String,
+    /// BSON date, the TTL index ignores RFC3339 strings
+    pub fetched_at: bson::DateTime,
+}
+
+/// Writes provider responses to `raw_responses` (`[http.archive]`)
+#[derive(Clone)]
+pub struct ResponseArchive {
+    db: Database,
+}
+
+impl ResponseArchive {
+    pub fn new(db: Database) -> Self {
+        Self { db }
+    }
+
+    /// Archive a response, failures only lose the archived copy
+    pub async fn store(&self, provider: &str, url: &str, request: Option<&serde_json::Value>, status: u16, body: &str) {
+        let response = RawResponse {
+            provider: provider.to_string(),
+            url: url.to_string(),
+            request: request.cloned(),
+            status,
+            body: body.to_string(),
+            fetched_at: bson::DateTime::now(),
+        };
+
+        match self.db.collection::<RawResponse>(COLLECTION_NAME).insert_one(&response).await {
+            Ok(_) => debug!(provider = %provider, url = %url, size = body.len(), "Archived provider response"),
+            Err(e) => warn!(provider = %provider, url = %url, error = %e, "Failed to archive provider response"),
+        }
+    }
+}
+
+pub async fn initialize_collection(db: &Database, config: &ArchiveConfig) -> Result<(), DatabaseError> {
+    let collection = db.collection::<RawResponse>(COLLECTION_NAME);
+    let retention = std::time::Duration::from_secs(config.retention_days * 24 * 60 * 60);
+
+    let lookup_index = IndexModel::builder()
+        .keys(doc! { "provider": 1, "url": 1, "fetched_at": -1 })
+        .build();
+
+    let ttl_index = IndexModel::builder()
+        .keys(doc! { "fetched_at": 1 })
+        .options(IndexOptions::builder()
+            .name(TTL_INDEX_NAME.to_string())
+            .expire_after(retention)
+            .build())
+        .build();
+
+    collection.create_indexes(vec![lookup_index]).await
+        .map_err(|e| DatabaseError::Query(format!("Failed to create raw_responses indexes: {}", e)))?;
+
+    // An existing TTL index keeps its expiry, change it in place when retention_days changed
+    if collection.create_index(ttl_index).await.is_err() {
+        db.run_command(doc! {
+            "collMod": COLLECTION_NAME,
+            "index": { "name": TTL_INDEX_NAME, "expireAfterSeconds": retention.as_secs() as i64 },
+        })
+        .await
+        .map_err(|e| DatabaseError::Query(format!("Failed to update raw_responses retention: {}", e)))?;
+        info!(retention_days = config.retention_days, "Updated retention of archived provider responses");
+    }
+
+    debug!("Created indexes for raw_responses collection");
+    Ok(())
+}
+
+/// Latest successful response of every request of a provider whose URL matches `url_pattern`, oldest first
+pub async fn latest_responses(db: &Database, provider: &str, url_pattern: &str) -> Result<Cursor<RawResponse>, DatabaseError> {
+    let pipeline = vec![
+        doc! { "$match": { "provider": provider, "status": 200, "url": { "$regex": url_pattern } } },
+        doc! { "$sort": { "fetched_at": -1 } },
+        doc! { "$group": { "_id": { "url": "$url", "request": "$request" }, "response": { "$first": "$$ROOT" } } },
+        doc! { "$replaceRoot": { "newRoot": "$response" } },
+        doc! { "$sort": { "fetched_at": 1 } },
+    ];
+
+    db.collection::<RawResponse>(COLLECTION_NAME)
+        .aggregate(pipeline)
+        .allow_disk_use(true)
+        .with_type::<RawResponse>()
+        .await
+        .map_err(|e| DatabaseError::Query(format!("Failed to get archived responses: {}", e)))
+}
+
+/// Latest successful response of a provider whose URL matches `url_pattern`
+pub async fn latest_response(db: &Database, provider: &str, url_pattern: &str) -> Result<Option<RawResponse>, DatabaseError> {
+    db.collection::<RawResponse>(COLLECTION_NAME)
+        .find_one(doc! { "provider": provider, "status": 200, "url": { "$regex": url_pattern } })
+        .sort(doc! { "fetched_at": -1 })
+        .await
+        .map_err(|e| DatabaseError::Query(format!("Failed to get archived response: {}", e)))
+}
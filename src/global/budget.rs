@@ -0,0 +1,195 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+
+use chrono::NaiveDate;
+use mongodb::bson::doc;
+use mongodb::Database;
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info, warn};
+
+// Collection name for the daily request counters, one document per provider and day
+const COLLECTION_NAME: &str = "request_budgets";
+/// How often the requests counted in memory are added to the persisted counters
+const FLUSH_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Persisted requests of a provider on a UTC day
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct BudgetRecord {
+    provider: String,
+    day: NaiveDate,
+    requests: u64,
+}
+
+/// Requests a provider was sent today, against its `daily_budget`
+#[derive(Debug, Clone, Serialize)]
+pub struct BudgetUsage {
+    pub day: NaiveDate,
+    pub requests: u64,
+    /// Requests allowed per UTC day, unlimited when absent
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remaining: Option<u64>,
+}
+
+#[derive(Debug)]
+struct DayCount {
+    day: NaiveDate,
+    requests: u64,
+}
+
+/// Daily request budget of a provider client (`child_modules.<name>.daily_budget`).
+/// Requests are counted in memory, and once the database is attached the counts are added
+/// to the persisted counters every `FLUSH_INTERVAL`, so restarts don't reset the day.
+#[derive(Debug)]
+pub struct RequestBudget {
+    provider: String,
+    /// Requests allowed per UTC day, 0 is unlimited
+    limit: AtomicU64,
+    today: Mutex<DayCount>,
+    /// Requests not persisted yet, by day
+    unflushed: Mutex<HashMap<NaiveDate, u64>>,
+    db: OnceLock<Database>,
+}
+
+fn today() -> NaiveDate {
+    chrono::Utc::now().date_naive()
+}
+
+fn record_id(provider: &str, day: NaiveDate) -> String {
+    format!("{}:{}", provider, day)
+}
+
+impl RequestBudget {
+    pub fn new(provider: impl Into<String>, limit: Option<u64>) -> Self {
+        Self {
+            provider: provider.into(),
+            limit: AtomicU64::new(limit.unwrap_or(0)),
+            today: Mutex::new(DayCount { day: today(), requests: 0 }),
+            unflushed: Mutex::new(HashMap::new()),
+            db: OnceLock::new(),
+        }
+    }
+
+    /// Change the daily limit, applied on configuration reload
+    pub fn set_limit(&self, limit: Option<u64>) {
+        self.limit.store(limit.unwrap_or(0), Ordering::Relaxed);
+    }
+
+    fn limit(&self) -> Option<u64> {
+        Some(self.limit.load(Ordering::Relaxed)).filter(|limit| *limit > 0)
+    }
+
+    /// Persist the counters in the database, starting from the requests already recorded today
+    pub async fn attach(self: &Arc<Self>, db: Database) {
+        let day = today();
+        let record = db.collection::<BudgetRecord>(COLLECTION_NAME)
+            .find_one(doc! { "_id": record_id(&self.provider, day) })
+            .await;
+
+        match record {
+            Ok(Some(record)) => {
+                if let Ok(mut today) = self.today.lock()
+                    && today.day == day
+                {
+                    today.requests += record.requests;
+                }
+                debug!(provider = %self.provider, requests = record.requests, "Loaded today's request count");
+            }
+            Ok(None) => {}
+            Err(e) => warn!(provider = %self.provider, error = %e, "Failed to load today's request count"),
+        }
+
+        if self.db.set(db).is_err() {
+            return;
+        }
+
+        let budget = Arc::downgrade(self);
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(FLUSH_INTERVAL);
+            loop {
+                interval.tick().await;
+                let Some(budget) = budget.upgrade() else { break };
+                budget.flush().await;
+            }
+        });
+    }
+
+    /// Add the requests counted since the last flush to the persisted counters
+    async fn flush(&self) {
+        let Some(db) = self.db.get() else { return };
+        let unflushed = match self.unflushed.lock() {
+            Ok(mut unflushed) => std::mem::take(&mut *unflushed),
+            Err(_) => return,
+        };
+
+        for (day, requests) in unflushed {
+            let result = db.collection::<BudgetRecord>(COLLECTION_NAME)
+                .update_one(
+                    doc! { "_id": record_id(&self.provider, day) },
+                    doc! {
+                        "$inc": { "requests": requests as i64 },
+                        "$setOnInsert": { "provider": &self.provider, "day": day.to_string() },
+                    },
+                )
+                .upsert(true)
+                .await;
+            if let Err(e) = result {
+                warn!(provider = %self.provider, error = %e, "Failed to persist request count, retrying on the next flush");
+                if let Ok(mut unflushed) = self.unflushed.lock() {
+                    *unflushed.entry(day).or_default() += requests;
+                }
+            }
+        }
+    }
+
+    /// Count a request sent to the provider
+    pub fn record(&self) {
+        let (day, requests) = match self.today.lock() {
+            Ok(mut today) => {
+                let day = self::today();
+                if today.day != day {
+                    *today = DayCount { day, requests: 0 };
+                }
+                today.requests += 1;
+                (today.day, today.requests)
+            }
+            Err(_) => return,
+        };
+
+        if let Some(limit) = self.limit()
+            && requests == limit
+        {
+            info!(provider = %self.provider, limit = limit, "Daily request budget exhausted, low priority tasks wait for tomorrow");
+        }
+
+        if self.db.get().is_some()
+            && let Ok(mut unflushed) = self.unflushed.lock()
+        {
+            *unflushed.entry(day).or_default() += 1;
+        }
+    }
+
+    /// Whether today's requests reached the daily limit
+    pub fn is_exhausted(&self) -> bool {
+        self.usage().remaining == Some(0)
+    }
+
+    pub fn usage(&self) -> BudgetUsage {
+        let day = today();
+        let requests = match self.today.lock() {
+            Ok(today) if today.day == day => today.requests,
+            _ => 0,
+        };
+        let limit = self.limit();
+
+        BudgetUsage {
+            day,
+            requests,
+            limit,
+            remaining: limit.map(|limit| limit.saturating_sub(requests)),
+        }
+    }
+}
@@ -3,7 +3,19 @@ use std::collections::HashMap;
 use anyhow::Result;
 use tracing::warn;
 
+use crate::global::alert::AlertingConfig;
+use crate::global::disk::DiskConfig;
 use crate::global::error::ConfigError;
+use crate::global::supervisor::SupervisorConfig;
+use crate::global::secrets::{redacted, SecretResolver, SecretsConfig, REDACTED};
+use crate::global::webhook::WebhooksConfig;
+use crate::integrations::IntegrationsConfig;
+use crate::anime::bootstrap::BootstrapConfig;
+use crate::anime::fallback::FallbackProvider;
+use crate::anime::freshness::MAX_SECTION_AGE_DAYS;
+
+/// Configuration file watched for hot-reload
+pub const CONFIG_FILE: &str = "config.toml";
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct AppConfig {
@@ -13,6 +25,30 @@ pub struct AppConfig {
     pub modules: ModulesConfig,
     pub child_modules: HashMap<String, ChildModuleConfig>,
     pub http: HttpConfig,
+    #[serde(default)]
+    pub secrets: SecretsConfig,
+    #[serde(default)]
+    pub picture: PictureConfig,
+    #[serde(default)]
+    pub video: VideoConfig,
+    #[serde(default)]
+    pub music: MusicConfig,
+    #[serde(default)]
+    pub anime: AnimeConfig,
+    #[serde(default)]
+    pub features: FeatureFlags,
+    #[serde(default)]
+    pub alerting: AlertingConfig,
+    #[serde(default)]
+    pub webhooks: WebhooksConfig,
+    #[serde(default)]
+    pub integrations: IntegrationsConfig,
+    #[serde(default)]
+    pub supervisor: SupervisorConfig,
+    #[serde(default)]
+    pub disk: DiskConfig,
+    #[serde(default)]
+    pub bootstrap: BootstrapConfig,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -20,6 +56,9 @@ pub struct AppSettings {
     pub log_level: String,
     #[serde(default)]
     pub logging: LoggingConfig,
+    /// Reload log level, rate limits and retry settings when config.toml changes
+    #[serde(default = "default_hot_reload")]
+    pub hot_reload: bool,
 }
 
 // Add new ApiConfig struct:
@@ -28,6 +67,92 @@ pub struct ApiConfig {
     pub enabled: bool,
     pub host: String,
     pub port: u16,
+    #[serde(default)]
+    pub compression: CompressionConfig,
+    /// Reject the mutating endpoints at startup, toggled at runtime with POST /api/admin/read-only
+    #[serde(default)]
+    pub read_only: bool,
+    /// Keys of the admin endpoints that stay available in read-only mode
+    #[serde(default)]
+    pub admin: AdminConfig,
+    /// Isolated libraries served under /ns/{name}/, by namespace name
+    #[serde(default)]
+    pub namespaces: HashMap<String, NamespaceConfig>,
+}
+
+/// A library with its own database, picture directory and task queues
+#[derive(Clone, Default, Deserialize, Serialize)]
+pub struct NamespaceConfig {
+    /// Keys accepted in X-Api-Key for this namespace only, the namespace is open when empty.
+    /// Each key can be a secret reference.
+    #[serde(default)]
+    pub api_keys: Vec<String>,
+}
+
+impl std::fmt::Debug for NamespaceConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NamespaceConfig")
+            .field("api_keys", &vec![REDACTED; self.api_keys.len()])
+            .finish()
+    }
+}
+
+/// `[api.admin]` config section
+#[derive(Clone, Default, Deserialize, Serialize)]
+pub struct AdminConfig {
+    /// Keys accepted in X-Api-Key by the mutating /api/admin routes, module pause/resume and
+    /// every namespace. Those routes are disabled when empty, read-only mode then only comes
+    /// from `api.read_only`. Each key can be a secret reference.
+    #[serde(default)]
+    pub api_keys: Vec<String>,
+}
+
+impl std::fmt::Debug for AdminConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AdminConfig")
+            .field("api_keys", &vec![REDACTED; self.api_keys.len()])
+            .finish()
+    }
+}
+
+/// Namespace names become part of database names and URL paths
+pub fn is_valid_namespace(name: &str) -> bool {
+    !name.is_empty()
+        && name.len() <= 32
+        && name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
+
+/// Response compression, negotiated with the client's Accept-Encoding
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct CompressionConfig {
+    #[serde(default = "default_compression_enabled")]
+    pub enabled: bool,
+    #[serde(default = "default_compression_enabled")]
+    pub gzip: bool,
+    #[serde(default = "default_compression_enabled")]
+    pub brotli: bool,
+    /// Smaller responses are sent uncompressed
+    #[serde(default = "default_compression_min_size")]
+    pub min_size_bytes: u16,
+}
+
+fn default_compression_enabled() -> bool {
+    true
+}
+
+fn default_compression_min_size() -> u16 {
+    1024
+}
+
+impl Default for CompressionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: default_compression_enabled(),
+            gzip: default_compression_enabled(),
+            brotli: default_compression_enabled(),
+            min_size_bytes: default_compression_min_size(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -42,6 +167,15 @@ pub struct LoggingConfig {
     pub log_rotation: LogRotation,
     #[serde(default = "default_log_to_console")]
     pub log_to_console: bool,
+    /// Maximum number of log files kept, including the current one
+    #[serde(default)]
+    pub max_files: Option<usize>,
+    /// Delete rotated log files older than this many days
+    #[serde(default)]
+    pub max_age_days: Option<u64>,
+    /// Gzip rotated log files
+    #[serde(default)]
+    pub compress_rotated: bool,
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -52,6 +186,10 @@ pub enum LogRotation {
     Never,
 }
 
+fn default_hot_reload() -> bool {
+    true
+}
+
 fn default_log_to_file() -> bool {
     true
 }
@@ -78,6 +216,10 @@ impl Default for ApiConfig {
             enabled: true,
             host: "0.0.0.0".to_string(),
             port: 3000,
+            compression: CompressionConfig::default(),
+            read_only: false,
+            admin: AdminConfig::default(),
+            namespaces: HashMap::new(),
         }
     }
 }
@@ -90,15 +232,71 @@ impl Default for LoggingConfig {
             log_file_prefix: default_log_file_prefix(),
             log_rotation: default_log_rotation(),
             log_to_console: default_log_to_console(),
+            max_files: None,
+            max_age_days: None,
+            compress_rotated: false,
         }
     }
 }
 
-#[derive(Debug, Clone, Deserialize, Serialize)]
+#[derive(Clone, Deserialize, Serialize)]
 pub struct DatabaseConfig {
     pub host: String,
     pub port: i32,
     pub name: String,
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+    #[serde(default)]
+    pub password_file: Option<String>,
+}
+
+impl std::fmt::Debug for DatabaseConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DatabaseConfig")
+            .field("host", &self.host)
+            .field("port", &self.port)
+            .field("name", &self.name)
+            .field("username", &self.username)
+            .field("password", &redacted(&self.password))
+            .field("password_file", &self.password_file)
+            .finish()
+    }
+}
+
+impl DatabaseConfig {
+    /// Same server, with the database of a namespace
+    pub fn for_namespace(&self, namespace: &str) -> Self {
+        Self {
+            name: format!("{}_{}", self.name, namespace),
+            ..self.clone()
+        }
+    }
+
+    /// MongoDB connection URI, including credentials when configured
+    pub fn uri(&self) -> String {
+        match (&self.username, &self.password) {
+            (Some(user), Some(password)) => format!(
+                "mongodb://{}:{}@{}:{}",
+                urlencoding::encode(user),
+                urlencoding::encode(password),
+                self.host,
+                self.port
+            ),
+            _ => format!("mongodb://{}:{}", self.host, self.port),
+        }
+    }
+
+    /// Connection URI safe to log (password masked)
+    pub fn redacted_uri(&self) -> String {
+        match &self.username {
+            Some(user) if self.password.is_some() => {
+                format!("mongodb://{}:***@{}:{}", user, self.host, self.port)
+            }
+            _ => format!("mongodb://{}:{}", self.host, self.port),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -106,6 +304,33 @@ pub struct ModulesConfig {
     pub anime: ParentModuleConfig,
     #[serde(default)]
     pub manga: ParentModuleConfig,
+    #[serde(default = "default_enabled_module")]
+    pub picture: ParentModuleConfig,
+    #[serde(default)]
+    pub video: ParentModuleConfig,
+    #[serde(default)]
+    pub music: ParentModuleConfig,
+}
+
+fn default_enabled_module() -> ParentModuleConfig {
+    ParentModuleConfig { enabled: true }
+}
+
+impl ModulesConfig {
+    /// Config names of the enabled parent modules
+    pub fn enabled(&self) -> Vec<&'static str> {
+        [
+            ("anime", &self.anime),
+            ("manga", &self.manga),
+            ("picture", &self.picture),
+            ("video", &self.video),
+            ("music", &self.music),
+        ]
+        .into_iter()
+        .filter(|(_, module)| module.enabled)
+        .map(|(name, _)| name)
+        .collect()
+    }
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -119,22 +344,452 @@ impl Default for ParentModuleConfig {
     }
 }
 
+/// Experimental behaviors, all disabled by default
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct FeatureFlags {
+    /// Periodically refresh the stale extended data sections of stored anime, after
+    /// `AUTO_REFRESH_DAYS` unless `anime.section_refresh_days` sets another age
+    #[serde(default)]
+    pub auto_refresh: bool,
+    /// Send the `[webhooks]` data event notifications
+    #[serde(default)]
+    pub webhooks: bool,
+}
+
+/// Section age refreshed by the `auto_refresh` feature when `anime.section_refresh_days` is 0
+pub const AUTO_REFRESH_DAYS: i64 = 30;
+
+impl FeatureFlags {
+    /// Names of the enabled features, for logging
+    pub fn enabled(&self) -> Vec<&'static str> {
+        let mut enabled = Vec::new();
+        if self.auto_refresh {
+            enabled.push("auto_refresh");
+        }
+        if self.webhooks {
+            enabled.push("webhooks");
+        }
+        enabled
+    }
+}
+
+/// Settings of the picture fetcher module
 #[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PictureConfig {
+    #[serde(default = "default_picture_storage_path")]
+    pub storage_path: String,
+    #[serde(default = "default_picture_queue_size")]
+    pub queue_size: usize,
+    /// Number of pictures downloaded at the same time
+    #[serde(default = "default_picture_concurrency")]
+    pub concurrency: usize,
+    /// How often old failed downloads are cleaned up
+    #[serde(default = "default_picture_cleanup_interval_hours")]
+    pub cleanup_interval_hours: u64,
+    /// Variants of the provider images downloaded by the anime picture tasks
+    #[serde(default)]
+    pub variants: ImageVariantPolicy,
+    /// Also download the thumbnails of the AniList streaming episodes
+    #[serde(default)]
+    pub streaming_thumbnails: bool,
+    /// Memory shared by the downloads in progress, in MB, 0 for no limit
+    #[serde(default = "default_picture_max_buffered_mb")]
+    pub max_buffered_mb: u64,
+    /// Where downloaded files are stored under `storage_path`
+    #[serde(default)]
+    pub storage_mode: PictureStorageMode,
+    /// Delete the pictures of removed anime, and their files once unused
+    #[serde(default = "default_picture_collect_garbage")]
+    pub collect_garbage: bool,
+}
+
+/// Layout of the downloaded pictures under `picture.storage_path`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PictureStorageMode {
+    /// Directories by entity and category, named after the URL
+    #[default]
+    Path,
+    /// `objects/ab/cd/<sha256>`, identical files are stored once
+    Content,
+}
+
+/// Which sizes and formats of a provider image are downloaded
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImageVariantPolicy {
+    /// Every size of both the JPG and the WebP image
+    #[default]
+    All,
+    /// Only the large JPG, or the regular JPG when there is no large one
+    LargeJpg,
+    /// The largest available size of each image, JPG preferred over WebP
+    Best,
+}
+
+fn default_picture_storage_path() -> String {
+    "./pictures".to_string()
+}
+
+fn default_picture_queue_size() -> usize {
+    4000
+}
+
+fn default_picture_concurrency() -> usize {
+    1
+}
+
+fn default_picture_cleanup_interval_hours() -> u64 {
+    6
+}
+
+fn default_picture_max_buffered_mb() -> u64 {
+    256
+}
+
+fn default_picture_collect_garbage() -> bool {
+    true
+}
+
+impl PictureConfig {
+    pub fn cleanup_interval(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.cleanup_interval_hours.max(1) * 60 * 60)
+    }
+}
+
+impl Default for PictureConfig {
+    fn default() -> Self {
+        Self {
+            storage_path: default_picture_storage_path(),
+            queue_size: default_picture_queue_size(),
+            concurrency: default_picture_concurrency(),
+            cleanup_interval_hours: default_picture_cleanup_interval_hours(),
+            variants: ImageVariantPolicy::default(),
+            streaming_thumbnails: false,
+            max_buffered_mb: default_picture_max_buffered_mb(),
+            storage_mode: PictureStorageMode::default(),
+            collect_garbage: default_picture_collect_garbage(),
+        }
+    }
+}
+
+/// Settings of the video module, which downloads trailers and promotional videos
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct VideoConfig {
+    #[serde(default = "default_video_storage_path")]
+    pub storage_path: String,
+    #[serde(default = "default_video_queue_size")]
+    pub queue_size: usize,
+    /// Number of videos downloaded at the same time
+    #[serde(default = "default_video_concurrency")]
+    pub concurrency: usize,
+    /// How often old failed downloads are cleaned up
+    #[serde(default = "default_video_cleanup_interval_hours")]
+    pub cleanup_interval_hours: u64,
+    /// yt-dlp executable, or another command accepting its options
+    #[serde(default = "default_video_command")]
+    pub command: String,
+    /// yt-dlp format selection
+    #[serde(default = "default_video_format")]
+    pub format: String,
+    /// A download still running after this long is killed and marked failed
+    #[serde(default = "default_video_timeout_minutes")]
+    pub timeout_minutes: u64,
+}
+
+fn default_video_storage_path() -> String {
+    "./videos".to_string()
+}
+
+fn default_video_queue_size() -> usize {
+    500
+}
+
+fn default_video_concurrency() -> usize {
+    1
+}
+
+fn default_video_cleanup_interval_hours() -> u64 {
+    24
+}
+
+fn default_video_command() -> String {
+    "yt-dlp".to_string()
+}
+
+fn default_video_format() -> String {
+    "bv*[height<=1080]+ba/b[height<=1080]/b".to_string()
+}
+
+fn default_video_timeout_minutes() -> u64 {
+    30
+}
+
+impl VideoConfig {
+    pub fn cleanup_interval(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.cleanup_interval_hours.max(1) * 60 * 60)
+    }
+
+    pub fn timeout(&self) -> std::time::Duration {
+        std::time::Duration::from_secs(self.timeout_minutes.max(1) * 60)
+    }
+}
+
+impl Default for VideoConfig {
+    fn default() -> Self {
+        Self {
+            storage_path: default_video_storage_path(),
+            queue_size: default_video_queue_size(),
+            concurrency: default_video_concurrency(),
+            cleanup_interval_hours: default_video_cleanup_interval_hours(),
+            command: default_video_command(),
+            format: default_video_format(),
+            timeout_minutes: default_video_timeout_minutes(),
+        }
+    }
+}
+
+/// Settings of the music module, which stores the theme songs of the anime
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct MusicConfig {
+    #[serde(default = "default_music_queue_size")]
+    pub queue_size: usize,
+    #[serde(default = "default_music_concurrency")]
+    pub concurrency: usize,
+    /// Match the theme songs with their AnisongDB entries (composer, audio samples)
+    #[serde(default = "default_anisongdb")]
+    pub anisongdb: bool,
+    #[serde(default = "default_anisongdb_url")]
+    pub anisongdb_url: String,
+}
+
+fn default_music_queue_size() -> usize {
+    500
+}
+
+fn default_music_concurrency() -> usize {
+    1
+}
+
+fn default_anisongdb() -> bool {
+    true
+}
+
+fn default_anisongdb_url() -> String {
+    "https://anisongdb.com/api".to_string()
+}
+
+impl Default for MusicConfig {
+    fn default() -> Self {
+        Self {
+            queue_size: default_music_queue_size(),
+            concurrency: default_music_concurrency(),
+            anisongdb: default_anisongdb(),
+            anisongdb_url: default_anisongdb_url(),
+        }
+    }
+}
+
+/// Settings of the anime module
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct AnimeConfig {
+    #[serde(default = "default_anime_queue_size")]
+    pub queue_size: usize,
+    /// Queue picture downloads whenever an anime is fetched, even without `with_pictures`
+    #[serde(default)]
+    pub auto_pictures: bool,
+    /// Queue a MyAnimeList fetch for AniList anime whose MAL id isn't stored
+    #[serde(default)]
+    pub mal_backfill: bool,
+    /// Providers tried in order when MyAnimeList returns 404 for the anime
+    #[serde(default = "default_anime_fallback")]
+    pub fallback: Vec<FallbackProvider>,
+    /// Hours between statistics snapshots (score, members, favorites, watching), 0 disables them
+    #[serde(default = "default_snapshot_interval_hours")]
+    pub snapshot_interval_hours: u64,
+    /// MAL ids included in statistics snapshots, currently airing anime when empty
+    #[serde(default)]
+    pub snapshot_anime: Vec<u32>,
+    /// Minutes between refreshes of the watched anime (episodes, statistics, pictures), 0 disables them
+    #[serde(default = "default_watchlist_refresh_minutes")]
+    pub watchlist_refresh_minutes: u64,
+    /// Infer a missing season and year from the start date when converting provider data
+    #[serde(default = "default_infer_season")]
+    pub infer_season: bool,
+    /// Days after which an extended data section (characters, episodes, statistics, ...)
+    /// is fetched again, checked hourly. 0 disables the refresh, unless `features.auto_refresh`.
+    #[serde(default)]
+    pub section_refresh_days: u64,
+    /// Anime refreshed per section on each check
+    #[serde(default = "default_section_refresh_limit")]
+    pub section_refresh_limit: i64,
+}
+
+fn default_anime_queue_size() -> usize {
+    1000
+}
+
+fn default_anime_fallback() -> Vec<FallbackProvider> {
+    vec![FallbackProvider::Jikan, FallbackProvider::Anilist]
+}
+
+fn default_snapshot_interval_hours() -> u64 {
+    24
+}
+
+fn default_infer_season() -> bool {
+    true
+}
+
+fn default_watchlist_refresh_minutes() -> u64 {
+    60
+}
+
+fn default_section_refresh_limit() -> i64 {
+    50
+}
+
+impl AnimeConfig {
+    /// Period of the statistics snapshots, `None` when disabled
+    pub fn snapshot_interval(&self) -> Option<std::time::Duration> {
+        (self.snapshot_interval_hours > 0)
+            .then(|| std::time::Duration::from_secs(self.snapshot_interval_hours * 60 * 60))
+    }
+
+    /// Period of the watchlist refreshes, `None` when disabled
+    pub fn watchlist_refresh_interval(&self) -> Option<std::time::Duration> {
+        (self.watchlist_refresh_minutes > 0)
+            .then(|| std::time::Duration::from_secs(self.watchlist_refresh_minutes * 60))
+    }
+
+    /// Age in days after which sections are refetched, `None` when disabled
+    pub fn section_refresh_age(&self) -> Option<i64> {
+        (self.section_refresh_days > 0).then_some(self.section_refresh_days as i64)
+    }
+}
+
+impl Default for AnimeConfig {
+    fn default() -> Self {
+        Self {
+            queue_size: default_anime_queue_size(),
+            auto_pictures: false,
+            mal_backfill: false,
+            fallback: default_anime_fallback(),
+            snapshot_interval_hours: default_snapshot_interval_hours(),
+            snapshot_anime: Vec::new(),
+            watchlist_refresh_minutes: default_watchlist_refresh_minutes(),
+            infer_season: default_infer_season(),
+            section_refresh_days: 0,
+            section_refresh_limit: default_section_refresh_limit(),
+        }
+    }
+}
+
+#[derive(Clone, Deserialize, Serialize)]
 pub struct ChildModuleConfig {
     pub enabled: bool,
     pub rate_limit: f64,
+    /// Requests allowed per UTC day, low priority tasks wait for the next day once spent
+    #[serde(default)]
+    pub daily_budget: Option<u64>,
     #[serde(default)]
     pub api_key: String,
+    /// Path to a file containing the API key, takes precedence over api_key
+    #[serde(default)]
+    pub api_key_file: Option<String>,
     #[serde(default)]
     pub requires_api_key: bool,
+    /// Replaces the provider's public API URL, e.g. to point at a mock server
+    #[serde(default)]
+    pub base_url: Option<String>,
+    /// User-Agent template of the provider's requests, replaces `http.user_agent`
+    #[serde(default)]
+    pub user_agent: Option<String>,
+}
+
+impl std::fmt::Debug for ChildModuleConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let api_key = if self.api_key.is_empty() { "" } else { REDACTED };
+        f.debug_struct("ChildModuleConfig")
+            .field("enabled", &self.enabled)
+            .field("rate_limit", &self.rate_limit)
+            .field("daily_budget", &self.daily_budget)
+            .field("api_key", &api_key)
+            .field("api_key_file", &self.api_key_file)
+            .field("requires_api_key", &self.requires_api_key)
+            .field("base_url", &self.base_url)
+            .field("user_agent", &self.user_agent)
+            .finish()
+    }
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
 pub struct HttpConfig {
     pub timeout_seconds: u64,
+    /// User-Agent template, `{app}`, `{version}` and `{contact}` are replaced
     pub user_agent: String,
+    /// Contact (URL or e-mail) put in the User-Agent with `{contact}`, some providers ask for one
+    #[serde(default)]
+    pub contact: String,
     pub default_rate_limit: f64,
+    /// Lower the request rate of a client after rate limit responses, back up to
+    /// its configured `rate_limit` once responses are clean again
+    #[serde(default = "default_adaptive_rate_limit")]
+    pub adaptive_rate_limit: bool,
     pub retry: RetryConfig,
+    /// Whether provider responses come from the network, are recorded, or are replayed
+    #[serde(default)]
+    pub mode: HttpMode,
+    /// Directory of the recorded provider responses
+    #[serde(default = "default_fixtures_dir")]
+    pub fixtures_dir: String,
+    /// Copy of the provider responses in the `raw_responses` collection
+    #[serde(default)]
+    pub archive: ArchiveConfig,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct ArchiveConfig {
+    /// Store the body of each successful MyAnimeList, Jikan and AniList response
+    #[serde(default)]
+    pub enabled: bool,
+    /// Archived responses are deleted by MongoDB after this many days
+    #[serde(default = "default_archive_retention_days")]
+    pub retention_days: u64,
+}
+
+impl Default for ArchiveConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            retention_days: default_archive_retention_days(),
+        }
+    }
+}
+
+fn default_archive_retention_days() -> u64 {
+    30
+}
+
+/// Source of the provider responses fetched with `ClientWithLimiter::fetch_json`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HttpMode {
+    /// Requests go to the providers
+    #[default]
+    Live,
+    /// Requests go to the providers and their responses are saved to `fixtures_dir`
+    Record,
+    /// Responses are read from `fixtures_dir`, nothing is sent to the providers
+    Replay,
+}
+
+fn default_adaptive_rate_limit() -> bool {
+    true
+}
+
+fn default_fixtures_dir() -> String {
+    "fixtures".to_string()
 }
 
 #[derive(Debug, Clone, Deserialize, Serialize)]
@@ -148,16 +803,112 @@ impl AppConfig {
     /// Load configuration from config.toml file
     pub fn load() -> Result<Self> {
         let config = config::Config::builder()
-            .add_source(config::File::with_name("config"))
+            .add_source(config::File::with_name(CONFIG_FILE))
             .build()
             .map_err(|e| ConfigError::LoadFailed(e.to_string()))?;
 
-        let app_config: AppConfig = config.try_deserialize()
+        let mut app_config: AppConfig = config.try_deserialize()
             .map_err(|e| ConfigError::LoadFailed(e.to_string()))?;
 
+        app_config.resolve_secrets()?;
+
+        if let Some(name) = app_config.api.namespaces.keys().find(|name| !is_valid_namespace(name)) {
+            return Err(ConfigError::Invalid(format!(
+                "api.namespaces.{}: names may only contain lowercase letters, digits and underscores (at most 32)",
+                name
+            )).into());
+        }
+
+        if app_config.anime.section_refresh_days > MAX_SECTION_AGE_DAYS {
+            return Err(ConfigError::Invalid(format!(
+                "anime.section_refresh_days: at most {} days",
+                MAX_SECTION_AGE_DAYS
+            )).into());
+        }
+
+        // A queue holds its tasks in a bounded channel, which needs room for one
+        if let Some((section, _)) = app_config.queue_sizes().into_iter().find(|(_, size)| *size == 0) {
+            return Err(ConfigError::Invalid(format!("{}.queue_size: must be at least 1", section)).into());
+        }
+
         Ok(app_config)
     }
 
+    /// Queue size of each parent module, by config section
+    pub fn queue_sizes(&self) -> [(&'static str, usize); 4] {
+        [
+            ("anime", self.anime.queue_size),
+            ("picture", self.picture.queue_size),
+            ("video", self.video.queue_size),
+            ("music", self.music.queue_size),
+        ]
+    }
+
+    /// Replace secret references (`env:`, `file:`, `vault:` and `*_file` paths)
+    /// with their actual values
+    fn resolve_secrets(&mut self) -> Result<(), ConfigError> {
+        let resolver = SecretResolver::from_config(&self.secrets)?;
+
+        for (name, module) in self.child_modules.iter_mut() {
+            if !module.enabled {
+                continue;
+            }
+
+            module.api_key = resolver
+                .resolve_field(&module.api_key, module.api_key_file.as_deref())
+                .map_err(|e| ConfigError::Invalid(format!("child_modules.{}.api_key: {}", name, e)))?;
+        }
+
+        if let Some(username) = &self.database.username {
+            self.database.username = Some(resolver.resolve_value(username)?);
+        }
+
+        let password = self.database.password.clone().unwrap_or_default();
+        if !password.is_empty() || self.database.password_file.is_some() {
+            let resolved = resolver
+                .resolve_field(&password, self.database.password_file.as_deref())
+                .map_err(|e| ConfigError::Invalid(format!("database.password: {}", e)))?;
+            self.database.password = Some(resolved);
+        }
+
+        for (index, endpoint) in self.webhooks.endpoints.iter_mut().enumerate() {
+            if let Some(secret) = &endpoint.secret {
+                let resolved = resolver
+                    .resolve_value(secret)
+                    .map_err(|e| ConfigError::Invalid(format!("webhooks.endpoints[{}].secret: {}", index, e)))?;
+                endpoint.secret = Some(resolved);
+            }
+        }
+
+        for (name, namespace) in self.api.namespaces.iter_mut() {
+            for key in namespace.api_keys.iter_mut() {
+                *key = resolver
+                    .resolve_value(key)
+                    .map_err(|e| ConfigError::Invalid(format!("api.namespaces.{}.api_keys: {}", name, e)))?;
+            }
+        }
+
+        for key in self.api.admin.api_keys.iter_mut() {
+            *key = resolver
+                .resolve_value(key)
+                .map_err(|e| ConfigError::Invalid(format!("api.admin.api_keys: {}", e)))?;
+        }
+
+        let discord = &mut self.integrations.discord;
+        if let Some(url) = &discord.webhook_url {
+            discord.webhook_url = Some(resolver
+                .resolve_value(url)
+                .map_err(|e| ConfigError::Invalid(format!("integrations.discord.webhook_url: {}", e)))?);
+        }
+        if let Some(token) = &discord.bot_token {
+            discord.bot_token = Some(resolver
+                .resolve_value(token)
+                .map_err(|e| ConfigError::Invalid(format!("integrations.discord.bot_token: {}", e)))?);
+        }
+
+        Ok(())
+    }
+
     /// Validate that a child module has required configuration
     /// Returns Ok(()) if valid, Err(ConfigError) if missing required config
     pub fn validate_child_module(&self, module_name: &str, requires_api_key: bool) -> Result<(), ConfigError> {
@@ -196,10 +947,19 @@ impl AppConfig {
         match module_name {
             "anime" => self.modules.anime.enabled,
             "manga" => self.modules.manga.enabled,
+            "picture" => self.modules.picture.enabled,
+            "video" => self.modules.video.enabled,
+            "music" => self.modules.music.enabled,
             _ => false,
         }
     }
 
+    /// Age in days after which extended data sections are fetched again, `None` when disabled
+    pub fn section_refresh_age(&self) -> Option<i64> {
+        self.anime.section_refresh_age()
+            .or_else(|| self.features.auto_refresh.then_some(AUTO_REFRESH_DAYS))
+    }
+
     /// Check if a child module is enabled
     pub fn is_child_module_enabled(&self, module_name: &str) -> bool {
         self.child_modules
@@ -221,6 +981,34 @@ impl AppConfig {
             .unwrap_or(self.http.default_rate_limit)
     }
 
+    /// Daily request budget of a child module, unlimited when not configured
+    pub fn get_daily_budget(&self, module_name: &str) -> Option<u64> {
+        self.child_modules
+            .get(module_name)
+            .and_then(|config| config.daily_budget)
+    }
+
+    /// API URL of a child module, `default` unless overridden with `base_url`
+    pub fn get_base_url(&self, module_name: &str, default: &str) -> String {
+        self.child_modules
+            .get(module_name)
+            .and_then(|config| config.base_url.as_deref())
+            .unwrap_or(default)
+            .trim_end_matches('/')
+            .to_string()
+    }
+
+    /// User-Agent of a child module's requests, from its `user_agent` template or `http.user_agent`
+    pub fn get_user_agent(&self, module_name: &str) -> String {
+        self.child_modules
+            .get(module_name)
+            .and_then(|config| config.user_agent.as_deref())
+            .unwrap_or(&self.http.user_agent)
+            .replace("{app}", env!("CARGO_PKG_NAME"))
+            .replace("{version}", env!("CARGO_PKG_VERSION"))
+            .replace("{contact}", &self.http.contact)
+    }
+
     /// Get API key for a child module
     pub fn get_api_key(&self, module_name: &str) -> Option<String> {
         self.child_modules
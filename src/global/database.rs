@@ -5,6 +5,7 @@ use serde::{Serialize, Deserialize};
 use tracing::{debug, info, warn};
 use std::time::Duration;
 
+use super::config::DatabaseConfig;
 use super::error::{AppError, DatabaseError};
 
 #[derive(Clone)]
@@ -13,11 +14,11 @@ pub struct DatabaseInstance {
 }
 
 impl DatabaseInstance {
-    pub async fn new(host: &str, port: i32, db_name: &str) -> Result<Self, AppError> {
-        let uri = format!("mongodb://{}:{}", host, port);
-        info!(uri = %uri, database = %db_name, "Connecting to MongoDB");
+    pub async fn new(config: &DatabaseConfig) -> Result<Self, AppError> {
+        let db_name = &config.name;
+        info!(uri = %config.redacted_uri(), database = %db_name, "Connecting to MongoDB");
 
-        let db = Self::connect(&uri, db_name).await?;
+        let db = Self::connect(&config.uri(), db_name).await?;
 
         // Initialize indexes and collections
         db.initialize_global_collections().await?;
@@ -27,11 +28,27 @@ impl DatabaseInstance {
     }
 
     async fn connect(uri: &str, db_name: &str) -> Result<Self, DatabaseError> {
-        debug!(uri = %uri, "Creating MongoDB client");
+        debug!("Creating MongoDB client");
         let client = mongodb::Client::with_uri_str(uri).await?;
         Ok(Self { db: client.database(db_name) })
     }
 
+    /// Check that the MongoDB server is reachable without initializing collections
+    pub async fn ping(config: &DatabaseConfig) -> Result<(), DatabaseError> {
+        let instance = Self::connect(&config.uri(), &config.name).await?;
+
+        instance.db.run_command(doc! { "ping": 1 }).await?;
+
+        debug!(uri = %config.redacted_uri(), "MongoDB ping succeeded");
+        Ok(())
+    }
+
+    /// Check that the connected MongoDB server answers
+    pub async fn check_connection(&self) -> Result<(), DatabaseError> {
+        self.db.run_command(doc! { "ping": 1 }).await?;
+        Ok(())
+    }
+
     pub fn db(&self) -> &Database {
         &self.db
     }
@@ -49,6 +66,9 @@ impl DatabaseInstance {
         // API rate limit tracking collection
         self.create_rate_limit_indexes().await?;
 
+        // Job collection
+        self.create_job_indexes().await?;
+
         info!("Global collections initialized");
         Ok(())
     }
@@ -77,11 +97,17 @@ impl DatabaseInstance {
             .keys(doc! { "name": 1 })
             .build();
 
+        // Index on job_id for job progress
+        let job_index = IndexModel::builder()
+            .keys(doc! { "job_id": 1 })
+            .build();
+
         collection.create_indexes(vec![
             id_index,
             status_index,
             priority_index,
             name_index,
+            job_index,
         ]).await
             .map_err(|e| DatabaseError::Query(format!("Failed to create task_queue indexes: {}", e)))?;
 
@@ -133,6 +159,27 @@ impl DatabaseInstance {
         Ok(())
     }
 
+    async fn create_job_indexes(&self) -> Result<(), DatabaseError> {
+        let collection = self.db.collection::<Document>("jobs");
+
+        // Index on job ID (unique)
+        let id_index = IndexModel::builder()
+            .keys(doc! { "job_id": 1 })
+            .options(IndexOptions::builder().unique(true).build())
+            .build();
+
+        // Index on created_at for listing recent jobs
+        let created_index = IndexModel::builder()
+            .keys(doc! { "created_at": -1 })
+            .build();
+
+        collection.create_indexes(vec![id_index, created_index]).await
+            .map_err(|e| DatabaseError::Query(format!("Failed to create jobs indexes: {}", e)))?;
+
+        debug!("Created indexes for jobs collection");
+        Ok(())
+    }
+
     // ========================================================================
     // Global Database Operations
     // ========================================================================
@@ -236,7 +283,8 @@ impl DatabaseInstance {
         let completed_count = task_queue.count_documents(doc! { "status": "Completed" }).await
             .map_err(|e| DatabaseError::Query(format!("Failed to count completed: {}", e)))?;
         
-        let failed_count = task_queue.count_documents(doc! { "status": "Failed" }).await
+        // Failed is a struct variant, serialized as { "Failed": { "error": ... } }
+        let failed_count = task_queue.count_documents(doc! { "status.Failed": { "$exists": true } }).await
             .map_err(|e| DatabaseError::Query(format!("Failed to count failed: {}", e)))?;
 
         Ok(DatabaseStats {
@@ -0,0 +1,202 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+use tracing::{debug, info, warn};
+
+use crate::global::alert::{Alert, AlertKind, AlertManager, AlertSeverity};
+use crate::global::config::AppConfig;
+
+/// `[disk]` config section: measurement of the storage directories
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct DiskConfig {
+    /// How often the storage directories are measured, 0 measures them only on request
+    #[serde(default = "default_check_interval_seconds")]
+    pub check_interval_seconds: u64,
+    /// Alert when a storage filesystem has less free space, 0 disables the alert
+    #[serde(default)]
+    pub min_free_mb: u64,
+}
+
+fn default_check_interval_seconds() -> u64 {
+    600
+}
+
+impl Default for DiskConfig {
+    fn default() -> Self {
+        Self {
+            check_interval_seconds: default_check_interval_seconds(),
+            min_free_mb: 0,
+        }
+    }
+}
+
+/// Space used by the files of a storage directory and left on its filesystem
+#[derive(Debug, Clone, Serialize)]
+pub struct DiskUsage {
+    /// Storage of the pictures or the videos
+    pub name: &'static str,
+    pub path: String,
+    pub files: u64,
+    pub bytes: u64,
+    /// Space available to the process on the filesystem, unknown on non-Unix systems
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub free_bytes: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_bytes: Option<u64>,
+    pub measured_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl DiskUsage {
+    /// Whether the free space is below `min_free_mb`, false when disabled or unknown
+    pub fn is_low(&self, min_free_mb: u64) -> bool {
+        min_free_mb > 0 && self.free_bytes.is_some_and(|free| free < min_free_mb * 1024 * 1024)
+    }
+}
+
+/// Storage directories of the configuration, by name
+fn storage_directories(config: &AppConfig) -> [(&'static str, PathBuf); 2] {
+    [
+        ("pictures", PathBuf::from(&config.picture.storage_path)),
+        ("videos", PathBuf::from(&config.video.storage_path)),
+    ]
+}
+
+/// Measure a storage directory on the blocking pool, a missing directory is empty
+pub async fn measure(name: &'static str, path: PathBuf) -> DiskUsage {
+    let display = path.display().to_string();
+    let measured = tokio::task::spawn_blocking(move || {
+        let mut files = 0;
+        let mut bytes = 0;
+        add_directory(&path, &mut files, &mut bytes);
+        (files, bytes, filesystem_space(&path))
+    })
+    .await;
+
+    let (files, bytes, space) = measured.unwrap_or_default();
+    DiskUsage {
+        name,
+        path: display,
+        files,
+        bytes,
+        free_bytes: space.map(|(free, _)| free),
+        total_bytes: space.map(|(_, total)| total),
+        measured_at: chrono::Utc::now(),
+    }
+}
+
+fn add_directory(path: &Path, files: &mut u64, bytes: &mut u64) {
+    let entries = match std::fs::read_dir(path) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return,
+        Err(e) => {
+            warn!(path = %path.display(), error = %e, "Failed to read storage directory");
+            return;
+        }
+    };
+
+    for entry in entries.flatten() {
+        let Ok(metadata) = entry.metadata() else { continue };
+        if metadata.is_dir() {
+            add_directory(&entry.path(), files, bytes);
+        } else if metadata.is_file() {
+            *files += 1;
+            *bytes += metadata.len();
+        }
+    }
+}
+
+/// Free and total bytes of the filesystem holding `path`, or of its closest existing parent
+#[cfg(unix)]
+fn filesystem_space(path: &Path) -> Option<(u64, u64)> {
+    use std::os::unix::ffi::OsStrExt;
+
+    let existing = path.ancestors().find(|ancestor| ancestor.exists())?;
+    let existing = if existing.as_os_str().is_empty() { Path::new(".") } else { existing };
+    let path = std::ffi::CString::new(existing.as_os_str().as_bytes()).ok()?;
+
+    let mut stat = std::mem::MaybeUninit::<libc::statvfs>::uninit();
+    // SAFETY: `path` is a valid C string and `stat` is only read after statvfs succeeded
+    let stat = unsafe {
+        if libc::statvfs(path.as_ptr(), stat.as_mut_ptr()) != 0 {
+            return None;
+        }
+        stat.assume_init()
+    };
+
+    let block_size = stat.f_frsize as u64;
+    Some((stat.f_bavail as u64 * block_size, stat.f_blocks as u64 * block_size))
+}
+
+#[cfg(not(unix))]
+fn filesystem_space(_path: &Path) -> Option<(u64, u64)> {
+    None
+}
+
+/// Latest measurement of the storage directories, shared by the API handlers
+#[derive(Clone, Default)]
+pub struct DiskMonitor {
+    latest: Arc<RwLock<Vec<DiskUsage>>>,
+}
+
+impl DiskMonitor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Latest periodic measurement, measured now when it hasn't run yet or is disabled
+    /// (`check_interval_seconds = 0`), so the result is never older than the interval
+    pub async fn usage(&self, config: &AppConfig) -> Vec<DiskUsage> {
+        if config.disk.check_interval_seconds > 0 {
+            let latest = self.latest.read().await.clone();
+            if !latest.is_empty() {
+                return latest;
+            }
+        }
+        self.refresh(config).await
+    }
+
+    async fn refresh(&self, config: &AppConfig) -> Vec<DiskUsage> {
+        let mut usage = Vec::new();
+        for (name, path) in storage_directories(config) {
+            usage.push(measure(name, path).await);
+        }
+        *self.latest.write().await = usage.clone();
+        usage
+    }
+
+    /// Measure the storage directories every `disk.check_interval_seconds` and alert
+    /// when one runs low on free space
+    pub fn spawn(self, config: Arc<AppConfig>, alerts: AlertManager) {
+        if config.disk.check_interval_seconds == 0 {
+            debug!("Periodic disk usage measurement disabled");
+            return;
+        }
+        info!(interval_seconds = config.disk.check_interval_seconds, "Disk usage monitor started");
+
+        tokio::spawn(async move {
+            let interval = Duration::from_secs(config.disk.check_interval_seconds);
+            loop {
+                for usage in self.refresh(&config).await {
+                    debug!(storage = usage.name, files = usage.files, bytes = usage.bytes, free_bytes = ?usage.free_bytes, "Measured storage directory");
+                    if usage.is_low(config.disk.min_free_mb) {
+                        let free_mb = usage.free_bytes.unwrap_or_default() / (1024 * 1024);
+                        warn!(storage = usage.name, path = %usage.path, free_mb = free_mb, "Low free disk space");
+                        alerts.notify(Alert::new(
+                            AlertKind::LowDiskSpace,
+                            AlertSeverity::Warning,
+                            format!("Low free disk space for {}", usage.name),
+                            format!(
+                                "{} MB free on the filesystem of {}, threshold is {} MB",
+                                free_mb, usage.path, config.disk.min_free_mb
+                            ),
+                        )).await;
+                    }
+                }
+                tokio::time::sleep(interval).await;
+            }
+        });
+    }
+}
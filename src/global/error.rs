@@ -3,14 +3,69 @@ pub enum AppError {
     #[error(transparent)]
     Database(#[from] DatabaseError),
 
+    /// Internal failure of a module or queue, e.g. a closed channel
     #[error("module error: {0}")]
     Module(String),
 
+    /// A provider failed outside of the HTTP client, e.g. a GraphQL error or a failed download
+    #[error("{provider} error: {message}")]
+    Provider {
+        provider: String,
+        message: String,
+        /// Whether the same request may succeed later
+        retryable: bool,
+    },
+
+    /// Reading or writing a stored file failed
+    #[error("storage error: {0}")]
+    Storage(String),
+
+    /// The input of a task or command is invalid
+    #[error("invalid input: {0}")]
+    Validation(String),
+
+    /// The work was interrupted before it finished
+    #[error("canceled: {0}")]
+    Canceled(String),
+
     #[error(transparent)]
     Anime(#[from] crate::anime::error::AnimeError),
 
     #[error(transparent)]
     Http(#[from] HttpError),
+
+    /// The queue holds at least its configured number of tasks
+    #[error("queue {queue} is full ({depth}/{capacity} tasks)")]
+    QueueFull {
+        queue: String,
+        depth: u64,
+        capacity: u64,
+        /// Estimated time before the current tasks are done, `None` before any task completed
+        estimated_wait: Option<std::time::Duration>,
+    },
+}
+
+impl AppError {
+    /// Transient provider failure, e.g. a network error or a server error
+    pub fn provider(provider: impl Into<String>, message: impl Into<String>) -> Self {
+        Self::Provider { provider: provider.into(), message: message.into(), retryable: true }
+    }
+
+    /// Provider failure that a retry won't fix, e.g. an invalid response or a missing entry
+    pub fn provider_rejected(provider: impl Into<String>, message: impl Into<String>) -> Self {
+        Self::Provider { provider: provider.into(), message: message.into(), retryable: false }
+    }
+
+    /// Whether running the failed work again may succeed
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::Database(e) => matches!(e, DatabaseError::ConnexionFailed(_)),
+            Self::Http(e) => e.is_retryable(),
+            Self::Provider { retryable, .. } => *retryable,
+            Self::QueueFull { .. } => true,
+            Self::Module(_) | Self::Anime(_) | Self::Storage(_) | Self::Validation(_) | Self::Canceled(_) => false,
+        }
+    }
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -47,6 +102,28 @@ pub enum HttpError {
 
     #[error("max retries exceeded")]
     MaxRetriesExceeded,
+
+    #[error("no recorded response for {0}")]
+    FixtureMissing(String),
+
+    #[error("{client} circuit open after repeated failures, retry after {retry_after:?}")]
+    CircuitOpen {
+        client: String,
+        retry_after: std::time::Duration,
+    },
+}
+
+impl HttpError {
+    /// Transport errors and server errors are transient. Rate limits are not retried again,
+    /// the client already retried them with backoff before giving up.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::RequestFailed(_) | Self::CircuitOpen { .. } => true,
+            Self::UnexpectedStatus { status, .. } => *status >= 500,
+            Self::RateLimited { .. } | Self::MaxRetriesExceeded => false,
+            Self::NotFound(_) | Self::DeserializationFailed(_) | Self::FixtureMissing(_) => false,
+        }
+    }
 }
 
 #[derive(Debug, thiserror::Error)]
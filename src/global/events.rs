@@ -0,0 +1,100 @@
+use serde::Serialize;
+use tokio::sync::broadcast;
+use tracing::trace;
+
+use crate::global::job::JobStatus;
+
+/// Number of events kept for slow subscribers before they start lagging
+const EVENT_BUFFER: usize = 1024;
+
+/// Provider an anime entry was stored from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnimeSource {
+    MyAnimeList,
+    AniList,
+}
+
+/// Data changes published by tasks
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DataEvent {
+    /// An anime entry was fetched and upserted.
+    /// `id` is the MAL id for MyAnimeList and the AniList id for AniList.
+    AnimeStored {
+        source: AnimeSource,
+        id: u32,
+        /// Picture downloads were already queued by the task itself
+        pictures_queued: bool,
+    },
+    /// An existing anime entry was refreshed from its provider
+    AnimeUpdated {
+        source: AnimeSource,
+        id: u32,
+    },
+    /// An anime entry was removed, e.g. merged into a duplicate
+    AnimeDeleted {
+        source: AnimeSource,
+        id: u32,
+    },
+    /// A picture was downloaded (or matched an identical stored file)
+    PictureCompleted {
+        url: String,
+        file_path: String,
+        entity_type: Option<String>,
+        entity_id: Option<String>,
+    },
+    /// All tasks of a job finished
+    JobFinished {
+        job_id: String,
+        kind: String,
+        status: JobStatus,
+        total_tasks: u64,
+        failed_tasks: u64,
+    },
+}
+
+impl DataEvent {
+    /// All event names, for validating webhook filters
+    pub const KINDS: [&'static str; 5] = ["anime_stored", "anime_updated", "anime_deleted", "picture_completed", "job_finished"];
+
+    /// Event name, as used in the serialized `type` field
+    pub fn kind(&self) -> &'static str {
+        match self {
+            DataEvent::AnimeStored { .. } => "anime_stored",
+            DataEvent::AnimeUpdated { .. } => "anime_updated",
+            DataEvent::AnimeDeleted { .. } => "anime_deleted",
+            DataEvent::PictureCompleted { .. } => "picture_completed",
+            DataEvent::JobFinished { .. } => "job_finished",
+        }
+    }
+}
+
+/// In-process publish/subscribe channel for data events.
+/// Publishing never blocks; events are dropped when nobody listens.
+#[derive(Clone)]
+pub struct EventBus {
+    tx: broadcast::Sender<DataEvent>,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        let (tx, _) = broadcast::channel(EVENT_BUFFER);
+        Self { tx }
+    }
+
+    pub fn publish(&self, event: DataEvent) {
+        trace!(event = ?event, "Publishing data event");
+        let _ = self.tx.send(event);
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<DataEvent> {
+        self.tx.subscribe()
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,13 +1,27 @@
-use std::sync::Arc;
-use std::time::Duration;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
 use std::collections::HashMap;
+use std::path::PathBuf;
 use reqwest::{Client, Response, StatusCode};
-use serde::de::DeserializeOwned;
+use arc_swap::ArcSwap;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use tracing::{info, debug, warn, error};
 
-use crate::global::config::AppConfig;
+use crate::global::alert::{Alert, AlertKind, AlertManager, AlertSeverity};
+use crate::global::archive::ResponseArchive;
+use crate::global::budget::RequestBudget;
+use crate::global::config::{self, AppConfig, HttpMode};
 use crate::global::module::RateLimiter;
 use crate::global::error::HttpError;
+use crate::global::lenient;
+use crate::global::queue;
+
+/// Public API URLs of the providers, overridable with `child_modules.<name>.base_url`
+pub const MY_ANIME_LIST_BASE_URL: &str = "https://api.myanimelist.net/v2";
+pub const JIKAN_BASE_URL: &str = "https://api.jikan.moe/v4";
+pub const ANILIST_BASE_URL: &str = "https://graphql.anilist.co";
 
 /// Manages HTTP clients with rate limiting for different APIs
 #[derive(Clone)]
@@ -28,6 +42,251 @@ pub struct ClientWithLimiter {
     pub client: Client,
     pub limiter: RateLimiter,
     pub name: String,
+    /// API URL without trailing slash, request URLs are built from it
+    pub base_url: String,
+    /// Retry settings used when a request doesn't provide its own
+    retry: Arc<ArcSwap<RetryConfig>>,
+    /// Live, record or replay, read at startup
+    mode: HttpMode,
+    /// Recorded responses of this client, in `<http.fixtures_dir>/<client name>`
+    fixtures_dir: PathBuf,
+    /// Set once the database is connected when `[http.archive]` is enabled, shared by all clients
+    archive: Arc<OnceLock<ResponseArchive>>,
+    /// Requests sent by `fetch_json` since startup
+    counters: Arc<RequestCounters>,
+    /// Requests sent today against `child_modules.<name>.daily_budget`
+    budget: Arc<RequestBudget>,
+    /// Effective rate of the limiter, lowered after rate limit responses
+    adaptive: Arc<AdaptiveRate>,
+    /// Stops requests to a failing provider, `None` for the default client whose hosts are unrelated
+    circuit: Option<Arc<CircuitBreaker>>,
+    /// Set once alerting is started, raises an alert when the circuit opens, shared by all clients
+    alerts: Arc<OnceLock<AlertManager>>,
+}
+
+/// Smallest share of the configured rate the adaptive rate goes down to
+const MIN_RATE_FACTOR: f64 = 0.1;
+
+/// Share of the current rate kept after a rate limit response
+const RATE_BACKOFF_FACTOR: f64 = 0.5;
+
+/// Increase of the rate after `RATE_RECOVERY_RESPONSES` clean responses in a row
+const RATE_RECOVERY_FACTOR: f64 = 1.25;
+const RATE_RECOVERY_RESPONSES: u32 = 20;
+
+/// Feedback controller of a client's rate (`http.adaptive_rate_limit`): the rate is halved
+/// on each 429/403 and raised again by steps once responses are clean, up to the configured rate
+#[derive(Debug)]
+struct AdaptiveRate {
+    enabled: AtomicBool,
+    state: Mutex<AdaptiveState>,
+}
+
+#[derive(Debug)]
+struct AdaptiveState {
+    /// `rate_limit` of the configuration, in requests per second
+    configured: f64,
+    /// Share of the configured rate currently applied
+    factor: f64,
+    /// Clean responses since the last change
+    clean: u32,
+}
+
+impl AdaptiveRate {
+    fn new(configured: f64, enabled: bool) -> Self {
+        Self {
+            enabled: AtomicBool::new(enabled),
+            state: Mutex::new(AdaptiveState { configured, factor: 1.0, clean: 0 }),
+        }
+    }
+
+    /// Apply a new configured rate, keeping the current reduction while enabled
+    fn configure(&self, limiter: &RateLimiter, configured: f64, enabled: bool) {
+        self.enabled.store(enabled, Ordering::Relaxed);
+        let Ok(mut state) = self.state.lock() else { return };
+        state.configured = configured;
+        if !enabled {
+            state.factor = 1.0;
+            state.clean = 0;
+        }
+        limiter.set_rate(state.configured * state.factor);
+    }
+
+    /// Adjust the rate of `limiter` after a response, `None` when none was received
+    fn observe(&self, limiter: &RateLimiter, client: &str, status: Option<StatusCode>) {
+        if !self.enabled.load(Ordering::Relaxed) {
+            return;
+        }
+        let Ok(mut state) = self.state.lock() else { return };
+
+        let factor = match status {
+            Some(StatusCode::TOO_MANY_REQUESTS | StatusCode::FORBIDDEN) => {
+                state.clean = 0;
+                (state.factor * RATE_BACKOFF_FACTOR).max(MIN_RATE_FACTOR)
+            }
+            Some(status) if status.is_success() || status == StatusCode::NOT_MODIFIED || status == StatusCode::NOT_FOUND => {
+                if state.factor >= 1.0 {
+                    return;
+                }
+                state.clean += 1;
+                if state.clean < RATE_RECOVERY_RESPONSES {
+                    return;
+                }
+                state.clean = 0;
+                (state.factor * RATE_RECOVERY_FACTOR).min(1.0)
+            }
+            // Transport errors and server errors say nothing about the rate
+            _ => return,
+        };
+        if factor == state.factor {
+            return;
+        }
+
+        if factor < state.factor {
+            warn!(client = %client, rate = state.configured * factor, configured = state.configured, "Rate limited, lowering request rate");
+        } else {
+            debug!(client = %client, rate = state.configured * factor, configured = state.configured, "Clean responses, raising request rate");
+        }
+        state.factor = factor;
+        limiter.set_rate(state.configured * factor);
+    }
+}
+
+/// Failed requests in a row (transport errors and server errors) opening a circuit
+const CIRCUIT_FAILURE_THRESHOLD: u32 = 5;
+
+/// Time requests fail fast once a circuit is open
+const CIRCUIT_OPEN_DURATION: Duration = Duration::from_secs(60);
+
+/// Circuit breaker of a provider client: after `CIRCUIT_FAILURE_THRESHOLD` failed requests
+/// in a row, requests fail fast for `CIRCUIT_OPEN_DURATION`. Requests are then sent again,
+/// the first one failing opens the circuit again and the first one succeeding closes it.
+#[derive(Debug, Default)]
+struct CircuitBreaker {
+    state: Mutex<CircuitState>,
+}
+
+#[derive(Debug, Default)]
+struct CircuitState {
+    /// Failed requests since the last successful one
+    failures: u32,
+    open_until: Option<Instant>,
+}
+
+impl CircuitBreaker {
+    /// Remaining time the circuit stays open, `None` when requests can be sent
+    fn open_for(&self) -> Option<Duration> {
+        let state = self.state.lock().ok()?;
+        let remaining = state.open_until?.checked_duration_since(Instant::now())?;
+        (!remaining.is_zero()).then_some(remaining)
+    }
+
+    /// Count a response, `None` when none was received. Returns true when the circuit opens.
+    fn observe(&self, status: Option<StatusCode>) -> bool {
+        let Ok(mut state) = self.state.lock() else { return false };
+
+        if status.is_some_and(|status| !status.is_server_error()) {
+            state.failures = 0;
+            state.open_until = None;
+            return false;
+        }
+
+        state.failures += 1;
+        let now = Instant::now();
+        // Requests sent before the circuit opened can still fail, it is already open
+        if state.failures < CIRCUIT_FAILURE_THRESHOLD || state.open_until.is_some_and(|until| until > now) {
+            return false;
+        }
+        state.open_until = Some(now + CIRCUIT_OPEN_DURATION);
+        true
+    }
+}
+
+/// Requests sent by a client, by outcome
+#[derive(Debug, Default)]
+struct RequestCounters {
+    sent: AtomicU64,
+    succeeded: AtomicU64,
+    not_modified: AtomicU64,
+    not_found: AtomicU64,
+    rate_limited: AtomicU64,
+    failed: AtomicU64,
+}
+
+impl RequestCounters {
+    /// Count a request, `None` when no response was received
+    fn count(&self, status: Option<StatusCode>) {
+        self.sent.fetch_add(1, Ordering::Relaxed);
+        let outcome = match status {
+            Some(StatusCode::OK) => &self.succeeded,
+            Some(StatusCode::NOT_MODIFIED) => &self.not_modified,
+            Some(StatusCode::NOT_FOUND) => &self.not_found,
+            Some(StatusCode::TOO_MANY_REQUESTS | StatusCode::FORBIDDEN) => &self.rate_limited,
+            _ => &self.failed,
+        };
+        outcome.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Snapshot of the requests sent by a client since startup, each retry counts as a request
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct RequestCounts {
+    pub sent: u64,
+    pub succeeded: u64,
+    pub not_modified: u64,
+    pub not_found: u64,
+    pub rate_limited: u64,
+    /// Transport errors and unexpected statuses
+    pub failed: u64,
+}
+
+/// Provider response saved by the record mode and served by the replay mode
+#[derive(Serialize, Deserialize)]
+struct Fixture {
+    #[serde(default = "default_fixture_method")]
+    method: String,
+    url: String,
+    status: u16,
+    body: String,
+}
+
+fn default_fixture_method() -> String {
+    "GET".to_string()
+}
+
+/// Validators of a response, sent back with a conditional request
+/// so the provider only returns the body when it changed
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Validators {
+    pub etag: Option<String>,
+    pub last_modified: Option<String>,
+}
+
+impl Validators {
+    fn from_response(response: &Response) -> Self {
+        let header = |name| {
+            response.headers()
+                .get(name)
+                .and_then(|value: &reqwest::header::HeaderValue| value.to_str().ok())
+                .map(str::to_string)
+        };
+        Self {
+            etag: header(reqwest::header::ETAG),
+            last_modified: header(reqwest::header::LAST_MODIFIED),
+        }
+    }
+
+    /// Whether the provider sent nothing to make a conditional request with
+    pub fn is_empty(&self) -> bool {
+        self.etag.is_none() && self.last_modified.is_none()
+    }
+}
+
+/// Result of a conditional request
+pub enum Conditional<T> {
+    /// The response changed, with its new validators
+    Modified(T, Validators),
+    NotModified,
 }
 
 /// Configuration for retry behavior
@@ -38,6 +297,16 @@ pub struct RetryConfig {
     pub max_delay: Duration,
 }
 
+impl From<&config::RetryConfig> for RetryConfig {
+    fn from(config: &config::RetryConfig) -> Self {
+        Self {
+            max_retries: config.max_retries,
+            base_delay: Duration::from_millis(config.base_delay_ms),
+            max_delay: Duration::from_millis(config.max_delay_ms),
+        }
+    }
+}
+
 impl Default for RetryConfig {
     fn default() -> Self {
         Self {
@@ -96,7 +365,7 @@ impl HttpClientManager {
         // Create default client
         let default_client = Client::builder()
             .timeout(Duration::from_secs(config.http.timeout_seconds))
-            .user_agent(&config.http.user_agent)
+            .user_agent(config.get_user_agent("default"))
             .build()
             .expect("Failed to create default HTTP client");
 
@@ -104,7 +373,7 @@ impl HttpClientManager {
         let mal_rate_limit = config.get_rate_limit("my_anime_list");
         let mal_client = Client::builder()
             .timeout(Duration::from_secs(config.http.timeout_seconds))
-            .user_agent(&config.http.user_agent)
+            .user_agent(config.get_user_agent("my_anime_list"))
             .build()
             .expect("Failed to create MyAnimeList HTTP client");
 
@@ -112,7 +381,7 @@ impl HttpClientManager {
         let jikan_rate_limit = config.get_rate_limit("jikan");
         let jikan_client = Client::builder()
             .timeout(Duration::from_secs(config.http.timeout_seconds))
-            .user_agent(&config.http.user_agent)
+            .user_agent(config.get_user_agent("jikan"))
             .build()
             .expect("Failed to create Jikan HTTP client");
 
@@ -120,37 +389,111 @@ impl HttpClientManager {
         let anilist_rate_limit = config.get_rate_limit("anilist");
         let anilist_client = Client::builder()
             .timeout(Duration::from_secs(config.http.timeout_seconds))
-            .user_agent(&config.http.user_agent)
+            .user_agent(config.get_user_agent("anilist"))
             .build()
             .expect("Failed to create AniList HTTP client");
 
+        let retry = Arc::new(ArcSwap::from_pointee(RetryConfig::from(&config.http.retry)));
+        let fixtures_dir = PathBuf::from(&config.http.fixtures_dir);
+        let archive = Arc::new(OnceLock::new());
+        let alerts = Arc::new(OnceLock::new());
+        if config.http.mode != HttpMode::Live {
+            info!(mode = ?config.http.mode, directory = %config.http.fixtures_dir, "Provider responses are recorded or replayed");
+        }
+
         Self {
             clients: Arc::new(ClientPool {
                 default: ClientWithLimiter {
                     client: default_client.clone(),
                     limiter: RateLimiter::new("default", config.http.default_rate_limit),
                     name: "default".to_string(),
+                    base_url: String::new(),
+                    retry: retry.clone(),
+                    mode: config.http.mode,
+                    fixtures_dir: fixtures_dir.join("default"),
+                    // Only provider responses are archived
+                    archive: Arc::new(OnceLock::new()),
+                    counters: Arc::default(),
+                    budget: Arc::new(RequestBudget::new("default", None)),
+                    adaptive: Arc::new(AdaptiveRate::new(config.http.default_rate_limit, config.http.adaptive_rate_limit)),
+                    circuit: None,
+                    alerts: alerts.clone(),
                 },
                 my_anime_list: ClientWithLimiter {
                     client: mal_client,
                     limiter: RateLimiter::new("my_anime_list", mal_rate_limit),
                     name: "my_anime_list".to_string(),
+                    base_url: config.get_base_url("my_anime_list", MY_ANIME_LIST_BASE_URL),
+                    retry: retry.clone(),
+                    mode: config.http.mode,
+                    fixtures_dir: fixtures_dir.join("my_anime_list"),
+                    archive: archive.clone(),
+                    counters: Arc::default(),
+                    budget: Arc::new(RequestBudget::new("my_anime_list", config.get_daily_budget("my_anime_list"))),
+                    adaptive: Arc::new(AdaptiveRate::new(mal_rate_limit, config.http.adaptive_rate_limit)),
+                    circuit: Some(Arc::default()),
+                    alerts: alerts.clone(),
                 },
                 jikan: ClientWithLimiter {
                     client: jikan_client,
                     limiter: RateLimiter::new("jikan", jikan_rate_limit),
                     name: "jikan".to_string(),
+                    base_url: config.get_base_url("jikan", JIKAN_BASE_URL),
+                    retry: retry.clone(),
+                    mode: config.http.mode,
+                    fixtures_dir: fixtures_dir.join("jikan"),
+                    archive: archive.clone(),
+                    counters: Arc::default(),
+                    budget: Arc::new(RequestBudget::new("jikan", config.get_daily_budget("jikan"))),
+                    adaptive: Arc::new(AdaptiveRate::new(jikan_rate_limit, config.http.adaptive_rate_limit)),
+                    circuit: Some(Arc::default()),
+                    alerts: alerts.clone(),
                 },
                 anilist: ClientWithLimiter {
                     client: anilist_client,
                     limiter: RateLimiter::new("anilist", anilist_rate_limit),
                     name: "anilist".to_string(),
+                    base_url: config.get_base_url("anilist", ANILIST_BASE_URL),
+                    retry: retry.clone(),
+                    mode: config.http.mode,
+                    fixtures_dir: fixtures_dir.join("anilist"),
+                    archive: archive.clone(),
+                    counters: Arc::default(),
+                    budget: Arc::new(RequestBudget::new("anilist", config.get_daily_budget("anilist"))),
+                    adaptive: Arc::new(AdaptiveRate::new(anilist_rate_limit, config.http.adaptive_rate_limit)),
+                    circuit: Some(Arc::default()),
+                    alerts: alerts.clone(),
                 },
             }),
             config,
         }
     }
 
+    /// Archive the provider responses in the database when `[http.archive]` is enabled
+    pub fn with_archive(self, db: mongodb::Database) -> Self {
+        if self.config.http.archive.enabled {
+            info!(retention_days = self.config.http.archive.retention_days, "Provider responses are archived in raw_responses");
+            // The provider clients share the same archive
+            let _ = self.clients.jikan.archive.set(ResponseArchive::new(db));
+        }
+        self
+    }
+
+    /// Raise an alert when the circuit of a provider client opens
+    pub fn with_alerts(self, alerts: AlertManager) -> Self {
+        // The clients share the same alert manager
+        let _ = self.clients.default.alerts.set(alerts);
+        self
+    }
+
+    /// Count the provider requests of each day in the database, continuing today's counts
+    pub async fn with_request_budgets(self, db: mongodb::Database) -> Self {
+        for client in [&self.clients.my_anime_list, &self.clients.jikan, &self.clients.anilist] {
+            client.budget.attach(db.clone()).await;
+        }
+        self
+    }
+
     /// Get the default HTTP client with rate limiter
     pub fn default(&self) -> &ClientWithLimiter {
         &self.clients.default
@@ -170,6 +513,23 @@ impl HttpClientManager {
     pub fn anilist(&self) -> &ClientWithLimiter {
         &self.clients.anilist
     }
+
+    /// Apply hot-reloadable settings (rate limits and retry policy) from a new configuration
+    pub fn apply_config(&self, config: &AppConfig) {
+        let clients = &self.clients;
+
+        let adaptive = config.http.adaptive_rate_limit;
+        clients.default.adaptive.configure(&clients.default.limiter, config.http.default_rate_limit, adaptive);
+        for client in [&clients.my_anime_list, &clients.jikan, &clients.anilist] {
+            client.adaptive.configure(&client.limiter, config.get_rate_limit(&client.name), adaptive);
+            client.budget.set_limit(config.get_daily_budget(&client.name));
+        }
+
+        // All clients share the same retry settings
+        clients.default.retry.store(Arc::new(RetryConfig::from(&config.http.retry)));
+
+        debug!("Applied reloaded HTTP configuration");
+    }
 }
 
 impl ClientWithLimiter {
@@ -216,12 +576,53 @@ impl ClientWithLimiter {
         url: &str,
         config: Option<RequestConfig>,
     ) -> Result<T, HttpError> {
+        if self.mode == HttpMode::Replay {
+            return self.replay(url).await;
+        }
+
+        match self.fetch_body(url, config, None).await? {
+            Some((body, _)) => self.deserialize_body(&body),
+            None => Err(HttpError::UnexpectedStatus {
+                status: StatusCode::NOT_MODIFIED.as_u16(),
+                message: "Not modified".to_string(),
+            }),
+        }
+    }
+
+    /// Fetch and deserialize JSON unless it is unchanged since the response `validators`
+    /// were returned with, as told by a 304 Not Modified
+    pub async fn fetch_json_if_modified<T: DeserializeOwned>(
+        &self,
+        url: &str,
+        config: Option<RequestConfig>,
+        validators: &Validators,
+    ) -> Result<Conditional<T>, HttpError> {
+        if self.mode == HttpMode::Replay {
+            return self.replay(url).await.map(|value| Conditional::Modified(value, Validators::default()));
+        }
+
+        match self.fetch_body(url, config, Some(validators)).await? {
+            Some((body, validators)) => Ok(Conditional::Modified(self.deserialize_body(&body)?, validators)),
+            None => Ok(Conditional::NotModified),
+        }
+    }
+
+    /// Send a GET request with retries on rate limits, returns the body of a 200 response
+    /// with its validators, or None for a 304 response to a conditional request
+    async fn fetch_body(
+        &self,
+        url: &str,
+        config: Option<RequestConfig>,
+        validators: Option<&Validators>,
+    ) -> Result<Option<(String, Validators)>, HttpError> {
         let config = config.unwrap_or_default();
-        let retry_config = config.retry_config.unwrap_or_default();
+        let retry_config = config.retry_config
+            .unwrap_or_else(|| self.retry.load().as_ref().clone());
         let mut attempt = 0;
 
         loop {
             attempt += 1;
+            self.check_circuit()?;
             
             // Acquire rate limit permission
             self.limiter.acquire().await;
@@ -241,30 +642,53 @@ impl ClientWithLimiter {
             for (key, value) in &config.headers {
                 request = request.header(key, value);
             }
+            if let Some(validators) = validators {
+                if let Some(etag) = &validators.etag {
+                    request = request.header(reqwest::header::IF_NONE_MATCH, etag);
+                }
+                if let Some(last_modified) = &validators.last_modified {
+                    request = request.header(reqwest::header::IF_MODIFIED_SINCE, last_modified);
+                }
+            }
 
             // Make the request
             let response = match request.send().await {
                 Ok(resp) => resp,
                 Err(e) => {
+                    self.track_request(None).await;
                     error!(client = %self.name, url = %url, error = %e, "HTTP request failed");
                     return Err(HttpError::RequestFailed(e));
                 }
             };
 
             let status = response.status();
+            self.track_request(Some(status)).await;
             
             // Handle different status codes
             match status {
                 StatusCode::OK => {
                     // Success - deserialize and return
                     debug!(client = %self.name, url = %url, status = %status, "Request successful");
-                    return self.deserialize_response(response).await;
+                    let validators = Validators::from_response(&response);
+                    let body = response.text().await.map_err(|e| {
+                        error!(client = %self.name, error = %e, "Failed to read response body");
+                        HttpError::DeserializationFailed(e.to_string())
+                    })?;
+                    self.record("GET", url, None, status, &body).await;
+                    self.archive(url, None, &body).await;
+                    return Ok(Some((body, validators)));
+                }
+
+                StatusCode::NOT_MODIFIED if validators.is_some() => {
+                    debug!(client = %self.name, url = %url, "Resource not modified (304)");
+                    return Ok(None);
                 }
                 
                 StatusCode::NOT_FOUND => {
                     // 404 - resource doesn't exist
                     let error_body = response.text().await
                         .unwrap_or_else(|_| "No error message".to_string());
+                    self.record("GET", url, None, status, &error_body).await;
                     
                     warn!(client = %self.name, url = %url, "Resource not found (404)");
                     return Err(HttpError::NotFound(error_body));
@@ -328,24 +752,196 @@ impl ClientWithLimiter {
         }
     }
 
-    /// Deserialize the response body to type T
-    async fn deserialize_response<T: DeserializeOwned>(&self, response: Response) -> Result<T, HttpError> {
-        let body = response.text().await.map_err(|e| {
+    /// Send a JSON POST request (e.g. a GraphQL query) and return the response body whatever
+    /// its status, GraphQL errors come with error statuses. Recorded and replayed like
+    /// `fetch_json`, the request body being part of the fixture key.
+    pub async fn post_json<B: Serialize>(&self, url: &str, body: &B) -> Result<String, HttpError> {
+        let body = serde_json::to_vec(body)
+            .map_err(|e| HttpError::DeserializationFailed(format!("failed to serialize request: {}", e)))?;
+
+        if self.mode == HttpMode::Replay {
+            return self.load_fixture("POST", url, Some(&body)).await.map(|fixture| fixture.body);
+        }
+
+        self.check_circuit()?;
+        self.limiter.acquire().await;
+        debug!(client = %self.name, url = %url, "Making HTTP POST request");
+
+        let response = self.client
+            .post(url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .header(reqwest::header::ACCEPT, "application/json")
+            .body(body.clone())
+            .send()
+            .await;
+        self.track_request(response.as_ref().ok().map(|response| response.status())).await;
+        let response = response.map_err(|e| {
+            error!(client = %self.name, url = %url, error = %e, "HTTP request failed");
+            HttpError::RequestFailed(e)
+        })?;
+
+        let status = response.status();
+        let text = response.text().await.map_err(|e| {
             error!(client = %self.name, error = %e, "Failed to read response body");
             HttpError::DeserializationFailed(e.to_string())
         })?;
+        self.record("POST", url, Some(&body), status, &text).await;
+        Ok(text)
+    }
+
+    /// Count a request sent without `fetch_json`, `None` when no response was received
+    pub async fn track_request(&self, status: Option<StatusCode>) {
+        self.counters.count(status);
+        self.adaptive.observe(&self.limiter, &self.name, status);
+        self.budget.record();
+        if self.circuit.as_ref().is_some_and(|circuit| circuit.observe(status)) {
+            self.circuit_opened();
+        }
+    }
+
+    /// Fail fast while the circuit of this client is open, call before sending a request
+    /// without `fetch_json`
+    pub fn check_circuit(&self) -> Result<(), HttpError> {
+        match self.circuit.as_ref().and_then(|circuit| circuit.open_for()) {
+            Some(retry_after) => Err(HttpError::CircuitOpen { client: self.name.clone(), retry_after }),
+            None => Ok(()),
+        }
+    }
 
+    fn circuit_opened(&self) {
+        warn!(
+            client = %self.name,
+            failures = CIRCUIT_FAILURE_THRESHOLD,
+            open_for = ?CIRCUIT_OPEN_DURATION,
+            "Provider failing, circuit open"
+        );
+
+        // Alerts are deduplicated by title, so per client
+        let Some(alerts) = self.alerts.get().cloned() else { return };
+        let alert = Alert::new(
+            AlertKind::CircuitOpen,
+            AlertSeverity::Warning,
+            format!("Circuit open for {}", self.name),
+            format!(
+                "{} requests in a row failed, requests to {} are stopped for {}s at a time until one succeeds",
+                CIRCUIT_FAILURE_THRESHOLD,
+                self.name,
+                CIRCUIT_OPEN_DURATION.as_secs()
+            ),
+        );
+        tokio::spawn(async move { alerts.notify(alert).await });
+    }
+
+    /// Daily request budget of this client
+    pub fn budget(&self) -> &RequestBudget {
+        &self.budget
+    }
+
+    /// Requests sent by this client since startup
+    pub fn request_counts(&self) -> RequestCounts {
+        let counters = &self.counters;
+        RequestCounts {
+            sent: counters.sent.load(Ordering::Relaxed),
+            succeeded: counters.succeeded.load(Ordering::Relaxed),
+            not_modified: counters.not_modified.load(Ordering::Relaxed),
+            not_found: counters.not_found.load(Ordering::Relaxed),
+            rate_limited: counters.rate_limited.load(Ordering::Relaxed),
+            failed: counters.failed.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Deserialize the response body to type T
+    fn deserialize_body<T: DeserializeOwned>(&self, body: &str) -> Result<T, HttpError> {
         debug!(body = %body, "debug response body");
 
-        serde_json::from_str::<T>(&body).map_err(|e| {
-            error!(
-                client = %self.name,
-                error = %e,
-                body = %body,
-                "Failed to deserialize JSON response"
-            );
-            HttpError::DeserializationFailed(e.to_string())
-        })
+        let error = match serde_json::from_str::<T>(body) {
+            Ok(value) => return Ok(value),
+            Err(e) => e,
+        };
+
+        // Providers occasionally send a malformed field, keep the rest of the payload
+        match lenient::from_str_lenient::<T>(body) {
+            Ok((value, warnings)) => {
+                for warning in warnings {
+                    queue::record_warning(format!("{}: dropped malformed field {}", self.name, warning));
+                }
+                Ok(value)
+            }
+            Err(e) => {
+                error!(
+                    client = %self.name,
+                    error = %error,
+                    body = %body,
+                    "Failed to deserialize JSON response"
+                );
+                Err(HttpError::DeserializationFailed(e.to_string()))
+            }
+        }
+    }
+
+    /// File of the recorded response of a request, keyed by its method, URL and body hash.
+    /// Request headers (API keys) are not part of the key.
+    fn fixture_path(&self, method: &str, url: &str, body: Option<&[u8]>) -> PathBuf {
+        let body_hash = body.map(|body| format!("{:x}", Sha256::digest(body))).unwrap_or_default();
+        let digest = format!("{:x}", Sha256::digest(format!("{} {}\n{}", method, url, body_hash).as_bytes()));
+        self.fixtures_dir.join(format!("{}.json", &digest[..32]))
+    }
+
+    /// Save a response in record mode, failures only lose the recording
+    async fn record(&self, method: &str, url: &str, request: Option<&[u8]>, status: StatusCode, body: &str) {
+        if self.mode != HttpMode::Record {
+            return;
+        }
+
+        let fixture = Fixture {
+            method: method.to_string(),
+            url: url.to_string(),
+            status: status.as_u16(),
+            body: body.to_string(),
+        };
+        let path = self.fixture_path(method, url, request);
+        let result = async {
+            tokio::fs::create_dir_all(&self.fixtures_dir).await?;
+            let content = serde_json::to_vec_pretty(&fixture)?;
+            tokio::fs::write(&path, content).await
+        }.await;
+
+        match result {
+            Ok(()) => debug!(client = %self.name, url = %url, path = ?path, "Recorded response"),
+            Err(e) => warn!(client = %self.name, url = %url, error = %e, "Failed to record response"),
+        }
+    }
+
+    /// Archive a successful response body when `[http.archive]` is enabled.
+    /// `request` identifies POST requests sharing the same URL
+    pub async fn archive(&self, url: &str, request: Option<&serde_json::Value>, body: &str) {
+        if let Some(archive) = self.archive.get() {
+            archive.store(&self.name, url, request, StatusCode::OK.as_u16(), body).await;
+        }
+    }
+
+    /// Serve a recorded GET response, without rate limiting
+    async fn replay<T: DeserializeOwned>(&self, url: &str) -> Result<T, HttpError> {
+        let fixture = self.load_fixture("GET", url, None).await?;
+        match fixture.status {
+            200 => self.deserialize_body(&fixture.body),
+            404 => Err(HttpError::NotFound(fixture.body)),
+            status => Err(HttpError::UnexpectedStatus { status, message: fixture.body }),
+        }
+    }
+
+    /// Recorded response of a request
+    async fn load_fixture(&self, method: &str, url: &str, body: Option<&[u8]>) -> Result<Fixture, HttpError> {
+        let path = self.fixture_path(method, url, body);
+        let content = tokio::fs::read(&path).await.map_err(|_| {
+            warn!(client = %self.name, method = %method, url = %url, path = ?path, "No recorded response to replay");
+            HttpError::FixtureMissing(format!("{} {}", method, url))
+        })?;
+        let fixture: Fixture = serde_json::from_slice(&content)
+            .map_err(|e| HttpError::DeserializationFailed(format!("invalid fixture {:?}: {}", path, e)))?;
+
+        debug!(client = %self.name, method = %method, url = %url, status = fixture.status, "Replaying recorded response");
+        Ok(fixture)
     }
 
     /// Parse the Retry-After header if present
@@ -359,4 +955,107 @@ impl ClientWithLimiter {
                 s.parse::<u64>().ok().map(Duration::from_secs)
             })
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use wiremock::matchers::{body_json, header, method, path};
+    use wiremock::{Mock, ResponseTemplate};
+
+    use super::*;
+    use crate::testing::{Providers, ANILIST_PATH, JIKAN_PATH, MY_ANIME_LIST_PATH};
+
+    #[tokio::test]
+    async fn fetch_json_sends_the_request_to_the_configured_base_url() {
+        let providers = Providers::start(HttpMode::Live).await;
+        Mock::given(method("GET"))
+            .and(path(format!("{}/anime/1", MY_ANIME_LIST_PATH)))
+            .and(header("X-MAL-CLIENT-ID", "key"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "id": 1 })))
+            .expect(1)
+            .mount(&providers.server)
+            .await;
+
+        let client = providers.http.my_anime_list();
+        let config = RequestConfig::new().with_header("X-MAL-CLIENT-ID", "key");
+        let body: serde_json::Value = client
+            .fetch_json(&format!("{}/anime/1", client.base_url), Some(config))
+            .await
+            .unwrap();
+
+        assert_eq!(body, json!({ "id": 1 }));
+        assert_eq!(client.request_counts().succeeded, 1);
+    }
+
+    #[tokio::test]
+    async fn fetch_json_maps_a_404_to_not_found() {
+        let providers = Providers::start(HttpMode::Live).await;
+        Mock::given(method("GET"))
+            .and(path(format!("{}/anime/1/full", JIKAN_PATH)))
+            .respond_with(ResponseTemplate::new(404).set_body_string("Resource does not exist"))
+            .mount(&providers.server)
+            .await;
+
+        let client = providers.http.jikan();
+        let result = client
+            .fetch_json::<serde_json::Value>(&format!("{}/anime/1/full", client.base_url), None)
+            .await;
+
+        assert!(matches!(result, Err(HttpError::NotFound(body)) if body == "Resource does not exist"));
+    }
+
+    #[tokio::test]
+    async fn recorded_responses_are_replayed_without_requests() {
+        let providers = Providers::start(HttpMode::Record).await;
+        let query = json!({ "query": "{ Media { id } }" });
+        Mock::given(method("GET"))
+            .and(path(format!("{}/anime/1/full", JIKAN_PATH)))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "data": { "mal_id": 1 } })))
+            .expect(1)
+            .mount(&providers.server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path(ANILIST_PATH))
+            .and(body_json(&query))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "data": { "Media": { "id": 1 } } })))
+            .expect(1)
+            .mount(&providers.server)
+            .await;
+
+        let jikan_url = format!("{}/anime/1/full", providers.http.jikan().base_url);
+        let anilist_url = providers.http.anilist().base_url.clone();
+        let recorded: serde_json::Value = providers.http.jikan().fetch_json(&jikan_url, None).await.unwrap();
+        let recorded_post = providers.http.anilist().post_json(&anilist_url, &query).await.unwrap();
+
+        // The mocks expect a single request each, replaying must not send any
+        let replay = providers.clients(HttpMode::Replay);
+        let replayed: serde_json::Value = replay.jikan().fetch_json(&jikan_url, None).await.unwrap();
+        let replayed_post = replay.anilist().post_json(&anilist_url, &query).await.unwrap();
+        assert_eq!(replayed, recorded);
+        assert_eq!(replayed_post, recorded_post);
+
+        // Another request body is another fixture
+        let other = replay.anilist().post_json(&anilist_url, &json!({ "query": "{ Page { id } }" })).await;
+        assert!(matches!(other, Err(HttpError::FixtureMissing(_))));
+    }
+
+    #[tokio::test]
+    async fn circuit_opens_after_repeated_server_errors() {
+        let providers = Providers::start(HttpMode::Live).await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(503))
+            .expect(u64::from(CIRCUIT_FAILURE_THRESHOLD))
+            .mount(&providers.server)
+            .await;
+
+        let client = providers.http.my_anime_list();
+        let url = format!("{}/anime/1", client.base_url);
+        for _ in 0..CIRCUIT_FAILURE_THRESHOLD {
+            let result = client.fetch_json::<serde_json::Value>(&url, None).await;
+            assert!(matches!(result, Err(HttpError::UnexpectedStatus { status: 503, .. })));
+        }
+
+        let result = client.fetch_json::<serde_json::Value>(&url, None).await;
+        assert!(matches!(result, Err(HttpError::CircuitOpen { ref client, .. }) if client == "my_anime_list"));
+    }
+}
@@ -0,0 +1,316 @@
+use mongodb::bson::doc;
+use mongodb::options::ReturnDocument;
+use serde::{Deserialize, Deserializer, Serialize};
+use tracing::{info, warn};
+
+use super::{database::DatabaseInstance, error::{AppError, DatabaseError}, events::{DataEvent, EventBus}};
+
+/// Failures kept per job, older ones are dropped
+const MAX_JOB_FAILURES: i32 = 100;
+
+/// Aggregate status of a job, derived from its task counters
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    /// No task started yet
+    Pending,
+    Running,
+    /// All tasks finished successfully
+    Completed,
+    /// All tasks finished, some of them failed
+    CompletedWithErrors,
+    /// All tasks finished and none succeeded
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JobFailure {
+    pub task_id: String,
+    pub task_name: String,
+    pub error: String,
+    pub failed_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// A group of related tasks (full collect, batch fetch, seasonal import...),
+/// persisted in the `jobs` collection. Counters are updated by the task queue.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Job {
+    pub job_id: String,
+    pub kind: String,
+    pub description: String,
+    pub total_tasks: u64,
+    #[serde(deserialize_with = "clamped_counter")]
+    pub running_tasks: u64,
+    pub completed_tasks: u64,
+    pub failed_tasks: u64,
+    /// Set once its creator queued every task, the job only finishes once sealed
+    #[serde(default = "default_sealed")]
+    pub sealed: bool,
+    #[serde(default)]
+    pub failures: Vec<JobFailure>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// A task finishing after its start failed to be recorded takes the counter below 0
+fn clamped_counter<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    let value = i64::deserialize(deserializer)?;
+    Ok(value.max(0) as u64)
+}
+
+/// Jobs stored before sealing existed were complete when created
+fn default_sealed() -> bool {
+    true
+}
+
+impl Job {
+    /// Tasks queued but not started yet
+    pub fn pending_tasks(&self) -> u64 {
+        self.total_tasks
+            .saturating_sub(self.running_tasks + self.completed_tasks + self.failed_tasks)
+    }
+
+    pub fn finished_tasks(&self) -> u64 {
+        self.completed_tasks + self.failed_tasks
+    }
+
+    pub fn status(&self) -> JobStatus {
+        if !self.sealed || self.finished_tasks() < self.total_tasks || self.total_tasks == 0 {
+            if self.running_tasks == 0 && self.finished_tasks() == 0 {
+                JobStatus::Pending
+            } else {
+                JobStatus::Running
+            }
+        } else if self.failed_tasks == 0 {
+            JobStatus::Completed
+        } else if self.completed_tasks == 0 {
+            JobStatus::Failed
+        } else {
+            JobStatus::CompletedWithErrors
+        }
+    }
+
+    /// Share of finished tasks, between 0 and 1
+    pub fn progress(&self) -> f64 {
+        if self.total_tasks == 0 {
+            return 0.0;
+        }
+        self.finished_tasks() as f64 / self.total_tasks as f64
+    }
+}
+
+/// Task lifecycle events counted into a job
+pub enum JobTaskEvent<'a> {
+    Queued,
+    /// Counted as queued but its queue refused it
+    Unqueued,
+    Started,
+    /// Stopped by a shutdown before finishing, the task waits to run again
+    Interrupted,
+    Completed,
+    Failed {
+        task_id: &'a str,
+        task_name: &'a str,
+        error: &'a str,
+    },
+    /// Left pending by a previous run and never started, counted as failed
+    Abandoned {
+        task_id: &'a str,
+        task_name: &'a str,
+        error: &'a str,
+    },
+}
+
+/// Create and persist a new job, its id is prefixed with the kind
+pub async fn create_job(db: &DatabaseInstance, kind: &str, description: impl Into<String>) -> Result<Job, AppError> {
+    let now = chrono::Utc::now();
+    let job = Job {
+        job_id: format!("{}_{}", kind, uuid::Uuid::new_v4()),
+        kind: kind.to_string(),
+        description: description.into(),
+        total_tasks: 0,
+        running_tasks: 0,
+        completed_tasks: 0,
+        failed_tasks: 0,
+        sealed: false,
+        failures: Vec::new(),
+        created_at: now,
+        updated_at: now,
+    };
+
+    db.collection::<Job>("jobs")
+        .insert_one(&job)
+        .await
+        .map_err(|e| AppError::Database(DatabaseError::Query(format!("Failed to create job: {}", e))))?;
+
+    Ok(job)
+}
+
+/// Update the counters of a job for a task event.
+/// Returns the job when this event finished its last task.
+pub async fn record_task_event(db: &DatabaseInstance, job_id: &str, event: JobTaskEvent<'_>) -> Result<Option<Job>, AppError> {
+    let now = chrono::Utc::now().to_rfc3339();
+    let finishes_task = matches!(event, JobTaskEvent::Completed | JobTaskEvent::Failed { .. } | JobTaskEvent::Abandoned { .. });
+
+    let update = match event {
+        JobTaskEvent::Queued => doc! {
+            "$inc": { "total_tasks": 1_i64 },
+            "$set": { "updated_at": now },
+        },
+        JobTaskEvent::Unqueued => doc! {
+            "$inc": { "total_tasks": -1_i64 },
+            "$set": { "updated_at": now },
+        },
+        JobTaskEvent::Started => doc! {
+            "$inc": { "running_tasks": 1_i64 },
+            "$set": { "updated_at": now },
+        },
+        JobTaskEvent::Interrupted => doc! {
+            "$inc": { "running_tasks": -1_i64 },
+            "$set": { "updated_at": now },
+        },
+        JobTaskEvent::Completed => doc! {
+            "$inc": { "running_tasks": -1_i64, "completed_tasks": 1_i64 },
+            "$set": { "updated_at": now },
+        },
+        JobTaskEvent::Failed { task_id, task_name, error } => doc! {
+            "$inc": { "running_tasks": -1_i64, "failed_tasks": 1_i64 },
+            "$set": { "updated_at": &now },
+            "$push": { "failures": failure_push(task_id, task_name, error, &now) },
+        },
+        JobTaskEvent::Abandoned { task_id, task_name, error } => doc! {
+            "$inc": { "failed_tasks": 1_i64 },
+            "$set": { "updated_at": &now },
+            "$push": { "failures": failure_push(task_id, task_name, error, &now) },
+        },
+    };
+
+    let job = db.collection::<Job>("jobs")
+        .find_one_and_update(doc! { "job_id": job_id }, update)
+        .return_document(ReturnDocument::After)
+        .await
+        .map_err(|e| AppError::Database(DatabaseError::Query(format!("Failed to update job: {}", e))))?;
+
+    let Some(job) = job else {
+        warn!(job_id = %job_id, "Task belongs to an unknown job");
+        return Ok(None);
+    };
+
+    // Counters are updated atomically, only the last finishing task of a sealed job sees this state
+    let finished = finishes_task && job.sealed && job.finished_tasks() >= job.total_tasks;
+    Ok(finished.then_some(job))
+}
+
+/// `$push` modifier adding one failure to a job
+fn failure_push(task_id: &str, task_name: &str, error: &str, failed_at: &str) -> mongodb::bson::Document {
+    doc! {
+        "$each": [{
+            "task_id": task_id,
+            "task_name": task_name,
+            "error": error,
+            "failed_at": failed_at,
+        }],
+        "$slice": -MAX_JOB_FAILURES,
+    }
+}
+
+/// Mark a job as fully queued. When its tasks already all finished, the job finishes here
+/// and `JobFinished` is published.
+pub async fn seal_job(db: &DatabaseInstance, job_id: &str, events: Option<&EventBus>) -> Result<(), AppError> {
+    let job = db.collection::<Job>("jobs")
+        .find_one_and_update(
+            doc! { "job_id": job_id, "sealed": { "$ne": true } },
+            doc! { "$set": { "sealed": true, "updated_at": chrono::Utc::now().to_rfc3339() } },
+        )
+        .return_document(ReturnDocument::After)
+        .await
+        .map_err(|e| AppError::Database(DatabaseError::Query(format!("Failed to seal job: {}", e))))?;
+
+    // Unknown or already sealed
+    let Some(job) = job else { return Ok(()) };
+
+    if job.total_tasks > 0 && job.finished_tasks() >= job.total_tasks {
+        info!(job_id = %job_id, status = ?job.status(), "Job finished");
+        if let Some(events) = events {
+            publish_finished(&job, events);
+        }
+    }
+    Ok(())
+}
+
+/// Let tasks join a sealed job again, e.g. to resume queueing it. It finishes once sealed again.
+/// Returns None for an unknown job.
+pub async fn reopen_job(db: &DatabaseInstance, job_id: &str) -> Result<Option<Job>, AppError> {
+    db.collection::<Job>("jobs")
+        .find_one_and_update(
+            doc! { "job_id": job_id },
+            doc! { "$set": { "sealed": false, "updated_at": chrono::Utc::now().to_rfc3339() } },
+        )
+        .return_document(ReturnDocument::After)
+        .await
+        .map_err(|e| AppError::Database(DatabaseError::Query(format!("Failed to reopen job: {}", e))))
+}
+
+/// Publish the `JobFinished` event of a finished job
+pub fn publish_finished(job: &Job, events: &EventBus) {
+    events.publish(DataEvent::JobFinished {
+        status: job.status(),
+        job_id: job.job_id.clone(),
+        kind: job.kind.clone(),
+        total_tasks: job.total_tasks,
+        failed_tasks: job.failed_tasks,
+    });
+}
+
+/// Add failures that didn't fail a whole task (e.g. single items of a batch) to a job,
+/// task counters are left unchanged
+pub async fn record_failures(db: &DatabaseInstance, job_id: &str, failures: Vec<JobFailure>) -> Result<(), AppError> {
+    if failures.is_empty() {
+        return Ok(());
+    }
+
+    let failures = mongodb::bson::to_bson(&failures)
+        .map_err(|e| AppError::Database(DatabaseError::Query(format!("Failed to serialize job failures: {}", e))))?;
+
+    db.collection::<Job>("jobs")
+        .update_one(
+            doc! { "job_id": job_id },
+            doc! {
+                "$set": { "updated_at": chrono::Utc::now().to_rfc3339() },
+                "$push": { "failures": { "$each": failures, "$slice": -MAX_JOB_FAILURES } },
+            },
+        )
+        .await
+        .map_err(|e| AppError::Database(DatabaseError::Query(format!("Failed to update job: {}", e))))?;
+
+    Ok(())
+}
+
+pub async fn get_job(db: &DatabaseInstance, job_id: &str) -> Result<Option<Job>, AppError> {
+    db.collection::<Job>("jobs")
+        .find_one(doc! { "job_id": job_id })
+        .await
+        .map_err(|e| AppError::Database(DatabaseError::Query(format!("Failed to get job: {}", e))))
+}
+
+/// Most recent jobs first
+pub async fn list_jobs(db: &DatabaseInstance, limit: i64) -> Result<Vec<Job>, AppError> {
+    use futures::stream::StreamExt;
+
+    let mut cursor = db.collection::<Job>("jobs")
+        .find(doc! {})
+        .sort(doc! { "created_at": -1 })
+        .limit(limit)
+        .await
+        .map_err(|e| AppError::Database(DatabaseError::Query(format!("Failed to list jobs: {}", e))))?;
+
+    let mut jobs = Vec::new();
+    while let Some(result) = cursor.next().await {
+        match result {
+            Ok(job) => jobs.push(job),
+            Err(e) => warn!(error = %e, "Failed to deserialize job"),
+        }
+    }
+
+    Ok(jobs)
+}
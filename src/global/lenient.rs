@@ -0,0 +1,66 @@
+use serde::de::{DeserializeOwned, Error as _};
+use serde_json::Value;
+use serde_path_to_error::{Path, Segment};
+
+/// Fields dropped from a payload before giving up on it
+const MAX_DROPPED_FIELDS: usize = 32;
+
+/// Deserialize `body`, dropping the fields that don't fit the model instead of failing
+/// the whole payload. A dropped field falls back to its serde default and a dropped list
+/// entry is skipped, so only a missing or malformed required field fails the parse.
+///
+/// Returns the value with a "path: error" warning for each dropped field.
+pub fn from_str_lenient<T: DeserializeOwned>(body: &str) -> Result<(T, Vec<String>), serde_json::Error> {
+    let mut value: Value = serde_json::from_str(body)?;
+    let mut warnings = Vec::new();
+    let mut first_error: Option<String> = None;
+
+    loop {
+        let error = match serde_path_to_error::deserialize::<_, T>(&value) {
+            Ok(parsed) => return Ok((parsed, warnings)),
+            Err(error) => error,
+        };
+        let warning = format!("{}: {}", error.path(), error.inner());
+
+        // A dropped object fails again as a missing field of its parent,
+        // report the error that started it rather than the last one
+        if warnings.len() >= MAX_DROPPED_FIELDS || !remove_path(&mut value, error.path()) {
+            return Err(serde_json::Error::custom(first_error.unwrap_or(warning)));
+        }
+        first_error.get_or_insert_with(|| warning.clone());
+        warnings.push(warning);
+    }
+}
+
+/// Remove the value at `path`, false when the path is the root or doesn't address a
+/// key or index of the payload
+fn remove_path(value: &mut Value, path: &Path) -> bool {
+    let segments: Vec<&Segment> = path.iter().collect();
+    let Some((last, parents)) = segments.split_last() else {
+        return false;
+    };
+
+    let mut current = value;
+    for segment in parents {
+        current = match (segment, current) {
+            (Segment::Map { key }, Value::Object(map)) => match map.get_mut(key) {
+                Some(child) => child,
+                None => return false,
+            },
+            (Segment::Seq { index }, Value::Array(items)) => match items.get_mut(*index) {
+                Some(child) => child,
+                None => return false,
+            },
+            _ => return false,
+        };
+    }
+
+    match (last, current) {
+        (Segment::Map { key }, Value::Object(map)) => map.remove(key).is_some(),
+        (Segment::Seq { index }, Value::Array(items)) if *index < items.len() => {
+            items.remove(*index);
+            true
+        }
+        _ => false,
+    }
+}
@@ -0,0 +1,250 @@
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, SystemTime};
+
+use flate2::Compression;
+use flate2::write::GzEncoder;
+use tracing::{debug, warn};
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::{layer::SubscriberExt, reload, util::SubscriberInitExt, EnvFilter, Registry};
+
+use crate::global::config::{AppConfig, LogRotation, LoggingConfig};
+
+/// Owns the logging resources for the lifetime of the application:
+/// the non-blocking writer guard and the reloadable log filter.
+/// Call `shutdown` before exiting so buffered log lines are written.
+pub struct LoggingHandle {
+    filter: reload::Handle<EnvFilter, Registry>,
+    guard: Mutex<Option<WorkerGuard>>,
+    /// Log level forced by RUST_LOG, config changes don't override it
+    env_override: bool,
+}
+
+impl LoggingHandle {
+    /// Setup logging based on configuration
+    pub fn init(config: &AppConfig) -> io::Result<Self> {
+        let log_level = &config.app.log_level;
+        let logging_config = &config.app.logging;
+
+        // Create the log directory if it doesn't exist
+        if logging_config.log_to_file {
+            fs::create_dir_all(&logging_config.log_directory)?;
+            println!("Logs will be written to: {}/", logging_config.log_directory);
+        }
+
+        // Build the env filter
+        let env_filter = EnvFilter::try_from_default_env().ok();
+        let env_override = env_filter.is_some();
+        let env_filter = env_filter.unwrap_or_else(|| log_filter(log_level));
+        let (filter_layer, filter) = reload::Layer::new(env_filter);
+
+        // Build the subscriber with layers
+        let registry = tracing_subscriber::registry().with(filter_layer);
+        let mut guard = None;
+
+        if logging_config.log_to_file {
+            // Determine rotation strategy
+            let rotation = match logging_config.log_rotation {
+                LogRotation::Daily => Rotation::DAILY,
+                LogRotation::Hourly => Rotation::HOURLY,
+                LogRotation::Never => Rotation::NEVER,
+            };
+
+            // Create file appender
+            let file_appender = RollingFileAppender::new(
+                rotation,
+                &logging_config.log_directory,
+                format!("{}.log", logging_config.log_file_prefix),
+            );
+
+            let (non_blocking, worker_guard) = tracing_appender::non_blocking(file_appender);
+            guard = Some(worker_guard);
+
+            // Create file layer
+            let file_layer = tracing_subscriber::fmt::layer()
+                .with_writer(non_blocking)
+                .with_ansi(false)
+                .with_target(true)
+                .with_thread_ids(true)
+                .with_line_number(true);
+
+            if logging_config.log_to_console {
+                // Both console and file
+                let console_layer = tracing_subscriber::fmt::layer()
+                    .with_writer(std::io::stdout)
+                    .with_target(false);
+
+                registry
+                    .with(file_layer)
+                    .with(console_layer)
+                    .init();
+            } else {
+                // File only
+                registry
+                    .with(file_layer)
+                    .init();
+            }
+        } else {
+            // Console only, also used when no output is configured
+            let console_layer = tracing_subscriber::fmt::layer()
+                .with_writer(std::io::stdout)
+                .with_target(false);
+
+            registry
+                .with(console_layer)
+                .init();
+        }
+
+        Ok(Self {
+            filter,
+            guard: Mutex::new(guard),
+            env_override,
+        })
+    }
+
+    /// Change the log level at runtime (ignored when RUST_LOG is set)
+    pub fn set_level(&self, log_level: &str) {
+        if self.env_override {
+            debug!("RUST_LOG is set, ignoring configured log level change");
+            return;
+        }
+
+        match self.filter.reload(log_filter(log_level)) {
+            Ok(()) => debug!(level = %log_level, "Log level updated"),
+            Err(e) => warn!(error = %e, "Failed to update log level"),
+        }
+    }
+
+    /// Flush buffered log lines to the log file.
+    /// Logging to file stops after this call, console output continues.
+    pub fn shutdown(&self) {
+        let guard = self.guard.lock().map(|mut g| g.take()).unwrap_or(None);
+        drop(guard);
+    }
+}
+
+/// Build the log filter for a configured level
+fn log_filter(log_level: &str) -> EnvFilter {
+    format!("media_collector={},info", log_level).into()
+}
+
+/// Outcome of a log retention pass
+#[derive(Debug, Default)]
+pub struct RetentionReport {
+    pub compressed: usize,
+    pub deleted: usize,
+}
+
+struct LogFile {
+    path: PathBuf,
+    modified: SystemTime,
+}
+
+/// Compress and prune rotated log files according to the logging configuration.
+/// The most recently written file is the one the appender is using and is never touched.
+pub fn apply_log_retention(config: &LoggingConfig) -> io::Result<RetentionReport> {
+    let mut report = RetentionReport::default();
+
+    if !config.log_to_file {
+        return Ok(report);
+    }
+
+    let mut files = list_log_files(Path::new(&config.log_directory), &config.log_file_prefix)?;
+    if files.is_empty() {
+        return Ok(report);
+    }
+
+    // Newest first, the first entry is the active file
+    files.sort_by_key(|f| std::cmp::Reverse(f.modified));
+    let mut rotated: Vec<LogFile> = files.into_iter().skip(1).collect();
+
+    if config.compress_rotated {
+        for file in rotated.iter_mut() {
+            if is_compressed(&file.path) {
+                continue;
+            }
+            match compress_file(&file.path) {
+                Ok(gz_path) => {
+                    debug!(file = %file.path.display(), "Compressed rotated log file");
+                    file.path = gz_path;
+                    report.compressed += 1;
+                }
+                Err(e) => warn!(file = %file.path.display(), error = %e, "Failed to compress log file"),
+            }
+        }
+    }
+
+    let max_age = config.max_age_days.map(|days| Duration::from_secs(days * 24 * 60 * 60));
+    let now = SystemTime::now();
+
+    for (index, file) in rotated.iter().enumerate() {
+        // The active file counts towards max_files
+        let over_count = config.max_files.is_some_and(|max| index + 1 >= max);
+        let too_old = max_age.is_some_and(|max_age| {
+            now.duration_since(file.modified).map(|age| age > max_age).unwrap_or(false)
+        });
+
+        if over_count || too_old {
+            match fs::remove_file(&file.path) {
+                Ok(()) => {
+                    debug!(file = %file.path.display(), "Deleted old log file");
+                    report.deleted += 1;
+                }
+                Err(e) => warn!(file = %file.path.display(), error = %e, "Failed to delete log file"),
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+fn list_log_files(directory: &Path, prefix: &str) -> io::Result<Vec<LogFile>> {
+    let file_prefix = format!("{}.log", prefix);
+    let mut files = Vec::new();
+
+    for entry in fs::read_dir(directory)? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        if !metadata.is_file() {
+            continue;
+        }
+
+        let name = entry.file_name();
+        if !name.to_string_lossy().starts_with(&file_prefix) {
+            continue;
+        }
+
+        files.push(LogFile {
+            path: entry.path(),
+            modified: metadata.modified()?,
+        });
+    }
+
+    Ok(files)
+}
+
+fn is_compressed(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "gz")
+}
+
+/// Gzip a file next to the original, then remove the original.
+/// The modification time is preserved so age-based retention keeps working.
+fn compress_file(path: &Path) -> io::Result<PathBuf> {
+    let mut gz_name = path.as_os_str().to_os_string();
+    gz_name.push(".gz");
+    let gz_path = PathBuf::from(gz_name);
+
+    let modified = fs::metadata(path)?.modified()?;
+
+    let mut input = File::open(path)?;
+    let mut encoder = GzEncoder::new(File::create(&gz_path)?, Compression::default());
+    io::copy(&mut input, &mut encoder)?;
+    let output = encoder.finish()?;
+    output.set_modified(modified)?;
+
+    fs::remove_file(path)?;
+    Ok(gz_path)
+}
@@ -0,0 +1,55 @@
+use std::future::Future;
+
+use mongodb::bson::{self, doc};
+use mongodb::Database;
+use serde::{Deserialize, Serialize};
+use tracing::info;
+
+use crate::global::error::DatabaseError;
+
+// Collection name for the applied migrations
+const COLLECTION_NAME: &str = "migrations";
+
+/// A data migration applied to the database
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MigrationRecord {
+    pub id: String,
+    /// Documents the migration updated
+    pub updated: u64,
+    pub applied_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Run the migration `id` unless it was already applied to this database.
+/// `migrate` returns the number of updated documents; it is recorded only on success,
+/// so a failed migration runs again on the next start.
+pub async fn run_once<F, Fut>(db: &Database, id: &str, migrate: F) -> Result<(), DatabaseError>
+where
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = Result<u64, DatabaseError>>,
+{
+    let collection = db.collection::<MigrationRecord>(COLLECTION_NAME);
+    if collection.find_one(doc! { "id": id }).await
+        .map_err(|e| DatabaseError::Query(format!("Failed to find migration: {}", e)))?
+        .is_some()
+    {
+        return Ok(());
+    }
+
+    info!(migration = %id, "Running migration");
+    let updated = migrate().await?;
+
+    let record = MigrationRecord {
+        id: id.to_string(),
+        updated,
+        applied_at: chrono::Utc::now(),
+    };
+    let record = bson::to_document(&record)
+        .map_err(|e| DatabaseError::Query(format!("Failed to serialize migration: {}", e)))?;
+    db.collection::<bson::Document>(COLLECTION_NAME)
+        .insert_one(record)
+        .await
+        .map_err(|e| DatabaseError::Query(format!("Failed to record migration: {}", e)))?;
+
+    info!(migration = %id, updated = updated, "Migration applied");
+    Ok(())
+}
@@ -4,5 +4,21 @@ pub mod state;
 pub mod module;
 pub mod http;
 pub mod config;
+pub mod secrets;
+pub mod reload;
+pub mod logs;
+pub mod alert;
+pub mod webhook;
+pub mod events;
+pub mod registry;
+pub mod supervisor;
 pub mod queue;
-pub mod model;
\ No newline at end of file
+pub mod job;
+pub mod model;
+pub mod validation;
+pub mod xml;
+pub mod lenient;
+pub mod migration;
+pub mod archive;
+pub mod disk;
+pub mod budget;
\ No newline at end of file
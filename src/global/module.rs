@@ -3,20 +3,50 @@ use std::sync::Arc;
 use std::future::Future;
 use std::pin::Pin;
 use std::num::NonZeroU32;
+use arc_swap::ArcSwap;
 use governor::{Quota, RateLimiter as GovernorRateLimiter};
 use governor::clock::{Clock, DefaultClock};
 use governor::state::{InMemoryState, NotKeyed};
-use tracing::{debug, trace};
+use tracing::{debug, info, trace};
 
-use super::{database::DatabaseInstance, error::AppError};
+use super::{database::DatabaseInstance, error::AppError, supervisor::Heartbeat};
+
+/// How often parent modules record a heartbeat
+pub const HEARTBEAT_INTERVAL: std::time::Duration = std::time::Duration::from_secs(60);
+
+/// A module whose last heartbeat is older than this is considered stale
+pub const HEARTBEAT_STALE_AFTER: std::time::Duration = std::time::Duration::from_secs(300);
 
 /// Message types that can be sent to parent modules
 #[derive(Debug, Clone)]
 pub enum ModuleMessage {
     Shutdown,
+    /// Stop pulling tasks from the queue, queued tasks and module state are kept
+    Pause,
+    /// Continue processing tasks after a pause
+    Resume,
+    /// Apply a setting changed at runtime (e.g. on configuration reload)
+    UpdateConfig(ModuleConfigUpdate),
     Custom(String),
 }
 
+/// Runtime settings that can be changed without recreating a module.
+/// Modules ignore the updates they don't support.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ModuleConfigUpdate {
+    /// Number of tasks executed at the same time
+    Concurrency(usize),
+    /// Period of the module's periodic cleanup
+    CleanupInterval(std::time::Duration),
+    /// Period of the statistics snapshots (`None` disables them) and the MAL ids they cover
+    Snapshots { interval: Option<std::time::Duration>, anime: Vec<u32> },
+    /// Period of the watchlist refreshes, `None` disables them
+    WatchlistInterval(Option<std::time::Duration>),
+    /// Age in days after which data sections are fetched again (`None` disables the refresh)
+    /// and anime refreshed per section on each check
+    SectionRefresh { max_age_days: Option<i64>, limit: i64 },
+}
+
 /// Response from child module operations
 #[derive(Debug)]
 pub struct ModuleResponse<T> {
@@ -29,10 +59,13 @@ pub trait ParentModule: Send + Sync {
     fn name(&self) -> &str;
     
     /// Initialize and run the module
+    /// The loop should call `heartbeat.beat()` at least every HEARTBEAT_INTERVAL,
+    /// otherwise the module is reported as stalled
     fn run(
         &self,
         db: Arc<DatabaseInstance>,
         rx: mpsc::Receiver<ModuleMessage>,
+        heartbeat: Heartbeat,
     ) -> Pin<Box<dyn Future<Output = Result<(), AppError>> + Send + '_>>;
 }
 
@@ -54,16 +87,20 @@ pub trait ChildModule: Send + Sync {
 }
 
 /// Rate limiter for API requests using the governor crate
+/// The quota can be replaced at runtime (e.g. on configuration reload);
+/// all clones share the same underlying limiter.
 pub struct RateLimiter {
-    limiter: Arc<GovernorRateLimiter<NotKeyed, InMemoryState, DefaultClock>>,
+    state: Arc<ArcSwap<LimiterState>>,
     name: String,
+}
+
+struct LimiterState {
+    limiter: GovernorRateLimiter<NotKeyed, InMemoryState, DefaultClock>,
     requests_per_second: f64,
 }
 
-impl RateLimiter {
-    /// Create a new rate limiter using governor
-    /// `requests_per_second` - maximum number of requests allowed per second
-    pub fn new(name: &str, requests_per_second: f64) -> Self {
+impl LimiterState {
+    fn new(requests_per_second: f64) -> Self {
         // Convert requests per second to a quota
         // For sub-second rates, we use milliseconds precision
         let quota = if requests_per_second >= 1.0 {
@@ -76,21 +113,52 @@ impl RateLimiter {
         };
 
         Self {
-            limiter: Arc::new(GovernorRateLimiter::direct(quota)),
-            name: name.to_string(),
+            limiter: GovernorRateLimiter::direct(quota),
             requests_per_second,
         }
     }
+}
+
+impl RateLimiter {
+    /// Create a new rate limiter using governor
+    /// `requests_per_second` - maximum number of requests allowed per second
+    pub fn new(name: &str, requests_per_second: f64) -> Self {
+        Self {
+            state: Arc::new(ArcSwap::from_pointee(LimiterState::new(requests_per_second))),
+            name: name.to_string(),
+        }
+    }
+
+    /// Current configured rate in requests per second
+    pub fn requests_per_second(&self) -> f64 {
+        self.state.load().requests_per_second
+    }
+
+    /// Replace the quota with a new rate, applies to all clones of this limiter
+    pub fn set_rate(&self, requests_per_second: f64) {
+        if requests_per_second <= 0.0 || requests_per_second == self.requests_per_second() {
+            return;
+        }
+
+        info!(
+            limiter = %self.name,
+            old_rate = %self.requests_per_second(),
+            new_rate = %requests_per_second,
+            "Updating rate limit"
+        );
+        self.state.store(Arc::new(LimiterState::new(requests_per_second)));
+    }
 
     /// Acquire permission to make a request
     /// This will wait asynchronously if the rate limit is exceeded
     pub async fn acquire(&self) {
         loop {
-            match self.limiter.check() {
+            let state = self.state.load_full();
+            match state.limiter.check() {
                 Ok(_) => {
                     trace!(
                         limiter = %self.name,
-                        rate = %self.requests_per_second,
+                        rate = %state.requests_per_second,
                         "Rate limit permit acquired"
                     );
                     break;
@@ -112,7 +180,7 @@ impl RateLimiter {
     /// Try to acquire permission without waiting
     /// Returns Ok(()) if successful, Err with wait duration if rate limited
     pub fn try_acquire(&self) -> Result<(), std::time::Duration> {
-        match self.limiter.check() {
+        match self.state.load().limiter.check() {
             Ok(_) => Ok(()),
             Err(not_until) => {
                 let clock = DefaultClock::default();
@@ -126,14 +194,14 @@ impl RateLimiter {
 impl Clone for RateLimiter {
     fn clone(&self) -> Self {
         Self {
-            limiter: self.limiter.clone(),
+            state: self.state.clone(),
             name: self.name.clone(),
-            requests_per_second: self.requests_per_second,
         }
     }
 }
 
 /// Handle for controlling a parent module
+#[derive(Clone)]
 pub struct ModuleHandle {
     pub name: String,
     pub tx: mpsc::Sender<ModuleMessage>,
@@ -144,4 +212,20 @@ impl ModuleHandle {
         self.tx.send(ModuleMessage::Shutdown).await
             .map_err(|e| AppError::Module(format!("Failed to send shutdown: {}", e)))
     }
+
+    pub async fn pause(&self) -> Result<(), AppError> {
+        self.tx.send(ModuleMessage::Pause).await
+            .map_err(|e| AppError::Module(format!("Failed to send pause: {}", e)))
+    }
+
+    pub async fn resume(&self) -> Result<(), AppError> {
+        self.tx.send(ModuleMessage::Resume).await
+            .map_err(|e| AppError::Module(format!("Failed to send resume: {}", e)))
+    }
+
+    /// Send a runtime setting change without waiting, usable from sync callbacks
+    pub fn update_config(&self, update: ModuleConfigUpdate) -> Result<(), AppError> {
+        self.tx.try_send(ModuleMessage::UpdateConfig(update))
+            .map_err(|e| AppError::Module(format!("Failed to send config update: {}", e)))
+    }
 }
\ No newline at end of file
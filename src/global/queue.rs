@@ -1,9 +1,73 @@
 use serde::{Deserialize, Serialize};
-use tokio::sync::mpsc;
-use std::{cmp::Ordering, collections::BinaryHeap, sync::Arc};
+use tokio::sync::{mpsc, Semaphore};
+use std::{cmp::Ordering, collections::BinaryHeap, sync::Arc, time::{Duration, Instant}};
+use std::sync::atomic::{AtomicU64, Ordering as AtomicOrdering};
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tokio_util::sync::CancellationToken;
+use mongodb::options::UpdateOneModel;
 use tracing::{info, debug, warn, error};
 
-use super::{database::DatabaseInstance, error::AppError};
+use super::{
+    database::DatabaseInstance,
+    error::{AppError, DatabaseError},
+    events::EventBus,
+    http::ClientWithLimiter,
+    job::{self, record_task_event, JobTaskEvent},
+};
+
+/// How often low priority tasks waiting for a provider budget are checked again
+const BUDGET_RECHECK: Duration = Duration::from_secs(60);
+// Executions of a task failing with a retryable error, the first included
+const TASK_MAX_ATTEMPTS: u32 = 3;
+// Delay before the first retry, doubled on each following one
+const TASK_RETRY_DELAY: Duration = Duration::from_secs(5);
+
+/// How often the worker persists the queue position and estimated start of waiting tasks
+const ESTIMATE_REFRESH: Duration = Duration::from_secs(30);
+/// Waiting tasks given an estimate, the rest of the queue keeps its last one
+const MAX_ESTIMATED_TASKS: usize = 1000;
+
+/// Cancellation tokens of the tasks a worker is executing, by execution.
+/// A task id may be executing more than once at the same time.
+#[derive(Debug, Default)]
+struct RunningTasks {
+    next_execution: AtomicU64,
+    tokens: Mutex<HashMap<u64, (String, CancellationToken)>>,
+}
+
+impl RunningTasks {
+    /// Track an execution of a task, returns its id for `unregister`
+    fn register(&self, task_id: &str, token: CancellationToken) -> u64 {
+        let execution = self.next_execution.fetch_add(1, AtomicOrdering::Relaxed);
+        if let Ok(mut tokens) = self.tokens.lock() {
+            tokens.insert(execution, (task_id.to_string(), token));
+        }
+        execution
+    }
+
+    fn unregister(&self, execution: u64) {
+        if let Ok(mut tokens) = self.tokens.lock() {
+            tokens.remove(&execution);
+        }
+    }
+
+    /// Ask every execution of a task to stop, returns how many were asked
+    fn cancel(&self, task_id: &str) -> usize {
+        let Ok(tokens) = self.tokens.lock() else { return 0 };
+        let mut canceled = 0;
+        for (id, token) in tokens.values() {
+            if id == task_id {
+                token.cancel();
+                canceled += 1;
+            }
+        }
+        if canceled > 0 {
+            info!(task_id = %task_id, executions = canceled, "Canceling task");
+        }
+        canceled
+    }
+}
 
 /// Priority levels for tasks
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
@@ -32,6 +96,74 @@ pub struct TaskData {
     pub status: TaskStatus,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub payload: serde_json::Value,
+    /// Job the task belongs to, set by the queue when persisting
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub job_id: Option<String>,
+    /// Recoverable problems met while executing, e.g. provider fields that failed to parse
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+    /// Place of a pending task in its queue, 1 starts next. Refreshed by the worker every
+    /// `ESTIMATE_REFRESH`, absent for tasks deferred by a provider budget
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub queue_position: Option<u64>,
+    /// Estimated start of a pending task, from the tasks ahead of it, the average task
+    /// duration and the request rate of its provider
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub estimated_start: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl TaskData {
+    /// Data of a pending task, the queue sets its job, warnings and estimates
+    pub fn new(
+        id: String,
+        name: String,
+        priority: TaskPriority,
+        created_at: chrono::DateTime<chrono::Utc>,
+        payload: serde_json::Value,
+    ) -> Self {
+        Self {
+            id,
+            name,
+            priority,
+            status: TaskStatus::Pending,
+            created_at,
+            payload,
+            job_id: None,
+            warnings: Vec::new(),
+            queue_position: None,
+            estimated_start: None,
+        }
+    }
+}
+
+/// What a task is given to execute, built by the worker for each execution.
+/// New capabilities are added here rather than to the `Task::execute` signature.
+#[derive(Clone)]
+pub struct TaskContext {
+    pub db: Arc<DatabaseInstance>,
+    /// Default HTTP client of the worker, provider tasks carry their own limited client
+    pub client: reqwest::Client,
+    /// Canceled on shutdown or when the task is canceled, long loops and downloads check it
+    pub cancel: CancellationToken,
+}
+
+impl TaskContext {
+    pub fn new(db: Arc<DatabaseInstance>, client: reqwest::Client) -> Self {
+        Self { db, client, cancel: CancellationToken::new() }
+    }
+
+    pub fn with_cancel(mut self, cancel: CancellationToken) -> Self {
+        self.cancel = cancel;
+        self
+    }
+
+    /// Fail with `AppError::Canceled` once the task was asked to stop
+    pub fn check_canceled(&self) -> Result<(), AppError> {
+        if self.cancel.is_cancelled() {
+            return Err(AppError::Canceled("Task was canceled".to_string()));
+        }
+        Ok(())
+    }
 }
 
 /// A task that can be queued and executed
@@ -43,9 +175,45 @@ pub trait Task: Send + Sync {
     
     /// Serialize task for persistence
     fn to_data(&self) -> TaskData;
+
+    /// Provider client the task sends its requests with, a low priority task
+    /// waits while the daily budget of its provider is exhausted
+    fn provider(&self) -> Option<&ClientWithLimiter> {
+        None
+    }
     
     /// Execute the task
-    async fn execute(&self, db: Arc<DatabaseInstance>, client: reqwest::Client) -> Result<(), AppError>;
+    async fn execute(&self, ctx: &TaskContext) -> Result<(), AppError>;
+}
+
+tokio::task_local! {
+    /// Job of the task being executed, inherited by the tasks it enqueues
+    static CURRENT_JOB: String;
+
+    /// Warnings recorded by the task being executed
+    static TASK_WARNINGS: Arc<Mutex<Vec<String>>>;
+}
+
+/// Job id of the task currently executing on this tokio task, if any
+pub fn current_job_id() -> Option<String> {
+    CURRENT_JOB.try_with(|job_id| job_id.clone()).ok()
+}
+
+/// Run a future as part of a job: tasks it enqueues join the job
+pub async fn in_job<F: std::future::Future>(job_id: String, future: F) -> F::Output {
+    CURRENT_JOB.scope(job_id, future).await
+}
+
+/// Log a recoverable problem and keep it in the result of the task being executed, if any
+pub fn record_warning(message: impl Into<String>) {
+    let message = message.into();
+    warn!(warning = %message, "Task warning");
+
+    let _ = TASK_WARNINGS.try_with(|warnings| {
+        if let Ok(mut warnings) = warnings.lock() {
+            warnings.push(message);
+        }
+    });
 }
 
 /// Wrapper for priority queue ordering
@@ -53,6 +221,7 @@ struct PriorityTask {
     task: Box<dyn Task>,
     priority: TaskPriority,
     created_at: chrono::DateTime<chrono::Utc>,
+    job_id: Option<String>,
 }
 
 impl PartialEq for PriorityTask {
@@ -79,41 +248,170 @@ impl Ord for PriorityTask {
     }
 }
 
+/// Estimate of a waiting task, written over the `TaskData` fields of the same name
+#[derive(Debug, Serialize)]
+struct TaskEstimate {
+    #[serde(skip)]
+    task_id: String,
+    queue_position: Option<u64>,
+    estimated_start: Option<chrono::DateTime<chrono::Utc>>,
+}
+
 /// Message types for the task queue
 pub enum QueueMessage {
-    /// Add a new task to the queue
-    AddTask(Box<dyn Task>),
+    /// Add a new task to the queue, optionally as part of a job
+    AddTask(Box<dyn Task>, Option<String>),
+    /// Stop starting new tasks, queued and running tasks are kept
+    Pause,
+    /// Start processing tasks again after a pause
+    Resume,
+    /// Change the maximum number of tasks executed at the same time
+    SetConcurrency(usize),
     /// Shutdown the queue
     Shutdown,
 }
 
-/// A task queue that executes tasks sequentially
+/// Counters and executing tasks shared between a queue and its worker
+#[derive(Debug, Default)]
+pub struct QueueStats {
+    processed: AtomicU64,
+    failed: AtomicU64,
+    pending: AtomicU64,
+    running: AtomicU64,
+    /// Total execution time of the finished tasks
+    busy_ms: AtomicU64,
+    /// Tasks the worker executes at the same time
+    concurrency: AtomicU64,
+    executing: RunningTasks,
+}
+
+impl QueueStats {
+    /// Tasks completed successfully
+    pub fn processed(&self) -> u64 {
+        self.processed.load(AtomicOrdering::Relaxed)
+    }
+
+    /// Tasks that returned an error
+    pub fn failed(&self) -> u64 {
+        self.failed.load(AtomicOrdering::Relaxed)
+    }
+
+    /// Tasks waiting in the worker's priority queue
+    pub fn pending(&self) -> u64 {
+        self.pending.load(AtomicOrdering::Relaxed)
+    }
+
+    /// Tasks currently executing
+    pub fn running(&self) -> u64 {
+        self.running.load(AtomicOrdering::Relaxed)
+    }
+
+    /// Time to work through `tasks` at the average duration of the finished tasks
+    pub fn estimated_wait(&self, tasks: u64) -> Option<Duration> {
+        let finished = self.processed() + self.failed();
+        if finished == 0 {
+            return None;
+        }
+        let average_ms = self.busy_ms.load(AtomicOrdering::Relaxed) / finished;
+        let concurrency = self.concurrency.load(AtomicOrdering::Relaxed).max(1);
+        Some(Duration::from_millis(tasks.saturating_mul(average_ms) / concurrency))
+    }
+}
+
+/// A task queue whose tasks are executed by priority by a QueueWorker
 pub struct TaskQueue {
     name: String,
+    /// Tasks accepted before `check_capacity` reports the queue as full
+    capacity: usize,
     tx: mpsc::Sender<QueueMessage>,
+    stats: Arc<QueueStats>,
+    db: Arc<DatabaseInstance>,
 }
 
 impl TaskQueue {
     /// Create a new task queue
     /// Returns (TaskQueue, receiver handle for the worker)
-    pub fn new(name: String, buffer_size: usize) -> (Self, mpsc::Receiver<QueueMessage>) {
+    pub fn new(name: String, buffer_size: usize, db: Arc<DatabaseInstance>) -> (Self, mpsc::Receiver<QueueMessage>) {
         let (tx, rx) = mpsc::channel(buffer_size);
-        (Self { name, tx }, rx)
+        (Self { name, capacity: buffer_size, tx, stats: Arc::new(QueueStats::default()), db }, rx)
+    }
+
+    /// Tasks waiting in the worker or still in the channel
+    pub fn depth(&self) -> u64 {
+        let in_channel = self.tx.max_capacity() - self.tx.capacity();
+        self.stats.pending() + in_channel as u64
+    }
+
+    /// Fail with `AppError::QueueFull` when the queue already holds its capacity,
+    /// for callers that should back off instead of piling up tasks
+    pub fn check_capacity(&self) -> Result<(), AppError> {
+        let depth = self.depth();
+        if depth < self.capacity as u64 {
+            return Ok(());
+        }
+
+        warn!(queue = %self.name, depth = depth, capacity = self.capacity, "Queue is full");
+        Err(AppError::QueueFull {
+            queue: self.name.clone(),
+            depth,
+            capacity: self.capacity as u64,
+            estimated_wait: self.stats.estimated_wait(depth + self.stats.running()),
+        })
+    }
+
+    /// Counters of this queue, pass them to the worker with `QueueWorker::with_stats`
+    pub fn stats(&self) -> Arc<QueueStats> {
+        self.stats.clone()
     }
 
-    /// Add a task to the queue
+    /// Add a task to the queue.
+    /// When called from a task that belongs to a job, the new task joins the same job.
     pub async fn enqueue(&self, task: Box<dyn Task>) -> Result<(), AppError> {
+        self.enqueue_with_job(task, current_job_id()).await
+    }
+
+    /// Add a task to the queue as part of a job
+    pub async fn enqueue_for_job(&self, task: Box<dyn Task>, job_id: &str) -> Result<(), AppError> {
+        self.enqueue_with_job(task, Some(job_id.to_string())).await
+    }
+
+    async fn enqueue_with_job(&self, task: Box<dyn Task>, job_id: Option<String>) -> Result<(), AppError> {
         info!(
             queue = %self.name,
             task_id = %task.id(),
             task_name = %task.name(),
             priority = ?task.priority(),
+            job_id = ?job_id,
             "Enqueueing task"
         );
-        
-        self.tx.send(QueueMessage::AddTask(task))
-            .await
-            .map_err(|e| AppError::Module(format!("Failed to enqueue task: {}", e)))
+
+        // Count the task into its job before it can run, so the job never looks finished early
+        if let Some(job_id) = &job_id
+            && let Err(e) = record_task_event(&self.db, job_id, JobTaskEvent::Queued).await
+        {
+            warn!(queue = %self.name, job_id = %job_id, error = %e, "Failed to update job");
+        }
+        // Listed by the task API and given estimates while it waits
+        if let Err(e) = QueueWorker::persist_task_status(&self.db, &task, job_id.clone(), TaskStatus::Pending, Vec::new()).await {
+            warn!(queue = %self.name, task_id = %task.id(), error = %e, "Failed to persist pending task");
+        }
+
+        let task_id = task.id();
+        let Err(e) = self.tx.send(QueueMessage::AddTask(task, job_id.clone())).await else {
+            return Ok(());
+        };
+
+        // The task never reaches the worker, undo its accounting
+        if let Some(job_id) = &job_id
+            && let Err(e) = record_task_event(&self.db, job_id, JobTaskEvent::Unqueued).await
+        {
+            warn!(queue = %self.name, job_id = %job_id, error = %e, "Failed to update job");
+        }
+        if let Err(e) = delete_pending_task(&self.db, &task_id).await {
+            warn!(queue = %self.name, task_id = %task_id, error = %e, "Failed to remove pending task");
+        }
+
+        Err(AppError::Module(format!("Failed to enqueue task: {}", e)))
     }
 
     /// Shutdown the queue
@@ -125,6 +423,37 @@ impl TaskQueue {
             .map_err(|e| AppError::Module(format!("Failed to shutdown queue: {}", e)))
     }
 
+    /// Stop the worker from starting new tasks
+    pub async fn pause(&self) -> Result<(), AppError> {
+        info!(queue = %self.name, "Pausing queue");
+
+        self.tx.send(QueueMessage::Pause)
+            .await
+            .map_err(|e| AppError::Module(format!("Failed to pause queue: {}", e)))
+    }
+
+    /// Let the worker start tasks again
+    pub async fn resume(&self) -> Result<(), AppError> {
+        info!(queue = %self.name, "Resuming queue");
+
+        self.tx.send(QueueMessage::Resume)
+            .await
+            .map_err(|e| AppError::Module(format!("Failed to resume queue: {}", e)))
+    }
+
+    /// Change the number of tasks the worker executes at the same time
+    pub async fn set_concurrency(&self, concurrency: usize) -> Result<(), AppError> {
+        self.tx.send(QueueMessage::SetConcurrency(concurrency))
+            .await
+            .map_err(|e| AppError::Module(format!("Failed to update queue concurrency: {}", e)))
+    }
+
+    /// Ask the executing task `task_id` of this queue to stop.
+    /// Returns false when no task with this id is executing.
+    pub fn cancel_task(&self, task_id: &str) -> bool {
+        self.stats.executing.cancel(task_id) > 0
+    }
+
     /// Get the queue name
     pub fn name(&self) -> &str {
         &self.name
@@ -135,7 +464,10 @@ impl Clone for TaskQueue {
     fn clone(&self) -> Self {
         Self {
             name: self.name.clone(),
+            capacity: self.capacity,
             tx: self.tx.clone(),
+            stats: self.stats.clone(),
+            db: self.db.clone(),
         }
     }
 }
@@ -145,37 +477,115 @@ pub struct QueueWorker {
     name: String,
     db: Arc<DatabaseInstance>,
     client: reqwest::Client,
+    concurrency: usize,
+    stats: Arc<QueueStats>,
+    /// Where to publish finished jobs
+    events: Option<EventBus>,
+    /// Canceled on shutdown, parent of the token of every executing task
+    shutdown: CancellationToken,
 }
 
 impl QueueWorker {
     pub fn new(name: String, db: Arc<DatabaseInstance>, client: reqwest::Client) -> Self {
-        Self { name, db, client }
+        Self { name, db, client, concurrency: 1, stats: Arc::new(QueueStats::default()), events: None, shutdown: CancellationToken::new() }
+    }
+
+    /// Report counters into the stats of the queue feeding this worker
+    pub fn with_stats(mut self, stats: Arc<QueueStats>) -> Self {
+        self.stats = stats;
+        self
+    }
+
+    /// Publish a data event when the last task of a job finishes
+    pub fn with_events(mut self, events: EventBus) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    /// Set the maximum number of tasks executed at the same time (default 1)
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
     }
 
     /// Run the worker, processing tasks until shutdown
-    pub async fn run(self, mut rx: mpsc::Receiver<QueueMessage>) -> Result<(), AppError> {
-        info!(worker = %self.name, "Task queue worker started");
+    pub async fn run(mut self, mut rx: mpsc::Receiver<QueueMessage>) -> Result<(), AppError> {
+        info!(worker = %self.name, concurrency = self.concurrency, "Task queue worker started");
         
-        let mut tasks_processed = 0;
+        let slots = Arc::new(Semaphore::new(self.concurrency));
+        self.stats.concurrency.store(self.concurrency as u64, AtomicOrdering::Relaxed);
         let mut priority_queue = BinaryHeap::new();
-        
-        // Load persisted tasks on startup
-        if let Err(e) = self.load_persisted_tasks(&mut priority_queue).await {
-            warn!(worker = %self.name, error = %e, "Failed to load persisted tasks");
-        }
+        // Low priority tasks waiting for their provider budget
+        let mut deferred: Vec<PriorityTask> = Vec::new();
+        let mut budget_checked_at = Instant::now();
+        let mut paused = false;
+        let mut estimated_at: Option<Instant> = None;
+        let mut estimates: Option<tokio::task::JoinHandle<()>> = None;
+
 
         loop {
-            // Try to get next task from priority queue or wait for new one
-            let task = if priority_queue.is_empty() {
-                // Wait for new task
-                match rx.recv().await {
-                    Some(QueueMessage::AddTask(task)) => {
-                        let priority = task.priority();
-                        let created_at = chrono::Utc::now();
-                        Some(PriorityTask { task, priority, created_at })
+            if !deferred.is_empty() && budget_checked_at.elapsed() >= BUDGET_RECHECK {
+                budget_checked_at = Instant::now();
+                let before = deferred.len();
+                for task in std::mem::take(&mut deferred) {
+                    if Self::over_budget(&task) {
+                        deferred.push(task);
+                    } else {
+                        priority_queue.push(task);
+                    }
+                }
+                if deferred.len() < before {
+                    info!(worker = %self.name, resumed = before - deferred.len(), "Provider budget available, resuming deferred tasks");
+                }
+            }
+
+            self.stats.pending.store((priority_queue.len() + deferred.len()) as u64, AtomicOrdering::Relaxed);
+
+            let has_waiting = !priority_queue.is_empty() || !deferred.is_empty();
+            if has_waiting
+                && estimated_at.is_none_or(|at| at.elapsed() >= ESTIMATE_REFRESH)
+                && estimates.as_ref().is_none_or(|previous| previous.is_finished())
+            {
+                estimated_at = Some(Instant::now());
+                let waiting = self.estimate_starts(&priority_queue, &deferred);
+                estimates = Some(tokio::spawn(Self::persist_estimates(self.db.clone(), waiting)));
+            }
+
+            if priority_queue.is_empty() || paused {
+                // Wait for new task (or for a resume while paused), deferred tasks are checked periodically
+                let msg = if deferred.is_empty() {
+                    rx.recv().await
+                } else {
+                    match tokio::time::timeout(BUDGET_RECHECK, rx.recv()).await {
+                        Ok(msg) => msg,
+                        Err(_) => continue,
+                    }
+                };
+                match msg {
+                    Some(QueueMessage::AddTask(task, job_id)) => {
+                        Self::push_task(&mut priority_queue, task, job_id);
+                    }
+                    Some(QueueMessage::Pause) => {
+                        if !paused {
+                            info!(worker = %self.name, pending = priority_queue.len(), "Worker paused");
+                        }
+                        paused = true;
+                    }
+                    Some(QueueMessage::Resume) => {
+                        if paused {
+                            info!(worker = %self.name, pending = priority_queue.len(), "Worker resumed");
+                        }
+                        paused = false;
+                    }
+                    Some(QueueMessage::SetConcurrency(concurrency)) => {
+                        self.resize_slots(&slots, concurrency);
                     }
                     Some(QueueMessage::Shutdown) => {
-                        info!(worker = %self.name, tasks_processed = tasks_processed, "Shutdown signal received");
+                        info!(
+                            worker = %self.name,
+                            tasks_processed = self.stats.processed(),
+                            "Shutdown signal received"
+                        );
                         break;
                     }
                     None => {
@@ -183,145 +593,473 @@ impl QueueWorker {
                         break;
                     }
                 }
-            } else {
-                // Process highest priority task, but check for new messages
-                tokio::select! {
-                    msg = rx.recv() => {
-                        match msg {
-                            Some(QueueMessage::AddTask(task)) => {
-                                let priority = task.priority();
-                                let created_at = chrono::Utc::now();
-                                priority_queue.push(PriorityTask { task, priority, created_at });
-                                None
-                            }
-                            Some(QueueMessage::Shutdown) => {
-                                info!(worker = %self.name, "Shutdown during processing");
-                                break;
-                            }
-                            None => break,
+                continue;
+            }
+
+            // Accept new messages first so higher priority tasks are considered,
+            // then start the highest priority task once an execution slot is free
+            tokio::select! {
+                biased;
+
+                msg = rx.recv() => {
+                    match msg {
+                        Some(QueueMessage::AddTask(task, job_id)) => {
+                            Self::push_task(&mut priority_queue, task, job_id);
                         }
-                    }
-                    _ = tokio::time::sleep(tokio::time::Duration::from_millis(10)) => {
-                        priority_queue.pop()
+                        Some(QueueMessage::Pause) => {
+                            info!(worker = %self.name, pending = priority_queue.len(), "Worker paused");
+                            paused = true;
+                        }
+                        Some(QueueMessage::Resume) => {}
+                        Some(QueueMessage::SetConcurrency(concurrency)) => {
+                            self.resize_slots(&slots, concurrency);
+                        }
+                        Some(QueueMessage::Shutdown) => {
+                            info!(worker = %self.name, "Shutdown during processing");
+                            break;
+                        }
+                        None => break,
                     }
                 }
-            };
-            
-            if let Some(priority_task) = task {
-                let task_id = priority_task.task.id();
-                let task_name = priority_task.task.name();
-                let priority = priority_task.priority;
-                
-                debug!(
-                    worker = %self.name,
-                    task_id = %task_id,
-                    task_name = %task_name,
-                    priority = ?priority,
-                    queue_size = priority_queue.len(),
-                    "Processing task"
-                );
-                
-                // Persist task as running
-                if let Err(e) = self.persist_task_status(&priority_task.task, TaskStatus::Running).await {
-                    warn!(worker = %self.name, task_id = %task_id, error = %e, "Failed to persist task status");
-                }
-                
-                match priority_task.task.execute(self.db.clone(), self.client.clone()).await {
-                    Ok(_) => {
-                        tasks_processed += 1;
-                        info!(
-                            worker = %self.name,
-                            task_id = %task_id,
-                            priority = ?priority,
-                            tasks_processed = tasks_processed,
-                            "Task completed"
-                        );
-                        
-                        // Persist as completed
-                        if let Err(e) = self.persist_task_status(&priority_task.task, TaskStatus::Completed).await {
-                            warn!(worker = %self.name, task_id = %task_id, error = %e, "Failed to persist completion");
+                permit = slots.clone().acquire_owned() => {
+                    let permit = permit
+                        .map_err(|e| AppError::Module(format!("Worker semaphore closed: {}", e)))?;
+
+                    if let Some(priority_task) = priority_queue.pop() {
+                        if Self::over_budget(&priority_task) {
+                            debug!(
+                                worker = %self.name,
+                                task_id = %priority_task.task.id(),
+                                task_name = %priority_task.task.name(),
+                                "Provider budget exhausted, deferring low priority task"
+                            );
+                            deferred.push(priority_task);
+                            continue;
                         }
-                    }
-                    Err(e) => {
-                        error!(
+
+                        debug!(
                             worker = %self.name,
-                            task_id = %task_id,
-                            priority = ?priority,
-                            error = %e,
-                            "Task failed"
+                            task_id = %priority_task.task.id(),
+                            task_name = %priority_task.task.name(),
+                            priority = ?priority_task.priority,
+                            queue_size = priority_queue.len(),
+                            "Processing task"
                         );
-                        
-                        // Persist as failed
-                        let status = TaskStatus::Failed { error: e.to_string() };
-                        if let Err(e) = self.persist_task_status(&priority_task.task, status).await {
-                            warn!(worker = %self.name, task_id = %task_id, error = %e, "Failed to persist failure");
-                        }
+
+                        let name = self.name.clone();
+                        let db = self.db.clone();
+                        let client = self.client.clone();
+                        let stats = self.stats.clone();
+                        let events = self.events.clone();
+                        let shutdown = self.shutdown.clone();
+
+                        tokio::spawn(async move {
+                            Self::process_task(&name, db, client, shutdown, priority_task, &stats, events.as_ref()).await;
+                            drop(permit);
+                        });
                     }
                 }
             }
         }
+
+        // Interrupt in-flight tasks and wait for them to stop
+        self.shutdown.cancel();
+        let _ = slots.acquire_many(self.concurrency as u32).await;
         
         info!(
             worker = %self.name,
-            tasks_processed = tasks_processed,
+            tasks_processed = self.stats.processed(),
             "Task queue worker stopped"
         );
         
         Ok(())
     }
 
-    async fn persist_task_status(&self, task: &Box<dyn Task>, status: TaskStatus) -> Result<(), AppError> {
+    /// Queue position and estimated start of the waiting tasks. A task starts once the tasks
+    /// ahead of it leave a slot free and, for a provider task, once the provider rate allowed
+    /// a request for each provider task ahead. Deferred tasks wait for the next budget day.
+    fn estimate_starts(&self, queue: &BinaryHeap<PriorityTask>, deferred: &[PriorityTask]) -> Vec<TaskEstimate> {
+        let now = chrono::Utc::now();
+        let running = self.stats.running();
+        let concurrency = self.concurrency as u64;
+
+        let mut ordered: Vec<&PriorityTask> = queue.iter().collect();
+        ordered.sort_unstable_by(|a, b| b.cmp(a));
+
+        let mut provider_ahead: HashMap<&str, u64> = HashMap::new();
+        let mut estimates = Vec::with_capacity(ordered.len().min(MAX_ESTIMATED_TASKS) + deferred.len());
+        for (position, priority_task) in ordered.into_iter().take(MAX_ESTIMATED_TASKS).enumerate() {
+            let position = position as u64;
+            // Tasks finishing before a slot is free for this one
+            let ahead = (running + position + 1).saturating_sub(concurrency);
+            let mut wait = self.stats.estimated_wait(ahead);
+
+            if let Some(client) = priority_task.task.provider() {
+                let requests = provider_ahead.entry(client.name.as_str()).or_default();
+                let rate = client.limiter.requests_per_second();
+                if rate > 0.0 {
+                    let provider_wait = Duration::from_secs_f64(*requests as f64 / rate);
+                    wait = Some(wait.unwrap_or_default().max(provider_wait));
+                }
+                *requests += 1;
+            }
+
+            estimates.push(TaskEstimate {
+                task_id: priority_task.task.id(),
+                queue_position: Some(position + 1),
+                estimated_start: wait.and_then(|wait| chrono::Duration::from_std(wait).ok()).map(|wait| now + wait),
+            });
+        }
+
+        // Budgets reset at midnight UTC
+        let next_day = (now.date_naive() + chrono::Days::new(1)).and_time(chrono::NaiveTime::MIN).and_utc();
+        estimates.extend(deferred.iter().map(|priority_task| TaskEstimate {
+            task_id: priority_task.task.id(),
+            queue_position: None,
+            estimated_start: Some(next_day),
+        }));
+
+        estimates
+    }
+
+    async fn persist_estimates(db: Arc<DatabaseInstance>, estimates: Vec<TaskEstimate>) {
+        let collection = db.db().collection::<mongodb::bson::Document>("task_queue");
+        let models: Vec<UpdateOneModel> = estimates.iter()
+            .filter_map(|estimate| match mongodb::bson::to_document(estimate) {
+                Ok(fields) => Some(
+                    UpdateOneModel::builder()
+                        .namespace(collection.namespace())
+                        // A task that started meanwhile keeps its running status without an estimate
+                        .filter(mongodb::bson::doc! { "id": &estimate.task_id, "status": "Pending" })
+                        .update(mongodb::bson::doc! { "$set": fields })
+                        .build(),
+                ),
+                Err(e) => {
+                    warn!(task_id = %estimate.task_id, error = %e, "Failed to serialize task estimate");
+                    None
+                }
+            })
+            .collect();
+        if models.is_empty() {
+            return;
+        }
+
+        if let Err(e) = db.db().client().bulk_write(models).ordered(false).await {
+            warn!(error = %e, "Failed to persist task estimates");
+            return;
+        }
+        debug!(tasks = estimates.len(), "Persisted task estimates");
+    }
+
+    /// Whether a low priority task must wait for the daily budget of its provider
+    fn over_budget(priority_task: &PriorityTask) -> bool {
+        priority_task.priority == TaskPriority::Low
+            && priority_task.task.provider().is_some_and(|client| client.budget().is_exhausted())
+    }
+
+    fn push_task(queue: &mut BinaryHeap<PriorityTask>, task: Box<dyn Task>, job_id: Option<String>) {
+        let priority = task.priority();
+        let created_at = chrono::Utc::now();
+        queue.push(PriorityTask { task, priority, created_at, job_id });
+    }
+
+    /// Change the number of execution slots.
+    /// Extra slots are removed as soon as running tasks release them.
+    fn resize_slots(&mut self, slots: &Arc<Semaphore>, concurrency: usize) {
+        let concurrency = concurrency.max(1);
+        if concurrency == self.concurrency {
+            return;
+        }
+
+        info!(
+            worker = %self.name,
+            old_concurrency = self.concurrency,
+            new_concurrency = concurrency,
+            "Updating worker concurrency"
+        );
+
+        if concurrency > self.concurrency {
+            slots.add_permits(concurrency - self.concurrency);
+        } else {
+            let excess = self.concurrency - concurrency;
+            let remaining = excess - slots.forget_permits(excess);
+            if remaining > 0 {
+                // Take the remaining slots back once running tasks release them
+                let slots = slots.clone();
+                tokio::spawn(async move {
+                    if let Ok(permits) = slots.acquire_many_owned(remaining as u32).await {
+                        permits.forget();
+                    }
+                });
+            }
+        }
+
+        self.concurrency = concurrency;
+        self.stats.concurrency.store(concurrency as u64, AtomicOrdering::Relaxed);
+    }
+
+    /// Execute a single task and persist its status transitions.
+    /// A task interrupted by `shutdown` is persisted as pending again, only a task canceled
+    /// on its own fails. `fail_interrupted_tasks` fails it on the next start.
+    async fn process_task(
+        worker: &str,
+        db: Arc<DatabaseInstance>,
+        client: reqwest::Client,
+        shutdown: CancellationToken,
+        priority_task: PriorityTask,
+        stats: &QueueStats,
+        events: Option<&EventBus>,
+    ) {
+        let task_id = priority_task.task.id();
+        let priority = priority_task.priority;
+
+        stats.running.fetch_add(1, AtomicOrdering::Relaxed);
+
+        // Persist task as running
+        let job_id = priority_task.job_id.clone();
+        if let Err(e) = Self::persist_task_status(&db, &priority_task.task, job_id.clone(), TaskStatus::Running, Vec::new()).await {
+            warn!(worker = %worker, task_id = %task_id, error = %e, "Failed to persist task status");
+        }
+        Self::record_job_event(&db, worker, job_id.as_deref(), JobTaskEvent::Started, events).await;
+        
+        let started = Instant::now();
+        let warnings = Arc::new(Mutex::new(Vec::new()));
+        let cancel = shutdown.child_token();
+        let ctx = TaskContext::new(db.clone(), client).with_cancel(cancel.clone());
+        let execution = stats.executing.register(&task_id, cancel.clone());
+        let mut attempt = 1;
+        let mut retry_delay = TASK_RETRY_DELAY;
+        let result = loop {
+            // Only the warnings of the last attempt are kept
+            if let Ok(mut warnings) = warnings.lock() {
+                warnings.clear();
+            }
+            let execution = TASK_WARNINGS.scope(warnings.clone(), priority_task.task.execute(&ctx));
+            let result = match job_id.clone() {
+                Some(job_id) => in_job(job_id, execution).await,
+                None => execution.await,
+            };
+            match result {
+                Err(e) if e.is_retryable() && attempt < TASK_MAX_ATTEMPTS && !cancel.is_cancelled() => {
+                    warn!(
+                        worker = %worker,
+                        task_id = %task_id,
+                        attempt = attempt,
+                        retry_in = ?retry_delay,
+                        error = %e,
+                        "Task failed with a retryable error, retrying"
+                    );
+                    tokio::select! {
+                        _ = tokio::time::sleep(retry_delay) => {}
+                        _ = cancel.cancelled() => break Err(AppError::Canceled("Task was canceled before its retry".to_string())),
+                    }
+                    attempt += 1;
+                    retry_delay *= 2;
+                }
+                result => break result,
+            }
+        };
+        stats.executing.unregister(execution);
+        let warnings = warnings.lock().map(|warnings| warnings.clone()).unwrap_or_default();
+        stats.running.fetch_sub(1, AtomicOrdering::Relaxed);
+        stats.busy_ms.fetch_add(started.elapsed().as_millis() as u64, AtomicOrdering::Relaxed);
+
+        match result {
+            Ok(_) => {
+                let processed = stats.processed.fetch_add(1, AtomicOrdering::Relaxed) + 1;
+                info!(
+                    worker = %worker,
+                    task_id = %task_id,
+                    priority = ?priority,
+                    tasks_processed = processed,
+                    "Task completed"
+                );
+                
+                // Persist as completed
+                if let Err(e) = Self::persist_task_status(&db, &priority_task.task, job_id.clone(), TaskStatus::Completed, warnings).await {
+                    warn!(worker = %worker, task_id = %task_id, error = %e, "Failed to persist completion");
+                }
+                Self::record_job_event(&db, worker, job_id.as_deref(), JobTaskEvent::Completed, events).await;
+            }
+            Err(AppError::Canceled(_)) if shutdown.is_cancelled() => {
+                info!(
+                    worker = %worker,
+                    task_id = %task_id,
+                    priority = ?priority,
+                    "Task interrupted by shutdown, kept pending"
+                );
+
+                if let Err(e) = Self::persist_task_status(&db, &priority_task.task, job_id.clone(), TaskStatus::Pending, warnings).await {
+                    warn!(worker = %worker, task_id = %task_id, error = %e, "Failed to persist interrupted task");
+                }
+                Self::record_job_event(&db, worker, job_id.as_deref(), JobTaskEvent::Interrupted, events).await;
+            }
+            Err(e) => {
+                stats.failed.fetch_add(1, AtomicOrdering::Relaxed);
+                error!(
+                    worker = %worker,
+                    task_id = %task_id,
+                    priority = ?priority,
+                    error = %e,
+                    "Task failed"
+                );
+                
+                // Persist as failed
+                let error = e.to_string();
+                let status = TaskStatus::Failed { error: error.clone() };
+                if let Err(e) = Self::persist_task_status(&db, &priority_task.task, job_id.clone(), status, warnings).await {
+                    warn!(worker = %worker, task_id = %task_id, error = %e, "Failed to persist failure");
+                }
+                let event = JobTaskEvent::Failed {
+                    task_id: &task_id,
+                    task_name: priority_task.task.name(),
+                    error: &error,
+                };
+                Self::record_job_event(&db, worker, job_id.as_deref(), event, events).await;
+            }
+        }
+    }
+
+    async fn record_job_event(
+        db: &DatabaseInstance,
+        worker: &str,
+        job_id: Option<&str>,
+        event: JobTaskEvent<'_>,
+        events: Option<&EventBus>,
+    ) {
+        let Some(job_id) = job_id else { return };
+
+        match record_task_event(db, job_id, event).await {
+            Ok(Some(job)) => {
+                info!(worker = %worker, job_id = %job_id, status = ?job.status(), "Job finished");
+                if let Some(events) = events {
+                    job::publish_finished(&job, events);
+                }
+            }
+            Ok(None) => {}
+            Err(e) => warn!(worker = %worker, job_id = %job_id, error = %e, "Failed to update job"),
+        }
+    }
+
+    async fn persist_task_status(
+        db: &DatabaseInstance,
+        task: &Box<dyn Task>,
+        job_id: Option<String>,
+        status: TaskStatus,
+        warnings: Vec<String>,
+    ) -> Result<(), AppError> {
         let mut task_data = task.to_data();
         task_data.status = status;
+        task_data.job_id = job_id;
+        task_data.warnings = warnings;
         
-        let collection = self.db.db().collection::<TaskData>("task_queue");
+        let collection = db.db().collection::<TaskData>("task_queue");
         
         let filter = mongodb::bson::doc! { "id": &task_data.id };
         let options = mongodb::options::ReplaceOptions::builder().upsert(true).build();
         
         collection.replace_one(filter, task_data).with_options(options)
             .await
-            .map_err(|e| AppError::Module(format!("Failed to persist task: {}", e)))?;
+            .map_err(|e| AppError::Database(DatabaseError::Query(format!("Failed to persist task: {}", e))))?;
         
         Ok(())
     }
+}
 
-    async fn load_persisted_tasks(&self, queue: &mut BinaryHeap<PriorityTask>) -> Result<(), AppError> {
-        use mongodb::bson::doc;
-        
-        let collection = self.db.db().collection::<TaskData>("task_queue");
-        
-        // Find pending tasks
-        let filter = doc! { "status": "Pending" };
-        let mut cursor = collection.find(filter).await
-            .map_err(|e| AppError::Module(format!("Failed to load tasks: {}", e)))?;
-        
-        let mut loaded_count = 0;
-        
-        use futures::stream::StreamExt;
-        while let Some(result) = cursor.next().await {
-            match result {
-                Ok(task_data) => {
-                    // Recreate task from data (you'll need to implement task factory)
-                    info!(
-                        worker = %self.name,
-                        task_id = %task_data.id,
-                        task_name = %task_data.name,
-                        "Loaded persisted task"
-                    );
-                    loaded_count += 1;
-                    // Note: You'll need to implement a task factory to recreate tasks from TaskData
-                }
-                Err(e) => {
-                    warn!(worker = %self.name, error = %e, "Failed to deserialize task");
-                }
-            }
+/// Fail the tasks a previous run left pending or running. Tasks are not rebuilt from their
+/// persisted data, they are counted into their jobs as failed so the jobs can finish.
+/// Called once per library database on startup, before its queues accept tasks.
+pub async fn fail_interrupted_tasks(db: &DatabaseInstance, events: Option<&EventBus>) -> Result<u64, AppError> {
+    use futures::stream::StreamExt;
+    use mongodb::bson::doc;
+
+    const ERROR: &str = "Interrupted by a restart before completing";
+
+    let collection = db.db().collection::<TaskData>("task_queue");
+    let mut cursor = collection.find(doc! { "status": { "$in": ["Pending", "Running"] } }).await
+        .map_err(|e| AppError::Database(DatabaseError::Query(format!("Failed to load interrupted tasks: {}", e))))?;
+
+    let mut tasks = Vec::new();
+    while let Some(result) = cursor.next().await {
+        match result {
+            Ok(task) => tasks.push(task),
+            Err(e) => warn!(error = %e, "Failed to deserialize task"),
         }
-        
-        if loaded_count > 0 {
-            info!(worker = %self.name, count = loaded_count, "Loaded persisted tasks");
+    }
+
+    for task in &tasks {
+        let was_running = matches!(task.status, TaskStatus::Running);
+        let status = mongodb::bson::to_bson(&TaskStatus::Failed { error: ERROR.to_string() })
+            .map_err(|e| AppError::Database(DatabaseError::Query(format!("Failed to serialize task status: {}", e))))?;
+        collection.update_one(doc! { "id": &task.id }, doc! { "$set": { "status": status } }).await
+            .map_err(|e| AppError::Database(DatabaseError::Query(format!("Failed to persist interrupted task: {}", e))))?;
+
+        let event = if was_running {
+            JobTaskEvent::Failed { task_id: &task.id, task_name: &task.name, error: ERROR }
+        } else {
+            JobTaskEvent::Abandoned { task_id: &task.id, task_name: &task.name, error: ERROR }
+        };
+        QueueWorker::record_job_event(db, "startup", task.job_id.as_deref(), event, events).await;
+    }
+
+    if !tasks.is_empty() {
+        warn!(count = tasks.len(), "Failed tasks interrupted by the previous run");
+    }
+
+    Ok(tasks.len() as u64)
+}
+
+/// Remove the pending row of a task that was never queued
+async fn delete_pending_task(db: &DatabaseInstance, task_id: &str) -> Result<(), AppError> {
+    use mongodb::bson::doc;
+
+    db.db().collection::<TaskData>("task_queue")
+        .delete_one(doc! { "id": task_id, "status": "Pending" })
+        .await
+        .map_err(|e| AppError::Database(DatabaseError::Query(format!("Failed to delete task: {}", e))))?;
+    Ok(())
+}
+
+/// Get a persisted task by id
+pub async fn get_task(db: &DatabaseInstance, task_id: &str) -> Result<Option<TaskData>, AppError> {
+    use mongodb::bson::doc;
+
+    db.db().collection::<TaskData>("task_queue")
+        .find_one(doc! { "id": task_id })
+        .await
+        .map_err(|e| AppError::Database(DatabaseError::Query(format!("Failed to get task: {}", e))))
+}
+
+/// Find persisted tasks, newest first, optionally restricted to a job
+pub async fn find_tasks(
+    db: &DatabaseInstance,
+    job_id: Option<&str>,
+    limit: i64,
+) -> Result<Vec<TaskData>, AppError> {
+    use futures::stream::StreamExt;
+    use mongodb::bson::doc;
+
+    let collection = db.db().collection::<TaskData>("task_queue");
+
+    let filter = match job_id {
+        Some(job_id) => doc! { "job_id": job_id },
+        None => doc! {},
+    };
+
+    let mut cursor = collection
+        .find(filter)
+        .sort(doc! { "created_at": -1 })
+        .limit(limit)
+        .await
+        .map_err(|e| AppError::Database(DatabaseError::Query(format!("Failed to query tasks: {}", e))))?;
+
+    let mut tasks = Vec::new();
+    while let Some(result) = cursor.next().await {
+        match result {
+            Ok(task) => tasks.push(task),
+            Err(e) => warn!(error = %e, "Failed to deserialize task"),
         }
-        
-        Ok(())
     }
-}
\ No newline at end of file
+
+    Ok(tasks)
+}
@@ -0,0 +1,137 @@
+use std::any::Any;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use tracing::{info, warn};
+
+use crate::global::config::AppConfig;
+use crate::global::database::DatabaseInstance;
+use crate::global::events::EventBus;
+use crate::global::http::HttpClientManager;
+use crate::global::module::{ModuleHandle, ParentModule};
+use crate::global::supervisor::ModuleSupervisor;
+
+/// Resources available to module constructors
+pub struct ModuleContext {
+    pub config: Arc<AppConfig>,
+    pub db: Arc<DatabaseInstance>,
+    pub http_manager: HttpClientManager,
+    pub events: EventBus,
+}
+
+/// A constructed module, kept both as a runnable parent module and as its concrete type
+struct ModuleInstance {
+    parent: Arc<dyn ParentModule>,
+    any: Arc<dyn Any + Send + Sync>,
+}
+
+type ModuleConstructor = Box<dyn Fn(&ModuleContext) -> Option<ModuleInstance> + Send + Sync>;
+
+/// Registry of parent modules keyed by their config name (`[modules.<name>]`).
+/// Modules are constructed and started in registration order, only if enabled in config.
+pub struct ModuleRegistry {
+    constructors: Vec<(String, ModuleConstructor)>,
+}
+
+impl ModuleRegistry {
+    pub fn new() -> Self {
+        Self {
+            constructors: Vec::new(),
+        }
+    }
+
+    /// Register a parent module constructor.
+    /// The constructor may return None if the module can't be created (e.g. missing config).
+    pub fn register<M, F>(mut self, config_name: &str, constructor: F) -> Self
+    where
+        M: ParentModule + 'static,
+        F: Fn(&ModuleContext) -> Option<M> + Send + Sync + 'static,
+    {
+        let constructor: ModuleConstructor = Box::new(move |ctx| {
+            constructor(ctx).map(|module| {
+                let module = Arc::new(module);
+                ModuleInstance {
+                    parent: module.clone(),
+                    any: module,
+                }
+            })
+        });
+
+        self.constructors.push((config_name.to_string(), constructor));
+        self
+    }
+
+    /// Construct every enabled module and spawn it under the supervisor
+    pub async fn start_enabled(
+        &self,
+        config: Arc<AppConfig>,
+        db: Arc<DatabaseInstance>,
+        http_manager: HttpClientManager,
+        events: EventBus,
+        supervisor: &ModuleSupervisor,
+    ) -> StartedModules {
+        let mut started = StartedModules::default();
+
+        for name in config.modules.enabled() {
+            if !self.constructors.iter().any(|(registered, _)| registered == name) {
+                warn!(module = %name, "Module is enabled but not implemented yet");
+            }
+        }
+
+        for (name, constructor) in &self.constructors {
+            if !config.is_parent_module_enabled(name) {
+                info!(module = %name, "Module is disabled in config");
+                continue;
+            }
+
+            info!(module = %name, "Initializing module");
+
+            let ctx = ModuleContext {
+                config: config.clone(),
+                db: db.clone(),
+                http_manager: http_manager.clone(),
+                events: events.clone(),
+            };
+
+            let Some(instance) = constructor(&ctx) else {
+                warn!(module = %name, "Module could not be created");
+                continue;
+            };
+
+            let handle = supervisor.spawn(name, instance.parent.clone(), db.clone()).await;
+            started.handles.push(handle.clone());
+            started.named_handles.insert(name.clone(), handle);
+            started.instances.insert(name.clone(), instance.any);
+        }
+
+        started
+    }
+}
+
+impl Default for ModuleRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Modules started by the registry
+#[derive(Default)]
+pub struct StartedModules {
+    pub handles: Vec<ModuleHandle>,
+    named_handles: HashMap<String, ModuleHandle>,
+    instances: HashMap<String, Arc<dyn Any + Send + Sync>>,
+}
+
+impl StartedModules {
+    /// Get the control handle of a started module by config name
+    pub fn handle(&self, config_name: &str) -> Option<ModuleHandle> {
+        self.named_handles.get(config_name).cloned()
+    }
+
+    /// Get a started module by config name with its concrete type
+    pub fn get<M: Send + Sync + 'static>(&self, config_name: &str) -> Option<Arc<M>> {
+        self.instances
+            .get(config_name)
+            .and_then(|module| module.clone().downcast::<M>().ok())
+    }
+}
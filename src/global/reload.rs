@@ -0,0 +1,210 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use arc_swap::ArcSwap;
+use notify::{EventKind, RecursiveMode, Watcher};
+use tokio::sync::mpsc;
+use tracing::{debug, error, info, warn};
+
+use crate::anime::bootstrap::BootstrapJob;
+use crate::global::config::AppConfig;
+use crate::global::error::ConfigError;
+
+/// Configuration shared across the application, swapped atomically on reload
+pub type SharedConfig = Arc<ArcSwap<AppConfig>>;
+
+/// Callback invoked with the new configuration after a successful reload
+pub type ReloadCallback = Box<dyn Fn(&AppConfig) + Send + Sync>;
+
+/// Time to wait for further file events before reloading,
+/// editors often write a file in several steps
+const DEBOUNCE_DELAY: Duration = Duration::from_millis(500);
+
+/// Watches the configuration file and propagates hot-reloadable settings
+pub struct ConfigWatcher {
+    config: SharedConfig,
+    path: PathBuf,
+    callbacks: Vec<ReloadCallback>,
+}
+
+impl ConfigWatcher {
+    pub fn new(config: SharedConfig, path: impl AsRef<Path>) -> Self {
+        Self {
+            config,
+            path: path.as_ref().to_path_buf(),
+            callbacks: Vec::new(),
+        }
+    }
+
+    /// Register a callback run after each successful reload
+    pub fn on_reload(mut self, callback: impl Fn(&AppConfig) + Send + Sync + 'static) -> Self {
+        self.callbacks.push(Box::new(callback));
+        self
+    }
+
+    /// Start watching the configuration file in the background
+    pub fn spawn(self) -> Result<(), ConfigError> {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let file_name = self.path.file_name().map(|n| n.to_os_string());
+
+        let mut watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+            match res {
+                Ok(event) => {
+                    let relevant = matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_))
+                        && event.paths.iter().any(|p| p.file_name().map(|n| n.to_os_string()) == file_name);
+                    if relevant {
+                        let _ = tx.send(());
+                    }
+                }
+                Err(e) => warn!(error = %e, "Configuration watcher error"),
+            }
+        })
+        .map_err(|e| ConfigError::Invalid(format!("failed to create config watcher: {}", e)))?;
+
+        // Watch the parent directory so that editors replacing the file are detected
+        let watch_dir = match self.path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+
+        watcher
+            .watch(&watch_dir, RecursiveMode::NonRecursive)
+            .map_err(|e| ConfigError::Invalid(format!("failed to watch {}: {}", watch_dir.display(), e)))?;
+
+        info!(path = %self.path.display(), "Watching configuration file for changes");
+
+        tokio::spawn(async move {
+            // Keep the watcher alive for as long as the task runs
+            let _watcher = watcher;
+
+            while rx.recv().await.is_some() {
+                tokio::time::sleep(DEBOUNCE_DELAY).await;
+                while rx.try_recv().is_ok() {}
+
+                self.reload();
+            }
+        });
+
+        Ok(())
+    }
+
+    fn reload(&self) {
+        debug!(path = %self.path.display(), "Configuration file changed, reloading");
+
+        let new_config = match AppConfig::load() {
+            Ok(cfg) => cfg,
+            Err(e) => {
+                error!(error = %e, "Failed to reload configuration, keeping previous settings");
+                return;
+            }
+        };
+
+        let old_config = self.config.load();
+        warn_restart_required(&old_config, &new_config);
+
+        for callback in &self.callbacks {
+            callback(&new_config);
+        }
+
+        self.config.store(Arc::new(new_config));
+        info!("Configuration reloaded");
+    }
+}
+
+/// Log settings that changed but are only read at startup
+fn warn_restart_required(old: &AppConfig, new: &AppConfig) {
+    let mut changed = Vec::new();
+
+    if old.database.host != new.database.host
+        || old.database.port != new.database.port
+        || old.database.name != new.database.name
+    {
+        changed.push("database");
+    }
+    if old.api.enabled != new.api.enabled || old.api.host != new.api.host || old.api.port != new.api.port {
+        changed.push("api");
+    }
+    if old.modules.anime.enabled != new.modules.anime.enabled
+        || old.modules.manga.enabled != new.modules.manga.enabled
+        || old.modules.video.enabled != new.modules.video.enabled
+        || old.modules.music.enabled != new.modules.music.enabled
+    {
+        changed.push("modules");
+    }
+    if old.http.timeout_seconds != new.http.timeout_seconds
+        || ["default", "my_anime_list", "jikan", "anilist"].iter().any(|name| old.get_user_agent(name) != new.get_user_agent(name))
+    {
+        changed.push("http.timeout_seconds/user_agent/contact");
+    }
+    if old.http.mode != new.http.mode || old.http.fixtures_dir != new.http.fixtures_dir {
+        changed.push("http.mode/fixtures_dir");
+    }
+    if old.http.archive.enabled != new.http.archive.enabled
+        || old.http.archive.retention_days != new.http.archive.retention_days
+    {
+        changed.push("http.archive");
+    }
+    if old.disk.check_interval_seconds != new.disk.check_interval_seconds
+        || old.disk.min_free_mb != new.disk.min_free_mb
+    {
+        changed.push("disk");
+    }
+    if old.bootstrap.jobs.iter().map(BootstrapJob::id).ne(new.bootstrap.jobs.iter().map(BootstrapJob::id)) {
+        changed.push("bootstrap");
+    }
+    if old.queue_sizes() != new.queue_sizes() {
+        changed.push("queue_size");
+    }
+    if old.anime.auto_pictures != new.anime.auto_pictures
+        || old.anime.mal_backfill != new.anime.mal_backfill
+        || section_changed(&old.anime.fallback, &new.anime.fallback)
+    {
+        changed.push("anime.auto_pictures/mal_backfill/fallback");
+    }
+    if old.picture.storage_path != new.picture.storage_path || old.picture.collect_garbage != new.picture.collect_garbage {
+        changed.push("picture.storage_path/collect_garbage");
+    }
+    if old.picture.variants != new.picture.variants
+        || old.picture.streaming_thumbnails != new.picture.streaming_thumbnails
+        || old.picture.max_buffered_mb != new.picture.max_buffered_mb
+        || old.picture.storage_mode != new.picture.storage_mode
+    {
+        changed.push("picture.variants/streaming_thumbnails/max_buffered_mb/storage_mode");
+    }
+    if old.video.storage_path != new.video.storage_path
+        || old.video.command != new.video.command
+        || old.video.format != new.video.format
+        || old.video.timeout_minutes != new.video.timeout_minutes
+    {
+        changed.push("video.storage_path/command/format/timeout_minutes");
+    }
+    if old.music.anisongdb != new.music.anisongdb || old.music.anisongdb_url != new.music.anisongdb_url {
+        changed.push("music.anisongdb/anisongdb_url");
+    }
+    if old.features.webhooks != new.features.webhooks {
+        changed.push("features.webhooks");
+    }
+
+    // Sections only read at startup
+    let sections = [
+        ("app.logging", section_changed(&old.app.logging, &new.app.logging)),
+        ("api.compression", section_changed(&old.api.compression, &new.api.compression)),
+        ("api.namespaces", section_changed(&old.api.namespaces, &new.api.namespaces)),
+        ("secrets", section_changed(&old.secrets, &new.secrets)),
+        ("alerting", section_changed(&old.alerting, &new.alerting)),
+        ("webhooks", section_changed(&old.webhooks, &new.webhooks)),
+        ("integrations", section_changed(&old.integrations, &new.integrations)),
+        ("supervisor", section_changed(&old.supervisor, &new.supervisor)),
+    ];
+    changed.extend(sections.into_iter().filter(|(_, changed)| *changed).map(|(name, _)| name));
+
+    if !changed.is_empty() {
+        warn!(sections = ?changed, "Changed settings require a restart to take effect");
+    }
+}
+
+/// Whether a configuration section differs, compared on its serialized form
+fn section_changed<T: serde::Serialize>(old: &T, new: &T) -> bool {
+    serde_json::to_value(old).ok() != serde_json::to_value(new).ok()
+}
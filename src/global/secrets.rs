@@ -0,0 +1,233 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::global::error::ConfigError;
+
+/// A source of secret values, selected by the scheme prefix of a secret reference
+/// (e.g. `env:MAL_API_KEY`, `file:/run/secrets/mal`, `vault:secret/data/media#mal`)
+pub trait SecretsProvider: Send + Sync {
+    /// Scheme handled by this provider (without the trailing colon)
+    fn scheme(&self) -> &str;
+
+    /// Resolve the part of the reference after the scheme prefix
+    fn resolve(&self, reference: &str) -> Result<String, ConfigError>;
+}
+
+/// Reads secrets from environment variables: `env:VARIABLE_NAME`
+pub struct EnvSecretsProvider;
+
+impl SecretsProvider for EnvSecretsProvider {
+    fn scheme(&self) -> &str {
+        "env"
+    }
+
+    fn resolve(&self, reference: &str) -> Result<String, ConfigError> {
+        std::env::var(reference)
+            .map_err(|_| ConfigError::Invalid(format!("environment variable '{}' is not set", reference)))
+    }
+}
+
+/// Reads secrets from files (e.g. Docker secrets): `file:/run/secrets/name`
+pub struct FileSecretsProvider;
+
+impl SecretsProvider for FileSecretsProvider {
+    fn scheme(&self) -> &str {
+        "file"
+    }
+
+    fn resolve(&self, reference: &str) -> Result<String, ConfigError> {
+        read_secret_file(reference)
+    }
+}
+
+/// Reads secrets from a HashiCorp Vault KV v2 engine: `vault:<path>#<key>`
+/// where path is the full API path (e.g. `secret/data/media-collector`)
+pub struct VaultSecretsProvider {
+    address: String,
+    token: String,
+}
+
+impl VaultSecretsProvider {
+    pub fn new(address: impl Into<String>, token: impl Into<String>) -> Self {
+        Self {
+            address: address.into().trim_end_matches('/').to_string(),
+            token: token.into(),
+        }
+    }
+
+    async fn fetch(&self, path: &str, key: &str) -> Result<String, ConfigError> {
+        let url = format!("{}/v1/{}", self.address, path.trim_start_matches('/'));
+
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(10))
+            .build()
+            .map_err(|e| ConfigError::Invalid(format!("failed to build Vault client: {}", e)))?;
+
+        let response = client
+            .get(&url)
+            .header("X-Vault-Token", &self.token)
+            .send()
+            .await
+            .map_err(|e| ConfigError::Invalid(format!("Vault request to '{}' failed: {}", path, e)))?;
+
+        if !response.status().is_success() {
+            return Err(ConfigError::Invalid(format!(
+                "Vault returned status {} for '{}'",
+                response.status(),
+                path
+            )));
+        }
+
+        let body: serde_json::Value = response
+            .json()
+            .await
+            .map_err(|e| ConfigError::Invalid(format!("invalid Vault response for '{}': {}", path, e)))?;
+
+        // KV v2 nests values under data.data, KV v1 directly under data
+        let data = body.get("data").map(|d| d.get("data").unwrap_or(d));
+
+        data.and_then(|d| d.get(key))
+            .and_then(|v| v.as_str())
+            .map(|v| v.to_string())
+            .ok_or_else(|| ConfigError::Invalid(format!("key '{}' not found in Vault secret '{}'", key, path)))
+    }
+}
+
+impl SecretsProvider for VaultSecretsProvider {
+    fn scheme(&self) -> &str {
+        "vault"
+    }
+
+    fn resolve(&self, reference: &str) -> Result<String, ConfigError> {
+        let (path, key) = reference.split_once('#').ok_or_else(|| {
+            ConfigError::Invalid(format!("Vault reference '{}' must be in the form <path>#<key>", reference))
+        })?;
+
+        // Configuration is loaded synchronously, possibly from inside the Tokio runtime,
+        // so run the request on a dedicated thread with its own runtime
+        std::thread::scope(|scope| {
+            scope
+                .spawn(|| {
+                    let runtime = tokio::runtime::Builder::new_current_thread()
+                        .enable_all()
+                        .build()
+                        .map_err(|e| ConfigError::Invalid(format!("failed to start Vault runtime: {}", e)))?;
+                    runtime.block_on(self.fetch(path, key))
+                })
+                .join()
+                .map_err(|_| ConfigError::Invalid("Vault resolver thread panicked".to_string()))?
+        })
+    }
+}
+
+/// Optional `[secrets]` config section
+#[derive(Clone, Deserialize, Serialize, Default)]
+pub struct SecretsConfig {
+    /// Vault server address, falls back to the VAULT_ADDR environment variable
+    #[serde(default)]
+    pub vault_address: Option<String>,
+    /// Vault token, falls back to the VAULT_TOKEN environment variable
+    #[serde(default)]
+    pub vault_token: Option<String>,
+    /// Path to a file containing the Vault token
+    #[serde(default)]
+    pub vault_token_file: Option<String>,
+}
+
+impl std::fmt::Debug for SecretsConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SecretsConfig")
+            .field("vault_address", &self.vault_address)
+            .field("vault_token", &redacted(&self.vault_token))
+            .field("vault_token_file", &self.vault_token_file)
+            .finish()
+    }
+}
+
+/// Printed instead of a secret value in the Debug output of config sections
+pub const REDACTED: &str = "***";
+
+/// Debug value of an optional secret, tells whether it is set without showing it
+pub fn redacted(secret: &Option<String>) -> Option<&'static str> {
+    secret.as_ref().map(|_| REDACTED)
+}
+
+/// Registry of secrets providers keyed by scheme
+pub struct SecretResolver {
+    providers: HashMap<String, Box<dyn SecretsProvider>>,
+}
+
+impl SecretResolver {
+    pub fn new() -> Self {
+        Self {
+            providers: HashMap::new(),
+        }
+    }
+
+    /// Resolver with the env and file providers, plus Vault when it is configured
+    pub fn from_config(config: &SecretsConfig) -> Result<Self, ConfigError> {
+        let mut resolver = Self::new()
+            .with_provider(EnvSecretsProvider)
+            .with_provider(FileSecretsProvider);
+
+        let address = config.vault_address.clone()
+            .or_else(|| std::env::var("VAULT_ADDR").ok());
+
+        let token = match &config.vault_token_file {
+            Some(path) => Some(read_secret_file(path)?),
+            None => config.vault_token.clone().or_else(|| std::env::var("VAULT_TOKEN").ok()),
+        };
+
+        if let (Some(address), Some(token)) = (address, token) {
+            resolver = resolver.with_provider(VaultSecretsProvider::new(address, token));
+        }
+
+        Ok(resolver)
+    }
+
+    pub fn with_provider(mut self, provider: impl SecretsProvider + 'static) -> Self {
+        self.providers.insert(provider.scheme().to_string(), Box::new(provider));
+        self
+    }
+
+    /// Resolve a config value: values prefixed by a registered scheme are looked up
+    /// through the matching provider, anything else is returned unchanged
+    pub fn resolve_value(&self, value: &str) -> Result<String, ConfigError> {
+        if let Some((scheme, reference)) = value.split_once(':') {
+            if let Some(provider) = self.providers.get(scheme) {
+                return provider.resolve(reference);
+            }
+            if scheme == "vault" {
+                return Err(ConfigError::Invalid(
+                    "Vault secret referenced but no Vault address/token is configured".to_string(),
+                ));
+            }
+        }
+
+        Ok(value.to_string())
+    }
+
+    /// Resolve a secret that may be given inline or through a `*_file` path.
+    /// The file path takes precedence over the inline value.
+    pub fn resolve_field(&self, value: &str, file: Option<&str>) -> Result<String, ConfigError> {
+        match file {
+            Some(path) if !path.is_empty() => read_secret_file(path),
+            _ => self.resolve_value(value),
+        }
+    }
+}
+
+impl Default for SecretResolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Read a secret file, trimming the trailing newline most tools add
+fn read_secret_file(path: &str) -> Result<String, ConfigError> {
+    std::fs::read_to_string(path)
+        .map(|s| s.trim().to_string())
+        .map_err(|e| ConfigError::Invalid(format!("failed to read secret file '{}': {}", path, e)))
+}
@@ -0,0 +1,339 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use tokio::sync::{mpsc, RwLock};
+use tracing::{error, info, warn};
+
+use crate::global::database::DatabaseInstance;
+use crate::global::module::{ModuleHandle, ModuleMessage, ParentModule, HEARTBEAT_STALE_AFTER};
+use crate::global::queue::QueueStats;
+
+/// `[supervisor]` config section: restart policy of crashed parent modules
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct SupervisorConfig {
+    /// Restarts allowed before a module is given up on
+    #[serde(default = "default_max_restarts")]
+    pub max_restarts: u32,
+    #[serde(default = "default_base_backoff_ms")]
+    pub base_backoff_ms: u64,
+    #[serde(default = "default_max_backoff_ms")]
+    pub max_backoff_ms: u64,
+    /// A run lasting this long counts as stable and resets the restart count
+    #[serde(default = "default_reset_after_secs")]
+    pub reset_after_secs: u64,
+}
+
+fn default_max_restarts() -> u32 {
+    5
+}
+
+fn default_base_backoff_ms() -> u64 {
+    1000
+}
+
+fn default_max_backoff_ms() -> u64 {
+    60000
+}
+
+fn default_reset_after_secs() -> u64 {
+    3600
+}
+
+impl Default for SupervisorConfig {
+    fn default() -> Self {
+        Self {
+            max_restarts: default_max_restarts(),
+            base_backoff_ms: default_base_backoff_ms(),
+            max_backoff_ms: default_max_backoff_ms(),
+            reset_after_secs: default_reset_after_secs(),
+        }
+    }
+}
+
+impl SupervisorConfig {
+    /// Exponential backoff before the given restart attempt (1-based)
+    fn backoff(&self, attempt: u32) -> Duration {
+        let exponential = self.base_backoff_ms.saturating_mul(2_u64.saturating_pow(attempt.saturating_sub(1)));
+        Duration::from_millis(exponential.min(self.max_backoff_ms))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModuleState {
+    Running,
+    /// Running but not processing tasks
+    Paused,
+    Restarting,
+    /// Exceeded the restart policy
+    Failed,
+    Stopped,
+}
+
+/// Runtime status of a supervised parent module
+#[derive(Debug, Clone, Serialize)]
+pub struct ModuleStatus {
+    pub name: String,
+    pub config_name: String,
+    pub state: ModuleState,
+    pub restarts: u32,
+    pub last_error: Option<String>,
+    pub started_at: chrono::DateTime<chrono::Utc>,
+    /// Last heartbeat reported by the module loop
+    pub last_active: Option<chrono::DateTime<chrono::Utc>>,
+    /// Queue counters, as reported by the last heartbeat
+    pub items_processed: u64,
+    pub items_failed: u64,
+    pub tasks_pending: u64,
+    pub tasks_running: u64,
+    /// Running but no heartbeat within HEARTBEAT_STALE_AFTER
+    pub stalled: bool,
+}
+
+impl ModuleStatus {
+    /// Recompute the stalled flag from the last heartbeat
+    pub fn refresh_stalled(&mut self) {
+        let reference = self.last_active.unwrap_or(self.started_at);
+        let silent_for = (chrono::Utc::now() - reference).to_std().unwrap_or_default();
+        let looping = matches!(self.state, ModuleState::Running | ModuleState::Paused);
+        self.stalled = looping && silent_for > HEARTBEAT_STALE_AFTER;
+    }
+}
+
+/// Handle given to a running parent module to report that its loop is alive
+#[derive(Clone)]
+pub struct Heartbeat {
+    module_name: String,
+    statuses: ModuleStatuses,
+    db: Arc<DatabaseInstance>,
+}
+
+impl Heartbeat {
+    /// Record activity in the shared status map and the module_state collection
+    pub async fn beat(&self, queue: &QueueStats) {
+        if let Some(status) = self.statuses.write().await.get_mut(&self.module_name) {
+            status.last_active = Some(chrono::Utc::now());
+            status.items_processed = queue.processed();
+            status.items_failed = queue.failed();
+            status.tasks_pending = queue.pending();
+            status.tasks_running = queue.running();
+            status.stalled = false;
+        }
+
+        if let Err(e) = self.db.update_module_heartbeat(&self.module_name).await {
+            warn!(module = %self.module_name, error = %e, "Failed to record heartbeat");
+        }
+    }
+}
+
+/// Status of all supervised modules, keyed by module name
+pub type ModuleStatuses = Arc<RwLock<HashMap<String, ModuleStatus>>>;
+
+/// Runs parent modules and restarts them when they crash
+#[derive(Clone)]
+pub struct ModuleSupervisor {
+    config: SupervisorConfig,
+    statuses: ModuleStatuses,
+}
+
+impl ModuleSupervisor {
+    pub fn new(config: SupervisorConfig) -> Self {
+        Self {
+            config,
+            statuses: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    /// Shared status map, exposed through the modules API
+    pub fn statuses(&self) -> ModuleStatuses {
+        self.statuses.clone()
+    }
+
+    /// Snapshot of all module statuses with up to date stalled flags, sorted by name
+    pub async fn snapshot(statuses: &ModuleStatuses) -> Vec<ModuleStatus> {
+        let mut modules: Vec<ModuleStatus> = statuses.read().await.values().cloned().collect();
+        for module in modules.iter_mut() {
+            module.refresh_stalled();
+        }
+        modules.sort_by(|a, b| a.name.cmp(&b.name));
+        modules
+    }
+
+    /// Spawn a parent module under supervision.
+    /// Messages sent through the returned handle are forwarded to the current module instance.
+    pub async fn spawn(
+        &self,
+        config_name: &str,
+        module: Arc<dyn ParentModule>,
+        db: Arc<DatabaseInstance>,
+    ) -> ModuleHandle {
+        let (tx, rx) = mpsc::channel(100);
+        let name = module.name().to_string();
+
+        info!(module = %name, "Spawning parent module");
+
+        self.statuses.write().await.insert(name.clone(), ModuleStatus {
+            name: name.clone(),
+            config_name: config_name.to_string(),
+            state: ModuleState::Running,
+            restarts: 0,
+            last_error: None,
+            started_at: chrono::Utc::now(),
+            last_active: None,
+            items_processed: 0,
+            items_failed: 0,
+            tasks_pending: 0,
+            tasks_running: 0,
+            stalled: false,
+        });
+
+        let supervisor = self.clone();
+        tokio::spawn(async move {
+            supervisor.supervise(module, db, rx).await;
+        });
+
+        ModuleHandle { name, tx }
+    }
+
+    async fn supervise(
+        &self,
+        module: Arc<dyn ParentModule>,
+        db: Arc<DatabaseInstance>,
+        mut rx: mpsc::Receiver<ModuleMessage>,
+    ) {
+        let name = module.name().to_string();
+        let mut shutting_down = false;
+        let mut paused = false;
+        let mut restarts = 0;
+        // Messages received while waiting to restart, delivered to the next run
+        let mut deferred = Vec::new();
+
+        loop {
+            let (inner_tx, inner_rx) = mpsc::channel(100);
+            let run_module = module.clone();
+            let run_db = db.clone();
+            let heartbeat = Heartbeat {
+                module_name: name.clone(),
+                statuses: self.statuses.clone(),
+                db: db.clone(),
+            };
+            let run_started = tokio::time::Instant::now();
+            let mut join = tokio::spawn(async move { run_module.run(run_db, inner_rx, heartbeat).await });
+
+            for msg in deferred.drain(..) {
+                if let Err(e) = inner_tx.try_send(msg) {
+                    warn!(module = %name, error = %e, "Failed to forward message to module");
+                }
+            }
+
+            // Forward control messages until the module exits
+            let outcome = loop {
+                tokio::select! {
+                    result = &mut join => break result,
+                    msg = rx.recv(), if !shutting_down => {
+                        let msg = msg.unwrap_or(ModuleMessage::Shutdown);
+                        match msg {
+                            ModuleMessage::Shutdown => shutting_down = true,
+                            ModuleMessage::Pause => {
+                                paused = true;
+                                self.set_state(&name, ModuleState::Paused, None).await;
+                            }
+                            ModuleMessage::Resume => {
+                                paused = false;
+                                self.set_state(&name, ModuleState::Running, None).await;
+                            }
+                            ModuleMessage::UpdateConfig(_) | ModuleMessage::Custom(_) => {}
+                        }
+                        if let Err(e) = inner_tx.try_send(msg) {
+                            warn!(module = %name, error = %e, "Failed to forward message to module");
+                        }
+                    }
+                }
+            };
+
+            let error = match outcome {
+                Ok(Ok(())) => None,
+                Ok(Err(e)) => Some(e.to_string()),
+                Err(e) => Some(format!("module panicked: {}", e)),
+            };
+
+            let Some(error) = error else {
+                info!(module = %name, "Module stopped");
+                self.set_state(&name, ModuleState::Stopped, None).await;
+                return;
+            };
+
+            error!(module = %name, error = %error, "Module terminated with error");
+
+            if shutting_down {
+                self.set_state(&name, ModuleState::Stopped, Some(error)).await;
+                return;
+            }
+
+            // Crashes far apart are not a crash loop, start counting again
+            if run_started.elapsed() >= Duration::from_secs(self.config.reset_after_secs) {
+                restarts = 0;
+            }
+
+            if restarts >= self.config.max_restarts {
+                error!(
+                    module = %name,
+                    restarts = restarts,
+                    "Module exceeded its restart limit, giving up"
+                );
+                self.set_state(&name, ModuleState::Failed, Some(error)).await;
+                return;
+            }
+
+            restarts += 1;
+            let delay = self.config.backoff(restarts);
+            warn!(module = %name, attempt = restarts, delay = ?delay, "Restarting module");
+
+            self.set_state(&name, ModuleState::Restarting, Some(error)).await;
+            if let Some(status) = self.statuses.write().await.get_mut(&name) {
+                status.restarts = restarts;
+            }
+
+            // Wait for the backoff, unless a shutdown is requested meanwhile
+            let backoff = tokio::time::sleep(delay);
+            tokio::pin!(backoff);
+            loop {
+                tokio::select! {
+                    _ = &mut backoff => break,
+                    msg = rx.recv() => match msg {
+                        None | Some(ModuleMessage::Shutdown) => {
+                            self.set_state(&name, ModuleState::Stopped, None).await;
+                            return;
+                        }
+                        Some(msg) => {
+                            match msg {
+                                ModuleMessage::Pause => paused = true,
+                                ModuleMessage::Resume => paused = false,
+                                _ => {}
+                            }
+                            deferred.push(msg);
+                        }
+                    }
+                }
+            }
+
+            // The module keeps its queue across restarts, so a pause still applies
+            let state = if paused { ModuleState::Paused } else { ModuleState::Running };
+            self.set_state(&name, state, None).await;
+            if let Some(status) = self.statuses.write().await.get_mut(&name) {
+                status.started_at = chrono::Utc::now();
+            }
+        }
+    }
+
+    async fn set_state(&self, name: &str, state: ModuleState, error: Option<String>) {
+        if let Some(status) = self.statuses.write().await.get_mut(name) {
+            status.state = state;
+            if error.is_some() {
+                status.last_error = error;
+            }
+        }
+    }
+}
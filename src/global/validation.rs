@@ -0,0 +1,215 @@
+use std::path::Path;
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::global::config::AppConfig;
+use crate::global::database::DatabaseInstance;
+
+/// Time allowed for the database reachability check
+const DATABASE_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CheckStatus {
+    Ok,
+    Warning,
+    Error,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CheckResult {
+    pub name: String,
+    pub status: CheckStatus,
+    pub message: String,
+}
+
+/// Structured result of a configuration dry-run
+#[derive(Debug, Clone, Serialize, Default)]
+pub struct ConfigReport {
+    pub checks: Vec<CheckResult>,
+}
+
+impl ConfigReport {
+    fn push(&mut self, name: impl Into<String>, status: CheckStatus, message: impl Into<String>) {
+        self.checks.push(CheckResult {
+            name: name.into(),
+            status,
+            message: message.into(),
+        });
+    }
+
+    /// True if no check reported an error (warnings are allowed)
+    pub fn is_ok(&self) -> bool {
+        self.checks.iter().all(|c| c.status != CheckStatus::Error)
+    }
+
+    /// Print a human readable report to stdout
+    pub fn print(&self) {
+        println!("Configuration check report");
+        println!("==========================");
+
+        for check in &self.checks {
+            let label = match check.status {
+                CheckStatus::Ok => "OK",
+                CheckStatus::Warning => "WARN",
+                CheckStatus::Error => "ERROR",
+            };
+            println!("[{:<5}] {:<24} {}", label, check.name, check.message);
+        }
+
+        let errors = self.checks.iter().filter(|c| c.status == CheckStatus::Error).count();
+        let warnings = self.checks.iter().filter(|c| c.status == CheckStatus::Warning).count();
+        println!();
+        println!("{} check(s), {} error(s), {} warning(s)", self.checks.len(), errors, warnings);
+    }
+}
+
+/// Validate the loaded configuration without starting any module or writing anything
+pub async fn check_config(config: &AppConfig) -> ConfigReport {
+    let mut report = ConfigReport::default();
+
+    check_child_modules(config, &mut report);
+    check_queues(config, &mut report);
+    check_storage_path("picture.storage_path", Path::new(&config.picture.storage_path), &mut report);
+    if config.is_parent_module_enabled("video") {
+        check_storage_path("video.storage_path", Path::new(&config.video.storage_path), &mut report);
+    }
+    check_database(config, &mut report).await;
+
+    report
+}
+
+/// Check API keys of enabled child modules
+fn check_child_modules(config: &AppConfig, report: &mut ConfigReport) {
+    let mut names: Vec<&String> = config.child_modules.keys().collect();
+    names.sort();
+
+    for name in names {
+        let module_config = &config.child_modules[name];
+        let check_name = format!("child_module.{}", name);
+
+        if !module_config.enabled {
+            report.push(check_name, CheckStatus::Ok, "disabled");
+            continue;
+        }
+
+        if module_config.rate_limit <= 0.0 {
+            report.push(
+                check_name,
+                CheckStatus::Error,
+                format!("rate_limit must be positive (got {})", module_config.rate_limit),
+            );
+            continue;
+        }
+
+        match config.validate_child_module(name, module_config.requires_api_key) {
+            Ok(()) => report.push(check_name, CheckStatus::Ok, "enabled"),
+            Err(e) => report.push(check_name, CheckStatus::Error, e.to_string()),
+        }
+    }
+}
+
+/// Check the queue size and concurrency of each parent module, a queue needs room for one task
+fn check_queues(config: &AppConfig, report: &mut ConfigReport) {
+    for (section, queue_size) in config.queue_sizes() {
+        let check_name = format!("{}.queue_size", section);
+        if queue_size == 0 {
+            report.push(check_name, CheckStatus::Error, "must be at least 1");
+        } else {
+            report.push(check_name, CheckStatus::Ok, queue_size.to_string());
+        }
+    }
+
+    let concurrencies = [
+        ("picture", config.picture.concurrency),
+        ("video", config.video.concurrency),
+        ("music", config.music.concurrency),
+    ];
+    for (section, concurrency) in concurrencies {
+        let check_name = format!("{}.concurrency", section);
+        if concurrency == 0 {
+            report.push(check_name, CheckStatus::Warning, "0 runs one task at a time, like 1");
+        } else {
+            report.push(check_name, CheckStatus::Ok, concurrency.to_string());
+        }
+    }
+}
+
+/// Check that a storage directory is writable, or can be created, without touching it
+fn check_storage_path(check_name: &str, storage_path: &Path, report: &mut ConfigReport) {
+    match check_storage_accessible(storage_path) {
+        Ok(message) => report.push(check_name, CheckStatus::Ok, message),
+        Err(message) => report.push(check_name, CheckStatus::Error, message),
+    }
+}
+
+/// Check from metadata that a directory is writable, or that its nearest existing parent is
+/// so it can be created on startup
+fn check_storage_accessible(storage_path: &Path) -> Result<String, String> {
+    let mut existing = storage_path;
+    while !existing.exists() {
+        existing = match existing.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+    }
+
+    let metadata = std::fs::metadata(existing)
+        .map_err(|e| format!("cannot read {}: {}", existing.display(), e))?;
+    if !metadata.is_dir() {
+        return Err(format!("{} is not a directory", existing.display()));
+    }
+    if metadata.permissions().readonly() {
+        return Err(format!("{} is read-only", existing.display()));
+    }
+
+    if existing == storage_path {
+        Ok(format!("{} is writable", storage_path.display()))
+    } else {
+        Ok(format!("{} will be created in {}", storage_path.display(), existing.display()))
+    }
+}
+
+/// Create the directory if needed and probe it with a small write
+pub fn check_storage_writable(storage_path: &Path) -> Result<(), String> {
+    std::fs::create_dir_all(storage_path)
+        .map_err(|e| format!("cannot create {}: {}", storage_path.display(), e))?;
+
+    let probe = storage_path.join(".write_check");
+    std::fs::write(&probe, b"ok")
+        .map_err(|e| format!("{} is not writable: {}", storage_path.display(), e))?;
+    let _ = std::fs::remove_file(&probe);
+
+    Ok(())
+}
+
+/// Check that MongoDB answers a ping within the timeout
+async fn check_database(config: &AppConfig, report: &mut ConfigReport) {
+    let check_name = "database";
+    let db = &config.database;
+
+    let ping = DatabaseInstance::ping(db);
+    match tokio::time::timeout(DATABASE_CHECK_TIMEOUT, ping).await {
+        Ok(Ok(())) => report.push(
+            check_name,
+            CheckStatus::Ok,
+            format!("reachable at {}:{}", db.host, db.port),
+        ),
+        Ok(Err(e)) => report.push(
+            check_name,
+            CheckStatus::Error,
+            format!("{}:{} unreachable: {}", db.host, db.port, e),
+        ),
+        Err(_) => report.push(
+            check_name,
+            CheckStatus::Error,
+            format!(
+                "{}:{} did not answer within {}s",
+                db.host,
+                db.port,
+                DATABASE_CHECK_TIMEOUT.as_secs()
+            ),
+        ),
+    }
+}
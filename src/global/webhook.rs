@@ -0,0 +1,233 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use tokio::sync::{broadcast, Semaphore};
+use tracing::{debug, error, info, warn};
+
+use crate::global::error::HttpError;
+use crate::global::events::{DataEvent, EventBus};
+use crate::global::secrets::redacted;
+
+/// Delivery attempts per event and endpoint
+const MAX_DELIVERY_ATTEMPTS: u32 = 3;
+
+/// Deliveries in flight at the same time, across all endpoints
+const MAX_CONCURRENT_DELIVERIES: usize = 16;
+
+/// Timeout of a single delivery request
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// `[webhooks]` config section: data event notifications for downstream systems
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+pub struct WebhooksConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub endpoints: Vec<WebhookEndpoint>,
+}
+
+#[derive(Clone, Deserialize, Serialize)]
+pub struct WebhookEndpoint {
+    pub url: String,
+    /// HMAC-SHA256 key used to sign payloads, accepts secret references (`env:`, `file:`, `vault:`)
+    #[serde(default)]
+    pub secret: Option<String>,
+    /// Event types sent to this endpoint (e.g. "anime_stored", "job_finished"), all when empty
+    #[serde(default)]
+    pub events: Vec<String>,
+}
+
+impl std::fmt::Debug for WebhookEndpoint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WebhookEndpoint")
+            .field("url", &self.url)
+            .field("secret", &redacted(&self.secret))
+            .field("events", &self.events)
+            .finish()
+    }
+}
+
+impl WebhookEndpoint {
+    fn accepts(&self, event: &DataEvent) -> bool {
+        self.events.is_empty() || self.events.iter().any(|kind| kind == event.kind())
+    }
+}
+
+/// JSON body POSTed to webhook endpoints
+#[derive(Debug, Serialize)]
+struct WebhookPayload<'a> {
+    id: String,
+    event: &'static str,
+    timestamp: chrono::DateTime<chrono::Utc>,
+    data: &'a DataEvent,
+}
+
+/// Forwards data events to the configured webhook endpoints as signed JSON payloads
+pub struct WebhookDispatcher {
+    client: reqwest::Client,
+    config: WebhooksConfig,
+}
+
+impl WebhookDispatcher {
+    pub fn new(client: reqwest::Client, config: WebhooksConfig) -> Self {
+        Self { client, config }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.config.enabled && !self.config.endpoints.is_empty()
+    }
+
+    /// Subscribe to the event bus and deliver events in the background
+    pub fn spawn(self, events: &EventBus) {
+        if !self.is_enabled() {
+            debug!("Webhooks disabled");
+            return;
+        }
+
+        for endpoint in &self.config.endpoints {
+            for kind in &endpoint.events {
+                if !DataEvent::KINDS.contains(&kind.as_str()) {
+                    warn!(url = %endpoint.url, event = %kind, "Webhook filter references an unknown event type");
+                }
+            }
+        }
+
+        info!(endpoints = self.config.endpoints.len(), "Webhook dispatcher started");
+
+        let mut rx = events.subscribe();
+        tokio::spawn(async move {
+            let deliveries = Arc::new(Semaphore::new(MAX_CONCURRENT_DELIVERIES));
+
+            loop {
+                let event = match rx.recv().await {
+                    Ok(event) => event,
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!(skipped = skipped, "Webhook dispatcher lagging, events dropped");
+                        continue;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                };
+
+                self.dispatch(&event, &deliveries).await;
+            }
+        });
+    }
+
+    async fn dispatch(&self, event: &DataEvent, deliveries: &Arc<Semaphore>) {
+        let endpoints: Vec<&WebhookEndpoint> = self.config.endpoints.iter()
+            .filter(|endpoint| endpoint.accepts(event))
+            .collect();
+        if endpoints.is_empty() {
+            return;
+        }
+
+        let payload = WebhookPayload {
+            id: uuid::Uuid::new_v4().to_string(),
+            event: event.kind(),
+            timestamp: chrono::Utc::now(),
+            data: event,
+        };
+        let body = match serde_json::to_string(&payload) {
+            Ok(body) => body,
+            Err(e) => {
+                error!(event = %event.kind(), error = %e, "Failed to serialize webhook payload");
+                return;
+            }
+        };
+
+        for endpoint in endpoints {
+            let Ok(permit) = deliveries.clone().acquire_owned().await else { return };
+
+            let delivery = Delivery {
+                client: self.client.clone(),
+                url: endpoint.url.clone(),
+                signature: endpoint.secret.as_deref().map(|secret| sign(secret, payload.timestamp.timestamp(), &body)),
+                event: payload.event,
+                id: payload.id.clone(),
+                timestamp: payload.timestamp.timestamp(),
+                body: body.clone(),
+            };
+
+            tokio::spawn(async move {
+                delivery.run().await;
+                drop(permit);
+            });
+        }
+    }
+}
+
+/// A payload to deliver to one endpoint
+struct Delivery {
+    client: reqwest::Client,
+    url: String,
+    signature: Option<String>,
+    event: &'static str,
+    id: String,
+    timestamp: i64,
+    body: String,
+}
+
+impl Delivery {
+    async fn run(&self) {
+        match self.send_with_retry().await {
+            Ok(()) => debug!(url = %self.url, event = %self.event, id = %self.id, "Webhook delivered"),
+            Err(e) => error!(url = %self.url, event = %self.event, id = %self.id, error = %e, "Failed to deliver webhook"),
+        }
+    }
+
+    async fn send_with_retry(&self) -> Result<(), HttpError> {
+        for attempt in 1..=MAX_DELIVERY_ATTEMPTS {
+            match self.send().await {
+                Ok(()) => return Ok(()),
+                Err(e) => {
+                    warn!(url = %self.url, attempt = attempt, error = %e, "Webhook delivery failed");
+                    if attempt < MAX_DELIVERY_ATTEMPTS {
+                        tokio::time::sleep(Duration::from_secs(2_u64.pow(attempt))).await;
+                    }
+                }
+            }
+        }
+
+        Err(HttpError::MaxRetriesExceeded)
+    }
+
+    async fn send(&self) -> Result<(), HttpError> {
+        let mut request = self.client
+            .post(&self.url)
+            .timeout(DELIVERY_TIMEOUT)
+            .header("Content-Type", "application/json")
+            .header("X-Webhook-Id", &self.id)
+            .header("X-Webhook-Event", self.event)
+            .header("X-Webhook-Timestamp", self.timestamp.to_string());
+
+        if let Some(signature) = &self.signature {
+            request = request.header("X-Webhook-Signature", signature);
+        }
+
+        let response = request.body(self.body.clone()).send().await?;
+        let status = response.status();
+
+        if !status.is_success() {
+            let message = response.text().await.unwrap_or_default();
+            return Err(HttpError::UnexpectedStatus {
+                status: status.as_u16(),
+                message,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+/// `sha256=<hex HMAC-SHA256 of "{timestamp}.{body}">`, the timestamp prevents replays
+fn sign(secret: &str, timestamp: i64, body: &str) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes())
+        .expect("HMAC accepts keys of any length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body.as_bytes());
+    format!("sha256={:x}", mac.finalize().into_bytes())
+}
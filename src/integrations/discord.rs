@@ -0,0 +1,292 @@
+use std::sync::Arc;
+
+use chrono::Utc;
+use mongodb::bson::doc;
+use mongodb::options::UpdateOptions;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tokio::sync::broadcast;
+use tracing::{debug, error, info, warn};
+
+use crate::anime::anilist;
+use crate::anime::episodes::{self, EpisodeFilter};
+use crate::anime::my_anime_list::{database, model::AnimeData};
+use crate::global::database::DatabaseInstance;
+use crate::global::error::{AppError, DatabaseError, HttpError};
+use crate::global::events::{AnimeSource, DataEvent, EventBus};
+use crate::global::job::{self, JobStatus};
+use crate::global::module::RateLimiter;
+use crate::global::secrets::redacted;
+
+const DISCORD_API_URL: &str = "https://discord.com/api/v10";
+
+/// Last episode notified per watched anime, so restarts don't repeat notifications
+const EPISODES_COLLECTION: &str = "discord_episode_notifications";
+
+/// Discord rejects embed descriptions above 4096 characters, synopses are cut well before
+const MAX_SYNOPSIS_CHARS: usize = 600;
+
+const COLOR_SUCCESS: u32 = 0x2ECC71;
+const COLOR_WARNING: u32 = 0xF1C40F;
+const COLOR_FAILURE: u32 = 0xE74C3C;
+const COLOR_EPISODE: u32 = 0x2E51A2;
+
+/// `[integrations.discord]` config section.
+/// Messages go to `webhook_url` when set, otherwise to `channel_id` through the bot API.
+#[derive(Clone, Deserialize, Serialize)]
+pub struct DiscordConfig {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Channel webhook URL, accepts secret references (`env:`, `file:`, `vault:`)
+    #[serde(default)]
+    pub webhook_url: Option<String>,
+    /// Bot token, accepts secret references
+    #[serde(default)]
+    pub bot_token: Option<String>,
+    #[serde(default)]
+    pub channel_id: Option<String>,
+    /// MAL ids of anime announced when new episodes are collected
+    #[serde(default)]
+    pub watched: Vec<u32>,
+    /// Announce finished collection jobs
+    #[serde(default = "default_notify_jobs")]
+    pub notify_jobs: bool,
+}
+
+fn default_notify_jobs() -> bool {
+    true
+}
+
+impl Default for DiscordConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            webhook_url: None,
+            bot_token: None,
+            channel_id: None,
+            watched: Vec::new(),
+            notify_jobs: default_notify_jobs(),
+        }
+    }
+}
+
+impl std::fmt::Debug for DiscordConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DiscordConfig")
+            .field("enabled", &self.enabled)
+            .field("webhook_url", &redacted(&self.webhook_url))
+            .field("bot_token", &redacted(&self.bot_token))
+            .field("channel_id", &self.channel_id)
+            .field("watched", &self.watched)
+            .field("notify_jobs", &self.notify_jobs)
+            .finish()
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct EpisodeNotification {
+    mal_id: i32,
+    episode: i32,
+    updated_at: String,
+}
+
+/// Posts embeds to a Discord channel for new episodes of watched anime and finished jobs
+pub struct DiscordNotifier {
+    client: reqwest::Client,
+    config: DiscordConfig,
+    db: Arc<DatabaseInstance>,
+    limiter: RateLimiter,
+}
+
+impl DiscordNotifier {
+    pub fn new(client: reqwest::Client, config: DiscordConfig, db: Arc<DatabaseInstance>) -> Self {
+        Self {
+            client,
+            config,
+            db,
+            // Discord allows 5 messages per 5 seconds per channel
+            limiter: RateLimiter::new("discord", 0.5),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.config.enabled && (self.config.webhook_url.is_some() || self.bot_channel().is_some())
+    }
+
+    fn bot_channel(&self) -> Option<(&str, &str)> {
+        Some((self.config.bot_token.as_deref()?, self.config.channel_id.as_deref()?))
+    }
+
+    /// Subscribe to the event bus and post notifications in the background
+    pub fn spawn(self, events: &EventBus) {
+        if !self.config.enabled {
+            debug!("Discord integration disabled");
+            return;
+        }
+        if !self.is_enabled() {
+            warn!("Discord integration enabled without webhook_url or bot_token and channel_id");
+            return;
+        }
+
+        info!(watched = self.config.watched.len(), notify_jobs = self.config.notify_jobs, "Discord integration started");
+
+        let mut rx = events.subscribe();
+        tokio::spawn(async move {
+            loop {
+                let event = match rx.recv().await {
+                    Ok(event) => event,
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        warn!(skipped = skipped, "Discord integration lagging, events dropped");
+                        continue;
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                };
+
+                if let Err(e) = self.handle(&event).await {
+                    error!(event = %event.kind(), error = %e, "Failed to send Discord notification");
+                }
+            }
+        });
+    }
+
+    async fn handle(&self, event: &DataEvent) -> Result<(), AppError> {
+        match event {
+            DataEvent::AnimeStored { source, id, .. } | DataEvent::AnimeUpdated { source, id } => {
+                let mal_id = match source {
+                    AnimeSource::MyAnimeList => Some(*id as i32),
+                    AnimeSource::AniList => anilist::database::get_anime_by_id(self.db.db(), *id as i32).await?
+                        .and_then(|anime| anime.mal_id),
+                };
+                match mal_id {
+                    Some(mal_id) if self.config.watched.contains(&(mal_id as u32)) => self.check_new_episode(mal_id).await,
+                    _ => Ok(()),
+                }
+            }
+            DataEvent::JobFinished { job_id, kind, status, total_tasks, failed_tasks } if self.config.notify_jobs => {
+                let description = job::get_job(&self.db, job_id).await?
+                    .map(|job| job.description)
+                    .unwrap_or_else(|| kind.clone());
+
+                let color = match status {
+                    JobStatus::Completed => COLOR_SUCCESS,
+                    JobStatus::CompletedWithErrors => COLOR_WARNING,
+                    _ => COLOR_FAILURE,
+                };
+                let embed = json!({
+                    "title": format!("Job finished: {}", description),
+                    "color": color,
+                    "fields": [
+                        { "name": "Status", "value": format!("{:?}", status), "inline": true },
+                        { "name": "Tasks", "value": total_tasks.to_string(), "inline": true },
+                        { "name": "Failed", "value": failed_tasks.to_string(), "inline": true },
+                    ],
+                    "footer": { "text": job_id },
+                    "timestamp": Utc::now().to_rfc3339(),
+                });
+                self.post(embed).await
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Announce the latest aired episode when it is newer than the last one announced.
+    /// The first time an anime is seen its current episode is only recorded.
+    async fn check_new_episode(&self, mal_id: i32) -> Result<(), AppError> {
+        let Some(anime) = database::get_anime_by_id(self.db.db(), mal_id).await? else {
+            return Ok(());
+        };
+        let next_airing = anilist::database::get_anime_by_mal_id(self.db.db(), mal_id).await?
+            .and_then(|anime| anime.next_airing_episode)
+            .map(|next| next.episode - 1);
+
+        let now = Utc::now();
+        let episodes = episodes::get_episodes(self.db.db(), mal_id, EpisodeFilter::default()).await?;
+        let collected = episodes::latest_aired(&episodes, now);
+
+        let Some(latest) = next_airing.max(collected).filter(|episode| *episode > 0) else {
+            return Ok(());
+        };
+
+        let collection = self.db.collection::<EpisodeNotification>(EPISODES_COLLECTION);
+        let previous = collection.find_one(doc! { "mal_id": mal_id })
+            .await
+            .map_err(|e| AppError::Database(DatabaseError::Query(format!("Failed to get episode notification: {}", e))))?
+            .map(|notification| notification.episode);
+
+        if previous.is_some_and(|previous| previous >= latest) {
+            return Ok(());
+        }
+
+        collection
+            .update_one(
+                doc! { "mal_id": mal_id },
+                doc! { "$set": { "episode": latest, "updated_at": now.to_rfc3339() } },
+            )
+            .with_options(UpdateOptions::builder().upsert(true).build())
+            .await
+            .map_err(|e| AppError::Database(DatabaseError::Query(format!("Failed to record episode notification: {}", e))))?;
+
+        if previous.is_none() {
+            debug!(mal_id = mal_id, episode = latest, "Recorded current episode of watched anime");
+            return Ok(());
+        }
+
+        info!(mal_id = mal_id, episode = latest, "New episode of watched anime");
+        self.post(episode_embed(&anime, latest)).await
+    }
+
+    async fn post(&self, embed: serde_json::Value) -> Result<(), AppError> {
+        self.limiter.acquire().await;
+
+        let body = json!({ "embeds": [embed] });
+        let request = match (&self.config.webhook_url, self.bot_channel()) {
+            (Some(url), _) => self.client.post(url),
+            (None, Some((token, channel_id))) => self.client
+                .post(format!("{}/channels/{}/messages", DISCORD_API_URL, channel_id))
+                .header("Authorization", format!("Bot {}", token)),
+            (None, None) => return Ok(()),
+        };
+
+        let response = request.json(&body).send().await.map_err(HttpError::from)?;
+        let status = response.status();
+
+        if !status.is_success() {
+            let message = response.text().await.unwrap_or_default();
+            return Err(HttpError::UnexpectedStatus {
+                status: status.as_u16(),
+                message,
+            }.into());
+        }
+
+        Ok(())
+    }
+}
+
+fn episode_embed(anime: &AnimeData, episode: i32) -> serde_json::Value {
+    let mut synopsis: String = anime.synopsis.chars().take(MAX_SYNOPSIS_CHARS).collect();
+    if synopsis.len() < anime.synopsis.len() {
+        synopsis.push('…');
+    }
+    let cover = [&anime.images.jpg.large_image_url, &anime.images.jpg.image_url]
+        .into_iter()
+        .find(|url| !url.is_empty());
+    let score = anime.score
+        .map(|score| format!("{:.2}", score))
+        .unwrap_or_else(|| "N/A".to_string());
+
+    let mut embed = json!({
+        "title": format!("{} - Episode {}", anime.display_title(), episode),
+        "url": anime.url,
+        "description": synopsis,
+        "color": COLOR_EPISODE,
+        "fields": [
+            { "name": "Score", "value": score, "inline": true },
+            { "name": "Episodes", "value": if anime.num_episodes > 0 { anime.num_episodes.to_string() } else { "?".to_string() }, "inline": true },
+        ],
+        "timestamp": Utc::now().to_rfc3339(),
+    });
+    if let Some(cover) = cover {
+        embed["thumbnail"] = json!({ "url": cover });
+    }
+    embed
+}
@@ -0,0 +1,12 @@
+pub mod discord;
+
+use serde::{Deserialize, Serialize};
+
+use discord::DiscordConfig;
+
+/// `[integrations]` config section: optional third-party notification targets
+#[derive(Debug, Clone, Deserialize, Serialize, Default)]
+pub struct IntegrationsConfig {
+    #[serde(default)]
+    pub discord: DiscordConfig,
+}
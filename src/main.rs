@@ -1,20 +1,28 @@
-use std::{fs, sync::Arc};
+use std::sync::Arc;
 
 use anyhow::Result;
-use tokio::sync::mpsc;
 use tracing::{info, debug, error, warn};
-use tracing_appender::rolling::{RollingFileAppender, Rotation};
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
-use crate::{anime::module::AnimeModule, global::{config::AppConfig, database::DatabaseInstance, http::HttpClientManager, module::{ChildModule, ModuleHandle, ParentModule}}, picture::PictureFetcherModule};
+use arc_swap::ArcSwap;
+use clap::Parser;
+
+use crate::{anime::{auto_pictures::spawn_auto_pictures, module::AnimeModule}, global::{events::EventBus, config::{AppConfig, CONFIG_FILE}, reload::{ConfigWatcher, SharedConfig}, alert::AlertManager, disk::DiskMonitor, webhook::WebhookDispatcher, logs::LoggingHandle, database::DatabaseInstance, http::HttpClientManager, module::{ChildModule, ModuleConfigUpdate}, registry::ModuleRegistry, supervisor::ModuleSupervisor}, picture::PictureFetcherModule, video::VideoModule, music::MusicModule};
 
 mod anime;
+mod cli;
 mod global;
 mod picture;
+mod video;
+mod music;
 mod api;
+mod integrations;
+#[cfg(test)]
+mod testing;
 
 #[tokio::main]
 async fn main() -> Result<()> {
+    let cli = cli::Cli::parse();
+
     // Load configuration first
     println!("Loading configuration from config.toml...");
     let config = match AppConfig::load() {
@@ -28,36 +36,60 @@ async fn main() -> Result<()> {
             return Err(e.into());
         }
     };
+
+    // Dry-run mode: validate configuration and exit without starting modules
+    if cli.check_config {
+        let report = global::validation::check_config(&config).await;
+        report.print();
+
+        if !report.is_ok() {
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
     
     // Initialize logging with configured settings
-    setup_logging(&config)?;
+    let logging = Arc::new(LoggingHandle::init(&config)?);
 
     info!("Starting media-collector...");
     debug!(?config, "Loaded configuration");
 
+    let enabled_features = config.features.enabled();
+    if !enabled_features.is_empty() {
+        warn!(features = ?enabled_features, "Experimental features enabled");
+    }
+
     // Initialize database
-    let db = DatabaseInstance::new(&config.database.host, config.database.port, &config.database.name).await?;
+    let db = DatabaseInstance::new(&config.database).await?;
     let db = Arc::new(db);
 
-    // Initialize child module collections
-    if config.is_parent_module_enabled("anime") {
-        if anime::my_anime_list::module::MyAnimeListModule::is_available(&config) {
-            info!("Initializing MyAnimeList database collections");
-            anime::my_anime_list::database::initialize_collections(db.db()).await?;
-        }
+    // Converters fill missing seasons from the start date unless disabled
+    anime::season::set_inference(config.anime.infer_season);
 
-        if anime::anilist::module::AniListModule::is_available(&config) {
-            info!("Initializing AniList database collections");
-            anime::anilist::database::initialize_collections(db.db()).await?;
-        }
+    // Initialize child module and picture tracking collections
+    initialize_data_collections(&config, &db).await?;
+
+    // Archived provider responses are shared by all namespaces
+    if config.http.archive.enabled {
+        global::archive::initialize_collection(db.db(), &config.http.archive).await?;
     }
 
-    // Initialize picture tracking collections
-    info!("Initializing picture tracking database collections");
-    picture::database::initialize_collections(db.db()).await?;
+    // One-shot commands run their tasks directly and exit
+    if let Some(command) = cli.command
+        && !matches!(command, cli::Command::Serve)
+    {
+        let http_manager = HttpClientManager::new(config.clone())
+            .with_archive(db.db().clone())
+            .with_request_budgets(db.db().clone())
+            .await;
+        let result = cli::run(command, config.clone(), db.clone(), http_manager).await;
+        logging.shutdown();
+        return result;
+    }
 
-    // Spawn database maintenance task
+    // Spawn database and log maintenance task
     let db_clone = db.clone();
+    let logging_config = config.app.logging.clone();
     tokio::spawn(async move {
         loop {
             tokio::time::sleep(tokio::time::Duration::from_secs(3600)).await; // Every hour
@@ -82,82 +114,284 @@ async fn main() -> Result<()> {
                 }
                 Err(e) => error!(error = %e, "Failed to get database stats"),
             }
+
+            // Compress and prune rotated log files
+            let logging_config = logging_config.clone();
+            match tokio::task::spawn_blocking(move || global::logs::apply_log_retention(&logging_config)).await {
+                Ok(Ok(report)) => {
+                    if report.compressed > 0 || report.deleted > 0 {
+                        info!(
+                            compressed = report.compressed,
+                            deleted = report.deleted,
+                            "Applied log retention"
+                        );
+                    }
+                }
+                Ok(Err(e)) => error!(error = %e, "Log retention failed"),
+                Err(e) => error!(error = %e, "Log retention task panicked"),
+            }
         }
     });
 
-    // Initialize HTTP client manager with rate limiters
-    let http_manager = HttpClientManager::new(config.clone());
-    
-    // Store module handles for graceful shutdown
-    let mut module_handles = Vec::new();
-
-    // Store module references for API
-    let mut anime_module_ref: Option<Arc<AnimeModule>> = None;
-    let mut picture_module_ref: Option<Arc<PictureFetcherModule>> = None;
-
-    // Initialize picture fetcher module
-    info!("Initializing picture fetcher module");
-    let picture_storage_path = "./pictures";
-    let picture_client = http_manager.default().client.clone();
-    let picture_module = PictureFetcherModule::new(
-        db.clone(),
-        picture_client,
-        picture_storage_path,
-    );
-    
-    picture_module_ref = Some(Arc::new(picture_module.clone()));
-    let picture_handle = spawn_parent_module(picture_module, db.clone()).await;
-    module_handles.push(picture_handle);
-    
-    // Launch anime module
-    if config.is_parent_module_enabled("anime") {
-        info!("Initializing anime module");
-        
-        let mal_client = http_manager.my_anime_list().client.clone();
-        let anime_module = AnimeModule::new(
-            db.clone(), 
-            mal_client,
-        );
-    
-        anime_module_ref = Some(Arc::new(anime_module.clone()));
-        let handle = spawn_parent_module(anime_module, db.clone()).await;
-        module_handles.push(handle);
+    // Initialize HTTP client manager with rate limiters and daily request budgets
+    let http_manager = HttpClientManager::new(config.clone())
+        .with_archive(db.db().clone())
+        .with_request_budgets(db.db().clone())
+        .await;
+
+    // Start alert monitoring (no-op unless [alerting] is enabled with webhooks)
+    let alert_manager = AlertManager::new(http_manager.default().client.clone(), config.alerting.clone());
+    alert_manager.clone().spawn_monitor(db.clone());
+    let http_manager = http_manager.with_alerts(alert_manager.clone());
+
+    // Measure the storage directories, alerting when they run low on space
+    let disk_monitor = DiskMonitor::new();
+    disk_monitor.clone().spawn(config.clone(), alert_manager);
+
+    // Shared configuration, updated when config.toml changes
+    let shared_config: SharedConfig = Arc::new(ArcSwap::new(config.clone()));
+
+    // Data events published by tasks
+    let events = EventBus::new();
+
+    // Tasks of the previous run are not resumed, fail them so their jobs finish
+    global::queue::fail_interrupted_tasks(&db, Some(&events)).await?;
+
+    // Register parent modules, started in this order when enabled in [modules]
+    let registry = ModuleRegistry::new()
+        .register("picture", |ctx| {
+            let picture_client = ctx.http_manager.default().client.clone();
+            Some(PictureFetcherModule::new(ctx.db.clone(), picture_client, &ctx.config.picture, ctx.events.clone()))
+        })
+        .register("video", |ctx| {
+            let video_client = ctx.http_manager.default().client.clone();
+            Some(VideoModule::new(ctx.db.clone(), video_client, &ctx.config.video))
+        })
+        .register("music", |ctx| {
+            let music_client = ctx.http_manager.default().client.clone();
+            Some(MusicModule::new(ctx.db.clone(), music_client, &ctx.config.music))
+        })
+        .register("anime", |ctx| {
+            let mal_client = ctx.http_manager.my_anime_list().client.clone();
+            let mut module = AnimeModule::new(ctx.db.clone(), mal_client, &ctx.config.anime, ctx.events.clone())
+                .with_section_refresh_age(ctx.config.section_refresh_age())
+                .with_jikan(ctx.http_manager.jikan().clone());
+            if let Some(api_key) = ctx.config.get_api_key("my_anime_list") {
+                module = module.with_mal(api_key, ctx.http_manager.my_anime_list().clone());
+            }
+            Some(module)
+        });
+
+    // Crashed modules are restarted with exponential backoff
+    let supervisor = ModuleSupervisor::new(config.supervisor.clone());
+    let modules = registry
+        .start_enabled(config.clone(), db.clone(), http_manager.clone(), events.clone(), &supervisor)
+        .await;
+
+    if modules.handles.is_empty() {
+        warn!("No parent modules are enabled in configuration");
     } else {
-        info!("Anime module is disabled in config");
+        info!(count = modules.handles.len(), "All enabled modules started successfully");
     }
 
-    if config.is_parent_module_enabled("manga") {
-        info!("Manga module is enabled but not implemented yet");
-        // let manga_module = manga::module::MangaModule::new();
-        // let handle = spawn_parent_module(manga_module, db.clone()).await;
-        // module_handles.push(handle);
+    if config.app.hot_reload {
+        let reload_http_manager = http_manager.clone();
+        let watcher = ConfigWatcher::new(shared_config.clone(), CONFIG_FILE)
+            .on_reload(move |cfg| reload_http_manager.apply_config(cfg))
+            .on_reload(|cfg| anime::season::set_inference(cfg.anime.infer_season))
+            .on_reload({
+                let logging = logging.clone();
+                move |cfg| logging.set_level(&cfg.app.log_level)
+            })
+            .on_reload({
+                let anime = modules.handle("anime");
+                move |cfg| {
+                    let Some(anime) = &anime else { return };
+                    let updates = [
+                        ModuleConfigUpdate::Snapshots {
+                            interval: cfg.anime.snapshot_interval(),
+                            anime: cfg.anime.snapshot_anime.clone(),
+                        },
+                        ModuleConfigUpdate::WatchlistInterval(cfg.anime.watchlist_refresh_interval()),
+                        ModuleConfigUpdate::SectionRefresh {
+                            max_age_days: cfg.section_refresh_age(),
+                            limit: cfg.anime.section_refresh_limit,
+                        },
+                    ];
+                    for update in updates {
+                        if let Err(e) = anime.update_config(update) {
+                            warn!(error = %e, "Failed to update anime module settings");
+                        }
+                    }
+                }
+            })
+            .on_reload({
+                let picture = modules.handle("picture");
+                move |cfg| {
+                    let Some(picture) = &picture else { return };
+                    let updates = [
+                        ModuleConfigUpdate::Concurrency(cfg.picture.concurrency),
+                        ModuleConfigUpdate::CleanupInterval(cfg.picture.cleanup_interval()),
+                    ];
+                    for update in updates {
+                        if let Err(e) = picture.update_config(update) {
+                            warn!(error = %e, "Failed to update picture module settings");
+                        }
+                    }
+                }
+            })
+            .on_reload({
+                let video = modules.handle("video");
+                move |cfg| {
+                    let Some(video) = &video else { return };
+                    let updates = [
+                        ModuleConfigUpdate::Concurrency(cfg.video.concurrency),
+                        ModuleConfigUpdate::CleanupInterval(cfg.video.cleanup_interval()),
+                    ];
+                    for update in updates {
+                        if let Err(e) = video.update_config(update) {
+                            warn!(error = %e, "Failed to update video module settings");
+                        }
+                    }
+                }
+            })
+            .on_reload({
+                let music = modules.handle("music");
+                move |cfg| {
+                    let Some(music) = &music else { return };
+                    if let Err(e) = music.update_config(ModuleConfigUpdate::Concurrency(cfg.music.concurrency)) {
+                        warn!(error = %e, "Failed to update music module settings");
+                    }
+                }
+            });
+
+        if let Err(e) = watcher.spawn() {
+            warn!(error = %e, "Configuration hot-reload disabled");
+        }
     }
 
-    if module_handles.is_empty() {
-        warn!("No parent modules are enabled in configuration");
-    } else {
-        info!(count = module_handles.len(), "All enabled modules started successfully");
+    // Forward data events to the configured webhooks (no-op unless [webhooks] is enabled)
+    if config.features.webhooks {
+        WebhookDispatcher::new(http_manager.default().client.clone(), config.webhooks.clone()).spawn(&events);
+    } else if config.webhooks.enabled {
+        warn!("[webhooks] is enabled but the webhooks feature is not, no notification is sent");
+    }
+
+    // Post new episodes of watched anime and finished jobs to Discord (no-op unless enabled)
+    integrations::discord::DiscordNotifier::new(
+        http_manager.default().client.clone(),
+        config.integrations.discord.clone(),
+        db.clone(),
+    ).spawn(&events);
+
+    if config.anime.auto_pictures {
+        match modules.get::<PictureFetcherModule>("picture") {
+            Some(picture_mod) => spawn_auto_pictures(&events, picture_mod),
+            None => warn!("anime.auto_pictures is enabled but the picture module is not running"),
+        }
+    }
+
+    if config.picture.collect_garbage {
+        anime::picture_gc::spawn_picture_gc(&events, db.clone());
+    }
+
+    if config.anime.mal_backfill {
+        let mal_module = modules.get::<AnimeModule>("anime").and_then(|anime_mod| {
+            anime::my_anime_list::MyAnimeListModule::new(
+                http_manager.my_anime_list().clone(),
+                http_manager.jikan().clone(),
+                config.clone(),
+                anime_mod.queue().clone(),
+            )
+        });
+        match mal_module {
+            Some(mal_module) => anime::mal_backfill::spawn_mal_backfill(
+                &events,
+                db.clone(),
+                Arc::new(mal_module.with_events(events.clone())),
+            ),
+            None => warn!("anime.mal_backfill is enabled but MyAnimeList is not available"),
+        }
+    }
+
+    if !config.bootstrap.jobs.is_empty() {
+        let mal_module = modules.get::<AnimeModule>("anime").and_then(|anime_mod| {
+            anime::my_anime_list::MyAnimeListModule::new(
+                http_manager.my_anime_list().clone(),
+                http_manager.jikan().clone(),
+                config.clone(),
+                anime_mod.queue().clone(),
+            )
+        });
+        match mal_module {
+            Some(mut mal_module) => {
+                mal_module = mal_module.with_events(events.clone());
+                if let Some(picture_mod) = modules.get::<PictureFetcherModule>("picture") {
+                    mal_module = mal_module.with_picture_module(picture_mod);
+                }
+                anime::bootstrap::spawn_bootstrap(config.clone(), db.clone(), http_manager.clone(), Arc::new(mal_module));
+            }
+            None => warn!("[bootstrap] jobs are configured but MyAnimeList is not available"),
+        }
     }
 
     tokio::time::sleep(tokio::time::Duration::from_secs(2)).await;
 
     // Initialize API state and server
+    // Modules shut down on Ctrl+C, the namespaces' modules are added with the API
+    let mut shutdown_handles = modules.handles.clone();
     if config.api.enabled {
         info!("Initializing API server");
         
         let mut api_state = api::state::ApiState::new(
-            config.clone(),
+            shared_config.clone(),
             db.clone(),
             Arc::new(http_manager.clone()),
-        );
+        )
+        .with_events(events.clone());
         
+        api_state = api_state
+            .with_module_statuses(supervisor.statuses())
+            .with_module_handles(modules.handles.clone())
+            .with_disk_monitor(disk_monitor.clone());
+
         // Add module references
-        if let Some(ref anime_mod) = anime_module_ref {
-            api_state = api_state.with_anime_module(anime_mod.clone());
+        if let Some(anime_mod) = modules.get::<AnimeModule>("anime") {
+            api_state = api_state.with_anime_module(anime_mod);
         }
-        
-        if let Some(ref picture_mod) = picture_module_ref {
-            api_state = api_state.with_picture_module(picture_mod.clone());
+
+        if let Some(picture_mod) = modules.get::<PictureFetcherModule>("picture") {
+            api_state = api_state.with_picture_module(picture_mod);
+        }
+
+        if let Some(video_mod) = modules.get::<VideoModule>("video") {
+            api_state = api_state.with_video_module(video_mod);
+        }
+
+        if let Some(music_mod) = modules.get::<MusicModule>("music") {
+            api_state = api_state.with_music_module(music_mod);
+        }
+
+        // Provider modules shared by the API handlers
+        api_state = api_state.with_provider_modules();
+
+        // Isolated libraries, each on its own database
+        let mut namespace_names: Vec<&String> = config.api.namespaces.keys().collect();
+        namespace_names.sort();
+        let mut namespaces = Vec::new();
+        for name in namespace_names {
+            info!(namespace = %name, "Initializing namespace");
+            let namespace_db = DatabaseInstance::new(&config.database.for_namespace(name)).await?;
+            initialize_data_collections(&config, &namespace_db).await?;
+            global::queue::fail_interrupted_tasks(&namespace_db, None).await?;
+
+            let namespace_config = &config.api.namespaces[name];
+            if namespace_config.api_keys.is_empty() {
+                warn!(namespace = %name, "Namespace has no API keys, its API is open");
+            }
+            let namespace = api::namespace::Namespace::new(name, namespace_config, &api_state, Arc::new(namespace_db)).await;
+            shutdown_handles.extend_from_slice(namespace.module_handles());
+            namespaces.push(namespace);
         }
         
         let api_host = config.api.host.clone();
@@ -165,7 +399,7 @@ async fn main() -> Result<()> {
         
         // Spawn API server in background
         tokio::spawn(async move {
-            if let Err(e) = api::start_api_server(api_state, &api_host, api_port).await {
+            if let Err(e) = api::start_api_server(api_state, namespaces, &api_host, api_port).await {
                 error!(error = %e, "API server failed");
             }
         });
@@ -185,7 +419,7 @@ async fn main() -> Result<()> {
     warn!("Shutdown signal received, initiating graceful shutdown");
 
     // Graceful shutdown
-    for handle in module_handles {
+    for handle in shutdown_handles {
         info!(module = %handle.name, "Sending shutdown signal");
         if let Err(e) = handle.shutdown().await {
             error!(module = %handle.name, error = %e, "Failed to shutdown module");
@@ -195,27 +429,67 @@ async fn main() -> Result<()> {
     // Give modules time to clean up
     tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
     info!("Shutdown complete");
+    logging.shutdown();
 
     Ok(())
 }
 
-/// Spawn a parent module that runs continuously
-async fn spawn_parent_module<M: ParentModule + 'static>(
-    module: M,
-    db: Arc<DatabaseInstance>,
-) -> ModuleHandle {
-    let (tx, rx) = mpsc::channel(100);
-    let name = module.name().to_string();
-
-    info!(module = %name, "Spawning parent module");
+/// Initialize the collections of the enabled child modules, the picture tracking collections
+/// and the video and theme song ones when their module is enabled
+async fn initialize_data_collections(config: &AppConfig, db: &DatabaseInstance) -> Result<()> {
+    if config.is_parent_module_enabled("anime") {
+        if anime::my_anime_list::module::MyAnimeListModule::is_available(config) {
+            info!("Initializing MyAnimeList database collections");
+            anime::my_anime_list::database::initialize_collections(db.db()).await?;
+            anime::my_anime_list::overflow::initialize_collection(db.db()).await?;
+            anime::episodes::initialize_collection(db.db()).await?;
+            global::migration::run_once(db.db(), "mal_episodes_collection", || {
+                anime::episodes::migrate_embedded_episodes(db.db())
+            }).await?;
+            global::migration::run_once(db.db(), "mal_duration_seconds", || {
+                anime::my_anime_list::database::backfill_duration_seconds(db.db())
+            }).await?;
+            if config.anime.infer_season {
+                global::migration::run_once(db.db(), "mal_infer_season", || {
+                    anime::my_anime_list::database::backfill_seasons(db.db())
+                }).await?;
+            }
+        }
 
-    tokio::spawn(async move {
-        if let Err(e) = module.run(db, rx).await {
-            error!(module = %module.name(), error = %e, "Module terminated with error");
+        if anime::anilist::module::AniListModule::is_available(config) {
+            info!("Initializing AniList database collections");
+            anime::anilist::database::initialize_collections(db.db()).await?;
+            global::migration::run_once(db.db(), "anilist_duration_seconds", || {
+                anime::anilist::database::backfill_duration_seconds(db.db())
+            }).await?;
+            if config.anime.infer_season {
+                global::migration::run_once(db.db(), "anilist_infer_season", || {
+                    anime::anilist::database::backfill_seasons(db.db())
+                }).await?;
+            }
         }
-    });
 
-    ModuleHandle { name, tx }
+        anime::titles::initialize_collection(db.db()).await?;
+        global::migration::run_once(db.db(), "title_prefixes", || {
+            anime::titles::backfill_prefixes(db.db())
+        }).await?;
+        anime::watchlist::initialize_collection(db.db()).await?;
+    }
+
+    info!("Initializing picture tracking database collections");
+    picture::database::initialize_collections(db.db()).await?;
+
+    if config.is_parent_module_enabled("video") {
+        info!("Initializing video tracking database collections");
+        video::database::initialize_collections(db.db()).await?;
+    }
+
+    if config.is_parent_module_enabled("music") {
+        info!("Initializing theme song database collections");
+        music::database::initialize_collections(db.db()).await?;
+    }
+
+    Ok(())
 }
 
 /// Spawn a child module for a single task
@@ -236,92 +510,10 @@ async fn spawn_child_module<M: ChildModule + 'static>(
     handle.await
         .map_err(|e| {
             error!(module = %module_name, error = %e, "Child module join error");
-            global::error::AppError::Module(format!("Join error: {}", e))
+            if e.is_cancelled() {
+                global::error::AppError::Canceled(format!("Child module {} was canceled", module_name))
+            } else {
+                global::error::AppError::Module(format!("Join error: {}", e))
+            }
         })?
 }
-
-/// Setup logging based on configuration
-fn setup_logging(config: &AppConfig) -> Result<()> {
-    let log_level = &config.app.log_level;
-    let logging_config = &config.app.logging;
-
-    // Create the log directory if it doesn't exist
-    if logging_config.log_to_file {
-        fs::create_dir_all(&logging_config.log_directory)?;
-        println!("Logs will be written to: {}/", logging_config.log_directory);
-    }
-
-    // Build the env filter
-    let env_filter = tracing_subscriber::EnvFilter::try_from_default_env()
-        .unwrap_or_else(|_| format!("media_collector={},info", log_level).into());
-
-    // Build the subscriber with layers
-    let registry = tracing_subscriber::registry().with(env_filter);
-
-    if logging_config.log_to_file {
-        // Determine rotation strategy
-        let rotation = match logging_config.log_rotation {
-            global::config::LogRotation::Daily => Rotation::DAILY,
-            global::config::LogRotation::Hourly => Rotation::HOURLY,
-            global::config::LogRotation::Never => Rotation::NEVER,
-        };
-
-        // Create file appender
-        let file_appender = RollingFileAppender::new(
-            rotation,
-            &logging_config.log_directory,
-            &format!("{}.log", logging_config.log_file_prefix),
-        );
-
-        let (non_blocking, _guard) = tracing_appender::non_blocking(file_appender);
-        
-        // Create file layer
-        let file_layer = tracing_subscriber::fmt::layer()
-            .with_writer(non_blocking)
-            .with_ansi(false)
-            .with_target(true)
-            .with_thread_ids(true)
-            .with_line_number(true);
-
-        if logging_config.log_to_console {
-            // Both console and file
-            let console_layer = tracing_subscriber::fmt::layer()
-                .with_writer(std::io::stdout)
-                .with_target(false);
-
-            registry
-                .with(file_layer)
-                .with(console_layer)
-                .init();
-        } else {
-            // File only
-            registry
-                .with(file_layer)
-                .init();
-        }
-
-        // Keep the guard alive for the duration of the program
-        // This is a workaround - in production you'd want to store this properly
-        std::mem::forget(_guard);
-    } else if logging_config.log_to_console {
-        // Console only
-        let console_layer = tracing_subscriber::fmt::layer()
-            .with_writer(std::io::stdout)
-            .with_target(false);
-
-        registry
-            .with(console_layer)
-            .init();
-    } else {
-        // No logging configured - use default console
-        let console_layer = tracing_subscriber::fmt::layer()
-            .with_writer(std::io::stdout)
-            .with_target(false);
-
-        registry
-            .with(console_layer)
-            .init();
-    }
-
-    Ok(())
-}
\ No newline at end of file
@@ -0,0 +1,84 @@
+use serde::Deserialize;
+use serde_json::json;
+
+use crate::global::error::AppError;
+use super::model::{AnisongDbSong, ThemeKind};
+use super::parser::ParsedTheme;
+
+/// Song of an anime as returned by the AnisongDB search API
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnisongDbEntry {
+    pub ann_song_id: Option<i64>,
+    /// "Opening 1", "Ending 2", "Insert Song"...
+    pub song_type: String,
+    pub song_name: String,
+    pub song_artist: Option<String>,
+    pub song_composer: Option<String>,
+    pub song_arranger: Option<String>,
+    pub audio: Option<String>,
+    #[serde(rename = "HQ")]
+    pub hq: Option<String>,
+    #[serde(rename = "MQ")]
+    pub mq: Option<String>,
+}
+
+/// Every song AnisongDB knows for an anime
+pub async fn fetch_anime_songs(
+    client: &reqwest::Client,
+    base_url: &str,
+    mal_id: i32,
+) -> Result<Vec<AnisongDbEntry>, AppError> {
+    let url = format!("{}/malIDs_request", base_url.trim_end_matches('/'));
+
+    let response = client.post(&url)
+        .json(&json!({ "malIds": [mal_id] }))
+        .send()
+        .await
+        .map_err(|e| AppError::provider("anisongdb", format!("Failed to query AnisongDB: {}", e)))?;
+
+    let status = response.status();
+    if !status.is_success() {
+        return Err(AppError::Provider {
+            provider: "anisongdb".to_string(),
+            message: format!("AnisongDB returned HTTP {}", status),
+            retryable: status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS,
+        });
+    }
+
+    response.json()
+        .await
+        .map_err(|e| AppError::provider("anisongdb", format!("Failed to read AnisongDB response: {}", e)))
+}
+
+/// AnisongDB entry of a parsed theme: same kind and number, or else the same title
+pub fn find_song(entries: &[AnisongDbEntry], theme: &ParsedTheme) -> Option<AnisongDbSong> {
+    let song_type = match theme.kind {
+        ThemeKind::Opening => format!("Opening {}", theme.sequence),
+        ThemeKind::Ending => format!("Ending {}", theme.sequence),
+    };
+    let title = normalize(&theme.title);
+
+    entries.iter()
+        .find(|entry| entry.song_type == song_type && normalize(&entry.song_name) == title)
+        .or_else(|| entries.iter().find(|entry| entry.song_type == song_type))
+        .or_else(|| entries.iter().find(|entry| normalize(&entry.song_name) == title))
+        .map(|entry| AnisongDbSong {
+            ann_song_id: entry.ann_song_id,
+            song_name: entry.song_name.clone(),
+            artist: entry.song_artist.clone(),
+            composer: entry.song_composer.clone(),
+            arranger: entry.song_arranger.clone(),
+            audio_url: entry.audio.clone(),
+            hq_url: entry.hq.clone(),
+            mq_url: entry.mq.clone(),
+        })
+}
+
+/// Lowercase alphanumerics, so punctuation and spacing differences still match
+fn normalize(title: &str) -> String {
+    title.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
@@ -0,0 +1,116 @@
+// src/music/database.rs
+use mongodb::{Database, IndexModel};
+use mongodb::options::{FindOptions, IndexOptions};
+use mongodb::bson::doc;
+use tracing::{info, debug, warn};
+use futures::stream::StreamExt;
+
+use super::model::ThemeSong;
+use crate::global::error::DatabaseError;
+
+const COLLECTION_NAME: &str = "theme_songs";
+
+/// Initialize the theme song collection and indexes
+pub async fn initialize_collections(db: &Database) -> Result<(), DatabaseError> {
+    info!("Initializing theme song collections");
+
+    let collection = db.collection::<ThemeSong>(COLLECTION_NAME);
+
+    // One document per opening or ending of an anime
+    let theme_index = IndexModel::builder()
+        .keys(doc! { "mal_id": 1, "kind": 1, "sequence": 1 })
+        .options(IndexOptions::builder().unique(true).build())
+        .build();
+
+    // Indexes for the artist and title searches
+    let artist_index = IndexModel::builder()
+        .keys(doc! { "artist": 1 })
+        .build();
+
+    let title_index = IndexModel::builder()
+        .keys(doc! { "title": 1 })
+        .build();
+
+    collection.create_indexes(vec![theme_index, artist_index, title_index]).await
+        .map_err(|e| DatabaseError::Query(format!("Failed to create theme song indexes: {}", e)))?;
+
+    debug!("Created indexes for theme_songs collection");
+    Ok(())
+}
+
+/// Replace the theme songs of an anime, removing the ones no longer listed
+pub async fn replace_anime_theme_songs(db: &Database, mal_id: i32, songs: &[ThemeSong]) -> Result<(), DatabaseError> {
+    let collection = db.collection::<ThemeSong>(COLLECTION_NAME);
+
+    collection.delete_many(doc! { "mal_id": mal_id }).await
+        .map_err(|e| DatabaseError::Query(format!("Failed to delete theme songs: {}", e)))?;
+
+    if !songs.is_empty() {
+        collection.insert_many(songs).await
+            .map_err(|e| DatabaseError::Query(format!("Failed to insert theme songs: {}", e)))?;
+    }
+
+    debug!(mal_id = mal_id, count = songs.len(), "Theme songs stored");
+    Ok(())
+}
+
+/// Theme songs of an anime, openings first
+pub async fn get_anime_theme_songs(db: &Database, mal_id: i32) -> Result<Vec<ThemeSong>, DatabaseError> {
+    let collection = db.collection::<ThemeSong>(COLLECTION_NAME);
+
+    let options = FindOptions::builder()
+        .sort(doc! { "kind": -1, "sequence": 1 })
+        .build();
+
+    let cursor = collection.find(doc! { "mal_id": mal_id })
+        .with_options(options)
+        .await
+        .map_err(|e| DatabaseError::Query(format!("Failed to get theme songs: {}", e)))?;
+
+    Ok(collect_songs(cursor).await)
+}
+
+/// Theme songs whose artist and title contain the given texts, case-insensitively
+pub async fn search_theme_songs(
+    db: &Database,
+    artist: Option<&str>,
+    title: Option<&str>,
+    limit: i64,
+) -> Result<Vec<ThemeSong>, DatabaseError> {
+    let collection = db.collection::<ThemeSong>(COLLECTION_NAME);
+
+    let contains = |text: &str| doc! { "$regex": regex::escape(text), "$options": "i" };
+    let mut filter = doc! {};
+    if let Some(artist) = artist {
+        filter.insert("artist", contains(artist));
+    }
+    if let Some(title) = title {
+        filter.insert("$or", vec![
+            doc! { "title": contains(title) },
+            doc! { "title_native": contains(title) },
+        ]);
+    }
+
+    let options = FindOptions::builder()
+        .limit(limit)
+        .sort(doc! { "mal_id": 1, "kind": -1, "sequence": 1 })
+        .build();
+
+    let cursor = collection.find(filter)
+        .with_options(options)
+        .await
+        .map_err(|e| DatabaseError::Query(format!("Failed to search theme songs: {}", e)))?;
+
+    Ok(collect_songs(cursor).await)
+}
+
+async fn collect_songs(mut cursor: mongodb::Cursor<ThemeSong>) -> Vec<ThemeSong> {
+    let mut results = Vec::new();
+    while let Some(result) = cursor.next().await {
+        match result {
+            Ok(song) => results.push(song),
+            Err(e) => warn!(error = %e, "Failed to deserialize theme song"),
+        }
+    }
+    results
+}
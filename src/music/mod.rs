@@ -0,0 +1,139 @@
+use std::sync::Arc;
+use std::pin::Pin;
+use std::future::Future;
+use tokio::sync::mpsc;
+use tracing::{info, debug, warn, error};
+
+use crate::global::config::MusicConfig;
+use crate::global::database::DatabaseInstance;
+use crate::global::error::AppError;
+use crate::global::module::{ParentModule, ModuleConfigUpdate, ModuleMessage, HEARTBEAT_INTERVAL};
+use crate::global::supervisor::Heartbeat;
+use crate::global::queue::{QueueWorker, TaskQueue};
+
+pub mod task;
+pub mod model;
+pub mod database;
+pub mod parser;
+pub mod anisongdb;
+
+/// Parses the theme songs of the stored anime and matches them with AnisongDB
+#[derive(Clone)]
+pub struct MusicModule {
+    queue: TaskQueue,
+    /// AnisongDB API URL, `None` when the enrichment is disabled
+    anisongdb_url: Option<String>,
+}
+
+impl MusicModule {
+    pub fn new(
+        db: Arc<DatabaseInstance>,
+        client: reqwest::Client,
+        config: &MusicConfig,
+    ) -> Self {
+        let (queue, rx) = TaskQueue::new("music_queue".to_string(), config.queue_size, db.clone());
+
+        // Spawn the queue worker
+        let worker = QueueWorker::new("music_worker".to_string(), db, client)
+            .with_stats(queue.stats())
+            .with_concurrency(config.concurrency);
+        tokio::spawn(async move {
+            if let Err(e) = worker.run(rx).await {
+                error!(error = %e, "Music queue worker error");
+            }
+        });
+
+        Self {
+            queue,
+            anisongdb_url: config.anisongdb.then(|| config.anisongdb_url.clone()),
+        }
+    }
+
+    pub fn queue(&self) -> &TaskQueue {
+        &self.queue
+    }
+
+    /// Queue a task to parse and store the theme songs of a stored anime
+    pub async fn queue_fetch_theme_songs(&self, anime_id: u32) -> Result<(), AppError> {
+        let task = task::FetchThemeSongsTask::new(anime_id, self.anisongdb_url.clone());
+        self.queue.enqueue(Box::new(task)).await
+    }
+}
+
+impl ParentModule for MusicModule {
+    fn name(&self) -> &str {
+        "music"
+    }
+
+    fn run(
+        &self,
+        _db: Arc<DatabaseInstance>,
+        mut rx: mpsc::Receiver<ModuleMessage>,
+        heartbeat: Heartbeat,
+    ) -> Pin<Box<dyn Future<Output = Result<(), AppError>> + Send + '_>> {
+        Box::pin(async move {
+            info!(
+                module = %self.name(),
+                anisongdb = self.anisongdb_url.is_some(),
+                "Music module started"
+            );
+
+            let mut heartbeat_interval = tokio::time::interval(HEARTBEAT_INTERVAL);
+
+            loop {
+                tokio::select! {
+                    msg = rx.recv() => {
+                        match msg {
+                            Some(ModuleMessage::Shutdown) => {
+                                info!(module = %self.name(), "Received shutdown signal");
+                                if let Err(e) = self.queue.shutdown().await {
+                                    warn!(module = %self.name(), error = %e, "Failed to shutdown queue");
+                                }
+                                break;
+                            }
+                            Some(ModuleMessage::Pause) => {
+                                info!(module = %self.name(), "Pausing module");
+                                if let Err(e) = self.queue.pause().await {
+                                    warn!(module = %self.name(), error = %e, "Failed to pause queue");
+                                }
+                            }
+                            Some(ModuleMessage::Resume) => {
+                                info!(module = %self.name(), "Resuming module");
+                                if let Err(e) = self.queue.resume().await {
+                                    warn!(module = %self.name(), error = %e, "Failed to resume queue");
+                                }
+                            }
+                            Some(ModuleMessage::UpdateConfig(update)) => match update {
+                                ModuleConfigUpdate::Concurrency(concurrency) => {
+                                    if let Err(e) = self.queue.set_concurrency(concurrency).await {
+                                        warn!(module = %self.name(), error = %e, "Failed to update concurrency");
+                                    }
+                                }
+                                ModuleConfigUpdate::CleanupInterval(_) => {
+                                    debug!(module = %self.name(), "Music module has no cleanup, ignoring interval");
+                                }
+                                update => {
+                                    debug!(module = %self.name(), update = ?update, "Ignoring unsupported config update");
+                                }
+                            },
+                            Some(ModuleMessage::Custom(data)) => {
+                                debug!(module = %self.name(), message = %data, "Received custom message");
+                            }
+                            None => {
+                                warn!(module = %self.name(), "Channel closed unexpectedly");
+                                break;
+                            }
+                        }
+                    }
+
+                    _ = heartbeat_interval.tick() => {
+                        heartbeat.beat(&self.queue.stats()).await;
+                    }
+                }
+            }
+
+            info!(module = %self.name(), "Music module stopped");
+            Ok(())
+        })
+    }
+}
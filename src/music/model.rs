@@ -0,0 +1,67 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use mongodb::bson;
+
+/// Whether a theme song plays at the start or the end of the episodes
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ThemeKind {
+    Opening,
+    Ending,
+}
+
+/// Episodes a theme song is used in, `end` equals `start` for a single episode
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub struct EpisodeRange {
+    pub start: u32,
+    pub end: u32,
+}
+
+/// Opening or ending of an anime, parsed from its MyAnimeList theme string
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThemeSong {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<bson::oid::ObjectId>,
+
+    /// MAL ID of the anime
+    pub mal_id: i32,
+
+    pub kind: ThemeKind,
+
+    /// Number of the opening or ending (1 for "OP1")
+    pub sequence: u32,
+
+    /// Theme string as listed by MyAnimeList
+    pub raw: String,
+
+    /// Romanized or English title
+    pub title: String,
+
+    /// Title in the original script, when listed next to the romanized one
+    pub title_native: Option<String>,
+
+    pub artist: Option<String>,
+
+    #[serde(default)]
+    pub episodes: Vec<EpisodeRange>,
+
+    /// Matching song found on AnisongDB, if any
+    pub anisongdb: Option<AnisongDbSong>,
+
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Song entry of AnisongDB
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnisongDbSong {
+    pub ann_song_id: Option<i64>,
+    pub song_name: String,
+    pub artist: Option<String>,
+    pub composer: Option<String>,
+    pub arranger: Option<String>,
+    /// Audio sample of the song
+    pub audio_url: Option<String>,
+    /// Video samples of the song in high and medium quality
+    pub hq_url: Option<String>,
+    pub mq_url: Option<String>,
+}
@@ -0,0 +1,100 @@
+use super::model::{EpisodeRange, ThemeKind};
+
+/// Fields of a MyAnimeList theme string, e.g.
+/// `1: "Guren no Yumiya (紅蓮の弓矢)" by Linked Horizon (eps 1-13)`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ParsedTheme {
+    pub kind: ThemeKind,
+    pub sequence: u32,
+    pub title: String,
+    pub title_native: Option<String>,
+    pub artist: Option<String>,
+    pub episodes: Vec<EpisodeRange>,
+}
+
+/// Parse one theme string, `position` (from 0) gives the sequence when the string has no number.
+/// Returns `None` for an empty string.
+pub fn parse_theme(raw: &str, kind: ThemeKind, position: usize) -> Option<ParsedTheme> {
+    let mut rest = raw.trim();
+    if rest.is_empty() {
+        return None;
+    }
+
+    // Leading "1:" or "#1:"
+    let mut sequence = position as u32 + 1;
+    if let Some((number, after)) = rest.split_once(':')
+        && let Ok(number) = number.trim().trim_start_matches('#').parse::<u32>()
+    {
+        sequence = number;
+        rest = after.trim_start();
+    }
+
+    // Trailing "(eps 1-13, 25)" or "(ep 26)"
+    let mut episodes = Vec::new();
+    if let Some(start) = rest.rfind(" (ep")
+        && rest.ends_with(')')
+    {
+        episodes = parse_episodes(&rest[start + 2..rest.len() - 1]);
+        if !episodes.is_empty() {
+            rest = rest[..start].trim_end();
+        }
+    }
+
+    // "Title" by Artist, titles without quotes are kept whole
+    let (title, artist) = match rest.strip_prefix('"').and_then(|quoted| quoted.split_once("\" by ")) {
+        Some((title, artist)) => (title, Some(artist.trim())),
+        None => match rest.strip_prefix('"').and_then(|quoted| quoted.strip_suffix('"')) {
+            Some(title) => (title, None),
+            None => match rest.rsplit_once(" by ") {
+                Some((title, artist)) => (title, Some(artist.trim())),
+                None => (rest, None),
+            },
+        },
+    };
+
+    let (title, title_native) = split_native_title(title.trim());
+
+    Some(ParsedTheme {
+        kind,
+        sequence,
+        title,
+        title_native,
+        artist: artist.filter(|artist| !artist.is_empty()).map(str::to_string),
+        episodes,
+    })
+}
+
+/// Split "Guren no Yumiya (紅蓮の弓矢)" into the romanized and the native title
+fn split_native_title(title: &str) -> (String, Option<String>) {
+    if let Some(inner) = title.strip_suffix(')')
+        && let Some(start) = inner.rfind(" (")
+    {
+        let native = &inner[start + 2..];
+        if !native.is_ascii() {
+            return (inner[..start].trim().to_string(), Some(native.trim().to_string()));
+        }
+    }
+    (title.to_string(), None)
+}
+
+/// Parse "eps 1-13, 25" into ranges, parts that aren't numbers are ignored
+fn parse_episodes(text: &str) -> Vec<EpisodeRange> {
+    let text = text.trim_start_matches("eps").trim_start_matches("ep");
+
+    text.split(',')
+        .filter_map(|part| {
+            let part = part.trim();
+            match part.split_once('-') {
+                Some((start, end)) => {
+                    let start = start.trim().parse().ok()?;
+                    let end = end.trim().parse().ok()?;
+                    Some(EpisodeRange { start, end })
+                }
+                None => {
+                    let episode = part.parse().ok()?;
+                    Some(EpisodeRange { start: episode, end: episode })
+                }
+            }
+        })
+        .collect()
+}
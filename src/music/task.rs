@@ -0,0 +1,143 @@
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+use crate::anime::my_anime_list::database::get_anime_by_id;
+use crate::global::{
+    error::AppError,
+    queue::{Task, TaskContext, TaskData, TaskPriority},
+};
+use super::model::{ThemeKind, ThemeSong};
+use super::{anisongdb, database, parser};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FetchThemeSongsPayload {
+    pub anime_id: u32,
+}
+
+/// Task parsing the openings and endings of a stored anime into `theme_songs`,
+/// matched with their AnisongDB entries when enabled
+pub struct FetchThemeSongsTask {
+    id: String,
+    anime_id: u32,
+    /// AnisongDB API URL, `None` when the enrichment is disabled
+    anisongdb_url: Option<String>,
+    created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl FetchThemeSongsTask {
+    pub fn new(anime_id: u32, anisongdb_url: Option<String>) -> Self {
+        Self {
+            id: format!("fetch_theme_songs_{}", anime_id),
+            anime_id,
+            anisongdb_url,
+            created_at: chrono::Utc::now(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Task for FetchThemeSongsTask {
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+
+    fn name(&self) -> &str {
+        "fetch_theme_songs"
+    }
+
+    fn priority(&self) -> TaskPriority {
+        TaskPriority::Low
+    }
+
+    fn to_data(&self) -> TaskData {
+        let payload = FetchThemeSongsPayload {
+            anime_id: self.anime_id,
+        };
+
+        TaskData::new(
+            self.id.clone(),
+            self.name().to_string(),
+            self.priority(),
+            self.created_at,
+            serde_json::json!(payload),
+        )
+    }
+
+    async fn execute(&self, ctx: &TaskContext) -> Result<(), AppError> {
+        let db = ctx.db.clone();
+        let client = ctx.client.clone();
+        info!(
+            task = %self.name(),
+            anime_id = self.anime_id,
+            "Fetching theme songs"
+        );
+
+        let anime = match get_anime_by_id(db.db(), self.anime_id as i32).await? {
+            Some(anime) => anime,
+            None => {
+                warn!(task = %self.name(), anime_id = self.anime_id, "Anime not found in database");
+                return Ok(());
+            }
+        };
+
+        let parsed: Vec<_> = [
+            (ThemeKind::Opening, &anime.theme.openings),
+            (ThemeKind::Ending, &anime.theme.endings),
+        ]
+            .into_iter()
+            .flat_map(|(kind, themes)| {
+                themes.iter()
+                    .enumerate()
+                    .filter_map(move |(position, raw)| Some((raw, parser::parse_theme(raw, kind, position)?)))
+            })
+            .collect();
+
+        // The songs are stored without enrichment when AnisongDB can't be reached
+        let entries = match &self.anisongdb_url {
+            Some(url) if !parsed.is_empty() => {
+                match anisongdb::fetch_anime_songs(&client, url, anime.mal_id).await {
+                    Ok(entries) => entries,
+                    Err(e) => {
+                        warn!(task = %self.name(), anime_id = self.anime_id, error = %e, "AnisongDB lookup failed");
+                        Vec::new()
+                    }
+                }
+            }
+            _ => Vec::new(),
+        };
+
+        let now = chrono::Utc::now();
+        let mut songs: Vec<ThemeSong> = Vec::new();
+        for (raw, theme) in parsed {
+            // MAL sometimes lists the same number twice, the first one wins
+            if songs.iter().any(|song| song.kind == theme.kind && song.sequence == theme.sequence) {
+                continue;
+            }
+            songs.push(ThemeSong {
+                id: None,
+                mal_id: anime.mal_id,
+                anisongdb: anisongdb::find_song(&entries, &theme),
+                kind: theme.kind,
+                sequence: theme.sequence,
+                raw: raw.clone(),
+                title: theme.title,
+                title_native: theme.title_native,
+                artist: theme.artist,
+                episodes: theme.episodes,
+                updated_at: now,
+            });
+        }
+
+        database::replace_anime_theme_songs(db.db(), anime.mal_id, &songs).await?;
+
+        info!(
+            task = %self.name(),
+            anime_id = self.anime_id,
+            count = songs.len(),
+            matched = songs.iter().filter(|song| song.anisongdb.is_some()).count(),
+            "Theme songs stored"
+        );
+
+        Ok(())
+    }
+}
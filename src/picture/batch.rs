@@ -0,0 +1,215 @@
+use mongodb::bson::doc;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::{info, warn};
+
+use crate::global::{
+    database::DatabaseInstance,
+    error::{AppError, DatabaseError},
+    job::{create_job, seal_job},
+    queue::current_job_id,
+};
+
+use super::{
+    database,
+    model::SHARED_ENTITY_TYPES,
+    task::FetchPictureTask,
+    url::canonical_url,
+    PictureFetcherModule,
+};
+
+pub const COLLECTION_NAME: &str = "picture_batches";
+
+/// Job kind of the batches that don't belong to a parent job
+const JOB_KIND: &str = "pictures";
+
+/// A picture to download for an entity
+#[derive(Debug, Clone)]
+pub struct PictureRequest {
+    pub url: String,
+    pub entity_type: String,
+    pub entity_id: String,
+    pub tags: Vec<String>,
+    /// Color shown in place of the picture until it is loaded
+    pub placeholder_color: Option<String>,
+}
+
+impl PictureRequest {
+    pub fn new(url: String, entity_type: String, entity_id: String, tags: Vec<String>) -> Self {
+        Self { url, entity_type, entity_id, tags, placeholder_color: None }
+    }
+}
+
+/// Progress of a batch whose queueing didn't finish, persisted in `picture_batches`.
+/// Deleted once every picture of the batch is queued.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PictureBatch {
+    pub batch_id: String,
+    pub job_id: String,
+    /// Number of pictures in the batch
+    pub total: u64,
+    /// Hash of the batch's picture URLs in order, a resumed batch must have the same pictures
+    #[serde(default)]
+    pub urls_hash: String,
+    /// Pictures already queued or merged into a stored one, in batch order
+    pub queued: u64,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Outcome of queueing a batch
+#[derive(Debug, Clone)]
+pub struct PictureBatchResult {
+    pub job_id: String,
+    /// Pictures queued by this call
+    pub queued: u64,
+    /// Character and people pictures already downloaded, only their tags were updated
+    pub merged: u64,
+    /// Pictures skipped because an interrupted run already queued them
+    pub resumed_from: u64,
+}
+
+impl PictureFetcherModule {
+    /// Queue the pictures of a batch as one job.
+    ///
+    /// Progress is saved after each picture, so when queueing fails midway the next call
+    /// with the same `batch_id` continues in the same job from the first picture not queued.
+    /// A batch whose pictures changed since the interruption is queued again from the start.
+    ///
+    /// Pictures of characters and people already downloaded for their entity (e.g. for
+    /// another anime) are not queued again, the batch tags are added to them instead.
+    pub async fn queue_batch(
+        &self,
+        db: &DatabaseInstance,
+        batch_id: &str,
+        description: impl Into<String>,
+        requests: Vec<PictureRequest>,
+    ) -> Result<PictureBatchResult, AppError> {
+        let collection = db.collection::<PictureBatch>(COLLECTION_NAME);
+        let total = requests.len() as u64;
+        let urls_hash = hash_urls(&requests);
+
+        let interrupted = collection.find_one(doc! { "batch_id": batch_id })
+            .await
+            .map_err(|e| AppError::Database(DatabaseError::Query(format!("Failed to get picture batch: {}", e))))?;
+
+        let batch = match interrupted {
+            Some(batch) if batch.total == total && batch.urls_hash == urls_hash => {
+                info!(
+                    batch_id = %batch_id,
+                    job_id = %batch.job_id,
+                    queued = batch.queued,
+                    total = total,
+                    "Resuming interrupted picture batch"
+                );
+                batch
+            }
+            interrupted => {
+                if interrupted.is_some() {
+                    warn!(batch_id = %batch_id, "Pictures of an interrupted batch changed, queueing it again");
+                }
+
+                let job_id = match current_job_id() {
+                    Some(job_id) => job_id,
+                    None => create_job(db, JOB_KIND, description).await?.job_id,
+                };
+                let now = chrono::Utc::now();
+                let batch = PictureBatch {
+                    batch_id: batch_id.to_string(),
+                    job_id,
+                    total,
+                    urls_hash,
+                    queued: 0,
+                    created_at: now,
+                    updated_at: now,
+                };
+
+                collection.replace_one(doc! { "batch_id": batch_id }, &batch)
+                    .upsert(true)
+                    .await
+                    .map_err(|e| AppError::Database(DatabaseError::Query(format!("Failed to save picture batch: {}", e))))?;
+                batch
+            }
+        };
+
+        let (mut queued, mut merged) = (0, 0);
+        for (index, request) in requests.into_iter().enumerate().skip(batch.queued as usize) {
+            if self.add_tags_to_stored(db, &request).await? {
+                merged += 1;
+            } else {
+                let task = FetchPictureTask::new(request.url, self.storage_path.clone(), None)
+                    .with_entity(request.entity_type, request.entity_id)
+                    .with_tags(request.tags)
+                    .with_placeholder_color(request.placeholder_color)
+                    .with_download_buffer(self.download_buffer.clone())
+                    .with_storage_mode(self.storage_mode)
+                    .with_events(self.events.clone());
+
+                self.queue.enqueue_for_job(Box::new(task), &batch.job_id).await?;
+                queued += 1;
+            }
+
+            collection.update_one(
+                doc! { "batch_id": batch_id },
+                doc! { "$set": {
+                    "queued": index as i64 + 1,
+                    "updated_at": chrono::Utc::now().to_rfc3339(),
+                } },
+            )
+                .await
+                .map_err(|e| AppError::Database(DatabaseError::Query(format!("Failed to save picture batch progress: {}", e))))?;
+        }
+
+        collection.delete_one(doc! { "batch_id": batch_id })
+            .await
+            .map_err(|e| AppError::Database(DatabaseError::Query(format!("Failed to delete picture batch: {}", e))))?;
+
+        // A job of the batch's own is complete, the job of a running task is sealed by its creator
+        if current_job_id().as_deref() != Some(batch.job_id.as_str()) {
+            seal_job(db, &batch.job_id, Some(&self.events)).await?;
+        }
+
+        Ok(PictureBatchResult {
+            job_id: batch.job_id,
+            queued,
+            merged,
+            resumed_from: batch.queued,
+        })
+    }
+
+    /// Add the tags of a character or people picture to its downloaded copy, if any.
+    /// Returns false when the picture still has to be downloaded.
+    async fn add_tags_to_stored(&self, db: &DatabaseInstance, request: &PictureRequest) -> Result<bool, AppError> {
+        if !SHARED_ENTITY_TYPES.contains(&request.entity_type.as_str()) {
+            return Ok(false);
+        }
+
+        let stored = database::get_completed_variant(
+            db.db(),
+            &canonical_url(&request.url),
+            Some(&request.entity_id),
+            Some(&request.entity_type),
+        ).await?;
+
+        match stored {
+            Some(stored) => Ok(database::add_picture_tags(
+                db.db(),
+                &stored.url,
+                Some(&request.entity_id),
+                Some(&request.entity_type),
+                &request.tags,
+            ).await?),
+            None => Ok(false),
+        }
+    }
+}
+
+/// Hash of the picture URLs of a batch, in order
+fn hash_urls(requests: &[PictureRequest]) -> String {
+    let mut hasher = Sha256::new();
+    for request in requests {
+        hasher.update(request.url.as_bytes());
+        hasher.update(b"\n");
+    }
+    format!("{:x}", hasher.finalize())
+}
@@ -0,0 +1,59 @@
+use std::sync::Arc;
+
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tracing::debug;
+
+/// Space reserved before reading each chunk, larger chunks reserve the rest once read
+pub const READ_RESERVATION: usize = 64 * 1024;
+
+/// Bound on the bytes of picture downloads held in memory at the same time across the
+/// concurrent tasks of the picture worker (`picture.max_buffered_mb`): the chunks read
+/// from the responses and not yet written to disk.
+/// Space is counted in KiB so large limits fit in the semaphore.
+///
+/// Space is reserved before reading a chunk, so the bound applies to the bytes read.
+#[derive(Clone)]
+pub struct DownloadBuffer {
+    /// None when unlimited
+    semaphore: Option<Arc<Semaphore>>,
+    capacity_kib: u32,
+}
+
+impl DownloadBuffer {
+    /// Buffer of `max_bytes`, 0 disables the limit
+    pub fn new(max_bytes: u64) -> Self {
+        let capacity_kib = (max_bytes / 1024).clamp(1, Semaphore::MAX_PERMITS.min(u32::MAX as usize) as u64) as u32;
+        Self {
+            semaphore: (max_bytes > 0).then(|| Arc::new(Semaphore::new(capacity_kib as usize))),
+            capacity_kib,
+        }
+    }
+
+    /// Wait until `bytes` fit in the buffer, the space is released when the permit is dropped.
+    /// Chunks larger than the whole buffer wait for all of it.
+    pub async fn reserve(&self, bytes: usize) -> Option<OwnedSemaphorePermit> {
+        let semaphore = self.semaphore.as_ref()?;
+        let kib = self.kib(bytes);
+        if semaphore.available_permits() < kib as usize {
+            debug!(bytes = bytes, "Waiting for space in the picture download buffer");
+        }
+        // The semaphore is never closed
+        semaphore.clone().acquire_many_owned(kib).await.ok()
+    }
+
+    /// Extend reserved space to `bytes`, for a chunk larger than the space reserved before reading it
+    pub async fn grow(&self, space: &mut OwnedSemaphorePermit, bytes: usize) {
+        let Some(semaphore) = &self.semaphore else { return };
+        let missing = self.kib(bytes).saturating_sub(space.num_permits() as u32);
+        if missing == 0 {
+            return;
+        }
+        if let Ok(extra) = semaphore.clone().acquire_many_owned(missing).await {
+            space.merge(extra);
+        }
+    }
+
+    fn kib(&self, bytes: usize) -> u32 {
+        (bytes as u64).div_ceil(1024).clamp(1, self.capacity_kib as u64) as u32
+    }
+}
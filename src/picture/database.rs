@@ -1,8 +1,8 @@
 // src/picture/database.rs
 use anyhow::Result;
-use mongodb::{Database, IndexModel};
+use mongodb::{Cursor, Database, IndexModel};
 use mongodb::options::{FindOptions, IndexOptions, ReplaceOptions, UpdateOptions};
-use mongodb::bson::{doc, Document};
+use mongodb::bson::{doc, oid::ObjectId, Document};
 use tracing::{info, debug, warn};
 use futures::stream::StreamExt;
 
@@ -23,6 +23,11 @@ pub async fn initialize_collections(db: &Database) -> Result<(), DatabaseError>
         .options(IndexOptions::builder().unique(true).build())
         .build();
     
+    // Index on the canonical URL, to find CDN variants of a picture
+    let canonical_index = IndexModel::builder()
+        .keys(doc! { "canonical_url": 1, "entity_type": 1, "entity_id": 1 })
+        .build();
+
     // Index on file_path
     let path_index = IndexModel::builder()
         .keys(doc! { "file_path": 1 })
@@ -50,6 +55,7 @@ pub async fn initialize_collections(db: &Database) -> Result<(), DatabaseError>
     
     collection.create_indexes(vec![
         url_index,
+        canonical_index,
         path_index,
         status_index,
         hash_index,
@@ -59,6 +65,18 @@ pub async fn initialize_collections(db: &Database) -> Result<(), DatabaseError>
         .map_err(|e| DatabaseError::Query(format!("Failed to create picture indexes: {}", e)))?;
     
     debug!("Created indexes for pictures collection");
+
+    // Unique index on the id of interrupted picture batches
+    db.collection::<Document>(super::batch::COLLECTION_NAME)
+        .create_index(
+            IndexModel::builder()
+                .keys(doc! { "batch_id": 1 })
+                .options(IndexOptions::builder().unique(true).build())
+                .build(),
+        )
+        .await
+        .map_err(|e| DatabaseError::Query(format!("Failed to create picture batch index: {}", e)))?;
+
     Ok(())
 }
 
@@ -91,6 +109,14 @@ pub async fn get_picture_by_url(db: &Database, url: &str) -> Result<Option<Pictu
         .map_err(|e| DatabaseError::Query(format!("Failed to get picture: {}", e)))
 }
 
+/// Get picture metadata by its ObjectId
+pub async fn get_picture_by_id(db: &Database, id: ObjectId) -> Result<Option<PictureMetadata>, DatabaseError> {
+    let collection = db.collection::<PictureMetadata>(COLLECTION_NAME);
+
+    collection.find_one(doc! { "_id": id }).await
+        .map_err(|e| DatabaseError::Query(format!("Failed to get picture: {}", e)))
+}
+
 /// Get picture metadata by file path
 pub async fn get_picture_by_path(db: &Database, path: &str) -> Result<Option<PictureMetadata>, DatabaseError> {
     let collection = db.collection::<PictureMetadata>(COLLECTION_NAME);
@@ -134,6 +160,43 @@ pub async fn get_picture_metadata(db: &Database, url: &str, entity_id: Option<&s
         )))
 }
 
+/// Completed picture of an entity downloaded from another variant of the same canonical URL
+pub async fn get_completed_variant(
+    db: &Database,
+    canonical_url: &str,
+    entity_id: Option<&str>,
+    entity_type: Option<&str>,
+) -> Result<Option<PictureMetadata>, DatabaseError> {
+    let collection = db.collection::<PictureMetadata>(COLLECTION_NAME);
+    let filter = doc! {
+        "canonical_url": canonical_url,
+        "entity_id": entity_id,
+        "entity_type": entity_type,
+        "status": "Completed",
+    };
+
+    collection.find_one(filter).await
+        .map_err(|e| DatabaseError::Query(format!("Failed to get picture variant: {}", e)))
+}
+
+/// Add tags to a stored picture, keeping the ones it already has.
+/// Returns false when the picture doesn't exist.
+pub async fn add_picture_tags(
+    db: &Database,
+    url: &str,
+    entity_id: Option<&str>,
+    entity_type: Option<&str>,
+    tags: &[String],
+) -> Result<bool, DatabaseError> {
+    let collection = db.collection::<PictureMetadata>(COLLECTION_NAME);
+    let filter = doc! { "url": url, "entity_id": entity_id, "entity_type": entity_type };
+
+    let result = collection.update_one(filter, doc! { "$addToSet": { "tags": { "$each": tags } } }).await
+        .map_err(|e| DatabaseError::Query(format!("Failed to add picture tags: {}", e)))?;
+
+    Ok(result.matched_count > 0)
+}
+
 /// Update picture status
 pub async fn update_picture_status(
     db: &Database,
@@ -221,6 +284,15 @@ pub async fn get_pictures_by_status(
     Ok(results)
 }
 
+/// Iterate over every successfully downloaded picture
+pub async fn get_completed_pictures_cursor(db: &Database) -> Result<Cursor<PictureMetadata>, DatabaseError> {
+    let collection = db.collection::<PictureMetadata>(COLLECTION_NAME);
+
+    collection.find(doc! { "status": "Completed" })
+        .await
+        .map_err(|e| DatabaseError::Query(format!("Failed to get pictures: {}", e)))
+}
+
 /// Get pictures by tags
 pub async fn get_pictures_by_tag(
     db: &Database,
@@ -344,17 +416,81 @@ pub async fn get_picture_stats(db: &Database) -> Result<PictureStats, DatabaseEr
     })
 }
 
-/// Delete picture metadata
-pub async fn delete_picture(db: &Database, url: &str) -> Result<bool, DatabaseError> {
+/// Delete picture metadata by URL, returning the deleted metadata
+pub async fn delete_picture(db: &Database, url: &str) -> Result<Option<PictureMetadata>, DatabaseError> {
     let collection = db.collection::<PictureMetadata>(COLLECTION_NAME);
     let filter = doc! { "url": url };
     
+    collection.find_one_and_delete(filter).await
+        .map_err(|e| DatabaseError::Query(format!("Failed to delete picture: {}", e)))
+}
+
+/// Delete picture metadata by its ObjectId, returning the deleted metadata
+pub async fn delete_picture_by_id(db: &Database, id: ObjectId) -> Result<Option<PictureMetadata>, DatabaseError> {
+    let collection = db.collection::<PictureMetadata>(COLLECTION_NAME);
+
+    collection.find_one_and_delete(doc! { "_id": id }).await
+        .map_err(|e| DatabaseError::Query(format!("Failed to delete picture: {}", e)))
+}
+
+/// Delete the metadata of a picture of an entity
+pub async fn delete_entity_picture(db: &Database, url: &str, entity_type: &str, entity_id: &str) -> Result<bool, DatabaseError> {
+    let collection = db.collection::<PictureMetadata>(COLLECTION_NAME);
+    let filter = doc! { "url": url, "entity_type": entity_type, "entity_id": entity_id };
+
     let result = collection.delete_one(filter).await
         .map_err(|e| DatabaseError::Query(format!("Failed to delete picture: {}", e)))?;
-    
+
     Ok(result.deleted_count > 0)
 }
 
+/// Number of pictures stored in a file, identical pictures share one file
+pub async fn count_pictures_by_path(db: &Database, path: &str) -> Result<u64, DatabaseError> {
+    let collection = db.collection::<PictureMetadata>(COLLECTION_NAME);
+
+    collection.count_documents(doc! { "file_path": path }).await
+        .map_err(|e| DatabaseError::Query(format!("Failed to count pictures: {}", e)))
+}
+
+/// Ids of the entities of a type having pictures
+pub async fn get_entity_ids(db: &Database, entity_type: &str) -> Result<Vec<String>, DatabaseError> {
+    let collection = db.collection::<PictureMetadata>(COLLECTION_NAME);
+
+    let ids = collection.distinct("entity_id", doc! { "entity_type": entity_type }).await
+        .map_err(|e| DatabaseError::Query(format!("Failed to get picture entities: {}", e)))?;
+
+    Ok(ids.into_iter().filter_map(|id| id.as_str().map(str::to_string)).collect())
+}
+
+/// Move the pictures of one entity to another, e.g. when duplicate anime are merged.
+/// Pictures the target already has (same URL) are dropped instead of moved.
+/// Returns the number of pictures moved.
+pub async fn reassign_entity_pictures(
+    db: &Database,
+    entity_type: &str,
+    from_entity_id: &str,
+    to_entity_id: &str,
+) -> Result<u64, DatabaseError> {
+    let collection = db.collection::<PictureMetadata>(COLLECTION_NAME);
+
+    let mut moved = 0;
+    for picture in get_pictures_by_entity(db, entity_type, from_entity_id).await? {
+        let filter = doc! { "url": &picture.url, "entity_type": entity_type, "entity_id": from_entity_id };
+
+        if picture_exists(db, &picture.url, Some(to_entity_id), Some(entity_type)).await? {
+            collection.delete_one(filter).await
+                .map_err(|e| DatabaseError::Query(format!("Failed to delete duplicate picture: {}", e)))?;
+        } else {
+            collection.update_one(filter, doc! { "$set": { "entity_id": to_entity_id } }).await
+                .map_err(|e| DatabaseError::Query(format!("Failed to reassign picture: {}", e)))?;
+            moved += 1;
+        }
+    }
+
+    debug!(entity_type = entity_type, from = from_entity_id, to = to_entity_id, moved = moved, "Pictures reassigned");
+    Ok(moved)
+}
+
 /// Clean up failed downloads older than specified days
 pub async fn cleanup_failed_pictures(db: &Database, days: i64) -> Result<u64, DatabaseError> {
     let collection = db.collection::<Document>(COLLECTION_NAME);
@@ -2,48 +2,76 @@ use std::sync::Arc;
 use std::path::{Path, PathBuf};
 use std::pin::Pin;
 use std::future::Future;
+use std::time::Duration;
 use tokio::sync::mpsc;
 use tracing::{info, debug, warn, error};
 
+use crate::global::config::{ImageVariantPolicy, PictureConfig, PictureStorageMode};
 use crate::global::database::DatabaseInstance;
 use crate::global::error::AppError;
-use crate::global::module::{ParentModule, ModuleMessage};
+use crate::global::events::EventBus;
+use crate::global::module::{ParentModule, ModuleConfigUpdate, ModuleMessage, HEARTBEAT_INTERVAL};
+use crate::global::supervisor::Heartbeat;
 use crate::global::queue::{QueueWorker, TaskQueue};
 
 pub mod task;
 pub mod model;
 pub mod database;
+pub mod verify;
+pub mod url;
+pub mod batch;
+pub mod buffer;
 
 #[derive(Clone)]
 pub struct PictureFetcherModule {
     queue: TaskQueue,
     storage_path: PathBuf,
+    cleanup_interval: Duration,
+    variant_policy: ImageVariantPolicy,
+    streaming_thumbnails: bool,
+    /// Shared by the downloads of the worker
+    download_buffer: buffer::DownloadBuffer,
+    storage_mode: PictureStorageMode,
+    events: EventBus,
 }
 
 impl PictureFetcherModule {
     pub fn new(
         db: Arc<DatabaseInstance>, 
         client: reqwest::Client,
-        storage_path: impl AsRef<Path>,
+        config: &PictureConfig,
+        events: EventBus,
     ) -> Self {
-        let storage_path = storage_path.as_ref().to_path_buf();
+        let storage_path = PathBuf::from(&config.storage_path);
         
         // Create storage directory if it doesn't exist
         if let Err(e) = std::fs::create_dir_all(&storage_path) {
             warn!(error = %e, path = ?storage_path, "Failed to create picture storage directory");
         }
         
-        let (queue, rx) = TaskQueue::new("picture_queue".to_string(), 4000);
+        let (queue, rx) = TaskQueue::new("picture_queue".to_string(), config.queue_size, db.clone());
         
         // Spawn the queue worker
-        let worker = QueueWorker::new("picture_worker".to_string(), db, client);
+        let worker = QueueWorker::new("picture_worker".to_string(), db, client)
+            .with_stats(queue.stats())
+            .with_concurrency(config.concurrency)
+            .with_events(events.clone());
         tokio::spawn(async move {
             if let Err(e) = worker.run(rx).await {
                 error!(error = %e, "Picture queue worker error");
             }
         });
 
-        Self { queue, storage_path }
+        Self {
+            queue,
+            storage_path,
+            cleanup_interval: config.cleanup_interval(),
+            variant_policy: config.variants,
+            streaming_thumbnails: config.streaming_thumbnails,
+            download_buffer: buffer::DownloadBuffer::new(config.max_buffered_mb * 1024 * 1024),
+            storage_mode: config.storage_mode,
+            events,
+        }
     }
 
     pub fn queue(&self) -> &TaskQueue {
@@ -54,6 +82,16 @@ impl PictureFetcherModule {
         &self.storage_path
     }
 
+    /// Variants of the provider images to download, read at startup
+    pub fn variant_policy(&self) -> ImageVariantPolicy {
+        self.variant_policy
+    }
+
+    /// Whether the thumbnails of streaming episodes are downloaded, read at startup
+    pub fn streaming_thumbnails(&self) -> bool {
+        self.streaming_thumbnails
+    }
+
     /// Queue a task to fetch and store a picture
     pub async fn queue_fetch_picture(
         &self,
@@ -64,7 +102,10 @@ impl PictureFetcherModule {
             url,
             self.storage_path.clone(),
             filename,
-        );
+        )
+        .with_download_buffer(self.download_buffer.clone())
+        .with_storage_mode(self.storage_mode)
+        .with_events(self.events.clone());
         
         self.queue.enqueue(Box::new(task)).await
     }
@@ -80,7 +121,11 @@ impl PictureFetcherModule {
             url,
             self.storage_path.clone(),
             filename,
-        ).with_tags(tags);
+        )
+        .with_tags(tags)
+        .with_download_buffer(self.download_buffer.clone())
+        .with_storage_mode(self.storage_mode)
+        .with_events(self.events.clone());
         
         self.queue.enqueue(Box::new(task)).await
     }
@@ -100,7 +145,10 @@ impl PictureFetcherModule {
             filename,
         )
         .with_entity(entity_type, entity_id)
-        .with_tags(tags);
+        .with_tags(tags)
+        .with_download_buffer(self.download_buffer.clone())
+        .with_storage_mode(self.storage_mode)
+        .with_events(self.events.clone());
         
         self.queue.enqueue(Box::new(task)).await
     }
@@ -126,6 +174,7 @@ impl ParentModule for PictureFetcherModule {
         &self,
         db: Arc<DatabaseInstance>,
         mut rx: mpsc::Receiver<ModuleMessage>,
+        heartbeat: Heartbeat,
     ) -> Pin<Box<dyn Future<Output = Result<(), AppError>> + Send + '_>> {
         Box::pin(async move {
             info!(
@@ -133,6 +182,12 @@ impl ParentModule for PictureFetcherModule {
                 storage_path = ?self.storage_path,
                 "Picture fetcher module started"
             );
+
+            let mut heartbeat_interval = tokio::time::interval(HEARTBEAT_INTERVAL);
+
+            // Periodic cleanup of old failed downloads
+            let mut cleanup_period = self.cleanup_interval;
+            let mut cleanup = cleanup_timer(cleanup_period);
             
             loop {
                 tokio::select! {
@@ -145,6 +200,35 @@ impl ParentModule for PictureFetcherModule {
                                 }
                                 break;
                             }
+                            Some(ModuleMessage::Pause) => {
+                                info!(module = %self.name(), "Pausing module");
+                                if let Err(e) = self.queue.pause().await {
+                                    warn!(module = %self.name(), error = %e, "Failed to pause queue");
+                                }
+                            }
+                            Some(ModuleMessage::Resume) => {
+                                info!(module = %self.name(), "Resuming module");
+                                if let Err(e) = self.queue.resume().await {
+                                    warn!(module = %self.name(), error = %e, "Failed to resume queue");
+                                }
+                            }
+                            Some(ModuleMessage::UpdateConfig(update)) => match update {
+                                ModuleConfigUpdate::Concurrency(concurrency) => {
+                                    if let Err(e) = self.queue.set_concurrency(concurrency).await {
+                                        warn!(module = %self.name(), error = %e, "Failed to update concurrency");
+                                    }
+                                }
+                                ModuleConfigUpdate::CleanupInterval(period) => {
+                                    if period != cleanup_period {
+                                        info!(module = %self.name(), interval = ?period, "Updating cleanup interval");
+                                        cleanup_period = period;
+                                        cleanup = cleanup_timer(cleanup_period);
+                                    }
+                                }
+                                update => {
+                                    debug!(module = %self.name(), update = ?update, "Ignoring unsupported config update");
+                                }
+                            },
                             Some(ModuleMessage::Custom(data)) => {
                                 debug!(module = %self.name(), message = %data, "Received custom message");
                             }
@@ -155,8 +239,11 @@ impl ParentModule for PictureFetcherModule {
                         }
                     }
                     
-                    // Periodic cleanup of old failed downloads (every 6 hours)
-                    _ = tokio::time::sleep(tokio::time::Duration::from_secs(21600)) => {
+                    _ = heartbeat_interval.tick() => {
+                        heartbeat.beat(&self.queue.stats()).await;
+                    }
+
+                    _ = cleanup.tick() => {
                         debug!(module = %self.name(), "Running periodic cleanup");
                         match database::cleanup_failed_pictures(db.db(), 7).await {
                             Ok(deleted) => {
@@ -184,4 +271,9 @@ impl ParentModule for PictureFetcherModule {
             Ok(())
         })
     }
-}
\ No newline at end of file
+}
+
+/// Interval whose first tick is one period from now
+fn cleanup_timer(period: Duration) -> tokio::time::Interval {
+    tokio::time::interval_at(tokio::time::Instant::now() + period, period)
+}
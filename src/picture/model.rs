@@ -19,6 +19,10 @@ pub struct PictureMetadata {
     
     /// Original URL of the picture
     pub url: String,
+
+    /// URL with the CDN variants collapsed, the one downloaded
+    #[serde(default)]
+    pub canonical_url: Option<String>,
     
     /// Local file path where the picture is stored
     pub file_path: String,
@@ -50,6 +54,10 @@ pub struct PictureMetadata {
     
     /// Associated entity ID
     pub entity_id: Option<String>,
+
+    /// Dominant color of the image (e.g. "#e4a15d"), shown in its place until it is loaded
+    #[serde(default)]
+    pub placeholder_color: Option<String>,
     
     /// Number of download attempts
     pub download_attempts: u32,
@@ -67,10 +75,15 @@ pub struct PictureMetadata {
     pub downloaded_at: Option<DateTime<Utc>>,
 }
 
+/// Entity types of characters and people, keyed by their MAL id.
+/// Their pictures are stored once and shared by every anime they appear in.
+pub const SHARED_ENTITY_TYPES: &[&str] = &["character", "voice_actor", "staff"];
+
 impl PictureMetadata {
     pub fn new(url: String, file_path: String, filename: String) -> Self {
         Self {
             id: None,
+            canonical_url: Some(super::url::canonical_url(&url)),
             url,
             file_path,
             filename,
@@ -82,6 +95,7 @@ impl PictureMetadata {
             tags: Vec::new(),
             entity_type: None,
             entity_id: None,
+            placeholder_color: None,
             download_attempts: 0,
             content_hash: None,
             created_at: Utc::now(),
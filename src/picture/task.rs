@@ -1,19 +1,35 @@
-use std::sync::Arc;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use serde::{Deserialize, Serialize};
 use tokio::fs;
 use tokio::io::AsyncWriteExt;
+use tokio_util::sync::CancellationToken;
 use tracing::{info, debug, warn, error};
 use sha2::{Sha256, Digest};
 
+use crate::anime::local_images;
+use crate::global::config::PictureStorageMode;
 use crate::{global::{
     database::DatabaseInstance,
     error::AppError,
-    queue::{Task, TaskData, TaskPriority, TaskStatus},
+    events::{DataEvent, EventBus},
+    queue::{Task, TaskContext, TaskData, TaskPriority},
 }, picture::database::{get_picture_metadata, picture_exists}};
+use super::buffer::{DownloadBuffer, READ_RESERVATION};
 use super::model::{PictureMetadata, PictureStatus};
 use super::database;
 
+/// Directory of the files stored by content, under the storage path
+const OBJECTS_DIRECTORY: &str = "objects";
+
+/// File of a picture stored by content: `objects/ab/cd/<sha256>`
+pub fn content_path(storage_path: &Path, content_hash: &str) -> PathBuf {
+    storage_path
+        .join(OBJECTS_DIRECTORY)
+        .join(&content_hash[..2])
+        .join(&content_hash[2..4])
+        .join(content_hash)
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FetchPicturePayload {
     pub url: String,
@@ -22,6 +38,8 @@ pub struct FetchPicturePayload {
     pub tags: Vec<String>,
     pub entity_type: Option<String>,
     pub entity_id: Option<String>,
+    #[serde(default)]
+    pub placeholder_color: Option<String>,
 }
 
 pub struct FetchPictureTask {
@@ -32,7 +50,13 @@ pub struct FetchPictureTask {
     tags: Vec<String>,
     entity_type: Option<String>,
     entity_id: Option<String>,
+    placeholder_color: Option<String>,
     created_at: chrono::DateTime<chrono::Utc>,
+    /// Where to publish the completed picture event
+    events: Option<EventBus>,
+    /// Bound on the memory of the worker's downloads
+    download_buffer: Option<DownloadBuffer>,
+    storage_mode: PictureStorageMode,
 }
 
 impl FetchPictureTask {
@@ -50,7 +74,11 @@ impl FetchPictureTask {
             tags: Vec::new(),
             entity_type: None,
             entity_id: None,
+            placeholder_color: None,
             created_at: chrono::Utc::now(),
+            events: None,
+            download_buffer: None,
+            storage_mode: PictureStorageMode::default(),
         }
     }
     
@@ -67,6 +95,150 @@ impl FetchPictureTask {
         self
     }
 
+    /// Color shown in place of the picture until it is loaded
+    pub fn with_placeholder_color(mut self, color: Option<String>) -> Self {
+        self.placeholder_color = color;
+        self
+    }
+
+    /// Hold the downloaded bytes within the worker's download buffer
+    pub fn with_download_buffer(mut self, buffer: DownloadBuffer) -> Self {
+        self.download_buffer = Some(buffer);
+        self
+    }
+
+    /// Store the file by entity (default) or by content
+    pub fn with_storage_mode(mut self, storage_mode: PictureStorageMode) -> Self {
+        self.storage_mode = storage_mode;
+        self
+    }
+
+    /// Publish a data event once the picture is stored
+    pub fn with_events(mut self, events: EventBus) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    fn publish_completed(&self, file_path: &str) {
+        if let Some(events) = &self.events {
+            events.publish(DataEvent::PictureCompleted {
+                url: self.url.clone(),
+                file_path: file_path.to_string(),
+                entity_type: self.entity_type.clone(),
+                entity_id: self.entity_id.clone(),
+            });
+        }
+    }
+
+    /// Record the stored file on the anime owning the picture, if any
+    async fn link_to_anime(&self, db: &DatabaseInstance, metadata: &PictureMetadata) {
+        if let Err(e) = local_images::link_picture(db.db(), metadata).await {
+            warn!(task = %self.name(), url = %self.url, error = %e, "Failed to link picture to its anime");
+        }
+    }
+
+    async fn create_directory(&self, path: &Path) -> Result<(), AppError> {
+        fs::create_dir_all(path)
+            .await
+            .map_err(|e| {
+                let error_msg = format!("Failed to create directory: {}", e);
+                error!(task = %self.name(), error = %error_msg);
+                AppError::Storage(error_msg)
+            })
+    }
+
+    /// Move a complete download to its final path. A file stored by content that
+    /// already exists has the same bytes and is kept.
+    async fn persist(&self, temp_path: &Path, file_path: &Path) -> Result<(), AppError> {
+        if self.storage_mode == PictureStorageMode::Content && fs::try_exists(file_path).await.unwrap_or(false) {
+            debug!(task = %self.name(), path = ?file_path, "Picture content already stored");
+            let _ = fs::remove_file(temp_path).await;
+            return Ok(());
+        }
+
+        if let Some(parent) = file_path.parent() {
+            self.create_directory(parent).await?;
+        }
+        fs::rename(temp_path, file_path)
+            .await
+            .map_err(|e| {
+                let error_msg = format!("Failed to move downloaded file: {}", e);
+                error!(task = %self.name(), error = %error_msg);
+                AppError::Storage(error_msg)
+            })
+    }
+
+    /// Write the response body to `path` chunk by chunk, hashing it on the way.
+    /// Returns the size and SHA-256 of the body.
+    async fn download(
+        &self,
+        response: &mut reqwest::Response,
+        path: &Path,
+        cancel: &CancellationToken,
+    ) -> Result<(u64, String), AppError> {
+        let mut file = fs::File::create(path)
+            .await
+            .map_err(|e| {
+                let error_msg = format!("Failed to create file: {}", e);
+                error!(task = %self.name(), error = %error_msg);
+                AppError::Storage(error_msg)
+            })?;
+
+        let mut hasher = Sha256::new();
+        let mut size = 0;
+        loop {
+            // Reserved before reading, so the chunks held by the concurrent downloads stay
+            // within the buffer. Held until the chunk is written.
+            let mut space = match &self.download_buffer {
+                Some(buffer) => buffer.reserve(READ_RESERVATION).await,
+                None => None,
+            };
+            let chunk = tokio::select! {
+                chunk = response.chunk() => chunk,
+                _ = cancel.cancelled() => return Err(AppError::Canceled("Picture download was canceled".to_string())),
+            };
+            let chunk = chunk.map_err(|e| {
+                let error_msg = format!("Failed to read picture bytes: {}", e);
+                error!(task = %self.name(), error = %error_msg);
+                AppError::provider("picture", error_msg)
+            })?;
+            let Some(chunk) = chunk else { break };
+
+            if let (Some(buffer), Some(space)) = (&self.download_buffer, space.as_mut()) {
+                buffer.grow(space, chunk.len()).await;
+            }
+            size += chunk.len() as u64;
+
+            // Hashed on the blocking pool, like stored pictures are verified, while the chunk is written
+            let hashing = tokio::task::spawn_blocking({
+                let chunk = chunk.clone();
+                move || {
+                    hasher.update(&chunk);
+                    hasher
+                }
+            });
+            let written = file.write_all(&chunk).await;
+            hasher = hashing.await
+                .map_err(|e| AppError::Storage(format!("Picture hashing failed: {}", e)))?;
+            written.map_err(|e| {
+                let error_msg = format!("Failed to write file: {}", e);
+                error!(task = %self.name(), error = %error_msg);
+                AppError::Storage(error_msg)
+            })?;
+        }
+
+        // On disk before the rename makes it visible
+        file.sync_all()
+            .await
+            .map_err(|e| {
+                let error_msg = format!("Failed to flush file: {}", e);
+                error!(task = %self.name(), error = %error_msg);
+                AppError::Storage(error_msg)
+            })?;
+
+        Ok((size, format!("{:x}", hasher.finalize())))
+    }
+
     /// Extract filename from URL or use provided filename
     fn get_filename(&self) -> String {
         if let Some(ref name) = self.filename {
@@ -86,29 +258,28 @@ impl FetchPictureTask {
     fn build_directory_path(&self, base_path: &PathBuf) -> PathBuf {
         let mut path = base_path.clone();
         
-        // Check if this is a common entity type (shared across media)
-        let is_character = self.tags.contains(&"character".to_string());
-        let is_staff = self.tags.contains(&"staff".to_string());
-        let is_voice_actor = self.tags.contains(&"voice_actor".to_string());
+        // Check if this is a common entity type (shared across media),
+        // AniList ones are kept apart since their IDs are not MAL IDs
+        let shared_directory = [
+            ("character", "characters"),
+            ("voice_actor", "voice_actors"),
+            ("staff", "staff"),
+            ("anilist_character", "anilist_characters"),
+            ("anilist_voice_actor", "anilist_voice_actors"),
+            ("anilist_staff", "anilist_staff"),
+        ]
+            .into_iter()
+            .find(|(category, _)| self.tags.iter().any(|tag| tag == category))
+            .map(|(_, directory)| directory);
         
         // For common entities, store in shared directories by their ID
-        if is_character || is_staff || is_voice_actor {
-            // Extract the entity ID from tags (format: "character_123", "staff_456", etc.)
-            let entity_id = self.extract_entity_id_from_tags();
-            
-            if let Some(id) = entity_id {
-                if is_character {
-                    path.push("characters");
-                    path.push(id);
-                } else if is_voice_actor {
-                    path.push("voice_actors");
-                    path.push(id);
-                } else if is_staff {
-                    path.push("staff");
-                    path.push(id);
-                }
-                return path;
-            }
+        // Extract the entity ID from tags (format: "character_123", "staff_456", etc.)
+        if let Some(directory) = shared_directory
+            && let Some(id) = self.extract_entity_id_from_tags()
+        {
+            path.push(directory);
+            path.push(id);
+            return path;
         }
         
         // For media-specific content, use the entity-based structure
@@ -143,9 +314,14 @@ impl FetchPictureTask {
                     }
                 } else if self.tags.contains(&"picture".to_string()) {
                     path.push("pictures");
-                } else if self.tags.contains(&"banner".to_string()) {
+                } else if self.tags.contains(&"anilist_streaming".to_string()) {
+                    path.push("streaming");
+                } else if self.tags.contains(&"banner".to_string()) || self.tags.contains(&"anilist_banner".to_string()) {
                     path.push("banners");
-                } else if self.tags.contains(&"cover".to_string()) || self.tags.contains(&"main".to_string()) {
+                } else if self.tags.contains(&"cover".to_string())
+                    || self.tags.contains(&"anilist_cover".to_string())
+                    || self.tags.contains(&"main".to_string())
+                {
                     path.push("covers");
                 }
             }
@@ -193,13 +369,6 @@ impl FetchPictureTask {
             .collect()
     }
     
-    /// Calculate SHA-256 hash of file content
-    fn calculate_hash(data: &[u8]) -> String {
-        let mut hasher = Sha256::new();
-        hasher.update(data);
-        format!("{:x}", hasher.finalize())
-    }
-    
     /// Detect MIME type from file extension
     fn detect_mime_type(filename: &str) -> Option<String> {
         let extension = filename.split('.').last()?.to_lowercase();
@@ -255,23 +424,21 @@ impl Task for FetchPictureTask {
             tags: self.tags.clone(),
             entity_type: self.entity_type.clone(),
             entity_id: self.entity_id.clone(),
+            placeholder_color: self.placeholder_color.clone(),
         };
 
-        TaskData {
-            id: self.id.clone(),
-            name: self.name().to_string(),
-            priority: self.priority(),
-            status: TaskStatus::Pending,
-            created_at: self.created_at,
-            payload: serde_json::to_value(payload).unwrap(),
-        }
+        TaskData::new(
+            self.id.clone(),
+            self.name().to_string(),
+            self.priority(),
+            self.created_at,
+            serde_json::to_value(payload).unwrap(),
+        )
     }
 
-    async fn execute(
-        &self,
-        db: Arc<DatabaseInstance>,
-        client: reqwest::Client,
-    ) -> Result<(), AppError> {
+    async fn execute(&self, ctx: &TaskContext) -> Result<(), AppError> {
+        let db = ctx.db.clone();
+        let client = ctx.client.clone();
         info!(
             task = %self.name(),
             url = %self.url,
@@ -295,6 +462,7 @@ impl Task for FetchPictureTask {
         metadata.tags = self.tags.clone();
         metadata.entity_type = self.entity_type.clone();
         metadata.entity_id = self.entity_id.clone();
+        metadata.placeholder_color = self.placeholder_color.clone();
         metadata.status = PictureStatus::Downloading;
         
         // Check if picture already exists in database
@@ -306,34 +474,61 @@ impl Task for FetchPictureTask {
                         url = %self.url,
                         entity_id = ?self.entity_id,
                         entity_type = ?self.entity_type,
-                        "Picture already downloaded, skipping"
+                        "Picture already downloaded, adding tags"
                     );
+                    database::add_picture_tags(db.db(), &self.url, self.entity_id.as_deref(), self.entity_type.as_deref(), &self.tags).await?;
                     return Ok(());
                 }
-                // Update download attempts
+                // Update download attempts, keeping the tags of earlier queueings
                 metadata.download_attempts = existing.download_attempts + 1;
+                metadata.tags = existing.tags;
+                for tag in &self.tags {
+                    if !metadata.tags.contains(tag) {
+                        metadata.tags.push(tag.clone());
+                    }
+                }
             }
         }
+
+        // Another URL of the same image (other CDN host, resized copy) may already be stored
+        let canonical_url = metadata.canonical_url.clone().unwrap_or_else(|| self.url.clone());
+        if let Some(variant) = database::get_completed_variant(db.db(), &canonical_url, self.entity_id.as_deref(), self.entity_type.as_deref()).await?
+            && variant.url != self.url
+        {
+            info!(
+                task = %self.name(),
+                url = %self.url,
+                variant_of = %variant.url,
+                "Picture is a CDN variant of a downloaded one, adding tags"
+            );
+            database::add_picture_tags(db.db(), &variant.url, self.entity_id.as_deref(), self.entity_type.as_deref(), &self.tags).await?;
+            return Ok(());
+        }
         
         // Save initial metadata
         database::upsert_picture(db.db(), &metadata).await?;
 
         // Fetch the image
-        let response = client
-            .get(&self.url)
+        let mut response = client
+            .get(&canonical_url)
             .send()
             .await
             .map_err(|e| {
                 let error_msg = format!("Failed to fetch picture: {}", e);
                 error!(task = %self.name(), error = %error_msg);
-                AppError::Module(error_msg)
+                AppError::provider("picture", error_msg)
             })?;
 
-        if !response.status().is_success() {
-            let error_msg = format!("Failed to fetch picture: HTTP {}", response.status());
+        let status = response.status();
+        if !status.is_success() {
+            let error_msg = format!("Failed to fetch picture: HTTP {}", status);
             metadata.status = PictureStatus::Failed { error: error_msg.clone() };
             database::upsert_picture(db.db(), &metadata).await?;
-            return Err(AppError::Module(error_msg));
+            return Err(AppError::Provider {
+                provider: "picture".to_string(),
+                message: error_msg,
+                retryable: status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS,
+            });
         }
         
         // Extract MIME type from response headers
@@ -344,25 +539,29 @@ impl Task for FetchPictureTask {
             .map(|s| s.to_string())
             .or_else(|| Self::detect_mime_type(&filename));
 
-        // Get the image bytes
-        let bytes = response
-            .bytes()
-            .await
-            .map_err(|e| {
-                let error_msg = format!("Failed to read picture bytes: {}", e);
-                error!(task = %self.name(), error = %error_msg);
-                AppError::Module(error_msg)
-            })?;
+        // Stream the image to a temporary file on the filesystem of its final path, renamed once
+        // complete so a crash or a failed download never leaves a truncated picture
+        let temp_directory = match self.storage_mode {
+            PictureStorageMode::Path => directory_path,
+            PictureStorageMode::Content => self.storage_path.join(OBJECTS_DIRECTORY),
+        };
+        self.create_directory(&temp_directory).await?;
+        let temp_path = temp_directory.join(format!(".{}.{}.part", filename, uuid::Uuid::new_v4()));
+
+        let (file_size, content_hash) = match self.download(&mut response, &temp_path, &ctx.cancel).await {
+            Ok(downloaded) => downloaded,
+            Err(e) => {
+                let _ = fs::remove_file(&temp_path).await;
+                return Err(e);
+            }
+        };
 
         debug!(
             task = %self.name(),
-            size = bytes.len(),
+            size = file_size,
             "Downloaded picture"
         );
         
-        // Calculate content hash for deduplication
-        let content_hash = Self::calculate_hash(&bytes);
-        
         // Check if we already have this exact file
         if let Some(existing) = database::get_picture_by_hash(db.db(), &content_hash).await? {
             if existing.is_completed() && existing.url != self.url {
@@ -372,6 +571,7 @@ impl Task for FetchPictureTask {
                     duplicate_of = %existing.url,
                     "Picture is a duplicate, updating metadata only"
                 );
+                let _ = fs::remove_file(&temp_path).await;
                 metadata.file_path = existing.file_path;
                 metadata.file_size = existing.file_size;
                 metadata.width = existing.width;
@@ -380,48 +580,25 @@ impl Task for FetchPictureTask {
                 metadata.mime_type = mime_type;
                 metadata.status = PictureStatus::Completed;
                 database::upsert_picture(db.db(), &metadata).await?;
+                self.link_to_anime(&db, &metadata).await;
+                self.publish_completed(&metadata.file_path);
                 return Ok(());
             }
         }
 
-        // Ensure parent directory exists
-        if let Some(parent) = file_path.parent() {
-            fs::create_dir_all(parent)
-                .await
-                .map_err(|e| {
-                    let error_msg = format!("Failed to create directory: {}", e);
-                    error!(task = %self.name(), error = %error_msg);
-                    AppError::Module(error_msg)
-                })?;
-        }
-
-        // Write to file
-        let mut file = fs::File::create(&file_path)
-            .await
-            .map_err(|e| {
-                let error_msg = format!("Failed to create file: {}", e);
-                error!(task = %self.name(), error = %error_msg);
-                AppError::Module(error_msg)
-            })?;
-
-        file.write_all(&bytes)
-            .await
-            .map_err(|e| {
-                let error_msg = format!("Failed to write file: {}", e);
-                error!(task = %self.name(), error = %error_msg);
-                AppError::Module(error_msg)
-            })?;
+        let file_path = match self.storage_mode {
+            PictureStorageMode::Path => file_path,
+            PictureStorageMode::Content => content_path(&self.storage_path, &content_hash),
+        };
+        metadata.file_path = file_path.to_string_lossy().to_string();
 
-        file.flush()
-            .await
-            .map_err(|e| {
-                let error_msg = format!("Failed to flush file: {}", e);
-                error!(task = %self.name(), error = %error_msg);
-                AppError::Module(error_msg)
-            })?;
+        if let Err(e) = self.persist(&temp_path, &file_path).await {
+            let _ = fs::remove_file(&temp_path).await;
+            return Err(e);
+        }
         
         // Update metadata with file information
-        metadata.file_size = Some(bytes.len() as u64);
+        metadata.file_size = Some(file_size);
         metadata.mime_type = mime_type;
         metadata.content_hash = Some(content_hash);
         metadata.status = PictureStatus::Completed;
@@ -437,11 +614,53 @@ impl Task for FetchPictureTask {
             task = %self.name(),
             url = %self.url,
             path = ?file_path,
-            size = bytes.len(),
+            size = file_size,
             hash = %metadata.content_hash.as_ref().unwrap(),
             "Picture saved and tracked successfully"
         );
+        self.link_to_anime(&db, &metadata).await;
+        self.publish_completed(&metadata.file_path);
 
         Ok(())
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+mod tests {
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+    use crate::testing::TestDatabase;
+
+    #[tokio::test]
+    #[ignore = "needs a Docker daemon for the MongoDB container"]
+    async fn stores_the_downloaded_picture_and_its_metadata() {
+        let server = MockServer::start().await;
+        let storage = tempfile::TempDir::new().unwrap();
+        let database = TestDatabase::start().await;
+        let bytes = b"\xff\xd8\xff\xe0 picture bytes".to_vec();
+        Mock::given(method("GET"))
+            .and(path("/images/anime/1/1.jpg"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(bytes.clone(), "image/jpeg"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let url = format!("{}/images/anime/1/1.jpg", server.uri());
+        FetchPictureTask::new(url.clone(), storage.path().to_path_buf(), None)
+            .with_entity("anime".to_string(), "1".to_string())
+            .with_tags(vec!["main".to_string()])
+            .execute(&TaskContext::new(database.db.clone(), reqwest::Client::new()))
+            .await
+            .unwrap();
+
+        let metadata = get_picture_metadata(database.db.db(), &url, Some("1"), Some("anime"))
+            .await
+            .unwrap()
+            .expect("picture metadata not stored");
+        assert!(metadata.is_completed());
+        assert_eq!(metadata.file_size, Some(bytes.len() as u64));
+        assert_eq!(std::fs::read(&metadata.file_path).unwrap(), bytes);
+        assert!(Path::new(&metadata.file_path).starts_with(storage.path().join("anime").join("1").join("covers")));
+    }
+}
@@ -0,0 +1,51 @@
+use reqwest::Url;
+
+/// Hosts of the MyAnimeList image CDN, current and legacy
+const MAL_CDN_HOSTS: [&str; 3] = ["cdn.myanimelist.net", "myanimelist.cdn-dena.com", "cdn-dena.myanimelist.net"];
+
+/// URL of a picture with the obvious CDN variants collapsed, so the same image is tracked
+/// and downloaded once:
+/// - host lowercased (done by the URL parser)
+/// - legacy MyAnimeList CDN hosts mapped to cdn.myanimelist.net, served over https.
+///   Other hosts keep their scheme, they may not serve https
+/// - MyAnimeList resize prefixes (`/r/50x70/images/...`) and their `?s=` signatures dropped,
+///   the original size is downloaded instead
+///
+/// Size suffixes (`138006l.jpg`, `138006t.jpg`) and formats (jpg, webp) are different files
+/// and kept. URLs that don't parse are returned unchanged.
+pub fn canonical_url(url: &str) -> String {
+    let Ok(mut parsed) = Url::parse(url.trim()) else {
+        return url.to_string();
+    };
+
+    let Some(host) = parsed.host_str().map(str::to_string) else {
+        return parsed.into();
+    };
+
+    if MAL_CDN_HOSTS.contains(&host.as_str()) {
+        let _ = parsed.set_host(Some(MAL_CDN_HOSTS[0]));
+        if parsed.scheme() == "http" {
+            let _ = parsed.set_scheme("https");
+        }
+
+        let segments: Vec<String> = parsed.path_segments()
+            .map(|segments| segments.map(str::to_string).collect())
+            .unwrap_or_default();
+        if segments.len() > 2 && segments[0] == "r" && is_resize(&segments[1]) {
+            parsed.set_path(&segments[2..].join("/"));
+        }
+        parsed.set_query(None);
+    }
+    parsed.set_fragment(None);
+
+    parsed.into()
+}
+
+/// "50x70" style resize segment
+fn is_resize(segment: &str) -> bool {
+    segment.split_once('x').is_some_and(|(width, height)| {
+        !width.is_empty() && !height.is_empty()
+            && width.chars().all(|c| c.is_ascii_digit())
+            && height.chars().all(|c| c.is_ascii_digit())
+    })
+}
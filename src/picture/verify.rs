@@ -0,0 +1,110 @@
+use futures::stream::StreamExt;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use tokio::fs;
+use tracing::{info, warn};
+
+use super::database;
+use super::model::PictureStatus;
+use crate::global::database::DatabaseInstance;
+use crate::global::error::AppError;
+
+/// Why a downloaded picture failed verification
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PictureProblem {
+    /// The file no longer exists on disk
+    Missing,
+    /// The file size differs from the recorded size
+    SizeMismatch { expected: u64, actual: u64 },
+    /// The file content no longer matches the recorded SHA-256 hash
+    HashMismatch,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct InvalidPicture {
+    pub url: String,
+    pub file_path: String,
+    pub problem: PictureProblem,
+}
+
+#[derive(Debug, Clone, Serialize, Default)]
+pub struct VerifyReport {
+    pub checked: u64,
+    pub invalid: Vec<InvalidPicture>,
+    /// Invalid pictures marked as failed (only with `mark_failed`)
+    pub marked_failed: u64,
+}
+
+impl VerifyReport {
+    pub fn is_ok(&self) -> bool {
+        self.invalid.is_empty()
+    }
+}
+
+/// SHA-256 of a stored picture, computed on the blocking pool so large files don't stall
+/// the tasks running on the async workers
+async fn hash_content(bytes: Vec<u8>) -> Result<String, AppError> {
+    tokio::task::spawn_blocking(move || format!("{:x}", Sha256::digest(&bytes)))
+        .await
+        .map_err(|e| AppError::Storage(format!("Picture hashing failed: {}", e)))
+}
+
+/// Check that every completed picture still exists on disk with the recorded size and hash.
+/// With `mark_failed`, invalid pictures are set to failed so the next fetch downloads them again.
+pub async fn verify_pictures(db: &DatabaseInstance, mark_failed: bool) -> Result<VerifyReport, AppError> {
+    let mut report = VerifyReport::default();
+    let mut cursor = database::get_completed_pictures_cursor(db.db()).await?;
+
+    while let Some(result) = cursor.next().await {
+        let picture = match result {
+            Ok(picture) => picture,
+            Err(e) => {
+                warn!(error = %e, "Failed to deserialize picture");
+                continue;
+            }
+        };
+        report.checked += 1;
+
+        let problem = match fs::read(&picture.file_path).await {
+            Err(_) => Some(PictureProblem::Missing),
+            Ok(bytes) => {
+                let actual = bytes.len() as u64;
+                match picture.file_size {
+                    Some(expected) if expected != actual => Some(PictureProblem::SizeMismatch { expected, actual }),
+                    _ => match picture.content_hash.as_deref() {
+                        Some(expected) => (expected != hash_content(bytes).await?).then_some(PictureProblem::HashMismatch),
+                        None => None,
+                    },
+                }
+            }
+        };
+
+        let Some(problem) = problem else { continue };
+
+        warn!(url = %picture.url, path = %picture.file_path, problem = ?problem, "Picture failed verification");
+
+        if mark_failed {
+            let status = PictureStatus::Failed {
+                error: format!("Verification failed: {:?}", problem),
+            };
+            database::update_picture_status(db.db(), &picture.url, status).await?;
+            report.marked_failed += 1;
+        }
+
+        report.invalid.push(InvalidPicture {
+            url: picture.url,
+            file_path: picture.file_path,
+            problem,
+        });
+    }
+
+    info!(
+        checked = report.checked,
+        invalid = report.invalid.len(),
+        marked_failed = report.marked_failed,
+        "Picture verification finished"
+    );
+
+    Ok(report)
+}
@@ -0,0 +1,133 @@
+//! Test harness: provider mocks, temporary storage and a MongoDB container.
+//!
+//! The providers are served by a `wiremock` server the clients are pointed at with
+//! `child_modules.<name>.base_url`, like a deployment pointing them at a mirror.
+
+use std::path::Path;
+use std::sync::Arc;
+use tempfile::TempDir;
+use testcontainers_modules::mongo::Mongo;
+use testcontainers_modules::testcontainers::{runners::AsyncRunner, ContainerAsync};
+use wiremock::MockServer;
+
+use crate::global::config::{AppConfig, DatabaseConfig, HttpMode};
+use crate::global::database::DatabaseInstance;
+use crate::global::http::HttpClientManager;
+
+/// Paths of the providers' APIs on the mock server
+pub const MY_ANIME_LIST_PATH: &str = "/mal/v2";
+pub const JIKAN_PATH: &str = "/jikan/v4";
+pub const ANILIST_PATH: &str = "/anilist";
+
+/// Configuration sending the provider requests to `server`, with the recorded responses
+/// in `storage`. Requests are neither retried nor slowed down by the rate limiters.
+pub fn config(server: &MockServer, storage: &Path, mode: HttpMode) -> AppConfig {
+    let mode = match mode {
+        HttpMode::Live => "live",
+        HttpMode::Record => "record",
+        HttpMode::Replay => "replay",
+    };
+    let toml = format!(
+        r#"
+        [app]
+        log_level = "debug"
+
+        [api]
+        enabled = false
+        host = "127.0.0.1"
+        port = 0
+
+        [database]
+        host = "127.0.0.1"
+        port = 27017
+        name = "media_collector_test"
+
+        [modules.anime]
+        enabled = true
+
+        [child_modules.my_anime_list]
+        enabled = true
+        rate_limit = 1000.0
+        base_url = "{uri}{mal}"
+
+        [child_modules.jikan]
+        enabled = true
+        rate_limit = 1000.0
+        base_url = "{uri}{jikan}"
+
+        [child_modules.anilist]
+        enabled = true
+        rate_limit = 1000.0
+        base_url = "{uri}{anilist}"
+
+        [http]
+        timeout_seconds = 5
+        user_agent = "{{app}}/{{version}}"
+        default_rate_limit = 1000.0
+        mode = "{mode}"
+        fixtures_dir = "{fixtures}"
+
+        [http.retry]
+        max_retries = 0
+        base_delay_ms = 1
+        max_delay_ms = 1
+        "#,
+        uri = server.uri(),
+        mal = MY_ANIME_LIST_PATH,
+        jikan = JIKAN_PATH,
+        anilist = ANILIST_PATH,
+        mode = mode,
+        fixtures = storage.join("fixtures").display(),
+    );
+
+    config::Config::builder()
+        .add_source(config::File::from_str(&toml, config::FileFormat::Toml))
+        .build()
+        .and_then(|config| config.try_deserialize())
+        .expect("invalid test configuration")
+}
+
+/// Mock providers, the temporary directory of the test and the clients pointed at them
+pub struct Providers {
+    pub server: MockServer,
+    pub storage: TempDir,
+    pub http: HttpClientManager,
+}
+
+impl Providers {
+    /// Start a mock server for the provider clients, in `mode`
+    pub async fn start(mode: HttpMode) -> Self {
+        let server = MockServer::start().await;
+        let storage = TempDir::new().expect("failed to create the test directory");
+        let http = HttpClientManager::new(Arc::new(config(&server, storage.path(), mode)));
+        Self { server, storage, http }
+    }
+
+    /// Clients on the same server and storage in another mode, e.g. to replay what was recorded
+    pub fn clients(&self, mode: HttpMode) -> HttpClientManager {
+        HttpClientManager::new(Arc::new(config(&self.server, self.storage.path(), mode)))
+    }
+}
+
+/// A database in a MongoDB container, removed once dropped. Needs a Docker daemon.
+pub struct TestDatabase {
+    pub db: Arc<DatabaseInstance>,
+    _container: ContainerAsync<Mongo>,
+}
+
+impl TestDatabase {
+    pub async fn start() -> Self {
+        let container = Mongo::default().start().await.expect("failed to start the MongoDB container");
+        let port = container.get_host_port_ipv4(27017).await.expect("MongoDB port not exposed");
+        let config = DatabaseConfig {
+            host: "127.0.0.1".to_string(),
+            port: port as i32,
+            name: "media_collector_test".to_string(),
+            username: None,
+            password: None,
+            password_file: None,
+        };
+        let db = DatabaseInstance::new(&config).await.expect("failed to connect to the MongoDB container");
+        Self { db: Arc::new(db), _container: container }
+    }
+}
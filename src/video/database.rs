@@ -0,0 +1,263 @@
+// src/video/database.rs
+use anyhow::Result;
+use mongodb::{Database, IndexModel};
+use mongodb::options::{FindOptions, IndexOptions, ReplaceOptions};
+use mongodb::bson::{doc, Document};
+use tracing::{info, debug, warn};
+use futures::stream::StreamExt;
+
+use super::model::{VideoMetadata, VideoStats};
+use crate::global::error::DatabaseError;
+
+const COLLECTION_NAME: &str = "videos";
+
+/// Filter of the failed downloads, whose status is stored as `{ "Failed": { "error": ... } }`
+fn failed_filter() -> Document {
+    doc! { "status.Failed": { "$exists": true } }
+}
+
+/// Initialize video tracking collection and indexes
+pub async fn initialize_collections(db: &Database) -> Result<(), DatabaseError> {
+    info!("Initializing video tracking collections");
+
+    let collection = db.collection::<VideoMetadata>(COLLECTION_NAME);
+
+    // Unique index on URL per entity
+    let url_index = IndexModel::builder()
+        .keys(doc! { "url": 1, "entity_type": 1, "entity_id": 1 })
+        .options(IndexOptions::builder().unique(true).build())
+        .build();
+
+    // Index on the entity, to list the videos of an anime
+    let entity_index = IndexModel::builder()
+        .keys(doc! { "entity_type": 1, "entity_id": 1 })
+        .build();
+
+    // Index on status for querying by download status
+    let status_index = IndexModel::builder()
+        .keys(doc! { "status": 1 })
+        .build();
+
+    // Index on tags for filtering
+    let tags_index = IndexModel::builder()
+        .keys(doc! { "tags": 1 })
+        .build();
+
+    collection.create_indexes(vec![
+        url_index,
+        entity_index,
+        status_index,
+        tags_index,
+    ]).await
+        .map_err(|e| DatabaseError::Query(format!("Failed to create video indexes: {}", e)))?;
+
+    debug!("Created indexes for videos collection");
+    Ok(())
+}
+
+/// Insert or update video metadata
+pub async fn upsert_video(db: &Database, video: &VideoMetadata) -> Result<(), DatabaseError> {
+    let collection = db.collection::<VideoMetadata>(COLLECTION_NAME);
+    let filter = doc! { "url": &video.url, "entity_id": &video.entity_id, "entity_type": &video.entity_type };
+    let options = ReplaceOptions::builder().upsert(true).build();
+
+    collection.replace_one(filter, video)
+        .with_options(options)
+        .await
+        .map_err(|e| DatabaseError::Query(format!("Failed to upsert video: {}", e)))?;
+
+    debug!(
+        url = %video.url,
+        status = ?video.status,
+        "Video metadata upserted"
+    );
+    Ok(())
+}
+
+/// Get video metadata by URL, for any entity
+pub async fn get_video_by_url(db: &Database, url: &str) -> Result<Option<VideoMetadata>, DatabaseError> {
+    let collection = db.collection::<VideoMetadata>(COLLECTION_NAME);
+
+    collection.find_one(doc! { "url": url }).await
+        .map_err(|e| DatabaseError::Query(format!("Failed to get video: {}", e)))
+}
+
+/// Get video metadata of a URL for an entity
+pub async fn get_video_metadata(
+    db: &Database,
+    url: &str,
+    entity_id: Option<&str>,
+    entity_type: Option<&str>,
+) -> Result<Option<VideoMetadata>, DatabaseError> {
+    let collection = db.collection::<VideoMetadata>(COLLECTION_NAME);
+    let filter = doc! { "url": url, "entity_id": entity_id, "entity_type": entity_type };
+
+    collection.find_one(filter).await
+        .map_err(|e| DatabaseError::Query(format!("Failed to get video metadata: {}", e)))
+}
+
+/// Completed download of a URL for any entity, so a trailer shared by several
+/// entries is downloaded once
+pub async fn get_completed_video(db: &Database, url: &str) -> Result<Option<VideoMetadata>, DatabaseError> {
+    let collection = db.collection::<VideoMetadata>(COLLECTION_NAME);
+
+    collection.find_one(doc! { "url": url, "status": "Completed" }).await
+        .map_err(|e| DatabaseError::Query(format!("Failed to get completed video: {}", e)))
+}
+
+/// Get all videos for an entity
+pub async fn get_videos_by_entity(
+    db: &Database,
+    entity_type: &str,
+    entity_id: &str
+) -> Result<Vec<VideoMetadata>, DatabaseError> {
+    let collection = db.collection::<VideoMetadata>(COLLECTION_NAME);
+    let filter = doc! {
+        "entity_type": entity_type,
+        "entity_id": entity_id
+    };
+
+    let cursor = collection.find(filter).await
+        .map_err(|e| DatabaseError::Query(format!("Failed to get videos: {}", e)))?;
+
+    Ok(collect_videos(cursor).await)
+}
+
+/// Get videos by status ("Pending", "Downloading", "Completed" or "Failed")
+pub async fn get_videos_by_status(
+    db: &Database,
+    status: &str,
+    limit: i64
+) -> Result<Vec<VideoMetadata>, DatabaseError> {
+    let collection = db.collection::<VideoMetadata>(COLLECTION_NAME);
+    let filter = match status {
+        "Failed" => failed_filter(),
+        status => doc! { "status": status },
+    };
+
+    let options = FindOptions::builder()
+        .limit(limit)
+        .sort(doc! { "created_at": 1 })
+        .build();
+
+    let cursor = collection.find(filter)
+        .with_options(options)
+        .await
+        .map_err(|e| DatabaseError::Query(format!("Failed to get videos: {}", e)))?;
+
+    Ok(collect_videos(cursor).await)
+}
+
+/// Get videos by tag
+pub async fn get_videos_by_tag(
+    db: &Database,
+    tag: &str,
+    limit: i64
+) -> Result<Vec<VideoMetadata>, DatabaseError> {
+    let collection = db.collection::<VideoMetadata>(COLLECTION_NAME);
+
+    let options = FindOptions::builder()
+        .limit(limit)
+        .sort(doc! { "downloaded_at": -1 })
+        .build();
+
+    let cursor = collection.find(doc! { "tags": tag })
+        .with_options(options)
+        .await
+        .map_err(|e| DatabaseError::Query(format!("Failed to get videos: {}", e)))?;
+
+    Ok(collect_videos(cursor).await)
+}
+
+async fn collect_videos(mut cursor: mongodb::Cursor<VideoMetadata>) -> Vec<VideoMetadata> {
+    let mut results = Vec::new();
+    while let Some(result) = cursor.next().await {
+        match result {
+            Ok(video) => results.push(video),
+            Err(e) => warn!(error = %e, "Failed to deserialize video"),
+        }
+    }
+    results
+}
+
+/// Get video statistics
+pub async fn get_video_stats(db: &Database) -> Result<VideoStats, DatabaseError> {
+    let collection = db.collection::<Document>(COLLECTION_NAME);
+
+    let total = collection.count_documents(doc! {}).await
+        .map_err(|e| DatabaseError::Query(format!("Failed to count total: {}", e)))?;
+
+    let completed = collection.count_documents(doc! { "status": "Completed" }).await
+        .map_err(|e| DatabaseError::Query(format!("Failed to count completed: {}", e)))?;
+
+    let pending = collection.count_documents(doc! { "status": { "$in": ["Pending", "Downloading"] } }).await
+        .map_err(|e| DatabaseError::Query(format!("Failed to count pending: {}", e)))?;
+
+    let failed = collection.count_documents(failed_filter()).await
+        .map_err(|e| DatabaseError::Query(format!("Failed to count failed: {}", e)))?;
+
+    // Calculate total size
+    let pipeline = vec![
+        doc! {
+            "$match": {
+                "status": "Completed",
+                "file_size": { "$exists": true }
+            }
+        },
+        doc! {
+            "$group": {
+                "_id": null,
+                "total_size": { "$sum": "$file_size" }
+            }
+        }
+    ];
+
+    let mut cursor = collection.aggregate(pipeline).await
+        .map_err(|e| DatabaseError::Query(format!("Failed to aggregate size: {}", e)))?;
+
+    let total_size = if let Some(result) = cursor.next().await {
+        let doc = result.map_err(|e| DatabaseError::Query(format!("Failed to read aggregate: {}", e)))?;
+        doc.get_i64("total_size").unwrap_or(0) as u64
+    } else {
+        0
+    };
+
+    Ok(VideoStats {
+        total_videos: total,
+        completed,
+        pending,
+        failed,
+        total_size_bytes: total_size,
+    })
+}
+
+/// Delete the video metadata of a URL, for every entity.
+/// Returns the deleted records so their files can be removed.
+pub async fn delete_video(db: &Database, url: &str) -> Result<Vec<VideoMetadata>, DatabaseError> {
+    let collection = db.collection::<VideoMetadata>(COLLECTION_NAME);
+
+    let cursor = collection.find(doc! { "url": url }).await
+        .map_err(|e| DatabaseError::Query(format!("Failed to get videos: {}", e)))?;
+    let videos = collect_videos(cursor).await;
+
+    collection.delete_many(doc! { "url": url }).await
+        .map_err(|e| DatabaseError::Query(format!("Failed to delete video: {}", e)))?;
+
+    Ok(videos)
+}
+
+/// Delete failed download records older than `days`
+pub async fn cleanup_failed_videos(db: &Database, days: i64) -> Result<u64, DatabaseError> {
+    let collection = db.collection::<Document>(COLLECTION_NAME);
+    let threshold = mongodb::bson::to_bson(&(chrono::Utc::now() - chrono::Duration::days(days)))
+        .map_err(|e| DatabaseError::Query(format!("Failed to serialize threshold: {}", e)))?;
+
+    let mut filter = failed_filter();
+    filter.insert("updated_at", doc! { "$lt": threshold });
+
+    let result = collection.delete_many(filter).await
+        .map_err(|e| DatabaseError::Query(format!("Failed to cleanup: {}", e)))?;
+
+    info!(deleted = result.deleted_count, "Cleaned up failed video records");
+    Ok(result.deleted_count)
+}
@@ -0,0 +1,101 @@
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::time::Duration;
+use serde::Deserialize;
+use tokio::process::Command;
+use tracing::debug;
+
+use crate::global::config::VideoConfig;
+use crate::global::error::AppError;
+
+/// A video saved by a downloader
+#[derive(Debug, Clone)]
+pub struct DownloadedVideo {
+    pub file_path: PathBuf,
+    pub video_id: Option<String>,
+    pub title: Option<String>,
+    pub duration_seconds: Option<u32>,
+}
+
+/// Saves the video of a page (e.g. a YouTube watch URL) into a directory
+#[async_trait::async_trait]
+pub trait VideoDownloader: Send + Sync {
+    async fn download(&self, url: &str, directory: &Path) -> Result<DownloadedVideo, AppError>;
+}
+
+/// Downloader running yt-dlp, or a command accepting the same options
+pub struct YtDlpDownloader {
+    command: String,
+    format: String,
+    timeout: Duration,
+}
+
+/// Line printed by yt-dlp once the video is moved to its final path
+#[derive(Debug, Deserialize)]
+struct YtDlpOutput {
+    id: Option<String>,
+    title: Option<String>,
+    duration: Option<f64>,
+    filepath: String,
+}
+
+impl YtDlpDownloader {
+    pub fn new(config: &VideoConfig) -> Self {
+        Self {
+            command: config.command.clone(),
+            format: config.format.clone(),
+            timeout: config.timeout(),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl VideoDownloader for YtDlpDownloader {
+    async fn download(&self, url: &str, directory: &Path) -> Result<DownloadedVideo, AppError> {
+        let output_template = directory.join("%(id)s.%(ext)s");
+
+        let mut command = Command::new(&self.command);
+        command
+            .arg("--no-playlist")
+            .arg("--no-progress")
+            .arg("--no-simulate")
+            .args(["--format", &self.format])
+            .arg("--output")
+            .arg(&output_template)
+            .args(["--print", "after_move:%(.{id,title,duration,filepath})j"])
+            .arg("--")
+            .arg(url)
+            .stdin(Stdio::null())
+            .kill_on_drop(true);
+
+        debug!(command = %self.command, url = %url, "Running video downloader");
+
+        let output = tokio::time::timeout(self.timeout, command.output())
+            .await
+            .map_err(|_| AppError::provider("video", format!("Video download timed out after {:?}", self.timeout)))?
+            .map_err(|e| AppError::Module(format!("Failed to run {}: {}", self.command, e)))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let reason = stderr.lines().rev().find(|line| !line.trim().is_empty()).unwrap_or("no output");
+            // Usually an unavailable or private video
+            return Err(AppError::provider_rejected("video", format!(
+                "{} exited with {}: {}",
+                self.command, output.status, reason.trim()
+            )));
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let printed = stdout.lines()
+            .rev()
+            .find_map(|line| serde_json::from_str::<YtDlpOutput>(line).ok())
+            .ok_or_else(|| AppError::provider_rejected("video", format!("{} did not report the downloaded file", self.command)))?;
+
+        Ok(DownloadedVideo {
+            file_path: PathBuf::from(printed.filepath),
+            video_id: printed.id,
+            title: printed.title,
+            duration_seconds: printed.duration.map(|duration| duration.round() as u32),
+        })
+    }
+}
@@ -0,0 +1,264 @@
+use std::sync::Arc;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::future::Future;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tracing::{info, debug, warn, error};
+
+use crate::anime::my_anime_list::model::AnimeData;
+use crate::global::config::VideoConfig;
+use crate::global::database::DatabaseInstance;
+use crate::global::error::AppError;
+use crate::global::module::{ParentModule, ModuleConfigUpdate, ModuleMessage, HEARTBEAT_INTERVAL};
+use crate::global::supervisor::Heartbeat;
+use crate::global::queue::{QueueWorker, TaskQueue};
+
+pub mod task;
+pub mod model;
+pub mod database;
+pub mod downloader;
+
+use downloader::{VideoDownloader, YtDlpDownloader};
+
+#[derive(Clone)]
+pub struct VideoModule {
+    queue: TaskQueue,
+    storage_path: PathBuf,
+    cleanup_interval: Duration,
+    downloader: Arc<dyn VideoDownloader>,
+}
+
+impl VideoModule {
+    pub fn new(
+        db: Arc<DatabaseInstance>,
+        client: reqwest::Client,
+        config: &VideoConfig,
+    ) -> Self {
+        Self::with_downloader(db, client, config, Arc::new(YtDlpDownloader::new(config)))
+    }
+
+    /// Module saving the videos with another downloader than yt-dlp
+    pub fn with_downloader(
+        db: Arc<DatabaseInstance>,
+        client: reqwest::Client,
+        config: &VideoConfig,
+        downloader: Arc<dyn VideoDownloader>,
+    ) -> Self {
+        let storage_path = PathBuf::from(&config.storage_path);
+
+        // Create storage directory if it doesn't exist
+        if let Err(e) = std::fs::create_dir_all(&storage_path) {
+            warn!(error = %e, path = ?storage_path, "Failed to create video storage directory");
+        }
+
+        let (queue, rx) = TaskQueue::new("video_queue".to_string(), config.queue_size, db.clone());
+
+        // Spawn the queue worker
+        let worker = QueueWorker::new("video_worker".to_string(), db, client)
+            .with_stats(queue.stats())
+            .with_concurrency(config.concurrency);
+        tokio::spawn(async move {
+            if let Err(e) = worker.run(rx).await {
+                error!(error = %e, "Video queue worker error");
+            }
+        });
+
+        Self {
+            queue,
+            storage_path,
+            cleanup_interval: config.cleanup_interval(),
+            downloader,
+        }
+    }
+
+    pub fn queue(&self) -> &TaskQueue {
+        &self.queue
+    }
+
+    /// Queue a task to download a video, optionally associated with an entity
+    pub async fn queue_fetch_video(
+        &self,
+        url: String,
+        entity: Option<(String, String)>,
+        tags: Vec<String>,
+    ) -> Result<(), AppError> {
+        let mut task = task::FetchVideoTask::new(
+            url,
+            self.storage_path.clone(),
+            self.downloader.clone(),
+        )
+        .with_tags(tags);
+
+        if let Some((entity_type, entity_id)) = entity {
+            task = task.with_entity(entity_type, entity_id);
+        }
+
+        self.queue.enqueue(Box::new(task)).await
+    }
+
+    /// Queue the trailer and the promotional videos of an anime.
+    /// Returns the number of videos queued.
+    pub async fn queue_anime_videos(&self, anime: &AnimeData) -> Result<usize, AppError> {
+        let anime_id = anime.mal_id.to_string();
+        let mut videos = Vec::new();
+
+        if let Some(url) = watch_url(anime.trailer.youtube_id.as_deref(), anime.trailer.url.as_deref()) {
+            videos.push((url, vec![
+                "anime".to_string(),
+                anime_id.clone(),
+                "trailer".to_string(),
+            ]));
+        }
+
+        if let Some(anime_videos) = &anime.videos {
+            for (idx, promo) in anime_videos.promo.iter().enumerate() {
+                if let Some(url) = watch_url(promo.trailer.youtube_id.as_deref(), promo.trailer.url.as_deref()) {
+                    videos.push((url, vec![
+                        "anime".to_string(),
+                        anime_id.clone(),
+                        "video_promo".to_string(),
+                        format!("video_promo_{}", idx),
+                    ]));
+                }
+            }
+        }
+
+        // The main trailer is usually one of the promos too
+        let mut seen = std::collections::HashSet::new();
+        videos.retain(|(url, _)| seen.insert(url.clone()));
+
+        let count = videos.len();
+        for (url, tags) in videos {
+            debug!(anime_id = anime.mal_id, url = %url, "Queueing anime video");
+            self.queue_fetch_video(url, Some(("anime".to_string(), anime_id.clone())), tags).await?;
+        }
+
+        Ok(count)
+    }
+}
+
+/// Watch page of a video, built from its YouTube id when known so the same
+/// video listed with different URLs is downloaded once
+fn watch_url(youtube_id: Option<&str>, url: Option<&str>) -> Option<String> {
+    match (youtube_id, url) {
+        (Some(youtube_id), _) if !youtube_id.is_empty() => {
+            Some(format!("https://www.youtube.com/watch?v={}", youtube_id))
+        }
+        (_, Some(url)) if !url.is_empty() => Some(url.to_string()),
+        _ => None,
+    }
+}
+
+impl ParentModule for VideoModule {
+    fn name(&self) -> &str {
+        "video"
+    }
+
+    fn run(
+        &self,
+        db: Arc<DatabaseInstance>,
+        mut rx: mpsc::Receiver<ModuleMessage>,
+        heartbeat: Heartbeat,
+    ) -> Pin<Box<dyn Future<Output = Result<(), AppError>> + Send + '_>> {
+        Box::pin(async move {
+            info!(
+                module = %self.name(),
+                storage_path = ?self.storage_path,
+                "Video module started"
+            );
+
+            let mut heartbeat_interval = tokio::time::interval(HEARTBEAT_INTERVAL);
+
+            // Periodic cleanup of old failed downloads
+            let mut cleanup_period = self.cleanup_interval;
+            let mut cleanup = cleanup_timer(cleanup_period);
+
+            loop {
+                tokio::select! {
+                    msg = rx.recv() => {
+                        match msg {
+                            Some(ModuleMessage::Shutdown) => {
+                                info!(module = %self.name(), "Received shutdown signal");
+                                if let Err(e) = self.queue.shutdown().await {
+                                    warn!(module = %self.name(), error = %e, "Failed to shutdown queue");
+                                }
+                                break;
+                            }
+                            Some(ModuleMessage::Pause) => {
+                                info!(module = %self.name(), "Pausing module");
+                                if let Err(e) = self.queue.pause().await {
+                                    warn!(module = %self.name(), error = %e, "Failed to pause queue");
+                                }
+                            }
+                            Some(ModuleMessage::Resume) => {
+                                info!(module = %self.name(), "Resuming module");
+                                if let Err(e) = self.queue.resume().await {
+                                    warn!(module = %self.name(), error = %e, "Failed to resume queue");
+                                }
+                            }
+                            Some(ModuleMessage::UpdateConfig(update)) => match update {
+                                ModuleConfigUpdate::Concurrency(concurrency) => {
+                                    if let Err(e) = self.queue.set_concurrency(concurrency).await {
+                                        warn!(module = %self.name(), error = %e, "Failed to update concurrency");
+                                    }
+                                }
+                                ModuleConfigUpdate::CleanupInterval(period) => {
+                                    if period != cleanup_period {
+                                        info!(module = %self.name(), interval = ?period, "Updating cleanup interval");
+                                        cleanup_period = period;
+                                        cleanup = cleanup_timer(cleanup_period);
+                                    }
+                                }
+                                update => {
+                                    debug!(module = %self.name(), update = ?update, "Ignoring unsupported config update");
+                                }
+                            },
+                            Some(ModuleMessage::Custom(data)) => {
+                                debug!(module = %self.name(), message = %data, "Received custom message");
+                            }
+                            None => {
+                                warn!(module = %self.name(), "Channel closed unexpectedly");
+                                break;
+                            }
+                        }
+                    }
+
+                    _ = heartbeat_interval.tick() => {
+                        heartbeat.beat(&self.queue.stats()).await;
+                    }
+
+                    _ = cleanup.tick() => {
+                        debug!(module = %self.name(), "Running periodic cleanup");
+                        match database::cleanup_failed_videos(db.db(), 7).await {
+                            Ok(deleted) => {
+                                if deleted > 0 {
+                                    info!(
+                                        module = %self.name(),
+                                        deleted = deleted,
+                                        "Cleaned up old failed video records"
+                                    );
+                                }
+                            }
+                            Err(e) => {
+                                warn!(
+                                    module = %self.name(),
+                                    error = %e,
+                                    "Failed to cleanup old videos"
+                                );
+                            }
+                        }
+                    }
+                }
+            }
+
+            info!(module = %self.name(), "Video module stopped");
+            Ok(())
+        })
+    }
+}
+
+/// Interval whose first tick is one period from now
+fn cleanup_timer(period: Duration) -> tokio::time::Interval {
+    tokio::time::interval_at(tokio::time::Instant::now() + period, period)
+}
@@ -0,0 +1,103 @@
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use mongodb::bson;
+
+/// Status of a video download
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum VideoStatus {
+    Pending,
+    Downloading,
+    Completed,
+    Failed { error: String },
+}
+
+/// Metadata for a downloaded video
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VideoMetadata {
+    #[serde(rename = "_id", skip_serializing_if = "Option::is_none")]
+    pub id: Option<bson::oid::ObjectId>,
+
+    /// Page of the video (e.g. a YouTube watch URL)
+    pub url: String,
+
+    /// Id of the video on its site, as reported by the downloader
+    pub video_id: Option<String>,
+
+    /// Title of the video, as reported by the downloader
+    pub title: Option<String>,
+
+    /// Local file path where the video is stored, once downloaded
+    pub file_path: Option<String>,
+
+    /// File size in bytes
+    pub file_size: Option<u64>,
+
+    /// MIME type (e.g., "video/mp4", "video/webm")
+    pub mime_type: Option<String>,
+
+    /// Length of the video
+    pub duration_seconds: Option<u32>,
+
+    /// Current status of the download
+    pub status: VideoStatus,
+
+    /// Optional tags for categorization
+    #[serde(default)]
+    pub tags: Vec<String>,
+
+    /// Associated entity type (e.g., "anime")
+    pub entity_type: Option<String>,
+
+    /// Associated entity ID
+    pub entity_id: Option<String>,
+
+    /// Number of download attempts
+    pub download_attempts: u32,
+
+    /// When the video was first requested
+    pub created_at: DateTime<Utc>,
+
+    /// When the video was last updated
+    pub updated_at: DateTime<Utc>,
+
+    /// When the video was successfully downloaded
+    pub downloaded_at: Option<DateTime<Utc>>,
+}
+
+impl VideoMetadata {
+    pub fn new(url: String) -> Self {
+        Self {
+            id: None,
+            url,
+            video_id: None,
+            title: None,
+            file_path: None,
+            file_size: None,
+            mime_type: None,
+            duration_seconds: None,
+            status: VideoStatus::Pending,
+            tags: Vec::new(),
+            entity_type: None,
+            entity_id: None,
+            download_attempts: 0,
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            downloaded_at: None,
+        }
+    }
+
+    /// Check if the download was successful
+    pub fn is_completed(&self) -> bool {
+        matches!(self.status, VideoStatus::Completed)
+    }
+}
+
+/// Statistics about downloaded videos
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VideoStats {
+    pub total_videos: u64,
+    pub completed: u64,
+    pub pending: u64,
+    pub failed: u64,
+    pub total_size_bytes: u64,
+}
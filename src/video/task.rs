@@ -0,0 +1,229 @@
+use std::sync::Arc;
+use std::path::{Path, PathBuf};
+use serde::{Deserialize, Serialize};
+use tokio::fs;
+use tracing::{info, error};
+
+use crate::global::{
+    database::DatabaseInstance,
+    error::AppError,
+    queue::{Task, TaskContext, TaskData, TaskPriority},
+};
+use super::downloader::VideoDownloader;
+use super::model::{VideoMetadata, VideoStatus};
+use super::database;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FetchVideoPayload {
+    pub url: String,
+    pub storage_path: String,
+    pub tags: Vec<String>,
+    pub entity_type: Option<String>,
+    pub entity_id: Option<String>,
+}
+
+pub struct FetchVideoTask {
+    id: String,
+    url: String,
+    storage_path: PathBuf,
+    tags: Vec<String>,
+    entity_type: Option<String>,
+    entity_id: Option<String>,
+    downloader: Arc<dyn VideoDownloader>,
+    created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl FetchVideoTask {
+    pub fn new(
+        url: String,
+        storage_path: PathBuf,
+        downloader: Arc<dyn VideoDownloader>,
+    ) -> Self {
+        let id = format!("fetch_video_{}", uuid::Uuid::new_v4());
+        Self {
+            id,
+            url,
+            storage_path,
+            tags: Vec::new(),
+            entity_type: None,
+            entity_id: None,
+            downloader,
+            created_at: chrono::Utc::now(),
+        }
+    }
+
+    /// Add tags for categorization
+    pub fn with_tags(mut self, tags: Vec<String>) -> Self {
+        self.tags = tags;
+        self
+    }
+
+    /// Associate with an entity
+    pub fn with_entity(mut self, entity_type: String, entity_id: String) -> Self {
+        self.entity_type = Some(entity_type);
+        self.entity_id = Some(entity_id);
+        self
+    }
+
+    /// Directory of the video: `{entity_type}/{entity_id}` when it belongs to an entity
+    fn build_directory_path(&self) -> PathBuf {
+        match (&self.entity_type, &self.entity_id) {
+            (Some(entity_type), Some(entity_id)) => self.storage_path.join(entity_type).join(entity_id),
+            _ => self.storage_path.join("misc"),
+        }
+    }
+
+    fn detect_mime_type(path: &Path) -> Option<String> {
+        let extension = path.extension()?.to_str()?.to_lowercase();
+        match extension.as_str() {
+            "mp4" | "m4v" => Some("video/mp4".to_string()),
+            "webm" => Some("video/webm".to_string()),
+            "mkv" => Some("video/x-matroska".to_string()),
+            "mov" => Some("video/quicktime".to_string()),
+            _ => None,
+        }
+    }
+
+    /// Record the failure and turn it into the task error
+    async fn fail(&self, db: &DatabaseInstance, metadata: &mut VideoMetadata, error: AppError) -> AppError {
+        error!(task = %self.name(), url = %self.url, error = %error);
+        // A canceled download didn't fail, it is back to waiting for its next run
+        metadata.status = match &error {
+            AppError::Canceled(_) => VideoStatus::Pending,
+            _ => VideoStatus::Failed { error: error.to_string() },
+        };
+        metadata.updated_at = chrono::Utc::now();
+        if let Err(e) = database::upsert_video(db.db(), metadata).await {
+            error!(task = %self.name(), url = %self.url, error = %e, "Failed to record video failure");
+        }
+        error
+    }
+}
+
+#[async_trait::async_trait]
+impl Task for FetchVideoTask {
+    fn id(&self) -> String {
+        self.id.clone()
+    }
+
+    fn name(&self) -> &str {
+        "fetch_video"
+    }
+
+    fn priority(&self) -> TaskPriority {
+        TaskPriority::Low
+    }
+
+    fn to_data(&self) -> TaskData {
+        let payload = FetchVideoPayload {
+            url: self.url.clone(),
+            storage_path: self.storage_path.to_string_lossy().to_string(),
+            tags: self.tags.clone(),
+            entity_type: self.entity_type.clone(),
+            entity_id: self.entity_id.clone(),
+        };
+
+        TaskData::new(
+            self.id.clone(),
+            self.name().to_string(),
+            self.priority(),
+            self.created_at,
+            serde_json::to_value(payload).unwrap(),
+        )
+    }
+
+    async fn execute(&self, ctx: &TaskContext) -> Result<(), AppError> {
+        let db = ctx.db.clone();
+        info!(
+            task = %self.name(),
+            url = %self.url,
+            "Fetching video"
+        );
+
+        let mut metadata = VideoMetadata::new(self.url.clone());
+        metadata.tags = self.tags.clone();
+        metadata.entity_type = self.entity_type.clone();
+        metadata.entity_id = self.entity_id.clone();
+        metadata.status = VideoStatus::Downloading;
+
+        if let Some(existing) = database::get_video_metadata(db.db(), &self.url, self.entity_id.as_deref(), self.entity_type.as_deref()).await? {
+            if existing.is_completed() {
+                info!(task = %self.name(), url = %self.url, "Video already downloaded");
+                return Ok(());
+            }
+            // Update download attempts, keeping the tags of earlier queueings
+            metadata.created_at = existing.created_at;
+            metadata.download_attempts = existing.download_attempts + 1;
+            metadata.tags = existing.tags;
+            for tag in &self.tags {
+                if !metadata.tags.contains(tag) {
+                    metadata.tags.push(tag.clone());
+                }
+            }
+        }
+
+        // The same trailer is often listed by several entries, reuse its file
+        if let Some(stored) = database::get_completed_video(db.db(), &self.url).await? {
+            info!(
+                task = %self.name(),
+                url = %self.url,
+                file_path = ?stored.file_path,
+                "Video already downloaded for another entity, reusing its file"
+            );
+            metadata.video_id = stored.video_id;
+            metadata.title = stored.title;
+            metadata.file_path = stored.file_path;
+            metadata.file_size = stored.file_size;
+            metadata.mime_type = stored.mime_type;
+            metadata.duration_seconds = stored.duration_seconds;
+            metadata.status = VideoStatus::Completed;
+            metadata.downloaded_at = Some(chrono::Utc::now());
+            database::upsert_video(db.db(), &metadata).await?;
+            return Ok(());
+        }
+
+        database::upsert_video(db.db(), &metadata).await?;
+
+        let directory_path = self.build_directory_path();
+        if let Err(e) = fs::create_dir_all(&directory_path).await {
+            return Err(self.fail(&db, &mut metadata, AppError::Storage(format!("Failed to create directory: {}", e))).await);
+        }
+
+        // Dropping the download kills the downloader process
+        let download = tokio::select! {
+            result = self.downloader.download(&self.url, &directory_path) => result,
+            _ = ctx.cancel.cancelled() => Err(AppError::Canceled("Video download was canceled".to_string())),
+        };
+        let downloaded = match download {
+            Ok(downloaded) => downloaded,
+            Err(e) => return Err(self.fail(&db, &mut metadata, e).await),
+        };
+
+        let file_size = match fs::metadata(&downloaded.file_path).await {
+            Ok(file) => file.len(),
+            Err(e) => return Err(self.fail(&db, &mut metadata, AppError::Storage(format!("Downloaded video is missing: {}", e))).await),
+        };
+
+        metadata.mime_type = Self::detect_mime_type(&downloaded.file_path);
+        metadata.file_path = Some(downloaded.file_path.to_string_lossy().to_string());
+        metadata.file_size = Some(file_size);
+        metadata.video_id = downloaded.video_id;
+        metadata.title = downloaded.title;
+        metadata.duration_seconds = downloaded.duration_seconds;
+        metadata.status = VideoStatus::Completed;
+        metadata.updated_at = chrono::Utc::now();
+        metadata.downloaded_at = Some(chrono::Utc::now());
+
+        database::upsert_video(db.db(), &metadata).await?;
+
+        info!(
+            task = %self.name(),
+            url = %self.url,
+            path = ?downloaded.file_path,
+            size = file_size,
+            "Video saved and tracked successfully"
+        );
+
+        Ok(())
+    }
+}